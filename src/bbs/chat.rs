@@ -0,0 +1,222 @@
+//! Teleconference: live chat rooms for logged-in BBS users.
+//!
+//! Behavior:
+//! - `C` / `CHAT [room]` from the main menu enters a room (default `lobby`); rooms are
+//!   created on first join and kept in memory only
+//! - Every line typed in chat mode is spoken to the room; `/` commands (`/who`, `/join`,
+//!   `/leave`, `/me`, `/rooms`) manage it, and moderators can `/mod` a room so only they
+//!   may speak, or `/kick` a member
+//! - Lines are queued as [`ChatEvent`]s and the server fans them out as DMs to the other
+//!   members, coalescing each recipient's lines into as few frames as fit
+//!   (see [`plan_fanout`])
+//! - The last [`CHAT_SCROLLBACK`] spoken lines are replayed to whoever joins next
+//!
+//! Membership is not stored here: a member is any session in chat mode whose
+//! `current_chat_room` names the room, so logouts and timeouts need no cleanup.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::tmush::events::plan_fanout_by;
+
+/// Room joined by a bare `C` / `CHAT`
+pub const DEFAULT_CHAT_ROOM: &str = "lobby";
+
+/// Spoken lines kept per room for latecomers
+pub const CHAT_SCROLLBACK: usize = 5;
+
+/// Rooms kept at once; empty unmoderated rooms are dropped to make space
+pub const MAX_CHAT_ROOMS: usize = 16;
+
+/// Longest room name, in bytes
+pub const MAX_CHAT_ROOM_NAME: usize = 16;
+
+/// Room name as typed (an optional leading `#`, any case) to its canonical form;
+/// `None` unless it is 1-16 letters, digits, `-` or `_`
+pub fn normalize_room_name(raw: &str) -> Option<String> {
+    let name = raw.trim().trim_start_matches('#').to_ascii_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_CHAT_ROOM_NAME
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then_some(name)
+}
+
+/// One chat room
+#[derive(Debug, Default)]
+pub struct ChatRoom {
+    /// Only moderators may speak while set
+    pub moderated: bool,
+    scrollback: VecDeque<String>,
+}
+
+impl ChatRoom {
+    /// Recent spoken lines, oldest first
+    pub fn scrollback(&self) -> impl Iterator<Item = &String> {
+        self.scrollback.iter()
+    }
+}
+
+/// A line waiting to be delivered to a room
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatEvent {
+    pub room: String,
+    /// Speaker's node; the line is not echoed back to it
+    pub from_node: String,
+    pub line: String,
+}
+
+/// A session currently in chat mode
+#[derive(Debug, Clone)]
+pub struct ChatMember {
+    pub node_key: String,
+    pub username: String,
+    pub room: String,
+}
+
+/// Every open room plus the lines not yet delivered
+#[derive(Debug, Default)]
+pub struct ChatRooms {
+    rooms: BTreeMap<String, ChatRoom>,
+    pending: Vec<ChatEvent>,
+}
+
+impl ChatRooms {
+    pub fn get(&self, name: &str) -> Option<&ChatRoom> {
+        self.rooms.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut ChatRoom> {
+        self.rooms.get_mut(name)
+    }
+
+    /// Open rooms in name order
+    pub fn rooms(&self) -> impl Iterator<Item = (&String, &ChatRoom)> {
+        self.rooms.iter()
+    }
+
+    /// The room called `name`, created on first use. At [`MAX_CHAT_ROOMS`] an empty,
+    /// unmoderated room (not in `occupied`) is dropped to make space; `None` if there
+    /// is none to drop.
+    pub fn open(&mut self, name: &str, occupied: &HashSet<String>) -> Option<&mut ChatRoom> {
+        if !self.rooms.contains_key(name) && self.rooms.len() >= MAX_CHAT_ROOMS {
+            let idle = self
+                .rooms
+                .iter()
+                .find(|(room, state)| !state.moderated && !occupied.contains(*room))
+                .map(|(room, _)| room.clone())?;
+            self.rooms.remove(&idle);
+        }
+        Some(self.rooms.entry(name.to_string()).or_default())
+    }
+
+    /// Queue a spoken line; it also goes into the room's scrollback
+    pub fn say(&mut self, room: &str, from_node: &str, line: String) {
+        if let Some(state) = self.rooms.get_mut(room) {
+            if state.scrollback.len() == CHAT_SCROLLBACK {
+                state.scrollback.pop_front();
+            }
+            state.scrollback.push_back(line.clone());
+        }
+        self.notice(room, from_node, line);
+    }
+
+    /// Queue a notice (join, leave, moderation) that is not kept in the scrollback
+    pub fn notice(&mut self, room: &str, from_node: &str, line: String) {
+        self.pending.push(ChatEvent {
+            room: room.to_string(),
+            from_node: from_node.to_string(),
+            line,
+        });
+    }
+
+    /// Take every queued line
+    pub fn drain(&mut self) -> Vec<ChatEvent> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Group events by recipient (every member of the event's room except the speaker)
+/// and pack each recipient's lines into frames of at most `max_bytes`, using the
+/// TinyMUSH room fanout. Returns `(node_key, frames)` in first-seen recipient order.
+pub fn plan_fanout(
+    events: &[ChatEvent],
+    members: &[ChatMember],
+    max_bytes: usize,
+) -> Vec<(String, Vec<String>)> {
+    plan_fanout_by(
+        events,
+        members,
+        max_bytes,
+        |member| &member.node_key,
+        |event, member| member.room == event.room && member.node_key != event.from_node,
+        |event| event.line.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(node_key: &str, room: &str) -> ChatMember {
+        ChatMember {
+            node_key: node_key.to_string(),
+            username: node_key.to_string(),
+            room: room.to_string(),
+        }
+    }
+
+    #[test]
+    fn room_names_are_normalized() {
+        assert_eq!(normalize_room_name("#Radio").as_deref(), Some("radio"));
+        assert_eq!(normalize_room_name("net-1").as_deref(), Some("net-1"));
+        assert_eq!(normalize_room_name("#"), None);
+        assert_eq!(normalize_room_name("a b"), None);
+        assert_eq!(normalize_room_name(&"x".repeat(17)), None);
+    }
+
+    #[test]
+    fn lines_reach_other_members_of_the_room_only() {
+        let mut chat = ChatRooms::default();
+        chat.open("lobby", &HashSet::new()).unwrap();
+        chat.say("lobby", "1", "<alice> hi".into());
+        chat.notice("lobby", "2", "* bob joined".into());
+        let members = [
+            member("1", "lobby"),
+            member("2", "lobby"),
+            member("3", "radio"),
+        ];
+        let plan = plan_fanout(&chat.drain(), &members, 200);
+        assert_eq!(
+            plan,
+            vec![
+                ("2".to_string(), vec!["<alice> hi".to_string()]),
+                ("1".to_string(), vec!["* bob joined".to_string()]),
+            ]
+        );
+        assert!(chat.drain().is_empty());
+    }
+
+    #[test]
+    fn scrollback_keeps_the_latest_lines_and_full_rooms_recycle_idle_ones() {
+        let mut chat = ChatRooms::default();
+        chat.open("lobby", &HashSet::new()).unwrap();
+        for i in 0..7 {
+            chat.say("lobby", "1", format!("line {}", i));
+        }
+        let kept: Vec<_> = chat.get("lobby").unwrap().scrollback().cloned().collect();
+        assert_eq!(kept.first().map(String::as_str), Some("line 2"));
+        assert_eq!(kept.len(), CHAT_SCROLLBACK);
+
+        let occupied: HashSet<String> = ["lobby".to_string()].into();
+        for i in 1..MAX_CHAT_ROOMS {
+            chat.open(&format!("room{:02}", i), &occupied).unwrap();
+        }
+        chat.get_mut("room01").unwrap().moderated = true;
+        // The first empty, unmoderated room in name order makes way
+        assert!(chat.open("extra", &occupied).is_some());
+        assert!(chat.get("lobby").is_some());
+        assert!(chat.get("room01").is_some());
+        assert!(chat.get("room02").is_none());
+    }
+}
@@ -29,10 +29,20 @@ use super::games::{self, GameDoorKind};
 use super::roles::LEVEL_MODERATOR;
 use super::session::{Session, SessionState};
 use crate::config::Config;
-use crate::storage::{ReplyEntry, Storage};
+use crate::storage::polls::POLL_RESULTS_MAX_BYTES;
+use crate::storage::{MailFolder, MailMessage, ReplyEntry, Storage};
 use crate::tmush::commands::TinyMushProcessor;
 use crate::validation::{sanitize_message_content, validate_user_name};
 
+/// Bytes of mail body shown per read slice (leaves room for header and footer)
+const MAIL_SLICE_BYTES: usize = 90;
+
+/// Bytes of a library file shown per read slice
+const FILE_SLICE_BYTES: usize = 100;
+
+/// Most hits a SEARCH keeps (pages of 5)
+const SEARCH_MAX_HITS: usize = 50;
+
 /// UI rendering helpers for compact, 230-byte-safe outputs
 mod ui {
     /// Truncate a &str to at most max_bytes bytes, not splitting UTF-8; append '…' if truncated
@@ -69,6 +79,22 @@ mod ui {
         line
     }
 
+    /// Split a &str into chunks of at most max_bytes bytes without splitting UTF-8
+    pub fn utf8_slices(s: &str, max_bytes: usize) -> Vec<String> {
+        let mut slices = Vec::new();
+        let mut current = String::new();
+        for ch in s.chars() {
+            if current.len() + ch.len_utf8() > max_bytes && !current.is_empty() {
+                slices.push(std::mem::take(&mut current));
+            }
+            current.push(ch);
+        }
+        if !current.is_empty() || slices.is_empty() {
+            slices.push(current);
+        }
+        slices
+    }
+
     /// Build a compact topics header + list + reply line
     pub fn topics_page(bbs_name: &str, items: &[String], footer: &str) -> String {
         let header = format!("[{}] Topics\n", bbs_name);
@@ -115,7 +141,7 @@ impl CommandProcessor {
 
     /// Render the top-level main menu based on enabled modules
     fn render_main_menu(&self, _session: &Session, config: &Config) -> String {
-        let mut line = String::from("Main Menu:\n[M]essages ");
+        let mut line = String::from("Main Menu:\n[M]essages [E]mail [F]ile [C]hat ");
         if games::has_enabled_doors(&config.games) {
             line.push_str("[G]ames ");
         }
@@ -155,6 +181,14 @@ impl CommandProcessor {
         Ok(out)
     }
 
+    /// Recently heard mesh nodes from the reader's node cache
+    fn render_nodes(&self, storage: &Storage, page: usize) -> Result<String> {
+        Ok(match super::nodes::load_node_cache(storage.base_dir()) {
+            Some(cache) => super::nodes::render_nodes_page(&cache, page, chrono::Utc::now()),
+            None => "No nodes heard yet.\n".into(),
+        })
+    }
+
     fn where_am_i(&self, session: &Session, config: &Config) -> String {
         // Build a compact breadcrumb like: BBS > Topics > hello > Threads > Read
         let mut parts: Vec<String> = vec![config.bbs.name.clone()];
@@ -202,6 +236,15 @@ impl CommandProcessor {
             SessionState::ConfirmDelete => {
                 parts.push("Confirm".into());
             }
+            SessionState::SearchResults => {
+                parts.push("Search".into());
+            }
+            SessionState::Chat => {
+                parts.push("Chat".into());
+                if let Some(room) = &session.current_chat_room {
+                    parts.push(room.clone());
+                }
+            }
             SessionState::UserMenu
             | SessionState::UserChangePassCurrent
             | SessionState::UserChangePassNew
@@ -214,6 +257,33 @@ impl CommandProcessor {
             SessionState::TinyMush => {
                 parts.push("TinyMUSH".into());
             }
+            SessionState::MailInbox => {
+                parts.push("Mail".into());
+                parts.push("Inbox".into());
+            }
+            SessionState::MailSent => {
+                parts.push("Mail".into());
+                parts.push("Sent".into());
+            }
+            SessionState::MailRead => {
+                parts.push("Mail".into());
+                parts.push("Read".into());
+            }
+            SessionState::MailComposeTo | SessionState::MailComposeBody => {
+                parts.push("Mail".into());
+                parts.push("Compose".into());
+            }
+            SessionState::FileAreas | SessionState::FileList | SessionState::FileRead => {
+                parts.push("Files".into());
+                if session.state != SessionState::FileAreas {
+                    if let Some(area) = &session.current_file_area {
+                        parts.push(area.clone());
+                    }
+                }
+                if session.state == SessionState::FileRead {
+                    parts.push("Read".into());
+                }
+            }
             SessionState::ReadingMessages => {
                 parts.push("Topics".into());
                 if let Some(t) = &session.current_topic {
@@ -259,6 +329,50 @@ impl CommandProcessor {
             }
         }
 
+        // Global SEARCH from any board browsing state
+        if session.is_logged_in()
+            && (cmd_upper == "SEARCH" || cmd_upper.starts_with("SEARCH "))
+            && matches!(
+                session.state,
+                SessionState::MainMenu
+                    | SessionState::Topics
+                    | SessionState::Subtopics
+                    | SessionState::Threads
+                    | SessionState::ThreadRead
+                    | SessionState::SearchResults
+            )
+        {
+            let query = raw.get(6..).unwrap_or("").trim();
+            if query.is_empty() {
+                return Ok("Usage: SEARCH <words>\n".into());
+            }
+            session.search_query = Some(query.to_string());
+            session.filter_text = None;
+            session.list_page = 1;
+            session.state = SessionState::SearchResults;
+            return self.render_search_results(session, storage).await;
+        }
+
+        // Reply notifications: follow threads and topics, opt in or out
+        if session.is_logged_in()
+            && matches!(
+                session.state,
+                SessionState::MainMenu
+                    | SessionState::Topics
+                    | SessionState::Subtopics
+                    | SessionState::Threads
+                    | SessionState::ThreadRead
+                    | SessionState::SearchResults
+            )
+        {
+            if let Some(reply) = self
+                .handle_subscription_command(session, &cmd_upper, storage)
+                .await?
+            {
+                return Ok(reply);
+            }
+        }
+
         match session.state {
             SessionState::Connected => {
                 self.handle_initial_connection(session, &cmd_upper, storage, config)
@@ -370,6 +484,10 @@ impl CommandProcessor {
                 self.handle_thread_read(session, raw, &cmd_upper, storage, config)
                     .await
             }
+            SessionState::SearchResults => {
+                self.handle_search_results(session, &cmd_upper, storage, config)
+                    .await
+            }
             SessionState::ComposeNewTitle => {
                 self.handle_compose_new_title(session, raw, storage, config)
                     .await
@@ -414,6 +532,40 @@ impl CommandProcessor {
                 self.handle_user_set_pass_new(session, raw, storage, config)
                     .await
             }
+            SessionState::MailInbox | SessionState::MailSent => {
+                self.handle_mail_list(session, raw, &cmd_upper, storage, config)
+                    .await
+            }
+            SessionState::MailRead => {
+                self.handle_mail_read(session, &cmd_upper, storage, config)
+                    .await
+            }
+            SessionState::MailComposeTo => {
+                self.handle_mail_compose_to(session, raw, storage, config)
+                    .await
+            }
+            SessionState::MailComposeBody => {
+                self.handle_mail_compose_body(session, raw, storage, config)
+                    .await
+            }
+            SessionState::FileAreas => {
+                self.handle_file_areas(session, raw, &cmd_upper, storage, config)
+                    .await
+            }
+            SessionState::FileList => {
+                self.handle_file_list(session, raw, &cmd_upper, storage, config)
+                    .await
+            }
+            SessionState::FileRead => {
+                self.handle_file_read(session, &cmd_upper, storage, config)
+                    .await
+            }
+            SessionState::Chat => {
+                // Chat input is handled by the server, which knows the other members
+                session.state = SessionState::MainMenu;
+                session.current_chat_room = None;
+                Ok(self.render_main_menu(session, config))
+            }
             SessionState::Disconnected => Ok("Session disconnected.".to_string()),
         }
     }
@@ -567,6 +719,17 @@ impl CommandProcessor {
                 session.list_page = 1;
                 self.render_topics_page(session, storage, config).await
             }
+            "E" => {
+                session.state = SessionState::MailInbox;
+                session.list_page = 1;
+                self.render_mail_list(session, storage).await
+            }
+            "F" => {
+                session.state = SessionState::FileAreas;
+                session.current_file_area = None;
+                session.list_page = 1;
+                self.render_file_areas(session, storage).await
+            }
             "P" => {
                 session.state = SessionState::UserMenu;
                 self.render_preferences_menu(session, storage).await
@@ -575,6 +738,23 @@ impl CommandProcessor {
                 session.logout().await?;
                 Ok("Goodbye!".to_string())
             }
+            "NODES" | "LASTHEARD" | "LH" => self.render_nodes(storage, 1),
+            cmd if cmd.starts_with("NODES ") => {
+                let page = cmd[6..].trim().parse::<usize>().unwrap_or(1);
+                self.render_nodes(storage, page)
+            }
+            cmd if cmd.starts_with("NODE ") => {
+                let query = cmd[5..].trim();
+                let Some(cache) = super::nodes::load_node_cache(storage.base_dir()) else {
+                    return Ok("No nodes heard yet.\n".into());
+                };
+                Ok(match cache.find(query) {
+                    Some(node) => {
+                        super::nodes::render_node_detail(&cache, node, chrono::Utc::now())
+                    }
+                    None => format!("Node {} not heard. NODES lists recent\n", query),
+                })
+            }
             "H" | "?" => {
                 // Build compact contextual help to fit within 230 bytes
                 let mut out = String::new();
@@ -586,7 +766,7 @@ impl CommandProcessor {
                     return Ok(out);
                 }
                 out.push_str("ACCT: P then [C]hange/[N]ew pass | [L]ogout\n");
-                out.push_str("MSG: M topics; digits pick; +/- next; F <txt> filter\n");
+                out.push_str("MSG: M topics; digits pick; +/- next; F <txt> filter; SEARCH\n");
                 if !game_doors.is_empty() {
                     out.push_str("GAME: G list; G# launch\n");
                 }
@@ -598,7 +778,7 @@ impl CommandProcessor {
                 if session.user_level >= 10 {
                     out.push_str("ADM: G @user=LEVEL | SYSLOG LEVEL msg | ADMIN\n");
                 }
-                out.push_str("OTHER: WHERE | U | Q\n");
+                out.push_str("OTHER: E mail | F files | C chat | NODES | WHERE | U | Q\n");
                 // Ensure length <=230 (should already be compact; final guard)
                 const MAX: usize = 230;
                 if out.len() > MAX {
@@ -1147,7 +1327,8 @@ impl CommandProcessor {
                 }
             }
             let pin = if m.pinned { " \u{1F4CC}" } else { "" }; // 📌
-            items.push(format!("{}{} {}{}", i + 1, pin, title, marker));
+            let poll = if m.poll.is_some() { " \u{1F4CA}" } else { "" }; // 📊
+            items.push(format!("{}{}{} {}{}", i + 1, pin, poll, title, marker));
         }
         let topic_disp = config
             .message_topics
@@ -1412,8 +1593,7 @@ impl CommandProcessor {
         } else {
             return self.render_threads_list(session, storage, config).await;
         };
-        let msgs = storage.get_messages(&topic, 200).await?;
-        if let Some(m) = msgs.into_iter().find(|mm| mm.id == id) {
+        if let Some(m) = storage.get_message(&topic, &id).await? {
             let topic_disp = config
                 .message_topics
                 .get(&topic)
@@ -1430,8 +1610,13 @@ impl CommandProcessor {
                 "[BBS][{} > {}]{}{} p1/1\n",
                 topic_disp, title, pin_note, locked_note
             );
-            // Show full body; rely on sender auto-chunking for large content
-            let mut body = m.content.clone();
+            // Show full body; rely on sender auto-chunking for large content.
+            // Poll threads show the current results in place of the question.
+            let now = chrono::Utc::now();
+            let mut body = match &m.poll {
+                Some(poll) => poll.render(now, "\n", POLL_RESULTS_MAX_BYTES),
+                None => m.content.clone(),
+            };
             if let Some(last) = m.replies.last() {
                 let rp = match last {
                     ReplyEntry::Legacy(s) => s.clone(),
@@ -1443,7 +1628,11 @@ impl CommandProcessor {
                 body.push_str("\n- ");
                 body.push_str(&rp);
             }
-            let footer = "Reply: + next, - prev, Y reply, B back, H help";
+            let footer = if m.poll.as_ref().is_some_and(|p| p.is_open(now)) {
+                "Reply: V<n> vote, + next, - prev, Y reply, B back, H help"
+            } else {
+                "Reply: + next, - prev, Y reply, B back, H help"
+            };
             Ok(format!("{}{}\n{}\n", head, body, footer))
         } else {
             Ok("Thread missing. B back.\n".into())
@@ -1459,17 +1648,25 @@ impl CommandProcessor {
         config: &Config,
     ) -> Result<String> {
         match upper {
+            "B" if session.search_query.is_some() => {
+                // Opened from SEARCH results: go back to the same results page
+                session.state = SessionState::SearchResults;
+                return self.render_search_results(session, storage).await;
+            }
             "B" => {
                 // From read, go back to threads; threads handler will handle further 'B'
                 session.state = SessionState::Threads;
                 return self.render_threads_list(session, storage, config).await;
             }
             "Q" => {
+                session.search_query = None;
                 session.state = SessionState::MainMenu;
                 return Ok(self.render_main_menu(session, config));
             }
             "H" | "?" => {
-                let mut s = "Read: + next, - prev, Y reply, B back, M topics".to_string();
+                let mut s =
+                    "Read: + next, - prev, Y reply, V<n> vote, SUB follow, B back, M topics"
+                        .to_string();
                 if session.user_level >= LEVEL_MODERATOR {
                     s.push_str(" | mod: D delete, P pin, R rename, K lock");
                 }
@@ -1502,6 +1699,27 @@ impl CommandProcessor {
             }
             _ => {}
         }
+        // V<n>: vote in a poll thread
+        if let Some(choice) = upper
+            .strip_prefix('V')
+            .and_then(|n| n.trim().parse::<usize>().ok())
+        {
+            let topic = session
+                .current_topic
+                .clone()
+                .unwrap_or_else(|| "general".into());
+            if let Some(id) = &session.current_thread_id {
+                let user = session.display_name();
+                return Ok(match storage.vote_poll(&topic, id, &user, choice).await {
+                    Ok(poll) => format!(
+                        "Voted {}.\n{}\n",
+                        choice,
+                        poll.render(chrono::Utc::now(), "\n", POLL_RESULTS_MAX_BYTES)
+                    ),
+                    Err(e) => format!("{}.\n", e),
+                });
+            }
+        }
         // Moderator actions while reading a thread
         if session.user_level >= LEVEL_MODERATOR {
             // Delete current
@@ -1564,6 +1782,222 @@ impl CommandProcessor {
         self.render_thread_read(session, storage, config).await
     }
 
+    /// SUB / UNSUB / SUBS / NOTIFY. Returns `None` for anything else.
+    ///
+    /// Without an argument SUB follows the open thread (ThreadRead) or the
+    /// current topic (Threads, Subtopics); `SUB <topic>` follows a topic by id.
+    async fn handle_subscription_command(
+        &self,
+        session: &Session,
+        upper: &str,
+        storage: &mut Storage,
+    ) -> Result<Option<String>> {
+        use crate::storage::subscriptions::SubscriptionTarget;
+        let mut words = upper.split_whitespace();
+        let verb = words.next().unwrap_or("");
+        let arg = words.next().map(|w| w.to_lowercase());
+        let user = session.display_name();
+        match verb {
+            "SUB" | "SUBSCRIBE" | "UNSUB" | "UNSUBSCRIBE" => {
+                let target = match (arg, &session.state) {
+                    (Some(topic), _) => {
+                        if !storage.topic_exists(&topic)
+                            || !self_topic_can_read(session.user_level, &topic, storage)
+                        {
+                            return Ok(Some(format!("No topic '{}'.\n", topic)));
+                        }
+                        SubscriptionTarget::Topic(topic)
+                    }
+                    (None, SessionState::ThreadRead) => {
+                        match (&session.current_topic, &session.current_thread_id) {
+                            (Some(topic), Some(id)) => SubscriptionTarget::Thread {
+                                topic: topic.clone(),
+                                id: id.clone(),
+                            },
+                            _ => return Ok(Some("Open a thread first.\n".into())),
+                        }
+                    }
+                    (None, SessionState::Threads | SessionState::Subtopics) => {
+                        match &session.current_topic {
+                            Some(topic) => SubscriptionTarget::Topic(topic.clone()),
+                            None => return Ok(Some("Open a topic first.\n".into())),
+                        }
+                    }
+                    (None, _) => {
+                        return Ok(Some(format!(
+                            "Usage: {} <topic>, or from a thread or topic\n",
+                            verb
+                        )))
+                    }
+                };
+                let what = match &target {
+                    SubscriptionTarget::Thread { .. } => "this thread".to_string(),
+                    SubscriptionTarget::Topic(topic) => topic.clone(),
+                };
+                let reply = if verb.starts_with("UN") {
+                    if storage.unsubscribe(&user, &target).await? {
+                        format!("Unsubscribed from {}.\n", what)
+                    } else {
+                        format!("You were not following {}.\n", what)
+                    }
+                } else if storage.subscribe(&user, target).await? {
+                    format!(
+                        "Following {}. You'll get a DM when there are replies.\n",
+                        what
+                    )
+                } else {
+                    format!("Already following {}.\n", what)
+                };
+                Ok(Some(reply))
+            }
+            "SUBS" => {
+                let subs = storage.get_subscriptions(&user).await?;
+                if subs.targets.is_empty() {
+                    return Ok(Some(
+                        "Not following anything. SUB in a thread or topic.\n".into(),
+                    ));
+                }
+                let mut out = format!(
+                    "Following {} (notify {}):\n",
+                    subs.targets.len(),
+                    if subs.muted { "off" } else { "on" }
+                );
+                for target in subs.targets.iter().rev().take(6) {
+                    let line = match target {
+                        SubscriptionTarget::Topic(topic) => format!("[{}]", topic),
+                        SubscriptionTarget::Thread { topic, id } => {
+                            let title = match storage.get_message(topic, id).await? {
+                                Some(m) => m.title.unwrap_or(m.content),
+                                None => "(gone)".to_string(),
+                            };
+                            format!("{}: {}", topic, ui::utf8_truncate(&title, 20))
+                        }
+                    };
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                Ok(Some(out))
+            }
+            "NOTIFY" => {
+                let enabled = match arg.as_deref() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    _ => {
+                        let subs = storage.get_subscriptions(&user).await?;
+                        return Ok(Some(format!(
+                            "Reply notifications are {}. NOTIFY ON|OFF\n",
+                            if subs.muted { "off" } else { "on" }
+                        )));
+                    }
+                };
+                storage.set_notifications(&user, enabled).await?;
+                Ok(Some(format!(
+                    "Reply notifications {}.\n",
+                    if enabled { "on" } else { "off" }
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Ranked SEARCH hits for the session's query, 5 per page
+    async fn render_search_results(
+        &self,
+        session: &Session,
+        storage: &mut Storage,
+    ) -> Result<String> {
+        let query = session.search_query.clone().unwrap_or_default();
+        let level = session.user_level;
+        let hits = storage
+            .search_messages(
+                &query,
+                |t| self_topic_can_read(level, t, storage),
+                SEARCH_MAX_HITS,
+            )
+            .await?;
+        if hits.is_empty() {
+            return Ok(format!(
+                "No hits for \"{}\". B back\n",
+                ui::utf8_truncate(&query, 40)
+            ));
+        }
+        let start = (session.list_page.saturating_sub(1)) * 5;
+        let page = hits.get(start..(start + 5).min(hits.len())).unwrap_or(&[]);
+        let items: Vec<String> = page
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let topic: String = h.topic.chars().take(8).collect();
+                format!("{} {}:{}", i + 1, topic, ui::utf8_truncate(&h.title, 14))
+            })
+            .collect();
+        let more = if hits.len() >= SEARCH_MAX_HITS {
+            "+"
+        } else {
+            ""
+        };
+        Ok(format!(
+            "[BBS] Search: {}{} hits\n{}\nReply: 1-5 read, L more, B back\n",
+            hits.len(),
+            more,
+            ui::list_1_to_5(&items)
+        ))
+    }
+
+    async fn handle_search_results(
+        &self,
+        session: &mut Session,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        match upper {
+            "B" | "M" => {
+                session.search_query = None;
+                session.state = SessionState::Topics;
+                session.list_page = 1;
+                return self.render_topics_page(session, storage, config).await;
+            }
+            "Q" => {
+                session.search_query = None;
+                session.state = SessionState::MainMenu;
+                return Ok(self.render_main_menu(session, config));
+            }
+            "L" => {
+                session.list_page += 1;
+                return self.render_search_results(session, storage).await;
+            }
+            "H" | "?" => {
+                return Ok("Search: 1-5 read, L more, B back, SEARCH <words> again\n".into());
+            }
+            _ => {}
+        }
+        if let Ok(n) = upper.parse::<usize>() {
+            if (1..=5).contains(&n) {
+                let query = session.search_query.clone().unwrap_or_default();
+                let level = session.user_level;
+                let hits = storage
+                    .search_messages(
+                        &query,
+                        |t| self_topic_can_read(level, t, storage),
+                        SEARCH_MAX_HITS,
+                    )
+                    .await?;
+                let idx = (session.list_page.saturating_sub(1)) * 5 + (n - 1);
+                if let Some(hit) = hits.get(idx) {
+                    session.current_topic = Some(hit.topic.clone());
+                    session.current_thread_id = Some(hit.id.clone());
+                    session.post_index = 1;
+                    session.slice_index = 1;
+                    session.state = SessionState::ThreadRead;
+                    return self.render_thread_read(session, storage, config).await;
+                }
+                return Ok("No more items. L shows more, B back\n".into());
+            }
+        }
+        self.render_search_results(session, storage).await
+    }
+
     async fn handle_compose_new_title(
         &self,
         session: &mut Session,
@@ -1999,6 +2433,572 @@ impl CommandProcessor {
         out.push_str(&self.render_preferences_menu(session, storage).await?);
         Ok(out)
     }
+
+    fn mail_folder(session: &Session) -> MailFolder {
+        if session.state == SessionState::MailSent {
+            MailFolder::Sent
+        } else {
+            MailFolder::Inbox
+        }
+    }
+
+    async fn render_mail_list(&self, session: &Session, storage: &mut Storage) -> Result<String> {
+        let user = session.display_name();
+        let folder = Self::mail_folder(session);
+        let mail = storage.get_mail(&user, folder).await?;
+        let start = (session.list_page.saturating_sub(1)) * 5;
+        let page = mail.get(start..(start + 5).min(mail.len())).unwrap_or(&[]);
+        let mut items: Vec<String> = Vec::new();
+        for (i, m) in page.iter().enumerate() {
+            let title = ui::utf8_truncate(m.content.lines().next().unwrap_or(""), 20);
+            let line = match folder {
+                MailFolder::Inbox => {
+                    let marker = if m.read { "" } else { "*" };
+                    format!("{} {}: {}{}", i + 1, m.from, title, marker)
+                }
+                MailFolder::Sent => format!("{} >{}: {}", i + 1, m.to, title),
+            };
+            items.push(line);
+        }
+        let header = match folder {
+            MailFolder::Inbox => {
+                let unread = mail.iter().filter(|m| !m.read).count();
+                format!(
+                    "[BBS] Inbox {}/{} ({} new)\n",
+                    mail.len(),
+                    storage.mail_quota(),
+                    unread
+                )
+            }
+            MailFolder::Sent => format!("[BBS] Sent {}/{}\n", mail.len(), storage.mail_quota()),
+        };
+        let list = if items.is_empty() {
+            "No mail.\n".to_string()
+        } else {
+            format!("{}\n", ui::list_1_to_5(&items))
+        };
+        let footer = match folder {
+            MailFolder::Inbox => "Reply: 1-5 read, C compose, S sent, L more, B back",
+            MailFolder::Sent => "Reply: 1-5 read, C compose, I inbox, L more, B back",
+        };
+        Ok(format!("{}{}{}\n", header, list, footer))
+    }
+
+    async fn handle_mail_list(
+        &self,
+        session: &mut Session,
+        raw: &str,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        match upper {
+            "H" | "?" => {
+                return Ok(
+                    "Mail: 1-5 read, D<n> del, C compose, I inbox, S sent, L more, B back\n".into(),
+                );
+            }
+            "B" => {
+                session.state = SessionState::MainMenu;
+                return Ok(self.render_main_menu(session, config));
+            }
+            "Q" | "X" => {
+                session.state = SessionState::MainMenu;
+                return Ok(self.render_main_menu(session, config));
+            }
+            "I" | "S" => {
+                session.state = if upper == "I" {
+                    SessionState::MailInbox
+                } else {
+                    SessionState::MailSent
+                };
+                session.list_page = 1;
+                return self.render_mail_list(session, storage).await;
+            }
+            "L" => {
+                session.list_page += 1;
+                return self.render_mail_list(session, storage).await;
+            }
+            "C" => {
+                session.state = SessionState::MailComposeTo;
+                session.pending_input = None;
+                return Ok("[BBS] Mail to (username, . cancels):\n".into());
+            }
+            _ => {}
+        }
+        let user = session.display_name();
+        let folder = Self::mail_folder(session);
+        // D<n>: delete the nth message on this page
+        if upper.starts_with('D') {
+            let idx_str = raw
+                .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+                .trim();
+            if let Some(n) = idx_str.chars().find_map(|c| c.to_digit(10)) {
+                let mail = storage.get_mail(&user, folder).await?;
+                let idx =
+                    (session.list_page.saturating_sub(1)) * 5 + (n as usize).saturating_sub(1);
+                if let Some(m) = mail.get(idx) {
+                    storage.delete_mail(&user, folder, &m.id).await?;
+                    let mut body = String::from("Deleted.\n");
+                    body.push_str(&self.render_mail_list(session, storage).await?);
+                    return Ok(body);
+                }
+                return Ok("No such item on this page.\n".into());
+            }
+            return Ok("Usage: D<n> (e.g., D1)\n".into());
+        }
+        if let Some(ch) = raw.chars().next() {
+            if ch.is_ascii_digit() && ch != '0' {
+                let n = ch.to_digit(10).unwrap() as usize;
+                let mail = storage.get_mail(&user, folder).await?;
+                let idx = (session.list_page.saturating_sub(1)) * 5 + (n - 1);
+                if let Some(m) = mail.get(idx) {
+                    session.current_thread_id = Some(m.id.clone());
+                    session.slice_index = 1;
+                    session.state = SessionState::MailRead;
+                    return self.render_mail_read(session, storage).await;
+                }
+                return Ok("No more items. L shows more, B back\n".into());
+            }
+        }
+        self.render_mail_list(session, storage).await
+    }
+
+    /// Find the focused mail message and the folder listing it lives in
+    async fn focused_mail(
+        &self,
+        session: &Session,
+        storage: &Storage,
+    ) -> Result<Option<(MailFolder, Vec<MailMessage>, usize)>> {
+        let Some(id) = session.current_thread_id.as_deref() else {
+            return Ok(None);
+        };
+        let user = session.display_name();
+        for folder in [MailFolder::Inbox, MailFolder::Sent] {
+            let mail = storage.get_mail(&user, folder).await?;
+            if let Some(pos) = mail.iter().position(|m| m.id == id) {
+                return Ok(Some((folder, mail, pos)));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn render_mail_read(&self, session: &Session, storage: &mut Storage) -> Result<String> {
+        let Some((folder, mail, pos)) = self.focused_mail(session, storage).await? else {
+            return Ok("Mail missing. B back.\n".into());
+        };
+        let m = &mail[pos];
+        if folder == MailFolder::Inbox && !m.read {
+            storage
+                .mark_mail_read(&session.display_name(), &m.id)
+                .await?;
+        }
+        let slices = ui::utf8_slices(&m.content, MAIL_SLICE_BYTES);
+        let slice = session.slice_index.clamp(1, slices.len());
+        let who = match folder {
+            MailFolder::Inbox => format!("From {}", m.from),
+            MailFolder::Sent => format!("To {}", m.to),
+        };
+        let head = format!(
+            "[BBS][Mail] {} {} s{}/{}\n",
+            who,
+            m.timestamp.format("%m/%d %H:%M"),
+            slice,
+            slices.len()
+        );
+        let footer = match folder {
+            MailFolder::Inbox => "Reply: + next, - prev, R reply, D del, B back",
+            MailFolder::Sent => "Reply: + next, - prev, D del, B back",
+        };
+        Ok(format!("{}{}\n{}\n", head, slices[slice - 1], footer))
+    }
+
+    async fn handle_mail_read(
+        &self,
+        session: &mut Session,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        let Some((folder, mail, pos)) = self.focused_mail(session, storage).await? else {
+            session.state = SessionState::MailInbox;
+            return self.render_mail_list(session, storage).await;
+        };
+        let list_state = match folder {
+            MailFolder::Inbox => SessionState::MailInbox,
+            MailFolder::Sent => SessionState::MailSent,
+        };
+        match upper {
+            "B" => {
+                session.state = list_state;
+                self.render_mail_list(session, storage).await
+            }
+            "Q" => {
+                session.state = SessionState::MainMenu;
+                Ok(self.render_main_menu(session, config))
+            }
+            "H" | "?" => Ok("Read: + next, - prev, R reply, D delete, B back\n".into()),
+            "+" => {
+                // Next slice of this message, then on to the next (older) one
+                let slices = ui::utf8_slices(&mail[pos].content, MAIL_SLICE_BYTES).len();
+                if session.slice_index < slices {
+                    session.slice_index += 1;
+                } else if pos + 1 < mail.len() {
+                    session.current_thread_id = Some(mail[pos + 1].id.clone());
+                    session.slice_index = 1;
+                } else {
+                    return Ok("End of mail. B back\n".into());
+                }
+                self.render_mail_read(session, storage).await
+            }
+            "-" => {
+                if session.slice_index > 1 {
+                    session.slice_index -= 1;
+                } else if pos > 0 {
+                    let prev = &mail[pos - 1];
+                    session.current_thread_id = Some(prev.id.clone());
+                    session.slice_index = ui::utf8_slices(&prev.content, MAIL_SLICE_BYTES).len();
+                } else {
+                    return Ok("Start of mail. B back\n".into());
+                }
+                self.render_mail_read(session, storage).await
+            }
+            "R" if folder == MailFolder::Inbox => {
+                let to = mail[pos].from.clone();
+                let prompt = format!("[BBS] Mail to {} (. cancels):\n", to);
+                session.pending_input = Some(to);
+                session.state = SessionState::MailComposeBody;
+                Ok(prompt)
+            }
+            "D" => {
+                let user = session.display_name();
+                storage.delete_mail(&user, folder, &mail[pos].id).await?;
+                session.current_thread_id = None;
+                session.state = list_state;
+                let mut body = String::from("Deleted.\n");
+                body.push_str(&self.render_mail_list(session, storage).await?);
+                Ok(body)
+            }
+            _ => self.render_mail_read(session, storage).await,
+        }
+    }
+
+    async fn handle_mail_compose_to(
+        &self,
+        session: &mut Session,
+        raw: &str,
+        storage: &mut Storage,
+        _config: &Config,
+    ) -> Result<String> {
+        let input = raw.trim();
+        if input == "." || input.is_empty() {
+            session.state = SessionState::MailInbox;
+            let mut out = String::from("Mail cancelled.\n");
+            out.push_str(&self.render_mail_list(session, storage).await?);
+            return Ok(out);
+        }
+        let recipient = match validate_user_name(input) {
+            Ok(name) => name,
+            Err(_) => return Ok("Invalid username. Mail to (. cancels):\n".into()),
+        };
+        let Some(user) = storage.get_user(&recipient).await? else {
+            return Ok(format!(
+                "No such user: {}. Mail to (. cancels):\n",
+                recipient
+            ));
+        };
+        if user.username.eq_ignore_ascii_case(&session.display_name()) {
+            return Ok("You cannot mail yourself. Mail to (. cancels):\n".into());
+        }
+        let prompt = format!("[BBS] Mail to {} (. cancels):\n", user.username);
+        session.pending_input = Some(user.username);
+        session.state = SessionState::MailComposeBody;
+        Ok(prompt)
+    }
+
+    async fn handle_mail_compose_body(
+        &self,
+        session: &mut Session,
+        raw: &str,
+        storage: &mut Storage,
+        _config: &Config,
+    ) -> Result<String> {
+        let body = raw.trim();
+        let Some(to) = session.pending_input.clone() else {
+            session.state = SessionState::MailInbox;
+            return self.render_mail_list(session, storage).await;
+        };
+        if body == "." {
+            session.pending_input = None;
+            session.state = SessionState::MailInbox;
+            let mut out = String::from("Mail cancelled.\n");
+            out.push_str(&self.render_mail_list(session, storage).await?);
+            return Ok(out);
+        }
+        if body.is_empty() {
+            return Ok("Body required.\n".into());
+        }
+        let from = session.display_name();
+        let status = match storage.send_mail(&from, &to, body).await {
+            Ok(_) => format!("Mail sent to {}.\n", to),
+            Err(e) => format!("Mail not sent: {}\n", e),
+        };
+        session.pending_input = None;
+        session.state = SessionState::MailInbox;
+        session.list_page = 1;
+        let mut out = status;
+        out.push_str(&self.render_mail_list(session, storage).await?);
+        Ok(out)
+    }
+
+    async fn render_file_areas(&self, session: &Session, storage: &mut Storage) -> Result<String> {
+        let library = storage.get_file_library().await?;
+        let areas = library.visible_areas(session.user_level);
+        let start = (session.list_page.saturating_sub(1)) * 5;
+        let items: Vec<String> = areas
+            .iter()
+            .skip(start)
+            .take(5)
+            .enumerate()
+            .map(|(i, (name, area))| format!("{} {}({})", i + 1, name, area.files.len()))
+            .collect();
+        let list = if areas.is_empty() {
+            "No files yet.\n".to_string()
+        } else if items.is_empty() {
+            "No more areas.\n".to_string()
+        } else {
+            format!("{}\n", ui::list_1_to_5(&items))
+        };
+        Ok(format!(
+            "[BBS] Files ({} areas)\n{}Reply: 1-5 open, L more, B back\n",
+            areas.len(),
+            list
+        ))
+    }
+
+    async fn handle_file_areas(
+        &self,
+        session: &mut Session,
+        raw: &str,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        match upper {
+            "H" | "?" => return Ok("Files: 1-5 open area, L more, B back\n".into()),
+            "B" | "Q" | "X" => {
+                session.state = SessionState::MainMenu;
+                return Ok(self.render_main_menu(session, config));
+            }
+            "L" => {
+                session.list_page += 1;
+                return self.render_file_areas(session, storage).await;
+            }
+            _ => {}
+        }
+        if let Some(n) = raw.chars().next().and_then(|c| c.to_digit(10)) {
+            let library = storage.get_file_library().await?;
+            let areas = library.visible_areas(session.user_level);
+            let idx = (session.list_page.saturating_sub(1)) * 5 + (n as usize).saturating_sub(1);
+            if let Some((name, _)) = areas.get(idx).filter(|_| n > 0) {
+                session.current_file_area = Some(name.to_string());
+                session.list_page = 1;
+                session.state = SessionState::FileList;
+                return self.render_file_list(session, storage).await;
+            }
+            return Ok("No such area. L shows more, B back\n".into());
+        }
+        self.render_file_areas(session, storage).await
+    }
+
+    /// The open file area, when the reader may still see it
+    async fn current_file_area(
+        &self,
+        session: &Session,
+        storage: &Storage,
+    ) -> Result<Option<crate::storage::files::FileArea>> {
+        let Some(name) = session.current_file_area.as_deref() else {
+            return Ok(None);
+        };
+        let mut library = storage.get_file_library().await?;
+        Ok(library
+            .areas
+            .remove(name)
+            .filter(|area| session.user_level >= area.read_level))
+    }
+
+    async fn render_file_list(&self, session: &Session, storage: &mut Storage) -> Result<String> {
+        let Some(area) = self.current_file_area(session, storage).await? else {
+            return Ok("Area missing. B back.\n".into());
+        };
+        let start = (session.list_page.saturating_sub(1)) * 5;
+        let items: Vec<String> = area
+            .files
+            .iter()
+            .skip(start)
+            .take(5)
+            .enumerate()
+            .map(|(i, f)| {
+                format!(
+                    "{} {} {} {}dl",
+                    i + 1,
+                    f.name,
+                    file_size_label(f.size),
+                    f.downloads
+                )
+            })
+            .collect();
+        let mut out = format!(
+            "[BBS] Files > {}\n",
+            session.current_file_area.as_deref().unwrap_or("")
+        );
+        if !area.description.is_empty() {
+            out.push_str(&ui::utf8_truncate(&area.description, 60));
+            out.push('\n');
+        }
+        if area.files.is_empty() {
+            out.push_str("No files.\n");
+        } else if items.is_empty() {
+            out.push_str("No more files.\n");
+        } else {
+            out.push_str(&ui::list_1_to_5(&items));
+            out.push('\n');
+        }
+        out.push_str("Reply: 1-5 read, L more, B back\n");
+        Ok(out)
+    }
+
+    async fn handle_file_list(
+        &self,
+        session: &mut Session,
+        raw: &str,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        match upper {
+            "H" | "?" => return Ok("Area: 1-5 read file, L more, B back, Q main\n".into()),
+            "B" => {
+                session.state = SessionState::FileAreas;
+                session.current_file_area = None;
+                session.list_page = 1;
+                return self.render_file_areas(session, storage).await;
+            }
+            "Q" | "X" => {
+                session.current_file_area = None;
+                session.state = SessionState::MainMenu;
+                return Ok(self.render_main_menu(session, config));
+            }
+            "L" => {
+                session.list_page += 1;
+                return self.render_file_list(session, storage).await;
+            }
+            _ => {}
+        }
+        if let Some(n) = raw.chars().next().and_then(|c| c.to_digit(10)) {
+            let Some(area) = self.current_file_area(session, storage).await? else {
+                return self.render_file_list(session, storage).await;
+            };
+            let idx = (session.list_page.saturating_sub(1)) * 5 + (n as usize).saturating_sub(1);
+            if let Some(file) = area.files.get(idx).filter(|_| n > 0) {
+                let area_name = session.current_file_area.clone().unwrap_or_default();
+                storage.record_file_download(&area_name, &file.name).await?;
+                session.current_thread_id = Some(file.name.clone());
+                session.slice_index = 1;
+                session.state = SessionState::FileRead;
+                return self.render_file_read(session, storage).await;
+            }
+            return Ok("No such file. L shows more, B back\n".into());
+        }
+        self.render_file_list(session, storage).await
+    }
+
+    /// Slices of the open file, or `None` when it is gone or no longer readable
+    async fn file_slices(
+        &self,
+        session: &Session,
+        storage: &Storage,
+    ) -> Result<Option<Vec<String>>> {
+        let (Some(area), Some(name)) = (
+            self.current_file_area(session, storage).await?,
+            session.current_thread_id.as_deref(),
+        ) else {
+            return Ok(None);
+        };
+        if area.file(name).is_none() {
+            return Ok(None);
+        }
+        let area_name = session.current_file_area.as_deref().unwrap_or("");
+        let text = storage.read_library_file(area_name, name).await?;
+        Ok(Some(ui::utf8_slices(&text, FILE_SLICE_BYTES)))
+    }
+
+    async fn render_file_read(&self, session: &Session, storage: &mut Storage) -> Result<String> {
+        let Some(slices) = self.file_slices(session, storage).await? else {
+            return Ok("File missing. B back.\n".into());
+        };
+        let slice = session.slice_index.clamp(1, slices.len());
+        Ok(format!(
+            "[BBS][{} > {}] s{}/{}\n{}\nReply: + next, - prev, B back\n",
+            session.current_file_area.as_deref().unwrap_or(""),
+            session.current_thread_id.as_deref().unwrap_or(""),
+            slice,
+            slices.len(),
+            slices[slice - 1]
+        ))
+    }
+
+    async fn handle_file_read(
+        &self,
+        session: &mut Session,
+        upper: &str,
+        storage: &mut Storage,
+        config: &Config,
+    ) -> Result<String> {
+        match upper {
+            "B" => {
+                session.current_thread_id = None;
+                session.state = SessionState::FileList;
+                self.render_file_list(session, storage).await
+            }
+            "Q" => {
+                session.current_thread_id = None;
+                session.current_file_area = None;
+                session.state = SessionState::MainMenu;
+                Ok(self.render_main_menu(session, config))
+            }
+            "H" | "?" => Ok("Read: + next, - prev, B back, Q main\n".into()),
+            "+" => {
+                let slices = self
+                    .file_slices(session, storage)
+                    .await?
+                    .map(|s| s.len())
+                    .unwrap_or(1);
+                if session.slice_index >= slices {
+                    return Ok("End of file. B back\n".into());
+                }
+                session.slice_index += 1;
+                self.render_file_read(session, storage).await
+            }
+            "-" => {
+                if session.slice_index <= 1 {
+                    return Ok("Start of file. B back\n".into());
+                }
+                session.slice_index -= 1;
+                self.render_file_read(session, storage).await
+            }
+            _ => self.render_file_read(session, storage).await,
+        }
+    }
+}
+
+/// Compact size for file listings ("512b", "1.5k")
+fn file_size_label(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{}b", bytes)
+    } else {
+        format!("{:.1}k", bytes as f64 / 1024.0)
+    }
 }
 
 impl Default for CommandProcessor {
@@ -10,6 +10,9 @@
 //! - [`commands`] - Command processing and execution engine
 //! - [`public`] - Public channel command parsing and discovery protocols
 //! - [`roles`] - User role definitions and permission management
+//! - [`replication`] - Store-and-forward topic sync between BBS nodes
+//! - [`nodes`] - Recently heard mesh nodes (`NODES`, `NODE`, public `SEEN`)
+//! - [`chat`] - Teleconference chat rooms for logged-in users
 //!
 //! ## Architecture
 //!
@@ -63,13 +66,16 @@
 //! 5. User interacts with full BBS command set privately
 //! 6. Session ends with `LOGOUT` or timeout
 
+pub mod chat;
 pub mod commands;
 pub mod dispatch;
 pub mod eightball;
 pub mod fortune;
 pub mod game_registry;
 pub mod games;
+pub mod nodes;
 pub mod public;
+pub mod replication;
 pub mod roles;
 pub mod server;
 pub mod session;
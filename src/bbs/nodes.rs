@@ -0,0 +1,137 @@
+//! Recently heard mesh nodes for the `NODES` / `NODE <id>` DM commands and the public
+//! `<prefix>SEEN <node>` lookup (default prefix `^`).
+//!
+//! Behavior:
+//! - Source: the node cache the Meshtastic reader persists at `<storage.data_dir>/node_cache.json`
+//!   (last heard time, SNR/RSSI, hops, last position and battery per node)
+//! - Read-only: every call re-reads the file, so replies reflect the latest reader save
+//! - Output: compact single-message replies that stay under the 230 byte frame limit
+
+use chrono::{DateTime, Utc};
+
+use crate::meshtastic::{node_cache_path, CachedNodeInfo, NodeCache};
+
+/// Nodes shown per `NODES` page
+pub const NODES_PER_PAGE: usize = 5;
+
+/// Load the node cache persisted under `base_dir`, if the reader has written one yet
+pub fn load_node_cache(base_dir: &str) -> Option<NodeCache> {
+    NodeCache::load_from_file(node_cache_path(base_dir)).ok()
+}
+
+/// Compact age such as `45s`, `12m`, `3h` or `2d`
+pub fn format_age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - then).num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+fn format_distance(km: f64) -> String {
+    if km < 10.0 {
+        format!("{:.1}km", km)
+    } else {
+        format!("{:.0}km", km)
+    }
+}
+
+fn format_battery(node: &CachedNodeInfo) -> Option<String> {
+    match (node.battery_level, node.voltage) {
+        (Some(level), _) if level > 100 => Some("pwr".into()),
+        (Some(level), Some(v)) if v > 0.0 => Some(format!("{}% {:.2}V", level, v)),
+        (Some(level), _) => Some(format!("{}%", level)),
+        (None, Some(v)) if v > 0.0 => Some(format!("{:.2}V", v)),
+        _ => None,
+    }
+}
+
+/// One page of recently heard nodes, most recent first
+pub fn render_nodes_page(cache: &NodeCache, page: usize, now: DateTime<Utc>) -> String {
+    let nodes = cache.recently_heard();
+    if nodes.is_empty() {
+        return "No nodes heard yet.\n".into();
+    }
+    let pages = nodes.len().div_ceil(NODES_PER_PAGE);
+    let page = page.clamp(1, pages);
+    let start = (page - 1) * NODES_PER_PAGE;
+    let mut out = format!("Heard nodes ({}/{}):\n", page, pages);
+    for node in nodes.iter().skip(start).take(NODES_PER_PAGE) {
+        let mut label: String = node.label().chars().take(10).collect();
+        label.push(' ');
+        label.push_str(&format_age(node.heard_at(), now));
+        if let Some(hops) = node.hops_away {
+            label.push_str(&format!(" h{}", hops));
+        }
+        if let Some(km) = cache.distance_km(node.node_id) {
+            label.push(' ');
+            label.push_str(&format_distance(km));
+        }
+        out.push_str(&label);
+        out.push('\n');
+    }
+    if page < pages {
+        out.push_str(&format!("NODES {} more | NODE <id>\n", page + 1));
+    } else {
+        out.push_str("NODE <id> for details\n");
+    }
+    out
+}
+
+/// Last-heard time, link quality, position and battery for a single node
+pub fn render_node_detail(cache: &NodeCache, node: &CachedNodeInfo, now: DateTime<Utc>) -> String {
+    let mut out = format!("{} !{:08x}\n", node.label(), node.node_id);
+    let long = node.long_name.trim();
+    if !long.is_empty() && long != node.label() {
+        out.push_str(&format!("{}\n", long.chars().take(40).collect::<String>()));
+    }
+    out.push_str(&format!("Heard {} ago", format_age(node.heard_at(), now)));
+    if let Some(hops) = node.hops_away {
+        out.push_str(&format!(", {} hops", hops));
+    }
+    out.push('\n');
+    match (node.snr, node.rssi) {
+        (Some(snr), Some(rssi)) => out.push_str(&format!("SNR {:.1} RSSI {}\n", snr, rssi)),
+        (Some(snr), None) => out.push_str(&format!("SNR {:.1}\n", snr)),
+        (None, Some(rssi)) => out.push_str(&format!("RSSI {}\n", rssi)),
+        (None, None) => {}
+    }
+    if let Some(pos) = &node.position {
+        out.push_str(&format!("Pos {:.5},{:.5}", pos.latitude, pos.longitude));
+        if let Some(alt) = pos.altitude {
+            out.push_str(&format!(" {}m", alt));
+        }
+        out.push_str(&format!(" ({} ago)\n", format_age(pos.time, now)));
+        if let Some(km) = cache.distance_km(node.node_id) {
+            out.push_str(&format!("{} from BBS\n", format_distance(km)));
+        }
+    }
+    if let Some(bat) = format_battery(node) {
+        out.push_str(&format!("Battery {}\n", bat));
+    }
+    out
+}
+
+/// One-line answer for the public `<prefix>SEEN <node>` command
+pub fn render_seen(cache: Option<&NodeCache>, query: &str, now: DateTime<Utc>) -> String {
+    let Some((cache, node)) = cache.and_then(|c| c.find(query).map(|n| (c, n))) else {
+        return format!("{} not heard", query);
+    };
+    let mut out = format!(
+        "{} heard {} ago",
+        node.label(),
+        format_age(node.heard_at(), now)
+    );
+    if let Some(hops) = node.hops_away {
+        out.push_str(&format!(", {} hops", hops));
+    }
+    if let Some(km) = cache.distance_km(node.node_id) {
+        out.push_str(&format!(", {} away", format_distance(km)));
+    }
+    if let Some(bat) = format_battery(node) {
+        out.push_str(&format!(", bat {}", bat));
+    }
+    out
+}
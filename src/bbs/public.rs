@@ -2,7 +2,7 @@
 //!
 //! This module implements rate‑limiting and simple prefix‑based commands that can be
 //! used from a shared public chat (e.g. `&lt;prefix&gt;HELP`, `&lt;prefix&gt;LOGIN alice`, `&lt;prefix&gt;SLOT`, `&lt;prefix&gt;8BALL`,
-//! `&lt;prefix&gt;FORTUNE`, `&lt;prefix&gt;WEATHER`; default prefix is `^` and is configurable). The [PublicState] tracks per‑node cooldowns to avoid spam
+//! `&lt;prefix&gt;FORTUNE`, `&lt;prefix&gt;WEATHER`, `&lt;prefix&gt;SEEN node`; default prefix is `^` and is configurable). The [PublicState] tracks per‑node cooldowns to avoid spam
 //! while keeping logic extremely small and fast.
 //!
 //! The [PublicCommandParser] recognizes commands only when prefixed with one of the configured
@@ -10,11 +10,15 @@
 //! accidental triggers from normal conversation. It returns a [PublicCommand] enum for
 //! server code to handle. Arguments after the command are intentionally minimal.
 //!
+//! Each served channel has a [ChannelPolicy] with its own parser, enabled commands and
+//! broadcast policy; see [channel_policies].
+//!
 //! Cooldowns are tuned for interactive feel on a mesh network and can be adjusted by
 //! changing the fields on [PublicState]. The internal maps are periodically pruned to
-//! bound memory usage.
-use log::trace;
-use std::collections::HashMap;
+//! bound memory usage. Nodes on the moderator ignore list are skipped entirely.
+use crate::config::{ChannelBroadcast, Config};
+use log::{trace, warn};
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone)]
@@ -38,6 +42,8 @@ pub struct PublicState {
     // Lightweight cooldown for <prefix>FORTUNE
     pub fortune_last: HashMap<String, Instant>,
     pub fortune_cooldown: Duration,
+    // Nodes a moderator has told us to ignore on the public channel (IGNORE)
+    pub ignored: HashSet<String>,
 }
 
 impl PublicState {
@@ -53,6 +59,7 @@ impl PublicState {
             eightball_cooldown: Duration::from_secs(2),
             fortune_last: HashMap::new(),
             fortune_cooldown: Duration::from_secs(5),
+            ignored: HashSet::new(),
         }
     }
 
@@ -82,6 +89,20 @@ impl PublicState {
         );
     }
 
+    /// Start or stop ignoring public commands from a node
+    pub fn set_ignored(&mut self, node_id: &str, ignored: bool) {
+        if ignored {
+            self.ignored.insert(node_id.to_string());
+            self.pending.remove(node_id);
+        } else {
+            self.ignored.remove(node_id);
+        }
+    }
+
+    pub fn is_ignored(&self, node_id: &str) -> bool {
+        self.ignored.contains(node_id)
+    }
+
     pub fn take_pending(&mut self, node_id: &str) -> Option<String> {
         self.pending.remove(node_id).map(|p| p.requested_username)
     }
@@ -226,6 +247,27 @@ impl PublicCommandParser {
             trace!("Parsed SLOTSTATS from '{}'", raw);
             return PublicCommand::SlotStats;
         }
+        // Latest open poll: <prefix>POLL
+        if body.eq_ignore_ascii_case("POLL") {
+            trace!("Parsed POLL from '{}'", raw);
+            return PublicCommand::Poll;
+        }
+        // Last-heard lookup: <prefix>SEEN <node>
+        if body
+            .get(..4)
+            .map(|s| s.eq_ignore_ascii_case("SEEN"))
+            .unwrap_or(false)
+            && body
+                .get(4..)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+        {
+            let node = body[4..].trim();
+            if node.is_empty() {
+                return PublicCommand::Invalid("Node required".into());
+            }
+            trace!("Parsed SEEN '{}' from '{}'", node, raw);
+            return PublicCommand::Seen(node.to_string());
+        }
         if body.len() >= 5
             && body
                 .get(..5)
@@ -269,6 +311,132 @@ pub enum PublicCommand {
     SlotStats,
     EightBall,
     Fortune,
+    Seen(String),
+    Poll,
     Unknown,
     Invalid(String),
 }
+
+/// Keywords accepted in `[[meshtastic.channels]].commands`
+pub const PUBLIC_COMMAND_KEYWORDS: &[&str] = &[
+    "HELP",
+    "LOGIN",
+    "WEATHER",
+    "SLOT",
+    "SLOTSTATS",
+    "8BALL",
+    "FORTUNE",
+    "SEEN",
+    "POLL",
+];
+
+impl PublicCommand {
+    /// Policy keyword for this command (`HELP` stands for whatever help keyword is
+    /// configured); `None` for unknown and invalid input
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            PublicCommand::Help => Some("HELP"),
+            PublicCommand::Login(_) => Some("LOGIN"),
+            PublicCommand::Weather => Some("WEATHER"),
+            PublicCommand::SlotMachine => Some("SLOT"),
+            PublicCommand::SlotStats => Some("SLOTSTATS"),
+            PublicCommand::EightBall => Some("8BALL"),
+            PublicCommand::Fortune => Some("FORTUNE"),
+            PublicCommand::Seen(_) => Some("SEEN"),
+            PublicCommand::Poll => Some("POLL"),
+            PublicCommand::Unknown | PublicCommand::Invalid(_) => None,
+        }
+    }
+}
+
+/// How public commands are handled on one channel
+#[derive(Clone)]
+pub struct ChannelPolicy {
+    pub index: u32,
+    pub parser: PublicCommandParser,
+    /// Enabled command keywords; `None` enables all of them
+    pub commands: Option<HashSet<&'static str>>,
+    pub broadcast: ChannelBroadcast,
+}
+
+impl ChannelPolicy {
+    /// Whether `cmd` is answered on this channel. Invalid input is answered only
+    /// when every command is enabled, so disabled commands stay silent.
+    pub fn allows(&self, cmd: &PublicCommand) -> bool {
+        match (&self.commands, cmd.keyword()) {
+            (None, _) => true,
+            (Some(enabled), Some(keyword)) => enabled.contains(keyword),
+            (Some(_), None) => matches!(cmd, PublicCommand::Unknown),
+        }
+    }
+
+    /// Whether `keyword` (one of [PUBLIC_COMMAND_KEYWORDS]) is enabled
+    pub fn enables(&self, keyword: &str) -> bool {
+        self.commands
+            .as_ref()
+            .is_none_or(|enabled| enabled.contains(keyword))
+    }
+}
+
+/// Policies for every channel the BBS serves: the primary `meshtastic.channel`
+/// (from the `[bbs]` prefix and help keyword unless listed explicitly) followed by
+/// each `[[meshtastic.channels]]` entry. Later duplicates of an index are ignored.
+pub fn channel_policies(config: &Config) -> Vec<ChannelPolicy> {
+    let bbs = &config.bbs;
+    let mut policies: Vec<ChannelPolicy> = Vec::new();
+    for ch in &config.meshtastic.channels {
+        if policies.iter().any(|p| p.index == ch.index as u32) {
+            warn!(
+                "Channel {} is listed more than once; using the first entry",
+                ch.index
+            );
+            continue;
+        }
+        let commands = ch.commands.as_ref().map(|names| {
+            names
+                .iter()
+                .filter_map(|name| {
+                    let keyword = PUBLIC_COMMAND_KEYWORDS
+                        .iter()
+                        .find(|k| k.eq_ignore_ascii_case(name.trim()))
+                        .copied();
+                    if keyword.is_none() {
+                        warn!("Unknown public command '{}' for channel {}", name, ch.index);
+                    }
+                    keyword
+                })
+                .collect()
+        });
+        policies.push(ChannelPolicy {
+            index: ch.index as u32,
+            parser: PublicCommandParser::new_with_prefix(
+                ch.prefix
+                    .clone()
+                    .or_else(|| bbs.public_command_prefix.clone()),
+                Some(
+                    ch.help_command
+                        .clone()
+                        .unwrap_or_else(|| bbs.help_command.clone()),
+                ),
+            ),
+            commands,
+            broadcast: ch.broadcast,
+        });
+    }
+    let primary = config.meshtastic.channel as u32;
+    if !policies.iter().any(|p| p.index == primary) {
+        policies.insert(
+            0,
+            ChannelPolicy {
+                index: primary,
+                parser: PublicCommandParser::new_with_prefix(
+                    bbs.public_command_prefix.clone(),
+                    Some(bbs.help_command.clone()),
+                ),
+                commands: None,
+                broadcast: ChannelBroadcast::Public,
+            },
+        );
+    }
+    policies
+}
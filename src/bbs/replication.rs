@@ -0,0 +1,647 @@
+//! Store-and-forward topic replication between meshbbs nodes.
+//!
+//! Two or more BBS instances on the same mesh keep opted-in topics in sync using
+//! compact text frames sent as direct messages. Every frame starts with
+//! [`SYNC_PREFIX`] so the server can divert peer traffic before session handling.
+//!
+//! Protocol (one round, initiated independently by each side):
+//!
+//! ```text
+//! A -> B  INV  general:12:3fa1,tech:3:0c2e     per-topic count + digest of newest IDs
+//! B -> A  HAVE general 6650a1b2c3d4,...        only for topics whose digest differs
+//! A -> B  WANT general 6650a1b2c3d4,...        IDs A lacks
+//! B -> A  MSG  6650a1b2c3d4 1/3 {...}          chunked JSON, reassembled by message_id
+//! ```
+//!
+//! Received messages are verified against their `crc16`, deduplicated by
+//! `message_id`, and stored with their original author and timestamp. Because each
+//! side pulls what it lacks, a round in each direction converges both nodes.
+//! Replies are not replicated; only top-level posts travel between nodes.
+//!
+//! Frames are queued at `Background` priority in the dispatch scheduler so sync
+//! traffic never delays interactive replies.
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use crc::{Crc, CRC_16_IBM_SDLC};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::config::ReplicationConfig;
+use crate::storage::{Message, Storage};
+
+/// Marker that starts every replication frame
+pub const SYNC_PREFIX: &str = "~SY1 ";
+
+/// Length of a `Message::message_id` (6 bytes as hex)
+const MESSAGE_ID_LEN: usize = 12;
+
+/// Smallest data payload `message_frames` puts in one MSG frame
+const MIN_PART_BYTES: usize = 16;
+
+/// Upper bound on a message's wire JSON: the 10 KB post body limit plus room for
+/// JSON escaping, title and metadata
+const MAX_WIRE_BYTES: usize = 20_000;
+
+/// Most MSG frames one message can span; frames claiming more are rejected so a
+/// peer cannot make us allocate an arbitrarily large reassembly buffer
+pub const MAX_SYNC_PARTS: usize = MAX_WIRE_BYTES / MIN_PART_BYTES;
+
+/// Incomplete transfers are discarded after this long without progress
+const ASSEMBLY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Per-topic summary advertised in an inventory frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopicDigest {
+    pub topic: String,
+    pub count: usize,
+    pub digest: u16,
+}
+
+/// One replication frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncFrame {
+    Inventory(Vec<TopicDigest>),
+    Have {
+        topic: String,
+        ids: Vec<String>,
+    },
+    Want {
+        topic: String,
+        ids: Vec<String>,
+    },
+    Part {
+        id: String,
+        index: usize,
+        total: usize,
+        data: String,
+    },
+}
+
+impl SyncFrame {
+    /// Render the frame as DM text
+    pub fn encode(&self) -> String {
+        match self {
+            SyncFrame::Inventory(digests) => {
+                let body: Vec<String> = digests
+                    .iter()
+                    .map(|d| format!("{}:{}:{:04x}", d.topic, d.count, d.digest))
+                    .collect();
+                format!("{}INV {}", SYNC_PREFIX, body.join(","))
+            }
+            SyncFrame::Have { topic, ids } => {
+                format!("{}HAVE {} {}", SYNC_PREFIX, topic, ids.join(","))
+            }
+            SyncFrame::Want { topic, ids } => {
+                format!("{}WANT {} {}", SYNC_PREFIX, topic, ids.join(","))
+            }
+            SyncFrame::Part {
+                id,
+                index,
+                total,
+                data,
+            } => format!("{}MSG {} {}/{} {}", SYNC_PREFIX, id, index, total, data),
+        }
+    }
+
+    /// Parse DM text; `None` for anything that is not a well-formed frame
+    pub fn parse(text: &str) -> Option<Self> {
+        let rest = text.strip_prefix(SYNC_PREFIX)?;
+        let (kind, body) = rest.split_once(' ').unwrap_or((rest, ""));
+        match kind {
+            "INV" => {
+                let mut digests = Vec::new();
+                for entry in body.split(',').filter(|e| !e.is_empty()) {
+                    let mut fields = entry.split(':');
+                    let topic = fields.next()?.to_string();
+                    let count = fields.next()?.parse().ok()?;
+                    let digest = u16::from_str_radix(fields.next()?, 16).ok()?;
+                    digests.push(TopicDigest {
+                        topic,
+                        count,
+                        digest,
+                    });
+                }
+                Some(SyncFrame::Inventory(digests))
+            }
+            "HAVE" | "WANT" => {
+                let (topic, ids) = body.split_once(' ').unwrap_or((body, ""));
+                if topic.is_empty() {
+                    return None;
+                }
+                let ids: Vec<String> = ids
+                    .split(',')
+                    .filter(|id| is_message_id(id))
+                    .map(str::to_string)
+                    .collect();
+                let topic = topic.to_string();
+                Some(if kind == "HAVE" {
+                    SyncFrame::Have { topic, ids }
+                } else {
+                    SyncFrame::Want { topic, ids }
+                })
+            }
+            "MSG" => {
+                let mut fields = body.splitn(3, ' ');
+                let id = fields.next()?.to_string();
+                let (index, total) = fields.next()?.split_once('/')?;
+                let index: usize = index.parse().ok()?;
+                let total: usize = total.parse().ok()?;
+                let data = fields.next().unwrap_or("").to_string();
+                if !is_message_id(&id) || index == 0 || index > total || total > MAX_SYNC_PARTS {
+                    return None;
+                }
+                Some(SyncFrame::Part {
+                    id,
+                    index,
+                    total,
+                    data,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_message_id(id: &str) -> bool {
+    id.len() == MESSAGE_ID_LEN && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Split `json` into pieces of at most `budget` bytes on char boundaries
+fn chunk_json(json: &str, budget: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for ch in json.chars() {
+        if current.len() + ch.len_utf8() > budget {
+            chunks.push(std::mem::take(&mut current));
+        }
+        current.push(ch);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Digest over a set of message IDs (order independent)
+pub fn ids_digest(ids: &[String]) -> u16 {
+    const CRC16: Crc<u16> = Crc::<u16>::new(&CRC_16_IBM_SDLC);
+    let mut sorted: Vec<&String> = ids.iter().collect();
+    sorted.sort();
+    let mut digest = CRC16.digest();
+    for id in sorted {
+        digest.update(id.as_bytes());
+    }
+    digest.finalize()
+}
+
+/// Wire form of a replicated post
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WireMessage {
+    id: String,
+    topic: String,
+    author: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    content: String,
+    ts: DateTime<Utc>,
+    crc: u16,
+}
+
+impl WireMessage {
+    fn from_message(message: &Message) -> Option<Self> {
+        let id = message.message_id.clone()?;
+        let crc = message.crc16.unwrap_or_else(|| {
+            Storage::calculate_message_crc(
+                &message.topic,
+                &message.author,
+                &message.content,
+                &message.timestamp,
+            )
+        });
+        Some(Self {
+            id,
+            topic: message.topic.clone(),
+            author: message.author.clone(),
+            title: message.title.clone(),
+            content: message.content.clone(),
+            ts: message.timestamp,
+            crc,
+        })
+    }
+
+    fn crc_ok(&self) -> bool {
+        Storage::calculate_message_crc(&self.topic, &self.author, &self.content, &self.ts)
+            == self.crc
+    }
+
+    fn into_message(self) -> Message {
+        Message {
+            id: String::new(),
+            topic: self.topic,
+            author: self.author,
+            title: self.title,
+            content: self.content,
+            timestamp: self.ts,
+            replies: Vec::new(),
+            pinned: false,
+            message_id: Some(self.id),
+            crc16: Some(self.crc),
+            poll: None,
+        }
+    }
+}
+
+struct Assembly {
+    parts: Vec<Option<String>>,
+    updated: Instant,
+}
+
+/// Counters exposed for status reporting
+#[derive(Debug, Clone, Default)]
+pub struct ReplicationStats {
+    pub frames_sent: u64,
+    pub frames_received: u64,
+    pub messages_imported: u64,
+    pub duplicates: u64,
+    pub crc_failures: u64,
+    pub rejected: u64,
+}
+
+/// Replication state owned by the server
+pub struct ReplicationEngine {
+    config: ReplicationConfig,
+    peers: HashSet<u32>,
+    max_frame: usize,
+    last_round: Option<Instant>,
+    assemblies: HashMap<(u32, String), Assembly>,
+    stats: ReplicationStats,
+}
+
+impl ReplicationEngine {
+    pub fn new(config: &ReplicationConfig, max_frame: usize) -> Self {
+        Self {
+            peers: config.peer_ids().into_iter().collect(),
+            config: config.clone(),
+            max_frame,
+            last_round: None,
+            assemblies: HashMap::new(),
+            stats: ReplicationStats::default(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.enabled && !self.peers.is_empty()
+    }
+
+    pub fn is_peer(&self, node_id: u32) -> bool {
+        self.enabled() && self.peers.contains(&node_id)
+    }
+
+    pub fn peers(&self) -> Vec<u32> {
+        let mut peers: Vec<u32> = self.peers.iter().copied().collect();
+        peers.sort();
+        peers
+    }
+
+    pub fn stats(&self) -> &ReplicationStats {
+        &self.stats
+    }
+
+    /// Record frames handed to the scheduler
+    pub fn note_sent(&mut self, frames: usize) {
+        self.stats.frames_sent += frames as u64;
+    }
+
+    /// Whether an inventory round is due; marks the round as started when it is
+    pub fn round_due(&mut self, now: Instant) -> bool {
+        if !self.enabled() {
+            return false;
+        }
+        let interval = Duration::from_secs(self.config.interval_secs.max(60));
+        match self.last_round {
+            Some(last) if now.duration_since(last) < interval => false,
+            _ => {
+                self.last_round = Some(now);
+                self.assemblies
+                    .retain(|_, a| now.duration_since(a.updated) < ASSEMBLY_TIMEOUT);
+                true
+            }
+        }
+    }
+
+    async fn local_ids(&self, storage: &Storage, topic: &str) -> Result<Vec<String>> {
+        Ok(storage
+            .get_messages(topic, self.config.max_ids_per_topic)
+            .await?
+            .into_iter()
+            .filter_map(|m| m.message_id)
+            .collect())
+    }
+
+    /// Inventory frames advertising every replicated topic
+    pub async fn inventory_frames(&self, storage: &Storage) -> Result<Vec<String>> {
+        let mut frames = Vec::new();
+        let mut current: Vec<TopicDigest> = Vec::new();
+        for topic in storage.list_replicated_topics() {
+            let ids = self.local_ids(storage, &topic).await?;
+            current.push(TopicDigest {
+                topic,
+                count: ids.len(),
+                digest: ids_digest(&ids),
+            });
+            if SyncFrame::Inventory(current.clone()).encode().len() > self.max_frame {
+                let overflow = current.pop().expect("just pushed");
+                if !current.is_empty() {
+                    frames.push(SyncFrame::Inventory(std::mem::take(&mut current)).encode());
+                }
+                current.push(overflow);
+            }
+        }
+        if !current.is_empty() {
+            frames.push(SyncFrame::Inventory(current).encode());
+        }
+        Ok(frames)
+    }
+
+    /// Split an ID list into HAVE/WANT frames that fit the frame budget
+    fn id_frames(&self, topic: &str, ids: &[String], want: bool) -> Vec<String> {
+        let overhead = SYNC_PREFIX.len() + "WANT ".len() + topic.len() + 1;
+        let per_frame = (self.max_frame.saturating_sub(overhead) / (MESSAGE_ID_LEN + 1)).max(1);
+        ids.chunks(per_frame)
+            .map(|chunk| {
+                let (topic, ids) = (topic.to_string(), chunk.to_vec());
+                if want {
+                    SyncFrame::Want { topic, ids }
+                } else {
+                    SyncFrame::Have { topic, ids }
+                }
+                .encode()
+            })
+            .collect()
+    }
+
+    /// Payload bytes per MSG frame when the part counter has `digits` digits:
+    /// "~SY1 MSG <12 hex> n/m "
+    fn part_budget(&self, digits: usize) -> usize {
+        let overhead = SYNC_PREFIX.len() + "MSG ".len() + MESSAGE_ID_LEN + 2 * digits + 3;
+        self.max_frame.saturating_sub(overhead).max(MIN_PART_BYTES)
+    }
+
+    /// Chunk one stored message into MSG frames
+    fn message_frames(&self, message: &Message) -> Result<Vec<String>> {
+        let Some(wire) = WireMessage::from_message(message) else {
+            return Ok(Vec::new());
+        };
+        let json = serde_json::to_string(&wire)?;
+        // The "n/m" counter grows with the part count, so re-chunk until the
+        // digits assumed for it cover the total
+        let mut digits = 1;
+        let chunks = loop {
+            let chunks = chunk_json(&json, self.part_budget(digits));
+            let needed = chunks.len().to_string().len();
+            if needed <= digits {
+                break chunks;
+            }
+            digits = needed;
+        };
+        let total = chunks.len();
+        if total > MAX_SYNC_PARTS {
+            warn!(
+                "Not replicating message {}: {} frames exceeds the {} frame limit",
+                wire.id, total, MAX_SYNC_PARTS
+            );
+            return Ok(Vec::new());
+        }
+        Ok(chunks
+            .into_iter()
+            .enumerate()
+            .map(|(i, data)| {
+                SyncFrame::Part {
+                    id: wire.id.clone(),
+                    index: i + 1,
+                    total,
+                    data,
+                }
+                .encode()
+            })
+            .collect())
+    }
+
+    /// Handle one frame from a peer, returning frames to send back to it
+    pub async fn handle_frame(
+        &mut self,
+        peer: u32,
+        text: &str,
+        storage: &mut Storage,
+    ) -> Result<Vec<String>> {
+        let Some(frame) = SyncFrame::parse(text) else {
+            debug!("Ignoring malformed sync frame from {}", peer);
+            return Ok(Vec::new());
+        };
+        self.stats.frames_received += 1;
+        let replicated: HashSet<String> = storage.list_replicated_topics().into_iter().collect();
+
+        match frame {
+            SyncFrame::Inventory(digests) => {
+                let mut replies = Vec::new();
+                for remote in digests {
+                    if !replicated.contains(&remote.topic) {
+                        continue;
+                    }
+                    let ids = self.local_ids(storage, &remote.topic).await?;
+                    if ids.len() == remote.count && ids_digest(&ids) == remote.digest {
+                        continue;
+                    }
+                    replies.extend(self.id_frames(&remote.topic, &ids, false));
+                }
+                Ok(replies)
+            }
+            SyncFrame::Have { topic, ids } => {
+                if !replicated.contains(&topic) {
+                    return Ok(Vec::new());
+                }
+                let mut missing = Vec::new();
+                for id in ids {
+                    if self.assemblies.contains_key(&(peer, id.clone())) {
+                        continue;
+                    }
+                    if storage.get_replicated_message(&topic, &id).await?.is_none() {
+                        missing.push(id);
+                    }
+                }
+                Ok(self.id_frames(&topic, &missing, true))
+            }
+            SyncFrame::Want { topic, ids } => {
+                if !replicated.contains(&topic) {
+                    return Ok(Vec::new());
+                }
+                let mut replies = Vec::new();
+                for id in ids {
+                    if let Some(message) = storage.get_replicated_message(&topic, &id).await? {
+                        replies.extend(self.message_frames(&message)?);
+                    }
+                }
+                Ok(replies)
+            }
+            SyncFrame::Part {
+                id,
+                index,
+                total,
+                data,
+            } => {
+                self.accept_part(peer, id, index, total, data, storage)
+                    .await?;
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    async fn accept_part(
+        &mut self,
+        peer: u32,
+        id: String,
+        index: usize,
+        total: usize,
+        data: String,
+        storage: &mut Storage,
+    ) -> Result<()> {
+        let key = (peer, id.clone());
+        let assembly = self
+            .assemblies
+            .entry(key.clone())
+            .or_insert_with(|| Assembly {
+                parts: vec![None; total],
+                updated: Instant::now(),
+            });
+        if assembly.parts.len() != total {
+            // Sender restarted the transfer with a different chunking
+            assembly.parts = vec![None; total];
+        }
+        assembly.parts[index - 1] = Some(data);
+        assembly.updated = Instant::now();
+        if assembly.parts.iter().any(Option::is_none) {
+            return Ok(());
+        }
+
+        let assembly = self.assemblies.remove(&key).expect("present");
+        let json: String = assembly.parts.into_iter().flatten().collect();
+        let wire: WireMessage = match serde_json::from_str(&json) {
+            Ok(w) => w,
+            Err(e) => {
+                warn!(
+                    "Discarding undecodable sync message {} from {}: {}",
+                    id, peer, e
+                );
+                self.stats.crc_failures += 1;
+                return Ok(());
+            }
+        };
+        if wire.id != id || !wire.crc_ok() {
+            warn!("CRC mismatch on sync message {} from {}", id, peer);
+            self.stats.crc_failures += 1;
+            return Ok(());
+        }
+        match storage
+            .import_replicated_message(&wire.into_message())
+            .await
+        {
+            Ok(true) => self.stats.messages_imported += 1,
+            Ok(false) => self.stats.duplicates += 1,
+            Err(e) => {
+                warn!("Rejected sync message {} from {}: {}", id, peer, e);
+                self.stats.rejected += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let frames = vec![
+            SyncFrame::Inventory(vec![
+                TopicDigest {
+                    topic: "general".into(),
+                    count: 12,
+                    digest: 0x3fa1,
+                },
+                TopicDigest {
+                    topic: "tech".into(),
+                    count: 0,
+                    digest: 0,
+                },
+            ]),
+            SyncFrame::Have {
+                topic: "general".into(),
+                ids: vec!["6650a1b2c3d4".into(), "6650a1b2c3d5".into()],
+            },
+            SyncFrame::Want {
+                topic: "general".into(),
+                ids: vec!["6650a1b2c3d4".into()],
+            },
+            SyncFrame::Part {
+                id: "6650a1b2c3d4".into(),
+                index: 2,
+                total: 3,
+                data: "{\"a\": \"b c\"}".into(),
+            },
+        ];
+        for frame in frames {
+            assert_eq!(SyncFrame::parse(&frame.encode()), Some(frame));
+        }
+        assert_eq!(SyncFrame::parse("hello"), None);
+        assert_eq!(SyncFrame::parse("~SY1 MSG nothex 1/1 x"), None);
+        assert_eq!(SyncFrame::parse("~SY1 MSG 6650a1b2c3d4 4/3 x"), None);
+        assert_eq!(SyncFrame::parse("~SY1 MSG 6650a1b2c3d4 0/3 x"), None);
+    }
+
+    #[test]
+    fn oversized_part_counts_are_rejected() {
+        let at_limit = format!("~SY1 MSG 6650a1b2c3d4 1/{} x", MAX_SYNC_PARTS);
+        assert!(SyncFrame::parse(&at_limit).is_some());
+        let over = format!("~SY1 MSG 6650a1b2c3d4 1/{} x", MAX_SYNC_PARTS + 1);
+        assert_eq!(SyncFrame::parse(&over), None);
+        assert_eq!(
+            SyncFrame::parse("~SY1 MSG 6650a1b2c3d4 1/4000000000 x"),
+            None
+        );
+    }
+
+    #[test]
+    fn digest_ignores_order() {
+        let a = vec!["6650a1b2c3d4".to_string(), "6650a1b2c3d5".to_string()];
+        let b = vec![a[1].clone(), a[0].clone()];
+        assert_eq!(ids_digest(&a), ids_digest(&b));
+        assert_ne!(ids_digest(&a), ids_digest(&a[..1]));
+    }
+
+    #[test]
+    fn parts_fit_the_frame_with_three_digit_counters() {
+        let max_frame = 48;
+        let engine = ReplicationEngine::new(&ReplicationConfig::default(), max_frame);
+        let message = Message {
+            id: "local".into(),
+            topic: "general".into(),
+            author: "alice".into(),
+            title: None,
+            content: "x".repeat(2500),
+            timestamp: Utc::now(),
+            replies: Vec::new(),
+            pinned: false,
+            message_id: Some("6650a1b2c3d4".into()),
+            crc16: None,
+            poll: None,
+        };
+        let frames = engine.message_frames(&message).unwrap();
+        assert!(frames.len() >= 100);
+        for frame in &frames {
+            assert!(frame.len() <= max_frame, "{} bytes: {}", frame.len(), frame);
+        }
+    }
+}
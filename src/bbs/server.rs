@@ -1,7 +1,6 @@
 #[cfg(feature = "meshtastic-proto")]
 use anyhow::anyhow;
 use anyhow::Result;
-#[cfg(feature = "meshtastic-proto")]
 use chrono::Utc;
 use log::{debug, error, info, trace, warn};
 use std::collections::HashMap;
@@ -11,12 +10,15 @@ use tokio::time::{Duration, Instant};
 use super::games::{self, GameDoorKind};
 #[cfg(feature = "meshtastic-proto")]
 use super::public::PublicCommand;
-use super::public::{PublicCommandParser, PublicState};
+use super::public::{channel_policies, ChannelPolicy, PublicCommandParser, PublicState};
+use super::roles::LEVEL_MODERATOR;
 #[cfg(feature = "meshtastic-proto")]
-use super::roles::{role_name, LEVEL_MODERATOR, LEVEL_USER};
+use super::roles::{role_name, LEVEL_USER};
 use super::session::Session;
 #[cfg(feature = "weather")]
 use super::weather::WeatherService;
+#[cfg(feature = "meshtastic-proto")]
+use crate::config::ChannelBroadcast;
 use crate::config::Config;
 use crate::logutil::escape_log;
 use crate::meshtastic::MeshtasticDevice;
@@ -119,12 +121,23 @@ pub struct BbsServer {
     node_id_rx: Option<mpsc::UnboundedReceiver<u32>>, // reader-provided node ID notifications
     public_state: PublicState,
     public_parser: PublicCommandParser,
+    /// Served channels and their public command policies (primary first)
+    public_channels: Vec<ChannelPolicy>,
+    /// Channel each node last reached us on; DMs to the node go out there
+    node_channels: HashMap<String, u32>,
     #[cfg(feature = "weather")]
     weather_service: WeatherService,
     #[cfg(feature = "weather")]
     weather_last_poll: Instant, // track when we last attempted proactive weather refresh
     housing_cleanup_last_check: Instant, // track when we last ran housing cleanup
     housing_payment_last_check: Instant, // track when we last processed recurring payments
+    dungeon_reap_last_check: Instant,    // track when we last tore down idle dungeon instances
+    auction_settle_last_check: Instant,  // track when we last settled finished auctions
+    world_tick_last_run: Instant,        // track when the TinyMUSH world last ticked
+    world_tick: crate::tmush::world_tick::WorldTick, // state carried between world ticks
+    retention_sweep_last_check: Instant, // track when we last archived expired threads
+    started_at: chrono::DateTime<Utc>,   // wall-clock start reported on /status
+    status_last_publish: Instant,        // track when we last refreshed the metrics status snapshot
     backup_scheduler: Option<crate::storage::backup_scheduler::BackupScheduler>, // automatic backup scheduler
     #[cfg(feature = "meshtastic-proto")]
     pending_direct: Vec<(u32, u32, String)>, // queue of (dest_node_id, channel, message) awaiting our node id
@@ -146,6 +159,10 @@ pub struct BbsServer {
     startup_welcomes_queued: bool, // track if we've already queued startup welcomes
     /// Game registry holding all door game resources
     game_registry: crate::bbs::GameRegistry,
+    /// Store-and-forward topic sync with peer BBS nodes
+    replication: crate::bbs::replication::ReplicationEngine,
+    /// Teleconference rooms and lines waiting for fanout
+    chat: super::chat::ChatRooms,
     #[allow(dead_code)]
     #[doc(hidden)]
     pub(crate) test_messages: Vec<(String, String)>, // collected outbound messages (testing)
@@ -166,9 +183,10 @@ fn verbose_help_with_prefix(pfx: char, help_cmd: &str) -> String {
         "Compact Navigation:\n  M       Topics menu (paged)\n  1-9     Pick item on page\n  L       More items\n  U/B     Up/back (to parent)\n  X       Exit\n  WHERE/W Where am I breadcrumb\n\n",
     "Topics → Subtopics → Threads → Read:\n  In Subtopics: 1-9 pick, U up\n  In Threads:   1-9 read, N new, F <text> filter, U up\n  In Read:      + next, - prev, Y reply\n\n",
     "Posting:\n  From Topics:  R recent messages  P compose  L list\n  While posting: type message text, '.' to finish or cancel\n\n",
+    "More:\n  SUB/UNSUB [topic], SUBS, NOTIFY ON|OFF\n  F files  C/CHAT [room] (/who /join /me /leave)\n  NODES [page], NODE <id>, {p}SEEN <node>\n  Polls: V<n> in Read, {p}POLL  Mods: POLL /mod /kick\n\n",
         "Moderator (level 5+):\n  Threads:  D<n> delete  P<n> pin/unpin  R<n> <title> rename  K lock/unlock area\n  Read:     D delete     P pin/unpin     R <title>            K lock/unlock area\n\n",
-        "Sysop (level 10):\n  G @user=LEVEL|ROLE      Grant level (1/5/10) or USER/MOD/SYSOP\n  SYSLOG LEVEL <msg>      Log message (INFO/WARN/ERROR)\n\n",
-        "Administration (mod/sysop):\n  USERS [pattern]         List users (filter optional)\n  WHO                     Show logged-in users\n  USERINFO <user>         Detailed user info\n  SESSIONS                List all sessions\n  KICK <user>             Force logout user\n  BROADCAST <msg>         Broadcast to all\n  ADMIN / DASHBOARD       System overview\n\n",
+        "Sysop (level 10):\n  G @user=LEVEL|ROLE      Grant level (1/5/10) or USER/MOD/SYSOP\n  SYSLOG LEVEL <msg>      Log message (INFO/WARN/ERROR)\n  FILEAREA/FILEADD/FILEDEL  File admin\n\n",
+        "Administration (mod/sysop):\n  USERS [pattern]         List users (filter optional)\n  WHO                     Show logged-in users\n  USERINFO <user>         Detailed user info\n  SESSIONS                List all sessions\n  KICK <user>             Force logout user\n  BAN/MUTE/IGNORE (UN…)   Moderation, see BANS\n  BROADCAST <msg>         Broadcast to all\n  ADMIN / DASHBOARD       System overview\n\n",
         "Misc:\n  {h}        Compact help\n  {h}+ / {h} V  Verbose help (this)\n  Weather (public)       Send WEATHER on public channel\n  Slot Machine (public)  {p}SLOT or {p}SLOTMACHINE to play\n  Slot Stats (public)    {p}SLOTSTATS\n  Magic 8-Ball (public)  {p}8BALL\n  Fortune (public)       {p}FORTUNE for classic Unix wisdom\n\n",
         "Limits:\n  Max frame ~230 bytes; verbose help auto-splits.\n"),
         p = pfx,
@@ -196,12 +214,95 @@ fn chunk_verbose_help_with_prefix(pfx: char, help_cmd: &str) -> Vec<String> {
     chunks
 }
 
+#[cfg(feature = "meshtastic-proto")]
+/// Metrics label for a DM: the leading verb with any number folded to `<n>`
+/// (`V2` -> `V<n>`, `12` -> `<n>`). Free text typed while composing, chatting,
+/// playing or entering a password is not a command and is skipped.
+fn dm_command_label(state: &super::session::SessionState, upper: &str) -> Option<String> {
+    use super::session::SessionState::*;
+    if matches!(
+        state,
+        PostingMessage
+            | ComposeNewTitle
+            | ComposeNewBody
+            | ComposeReply
+            | UserChangePassCurrent
+            | UserChangePassNew
+            | UserSetPassNew
+            | TinyHack
+            | TinyMush
+            | MailComposeTo
+            | MailComposeBody
+            | Chat
+    ) {
+        return None;
+    }
+    let word = upper.split_whitespace().next()?;
+    let digits = word.trim_start_matches(|c: char| !c.is_ascii_digit()).len();
+    let (verb, number) = word.split_at(word.len() - digits);
+    if !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+        && verb.len() <= 1
+        && verb.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return Some(format!("{}<n>", verb));
+    }
+    let plausible = word.len() <= 12
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '?' | '/' | '^'));
+    Some(if plausible {
+        word.to_string()
+    } else {
+        "OTHER".to_string()
+    })
+}
+
+/// Bytes on disk for each top-level directory under the data dir; loose files are
+/// counted as `other`
+fn storage_area_sizes(data_dir: &std::path::Path) -> std::collections::BTreeMap<String, u64> {
+    fn tree_size(path: &std::path::Path) -> u64 {
+        match std::fs::symlink_metadata(path) {
+            Ok(meta) if meta.is_dir() => std::fs::read_dir(path)
+                .map(|entries| entries.flatten().map(|e| tree_size(&e.path())).sum())
+                .unwrap_or(0),
+            Ok(meta) if meta.is_file() => meta.len(),
+            _ => 0,
+        }
+    }
+    let mut sizes = std::collections::BTreeMap::new();
+    for entry in std::fs::read_dir(data_dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let area = if path.is_dir() {
+            entry.file_name().to_string_lossy().into_owned()
+        } else {
+            "other".to_string()
+        };
+        *sizes.entry(area).or_insert(0) += tree_size(&path);
+    }
+    sizes
+}
+
 impl BbsServer {
     #[inline]
     fn primary_channel(&self) -> u32 {
         self.config.meshtastic.channel as u32
     }
 
+    /// Channel to DM a node on: the one it last reached us on, else the primary
+    fn reply_channel(&self, node_key: &str) -> u32 {
+        self.node_channels
+            .get(node_key)
+            .copied()
+            .unwrap_or_else(|| self.primary_channel())
+    }
+
+    /// Policy for a served channel (`None` means the primary channel)
+    fn channel_policy(&self, channel: Option<u32>) -> Option<&ChannelPolicy> {
+        let index = channel.unwrap_or_else(|| self.primary_channel());
+        self.public_channels.iter().find(|p| p.index == index)
+    }
+
     /// Return the configured primary Meshtastic channel (as u32).
     /// Chunk a UTF-8 string into <= max_bytes segments without splitting codepoints.
     /// Attempts to split on newline boundaries preferentially, then falls back to byte slicing.
@@ -254,7 +355,7 @@ impl BbsServer {
         struct NodeCache {
             nodes: std::collections::HashMap<u32, CachedNodeInfo>,
         }
-        let path = "data/node_cache.json";
+        let path = crate::meshtastic::node_cache_path(&self.config.storage.data_dir);
         let content = std::fs::read_to_string(path).ok()?;
         let cleaned = content.trim_start_matches('\0');
         let cache: NodeCache = serde_json::from_str(cleaned).ok()?;
@@ -332,7 +433,7 @@ impl BbsServer {
         struct NodeCache {
             nodes: std::collections::HashMap<u32, CachedNodeInfo>,
         }
-        let path = "data/node_cache.json";
+        let path = crate::meshtastic::node_cache_path(&self.config.storage.data_dir);
         let content = std::fs::read_to_string(path).ok()?;
         let cleaned = content.trim_start_matches('\0');
         let cache: NodeCache = serde_json::from_str(cleaned).ok()?;
@@ -413,7 +514,7 @@ impl BbsServer {
         }
 
         // Build optional Argon2 params from config
-        let storage = {
+        let mut storage = {
             use argon2::Params;
             if let Some(sec) = &config.security {
                 if let Some(a) = &sec.argon2 {
@@ -430,6 +531,7 @@ impl BbsServer {
                 Storage::new(&config.storage.data_dir).await?
             }
         };
+        storage.set_mail_quota(config.storage.mail_quota);
 
         let mut server = Self {
             config: config.clone(),
@@ -459,6 +561,8 @@ impl BbsServer {
                 config.bbs.public_command_prefix.clone(),
                 Some(config.bbs.help_command.clone()),
             ),
+            public_channels: channel_policies(&config),
+            node_channels: HashMap::new(),
             #[cfg(feature = "weather")]
             weather_service: WeatherService::new(config.weather.clone()),
             #[cfg(feature = "weather")]
@@ -466,6 +570,13 @@ impl BbsServer {
             // Initialize housing cleanup timers to run immediately on first tick
             housing_cleanup_last_check: Instant::now() - Duration::from_secs(86401), // More than 1 day ago
             housing_payment_last_check: Instant::now() - Duration::from_secs(86401), // More than 1 day ago
+            dungeon_reap_last_check: Instant::now(),
+            auction_settle_last_check: Instant::now(),
+            world_tick_last_run: Instant::now(),
+            world_tick: crate::tmush::world_tick::WorldTick::new(),
+            retention_sweep_last_check: Instant::now(),
+            started_at: Utc::now(),
+            status_last_publish: Instant::now(),
             // Initialize backup scheduler
             backup_scheduler: {
                 use crate::storage::backup_scheduler::{BackupScheduler, BackupSchedulerConfig};
@@ -507,6 +618,11 @@ impl BbsServer {
             #[cfg(feature = "meshtastic-proto")]
             startup_welcomes_queued: false,
             game_registry: crate::bbs::GameRegistry::new(),
+            replication: crate::bbs::replication::ReplicationEngine::new(
+                &config.replication,
+                config.storage.max_message_size,
+            ),
+            chat: super::chat::ChatRooms::default(),
             test_messages: Vec::new(),
             #[cfg(feature = "meshtastic-proto")]
             last_scheduler_stats_check: Instant::now(),
@@ -521,6 +637,10 @@ impl BbsServer {
         if !server.config.message_topics.is_empty() {
             Self::merge_toml_topics_to_runtime(&mut server.storage, &server.config).await?;
         }
+        // Restore the persisted public-channel ignore list
+        for node in server.storage.ignored_nodes().to_vec() {
+            server.public_state.set_ignored(&node.to_string(), true);
+        }
 
         // Initialize door game resources in the registry
         if server.config.games.tinymush_enabled {
@@ -613,6 +733,7 @@ impl BbsServer {
             port,
             self.config.meshtastic.baud_rate,
             tuning_clone,
+            &self.config.storage.data_dir,
         )
         .await?;
 
@@ -670,7 +791,7 @@ impl BbsServer {
         }
 
         // Load node cache
-        let cache_path = format!("{}/node_cache.json", self.config.storage.data_dir);
+        let cache_path = crate::meshtastic::node_cache_path(&self.config.storage.data_dir);
         let cache = match crate::meshtastic::NodeCache::load_from_file(&cache_path) {
             Ok(c) => c,
             Err(e) => {
@@ -1025,6 +1146,19 @@ impl BbsServer {
         );
         self.seed_sysop().await?;
 
+        if self.config.metrics.enabled {
+            match crate::metrics::http::bind(&self.config.metrics.bind).await {
+                Ok(listener) => {
+                    if let Ok(addr) = listener.local_addr() {
+                        info!("Metrics listener on http://{} (/metrics, /status)", addr);
+                    }
+                    self.publish_status().await;
+                    tokio::spawn(crate::metrics::http::serve(listener));
+                }
+                Err(e) => warn!("Metrics listener disabled: {}", e),
+            }
+        }
+
         let (tx, mut rx) = mpsc::unbounded_channel();
         self.message_tx = Some(tx);
 
@@ -1105,9 +1239,59 @@ impl BbsServer {
                             self.housing_payment_last_check = Instant::now();
                         }
 
+                        // Idle dungeon instances (checked once a minute)
+                        if self.dungeon_reap_last_check.elapsed() >= Duration::from_secs(60) {
+                            if let Err(e) = self.reap_idle_dungeons() {
+                                warn!("Dungeon instance cleanup error: {}", e);
+                            }
+                            self.dungeon_reap_last_check = Instant::now();
+                        }
+
+                        // Finished auctions (checked once a minute)
+                        if self.auction_settle_last_check.elapsed() >= Duration::from_secs(60) {
+                            if let Err(e) = self.settle_auctions() {
+                                warn!("Auction settlement error: {}", e);
+                            }
+                            self.auction_settle_last_check = Instant::now();
+                        }
+
+                        // TinyMUSH world tick (0 seconds disables it)
+                        let world_tick_seconds = self
+                            .config
+                            .games
+                            .tinymush_world_tick_seconds
+                            .unwrap_or(crate::tmush::world_tick::DEFAULT_WORLD_TICK_SECONDS);
+                        if world_tick_seconds > 0
+                            && self.world_tick_last_run.elapsed()
+                                >= Duration::from_secs(world_tick_seconds)
+                        {
+                            if let Err(e) = self.run_world_tick() {
+                                warn!("World tick error: {}", e);
+                            }
+                            self.world_tick_last_run = Instant::now();
+                        }
+
+                        // Topic retention (archive expired threads once an hour)
+                        if self.retention_sweep_last_check.elapsed()
+                            >= Duration::from_secs(crate::storage::retention::RETENTION_SWEEP_SECONDS)
+                        {
+                            if let Err(e) = self.sweep_topic_retention().await {
+                                warn!("Topic retention sweep error: {}", e);
+                            }
+                            self.retention_sweep_last_check = Instant::now();
+                        }
+
                         if self.node_cache_last_cleanup.elapsed() >= Duration::from_secs(3600) {
                             self.node_cache_last_cleanup = Instant::now();
                         }
+
+                        // Flush any TinyMUSH room activity not tied to an incoming command
+                        if let Err(e) = self.deliver_tmush_room_events().await {
+                            warn!("TinyMUSH room fanout failed: {}", e);
+                        }
+                        if let Err(e) = self.deliver_chat_events().await {
+                            warn!("Chat fanout failed: {}", e);
+                        }
                         if let Err(e) = self.check_and_send_ident().await {
                             debug!("Ident beacon error: {}", e);
                         }
@@ -1117,6 +1301,11 @@ impl BbsServer {
                             debug!("Backup scheduler error: {}", e);
                         }
 
+                        // Advertise replicated topics to peer BBS nodes
+                        if let Err(e) = self.run_replication_round().await {
+                            warn!("Replication round failed: {}", e);
+                        }
+
                         // Health monitoring: check scheduler status every 30 seconds
                         if self.last_scheduler_stats_check.elapsed() >= Duration::from_secs(30) {
                             if let Err(e) = self.check_scheduler_health().await {
@@ -1124,6 +1313,11 @@ impl BbsServer {
                             }
                             self.last_scheduler_stats_check = Instant::now();
                         }
+
+                        // Refresh the snapshot served on /metrics and /status
+                        if self.status_refresh_due() {
+                            self.publish_status().await;
+                        }
                     },
 
                     // Receive our node id from reader (so ident shows actual ID)
@@ -1239,6 +1433,12 @@ impl BbsServer {
                                 debug!("Legacy summary: {}", summary);
                             }
                         }
+                        if let Err(e) = self.run_replication_round().await {
+                            warn!("Replication round failed: {}", e);
+                        }
+                        if self.status_refresh_due() {
+                            self.publish_status().await;
+                        }
                     }
 
                     msg = rx.recv() => {
@@ -1375,6 +1575,10 @@ impl BbsServer {
                 .send_message(&k, "You have been logged out due to inactivity.")
                 .await;
             if let Some(s) = self.sessions.get_mut(&k) {
+                if let Some(room) = s.current_chat_room.as_deref() {
+                    self.chat
+                        .notice(room, &k, format!("* {} left (idle)", username));
+                }
                 let _ = s.logout().await;
             }
             info!(
@@ -1432,6 +1636,208 @@ impl BbsServer {
         }
     }
 
+    /// Whether `upper` is one of the moderation list commands handled by
+    /// [`Self::handle_moderation_command`]
+    fn is_moderation_command(upper: &str) -> bool {
+        let verb = upper.split_whitespace().next().unwrap_or("");
+        matches!(
+            verb,
+            "BAN" | "UNBAN" | "BANS" | "MUTE" | "UNMUTE" | "IGNORE" | "UNIGNORE"
+        )
+    }
+
+    /// BAN / UNBAN / BANS / MUTE / UNMUTE / IGNORE / UNIGNORE (moderator and up).
+    /// Storage records every change in the admin audit log.
+    async fn handle_moderation_command(
+        &mut self,
+        actor: &str,
+        actor_level: u8,
+        actor_node: &str,
+        raw: &str,
+    ) -> Result<String> {
+        use crate::storage::moderation::{format_span, parse_duration, parse_node_id, BanTarget};
+
+        if actor_level < LEVEL_MODERATOR {
+            return Ok("Permission denied.\n".into());
+        }
+        let parts: Vec<&str> = raw.split_whitespace().collect();
+        let verb = parts[0].to_uppercase();
+        let rest = &parts[1..];
+
+        if verb == "BANS" {
+            let now = Utc::now();
+            let bans = self.storage.list_bans();
+            let mutes = self.storage.list_mutes();
+            if bans.is_empty() && mutes.is_empty() && self.storage.ignored_nodes().is_empty() {
+                return Ok("No bans, mutes or ignores.\n".into());
+            }
+            let mut out = String::new();
+            for b in bans {
+                out.push_str(&format!(
+                    "BAN {} {}{}\n",
+                    b.target,
+                    format_span(b.expires_at.map(|t| t - now)),
+                    b.reason
+                        .as_deref()
+                        .map(|r| format!(" {}", r))
+                        .unwrap_or_default()
+                ));
+            }
+            for m in mutes {
+                out.push_str(&format!(
+                    "MUTE {} {} {}\n",
+                    m.username,
+                    m.topic.as_deref().unwrap_or("*"),
+                    format_span(m.expires_at.map(|t| t - now))
+                ));
+            }
+            for n in self.storage.ignored_nodes() {
+                out.push_str(&format!("IGNORE !{:08x}\n", n));
+            }
+            return Ok(out);
+        }
+
+        if verb == "IGNORE" || verb == "UNIGNORE" {
+            let Some(node_id) = rest.first().and_then(|t| parse_node_id(t)) else {
+                return Ok(format!("Usage: {} <!nodeid>\n", verb));
+            };
+            let ignore = verb == "IGNORE";
+            let changed = self
+                .storage
+                .set_node_ignored(node_id, ignore, actor)
+                .await?;
+            self.public_state.set_ignored(&node_id.to_string(), ignore);
+            return Ok(match (ignore, changed) {
+                (true, true) => format!("Ignoring !{:08x} on public.\n", node_id),
+                (true, false) => format!("!{:08x} already ignored.\n", node_id),
+                (false, true) => format!("No longer ignoring !{:08x}.\n", node_id),
+                (false, false) => format!("!{:08x} was not ignored.\n", node_id),
+            });
+        }
+
+        let Some(raw_target) = rest.first() else {
+            return Ok(match verb.as_str() {
+                "BAN" => "Usage: BAN <user|!node> [30m|12h|7d] [reason]\n",
+                "UNBAN" => "Usage: UNBAN <user|!node>\n",
+                "MUTE" => "Usage: MUTE <user> [topic|*] [30m|12h|7d] [reason]\n",
+                _ => "Usage: UNMUTE <user> [topic]\n",
+            }
+            .into());
+        };
+
+        // Resolve user targets to the stored account and refuse to act on peers or above
+        let mut target = BanTarget::parse(raw_target);
+        if let BanTarget::User(name) = &target {
+            let Some(user) = self.storage.get_user(name).await? else {
+                return Ok("User not found.\n".into());
+            };
+            if user.username.eq_ignore_ascii_case(actor) {
+                return Ok("Cannot target yourself.\n".into());
+            }
+            if user.username == self.config.bbs.sysop
+                || (user.user_level >= actor_level && actor != self.config.bbs.sysop)
+            {
+                return Ok("Cannot target a moderator or sysop.\n".into());
+            }
+            target = BanTarget::User(user.username);
+        } else if matches!(target, BanTarget::Node(id) if id.to_string() == actor_node) {
+            return Ok("Cannot target yourself.\n".into());
+        }
+
+        match verb.as_str() {
+            "BAN" => {
+                let mut rest = &rest[1..];
+                let duration = rest.first().and_then(|t| parse_duration(t));
+                if duration.is_some() {
+                    rest = &rest[1..];
+                }
+                let reason = (!rest.is_empty()).then(|| rest.join(" "));
+                self.storage
+                    .ban(target.clone(), actor, reason.as_deref(), duration)
+                    .await?;
+                // Drop whoever is connected under the banned name or node
+                let notice = match &target {
+                    BanTarget::User(name) => self.storage.user_ban(name),
+                    BanTarget::Node(id) => self.storage.node_ban(*id),
+                }
+                .map(|b| b.notice())
+                .unwrap_or_default();
+                let node = match &target {
+                    BanTarget::User(name) => self
+                        .sessions
+                        .iter()
+                        .find(|(_, s)| s.username.as_deref() == Some(name.as_str()))
+                        .map(|(k, _)| k.clone()),
+                    BanTarget::Node(id) => Some(id.to_string()),
+                };
+                if let Some(node) = node {
+                    if let Some(mut session) = self.sessions.remove(&node) {
+                        let _ = self.send_message(&node, &notice).await;
+                        let _ = session.logout().await;
+                    }
+                }
+                Ok(format!("Banned {} ({}).\n", target, format_span(duration)))
+            }
+            "UNBAN" => Ok(if self.storage.unban(&target, actor).await? {
+                format!("Unbanned {}.\n", target)
+            } else {
+                format!("{} is not banned.\n", target)
+            }),
+            "MUTE" | "UNMUTE" => {
+                let BanTarget::User(username) = target else {
+                    return Ok("Mutes apply to users, not nodes.\n".into());
+                };
+                let mut rest = &rest[1..];
+                let topic = match rest.first() {
+                    Some(&"*") => {
+                        rest = &rest[1..];
+                        None
+                    }
+                    Some(t) if self.storage.topic_exists(&t.to_lowercase()) => {
+                        rest = &rest[1..];
+                        Some(t.to_lowercase())
+                    }
+                    _ => None,
+                };
+                let scope = topic.as_deref().unwrap_or("all topics").to_string();
+                if verb == "UNMUTE" {
+                    return Ok(
+                        if self
+                            .storage
+                            .unmute_user(&username, topic.as_deref(), actor)
+                            .await?
+                        {
+                            format!("Unmuted {} in {}.\n", username, scope)
+                        } else {
+                            format!("{} is not muted in {}.\n", username, scope)
+                        },
+                    );
+                }
+                let duration = rest.first().and_then(|t| parse_duration(t));
+                if duration.is_some() {
+                    rest = &rest[1..];
+                }
+                let reason = (!rest.is_empty()).then(|| rest.join(" "));
+                self.storage
+                    .mute_user(
+                        &username,
+                        topic.as_deref(),
+                        actor,
+                        reason.as_deref(),
+                        duration,
+                    )
+                    .await?;
+                Ok(format!(
+                    "Muted {} in {} ({}).\n",
+                    username,
+                    scope,
+                    format_span(duration)
+                ))
+            }
+            _ => Ok("Unknown command.\n".into()),
+        }
+    }
+
     /// Send broadcast message to all logged-in users
     pub async fn broadcast_message(&mut self, message: &str, sender: &str) -> Result<usize> {
         let mut sent_count = 0;
@@ -1463,16 +1869,21 @@ impl BbsServer {
     /// When unread == 0 -> "There are no new messages.\n"
     /// When unread == 1 -> "1 new message since your last login.\n"
     /// When unread > 1 -> "<n> new messages since your last login.\n"
-    fn format_unread_line(unread: u32) -> String {
-        match unread {
+    /// Unread private mail, when any, adds "You have <n> unread mail (E).\n"
+    fn format_unread_line(unread: u32, unread_mail: u32) -> String {
+        let mut line = match unread {
             0 => "There are no new messages.\n".to_string(),
             1 => "1 new message since your last login.\n".to_string(),
             n => format!("{} new messages since your last login.\n", n),
+        };
+        if unread_mail > 0 {
+            line.push_str(&format!("You have {} unread mail (E).\n", unread_mail));
         }
+        line
     }
 
     fn format_main_menu(games_config: &crate::config::GamesConfig) -> String {
-        let mut line = String::from("Main Menu:\n[M]essages ");
+        let mut line = String::from("Main Menu:\n[M]essages [E]mail [F]ile [C]hat ");
         if games::has_enabled_doors(games_config) {
             line.push_str("[G]ames ");
         }
@@ -1822,6 +2233,27 @@ impl BbsServer {
 
         Ok(())
     }
+    /// Test helper to place a session's TinyMUSH player in a specific room.
+    #[allow(dead_code)]
+    pub async fn test_tmush_set_player_room(&mut self, node_key: &str, room_id: &str) -> Result<()> {
+        let username = self
+            .sessions
+            .get(node_key)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", node_key))?
+            .display_name();
+        let store = self
+            .game_registry
+            .get_tinymush_store()
+            .ok_or_else(|| anyhow::anyhow!("TinyMUSH store not available in game registry"))?;
+        let mut player = store
+            .get_player(&username)
+            .map_err(|e| anyhow::anyhow!("Failed to load player: {}", e))?;
+        player.current_room = room_id.to_string();
+        store
+            .put_player(player)
+            .map_err(|e| anyhow::anyhow!("Failed to save player: {}", e))?;
+        Ok(())
+    }
     #[allow(dead_code)]
     pub async fn test_create_topic(
         &mut self,
@@ -1876,6 +2308,16 @@ impl BbsServer {
         self.storage.store_message(topic, author, content).await
     }
     #[allow(dead_code)]
+    pub async fn test_append_reply(
+        &mut self,
+        topic: &str,
+        id: &str,
+        author: &str,
+        content: &str,
+    ) -> Result<()> {
+        self.storage.append_reply(topic, id, author, content).await
+    }
+    #[allow(dead_code)]
     pub async fn test_get_messages(
         &self,
         topic: &str,
@@ -1884,6 +2326,38 @@ impl BbsServer {
         self.storage.get_messages(topic, limit).await
     }
     #[allow(dead_code)]
+    pub async fn test_replication_round(&mut self) -> Result<()> {
+        self.run_replication_round().await
+    }
+    #[allow(dead_code)]
+    pub async fn test_replication_frame(&mut self, peer: u32, content: &str) -> Result<()> {
+        if !self.replication.is_peer(peer) {
+            return Err(anyhow::anyhow!("Node {} is not a replication peer", peer));
+        }
+        self.handle_replication_frame(peer, content).await
+    }
+    #[allow(dead_code)]
+    pub fn test_replication_stats(&self) -> crate::bbs::replication::ReplicationStats {
+        self.replication.stats().clone()
+    }
+    #[cfg(feature = "meshtastic-proto")]
+    pub async fn test_node_heard(&mut self, node_id: u32) -> Result<()> {
+        self.handle_node_detection(crate::meshtastic::NodeDetectionEvent {
+            node_id,
+            long_name: format!("Node {:08x}", node_id),
+            short_name: format!("{:04x}", node_id & 0xFFFF),
+            is_from_startup_queue: false,
+        })
+        .await
+    }
+    #[allow(dead_code)]
+    pub async fn test_sweep_retention(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<(String, usize)>> {
+        self.storage.sweep_retention(now).await
+    }
+    #[allow(dead_code)]
     pub async fn test_list_topics(&self) -> Result<Vec<String>> {
         self.storage.list_message_topics().await
     }
@@ -1952,6 +2426,16 @@ impl BbsServer {
     ) -> Result<()> {
         use crate::bbs::welcome;
 
+        // A subscriber's node being heard is the cue to send their reply summary
+        if !event.is_from_startup_queue {
+            if let Err(e) = self.notify_node_subscriber(event.node_id).await {
+                warn!(
+                    "Reply notification for node 0x{:08X} failed: {}",
+                    event.node_id, e
+                );
+            }
+        }
+
         // Check if welcome system is enabled
         if !self.config.welcome.enabled {
             return Ok(());
@@ -2145,7 +2629,37 @@ impl BbsServer {
         );
         // Source node id string form
         let node_key = ev.source.to_string();
+        if ev.is_direct
+            && ev.content.starts_with(crate::bbs::replication::SYNC_PREFIX)
+            && self.replication.is_peer(ev.source)
+        {
+            // Peer BBS sync traffic never reaches the session layer
+            return self.handle_replication_frame(ev.source, &ev.content).await;
+        }
+        if let Some(ban) = self.storage.node_ban(ev.source) {
+            // Banned nodes never get a session; tell them why at most once per cooldown
+            trace!("Dropping text from banned node {}", node_key);
+            if ev.is_direct {
+                let notice = ban.notice();
+                self.sessions.remove(&node_key);
+                if self.public_state.should_reply(&node_key) {
+                    let _ = self.send_message(&node_key, &notice).await;
+                }
+            }
+            return Ok(());
+        }
         if ev.is_direct {
+            // Answer on the channel the node used, as long as we serve it
+            if let Some(channel) = ev
+                .channel
+                .filter(|c| self.channel_policy(Some(*c)).is_some())
+            {
+                self.node_channels.insert(node_key.clone(), channel);
+            }
+            let was_logged_in = self
+                .sessions
+                .get(&node_key)
+                .is_some_and(|s| s.is_logged_in());
             // Direct (private) path: ensure session exists, finalize pending login if any
             if !self.sessions.contains_key(&node_key) {
                 trace!("Creating new session for direct node {}", node_key);
@@ -2155,6 +2669,9 @@ impl BbsServer {
                     let current = self.logged_in_session_count();
                     if (current as u32) >= self.config.bbs.max_users {
                         let _ = self.send_message(&node_key, "All available sessions are in use, please wait and try again later.").await;
+                    } else if let Some(ban) = self.storage.user_ban(&username) {
+                        let notice = ban.notice();
+                        let _ = self.send_message(&node_key, &notice).await;
                     } else {
                         // Security check: verify if user has a password set
                         if let Ok(Some(user)) = self.storage.get_user(&username).await {
@@ -2178,7 +2695,9 @@ impl BbsServer {
                                     .await
                                     .unwrap_or(0);
                                 let _ = self.storage.record_user_login(&username).await; // update last_login
-                                let summary = Self::format_unread_line(unread);
+                                let unread_mail =
+                                    self.storage.count_unread_mail(&username).await.unwrap_or(0);
+                                let summary = Self::format_unread_line(unread, unread_mail);
                                 let hint = if unread == 0 {
                                     Self::format_hint_line(&self.config.games, false)
                                 } else {
@@ -2206,7 +2725,7 @@ impl BbsServer {
                             self.storage
                                 .create_or_update_user(&username, &node_key)
                                 .await?;
-                            let summary = Self::format_unread_line(0);
+                            let summary = Self::format_unread_line(0, 0);
                             let hint = Self::format_hint_line(&self.config.games, false);
                             let menu = Self::format_main_menu(&self.config.games);
                             let _ = self
@@ -2230,6 +2749,13 @@ impl BbsServer {
             self.prune_idle_sessions().await; // always prune first
             let raw_content = ev.content.trim().to_string();
             let upper = raw_content.to_uppercase();
+            if let Some(label) = self
+                .sessions
+                .get(&node_key)
+                .and_then(|s| dm_command_label(&s.state, &upper))
+            {
+                crate::metrics::inc_command("dm", &label);
+            }
             // Count current logged in sessions (excluding the session for this node if it is not yet logged in)
             let logged_in_count = self.sessions.values().filter(|s| s.is_logged_in()).count();
             enum PostAction {
@@ -2280,8 +2806,8 @@ impl BbsServer {
                                     session.login(user.to_string(), 1).await?;
                                     session.unread_since = Some(Utc::now());
                                 }
-                                let summary = Self::format_unread_line(0);
-                                let hint = Self::format_hint_line(&self.config.games, true);
+                                let summary = Self::format_unread_line(0, 0);
+                                let hint = Self::format_hint_line(&self.config.games, false);
                                 let menu = Self::format_main_menu(&self.config.games);
                                 let full_welcome = format!(
                                     "Registered as {u}.\n{summary}{hint}{menu}",
@@ -2327,6 +2853,17 @@ impl BbsServer {
                 return Ok(());
             }
 
+            // Chat needs the other sessions (fanout, /who), so it bypasses the command chain
+            let chat_input = self.sessions.get(&node_key).is_some_and(|s| {
+                s.is_logged_in()
+                    && (s.state == super::session::SessionState::Chat
+                        || (s.state == super::session::SessionState::MainMenu
+                            && (upper == "C" || upper == "CHAT" || upper.starts_with("CHAT "))))
+            });
+            if chat_input {
+                return self.handle_chat_input(&node_key, &raw_content).await;
+            }
+
             if let Some(session) = self.sessions.get_mut(&node_key) {
                 session.update_activity();
                 #[cfg(feature = "meshtastic-proto")]
@@ -2383,6 +2920,10 @@ impl BbsServer {
                                     deferred_reply =
                                         Some("No such user. Use REGISTER <u> <p>.\n".into())
                                 }
+                                Some(u) if self.storage.user_ban(&u.username).is_some() => {
+                                    deferred_reply =
+                                        self.storage.user_ban(&u.username).map(|b| b.notice());
+                                }
                                 Some(u) => {
                                     let has_password = u.password_hash.is_some();
                                     let node_bound = u.node_id.as_deref() == Some(&node_key);
@@ -2420,7 +2961,13 @@ impl BbsServer {
                                                 // set_user_password already bumped last_login, so computing unread would yield zero. This is acceptable; show none.
                                                 let _ = self.storage.record_user_login(user).await; // ensure fresh timestamp after full login
                                                                                                     // No unread count expected here (legacy first login)
-                                                let summary = Self::format_unread_line(0); // first login after setting password shows no unread
+                                                let unread_mail = self
+                                                    .storage
+                                                    .count_unread_mail(user)
+                                                    .await
+                                                    .unwrap_or(0);
+                                                let summary =
+                                                    Self::format_unread_line(0, unread_mail); // first login after setting password shows no unread posts
 
                                                 // Check if this is the first login after registration and show follow-up welcome
                                                 let hint = Self::format_hint_line(
@@ -2485,7 +3032,13 @@ impl BbsServer {
                                                     .record_user_login(user)
                                                     .await
                                                     .unwrap_or(updated);
-                                                let summary = Self::format_unread_line(unread);
+                                                let unread_mail = self
+                                                    .storage
+                                                    .count_unread_mail(user)
+                                                    .await
+                                                    .unwrap_or(0);
+                                                let summary =
+                                                    Self::format_unread_line(unread, unread_mail);
 
                                                 // Check if this is the first login after registration and show follow-up welcome
                                                 let hint = if unread == 0 {
@@ -2730,13 +3283,20 @@ impl BbsServer {
                     } else {
                         let parts: Vec<&str> = raw_content.split_whitespace().collect();
                         if parts.len() < 3 {
-                            deferred_reply = Some("Usage: MODIFYTOPIC <id> name=<name> | desc=<desc> | read=<level> | post=<level>\n".into());
+                            deferred_reply = Some("Usage: MODIFYTOPIC <id> name=<name> | desc=<desc> | read=<level> | post=<level> | sync=on|off | maxage=<days>|off | maxthreads=<n>|off | pinned=keep|archive\n".into());
                         } else {
                             let topic_id = parts[1].to_lowercase();
                             let mut name: Option<&str> = None;
                             let mut description: Option<String> = None;
                             let mut read_level: Option<u8> = None;
                             let mut post_level: Option<u8> = None;
+                            let mut sync: Option<bool> = None;
+                            let mut retention = self
+                                .storage
+                                .get_topic_config(&topic_id)
+                                .and_then(|cfg| cfg.retention.clone())
+                                .unwrap_or_default();
+                            let mut retention_changed = false;
 
                             // Parse key=value pairs
                             for part in &parts[2..] {
@@ -2748,6 +3308,28 @@ impl BbsServer {
                                         }
                                         "read" => read_level = value.parse().ok(),
                                         "post" => post_level = value.parse().ok(),
+                                        "sync" => {
+                                            sync = match value.to_lowercase().as_str() {
+                                                "on" | "yes" | "true" => Some(true),
+                                                "off" | "no" | "false" => Some(false),
+                                                _ => None,
+                                            }
+                                        }
+                                        "maxage" => {
+                                            retention.max_age_days =
+                                                value.parse().ok().filter(|d| *d > 0);
+                                            retention_changed = true;
+                                        }
+                                        "maxthreads" => {
+                                            retention.max_threads =
+                                                value.parse().ok().filter(|n| *n > 0);
+                                            retention_changed = true;
+                                        }
+                                        "pinned" => {
+                                            retention.keep_pinned =
+                                                !value.eq_ignore_ascii_case("archive");
+                                            retention_changed = true;
+                                        }
                                         _ => {}
                                     }
                                 }
@@ -2765,10 +3347,36 @@ impl BbsServer {
                                 .await
                             {
                                 Ok(()) => {
-                                    deferred_reply = Some(format!(
-                                        "Topic '{}' modified successfully.\n",
-                                        topic_id
-                                    ))
+                                    let mut notes = Vec::new();
+                                    let mut result = Ok(());
+                                    if let Some(enabled) = sync {
+                                        result = self
+                                            .storage
+                                            .set_topic_replication(&topic_id, enabled)
+                                            .await;
+                                        notes.push(format!(
+                                            "sync {}",
+                                            if enabled { "on" } else { "off" }
+                                        ));
+                                    }
+                                    if retention_changed && result.is_ok() {
+                                        notes.push(format!("retention {}", retention));
+                                        result = self
+                                            .storage
+                                            .set_topic_retention(&topic_id, Some(retention))
+                                            .await;
+                                    }
+                                    deferred_reply = Some(match result {
+                                        Ok(()) if notes.is_empty() => {
+                                            format!("Topic '{}' modified successfully.\n", topic_id)
+                                        }
+                                        Ok(()) => format!(
+                                            "Topic '{}' modified successfully ({}).\n",
+                                            topic_id,
+                                            notes.join(", ")
+                                        ),
+                                        Err(e) => format!("Failed to modify topic: {}\n", e),
+                                    })
                                 }
                                 Err(e) => {
                                     deferred_reply =
@@ -2911,79 +3519,315 @@ impl BbsServer {
                             Err(e) => deferred_reply = Some(format!("Failed: {}\n", e)),
                         }
                     }
-                } else if upper.starts_with("USERS") {
+                } else if upper.starts_with("ARCHIVE ") {
                     if session.user_level < LEVEL_MODERATOR {
                         deferred_reply = Some("Permission denied.\n".into());
                     } else {
                         let parts: Vec<&str> = raw_content.split_whitespace().collect();
-                        let pattern = if parts.len() >= 2 {
-                            Some(parts[1].to_lowercase())
+                        if parts.len() < 2 {
+                            deferred_reply = Some("Usage: ARCHIVE <topic> [page]\n".into());
                         } else {
-                            None
-                        };
-
-                        match self.storage.list_all_users().await {
-                            Ok(mut users) => {
-                                // Filter users by pattern if provided
-                                if let Some(ref p) = pattern {
-                                    users.retain(|u| u.username.to_lowercase().contains(p));
-                                }
-
-                                let logged_in_usernames: std::collections::HashSet<&str> = self
-                                    .get_logged_in_users()
-                                    .iter()
-                                    .filter_map(|s| s.username.as_deref())
-                                    .collect();
-
-                                let mut response = if let Some(ref p) = pattern {
-                                    format!("Users matching '{}' ({} found):\n", p, users.len())
-                                } else {
-                                    format!(
-                                        "Registered Users ({}/{}):\n",
-                                        users.len(),
-                                        self.config.bbs.max_users
-                                    )
-                                };
-
-                                for user in users {
-                                    let status =
-                                        if logged_in_usernames.contains(user.username.as_str()) {
-                                            "Online"
-                                        } else {
-                                            "Offline"
-                                        };
-                                    let role = super::roles::role_name(user.user_level);
-                                    response.push_str(&format!(
-                                        "  {} ({}, Level {}) - {}\n",
-                                        user.username, role, user.user_level, status
-                                    ));
+                            let topic_id = parts[1].to_lowercase();
+                            let page = if parts.len() >= 3 {
+                                parts[2].parse::<usize>().unwrap_or(1).max(1)
+                            } else {
+                                1
+                            };
+                            match self.storage.list_archived(&topic_id).await {
+                                Ok(threads) if threads.is_empty() => {
+                                    deferred_reply =
+                                        Some(format!("No archived threads in {}.\n", topic_id));
                                 }
-
-                                if pattern.is_none() {
-                                    response.push_str(&format!(
-                                        "\nActive Sessions: {}\n",
-                                        self.logged_in_session_count()
-                                    ));
+                                Ok(threads) => {
+                                    let mut out =
+                                        format!("Archive {} ({}):\n", topic_id, threads.len());
+                                    for m in threads.iter().skip((page - 1) * 10).take(10) {
+                                        let title = m.title.clone().unwrap_or_else(|| {
+                                            m.content.chars().take(20).collect()
+                                        });
+                                        out.push_str(&format!(
+                                            "{} {} {} {}\n",
+                                            &m.id[..m.id.len().min(8)],
+                                            crate::storage::retention::last_activity(m)
+                                                .format("%m/%d"),
+                                            m.author,
+                                            title
+                                        ));
+                                    }
+                                    out.push_str("UNARCHIVE <topic> <id> restores\n");
+                                    deferred_reply = Some(out);
                                 }
-                                deferred_reply = Some(response);
-                            }
-                            Err(e) => {
-                                deferred_reply = Some(format!("Failed to list users: {}\n", e))
+                                Err(e) => deferred_reply = Some(format!("Failed: {}\n", e)),
                             }
                         }
                     }
-                } else if upper == "WHO" {
+                } else if upper.starts_with("UNARCHIVE ") {
                     if session.user_level < LEVEL_MODERATOR {
                         deferred_reply = Some("Permission denied.\n".into());
                     } else {
-                        let logged_in = self.get_logged_in_users();
-                        if logged_in.is_empty() {
-                            deferred_reply = Some("No users currently logged in.\n".into());
+                        let parts: Vec<&str> = raw_content.split_whitespace().collect();
+                        if parts.len() < 3 {
+                            deferred_reply = Some("Usage: UNARCHIVE <topic> <id>\n".into());
                         } else {
-                            let mut response = format!("Logged In Users ({}):\n", logged_in.len());
-                            for session in logged_in {
-                                let username = session.username.as_deref().unwrap_or("Guest");
-                                let role = super::roles::role_name(session.user_level);
+                            let topic_id = parts[1].to_lowercase();
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match self
+                                    .storage
+                                    .restore_archived(&topic_id, parts[2], &actor)
+                                    .await
+                                {
+                                    Ok(Some(m)) => format!("Restored {} to {}.\n", m.id, topic_id),
+                                    Ok(None) => {
+                                        format!(
+                                            "No archived thread {} in {}.\n",
+                                            parts[2], topic_id
+                                        )
+                                    }
+                                    Err(e) => format!("Failed: {}\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("POLL ") {
+                    if session.user_level < LEVEL_MODERATOR {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let mut words = raw_content.trim().splitn(3, ' ');
+                        words.next();
+                        let topic_id = words.next().unwrap_or("").to_lowercase();
+                        let mut rest = words.next().unwrap_or("").trim();
+                        let mut closes_at = None;
+                        if let Some((first, tail)) = rest.split_once(' ') {
+                            if let Some(d) = crate::storage::polls::parse_duration(first) {
+                                closes_at = Some(chrono::Utc::now() + d);
+                                rest = tail.trim();
+                            }
+                        }
+                        let mut fields = rest.split('|');
+                        let question = fields.next().unwrap_or("");
+                        let options: Vec<&str> = fields.collect();
+                        if topic_id.is_empty() || options.is_empty() {
+                            deferred_reply = Some(
+                                "Usage: POLL <topic> [48h|3d] <question> | <opt> | <opt>...\n"
+                                    .into(),
+                            );
+                        } else {
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match crate::storage::polls::Poll::new(
+                                    question, &options, closes_at,
+                                ) {
+                                    Ok(poll) => match self
+                                        .storage
+                                        .create_poll(&topic_id, &actor, poll)
+                                        .await
+                                    {
+                                        Ok(id) => format!(
+                                            "Poll {} created in {}.\n",
+                                            &id[..id.len().min(8)],
+                                            topic_id
+                                        ),
+                                        Err(e) => format!("Failed: {}\n", e),
+                                    },
+                                    Err(e) => format!("{}.\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("POLLCLOSE ") {
+                    if session.user_level < LEVEL_MODERATOR {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let parts: Vec<&str> = raw_content.split_whitespace().collect();
+                        if parts.len() < 3 {
+                            deferred_reply = Some("Usage: POLLCLOSE <topic> <id>\n".into());
+                        } else {
+                            let topic_id = parts[1].to_lowercase();
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match self.storage.close_poll(&topic_id, parts[2], &actor).await {
+                                    Ok(Some(m)) => match &m.poll {
+                                        Some(poll) => format!(
+                                            "Closed. {}\n",
+                                            poll.render(
+                                                chrono::Utc::now(),
+                                                "\n",
+                                                crate::storage::polls::POLL_RESULTS_MAX_BYTES - 8,
+                                            )
+                                        ),
+                                        None => "Closed.\n".into(),
+                                    },
+                                    Ok(None) => {
+                                        format!("No poll {} in {}.\n", parts[2], topic_id)
+                                    }
+                                    Err(e) => format!("Failed: {}\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("FILEAREA ") {
+                    if session.username.as_deref() != Some(&self.config.bbs.sysop) {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let parts: Vec<&str> = raw_content.split_whitespace().collect();
+                        if parts.len() < 2 {
+                            deferred_reply =
+                                Some("Usage: FILEAREA <area> [read_level] [description]\n".into());
+                        } else {
+                            let level = parts.get(2).and_then(|p| p.parse::<u8>().ok());
+                            let desc_start = if level.is_some() { 3 } else { 2 };
+                            let description = parts.get(desc_start..).map(|d| d.join(" "));
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match self
+                                    .storage
+                                    .set_file_area(
+                                        parts[1],
+                                        description.as_deref().filter(|d| !d.is_empty()),
+                                        level,
+                                        &actor,
+                                    )
+                                    .await
+                                {
+                                    Ok(true) => format!("File area '{}' created.\n", parts[1]),
+                                    Ok(false) => format!("File area '{}' updated.\n", parts[1]),
+                                    Err(e) => format!("Failed: {}\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("FILEADD ") || upper.starts_with("FILEAPPEND ") {
+                    if session.username.as_deref() != Some(&self.config.bbs.sysop) {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let append = upper.starts_with("FILEAPPEND ");
+                        let mut parts = raw_content.trim().splitn(4, ' ');
+                        parts.next();
+                        let area = parts.next().unwrap_or("");
+                        let name = parts.next().unwrap_or("");
+                        let text = parts.next().unwrap_or("").trim();
+                        if text.is_empty() {
+                            deferred_reply = Some(format!(
+                                "Usage: {} <area> <name> <text>\n",
+                                if append { "FILEAPPEND" } else { "FILEADD" }
+                            ));
+                        } else {
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match self
+                                    .storage
+                                    .add_library_file(area, name, text, append, &actor)
+                                    .await
+                                {
+                                    Ok(file) => format!(
+                                        "Saved {}/{} ({} bytes).\n",
+                                        area.to_lowercase(),
+                                        file.name,
+                                        file.size
+                                    ),
+                                    Err(e) => format!("Failed: {}\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("FILEDEL ") {
+                    if session.username.as_deref() != Some(&self.config.bbs.sysop) {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let parts: Vec<&str> = raw_content.split_whitespace().collect();
+                        if parts.len() < 3 {
+                            deferred_reply = Some("Usage: FILEDEL <area> <name>\n".into());
+                        } else {
+                            let actor = session.username.clone().unwrap_or("?".into());
+                            deferred_reply = Some(
+                                match self
+                                    .storage
+                                    .delete_library_file(parts[1], parts[2], &actor)
+                                    .await
+                                {
+                                    Ok(true) => format!("Deleted {}/{}.\n", parts[1], parts[2]),
+                                    Ok(false) => {
+                                        format!("No file {} in {}.\n", parts[2], parts[1])
+                                    }
+                                    Err(e) => format!("Failed: {}\n", e),
+                                },
+                            );
+                        }
+                    }
+                } else if upper.starts_with("USERS") {
+                    if session.user_level < LEVEL_MODERATOR {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let parts: Vec<&str> = raw_content.split_whitespace().collect();
+                        let pattern = if parts.len() >= 2 {
+                            Some(parts[1].to_lowercase())
+                        } else {
+                            None
+                        };
+
+                        match self.storage.list_all_users().await {
+                            Ok(mut users) => {
+                                // Filter users by pattern if provided
+                                if let Some(ref p) = pattern {
+                                    users.retain(|u| u.username.to_lowercase().contains(p));
+                                }
+
+                                let logged_in_usernames: std::collections::HashSet<&str> = self
+                                    .get_logged_in_users()
+                                    .iter()
+                                    .filter_map(|s| s.username.as_deref())
+                                    .collect();
+
+                                let mut response = if let Some(ref p) = pattern {
+                                    format!("Users matching '{}' ({} found):\n", p, users.len())
+                                } else {
+                                    format!(
+                                        "Registered Users ({}/{}):\n",
+                                        users.len(),
+                                        self.config.bbs.max_users
+                                    )
+                                };
+
+                                for user in users {
+                                    let status =
+                                        if logged_in_usernames.contains(user.username.as_str()) {
+                                            "Online"
+                                        } else {
+                                            "Offline"
+                                        };
+                                    let role = super::roles::role_name(user.user_level);
+                                    response.push_str(&format!(
+                                        "  {} ({}, Level {}) - {}\n",
+                                        user.username, role, user.user_level, status
+                                    ));
+                                }
+
+                                if pattern.is_none() {
+                                    response.push_str(&format!(
+                                        "\nActive Sessions: {}\n",
+                                        self.logged_in_session_count()
+                                    ));
+                                }
+                                deferred_reply = Some(response);
+                            }
+                            Err(e) => {
+                                deferred_reply = Some(format!("Failed to list users: {}\n", e))
+                            }
+                        }
+                    }
+                } else if upper == "WHO" {
+                    if session.user_level < LEVEL_MODERATOR {
+                        deferred_reply = Some("Permission denied.\n".into());
+                    } else {
+                        let logged_in = self.get_logged_in_users();
+                        if logged_in.is_empty() {
+                            deferred_reply = Some("No users currently logged in.\n".into());
+                        } else {
+                            let mut response = format!("Logged In Users ({}):\n", logged_in.len());
+                            for session in logged_in {
+                                let username = session.username.as_deref().unwrap_or("Guest");
+                                let role = super::roles::role_name(session.user_level);
                                 let duration = session.session_duration().num_minutes();
                                 let state = match session.state {
                                     super::session::SessionState::MainMenu => "Main Menu",
@@ -2992,6 +3836,7 @@ impl BbsServer {
                                     super::session::SessionState::PostingMessage => "Posting",
                                     super::session::SessionState::UserMenu => "User Menu",
                                     super::session::SessionState::TinyHack => "TinyHack",
+                                    super::session::SessionState::Chat => "Chat",
                                     _ => "Other",
                                 };
                                 response.push_str(&format!(
@@ -3078,6 +3923,7 @@ impl BbsServer {
                                 super::session::SessionState::ComposeNewBody => "Compose Body",
                                 super::session::SessionState::ComposeReply => "Compose Reply",
                                 super::session::SessionState::ConfirmDelete => "Confirm Delete",
+                                super::session::SessionState::SearchResults => "Search",
                                 super::session::SessionState::UserMenu => "User Menu",
                                 super::session::SessionState::UserChangePassCurrent => {
                                     "Pass Verify"
@@ -3086,6 +3932,15 @@ impl BbsServer {
                                 super::session::SessionState::UserSetPassNew => "Pass Set",
                                 super::session::SessionState::TinyHack => "TinyHack",
                                 super::session::SessionState::TinyMush => "TinyMUSH",
+                                super::session::SessionState::MailInbox => "Mail Inbox",
+                                super::session::SessionState::MailSent => "Mail Sent",
+                                super::session::SessionState::MailRead => "Mail Read",
+                                super::session::SessionState::MailComposeTo
+                                | super::session::SessionState::MailComposeBody => "Mail Compose",
+                                super::session::SessionState::FileAreas
+                                | super::session::SessionState::FileList
+                                | super::session::SessionState::FileRead => "Files",
+                                super::session::SessionState::Chat => "Chat",
                                 super::session::SessionState::Disconnected => "Disconnected",
                             };
                             response.push_str(&format!(
@@ -3132,6 +3987,13 @@ impl BbsServer {
                             }
                         }
                     }
+                } else if Self::is_moderation_command(&upper) {
+                    let actor = session.username.clone().unwrap_or_default();
+                    let level = session.user_level;
+                    deferred_reply = Some(
+                        self.handle_moderation_command(&actor, level, &node_key, &raw_content)
+                            .await?,
+                    );
                 } else if upper.starts_with("BROADCAST ") {
                     if session.user_level < LEVEL_MODERATOR {
                         deferred_reply = Some("Permission denied.\n".into());
@@ -3165,7 +4027,7 @@ impl BbsServer {
                             "  Session Timeout: {} min\n",
                             self.config.bbs.session_timeout
                         ));
-                        response.push_str("\nCommands: USERS, WHO, USERINFO <user>, SESSIONS, KICK <user>, BROADCAST <msg>\n");
+                        response.push_str("\nCommands: USERS, WHO, USERINFO <user>, SESSIONS, KICK <user>, BAN, MUTE, BANS, BROADCAST <msg>\n");
                         deferred_reply = Some(response);
                     }
                 } else if upper == "LOGOUT" {
@@ -3276,10 +4138,34 @@ impl BbsServer {
             if let Some(msg) = deferred_reply {
                 self.send_session_message(&node_key, &msg, true).await?;
             }
+            // Room activity caused by this command goes out after the actor's own reply
+            if let Err(e) = self.deliver_tmush_room_events().await {
+                warn!("TinyMUSH room fanout failed: {}", e);
+            }
+            // Chat members hear about anyone who just timed out
+            if let Err(e) = self.deliver_chat_events().await {
+                warn!("Chat fanout failed: {}", e);
+            }
+            // A fresh login gets the reply summary for the user's subscriptions
+            if !was_logged_in {
+                if let Some(username) = self
+                    .sessions
+                    .get(&node_key)
+                    .and_then(|s| s.username.clone())
+                {
+                    if let Err(e) = self.send_reply_notification(&node_key, &username).await {
+                        warn!("Reply notification for {} failed: {}", username, e);
+                    }
+                }
+            }
             // end direct path handling (removed extra closing brace)
         } else {
             // Public channel event: parse lightweight commands
             self.public_state.prune_expired();
+            if self.public_state.is_ignored(&node_key) {
+                trace!("Ignoring public text from node {}", node_key);
+                return Ok(());
+            }
 
             // Check if this is a node with default name that should be welcomed
             // This catches nodes that chat publicly without us having seen their NODEINFO
@@ -3316,7 +4202,28 @@ impl BbsServer {
                 }
             }
 
-            let cmd = self.public_parser.parse(&ev.content);
+            let Some(policy) = self.channel_policy(ev.channel).cloned() else {
+                trace!(
+                    "Ignoring public text on unserved channel {:?} from node {}",
+                    ev.channel,
+                    node_key
+                );
+                return Ok(());
+            };
+            // Follow-up DMs go out on the channel this node used
+            self.node_channels.insert(node_key.clone(), policy.index);
+            let mut cmd = policy.parser.parse(&ev.content);
+            if !policy.allows(&cmd) {
+                trace!(
+                    "Public command {:?} disabled on channel {}",
+                    cmd,
+                    policy.index
+                );
+                cmd = PublicCommand::Unknown;
+            }
+            if let Some(keyword) = cmd.keyword() {
+                crate::metrics::inc_command("public", keyword);
+            }
             trace!(
                 "Public command parse result for node {} => {:?}",
                 node_key,
@@ -3357,27 +4264,57 @@ impl BbsServer {
                         };
 
                         // Create broadcast message showing available public commands with chunking
-                        let primary_prefix = self.public_parser.primary_prefix_char();
-                        let help_cmd = self.public_parser.help_command();
-                        let mut public_commands = vec![
+                        let primary_prefix = policy.parser.primary_prefix_char();
+                        let help_cmd = policy.parser.help_command();
+                        let mut public_commands = vec![(
+                            "HELP",
                             format!("{p}{h} - Show this help", p = primary_prefix, h = help_cmd),
+                        )];
+                        public_commands.push((
+                            "LOGIN",
                             format!("{p}LOGIN <user> - Register for BBS", p = primary_prefix),
-                        ];
+                        ));
 
                         // Add optional weather command if enabled
                         #[cfg(feature = "weather")]
-                        public_commands.push(format!(
-                            "{p}WEATHER - Current conditions",
-                            p = primary_prefix
+                        public_commands.push((
+                            "WEATHER",
+                            format!("{p}WEATHER - Current conditions", p = primary_prefix),
                         ));
 
                         // Add games and utilities
                         public_commands.extend_from_slice(&[
-                            format!("{p}SLOT - Play slot machine", p = primary_prefix),
-                            format!("{p}SLOTSTATS - Show your stats", p = primary_prefix),
-                            format!("{p}8BALL - Magic 8-Ball oracle", p = primary_prefix),
-                            format!("{p}FORTUNE - Random wisdom", p = primary_prefix),
+                            (
+                                "SLOT",
+                                format!("{p}SLOT - Play slot machine", p = primary_prefix),
+                            ),
+                            (
+                                "SLOTSTATS",
+                                format!("{p}SLOTSTATS - Show your stats", p = primary_prefix),
+                            ),
+                            (
+                                "8BALL",
+                                format!("{p}8BALL - Magic 8-Ball oracle", p = primary_prefix),
+                            ),
+                            (
+                                "FORTUNE",
+                                format!("{p}FORTUNE - Random wisdom", p = primary_prefix),
+                            ),
+                            (
+                                "SEEN",
+                                format!("{p}SEEN <node> - Last heard", p = primary_prefix),
+                            ),
+                            (
+                                "POLL",
+                                format!("{p}POLL - Latest open poll", p = primary_prefix),
+                            ),
                         ]);
+                        // Only list what this channel answers
+                        let public_commands: Vec<String> = public_commands
+                            .into_iter()
+                            .filter(|(keyword, _)| policy.enables(keyword))
+                            .map(|(_, line)| line)
+                            .collect();
 
                         // Send DM first, then chunked public notices. This reduces the chance of a transient rate limit
                         // affecting the DM, since the DM is more time-sensitive for onboarding.
@@ -3394,6 +4331,10 @@ impl BbsServer {
                             Ok(_) => debug!("Sent HELP DM to {}", ev.source),
                             Err(e) => warn!("Failed to send HELP DM to {}: {}", ev.source, e),
                         }
+                        if policy.broadcast == ChannelBroadcast::Dm {
+                            // This channel keeps public replies off the air
+                            return Ok(());
+                        }
 
                         // Create chunked public notices, ensuring each stays under 230 bytes
                         let mut chunks = Vec::new();
@@ -3466,7 +4407,7 @@ impl BbsServer {
                                 );
                                 let outgoing = crate::meshtastic::OutgoingMessage {
                                     to_node: None,
-                                    channel: self.primary_channel(),
+                                    channel: policy.index,
                                     content: chunk.clone(),
                                     priority: crate::meshtastic::MessagePriority::Normal,
                                     kind: crate::meshtastic::OutgoingKind::Normal,
@@ -3484,7 +4425,7 @@ impl BbsServer {
                             // Fallback legacy path - ensure tx is properly cloned for each spawn
                             if let Some(base_tx) = self.outgoing_tx.clone() {
                                 // Avoid capturing &self inside spawned tasks; compute channel once.
-                                let channel = self.primary_channel();
+                                let channel = policy.index;
                                 for (i, chunk) in chunks.iter().enumerate() {
                                     let chunk_content = chunk.clone();
                                     let tx_clone = base_tx.clone();
@@ -3542,7 +4483,7 @@ impl BbsServer {
                         let mut broadcasted = false;
                         #[cfg(feature = "meshtastic-proto")]
                         {
-                            match self.send_broadcast(&weather).await {
+                            match self.send_public_reply(&policy, &node_key, &weather).await {
                                 Ok(_) => {
                                     trace!("Broadcasted weather to public channel: '{}'", weather);
                                     broadcasted = true;
@@ -3565,7 +4506,7 @@ impl BbsServer {
                     // Broadcast-only: do not DM slot results.
                     if self.public_state.allow_slot(&node_key) {
                         let base = self.storage.base_dir().to_string();
-                        let p = self.public_parser.primary_prefix_char();
+                        let p = policy.parser.primary_prefix_char();
                         let (outcome, coins) =
                             crate::bbs::slotmachine::perform_spin(&base, &node_key);
                         let msg = if outcome.r1 == "⛔" {
@@ -3601,7 +4542,7 @@ impl BbsServer {
                         // Broadcast result for room visibility (best-effort)
                         #[cfg(feature = "meshtastic-proto")]
                         {
-                            if let Err(e) = self.send_broadcast(&msg).await {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
                                 warn!("Slot result broadcast failed (best-effort): {e:?}");
                             }
                         }
@@ -3611,11 +4552,11 @@ impl BbsServer {
                     // Lightweight per-node cooldown similar to <prefix>SLOT; broadcast-only.
                     if self.public_state.allow_8ball(&node_key) {
                         let answer = crate::bbs::eightball::ask();
-                        let p = self.public_parser.primary_prefix_char();
+                        let p = policy.parser.primary_prefix_char();
                         let msg = format!("{p}8BALL ⟶ {}", answer);
                         #[cfg(feature = "meshtastic-proto")]
                         {
-                            if let Err(e) = self.send_broadcast(&msg).await {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
                                 warn!("8BALL broadcast failed (best-effort): {e:?}");
                             }
                         }
@@ -3625,11 +4566,11 @@ impl BbsServer {
                     // Lightweight per-node cooldown; broadcast-only like other games.
                     if self.public_state.allow_fortune(&node_key) {
                         let fortune = crate::bbs::fortune::get_fortune();
-                        let p = self.public_parser.primary_prefix_char();
+                        let p = policy.parser.primary_prefix_char();
                         let msg = format!("{p}FORTUNE ⟶ {}", fortune);
                         #[cfg(feature = "meshtastic-proto")]
                         {
-                            if let Err(e) = self.send_broadcast(&msg).await {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
                                 warn!("FORTUNE broadcast failed (best-effort): {e:?}");
                             }
                         }
@@ -3650,7 +4591,7 @@ impl BbsServer {
                         } else {
                             "-".to_string()
                         };
-                        let p = self.public_parser.primary_prefix_char();
+                        let p = policy.parser.primary_prefix_char();
                         let msg = if let Some(s) = summary {
                             let rate = if s.total_spins > 0 {
                                 (s.total_wins as f32) * 100.0 / (s.total_spins as f32)
@@ -3667,7 +4608,7 @@ impl BbsServer {
                         let mut broadcasted = false;
                         #[cfg(feature = "meshtastic-proto")]
                         {
-                            if let Err(e) = self.send_broadcast(&msg).await {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
                                 warn!("Slot stats broadcast failed: {e:?} (will fallback DM)");
                             } else {
                                 broadcasted = true;
@@ -3678,6 +4619,71 @@ impl BbsServer {
                         }
                     }
                 }
+                PublicCommand::Seen(query) => {
+                    if self.public_state.should_reply(&node_key) {
+                        let cache = crate::bbs::nodes::load_node_cache(self.storage.base_dir());
+                        let p = policy.parser.primary_prefix_char();
+                        let msg = format!(
+                            "{p}SEEN ⟶ {}",
+                            crate::bbs::nodes::render_seen(
+                                cache.as_ref(),
+                                &query,
+                                chrono::Utc::now()
+                            )
+                        );
+                        let mut broadcasted = false;
+                        #[cfg(feature = "meshtastic-proto")]
+                        {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
+                                warn!("SEEN broadcast failed: {e:?} (will fallback DM)");
+                            } else {
+                                broadcasted = true;
+                            }
+                        }
+                        if !broadcasted {
+                            let _ = self.send_message(&node_key, &msg).await;
+                        }
+                    }
+                }
+                PublicCommand::Poll => {
+                    if self.public_state.should_reply(&node_key) {
+                        let now = chrono::Utc::now();
+                        // Only polls in topics anyone may read are announced publicly
+                        let storage = &self.storage;
+                        let latest = storage
+                            .latest_open_poll(
+                                |t| {
+                                    storage
+                                        .get_topic_config(t)
+                                        .is_none_or(|c| c.read_level == 0)
+                                },
+                                now,
+                            )
+                            .await?;
+                        let p = policy.parser.primary_prefix_char();
+                        let msg = match latest.as_ref().and_then(|m| Some((m, m.poll.as_ref()?))) {
+                            Some((m, poll)) => {
+                                let head = format!("{p}POLL ⟶ [{}] ", m.topic);
+                                let budget = crate::storage::polls::POLL_RESULTS_MAX_BYTES
+                                    .saturating_sub(head.len());
+                                format!("{}{}", head, poll.render(now, " | ", budget))
+                            }
+                            None => format!("{p}POLL ⟶ No open polls."),
+                        };
+                        let mut broadcasted = false;
+                        #[cfg(feature = "meshtastic-proto")]
+                        {
+                            if let Err(e) = self.send_public_reply(&policy, &node_key, &msg).await {
+                                warn!("POLL broadcast failed: {e:?} (will fallback DM)");
+                            } else {
+                                broadcasted = true;
+                            }
+                        }
+                        if !broadcasted {
+                            let _ = self.send_message(&node_key, &msg).await;
+                        }
+                    }
+                }
                 PublicCommand::Invalid(reason) => {
                     if self.public_state.should_reply(&node_key) {
                         let reply = format!("Invalid: {}", reason);
@@ -3725,7 +4731,7 @@ impl BbsServer {
                 if let Some(id) = node_id {
                     let outgoing = OutgoingMessage {
                         to_node: Some(id),
-                        channel: self.primary_channel(),
+                        channel: self.reply_channel(to_node),
                         content: message.to_string(),
                         priority: MessagePriority::High,
                         kind: crate::meshtastic::OutgoingKind::Normal,
@@ -3756,7 +4762,7 @@ impl BbsServer {
                 if let Some(id) = node_id {
                     let outgoing = OutgoingMessage {
                         to_node: Some(id),
-                        channel: self.primary_channel(),
+                        channel: self.reply_channel(to_node),
                         content: message.to_string(),
                         priority: MessagePriority::High,
                         kind: crate::meshtastic::OutgoingKind::Normal,
@@ -3810,10 +4816,32 @@ impl BbsServer {
     /// Send a broadcast message to the public channel
     #[cfg(feature = "meshtastic-proto")]
     pub async fn send_broadcast(&mut self, message: &str) -> Result<()> {
+        self.send_broadcast_on(self.primary_channel(), message)
+            .await
+    }
+
+    /// Answer a public command the way its channel asks: broadcast on that
+    /// channel, or DM the requester when the channel is set to `broadcast = "dm"`
+    #[cfg(feature = "meshtastic-proto")]
+    async fn send_public_reply(
+        &mut self,
+        policy: &ChannelPolicy,
+        node_key: &str,
+        message: &str,
+    ) -> Result<()> {
+        match policy.broadcast {
+            ChannelBroadcast::Public => self.send_broadcast_on(policy.index, message).await,
+            ChannelBroadcast::Dm => self.send_message(node_key, message).await,
+        }
+    }
+
+    /// Send a broadcast message on a specific channel index
+    #[cfg(feature = "meshtastic-proto")]
+    pub async fn send_broadcast_on(&mut self, channel: u32, message: &str) -> Result<()> {
         if let Some(scheduler) = &self.scheduler {
             let outgoing = OutgoingMessage {
                 to_node: None,
-                channel: self.primary_channel(),
+                channel,
                 content: message.to_string(),
                 priority: MessagePriority::Normal,
                 kind: crate::meshtastic::OutgoingKind::Normal,
@@ -3830,7 +4858,7 @@ impl BbsServer {
         } else {
             let outgoing = OutgoingMessage {
                 to_node: None,
-                channel: self.primary_channel(),
+                channel,
                 content: message.to_string(),
                 priority: MessagePriority::Normal,
                 kind: crate::meshtastic::OutgoingKind::Normal,
@@ -3850,13 +4878,474 @@ impl BbsServer {
             } else {
                 // Mock/test mode: record broadcast for assertions
                 debug!("Mock broadcast (no device): {}", escape_log(message));
-                self.test_messages
-                    .push(("BCAST".to_string(), message.to_string()));
+                let label = if channel == self.primary_channel() {
+                    "BCAST".to_string()
+                } else {
+                    format!("BCAST@{}", channel)
+                };
+                self.test_messages.push((label, message.to_string()));
                 Ok(())
             }
         }
     }
 
+    /// Sessions currently in a chat room
+    fn chat_members(&self) -> Vec<super::chat::ChatMember> {
+        self.sessions
+            .iter()
+            .filter(|(_, s)| s.state == super::session::SessionState::Chat)
+            .filter_map(|(node_key, s)| {
+                Some(super::chat::ChatMember {
+                    node_key: node_key.clone(),
+                    username: s.display_name(),
+                    room: s.current_chat_room.clone()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Teleconference input: `C` / `CHAT [room]` from the main menu enters a room;
+    /// in chat mode plain lines are spoken and `/` commands manage the room.
+    async fn handle_chat_input(&mut self, node_key: &str, raw: &str) -> Result<()> {
+        let members = self.chat_members();
+        let Some(session) = self.sessions.get_mut(node_key) else {
+            return Ok(());
+        };
+        session.update_activity();
+        let name = session.display_name();
+        let is_moderator = session.user_level >= LEVEL_MODERATOR;
+        let current = match session.state {
+            super::session::SessionState::Chat => session.current_chat_room.clone(),
+            _ => None,
+        };
+
+        let text = raw.trim();
+        let reply = match (current, text.strip_prefix('/')) {
+            (None, _) => {
+                let target = text
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or(super::chat::DEFAULT_CHAT_ROOM);
+                Some(self.join_chat_room(node_key, &name, target, &members))
+            }
+            (Some(_), None) if text.is_empty() => None,
+            (Some(room), None) => {
+                let line = format!("<{}> {}", name, text);
+                self.chat_say(node_key, &name, &room, is_moderator, line)
+            }
+            (Some(room), Some(rest)) => {
+                let (command, arg) = rest
+                    .split_once(char::is_whitespace)
+                    .map(|(c, a)| (c, a.trim()))
+                    .unwrap_or((rest, ""));
+                let command = command.to_ascii_uppercase();
+                self.chat_command(
+                    node_key,
+                    &name,
+                    &room,
+                    is_moderator,
+                    &command,
+                    arg,
+                    &members,
+                )
+                .await?
+            }
+        };
+        if let Some(msg) = reply {
+            self.send_session_message(node_key, &msg, true).await?;
+        }
+        self.deliver_chat_events().await
+    }
+
+    /// A `/` command typed in chat mode; returns the reply for the typist
+    #[allow(clippy::too_many_arguments)]
+    async fn chat_command(
+        &mut self,
+        node_key: &str,
+        name: &str,
+        room: &str,
+        is_moderator: bool,
+        command: &str,
+        arg: &str,
+        members: &[super::chat::ChatMember],
+    ) -> Result<Option<String>> {
+        let reply = match command {
+            "WHO" | "W" => {
+                let mut here: Vec<&str> = members
+                    .iter()
+                    .filter(|m| m.room == room)
+                    .map(|m| m.username.as_str())
+                    .collect();
+                here.sort_unstable();
+                format!("#{}: {}\n", room, here.join(", "))
+            }
+            "ROOMS" | "LIST" => {
+                let mut out = String::from("Rooms:");
+                for (name, state) in self.chat.rooms() {
+                    let count = members.iter().filter(|m| &m.room == name).count();
+                    let flag = if state.moderated { "m" } else { "" };
+                    out.push_str(&format!(" #{}({}{})", name, count, flag));
+                }
+                out.push('\n');
+                out
+            }
+            "JOIN" | "J" if !arg.is_empty() => {
+                if super::chat::normalize_room_name(arg).as_deref() == Some(room) {
+                    return Ok(Some(format!("Already in #{}.\n", room)));
+                }
+                let reply = self.join_chat_room(node_key, name, arg, members);
+                let moved = self
+                    .sessions
+                    .get(node_key)
+                    .and_then(|s| s.current_chat_room.as_deref())
+                    != Some(room);
+                if moved {
+                    self.chat.notice(room, node_key, format!("* {} left", name));
+                }
+                reply
+            }
+            "ME" if !arg.is_empty() => {
+                let line = format!("* {} {}", name, arg);
+                return Ok(self.chat_say(node_key, name, room, is_moderator, line));
+            }
+            "LEAVE" | "QUIT" | "Q" | "EXIT" => {
+                self.chat.notice(room, node_key, format!("* {} left", name));
+                if let Some(session) = self.sessions.get_mut(node_key) {
+                    session.state = super::session::SessionState::MainMenu;
+                    session.current_chat_room = None;
+                }
+                let menu = Self::format_main_menu(&self.config.games);
+                format!("Left #{}.\n{}", room, menu)
+            }
+            "MOD" | "KICK" if !is_moderator => "Permission denied.\n".into(),
+            "MOD" => {
+                let Some(state) = self.chat.get_mut(room) else {
+                    return Ok(None);
+                };
+                state.moderated = !state.moderated;
+                let (line, detail) = if state.moderated {
+                    let line = format!(
+                        "* #{} is moderated by {}: only moderators speak",
+                        room, name
+                    );
+                    (line, "on")
+                } else {
+                    (format!("* #{} is open again", room), "off")
+                };
+                self.chat.notice(room, node_key, line.clone());
+                if let Err(e) = self
+                    .storage
+                    .log_admin_action("CHATMOD", Some(room), name, Some(detail))
+                    .await
+                {
+                    warn!("Failed to log admin action: {}", e);
+                }
+                format!("{}\n", line)
+            }
+            "KICK" if !arg.is_empty() => {
+                let Some(target) = members.iter().find(|m| {
+                    m.room == room && m.node_key != node_key && m.username.eq_ignore_ascii_case(arg)
+                }) else {
+                    return Ok(Some(format!("{} is not in #{}.\n", arg, room)));
+                };
+                if let Some(s) = self.sessions.get_mut(&target.node_key) {
+                    s.state = super::session::SessionState::MainMenu;
+                    s.current_chat_room = None;
+                }
+                let menu = Self::format_main_menu(&self.config.games);
+                let notice = format!("You were removed from #{} by {}.\n{}", room, name, menu);
+                self.send_session_message(&target.node_key, &notice, true)
+                    .await?;
+                let line = format!("* {} was removed by {}", target.username, name);
+                self.chat.notice(room, node_key, line);
+                if let Err(e) = self
+                    .storage
+                    .log_admin_action("CHATKICK", Some(&target.username), name, Some(room))
+                    .await
+                {
+                    warn!("Failed to log admin action: {}", e);
+                }
+                format!("Removed {} from #{}.\n", target.username, room)
+            }
+            _ => "Chat: type to talk. /who /rooms /join <room> /me <action> /leave\n".into(),
+        };
+        Ok(Some(reply))
+    }
+
+    /// Enter (or switch to) a room: replays the scrollback and announces the arrival
+    fn join_chat_room(
+        &mut self,
+        node_key: &str,
+        name: &str,
+        requested: &str,
+        members: &[super::chat::ChatMember],
+    ) -> String {
+        let Some(room) = super::chat::normalize_room_name(requested) else {
+            return "Room names are 1-16 letters, digits, - or _.\n".into();
+        };
+        let occupied: std::collections::HashSet<String> = members
+            .iter()
+            .filter(|m| m.node_key != node_key)
+            .map(|m| m.room.clone())
+            .collect();
+        let Some(state) = self.chat.open(&room, &occupied) else {
+            return "Too many chat rooms are open. /rooms lists them\n".into();
+        };
+        let mut out = format!("[Chat] #{}", room);
+        if state.moderated {
+            out.push_str(" (moderated)");
+        }
+        let mut others: Vec<&str> = members
+            .iter()
+            .filter(|m| m.room == room && m.node_key != node_key)
+            .map(|m| m.username.as_str())
+            .collect();
+        others.sort_unstable();
+        if others.is_empty() {
+            out.push_str(": nobody else here\n");
+        } else {
+            out.push_str(&format!(": {}\n", others.join(", ")));
+        }
+        for line in state.scrollback() {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str("/help for commands\n");
+        if let Some(session) = self.sessions.get_mut(node_key) {
+            session.state = super::session::SessionState::Chat;
+            session.current_chat_room = Some(room.clone());
+        }
+        self.chat
+            .notice(&room, node_key, format!("* {} joined", name));
+        out
+    }
+
+    /// Queue a spoken line (`<name> text` or a `/me` action); returns a reply only
+    /// when the speaker is not allowed to talk
+    fn chat_say(
+        &mut self,
+        node_key: &str,
+        name: &str,
+        room: &str,
+        is_moderator: bool,
+        line: String,
+    ) -> Option<String> {
+        // Only a global mute matches the empty topic
+        if self.storage.mute_for(name, "").is_some() {
+            return Some("You are muted.\n".into());
+        }
+        if !is_moderator && self.chat.get(room).is_some_and(|r| r.moderated) {
+            return Some(format!("#{} is moderated; only moderators speak.\n", room));
+        }
+        self.chat.say(room, node_key, line);
+        None
+    }
+
+    /// Deliver queued chat lines to the other members of each room, coalesced per
+    /// recipient like TinyMUSH room events
+    async fn deliver_chat_events(&mut self) -> Result<()> {
+        let events = self.chat.drain();
+        if events.is_empty() {
+            return Ok(());
+        }
+        let members = self.chat_members();
+        let plan =
+            super::chat::plan_fanout(&events, &members, self.config.storage.max_message_size);
+        for (node_key, frames) in plan {
+            for frame in frames {
+                if let Err(e) = self
+                    .send_room_event_frame(
+                        &node_key,
+                        &frame,
+                        crate::bbs::dispatch::Priority::Normal,
+                    )
+                    .await
+                {
+                    warn!("Chat delivery to {} failed: {}", node_key, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fan out pending TinyMUSH room events (speech, emotes, arrivals, trigger output)
+    /// to every other in-game session standing in the affected room.
+    ///
+    /// Lines for the same recipient are coalesced into as few frames as fit
+    /// `storage.max_message_size`, so a burst of activity costs one frame per listener.
+    async fn deliver_tmush_room_events(&mut self) -> Result<()> {
+        use crate::bbs::dispatch::Priority;
+        use crate::tmush::events::{plan_fanout, RoomEventKind, RoomListener};
+
+        let Some(store) = self.game_registry.get_tinymush_store() else {
+            return Ok(());
+        };
+        let events = store.room_events().drain();
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut listeners = Vec::new();
+        for (node_key, session) in &self.sessions {
+            if session.state != super::session::SessionState::TinyMush {
+                continue;
+            }
+            let username = session.display_name();
+            if let Ok(player) = store.get_player(&username) {
+                listeners.push(RoomListener {
+                    node_key: node_key.clone(),
+                    username,
+                    room_id: player.current_room,
+                });
+            }
+        }
+
+        // World tick output only gets airtime left over by players
+        let mut groups: [(Priority, Vec<_>); 3] = [
+            (Priority::Normal, Vec::new()),
+            (Priority::Low, Vec::new()),
+            (Priority::Background, Vec::new()),
+        ];
+        for event in events {
+            let group = match event.kind {
+                RoomEventKind::Ambient => 1,
+                RoomEventKind::Chatter => 2,
+                _ => 0,
+            };
+            groups[group].1.push(event);
+        }
+
+        for (priority, events) in groups {
+            if events.is_empty() {
+                continue;
+            }
+            let plan = plan_fanout(&events, &listeners, self.config.storage.max_message_size);
+            for (node_key, frames) in plan {
+                for frame in frames {
+                    if let Err(e) = self
+                        .send_room_event_frame(&node_key, &frame, priority)
+                        .await
+                    {
+                        warn!("Room event delivery to {} failed: {}", node_key, e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue one frame of room activity for a player at `priority`. Callers pass
+    /// Normal for speech so replies to the player's own commands (High) are never
+    /// delayed by chatter, and Low or Background for world tick output. Builds
+    /// without the scheduler send directly, so there is nothing to prioritise.
+    async fn send_room_event_frame(
+        &mut self,
+        to_node: &str,
+        frame: &str,
+        priority: crate::bbs::dispatch::Priority,
+    ) -> Result<()> {
+        #[cfg(feature = "meshtastic-proto")]
+        {
+            if let Some(scheduler) = &self.scheduler {
+                let node_id = if let Some(hex) = to_node
+                    .strip_prefix("0x")
+                    .or_else(|| to_node.strip_prefix("0X"))
+                {
+                    u32::from_str_radix(hex, 16).ok()
+                } else {
+                    to_node.parse::<u32>().ok()
+                };
+                let Some(id) = node_id else {
+                    return Err(anyhow!("Invalid node ID format: {}", to_node));
+                };
+                let outgoing = OutgoingMessage {
+                    to_node: Some(id),
+                    channel: self.reply_channel(to_node),
+                    content: frame.to_string(),
+                    priority: match priority {
+                        crate::bbs::dispatch::Priority::High => MessagePriority::High,
+                        _ => MessagePriority::Normal,
+                    },
+                    kind: crate::meshtastic::OutgoingKind::Normal,
+                    request_ack: false,
+                };
+                let env = crate::bbs::dispatch::MessageEnvelope::new(
+                    crate::bbs::dispatch::MessageCategory::Direct,
+                    priority,
+                    Duration::from_millis(0),
+                    outgoing,
+                );
+                scheduler.enqueue(env);
+                self.test_messages
+                    .push((to_node.to_string(), frame.to_string()));
+                return Ok(());
+            }
+        }
+        #[cfg(not(feature = "meshtastic-proto"))]
+        let _ = priority;
+        self.send_message(to_node, frame).await
+    }
+
+    /// Start a replication round if one is due: send our topic inventory to each peer.
+    async fn run_replication_round(&mut self) -> Result<()> {
+        if !self.replication.round_due(std::time::Instant::now()) {
+            return Ok(());
+        }
+        let frames = self.replication.inventory_frames(&self.storage).await?;
+        if frames.is_empty() {
+            return Ok(());
+        }
+        for peer in self.replication.peers() {
+            debug!("Replication: sending inventory to peer {}", peer);
+            for frame in &frames {
+                self.send_replication_frame(peer, frame).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a sync frame received from a peer and send any response frames back to it.
+    async fn handle_replication_frame(&mut self, peer: u32, content: &str) -> Result<()> {
+        let replies = self
+            .replication
+            .handle_frame(peer, content, &mut self.storage)
+            .await?;
+        for frame in replies {
+            self.send_replication_frame(peer, &frame).await?;
+        }
+        Ok(())
+    }
+
+    /// Queue one sync frame for a peer at Background priority so replication never
+    /// competes with interactive traffic.
+    async fn send_replication_frame(&mut self, peer: u32, frame: &str) -> Result<()> {
+        self.replication.note_sent(1);
+        #[cfg(feature = "meshtastic-proto")]
+        {
+            if let Some(scheduler) = &self.scheduler {
+                let outgoing = OutgoingMessage {
+                    to_node: Some(peer),
+                    channel: self.primary_channel(),
+                    content: frame.to_string(),
+                    priority: MessagePriority::Normal,
+                    kind: crate::meshtastic::OutgoingKind::Normal,
+                    request_ack: false,
+                };
+                let env = crate::bbs::dispatch::MessageEnvelope::new(
+                    crate::bbs::dispatch::MessageCategory::Maintenance,
+                    crate::bbs::dispatch::Priority::Background,
+                    Duration::from_millis(0),
+                    outgoing,
+                );
+                scheduler.enqueue(env);
+                self.test_messages
+                    .push((peer.to_string(), frame.to_string()));
+                return Ok(());
+            }
+        }
+        self.send_message(&peer.to_string(), frame).await
+    }
+
     /// Send a session-scoped reply, automatically appending a dynamic prompt unless suppressed.
     /// Ensures the combined body + optional newline + prompt is ≤ config.storage.max_message_size bytes.
     /// If chunked is true and not last_chunk, no prompt is appended (used for future multi-part HELP+).
@@ -4047,12 +5536,13 @@ impl BbsServer {
                                 s2.unread_since = Some(prev);
                             }
                         }
+                        let unread_mail = self.storage.count_unread_mail(user).await.unwrap_or(0);
                         let hint = Self::format_hint_line(&self.config.games, false);
                         let menu = Self::format_main_menu(&self.config.games);
                         deferred_reply = Some(format!(
                             "Welcome, {} you are now logged in.\n{}{}{}",
                             user,
-                            Self::format_unread_line(0),
+                            Self::format_unread_line(0, unread_mail),
                             hint,
                             menu
                         ));
@@ -4126,6 +5616,7 @@ impl BbsServer {
         if let Some(msg) = deferred_reply {
             self.send_session_message(node_key, &msg, true).await?;
         }
+        self.deliver_tmush_room_events().await?;
         Ok(())
     }
 
@@ -4208,6 +5699,106 @@ impl BbsServer {
         Ok(())
     }
 
+    /// Tear down TinyMUSH dungeon instances nobody has moved around in lately
+    fn reap_idle_dungeons(&mut self) -> Result<()> {
+        use crate::tmush::dungeon::{reap_idle, DEFAULT_IDLE_MINUTES};
+
+        let Some(tmush_store) = self.game_registry.get_tinymush_store() else {
+            return Ok(());
+        };
+        let idle_minutes = self
+            .config
+            .games
+            .tinymush_dungeon_idle_minutes
+            .unwrap_or(DEFAULT_IDLE_MINUTES);
+        let reaped = reap_idle(
+            tmush_store,
+            chrono::Duration::minutes(idle_minutes),
+            chrono::Utc::now(),
+        )?;
+        if !reaped.is_empty() {
+            info!("Tore down {} idle dungeon instances", reaped.len());
+        }
+        Ok(())
+    }
+
+    /// Move patrolling NPCs, fire idle triggers and run companion behaviors for
+    /// everyone currently in TinyMUSH. Output goes out with the room events.
+    fn run_world_tick(&mut self) -> Result<()> {
+        let Some(tmush_store) = self.game_registry.get_tinymush_store() else {
+            return Ok(());
+        };
+        let online: Vec<String> = self
+            .sessions
+            .values()
+            .filter(|s| s.state == super::session::SessionState::TinyMush)
+            .map(|s| s.display_name())
+            .collect();
+        let report = self.world_tick.run(
+            tmush_store,
+            &online,
+            chrono::Utc::now(),
+            &mut rand::thread_rng(),
+        )?;
+        if report != Default::default() {
+            debug!("World tick: {:?}", report);
+        }
+        Ok(())
+    }
+
+    /// Send a user their reply summary, if they have one due
+    #[cfg(feature = "meshtastic-proto")]
+    async fn send_reply_notification(&mut self, node_key: &str, username: &str) -> Result<()> {
+        let max = self.config.storage.max_message_size;
+        if let Some(summary) = self
+            .storage
+            .take_reply_notification(username, chrono::Utc::now(), max)
+            .await?
+        {
+            self.send_message(node_key, &summary).await?;
+        }
+        Ok(())
+    }
+
+    /// Reply summary for the user bound to a node that was just heard. Users
+    /// with a session already got theirs when they logged in.
+    #[cfg(feature = "meshtastic-proto")]
+    async fn notify_node_subscriber(&mut self, node_id: u32) -> Result<()> {
+        let node_key = node_id.to_string();
+        if self
+            .sessions
+            .get(&node_key)
+            .is_some_and(|s| s.is_logged_in())
+        {
+            return Ok(());
+        }
+        if let Some(user) = self.storage.find_user_by_node(&node_key).await? {
+            self.send_reply_notification(&node_key, &user.username)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Move threads past their topic's retention limits into the topic archive
+    async fn sweep_topic_retention(&mut self) -> Result<()> {
+        for (topic, count) in self.storage.sweep_retention(chrono::Utc::now()).await? {
+            info!("Archived {} expired threads from {}", count, topic);
+        }
+        Ok(())
+    }
+
+    /// Pay out or return TinyMUSH auction listings whose time is up
+    fn settle_auctions(&mut self) -> Result<()> {
+        let Some(tmush_store) = self.game_registry.get_tinymush_store() else {
+            return Ok(());
+        };
+        let settled = crate::tmush::auction::settle_due(tmush_store, chrono::Utc::now())?;
+        if !settled.is_empty() {
+            info!("Settled {} finished auctions", settled.len());
+        }
+        Ok(())
+    }
+
     /// Process recurring housing payments
     async fn process_housing_payments(&mut self) -> Result<()> {
         use crate::tmush::housing_cleanup::process_recurring_payments;
@@ -4259,6 +5850,88 @@ impl BbsServer {
         Ok(())
     }
 
+    fn status_refresh_due(&self) -> bool {
+        self.config.metrics.enabled
+            && self.status_last_publish.elapsed()
+                >= Duration::from_secs(self.config.metrics.refresh_secs.max(1))
+    }
+
+    /// Refresh the server-owned part of the metrics status snapshot
+    ///
+    /// Gathers session counts, scheduler gauges, storage sizes and backup state and
+    /// hands them to [`crate::metrics::publish_status`]; the HTTP listener merges
+    /// them with the live counters on each request.
+    pub async fn publish_status(&mut self) {
+        use crate::metrics::{BackupStatus, StatusSnapshot};
+        self.status_last_publish = Instant::now();
+
+        let mut status = StatusSnapshot {
+            bbs_name: self.config.bbs.name.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            started_at: Some(self.started_at),
+            updated_at: Some(Utc::now()),
+            device_connected: self.device.is_some(),
+            sessions_active: self.sessions.len() as u64,
+            sessions_logged_in: self.logged_in_session_count() as u64,
+            ..Default::default()
+        };
+        #[cfg(feature = "meshtastic-proto")]
+        {
+            // Reader/writer mode talks to the radio through the writer task
+            status.device_connected |= self.outgoing_tx.is_some();
+        }
+        #[cfg(feature = "meshtastic-proto")]
+        if let Some(ref scheduler) = self.scheduler {
+            if let Some(stats) = scheduler.snapshot().await {
+                status.scheduler = Some(crate::metrics::SchedulerGauges {
+                    queued: stats.queued as u64,
+                    dispatched_total: stats.dispatched_total,
+                    dropped_total: stats.dropped_total,
+                    dropped_overflow: stats.dropped_overflow,
+                    escalations: stats.escalations,
+                });
+            }
+        }
+        match self.storage.get_statistics().await {
+            Ok(stats) => {
+                status.total_messages = stats.total_messages as u64;
+                status.total_users = stats.total_users as u64;
+            }
+            Err(e) => debug!("Status statistics unavailable: {}", e),
+        }
+        // The walk stats every file under the data dir; keep it off the event loop
+        let data_dir = std::path::PathBuf::from(&self.config.storage.data_dir);
+        match tokio::task::spawn_blocking(move || storage_area_sizes(&data_dir)).await {
+            Ok(sizes) => status.storage_bytes = sizes,
+            Err(e) => debug!("Storage size scan failed: {}", e),
+        }
+
+        if let Some(ref scheduler) = self.backup_scheduler {
+            let backup = scheduler.status();
+            status.backup = BackupStatus {
+                enabled: backup.enabled,
+                frequency: format!("{:?}", backup.frequency),
+                ..Default::default()
+            };
+            // Only read existing metadata; BackupManager::new would create the directory
+            if backup.backup_path.is_dir() {
+                if let Ok(manager) = crate::storage::backup::BackupManager::new(
+                    backup.db_path,
+                    backup.backup_path,
+                    Default::default(),
+                ) {
+                    let backups = manager.list_backups();
+                    status.backup.count = backups.len() as u64;
+                    if let Some(newest) = backups.first() {
+                        status.backup.last_backup_at = Some(newest.created_at);
+                        status.backup.last_backup_bytes = Some(newest.size_bytes);
+                    }
+                }
+            }
+        }
+        crate::metrics::publish_status(status);
+    }
+
     /// Show BBS status and statistics
     pub async fn show_status(&self) -> Result<()> {
         println!("=== Meshbbs Status ===");
@@ -73,12 +73,18 @@ pub struct Session {
     pub list_page: usize,
     /// Currently focused thread (message) id when in thread contexts
     pub current_thread_id: Option<String>,
+    /// Currently open file library area; the focused file is `current_thread_id`
+    pub current_file_area: Option<String>,
+    /// Chat room while in [SessionState::Chat]
+    pub current_chat_room: Option<String>,
     /// Current post index within a thread (1-based); used for navigation with +/-
     pub post_index: usize,
     /// Current slice index within a post body (1-based) when content spans multiple slices
     pub slice_index: usize,
     /// Optional filter text for list/search context (e.g., `F <text>`)
     pub filter_text: Option<String>,
+    /// Active global SEARCH query; set while browsing results so B from a hit returns to them
+    pub search_query: Option<String>,
     /// Temporary scratchpad for multi-step flows (password changes, filters, etc.)
     pub pending_input: Option<String>,
     /// Baseline timestamp for unread indicators (captured as previous last_login when user logs in)
@@ -106,6 +112,7 @@ pub enum SessionState {
     ComposeNewBody,  // Two-step compose (step 2)
     ComposeReply,    // Reply compose to current thread
     ConfirmDelete,   // Confirm delete of selected entity
+    SearchResults,   // Ranked hits from a global SEARCH
     UserMenu,
     UserChangePassCurrent,
     UserChangePassNew,
@@ -114,6 +121,18 @@ pub enum SessionState {
     TinyHack,
     /// TinyMUSH multi-user shared world game
     TinyMush,
+    /// Private mail between registered users
+    MailInbox,
+    MailSent,
+    MailRead,        // Reading a single mail message slice
+    MailComposeTo,   // Two-step compose (step 1: recipient)
+    MailComposeBody, // Two-step compose (step 2: body)
+    /// Text file library
+    FileAreas,
+    FileList, // Files within current_file_area
+    FileRead, // Reading a single file slice
+    /// Teleconference chat room (see `bbs::chat`)
+    Chat,
     Disconnected,
 }
 
@@ -133,9 +152,12 @@ impl Session {
             help_seen: false,
             list_page: 1,
             current_thread_id: None,
+            current_file_area: None,
+            current_chat_room: None,
             post_index: 1,
             slice_index: 1,
             filter_text: None,
+            search_query: None,
             pending_input: None,
             unread_since: None,
             login_time: now,
@@ -207,6 +229,7 @@ impl Session {
         self.username = None;
         self.user_level = 0;
         self.current_topic = None;
+        self.current_chat_room = None;
         self.state = SessionState::Disconnected;
 
         Ok(())
@@ -280,6 +303,9 @@ impl Session {
     /// - Main/menu (logged in): `"username (lvl1)>"`
     /// - Reading messages/in topic: `"username@topic>"` (topic truncated to 20 chars)
     /// - Posting: `"post@topic>"` (falls back to `"post>"` if no topic)
+    /// - Mail: `"username@mail>"`, or `"mail>"` while composing
+    /// - Search results: `"username@search>"`
+    /// - Chat: `"username@chat/room>"`
     /// - Games (TinyHack/TinyMUSH): `""` (no prompt - games provide their own context)
     pub fn build_prompt(&self) -> String {
         // Unauthenticated
@@ -317,6 +343,21 @@ impl Session {
                     self.current_topic.as_deref().unwrap_or("bbs")
                 )
             }
+            SessionState::MailInbox | SessionState::MailSent | SessionState::MailRead => {
+                format!("{}@mail>", self.display_name())
+            }
+            SessionState::MailComposeTo | SessionState::MailComposeBody => "mail>".into(),
+            SessionState::FileAreas | SessionState::FileList | SessionState::FileRead => {
+                match &self.current_file_area {
+                    Some(area) => format!("{}@files/{}>", self.display_name(), area),
+                    None => format!("{}@files>", self.display_name()),
+                }
+            }
+            SessionState::SearchResults => format!("{}@search>", self.display_name()),
+            SessionState::Chat => match &self.current_chat_room {
+                Some(room) => format!("{}@chat/{}>", self.display_name(), room),
+                None => format!("{}@chat>", self.display_name()),
+            },
             SessionState::TinyHack | SessionState::TinyMush => {
                 // Suppress BBS prompt in game mode - games provide their own context
                 // To exit game, user types 'B' or 'QUIT' which games recognize
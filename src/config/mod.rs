@@ -21,6 +21,7 @@
 //! - [`MessageTopicConfig`] - Individual message topic configuration
 //! - [`LoggingConfig`] - Logging and debugging settings
 //! - [`SecurityConfig`] - Security and authentication parameters
+//! - [`ReplicationConfig`] - Store-and-forward sync with peer BBS nodes
 //!
 //! ## Usage
 //!
@@ -141,6 +142,12 @@ pub struct Config {
     /// New user welcome system
     #[serde(default)]
     pub welcome: crate::bbs::welcome::WelcomeConfig,
+    /// Store-and-forward topic replication with peer BBS nodes
+    #[serde(default)]
+    pub replication: ReplicationConfig,
+    /// Local Prometheus metrics and `/status` HTTP listener
+    #[serde(default)]
+    pub metrics: MetricsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,6 +193,42 @@ pub struct MeshtasticConfig {
     /// Interval (ms) for periodic scheduler stats logging (0 disables periodic stats logs).
     #[serde(default)]
     pub scheduler_stats_interval_ms: Option<u64>,
+    /// Additional channels to serve, each with its own public command policy
+    /// (`[[meshtastic.channels]]`). The primary `channel` is always served, using the
+    /// `[bbs]` prefix and help keyword unless it is listed here too.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<ChannelConfig>,
+}
+
+/// Public command policy for one channel index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    /// Channel index on the device (0-7)
+    pub index: u8,
+    /// Public commands answered on this channel, e.g. `["HELP", "LOGIN", "SEEN"]`.
+    /// All commands are enabled when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commands: Option<Vec<String>>,
+    /// Command prefix for this channel; falls back to `bbs.public_command_prefix`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Help keyword for this channel; falls back to `bbs.help_command`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help_command: Option<String>,
+    /// Whether public command results are broadcast on the channel or sent by DM
+    #[serde(default)]
+    pub broadcast: ChannelBroadcast,
+}
+
+/// Where replies to public commands go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelBroadcast {
+    /// Broadcast results on the channel the command arrived on
+    #[default]
+    Public,
+    /// Answer only the asking node by DM; nothing is broadcast on the channel
+    Dm,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -195,6 +238,13 @@ pub struct StorageConfig {
     /// Add [n/total] chunk markers to multi-part messages to help detect out-of-order delivery
     #[serde(default)]
     pub show_chunk_markers: bool,
+    /// Max messages kept per mail folder (inbox and sent)
+    #[serde(default = "default_mail_quota")]
+    pub mail_quota: usize,
+}
+
+fn default_mail_quota() -> usize {
+    crate::storage::DEFAULT_MAIL_QUOTA
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -224,6 +274,16 @@ pub struct GamesConfig {
     /// Optional override for TinyMUSH Sled database path; defaults to `<data_dir>/tinymush`.
     #[serde(default)]
     pub tinymush_db_path: Option<String>,
+    /// Maximum TinyMUSH dungeon instances running at once; defaults to 8.
+    #[serde(default)]
+    pub tinymush_dungeon_max_instances: Option<usize>,
+    /// Minutes without movement before a dungeon instance is torn down; defaults to 30.
+    #[serde(default)]
+    pub tinymush_dungeon_idle_minutes: Option<i64>,
+    /// Seconds between TinyMUSH world ticks (NPC patrols, idle triggers, companions);
+    /// defaults to 60, 0 disables.
+    #[serde(default)]
+    pub tinymush_world_tick_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -284,6 +344,100 @@ impl IdentBeaconConfig {
     }
 }
 
+/// Store-and-forward replication between meshbbs instances.
+///
+/// Each round this node advertises an inventory of its replicated topics to every
+/// peer; peers reply with the message IDs they hold and pull whatever they lack.
+/// Only topics opted in with `MODIFYTOPIC <id> sync=on` take part.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Peer BBS node IDs (decimal or `0x`-prefixed hex)
+    #[serde(default)]
+    pub peers: Vec<String>,
+    /// Seconds between inventory rounds
+    #[serde(default = "default_replication_interval")]
+    pub interval_secs: u64,
+    /// Newest messages per topic advertised in each round
+    #[serde(default = "default_replication_window")]
+    pub max_ids_per_topic: usize,
+}
+
+fn default_replication_interval() -> u64 {
+    900
+}
+
+fn default_replication_window() -> usize {
+    50
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            peers: Vec::new(),
+            interval_secs: default_replication_interval(),
+            max_ids_per_topic: default_replication_window(),
+        }
+    }
+}
+
+impl ReplicationConfig {
+    /// Parse the configured peer list, skipping (and logging) malformed entries
+    pub fn peer_ids(&self) -> Vec<u32> {
+        self.peers
+            .iter()
+            .filter_map(|p| {
+                let p = p.trim();
+                let parsed = match p.strip_prefix("0x").or_else(|| p.strip_prefix("0X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => p
+                        .strip_prefix('!')
+                        .map(|hex| u32::from_str_radix(hex, 16).ok())
+                        .unwrap_or_else(|| p.parse().ok()),
+                };
+                if parsed.is_none() {
+                    log::warn!("Ignoring invalid replication peer '{}'", p);
+                }
+                parsed
+            })
+            .collect()
+    }
+}
+
+/// Optional HTTP listener serving Prometheus metrics at `/metrics` and a JSON
+/// status document at `/status` (also read by `meshbbs status`). Only loopback
+/// addresses are accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_metrics_bind")]
+    pub bind: String,
+    /// Seconds between refreshes of the published status
+    #[serde(default = "default_metrics_refresh")]
+    pub refresh_secs: u64,
+}
+
+fn default_metrics_bind() -> String {
+    "127.0.0.1:9184".to_string()
+}
+
+fn default_metrics_refresh() -> u64 {
+    15
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: default_metrics_bind(),
+            refresh_secs: default_metrics_refresh(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeatherConfig {
     /// OpenWeatherMap API key
@@ -406,11 +560,13 @@ impl Default for Config {
                 scheduler_max_queue: Some(512),
                 scheduler_aging_threshold_ms: Some(5000),
                 scheduler_stats_interval_ms: Some(10000),
+                channels: Vec::new(),
             },
             storage: StorageConfig {
                 data_dir: "./data".to_string(),
                 max_message_size: 200, // Reduced from 230 to account for ~30 bytes Meshtastic protocol overhead
                 show_chunk_markers: false, // Set to true to add [n/total] markers for debugging out-of-order delivery
+                mail_quota: crate::storage::DEFAULT_MAIL_QUOTA,
             },
             message_topics,
             logging: LoggingConfig {
@@ -429,6 +585,8 @@ impl Default for Config {
                 cooldown_minutes: 5,
                 max_welcomes_per_node: 1,
             },
+            replication: ReplicationConfig::default(),
+            metrics: MetricsConfig::default(),
         }
     }
 }
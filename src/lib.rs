@@ -79,7 +79,7 @@ pub mod bbs;
 pub mod config;
 pub mod logutil;
 pub mod meshtastic;
-pub mod metrics; // new metrics module (Phase 3 scaffold)
+pub mod metrics;
 pub mod protobuf; // always declare; internal stubs handle feature gating
 pub mod storage;
 pub mod tmush;
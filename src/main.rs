@@ -5,6 +5,7 @@
 //! - `status` - print current status and a brief summary
 //! - `check-device --port <path> [-b <baud>] [--timeout <s>]` - verify Meshtastic device connectivity
 //! - `sysop-passwd` - interactively set the sysop password (argon2 hashed)
+//! - `reindex` - rebuild the message index from the JSON message files
 //!
 //! ## Installation
 //!
@@ -64,8 +65,12 @@ enum Commands {
         #[arg(long, default_value = "/tmp/meshbbs.pid")]
         pid_file: String,
     },
-    /// Show BBS status and statistics
-    Status,
+    /// Show BBS status and statistics (live from a running daemon when [metrics] is enabled)
+    Status {
+        /// Print the daemon's /status JSON instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
     /// Check Meshtastic device connectivity and configuration over serial
     CheckDevice {
         /// Device serial port
@@ -82,6 +87,73 @@ enum Commands {
     SysopPasswd,
     /// Hash a password for use in config.toml (reads password from stdin)
     HashPassword,
+    /// Rebuild the message index from the JSON message files (stop the server first)
+    Reindex,
+    /// Export or import TinyMUSH world content as JSON area files (stop the server first)
+    World {
+        #[command(subcommand)]
+        action: WorldAction,
+    },
+    /// Manage the text file library users browse from the [F]ile menu
+    Files {
+        #[command(subcommand)]
+        action: FilesAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorldAction {
+    /// Write rooms, objects, NPCs, quests, achievements, recipes, shops,
+    /// companions and housing templates to a directory of area files
+    Export {
+        /// Output directory (created if missing)
+        dir: String,
+    },
+    /// Merge a directory of area files into the world
+    Import {
+        /// Directory written by `world export`
+        dir: String,
+        /// Replace records whose id already exists with different content
+        #[arg(long)]
+        overwrite: bool,
+        /// Show the diff without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum FilesAction {
+    /// List areas and files with sizes and download counts
+    List,
+    /// Create a file area or update its read level and description
+    Area {
+        /// Area name (letters, digits, - and _)
+        area: String,
+        /// Minimum user level needed to see the area
+        #[arg(short, long)]
+        level: Option<u8>,
+        /// Short description shown above the file list
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+    /// Upload a UTF-8 text file into an area, replacing any file of the same name
+    Add {
+        /// Existing area name
+        area: String,
+        /// Path of the text file to upload
+        path: String,
+        /// Name shown to users (defaults to the file name without extension)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// Remove a file from an area
+    Remove {
+        /// Area holding the file
+        area: String,
+        /// File name as shown to users
+        name: String,
+    },
 }
 
 #[tokio::main]
@@ -249,11 +321,37 @@ async fn main() -> Result<()> {
             info!("BBS server starting...");
             bbs.run().await?;
         }
-        Commands::Status => {
+        Commands::Status { json } => {
             init_logging(&pre_config, cli.verbose);
             let config = pre_config.unwrap_or(Config::load(&cli.config).await?);
-            let bbs = BbsServer::new(config).await?;
-            bbs.show_status().await?;
+            let live = if config.metrics.enabled {
+                meshbbs::metrics::http::fetch_status(
+                    &config.metrics.bind,
+                    std::time::Duration::from_secs(3),
+                )
+                .await
+                .map_err(|e| {
+                    format!(
+                        "No running daemon answered on {}: {}",
+                        config.metrics.bind, e
+                    )
+                })
+            } else {
+                Err("Live status needs [metrics] enabled = true in config.toml".to_string())
+            };
+            match live {
+                Ok(status) if json => println!("{}", serde_json::to_string_pretty(&status)?),
+                Ok(status) => print_live_status(&status),
+                Err(reason) if json => {
+                    eprintln!("{}", reason);
+                    std::process::exit(1);
+                }
+                Err(reason) => {
+                    println!("({}; showing on-disk status)", reason);
+                    let bbs = BbsServer::new(config).await?;
+                    bbs.show_status().await?;
+                }
+            }
         }
         Commands::SysopPasswd => {
             init_logging(&pre_config, cli.verbose);
@@ -293,6 +391,133 @@ async fn main() -> Result<()> {
             tokio::fs::write(&cli.config, serialized).await?;
             println!("Sysop password updated successfully.");
         }
+        Commands::Reindex => {
+            let config = pre_config.unwrap_or(Config::load(&cli.config).await?);
+            let storage = meshbbs::storage::Storage::new(&config.storage.data_dir).await?;
+            if !storage.message_index_active() {
+                eprintln!("Error: message index is locked; stop the running server and retry.");
+                std::process::exit(1);
+            }
+            let count = storage.rebuild_message_index().await?;
+            println!("Message index rebuilt: {} messages.", count);
+        }
+        Commands::World { action } => {
+            use meshbbs::tmush::world_io::{export_world, import_world, ImportOptions};
+            use std::path::Path;
+
+            let config = pre_config.unwrap_or(Config::load(&cli.config).await?);
+            let db_path = config
+                .games
+                .tinymush_db_path
+                .as_deref()
+                .unwrap_or("data/tinymush");
+            let store = match meshbbs::tmush::TinyMushStoreBuilder::new(db_path)
+                .without_world_seed()
+                .open()
+            {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!(
+                        "Error: cannot open TinyMUSH store at {}: {} (is the server running?)",
+                        db_path, e
+                    );
+                    std::process::exit(1);
+                }
+            };
+            match action {
+                WorldAction::Export { dir } => {
+                    let manifest = export_world(&store, Path::new(&dir), None)?;
+                    println!("World exported to {}:", dir);
+                    for (kind, count) in &manifest.counts {
+                        println!("  {}: {}", kind, count);
+                    }
+                }
+                WorldAction::Import {
+                    dir,
+                    overwrite,
+                    dry_run,
+                } => {
+                    let opts = ImportOptions { overwrite, dry_run };
+                    let report = import_world(&store, Path::new(&dir), opts)?;
+                    print!("{}", report.summary());
+                    if !dry_run {
+                        println!("{} records written.", report.changes());
+                    }
+                }
+            }
+        }
+        Commands::Files { action } => {
+            use meshbbs::storage::files::MAX_LIBRARY_FILE_BYTES;
+            use meshbbs::validation::validate_file_size;
+
+            let config = pre_config.unwrap_or(Config::load(&cli.config).await?);
+            let storage = meshbbs::storage::Storage::new(&config.storage.data_dir).await?;
+            let actor = config.bbs.sysop.clone();
+            match action {
+                FilesAction::List => {
+                    let library = storage.get_file_library().await?;
+                    if library.areas.is_empty() {
+                        println!("No file areas.");
+                    }
+                    for (name, area) in &library.areas {
+                        println!(
+                            "{} (read level {}) {}",
+                            name, area.read_level, area.description
+                        );
+                        for file in &area.files {
+                            println!(
+                                "  {:<20} {:>6} bytes  {:>4} downloads  {} {}",
+                                file.name,
+                                file.size,
+                                file.downloads,
+                                file.uploaded_by,
+                                file.uploaded_at.format("%Y-%m-%d")
+                            );
+                        }
+                    }
+                }
+                FilesAction::Area {
+                    area,
+                    level,
+                    description,
+                } => {
+                    let created = storage
+                        .set_file_area(&area, description.as_deref(), level, &actor)
+                        .await?;
+                    println!(
+                        "File area '{}' {}.",
+                        area,
+                        if created { "created" } else { "updated" }
+                    );
+                }
+                FilesAction::Add { area, path, name } => {
+                    let path = std::path::Path::new(&path);
+                    let size = std::fs::metadata(path)?.len();
+                    if let Err(e) = validate_file_size(size, MAX_LIBRARY_FILE_BYTES) {
+                        eprintln!("Error: {}: {}", path.display(), e);
+                        std::process::exit(1);
+                    }
+                    let text = std::fs::read_to_string(path)?;
+                    let name = name.unwrap_or_else(|| {
+                        path.file_stem()
+                            .map(|s| s.to_string_lossy().into_owned())
+                            .unwrap_or_default()
+                    });
+                    let file = storage
+                        .add_library_file(&area, &name, &text, false, &actor)
+                        .await?;
+                    println!("Uploaded {}/{} ({} bytes).", area, file.name, file.size);
+                }
+                FilesAction::Remove { area, name } => {
+                    if storage.delete_library_file(&area, &name, &actor).await? {
+                        println!("Removed {}/{}.", area, name);
+                    } else {
+                        eprintln!("Error: no file {} in area {}.", name, area);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
         Commands::HashPassword => {
             // Read password from stdin (one line)
             use argon2::Argon2;
@@ -394,9 +619,71 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Human-readable summary of a running daemon's `/status`
+fn print_live_status(status: &meshbbs::metrics::StatusSnapshot) {
+    println!("=== Meshbbs Status (live) ===");
+    println!("BBS Name: {}", status.bbs_name);
+    println!("Version: {}", status.version);
+    let up = status.uptime_secs;
+    println!(
+        "Uptime: {}d {}h {}m",
+        up / 86400,
+        up % 86400 / 3600,
+        up % 3600 / 60
+    );
+    println!(
+        "Sessions: {} active, {} logged in",
+        status.sessions_active, status.sessions_logged_in
+    );
+    println!(
+        "Meshtastic Device: {}",
+        if status.device_connected {
+            "Connected"
+        } else {
+            "Not connected"
+        }
+    );
+    if let Some(s) = &status.scheduler {
+        println!(
+            "Scheduler: {} queued, {} sent, {} dropped",
+            s.queued, s.dispatched_total, s.dropped_total
+        );
+    }
+    let r = &status.reliable;
+    println!(
+        "Reliable DMs: {} sent, {} acked, {} failed, {} retries, avg ACK {}",
+        r.reliable_sent,
+        r.reliable_acked,
+        r.reliable_failed,
+        r.reliable_retries,
+        r.ack_latency_avg_ms
+            .map(|ms| format!("{}ms", ms))
+            .unwrap_or_else(|| "-".into())
+    );
+    println!("Total Messages: {}", status.total_messages);
+    println!("Total Users: {}", status.total_users);
+    let disk: u64 = status.storage_bytes.values().sum();
+    println!("Data on disk: {} KiB", disk / 1024);
+    let b = &status.backup;
+    match b.last_backup_at {
+        Some(at) if b.enabled => println!(
+            "Backups: {} ({} on disk, last {})",
+            b.frequency,
+            b.count,
+            at.format("%Y-%m-%d %H:%M UTC")
+        ),
+        _ if b.enabled => println!("Backups: {} (none yet)", b.frequency),
+        _ => println!("Backups: disabled ({} on disk)", b.count),
+    }
+    let commands: u64 = status.commands.values().flat_map(|c| c.values()).sum();
+    println!("Commands handled: {}", commands);
+}
+
 fn normalize_meshtastic_endpoint(transport: &str, endpoint: String) -> String {
     if transport.eq_ignore_ascii_case("tcp") && !endpoint.starts_with("tcp://") {
         format!("tcp://{}", endpoint)
+    } else if transport.eq_ignore_ascii_case("sim") && !endpoint.starts_with("sim://") {
+        format!("sim://{}", endpoint)
     } else {
         endpoint
     }
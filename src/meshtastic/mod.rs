@@ -7,6 +7,7 @@
 //! ## Features
 //!
 //! - **Serial Communication**: Connect to Meshtastic devices via USB/UART
+//! - **Simulated Radio**: `sim://` endpoints run an in-process fake node (see [`sim`])
 //! - **Protocol Support**: Both text parsing and protobuf decoding
 //! - **Event Processing**: Convert raw device messages to structured events
 //! - **SLIP Decoding**: Handle SLIP-encoded protocol buffer frames
@@ -240,6 +241,14 @@ mod utf8_tests {
         assert_eq!(endpoint, "192.168.1.8:4403");
     }
 
+    #[cfg(feature = "meshtastic-proto")]
+    #[test]
+    fn parse_transport_endpoint_sim_scheme() {
+        let (kind, endpoint) = super::parse_transport_endpoint("sim://ci?nodes=2");
+        assert_eq!(kind, super::TransportKind::Sim);
+        assert_eq!(endpoint, "ci?nodes=2");
+    }
+
     #[cfg(feature = "meshtastic-proto")]
     #[test]
     fn parse_transport_endpoint_defaults_to_serial() {
@@ -379,6 +388,9 @@ fn summarize_known_port_payload(port: proto::PortNum, payload: &[u8]) -> Option<
 #[cfg(feature = "meshtastic-proto")]
 pub mod slip; // restore SLIP decoder (Meshtastic uses SLIP over some transports)
 
+#[cfg(feature = "meshtastic-proto")]
+pub mod sim; // in-process simulated radio behind sim:// endpoints
+
 #[cfg(feature = "serial")]
 use serialport::SerialPort;
 
@@ -387,6 +399,17 @@ use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
 use std::path::Path;
 
+/// File name of the node cache the reader persists in the storage data directory
+pub const NODE_CACHE_FILE: &str = "node_cache.json";
+
+/// Path of the node cache persisted under `data_dir`
+pub fn node_cache_path(data_dir: &str) -> String {
+    Path::new(data_dir)
+        .join(NODE_CACHE_FILE)
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(feature = "meshtastic-proto")]
 trait TransportIo: Read + Write + Send {}
 
@@ -401,6 +424,7 @@ type SharedTransport = Arc<Mutex<Box<dyn TransportIo>>>;
 enum TransportKind {
     Serial,
     Tcp,
+    Sim,
 }
 
 #[cfg(feature = "meshtastic-proto")]
@@ -408,6 +432,9 @@ fn parse_transport_endpoint(endpoint: &str) -> (TransportKind, String) {
     if let Some(addr) = endpoint.strip_prefix("tcp://") {
         return (TransportKind::Tcp, addr.to_string());
     }
+    if let Some(spec) = endpoint.strip_prefix("sim://") {
+        return (TransportKind::Sim, spec.to_string());
+    }
     (TransportKind::Serial, endpoint.to_string())
 }
 
@@ -440,6 +467,16 @@ fn make_null_transport() -> SharedTransport {
     Arc::new(Mutex::new(Box::new(NullTransport)))
 }
 
+/// Last reported GPS fix for a node
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NodePosition {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>,
+    pub time: DateTime<Utc>,
+}
+
 /// Cached node information with timestamp for persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedNodeInfo {
@@ -448,6 +485,71 @@ pub struct CachedNodeInfo {
     pub short_name: String,
     pub last_seen: DateTime<Utc>,
     pub first_seen: DateTime<Utc>,
+    /// Time of the last packet of any kind from this node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_heard: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<NodePosition>,
+    /// Battery percent from device telemetry (101 means powered)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_level: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voltage: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snr: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<i32>,
+    /// Hops the last packet travelled (0 = heard directly)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hops_away: Option<u32>,
+}
+
+impl CachedNodeInfo {
+    fn placeholder(node_id: u32, now: DateTime<Utc>) -> Self {
+        Self {
+            node_id,
+            long_name: String::new(),
+            short_name: String::new(),
+            last_seen: now,
+            first_seen: now,
+            last_heard: None,
+            position: None,
+            battery_level: None,
+            voltage: None,
+            snr: None,
+            rssi: None,
+            hops_away: None,
+        }
+    }
+
+    /// Most recent time anything was received from the node
+    pub fn heard_at(&self) -> DateTime<Utc> {
+        self.last_heard
+            .map_or(self.last_seen, |h| h.max(self.last_seen))
+    }
+
+    /// Short name, falling back to long name and then the `!xxxxxxxx` id
+    pub fn label(&self) -> String {
+        let short = self.short_name.trim();
+        if !short.is_empty() {
+            return short.to_string();
+        }
+        let long = self.long_name.trim();
+        if !long.is_empty() {
+            return long.to_string();
+        }
+        format!("!{:08x}", self.node_id)
+    }
+}
+
+/// Great-circle distance between two positions in kilometres
+pub fn haversine_km(a: &NodePosition, b: &NodePosition) -> f64 {
+    const EARTH_RADIUS_KM: f64 = 6371.0;
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let dlat = lat2 - lat1;
+    let dlon = (b.longitude - a.longitude).to_radians();
+    let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * h.sqrt().asin()
 }
 
 /// Node cache for persistent storage
@@ -455,6 +557,9 @@ pub struct CachedNodeInfo {
 pub struct NodeCache {
     pub nodes: std::collections::HashMap<u32, CachedNodeInfo>,
     pub last_updated: DateTime<Utc>,
+    /// The BBS radio's own node number, once reported by the device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub our_node_id: Option<u32>,
 }
 
 impl NodeCache {
@@ -462,6 +567,7 @@ impl NodeCache {
         Self {
             nodes: std::collections::HashMap::new(),
             last_updated: Utc::now(),
+            our_node_id: None,
         }
     }
 
@@ -534,21 +640,127 @@ impl NodeCache {
                 n.short_name = short_name.clone();
                 n.last_seen = now;
             })
-            .or_insert(CachedNodeInfo {
-                node_id,
+            .or_insert_with(|| CachedNodeInfo {
                 long_name,
                 short_name,
-                last_seen: now,
-                first_seen: now,
+                ..CachedNodeInfo::placeholder(node_id, now)
             });
         self.last_updated = now;
     }
 
+    fn node_mut(&mut self, node_id: u32) -> &mut CachedNodeInfo {
+        let now = Utc::now();
+        self.last_updated = now;
+        self.nodes
+            .entry(node_id)
+            .or_insert_with(|| CachedNodeInfo::placeholder(node_id, now))
+    }
+
+    /// Note any packet from a node along with its link quality; `None` keeps the previous value
+    pub fn record_heard(
+        &mut self,
+        node_id: u32,
+        snr: Option<f32>,
+        rssi: Option<i32>,
+        hops_away: Option<u32>,
+    ) {
+        let node = self.node_mut(node_id);
+        node.last_heard = Some(Utc::now());
+        if snr.is_some() {
+            node.snr = snr;
+        }
+        if rssi.is_some() {
+            node.rssi = rssi;
+        }
+        if hops_away.is_some() {
+            node.hops_away = hops_away;
+        }
+    }
+
+    /// Store the latest position fix; (0, 0) is treated as "no fix" and ignored
+    pub fn update_position(
+        &mut self,
+        node_id: u32,
+        latitude: f64,
+        longitude: f64,
+        altitude: Option<i32>,
+    ) {
+        if latitude == 0.0 && longitude == 0.0 {
+            return;
+        }
+        self.node_mut(node_id).position = Some(NodePosition {
+            latitude,
+            longitude,
+            altitude,
+            time: Utc::now(),
+        });
+    }
+
+    /// Store battery state from device telemetry
+    pub fn update_device_metrics(
+        &mut self,
+        node_id: u32,
+        battery_level: Option<u32>,
+        voltage: Option<f32>,
+    ) {
+        let node = self.node_mut(node_id);
+        if battery_level.is_some() {
+            node.battery_level = battery_level;
+        }
+        if voltage.is_some() {
+            node.voltage = voltage;
+        }
+    }
+
+    /// Nodes other than the BBS itself, most recently heard first
+    pub fn recently_heard(&self) -> Vec<&CachedNodeInfo> {
+        let mut nodes: Vec<_> = self
+            .nodes
+            .values()
+            .filter(|n| Some(n.node_id) != self.our_node_id)
+            .collect();
+        nodes.sort_by(|a, b| {
+            b.heard_at()
+                .cmp(&a.heard_at())
+                .then(a.node_id.cmp(&b.node_id))
+        });
+        nodes
+    }
+
+    /// Look a node up by `!hex` / `0xhex` id, decimal number, short name or long name
+    pub fn find(&self, query: &str) -> Option<&CachedNodeInfo> {
+        let q = query.trim();
+        let hex = q
+            .strip_prefix('!')
+            .or_else(|| q.strip_prefix("0x"))
+            .or_else(|| q.strip_prefix("0X"));
+        let id = match hex {
+            Some(h) => u32::from_str_radix(h, 16).ok(),
+            None => q.parse::<u32>().ok(),
+        };
+        if let Some(node) = id.and_then(|id| self.nodes.get(&id)) {
+            return Some(node);
+        }
+        let by_name = |pick: fn(&CachedNodeInfo) -> &str| {
+            self.recently_heard()
+                .into_iter()
+                .find(|n| pick(n).trim().eq_ignore_ascii_case(q))
+        };
+        by_name(|n| &n.short_name).or_else(|| by_name(|n| &n.long_name))
+    }
+
+    /// Distance from the BBS node to `node_id`, when both have a position
+    pub fn distance_km(&self, node_id: u32) -> Option<f64> {
+        let ours = self.nodes.get(&self.our_node_id?)?.position?;
+        let theirs = self.nodes.get(&node_id)?.position?;
+        Some(haversine_km(&ours, &theirs))
+    }
+
     #[allow(dead_code)]
     pub fn remove_stale_nodes(&mut self, max_age_days: u32) -> usize {
         let cutoff = Utc::now() - chrono::Duration::days(max_age_days as i64);
         let initial_count = self.nodes.len();
-        self.nodes.retain(|_, node| node.last_seen > cutoff);
+        self.nodes.retain(|_, node| node.heard_at() > cutoff);
         let removed = initial_count - self.nodes.len();
         if removed > 0 {
             self.last_updated = Utc::now();
@@ -640,11 +852,16 @@ pub struct MeshtasticReader {
     node_id_tx: mpsc::UnboundedSender<u32>,
     node_cache: NodeCache,
     cache_file_path: String,
+    cache_saved_at: Option<std::time::Instant>,
     nodes: std::collections::HashMap<u32, proto::NodeInfo>,
     our_node_id: Option<u32>,
     binary_frames_seen: bool,
 }
 
+/// Minimum gap between node cache writes triggered by position/telemetry traffic
+#[cfg(feature = "meshtastic-proto")]
+const NODE_CACHE_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
 /// Writer task for Meshtastic device writing
 #[cfg(feature = "meshtastic-proto")]
 pub struct MeshtasticWriter {
@@ -895,7 +1112,7 @@ impl MeshtasticDevice {
                 #[cfg(feature = "meshtastic-proto")]
                 node_cache: NodeCache::new(),
                 #[cfg(feature = "meshtastic-proto")]
-                cache_file_path: "data/node_cache.json".to_string(),
+                cache_file_path: node_cache_path("data"),
                 #[cfg(feature = "meshtastic-proto")]
                 binary_frames_seen: false,
                 #[cfg(feature = "meshtastic-proto")]
@@ -934,7 +1151,7 @@ impl MeshtasticDevice {
                 #[cfg(feature = "meshtastic-proto")]
                 node_cache: NodeCache::new(),
                 #[cfg(feature = "meshtastic-proto")]
-                cache_file_path: "data/node_cache.json".to_string(),
+                cache_file_path: node_cache_path("data"),
                 #[cfg(feature = "meshtastic-proto")]
                 binary_frames_seen: false,
                 #[cfg(feature = "meshtastic-proto")]
@@ -1663,6 +1880,7 @@ async fn create_shared_tcp_transport(address: &str) -> Result<SharedTransport> {
 #[cfg(feature = "meshtastic-proto")]
 impl MeshtasticReader {
     /// Create a new reader task with shared port
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         shared_port: SharedTransport,
         transport_kind: TransportKind,
@@ -1671,6 +1889,7 @@ impl MeshtasticReader {
         control_rx: mpsc::UnboundedReceiver<ControlMessage>,
         writer_control_tx: mpsc::UnboundedSender<ControlMessage>,
         node_id_tx: mpsc::UnboundedSender<u32>,
+        data_dir: &str,
     ) -> Result<Self> {
         debug!("Initializing Meshtastic reader with shared port");
 
@@ -1685,7 +1904,8 @@ impl MeshtasticReader {
             writer_control_tx,
             node_id_tx,
             node_cache: NodeCache::new(),
-            cache_file_path: "data/node_cache.json".to_string(),
+            cache_file_path: node_cache_path(data_dir),
+            cache_saved_at: None,
             nodes: std::collections::HashMap::new(),
             our_node_id: None,
             binary_frames_seen: false,
@@ -1700,6 +1920,7 @@ impl MeshtasticReader {
         control_rx: mpsc::UnboundedReceiver<ControlMessage>,
         writer_control_tx: mpsc::UnboundedSender<ControlMessage>,
         node_id_tx: mpsc::UnboundedSender<u32>,
+        data_dir: &str,
     ) -> Result<Self> {
         info!("Initializing mock Meshtastic reader");
 
@@ -1714,7 +1935,8 @@ impl MeshtasticReader {
             writer_control_tx,
             node_id_tx,
             node_cache: NodeCache::new(),
-            cache_file_path: "data/node_cache.json".to_string(),
+            cache_file_path: node_cache_path(data_dir),
+            cache_saved_at: None,
             nodes: std::collections::HashMap::new(),
             our_node_id: None,
             binary_frames_seen: false,
@@ -1906,6 +2128,16 @@ impl MeshtasticReader {
                         let port =
                             PortNum::try_from(data_msg.portnum).unwrap_or(PortNum::UnknownApp);
 
+                        // Track last-heard time and link quality for NODES / SEEN
+                        if pkt.from != 0 && Some(pkt.from) != self.our_node_id {
+                            let hops = (pkt.hop_start > 0)
+                                .then(|| pkt.hop_start.saturating_sub(pkt.hop_limit));
+                            let snr = (pkt.rx_snr != 0.0).then_some(pkt.rx_snr);
+                            let rssi = (pkt.rx_rssi != 0).then_some(pkt.rx_rssi);
+                            self.node_cache.record_heard(pkt.from, snr, rssi, hops);
+                            self.save_node_cache_throttled();
+                        }
+
                         // Correlate explicit ACKs (priority=ACK and reply_id set)
                         if pkt.priority == 120 && data_msg.reply_id != 0 {
                             debug!(
@@ -1962,7 +2194,42 @@ impl MeshtasticReader {
                         match port {
                             PortNum::PositionApp => {
                                 // Position packets received (not used for ping anymore - we use TEXT_MESSAGE_APP with ACKs)
+                                use prost::Message;
                                 debug!("Received POSITION_APP packet from 0x{:08x}", pkt.from);
+                                let mut payload_buf = bytes::Bytes::from(data_msg.payload.to_vec());
+                                if let Ok(pos) = proto::Position::decode(&mut payload_buf) {
+                                    if let (Some(lat), Some(lon)) =
+                                        (pos.latitude_i, pos.longitude_i)
+                                    {
+                                        self.node_cache.update_position(
+                                            pkt.from,
+                                            lat as f64 * 1e-7,
+                                            lon as f64 * 1e-7,
+                                            pos.altitude.or(pos.altitude_hae),
+                                        );
+                                    }
+                                }
+                            }
+                            PortNum::TelemetryApp => {
+                                use prost::Message;
+                                use proto::telemetry::Variant as TVar;
+                                let mut payload_buf = bytes::Bytes::from(data_msg.payload.to_vec());
+                                if let Ok(proto::Telemetry {
+                                    variant: Some(TVar::DeviceMetrics(dm)),
+                                    ..
+                                }) = proto::Telemetry::decode(&mut payload_buf)
+                                {
+                                    self.node_cache.update_device_metrics(
+                                        pkt.from,
+                                        dm.battery_level,
+                                        dm.voltage,
+                                    );
+                                }
+                                if let Some(summary) =
+                                    summarize_known_port_payload(port, &data_msg.payload)
+                                {
+                                    debug!("Telemetry from {}: {}", pkt.from, summary);
+                                }
                             }
                             PortNum::NodeinfoApp => {
                                 // Handle NODEINFO packets received over the mesh
@@ -2076,6 +2343,7 @@ impl MeshtasticReader {
                     // Only send node ID to writer if we don't already know it
                     if self.our_node_id.is_none() {
                         self.our_node_id = Some(info.my_node_num);
+                        self.node_cache.our_node_id = Some(info.my_node_num);
                         debug!("Got our node ID: {}", info.my_node_num);
 
                         // Notify the writer about our node ID (first time only)
@@ -2112,6 +2380,24 @@ impl MeshtasticReader {
                             is_from_startup_queue: false, // Config-based detection
                         });
                         self.node_cache.update_node(n.num, long_name, short_name);
+                        // The device's node DB also carries the last known fix and battery
+                        if let Some(pos) = &n.position {
+                            if let (Some(lat), Some(lon)) = (pos.latitude_i, pos.longitude_i) {
+                                self.node_cache.update_position(
+                                    n.num,
+                                    lat as f64 * 1e-7,
+                                    lon as f64 * 1e-7,
+                                    pos.altitude,
+                                );
+                            }
+                        }
+                        if let Some(dm) = &n.device_metrics {
+                            self.node_cache.update_device_metrics(
+                                n.num,
+                                dm.battery_level,
+                                dm.voltage,
+                            );
+                        }
 
                         // Save cache (best effort)
                         if let Err(e) = self.save_node_cache() {
@@ -2326,6 +2612,20 @@ impl MeshtasticReader {
         }
         self.node_cache.save_to_file(&self.cache_file_path)
     }
+
+    /// Save the cache unless it was written within [`NODE_CACHE_SAVE_INTERVAL`]
+    fn save_node_cache_throttled(&mut self) {
+        if self
+            .cache_saved_at
+            .is_some_and(|t| t.elapsed() < NODE_CACHE_SAVE_INTERVAL)
+        {
+            return;
+        }
+        self.cache_saved_at = Some(std::time::Instant::now());
+        if let Err(e) = self.save_node_cache() {
+            debug!("Failed to save node cache: {}", e);
+        }
+    }
 }
 
 #[cfg(feature = "meshtastic-proto")]
@@ -3171,6 +3471,7 @@ pub async fn create_reader_writer_system(
     port_name: &str,
     baud_rate: u32,
     tuning: WriterTuning,
+    data_dir: &str,
 ) -> Result<(
     MeshtasticReader,
     MeshtasticWriter,
@@ -3196,6 +3497,7 @@ pub async fn create_reader_writer_system(
     let shared_transport = match transport_kind {
         TransportKind::Serial => create_shared_serial_port(&endpoint, baud_rate).await?,
         TransportKind::Tcp => create_shared_tcp_transport(&endpoint).await?,
+        TransportKind::Sim => sim::create_shared_sim_transport(&endpoint)?,
     };
 
     #[cfg(feature = "serial")]
@@ -3207,6 +3509,7 @@ pub async fn create_reader_writer_system(
         reader_control_rx,
         writer_control_tx.clone(),
         node_id_tx.clone(),
+        data_dir,
     )
     .await?;
     #[cfg(feature = "serial")]
@@ -3229,6 +3532,7 @@ pub async fn create_reader_writer_system(
                 reader_control_rx,
                 writer_control_tx.clone(),
                 node_id_tx.clone(),
+                data_dir,
             )
             .await?,
             MeshtasticWriter::new_mock(outgoing_rx, writer_control_rx, tuning).await?,
@@ -0,0 +1,498 @@
+//! Simulated radio for `sim://` endpoints.
+//!
+//! [`SimTransport`] stands in for a serial or TCP radio and speaks the same
+//! `0x94 0xC3 <len_hi> <len_lo>` framed ToRadio/FromRadio protobuf stream, so the reader,
+//! writer, retry and scheduler paths run unchanged against it:
+//!
+//! - `want_config_id` is answered with `my_info`, one `node_info` per fake node and
+//!   `config_complete_id`
+//! - packets sent with `want_ack` get a ROUTING_APP status back; a `loss` fraction is dropped
+//!   silently (so the writer retries) and a `nak` fraction is answered with a routing error
+//! - every frame handed to the reader is held back by `latency_ms`
+//!
+//! Endpoint form: `sim://<name>?nodes=3&loss=0.1&nak=0.05&latency_ms=250&seed=7`. All
+//! parameters are optional. The running simulator is registered under `<name>` (default
+//! `default`) so tests can reach it through [`handle`] to inject text from fake nodes and
+//! inspect what the BBS transmitted.
+
+use super::{proto, SharedTransport};
+use anyhow::{anyhow, Result};
+use prost::Message;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Node number the simulated radio reports for the BBS itself
+pub const SIM_NODE_NUM: u32 = 0x5151_0001;
+
+/// Node number of the first fake peer; the rest follow consecutively
+pub const SIM_FIRST_PEER: u32 = 0x5151_1001;
+
+/// Routing priority the firmware uses for ACK packets
+const PRIORITY_ACK: i32 = 120;
+
+/// Parameters parsed from a `sim://` endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimConfig {
+    pub name: String,
+    /// Number of fake peer nodes in the node DB
+    pub nodes: u32,
+    /// Fraction (0.0-1.0) of `want_ack` packets that get no answer at all
+    pub loss: f64,
+    /// Fraction (0.0-1.0) of `want_ack` packets answered with a routing error
+    pub nak: f64,
+    /// Delay before a frame becomes readable
+    pub latency: Duration,
+    /// Fixed RNG seed for reproducible loss; random when absent
+    pub seed: Option<u64>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            nodes: 3,
+            loss: 0.0,
+            nak: 0.0,
+            latency: Duration::from_millis(50),
+            seed: None,
+        }
+    }
+}
+
+impl SimConfig {
+    /// Parse the part of the endpoint after `sim://`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut cfg = SimConfig::default();
+        let (name, query) = spec.split_once('?').unwrap_or((spec, ""));
+        let name = name.trim_matches('/');
+        if !name.is_empty() {
+            cfg.name = name.to_string();
+        }
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("sim:// parameter '{}' needs a value", pair))?;
+            let bad = |e: &dyn std::fmt::Display| anyhow!("sim:// {}={}: {}", key, value, e);
+            match key {
+                "nodes" => cfg.nodes = value.parse().map_err(|e| bad(&e))?,
+                "loss" => cfg.loss = value.parse().map_err(|e| bad(&e))?,
+                "nak" => cfg.nak = value.parse().map_err(|e| bad(&e))?,
+                "latency_ms" => {
+                    cfg.latency = Duration::from_millis(value.parse().map_err(|e| bad(&e))?)
+                }
+                "seed" => cfg.seed = Some(value.parse().map_err(|e| bad(&e))?),
+                _ => return Err(anyhow!("unknown sim:// parameter '{}'", key)),
+            }
+        }
+        if !(0.0..=1.0).contains(&cfg.loss) || !(0.0..=1.0).contains(&cfg.nak) {
+            return Err(anyhow!("sim:// loss and nak must be between 0 and 1"));
+        }
+        Ok(cfg)
+    }
+
+    /// Node numbers of the fake peers
+    pub fn peer_ids(&self) -> Vec<u32> {
+        (0..self.nodes).map(|i| SIM_FIRST_PEER + i).collect()
+    }
+}
+
+/// A text packet the BBS handed to the simulated radio
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentText {
+    pub id: u32,
+    pub to: u32,
+    pub channel: u32,
+    pub want_ack: bool,
+    pub text: String,
+}
+
+/// Counters for how `want_ack` packets were answered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimStats {
+    pub acked: u64,
+    pub naked: u64,
+    pub dropped: u64,
+}
+
+struct SimState {
+    config: SimConfig,
+    rng: StdRng,
+    /// Encoded frames waiting for their delivery time
+    outbound: VecDeque<(Instant, Vec<u8>)>,
+    /// Bytes of delivered frames not yet consumed by `read`
+    readable: VecDeque<u8>,
+    /// Bytes written by the BBS not yet parsed into frames
+    inbound: Vec<u8>,
+    sent: Vec<SentText>,
+    stats: SimStats,
+    next_packet_id: u32,
+    next_frame_id: u32,
+}
+
+impl SimState {
+    fn queue(&mut self, payload: proto::from_radio::PayloadVariant) {
+        self.next_frame_id = self.next_frame_id.wrapping_add(1);
+        let msg = proto::FromRadio {
+            id: self.next_frame_id,
+            payload_variant: Some(payload),
+        };
+        let body = msg.encode_to_vec();
+        let mut frame = Vec::with_capacity(body.len() + 4);
+        frame.extend_from_slice(&[0x94, 0xC3, (body.len() >> 8) as u8, body.len() as u8]);
+        frame.extend_from_slice(&body);
+        let due = Instant::now() + self.config.latency;
+        self.outbound.push_back((due, frame));
+    }
+
+    fn queue_packet(&mut self, packet: proto::MeshPacket) {
+        self.queue(proto::from_radio::PayloadVariant::Packet(packet));
+    }
+
+    fn decoded_packet(
+        &mut self,
+        from: u32,
+        to: u32,
+        channel: u32,
+        data: proto::Data,
+    ) -> proto::MeshPacket {
+        self.next_packet_id = self.next_packet_id.wrapping_add(1).max(1);
+        proto::MeshPacket {
+            from,
+            to,
+            channel,
+            id: self.next_packet_id,
+            rx_snr: 6.0,
+            rx_rssi: -80,
+            hop_limit: 3,
+            hop_start: 3,
+            payload_variant: Some(proto::mesh_packet::PayloadVariant::Decoded(data)),
+            ..Default::default()
+        }
+    }
+
+    fn send_config(&mut self, request_id: u32) {
+        use proto::from_radio::PayloadVariant as FR;
+        self.queue(FR::MyInfo(proto::MyNodeInfo {
+            my_node_num: SIM_NODE_NUM,
+            ..Default::default()
+        }));
+        for (i, num) in self.config.peer_ids().into_iter().enumerate() {
+            self.queue(FR::NodeInfo(proto::NodeInfo {
+                num,
+                user: Some(proto::User {
+                    id: format!("!{:08x}", num),
+                    long_name: format!("Sim Node {}", i + 1),
+                    short_name: format!("SIM{}", i + 1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }));
+        }
+        self.queue(FR::ConfigCompleteId(request_id));
+    }
+
+    /// Answer a `want_ack` packet with an ACK, a NAK or nothing at all
+    fn route(&mut self, packet: &proto::MeshPacket) {
+        use proto::routing::{Error as RErr, Variant as RVar};
+        if self.rng.gen_bool(self.config.loss) {
+            self.stats.dropped += 1;
+            return;
+        }
+        let (from, reason) = if self.rng.gen_bool(self.config.nak) {
+            self.stats.naked += 1;
+            (SIM_NODE_NUM, RErr::MaxRetransmit)
+        } else {
+            self.stats.acked += 1;
+            let from = if packet.to == u32::MAX {
+                SIM_NODE_NUM
+            } else {
+                packet.to
+            };
+            (from, RErr::None)
+        };
+        let routing = proto::Routing {
+            variant: Some(RVar::ErrorReason(reason as i32)),
+        };
+        let data = proto::Data {
+            portnum: proto::PortNum::RoutingApp as i32,
+            payload: routing.encode_to_vec().into(),
+            request_id: packet.id,
+            ..Default::default()
+        };
+        let mut reply = self.decoded_packet(from, SIM_NODE_NUM, packet.channel, data);
+        reply.priority = PRIORITY_ACK;
+        self.queue_packet(reply);
+    }
+
+    fn handle_to_radio(&mut self, frame: &[u8]) {
+        use proto::mesh_packet::PayloadVariant as MP;
+        use proto::to_radio::PayloadVariant as TR;
+        let Ok(msg) = proto::ToRadio::decode(frame) else {
+            return;
+        };
+        match msg.payload_variant {
+            Some(TR::WantConfigId(id)) => self.send_config(id),
+            Some(TR::Packet(packet)) => {
+                if let Some(MP::Decoded(data)) = &packet.payload_variant {
+                    if data.portnum == proto::PortNum::TextMessageApp as i32 {
+                        self.sent.push(SentText {
+                            id: packet.id,
+                            to: packet.to,
+                            channel: packet.channel,
+                            want_ack: packet.want_ack,
+                            text: String::from_utf8_lossy(&data.payload).into_owned(),
+                        });
+                    }
+                }
+                if packet.want_ack && packet.id != 0 {
+                    self.route(&packet);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Split `0x94 0xC3` framed ToRadio messages out of the bytes written so far
+    fn drain_inbound(&mut self) {
+        loop {
+            match self.inbound.iter().position(|&b| b == 0x94) {
+                Some(0) => {}
+                Some(pos) => {
+                    self.inbound.drain(..pos);
+                }
+                None => {
+                    self.inbound.clear();
+                    return;
+                }
+            }
+            if self.inbound.len() < 4 {
+                return;
+            }
+            if self.inbound[1] != 0xC3 {
+                self.inbound.drain(..1);
+                continue;
+            }
+            let len = ((self.inbound[2] as usize) << 8) | self.inbound[3] as usize;
+            if self.inbound.len() < 4 + len {
+                return;
+            }
+            let frame: Vec<u8> = self.inbound.drain(..4 + len).skip(4).collect();
+            self.handle_to_radio(&frame);
+        }
+    }
+}
+
+/// Handle on a running simulator for injecting traffic and inspecting what was sent
+#[derive(Clone)]
+pub struct SimHandle(Arc<Mutex<SimState>>);
+
+impl SimHandle {
+    fn new(config: SimConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        SimHandle(Arc::new(Mutex::new(SimState {
+            config,
+            rng,
+            outbound: VecDeque::new(),
+            readable: VecDeque::new(),
+            inbound: Vec::new(),
+            sent: Vec::new(),
+            stats: SimStats::default(),
+            next_packet_id: 0,
+            next_frame_id: 0,
+        })))
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, SimState> {
+        self.0.lock().unwrap()
+    }
+
+    pub fn config(&self) -> SimConfig {
+        self.state().config.clone()
+    }
+
+    /// Node numbers of the fake peers
+    pub fn peer_ids(&self) -> Vec<u32> {
+        self.state().config.peer_ids()
+    }
+
+    /// Deliver a TEXT_MESSAGE_APP packet from `from`; `to` of `None` is a channel broadcast
+    pub fn inject_text(&self, from: u32, to: Option<u32>, channel: u32, text: &str) {
+        let mut state = self.state();
+        let data = proto::Data {
+            portnum: proto::PortNum::TextMessageApp as i32,
+            payload: text.as_bytes().to_vec().into(),
+            ..Default::default()
+        };
+        let packet = state.decoded_packet(from, to.unwrap_or(u32::MAX), channel, data);
+        state.queue_packet(packet);
+    }
+
+    /// Text packets the BBS has transmitted, oldest first (retries appear again)
+    pub fn sent(&self) -> Vec<SentText> {
+        self.state().sent.clone()
+    }
+
+    pub fn stats(&self) -> SimStats {
+        self.state().stats
+    }
+}
+
+/// Read/Write end handed to the reader and writer tasks
+pub struct SimTransport(SimHandle);
+
+impl Read for SimTransport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut state = self.0.state();
+        let now = Instant::now();
+        while state.outbound.front().is_some_and(|(due, _)| *due <= now) {
+            let (_, frame) = state.outbound.pop_front().unwrap();
+            state.readable.extend(frame);
+        }
+        if state.readable.is_empty() {
+            // Same shape as an idle serial port so the reader treats it as "no data"
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "sim radio idle",
+            ));
+        }
+        let n = buf.len().min(state.readable.len());
+        for (slot, byte) in buf.iter_mut().zip(state.readable.drain(..n)) {
+            *slot = byte;
+        }
+        Ok(n)
+    }
+}
+
+impl Write for SimTransport {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self.0.state();
+        state.inbound.extend_from_slice(buf);
+        state.drain_inbound();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, SimHandle>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, SimHandle>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Simulator most recently started under `name`
+pub fn handle(name: &str) -> Option<SimHandle> {
+    registry().lock().unwrap().get(name).cloned()
+}
+
+/// Start a simulator for the part of a `sim://` endpoint after the scheme
+pub(super) fn create_shared_sim_transport(spec: &str) -> Result<SharedTransport> {
+    let config = SimConfig::parse(spec)?;
+    log::info!(
+        "Starting simulated radio '{}' ({} nodes, loss={}, nak={}, latency={}ms)",
+        config.name,
+        config.nodes,
+        config.loss,
+        config.nak,
+        config.latency.as_millis()
+    );
+    let name = config.name.clone();
+    let sim = SimHandle::new(config);
+    registry().lock().unwrap().insert(name, sim.clone());
+    Ok(Arc::new(Mutex::new(Box::new(SimTransport(sim)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(msg: &proto::ToRadio) -> Vec<u8> {
+        let body = msg.encode_to_vec();
+        let mut out = vec![0x94, 0xC3, (body.len() >> 8) as u8, body.len() as u8];
+        out.extend_from_slice(&body);
+        out
+    }
+
+    fn read_all(t: &mut SimTransport) -> Vec<proto::FromRadio> {
+        let mut bytes = Vec::new();
+        let mut buf = [0u8; 64];
+        while let Ok(n) = t.read(&mut buf) {
+            bytes.extend_from_slice(&buf[..n]);
+        }
+        let mut out = Vec::new();
+        while bytes.len() >= 4 {
+            let len = ((bytes[2] as usize) << 8) | bytes[3] as usize;
+            out.push(proto::FromRadio::decode(&bytes[4..4 + len]).unwrap());
+            bytes.drain(..4 + len);
+        }
+        out
+    }
+
+    #[test]
+    fn parses_endpoint_parameters() {
+        let cfg = SimConfig::parse("ci?nodes=5&loss=0.25&latency_ms=0&seed=9").unwrap();
+        assert_eq!(cfg.name, "ci");
+        assert_eq!(cfg.nodes, 5);
+        assert_eq!(cfg.loss, 0.25);
+        assert_eq!(cfg.latency, Duration::ZERO);
+        assert_eq!(cfg.seed, Some(9));
+        assert_eq!(SimConfig::parse("").unwrap(), SimConfig::default());
+        assert!(SimConfig::parse("x?loss=2").is_err());
+        assert!(SimConfig::parse("x?bogus=1").is_err());
+    }
+
+    #[test]
+    fn answers_config_and_acks_text() {
+        use proto::from_radio::PayloadVariant as FR;
+        let sim = SimHandle::new(SimConfig::parse("unit?nodes=2&latency_ms=0").unwrap());
+        let mut t = SimTransport(sim.clone());
+        let want = proto::ToRadio {
+            payload_variant: Some(proto::to_radio::PayloadVariant::WantConfigId(77)),
+        };
+        t.write_all(&frame(&want)).unwrap();
+        let frames = read_all(&mut t);
+        assert!(matches!(
+            frames[0].payload_variant,
+            Some(FR::MyInfo(ref i)) if i.my_node_num == SIM_NODE_NUM
+        ));
+        assert_eq!(frames.len(), 4);
+        assert!(matches!(
+            frames[3].payload_variant,
+            Some(FR::ConfigCompleteId(77))
+        ));
+
+        let dm = proto::ToRadio {
+            payload_variant: Some(proto::to_radio::PayloadVariant::Packet(proto::MeshPacket {
+                from: SIM_NODE_NUM,
+                to: SIM_FIRST_PEER,
+                id: 4242,
+                want_ack: true,
+                payload_variant: Some(proto::mesh_packet::PayloadVariant::Decoded(proto::Data {
+                    portnum: proto::PortNum::TextMessageApp as i32,
+                    payload: b"hello".to_vec().into(),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            })),
+        };
+        t.write_all(&frame(&dm)).unwrap();
+        assert_eq!(sim.sent()[0].text, "hello");
+        let frames = read_all(&mut t);
+        let Some(FR::Packet(ack)) = &frames[0].payload_variant else {
+            panic!("expected routing packet");
+        };
+        assert_eq!(ack.from, SIM_FIRST_PEER);
+        let Some(proto::mesh_packet::PayloadVariant::Decoded(data)) = &ack.payload_variant else {
+            panic!("expected decoded payload");
+        };
+        assert_eq!(data.request_id, 4242);
+        assert_eq!(sim.stats().acked, 1);
+    }
+}
@@ -0,0 +1,171 @@
+//! Loopback-only HTTP listener for `/metrics` (Prometheus text) and `/status` (JSON)
+//!
+//! This is deliberately tiny: one request per connection, GET only, no keep-alive.
+//! Binding anywhere other than a loopback address is refused so the endpoint is
+//! never exposed off the host by accident; put a reverse proxy in front if needed.
+use super::{current_status, render_prometheus, StatusSnapshot};
+use anyhow::{anyhow, Context, Result};
+use log::{debug, warn};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpListener, TcpStream};
+use tokio::time::timeout;
+
+/// Largest request head accepted; anything bigger is answered with 400
+const MAX_REQUEST_BYTES: usize = 4096;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Bind the listener, refusing addresses that are not loopback
+pub async fn bind(addr: &str) -> Result<TcpListener> {
+    let resolved: Vec<_> = lookup_host(addr)
+        .await
+        .with_context(|| format!("Invalid metrics bind address '{}'", addr))?
+        .collect();
+    let target = resolved
+        .iter()
+        .find(|a| a.ip().is_loopback())
+        .copied()
+        .ok_or_else(|| anyhow!("Metrics bind address '{}' is not loopback", addr))?;
+    TcpListener::bind(target)
+        .await
+        .with_context(|| format!("Failed to bind metrics listener on {}", target))
+}
+
+/// Accept connections until the task is dropped
+pub async fn serve(listener: TcpListener) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tokio::spawn(async move {
+                    if let Err(e) = handle(stream).await {
+                        debug!("metrics request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => {
+                warn!("metrics listener accept failed: {}", e);
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream) -> Result<()> {
+    let mut buf = Vec::with_capacity(512);
+    let mut chunk = [0u8; 512];
+    let head_complete = |b: &[u8]| b.windows(4).any(|w| w == b"\r\n\r\n");
+    while !head_complete(&buf) && buf.len() <= MAX_REQUEST_BYTES {
+        let n = timeout(READ_TIMEOUT, stream.read(&mut chunk)).await??;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    let (status, content_type, body) = if buf.len() > MAX_REQUEST_BYTES {
+        (400, "text/plain", "Request too large\n".to_string())
+    } else {
+        let request = String::from_utf8_lossy(&buf);
+        let line = request.lines().next().unwrap_or("");
+        let mut parts = line.split_whitespace();
+        route(parts.next().unwrap_or(""), parts.next().unwrap_or(""))
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Status code, content type and body for a request line
+pub fn route(method: &str, target: &str) -> (u16, &'static str, String) {
+    let path = target.split('?').next().unwrap_or("");
+    match (method, path) {
+        ("GET", "/metrics") => (
+            200,
+            PROMETHEUS_CONTENT_TYPE,
+            render_prometheus(&current_status()),
+        ),
+        ("GET", "/status") => match serde_json::to_string_pretty(&current_status()) {
+            Ok(json) => (200, "application/json", json + "\n"),
+            Err(e) => (500, "text/plain", format!("{}\n", e)),
+        },
+        (_, "/metrics" | "/status") => (405, "text/plain", "Method not allowed\n".into()),
+        _ => (404, "text/plain", "Not found\n".into()),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Fetch `/status` from a running daemon listening on `addr`
+pub async fn fetch_status(addr: &str, wait: Duration) -> Result<StatusSnapshot> {
+    let body = fetch(addr, "/status", wait).await?;
+    serde_json::from_str(&body).context("Invalid /status response")
+}
+
+/// GET `path` from `addr` and return the body of a 200 response
+pub async fn fetch(addr: &str, path: &str, wait: Duration) -> Result<String> {
+    let exchange = async {
+        let mut stream = TcpStream::connect(addr).await?;
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+            path, addr
+        );
+        stream.write_all(request.as_bytes()).await?;
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).await?;
+        Ok::<_, std::io::Error>(raw)
+    };
+    let raw = timeout(wait, exchange)
+        .await
+        .map_err(|_| anyhow!("Timed out contacting {}", addr))?
+        .with_context(|| format!("Could not reach {}", addr))?;
+    let text = String::from_utf8_lossy(&raw);
+    let (head, body) = text
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("Malformed response from {}", addr))?;
+    let status_line = head.lines().next().unwrap_or("");
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(anyhow!("{} returned '{}'", path, status_line));
+    }
+    Ok(body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_get_on_known_paths_is_served() {
+        let (status, content_type, body) = route("GET", "/metrics");
+        assert_eq!(status, 200);
+        assert_eq!(content_type, PROMETHEUS_CONTENT_TYPE);
+        assert!(body.contains("# TYPE meshbbs_reliable_sent_total counter\n"));
+        let (status, _, body) = route("GET", "/status?pretty=1");
+        assert_eq!(status, 200);
+        assert!(serde_json::from_str::<StatusSnapshot>(&body).is_ok());
+        assert_eq!(route("POST", "/status").0, 405);
+        assert_eq!(route("GET", "/").0, 404);
+    }
+
+    #[tokio::test]
+    async fn non_loopback_addresses_are_refused() {
+        let err = bind("0.0.0.0:0").await.unwrap_err().to_string();
+        assert!(err.contains("is not loopback"), "{}", err);
+        assert!(bind("127.0.0.1:0").await.is_ok());
+    }
+}
@@ -0,0 +1,593 @@
+//! Process-wide counters for reliable sends/ACKs, games and commands, plus the
+//! status the server publishes for the optional HTTP listener ([`http`]).
+//!
+//! Counters are plain atomics or mutex-guarded maps so any task can bump them.
+//! The server refreshes a [`StatusSnapshot`] on its housekeeping tick; readers
+//! get it merged with the live counters from [`current_status`].
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+pub mod http;
+
+static RELIABLE_SENT: AtomicU64 = AtomicU64::new(0);
+static RELIABLE_ACKED: AtomicU64 = AtomicU64::new(0);
+static RELIABLE_FAILED: AtomicU64 = AtomicU64::new(0);
+static RELIABLE_RETRIES: AtomicU64 = AtomicU64::new(0);
+static ACK_LATENCY_SUM_MS: AtomicU64 = AtomicU64::new(0);
+static ACK_LATENCY_COUNT: AtomicU64 = AtomicU64::new(0);
+static BROADCAST_ACK_CONFIRMED: AtomicU64 = AtomicU64::new(0);
+static BROADCAST_ACK_EXPIRED: AtomicU64 = AtomicU64::new(0);
+
+static GAME_COUNTERS: OnceLock<Mutex<HashMap<String, GameCounter>>> = OnceLock::new();
+static COMMAND_COUNTERS: OnceLock<Mutex<BTreeMap<String, BTreeMap<String, u64>>>> = OnceLock::new();
+static PUBLISHED_STATUS: OnceLock<Mutex<StatusSnapshot>> = OnceLock::new();
+
+/// Distinct command labels kept per channel; later ones are counted as `OTHER`
+const MAX_COMMAND_LABELS: usize = 128;
+
+#[allow(dead_code)]
+pub fn inc_reliable_sent() {
+    RELIABLE_SENT.fetch_add(1, Ordering::Relaxed);
+}
+#[allow(dead_code)]
+pub fn inc_reliable_acked() {
+    RELIABLE_ACKED.fetch_add(1, Ordering::Relaxed);
+}
+#[allow(dead_code)]
+pub fn inc_reliable_failed() {
+    RELIABLE_FAILED.fetch_add(1, Ordering::Relaxed);
+}
+#[allow(dead_code)]
+pub fn inc_reliable_retries() {
+    RELIABLE_RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+#[allow(dead_code)]
+pub fn observe_ack_latency(sent_at: Instant) {
+    let ms = sent_at.elapsed().as_millis() as u64;
+    ACK_LATENCY_SUM_MS.fetch_add(ms, Ordering::Relaxed);
+    ACK_LATENCY_COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+#[allow(dead_code)]
+pub fn inc_broadcast_ack_confirmed() {
+    BROADCAST_ACK_CONFIRMED.fetch_add(1, Ordering::Relaxed);
+}
+#[allow(dead_code)]
+pub fn inc_broadcast_ack_expired() {
+    BROADCAST_ACK_EXPIRED.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameCounter {
+    pub entries: u64,
+    pub exits: u64,
+    pub currently_active: u64,
+    pub concurrent_peak: u64,
+}
+
+fn game_counter_lock() -> &'static Mutex<HashMap<String, GameCounter>> {
+    GAME_COUNTERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn record_game_entry(slug: &str) -> GameCounter {
+    let mut guard = game_counter_lock()
+        .lock()
+        .expect("game counter mutex poisoned");
+    let counter = guard.entry(slug.to_string()).or_default();
+    counter.entries = counter.entries.saturating_add(1);
+    counter.currently_active = counter.currently_active.saturating_add(1);
+    if counter.currently_active > counter.concurrent_peak {
+        counter.concurrent_peak = counter.currently_active;
+    }
+    *counter
+}
+
+pub fn record_game_exit(slug: &str) -> GameCounter {
+    let mut guard = game_counter_lock()
+        .lock()
+        .expect("game counter mutex poisoned");
+    let counter = guard.entry(slug.to_string()).or_default();
+    counter.exits = counter.exits.saturating_add(1);
+    if counter.currently_active > 0 {
+        counter.currently_active -= 1;
+    }
+    *counter
+}
+
+pub fn game_counters_snapshot() -> HashMap<String, GameCounter> {
+    game_counter_lock()
+        .lock()
+        .expect("game counter mutex poisoned")
+        .clone()
+}
+
+#[cfg(test)]
+pub(crate) fn reset_game_counters_for_tests() {
+    if let Some(lock) = GAME_COUNTERS.get() {
+        let mut guard = lock.lock().expect("game counter mutex poisoned");
+        guard.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_entry_exit_updates_counters() {
+        reset_game_counters_for_tests();
+        assert!(game_counters_snapshot().is_empty());
+
+        let entry_stats = record_game_entry("tinyhack");
+        assert_eq!(entry_stats.entries, 1);
+        assert_eq!(entry_stats.currently_active, 1);
+        assert_eq!(entry_stats.concurrent_peak, 1);
+        assert_eq!(entry_stats.exits, 0);
+
+        let mid_snapshot = game_counters_snapshot();
+        let tinyhack = mid_snapshot.get("tinyhack").expect("tinyhack counter");
+        assert_eq!(tinyhack.entries, 1);
+        assert_eq!(tinyhack.currently_active, 1);
+        assert_eq!(tinyhack.concurrent_peak, 1);
+
+        let exit_stats = record_game_exit("tinyhack");
+        assert_eq!(exit_stats.exits, 1);
+        assert_eq!(exit_stats.currently_active, 0);
+        assert_eq!(exit_stats.concurrent_peak, 1);
+
+        let final_snapshot = game_counters_snapshot();
+        let tinyhack_final = final_snapshot.get("tinyhack").expect("tinyhack counter");
+        assert_eq!(tinyhack_final.entries, 1);
+        assert_eq!(tinyhack_final.exits, 1);
+        assert_eq!(tinyhack_final.currently_active, 0);
+    }
+
+    #[test]
+    fn commands_are_capped_and_rendered_with_escaped_labels() {
+        for i in 0..MAX_COMMAND_LABELS + 3 {
+            inc_command("test-cap", &format!("CMD{}", i));
+        }
+        inc_command("test-cap", "CMD0");
+        let counts = command_counts_snapshot();
+        let cap = counts.get("test-cap").expect("channel counted");
+        assert_eq!(cap.len(), MAX_COMMAND_LABELS + 1);
+        assert_eq!(cap.get("CMD0"), Some(&2));
+        assert_eq!(cap.get("OTHER"), Some(&3));
+
+        let mut status = StatusSnapshot {
+            bbs_name: "Club \"North\" BBS".into(),
+            version: "1.0".into(),
+            sessions_active: 2,
+            scheduler: Some(SchedulerGauges {
+                queued: 4,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        status.storage_bytes.insert("messages".into(), 2048);
+        status.reliable.ack_latency_sum_ms = 1500;
+        status.reliable.ack_latency_count = 2;
+        let text = render_prometheus(&status);
+        assert!(text.contains("meshbbs_info{name=\"Club \\\"North\\\" BBS\",version=\"1.0\"} 1\n"));
+        assert!(text.contains("meshbbs_sessions{state=\"active\"} 2\n"));
+        assert!(text.contains(
+            "# TYPE meshbbs_scheduler_queue_depth gauge\nmeshbbs_scheduler_queue_depth 4\n"
+        ));
+        assert!(text.contains(
+            "meshbbs_ack_latency_seconds_sum 1.5\nmeshbbs_ack_latency_seconds_count 2\n"
+        ));
+        assert!(text.contains("meshbbs_storage_bytes{area=\"messages\"} 2048\n"));
+        assert!(!text.contains("meshbbs_last_backup_bytes"));
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub reliable_sent: u64,
+    pub reliable_acked: u64,
+    pub reliable_failed: u64,
+    pub reliable_retries: u64,
+    pub ack_latency_avg_ms: Option<u64>,
+    #[serde(default)]
+    pub ack_latency_sum_ms: u64,
+    #[serde(default)]
+    pub ack_latency_count: u64,
+    pub broadcast_ack_confirmed: u64,
+    pub broadcast_ack_expired: u64,
+}
+
+pub fn snapshot() -> Snapshot {
+    let sent = RELIABLE_SENT.load(Ordering::Relaxed);
+    let acked = RELIABLE_ACKED.load(Ordering::Relaxed);
+    let failed = RELIABLE_FAILED.load(Ordering::Relaxed);
+    let retries = RELIABLE_RETRIES.load(Ordering::Relaxed);
+    let sum = ACK_LATENCY_SUM_MS.load(Ordering::Relaxed);
+    let count = ACK_LATENCY_COUNT.load(Ordering::Relaxed);
+    let bcast_ok = BROADCAST_ACK_CONFIRMED.load(Ordering::Relaxed);
+    let bcast_exp = BROADCAST_ACK_EXPIRED.load(Ordering::Relaxed);
+    Snapshot {
+        reliable_sent: sent,
+        reliable_acked: acked,
+        reliable_failed: failed,
+        reliable_retries: retries,
+        ack_latency_avg_ms: if count > 0 { Some(sum / count) } else { None },
+        ack_latency_sum_ms: sum,
+        ack_latency_count: count,
+        broadcast_ack_confirmed: bcast_ok,
+        broadcast_ack_expired: bcast_exp,
+    }
+}
+
+fn command_counter_lock() -> &'static Mutex<BTreeMap<String, BTreeMap<String, u64>>> {
+    COMMAND_COUNTERS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// Count one handled command. `channel` is `dm` or `public`; `command` should be a
+/// normalized verb (no arguments) so label cardinality stays bounded.
+pub fn inc_command(channel: &str, command: &str) {
+    let mut guard = command_counter_lock()
+        .lock()
+        .expect("command counter mutex poisoned");
+    let per_channel = guard.entry(channel.to_string()).or_default();
+    let key = if per_channel.contains_key(command) || per_channel.len() < MAX_COMMAND_LABELS {
+        command
+    } else {
+        "OTHER"
+    };
+    *per_channel.entry(key.to_string()).or_default() += 1;
+}
+
+/// Command counts by channel, then command
+pub fn command_counts_snapshot() -> BTreeMap<String, BTreeMap<String, u64>> {
+    command_counter_lock()
+        .lock()
+        .expect("command counter mutex poisoned")
+        .clone()
+}
+
+/// Outbound scheduler gauges, copied from `bbs::dispatch::SchedulerStats`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SchedulerGauges {
+    pub queued: u64,
+    pub dispatched_total: u64,
+    pub dropped_total: u64,
+    pub dropped_overflow: u64,
+    pub escalations: u64,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BackupStatus {
+    pub enabled: bool,
+    /// Scheduler frequency as configured (`daily`, `hourly`, ...)
+    pub frequency: String,
+    /// Backup archives currently on disk
+    pub count: u64,
+    pub last_backup_at: Option<DateTime<Utc>>,
+    pub last_backup_bytes: Option<u64>,
+}
+
+/// Everything `/status` reports. The server fills in the state it owns via
+/// [`publish_status`]; [`current_status`] adds the live process counters.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub bbs_name: String,
+    pub version: String,
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the server last published this snapshot
+    pub updated_at: Option<DateTime<Utc>>,
+    pub uptime_secs: u64,
+    pub device_connected: bool,
+    pub sessions_active: u64,
+    pub sessions_logged_in: u64,
+    /// Absent when the build has no outbound scheduler
+    pub scheduler: Option<SchedulerGauges>,
+    /// Bytes on disk per data directory area (`messages`, `users`, `files`, ...)
+    pub storage_bytes: BTreeMap<String, u64>,
+    pub total_users: u64,
+    pub total_messages: u64,
+    pub backup: BackupStatus,
+    pub reliable: Snapshot,
+    pub commands: BTreeMap<String, BTreeMap<String, u64>>,
+    pub games: BTreeMap<String, GameCounter>,
+}
+
+/// Replace the server-owned part of the status snapshot
+pub fn publish_status(status: StatusSnapshot) {
+    let lock = PUBLISHED_STATUS.get_or_init(|| Mutex::new(StatusSnapshot::default()));
+    *lock.lock().expect("status mutex poisoned") = status;
+}
+
+/// Last published status merged with the current counters
+pub fn current_status() -> StatusSnapshot {
+    let mut status = PUBLISHED_STATUS
+        .get()
+        .map(|lock| lock.lock().expect("status mutex poisoned").clone())
+        .unwrap_or_default();
+    if let Some(started) = status.started_at {
+        status.uptime_secs = (Utc::now() - started).num_seconds().max(0) as u64;
+    }
+    status.reliable = snapshot();
+    status.commands = command_counts_snapshot();
+    status.games = game_counters_snapshot().into_iter().collect();
+    status
+}
+
+/// Render `status` in the Prometheus text exposition format (version 0.0.4)
+pub fn render_prometheus(status: &StatusSnapshot) -> String {
+    let mut out = String::new();
+    let bool_value = |b: bool| if b { 1 } else { 0 };
+
+    family(&mut out, "meshbbs_info", "gauge", "BBS name and version");
+    sample(
+        &mut out,
+        "meshbbs_info",
+        &[("name", &status.bbs_name), ("version", &status.version)],
+        1,
+    );
+    gauge(
+        &mut out,
+        "meshbbs_uptime_seconds",
+        "Seconds since the server started",
+        status.uptime_secs,
+    );
+    if let Some(updated) = status.updated_at {
+        gauge(
+            &mut out,
+            "meshbbs_status_updated_timestamp_seconds",
+            "Unix time of the last status refresh",
+            updated.timestamp(),
+        );
+    }
+    gauge(
+        &mut out,
+        "meshbbs_device_connected",
+        "1 when a Meshtastic device is connected",
+        bool_value(status.device_connected),
+    );
+
+    family(
+        &mut out,
+        "meshbbs_sessions",
+        "gauge",
+        "Open sessions by state",
+    );
+    sample(
+        &mut out,
+        "meshbbs_sessions",
+        &[("state", "active")],
+        status.sessions_active,
+    );
+    sample(
+        &mut out,
+        "meshbbs_sessions",
+        &[("state", "logged_in")],
+        status.sessions_logged_in,
+    );
+
+    if let Some(s) = &status.scheduler {
+        gauge(
+            &mut out,
+            "meshbbs_scheduler_queue_depth",
+            "Messages waiting in the outbound scheduler",
+            s.queued,
+        );
+        counter(
+            &mut out,
+            "meshbbs_scheduler_dispatched_total",
+            "Messages handed to the radio",
+            s.dispatched_total,
+        );
+        counter(
+            &mut out,
+            "meshbbs_scheduler_dropped_total",
+            "Messages dropped by the scheduler",
+            s.dropped_total,
+        );
+        counter(
+            &mut out,
+            "meshbbs_scheduler_dropped_overflow_total",
+            "Messages dropped because the queue was full",
+            s.dropped_overflow,
+        );
+        counter(
+            &mut out,
+            "meshbbs_scheduler_escalations_total",
+            "Messages escalated to a higher priority",
+            s.escalations,
+        );
+    }
+
+    let r = &status.reliable;
+    counter(
+        &mut out,
+        "meshbbs_reliable_sent_total",
+        "Reliable DMs sent",
+        r.reliable_sent,
+    );
+    counter(
+        &mut out,
+        "meshbbs_reliable_acked_total",
+        "Reliable DMs acknowledged",
+        r.reliable_acked,
+    );
+    counter(
+        &mut out,
+        "meshbbs_reliable_failed_total",
+        "Reliable DMs that were never acknowledged",
+        r.reliable_failed,
+    );
+    counter(
+        &mut out,
+        "meshbbs_reliable_retries_total",
+        "Reliable DM retransmissions",
+        r.reliable_retries,
+    );
+    family(
+        &mut out,
+        "meshbbs_ack_latency_seconds",
+        "summary",
+        "Time from send to ACK for reliable DMs",
+    );
+    sample(
+        &mut out,
+        "meshbbs_ack_latency_seconds_sum",
+        &[],
+        r.ack_latency_sum_ms as f64 / 1000.0,
+    );
+    sample(
+        &mut out,
+        "meshbbs_ack_latency_seconds_count",
+        &[],
+        r.ack_latency_count,
+    );
+    counter(
+        &mut out,
+        "meshbbs_broadcast_ack_confirmed_total",
+        "Broadcasts confirmed by at least one ACK",
+        r.broadcast_ack_confirmed,
+    );
+    counter(
+        &mut out,
+        "meshbbs_broadcast_ack_expired_total",
+        "Broadcasts whose ACK window expired",
+        r.broadcast_ack_expired,
+    );
+
+    family(
+        &mut out,
+        "meshbbs_commands_total",
+        "counter",
+        "Commands handled by channel",
+    );
+    for (channel, commands) in &status.commands {
+        for (command, count) in commands {
+            sample(
+                &mut out,
+                "meshbbs_commands_total",
+                &[("channel", channel), ("command", command)],
+                count,
+            );
+        }
+    }
+
+    family(
+        &mut out,
+        "meshbbs_game_entries_total",
+        "counter",
+        "Game sessions started",
+    );
+    for (game, c) in &status.games {
+        sample(
+            &mut out,
+            "meshbbs_game_entries_total",
+            &[("game", game)],
+            c.entries,
+        );
+    }
+    family(
+        &mut out,
+        "meshbbs_game_active",
+        "gauge",
+        "Players currently in a game",
+    );
+    for (game, c) in &status.games {
+        sample(
+            &mut out,
+            "meshbbs_game_active",
+            &[("game", game)],
+            c.currently_active,
+        );
+    }
+
+    family(
+        &mut out,
+        "meshbbs_storage_bytes",
+        "gauge",
+        "Bytes on disk per data area",
+    );
+    for (area, bytes) in &status.storage_bytes {
+        sample(&mut out, "meshbbs_storage_bytes", &[("area", area)], bytes);
+    }
+    gauge(
+        &mut out,
+        "meshbbs_users",
+        "Registered users",
+        status.total_users,
+    );
+    gauge(
+        &mut out,
+        "meshbbs_messages",
+        "Stored messages",
+        status.total_messages,
+    );
+
+    let b = &status.backup;
+    gauge(
+        &mut out,
+        "meshbbs_backup_enabled",
+        "1 when scheduled backups are enabled",
+        bool_value(b.enabled),
+    );
+    gauge(
+        &mut out,
+        "meshbbs_backups",
+        "Backup archives on disk",
+        b.count,
+    );
+    if let Some(at) = b.last_backup_at {
+        gauge(
+            &mut out,
+            "meshbbs_last_backup_timestamp_seconds",
+            "Unix time of the newest backup",
+            at.timestamp(),
+        );
+    }
+    if let Some(bytes) = b.last_backup_bytes {
+        gauge(
+            &mut out,
+            "meshbbs_last_backup_bytes",
+            "Size of the newest backup",
+            bytes,
+        );
+    }
+    out
+}
+
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let rendered: Vec<String> = labels
+            .iter()
+            .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+            .collect();
+        let _ = write!(out, "{{{}}}", rendered.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    family(out, name, "gauge", help);
+    sample(out, name, &[], value);
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    family(out, name, "counter", help);
+    sample(out, name, &[], value);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,116 @@
+//! Text file library: sysop-curated documents in `data/files/`
+//!
+//! Files are grouped into areas (`data/files/<area>/<name>.txt`), each with a
+//! minimum read level. `data/files/index.json` holds the area descriptions and
+//! per-file metadata, including how many times each file has been opened.
+//! Sysops add files with `meshbbs files add` or the `FILEADD` admin command;
+//! users browse them from the `[F]ile` main menu entry.
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Largest text file the library accepts, in bytes
+pub const MAX_LIBRARY_FILE_BYTES: u64 = 16_384;
+
+/// Largest `index.json` the library will load, in bytes
+pub const MAX_LIBRARY_INDEX_BYTES: u64 = 1_000_000;
+
+/// Every area and the files in it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileLibrary {
+    #[serde(default)]
+    pub areas: BTreeMap<String, FileArea>,
+}
+
+/// A category of files with its own read level
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileArea {
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub read_level: u8,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// Sorted by name
+    #[serde(default)]
+    pub files: Vec<LibraryFile>,
+}
+
+/// Metadata for one text file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryFile {
+    pub name: String,
+    pub size: u64,
+    pub uploaded_by: String,
+    pub uploaded_at: DateTime<Utc>,
+    /// Times the file has been opened from the Files menu
+    #[serde(default)]
+    pub downloads: u32,
+}
+
+impl FileLibrary {
+    /// Areas a user at `level` may read, in name order
+    pub fn visible_areas(&self, level: u8) -> Vec<(&String, &FileArea)> {
+        self.areas
+            .iter()
+            .filter(|(_, area)| level >= area.read_level)
+            .collect()
+    }
+}
+
+impl FileArea {
+    pub fn file(&self, name: &str) -> Option<&LibraryFile> {
+        self.files.iter().find(|f| f.name == name)
+    }
+
+    pub fn file_mut(&mut self, name: &str) -> Option<&mut LibraryFile> {
+        self.files.iter_mut().find(|f| f.name == name)
+    }
+}
+
+/// Location of `index.json`
+pub fn index_path(data_dir: &str) -> PathBuf {
+    Path::new(data_dir).join("files").join("index.json")
+}
+
+/// Location of a file's text; `area` and `name` must already be validated
+pub fn file_path(data_dir: &str, area: &str, name: &str) -> PathBuf {
+    Path::new(data_dir)
+        .join("files")
+        .join(area)
+        .join(format!("{}.txt", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(read_level: u8) -> FileArea {
+        FileArea {
+            description: String::new(),
+            read_level,
+            created_by: "sysop".to_string(),
+            created_at: Utc::now(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn areas_above_the_reader_level_are_hidden() {
+        let mut library = FileLibrary::default();
+        library.areas.insert("radio".to_string(), area(0));
+        library.areas.insert("emcomm".to_string(), area(5));
+        library.areas.insert("admin".to_string(), area(10));
+        let names = |level| {
+            library
+                .visible_areas(level)
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(1), vec!["radio"]);
+        assert_eq!(names(5), vec!["emcomm", "radio"]);
+        assert_eq!(names(10), vec!["admin", "emcomm", "radio"]);
+    }
+}
@@ -0,0 +1,621 @@
+//! Persistent message index
+//!
+//! The JSON files under `data/messages/<topic>/` stay the source of truth; this
+//! module keeps a sled database in `data/message_index/` so listing recent posts,
+//! unread counts and per-user post counts no longer parse every file.
+//!
+//! Trees:
+//!
+//! ```text
+//! posts   <topic>\0<ts:8><id>  → author        ordered newest-last per topic
+//! ids     <topic>\0<id>        → <ts:8>        reverse lookup for deletes
+//! users   <author>             → <count:8>     top-level posts per user
+//! topics  <topic>              → <count:8><high_water:8><dir_stamp:8>
+//! terms   <term>\0<topic>\0<id> → <weight:4>    inverted index for SEARCH
+//! docs    <topic>\0<id>        → terms, newline separated (for removal)
+//! sync    <topic>\0<message_id> → <id>         replication id lookup
+//! meta    "version"            → schema version
+//! ```
+//!
+//! Timestamps are stored as sign-flipped big-endian nanoseconds so byte order
+//! matches time order. `dir_stamp` records the topic directory mtime seen at the
+//! last reconcile; [`Storage`](super::Storage) compares it on each read and
+//! reconciles against the directory when files were added or removed behind
+//! its back (another process, a restored backup, a hand-copied file).
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use super::{Message, ReplyEntry};
+
+/// Bump when the key layout changes; a mismatch triggers a full rebuild on open
+const INDEX_VERSION: &[u8] = b"3";
+
+/// Directory (under the data dir) holding the sled database
+pub const INDEX_DIR: &str = "message_index";
+
+/// Databases already open in this process, shared between `Storage` instances
+/// on the same data directory (sled allows one open handle per path).
+static OPEN_INDEXES: OnceLock<Mutex<HashMap<PathBuf, Weak<sled::Db>>>> = OnceLock::new();
+
+/// Shortest and longest terms kept in the inverted index
+const MIN_TERM_LEN: usize = 2;
+const MAX_TERM_LEN: usize = 24;
+
+/// Common words not worth indexing
+const STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "in", "is",
+    "it", "of", "on", "or", "so", "that", "the", "this", "to", "was", "we", "with", "you",
+];
+
+/// One search match: `(topic, id, score, timestamp)`
+pub type SearchRow = (String, String, u32, DateTime<Utc>);
+
+/// Split text into lowercase search terms
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= MIN_TERM_LEN)
+        .map(|w| {
+            w.to_lowercase()
+                .chars()
+                .take(MAX_TERM_LEN)
+                .collect::<String>()
+        })
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Term weights for one post: title words count triple, body and reply words once
+/// per occurrence
+pub fn term_weights(message: &Message) -> HashMap<String, u32> {
+    let mut weights: HashMap<String, u32> = HashMap::new();
+    let title = message
+        .title
+        .as_deref()
+        .unwrap_or_else(|| message.content.lines().next().unwrap_or(""));
+    for term in tokenize(title) {
+        *weights.entry(term).or_default() += 3;
+    }
+    for term in tokenize(&message.content) {
+        *weights.entry(term).or_default() += 1;
+    }
+    for reply in &message.replies {
+        let text = match reply {
+            ReplyEntry::Reply(r) => r.content.as_str(),
+            ReplyEntry::Legacy(s) => s.as_str(),
+        };
+        for term in tokenize(text) {
+            *weights.entry(term).or_default() += 1;
+        }
+    }
+    weights
+}
+
+/// Score a post against query terms. Every query term must match a post term
+/// exactly or as a prefix; exact matches count double. `None` when any term misses.
+pub fn score_terms<'a>(
+    doc: impl Iterator<Item = (&'a str, u32)> + Clone,
+    query: &[String],
+) -> Option<u32> {
+    let mut total = 0;
+    for q in query {
+        let hit: u32 = doc
+            .clone()
+            .filter(|(t, _)| t.starts_with(q.as_str()))
+            .map(|(t, w)| if t == q { w * 2 } else { w })
+            .sum();
+        if hit == 0 {
+            return None;
+        }
+        total += hit;
+    }
+    Some(total)
+}
+
+/// Per-topic summary kept alongside the post keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TopicMark {
+    pub count: u64,
+    pub high_water: Option<DateTime<Utc>>,
+    dir_stamp: u64,
+}
+
+impl TopicMark {
+    fn decode(bytes: &[u8]) -> Self {
+        let word = |i: usize| {
+            bytes
+                .get(i * 8..i * 8 + 8)
+                .and_then(|b| b.try_into().ok())
+                .map(u64::from_be_bytes)
+                .unwrap_or(0)
+        };
+        Self {
+            count: word(0),
+            high_water: match word(1) {
+                0 => None,
+                hw => Some(decode_ts(hw)),
+            },
+            dir_stamp: word(2),
+        }
+    }
+
+    fn encode(&self) -> [u8; 24] {
+        let mut out = [0u8; 24];
+        out[..8].copy_from_slice(&self.count.to_be_bytes());
+        let hw = self.high_water.map(|t| ts_word(&t)).unwrap_or(0);
+        out[8..16].copy_from_slice(&hw.to_be_bytes());
+        out[16..].copy_from_slice(&self.dir_stamp.to_be_bytes());
+        out
+    }
+}
+
+fn ts_word(ts: &DateTime<Utc>) -> u64 {
+    let nanos = ts
+        .timestamp_nanos_opt()
+        .unwrap_or_else(|| ts.timestamp_micros().saturating_mul(1000));
+    (nanos as u64) ^ (1 << 63)
+}
+
+fn decode_ts(word: u64) -> DateTime<Utc> {
+    Utc.timestamp_nanos((word ^ (1 << 63)) as i64)
+}
+
+fn topic_prefix(topic: &str) -> Vec<u8> {
+    let mut key = topic.as_bytes().to_vec();
+    key.push(0);
+    key
+}
+
+fn post_key(topic: &str, ts: &DateTime<Utc>, id: &str) -> Vec<u8> {
+    let mut key = topic_prefix(topic);
+    key.extend_from_slice(&ts_word(ts).to_be_bytes());
+    key.extend_from_slice(id.as_bytes());
+    key
+}
+
+fn id_key(topic: &str, id: &str) -> Vec<u8> {
+    let mut key = topic_prefix(topic);
+    key.extend_from_slice(id.as_bytes());
+    key
+}
+
+fn read_u64(bytes: Option<sled::IVec>) -> u64 {
+    bytes
+        .and_then(|b| b.as_ref().try_into().ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(0)
+}
+
+fn read_u64_be32(bytes: &[u8]) -> u32 {
+    bytes.try_into().map(u32::from_be_bytes).unwrap_or(0)
+}
+
+fn tx_err(e: TransactionError<()>) -> anyhow::Error {
+    match e {
+        TransactionError::Abort(()) => anyhow!("message index transaction aborted"),
+        TransactionError::Storage(e) => anyhow!("message index error: {}", e),
+    }
+}
+
+/// Handle to the sled message index for one data directory
+pub struct MessageIndex {
+    _db: Arc<sled::Db>,
+    posts: sled::Tree,
+    ids: sled::Tree,
+    users: sled::Tree,
+    topics: sled::Tree,
+    terms: sled::Tree,
+    docs: sled::Tree,
+    sync: sled::Tree,
+    meta: sled::Tree,
+}
+
+impl MessageIndex {
+    /// Open (or create) the index under `data_dir`. Fails if another process holds it.
+    pub fn open(data_dir: &str) -> Result<Self> {
+        let path = Path::new(data_dir).join(INDEX_DIR);
+        std::fs::create_dir_all(&path)?;
+        let path = path.canonicalize()?;
+
+        let registry = OPEN_INDEXES.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut open = registry
+            .lock()
+            .map_err(|_| anyhow!("message index registry poisoned"))?;
+        let db = match open.get(&path).and_then(Weak::upgrade) {
+            Some(db) => db,
+            None => {
+                let db = Arc::new(sled::open(&path)?);
+                open.insert(path, Arc::downgrade(&db));
+                db
+            }
+        };
+        open.retain(|_, weak| weak.strong_count() > 0);
+
+        Ok(Self {
+            posts: db.open_tree("posts")?,
+            ids: db.open_tree("ids")?,
+            users: db.open_tree("users")?,
+            topics: db.open_tree("topics")?,
+            terms: db.open_tree("terms")?,
+            docs: db.open_tree("docs")?,
+            sync: db.open_tree("sync")?,
+            meta: db.open_tree("meta")?,
+            _db: db,
+        })
+    }
+
+    /// Whether the index was built with the current key layout
+    pub fn is_current(&self) -> Result<bool> {
+        Ok(self.meta.get("version")?.as_deref() == Some(INDEX_VERSION))
+    }
+
+    /// Drop every entry (start of a rebuild)
+    pub fn clear(&self) -> Result<()> {
+        self.meta.remove("version")?;
+        for tree in [
+            &self.posts,
+            &self.ids,
+            &self.users,
+            &self.topics,
+            &self.terms,
+            &self.docs,
+            &self.sync,
+        ] {
+            tree.clear()?;
+        }
+        Ok(())
+    }
+
+    /// Record that a full build completed
+    pub fn mark_current(&self) -> Result<()> {
+        self.meta.insert("version", INDEX_VERSION)?;
+        self._db.flush()?;
+        Ok(())
+    }
+
+    /// Add a post; a no-op when the id is already indexed for the topic
+    pub fn insert(&self, topic: &str, id: &str, author: &str, ts: &DateTime<Utc>) -> Result<()> {
+        let pkey = post_key(topic, ts, id);
+        let ikey = id_key(topic, id);
+        let stamp = ts_word(ts).to_be_bytes();
+        (&self.posts, &self.ids, &self.users, &self.topics)
+            .transaction(|(posts, ids, users, topics)| {
+                if ids.get(ikey.as_slice())?.is_some() {
+                    return Ok(());
+                }
+                posts.insert(pkey.as_slice(), author.as_bytes())?;
+                ids.insert(ikey.as_slice(), &stamp[..])?;
+                let n = read_u64(users.get(author.as_bytes())?);
+                users.insert(author.as_bytes(), &(n + 1).to_be_bytes()[..])?;
+                let mut mark = topics
+                    .get(topic.as_bytes())?
+                    .map(|b| TopicMark::decode(&b))
+                    .unwrap_or_default();
+                mark.count += 1;
+                if mark.high_water.is_none_or(|hw| hw < *ts) {
+                    mark.high_water = Some(*ts);
+                }
+                topics.insert(topic.as_bytes(), &mark.encode()[..])?;
+                Ok::<(), ConflictableTransactionError<()>>(())
+            })
+            .map_err(tx_err)
+    }
+
+    /// Remove a post; returns false when it was not indexed
+    pub fn remove(&self, topic: &str, id: &str) -> Result<bool> {
+        let ikey = id_key(topic, id);
+        let removed = (&self.posts, &self.ids, &self.users, &self.topics)
+            .transaction(|(posts, ids, users, topics)| {
+                let Some(stamp) = ids.remove(ikey.as_slice())? else {
+                    return Ok(false);
+                };
+                let mut pkey = topic_prefix(topic);
+                pkey.extend_from_slice(&stamp);
+                pkey.extend_from_slice(id.as_bytes());
+                if let Some(author) = posts.remove(pkey)? {
+                    let n = read_u64(users.get(&author)?);
+                    if n <= 1 {
+                        users.remove(&author)?;
+                    } else {
+                        users.insert(&author, &(n - 1).to_be_bytes()[..])?;
+                    }
+                }
+                if let Some(bytes) = topics.get(topic.as_bytes())? {
+                    let mut mark = TopicMark::decode(&bytes);
+                    mark.count = mark.count.saturating_sub(1);
+                    topics.insert(topic.as_bytes(), &mark.encode()[..])?;
+                }
+                Ok::<bool, ConflictableTransactionError<()>>(true)
+            })
+            .map_err(tx_err)?;
+        if removed {
+            self.refresh_high_water(topic)?;
+            self.clear_terms(topic, id)?;
+        }
+        Ok(removed)
+    }
+
+    fn term_key(term: &str, topic: &str, id: &str) -> Vec<u8> {
+        let mut key = term.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(&id_key(topic, id));
+        key
+    }
+
+    fn clear_terms(&self, topic: &str, id: &str) -> Result<()> {
+        if let Some(old) = self.docs.remove(id_key(topic, id))? {
+            for term in String::from_utf8_lossy(&old).lines() {
+                self.terms.remove(Self::term_key(term, topic, id))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the searchable terms of a post
+    pub fn set_terms(&self, topic: &str, id: &str, weights: &HashMap<String, u32>) -> Result<()> {
+        self.clear_terms(topic, id)?;
+        let mut batch = sled::Batch::default();
+        for (term, weight) in weights {
+            batch.insert(Self::term_key(term, topic, id), &weight.to_be_bytes()[..]);
+        }
+        self.terms.apply_batch(batch)?;
+        let listing: Vec<&str> = weights.keys().map(String::as_str).collect();
+        self.docs
+            .insert(id_key(topic, id), listing.join("\n").as_bytes())?;
+        Ok(())
+    }
+
+    /// Posts matching every query term (exact or prefix)
+    pub fn search(&self, query: &[String]) -> Result<Vec<SearchRow>> {
+        let Some((first, rest)) = query.split_first() else {
+            return Ok(Vec::new());
+        };
+        // Candidates come from the first term; the rest are checked per post
+        let mut candidates: HashMap<Vec<u8>, Vec<(String, u32)>> = HashMap::new();
+        for q in std::iter::once(first).chain(rest) {
+            for entry in self.terms.scan_prefix(q.as_bytes()) {
+                let (key, weight) = entry?;
+                let Some(split) = key.iter().position(|b| *b == 0) else {
+                    continue;
+                };
+                let doc = key[split + 1..].to_vec();
+                if q != first && !candidates.contains_key(&doc) {
+                    continue;
+                }
+                let term = String::from_utf8_lossy(&key[..split]).into_owned();
+                candidates
+                    .entry(doc)
+                    .or_default()
+                    .push((term, read_u64_be32(&weight)));
+            }
+        }
+
+        let mut hits = Vec::new();
+        for (doc, terms) in candidates {
+            let Some(score) = score_terms(terms.iter().map(|(t, w)| (t.as_str(), *w)), query)
+            else {
+                continue;
+            };
+            let Some(split) = doc.iter().position(|b| *b == 0) else {
+                continue;
+            };
+            let Some(stamp) = self.ids.get(&doc)? else {
+                continue;
+            };
+            let ts = decode_ts(read_u64(Some(stamp)));
+            let topic = String::from_utf8_lossy(&doc[..split]).into_owned();
+            let id = String::from_utf8_lossy(&doc[split + 1..]).into_owned();
+            hits.push((topic, id, score, ts));
+        }
+        Ok(hits)
+    }
+
+    fn refresh_high_water(&self, topic: &str) -> Result<()> {
+        let newest = self.posts.scan_prefix(topic_prefix(topic)).next_back();
+        let high_water = match newest {
+            Some(entry) => {
+                let (key, _) = entry?;
+                let start = topic.len() + 1;
+                key.get(start..start + 8)
+                    .and_then(|b| b.try_into().ok())
+                    .map(|b| decode_ts(u64::from_be_bytes(b)))
+            }
+            None => None,
+        };
+        self.topics.fetch_and_update(topic.as_bytes(), |old| {
+            old.map(|b| {
+                let mut mark = TopicMark::decode(b);
+                mark.high_water = high_water;
+                mark.encode().to_vec()
+            })
+        })?;
+        Ok(())
+    }
+
+    /// Drop every post of a topic (deleted topic, or before a topic reindex)
+    pub fn clear_topic(&self, topic: &str) -> Result<()> {
+        for id in self.topic_ids(topic)? {
+            self.remove(topic, &id)?;
+        }
+        for entry in self.sync.scan_prefix(topic_prefix(topic)) {
+            self.sync.remove(entry?.0)?;
+        }
+        self.topics.remove(topic.as_bytes())?;
+        Ok(())
+    }
+
+    /// Map a post's replication id (`Message::message_id`) to its file id
+    pub fn set_sync_id(&self, topic: &str, id: &str, message_id: &str) -> Result<()> {
+        self.sync.insert(id_key(topic, message_id), id.as_bytes())?;
+        Ok(())
+    }
+
+    /// File id of the post carrying replication id `message_id`. Mappings left
+    /// behind by deleted posts are dropped on the way.
+    pub fn id_for_sync_id(&self, topic: &str, message_id: &str) -> Result<Option<String>> {
+        let key = id_key(topic, message_id);
+        let Some(id) = self.sync.get(&key)? else {
+            return Ok(None);
+        };
+        let id = String::from_utf8_lossy(&id).into_owned();
+        if self.ids.get(id_key(topic, &id))?.is_none() {
+            self.sync.remove(key)?;
+            return Ok(None);
+        }
+        Ok(Some(id))
+    }
+
+    /// All indexed ids for a topic
+    pub fn topic_ids(&self, topic: &str) -> Result<HashSet<String>> {
+        let prefix = topic_prefix(topic);
+        let mut out = HashSet::new();
+        for entry in self.ids.scan_prefix(&prefix) {
+            let (key, _) = entry?;
+            out.insert(String::from_utf8_lossy(&key[prefix.len()..]).into_owned());
+        }
+        Ok(out)
+    }
+
+    /// Newest `limit` ids of a topic, newest first
+    pub fn newest_ids(&self, topic: &str, limit: usize) -> Result<Vec<String>> {
+        let prefix = topic_prefix(topic);
+        let mut out = Vec::new();
+        for entry in self.posts.scan_prefix(&prefix).rev().take(limit) {
+            let (key, _) = entry?;
+            out.push(String::from_utf8_lossy(&key[prefix.len() + 8..]).into_owned());
+        }
+        Ok(out)
+    }
+
+    /// Posts in a topic strictly newer than `since`
+    pub fn count_since(&self, topic: &str, since: &DateTime<Utc>) -> Result<u32> {
+        let mark = self.topic_mark(topic)?;
+        if mark.high_water.is_none_or(|hw| hw <= *since) {
+            return Ok(0);
+        }
+        let prefix = topic_prefix(topic);
+        let mut start = prefix.clone();
+        start.extend_from_slice(&(ts_word(since) + 1).to_be_bytes());
+        let mut end = prefix;
+        *end.last_mut().expect("prefix ends with separator") = 1;
+        Ok(self.posts.range(start..end).count() as u32)
+    }
+
+    pub fn topic_mark(&self, topic: &str) -> Result<TopicMark> {
+        Ok(self
+            .topics
+            .get(topic.as_bytes())?
+            .map(|b| TopicMark::decode(&b))
+            .unwrap_or_default())
+    }
+
+    /// Directory stamp recorded at the last reconcile (0 = never / unsettled)
+    pub fn dir_stamp(&self, topic: &str) -> Result<u64> {
+        Ok(self.topic_mark(topic)?.dir_stamp)
+    }
+
+    pub fn set_dir_stamp(&self, topic: &str, stamp: u64) -> Result<()> {
+        let mut mark = self.topic_mark(topic)?;
+        mark.dir_stamp = stamp;
+        self.topics.insert(topic.as_bytes(), &mark.encode()[..])?;
+        Ok(())
+    }
+
+    /// Top-level posts authored by `author` across all indexed topics
+    pub fn user_posts(&self, author: &str) -> Result<u32> {
+        Ok(read_u64(self.users.get(author.as_bytes())?) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 500).unwrap()
+    }
+
+    #[test]
+    fn insert_remove_and_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = MessageIndex::open(&dir.path().to_string_lossy()).unwrap();
+        index.insert("general", "a", "alice", &ts(100)).unwrap();
+        index.insert("general", "b", "bob", &ts(300)).unwrap();
+        index.insert("general", "c", "alice", &ts(200)).unwrap();
+        index.insert("general", "c", "alice", &ts(200)).unwrap();
+        index.insert("general2", "d", "alice", &ts(400)).unwrap();
+
+        assert_eq!(index.newest_ids("general", 2).unwrap(), vec!["b", "c"]);
+        assert_eq!(index.user_posts("alice").unwrap(), 3);
+        assert_eq!(index.count_since("general", &ts(100)).unwrap(), 2);
+        assert_eq!(index.count_since("general", &ts(300)).unwrap(), 0);
+        let mark = index.topic_mark("general").unwrap();
+        assert_eq!((mark.count, mark.high_water), (3, Some(ts(300))));
+
+        assert!(index.remove("general", "b").unwrap());
+        assert!(!index.remove("general", "b").unwrap());
+        assert_eq!(index.user_posts("bob").unwrap(), 0);
+        assert_eq!(
+            index.topic_mark("general").unwrap().high_water,
+            Some(ts(200))
+        );
+
+        index.clear_topic("general").unwrap();
+        assert!(index.newest_ids("general", 10).unwrap().is_empty());
+        assert_eq!(index.user_posts("alice").unwrap(), 1);
+        assert_eq!(index.newest_ids("general2", 10).unwrap(), vec!["d"]);
+    }
+
+    #[test]
+    fn tokenize_and_score() {
+        assert_eq!(
+            tokenize("The Repeater's back-up PSU is OK!"),
+            vec!["repeater", "back", "up", "psu", "ok"]
+        );
+        let doc = [("repeater", 4), ("reply", 1), ("psu", 1)];
+        let q = |s: &str| tokenize(s);
+        assert_eq!(score_terms(doc.iter().copied(), &q("repeater")), Some(8));
+        assert_eq!(score_terms(doc.iter().copied(), &q("rep")), Some(5));
+        assert_eq!(score_terms(doc.iter().copied(), &q("rep psu")), Some(7));
+        assert_eq!(score_terms(doc.iter().copied(), &q("rep antenna")), None);
+    }
+
+    #[test]
+    fn search_matches_all_terms() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = MessageIndex::open(&dir.path().to_string_lossy()).unwrap();
+        let weights = |words: &[(&str, u32)]| -> HashMap<String, u32> {
+            words.iter().map(|(w, n)| (w.to_string(), *n)).collect()
+        };
+        index.insert("general", "a", "alice", &ts(1)).unwrap();
+        index
+            .set_terms("general", "a", &weights(&[("repeater", 3), ("psu", 1)]))
+            .unwrap();
+        index.insert("tech", "b", "bob", &ts(2)).unwrap();
+        index
+            .set_terms("tech", "b", &weights(&[("repeaters", 1)]))
+            .unwrap();
+
+        let hits = index.search(&tokenize("repeater")).unwrap();
+        assert_eq!(hits.len(), 2);
+        let hits = index.search(&tokenize("repeat psu")).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].0.as_str(), hits[0].1.as_str()), ("general", "a"));
+
+        index.remove("general", "a").unwrap();
+        assert_eq!(index.search(&tokenize("psu")).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn handles_share_one_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().to_string_lossy().to_string();
+        let first = MessageIndex::open(&data_dir).unwrap();
+        let second = MessageIndex::open(&data_dir).unwrap();
+        first.insert("general", "a", "alice", &ts(1)).unwrap();
+        assert_eq!(second.user_posts("alice").unwrap(), 1);
+    }
+}
@@ -18,8 +18,14 @@
 //! ```text
 //! data/
 //! ├── users/          ← User account data
-//! ├── messages/       ← Message topic storage
+//! ├── messages/       ← Message topic storage (threads carry replies and [`polls`])
+//! ├── mail/           ← Private mailboxes (one JSON file per user)
+//! ├── files/          ← Text file library areas and index.json (see [`files`])
+//! ├── subscriptions/  ← Followed threads and topics (see [`subscriptions`])
+//! ├── message_index/  ← sled index over messages/ (rebuildable, see [`index`])
+//! ├── archive/        ← Threads expired by topic retention (see [`retention`])
 //! ├── audit/          ← Administrative audit logs
+//! ├── moderation.json ← Bans, posting mutes and public ignores (see [`moderation`])
 //! └── config/         ← Runtime configuration
 //! ```
 //!
@@ -61,6 +67,7 @@
 //!
 //! - [`Message`] - Individual message posts with metadata
 //! - [`User`] - User account information and permissions
+//! - [`MailMessage`] - Private mail between registered users
 //! - [`DeletionAuditEntry`] - Records of message deletions
 //! - [`AdminAuditEntry`] - Records of administrative actions
 //!
@@ -112,8 +119,15 @@ pub struct Storage {
     topic_levels: std::collections::HashMap<String, (u8, u8)>, // topic -> (read_level, post_level)
     max_message_bytes: usize,
     runtime_topics: RuntimeTopicsConfig, // Runtime-managed topic configurations
+    mail_quota: usize,                   // Max messages per mailbox folder
+    notify_daily_cap: u32,               // Reply summaries per user per UTC day
+    index: Option<index::MessageIndex>,  // None when another process holds the index
+    moderation: moderation::ModerationList, // Bans, mutes and public ignores
 }
 
+/// Default number of messages a user may hold in each mailbox folder
+pub const DEFAULT_MAIL_QUOTA: usize = 50;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reply {
     pub author: String,
@@ -148,6 +162,20 @@ pub struct Message {
     /// CRC-16 checksum for message integrity verification
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub crc16: Option<u16>,
+    /// Set on poll threads; votes are saved with the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<polls::Poll>,
+}
+
+/// One ranked result from [`Storage::search_messages`]
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub topic: String,
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    pub timestamp: DateTime<Utc>,
+    pub score: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -167,6 +195,51 @@ pub struct AdminAuditEntry {
     pub details: Option<String>, // additional context
 }
 
+/// A private mail message between two registered users
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailMessage {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    /// Whether the recipient has opened the message (always true for sent copies)
+    #[serde(default)]
+    pub read: bool,
+}
+
+/// Mailbox folders kept per user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailFolder {
+    Inbox,
+    Sent,
+}
+
+/// On-disk mailbox layout (`mail/<user>.json`), oldest message first
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct Mailbox {
+    #[serde(default)]
+    inbox: Vec<MailMessage>,
+    #[serde(default)]
+    sent: Vec<MailMessage>,
+}
+
+impl Mailbox {
+    fn folder(&self, folder: MailFolder) -> &Vec<MailMessage> {
+        match folder {
+            MailFolder::Inbox => &self.inbox,
+            MailFolder::Sent => &self.sent,
+        }
+    }
+
+    fn folder_mut(&mut self, folder: MailFolder) -> &mut Vec<MailMessage> {
+        match folder {
+            MailFolder::Inbox => &mut self.inbox,
+            MailFolder::Sent => &mut self.sent,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub username: String,
@@ -189,6 +262,18 @@ pub struct User {
     pub welcome_shown_on_first_login: bool,
 }
 
+/// Audit detail line for a ban or mute: reason and expiry, when given
+fn moderation_details(reason: Option<&str>, expires_at: Option<DateTime<Utc>>) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(t) = expires_at {
+        parts.push(format!("until {}", t.format("%Y-%m-%d %H:%M UTC")));
+    }
+    if let Some(r) = reason {
+        parts.push(format!("reason: {}", r));
+    }
+    (!parts.is_empty()).then(|| parts.join("; "))
+}
+
 fn default_user_level() -> u8 {
     1
 }
@@ -214,6 +299,12 @@ pub struct RuntimeTopicConfig {
     /// Optional parent topic for hierarchical organization (subtopics)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
+    /// Opt-in for store-and-forward replication with peer BBS nodes
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub replicate: bool,
+    /// Optional policy for archiving old threads (see [`retention`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retention: Option<retention::TopicRetention>,
 }
 
 /// Collection of all runtime topic configurations
@@ -235,21 +326,30 @@ impl Storage {
         let messages_dir = Path::new(data_dir).join("messages");
         let users_dir = Path::new(data_dir).join("users");
         let files_dir = Path::new(data_dir).join("files");
+        let mail_dir = Path::new(data_dir).join("mail");
 
         fs::create_dir_all(&messages_dir).await?;
         fs::create_dir_all(&users_dir).await?;
         fs::create_dir_all(&files_dir).await?;
+        fs::create_dir_all(&mail_dir).await?;
 
         let locked = Self::load_locked_topics(data_dir).await?;
         let runtime_topics = Self::load_runtime_topics(data_dir).await?;
-        Ok(Storage {
+        let moderation = Self::load_moderation(data_dir).await?;
+        let mut storage = Storage {
             data_dir: data_dir.to_string(),
             argon2: Argon2::default(),
             locked_topics: locked,
             topic_levels: HashMap::new(),
             max_message_bytes: 230,
             runtime_topics,
-        })
+            mail_quota: DEFAULT_MAIL_QUOTA,
+            notify_daily_cap: subscriptions::DEFAULT_NOTIFY_DAILY_CAP,
+            index: None,
+            moderation,
+        };
+        storage.attach_message_index().await;
+        Ok(storage)
     }
 
     /// Initialize storage with explicit Argon2 params
@@ -259,9 +359,11 @@ impl Storage {
         let messages_dir = Path::new(data_dir).join("messages");
         let users_dir = Path::new(data_dir).join("users");
         let files_dir = Path::new(data_dir).join("files");
+        let mail_dir = Path::new(data_dir).join("mail");
         fs::create_dir_all(&messages_dir).await?;
         fs::create_dir_all(&users_dir).await?;
         fs::create_dir_all(&files_dir).await?;
+        fs::create_dir_all(&mail_dir).await?;
         let argon2 = if let Some(p) = params {
             Argon2::new(Algorithm::Argon2id, Version::V0x13, p)
         } else {
@@ -269,14 +371,21 @@ impl Storage {
         };
         let locked = Self::load_locked_topics(data_dir).await?;
         let runtime_topics = Self::load_runtime_topics(data_dir).await?;
-        Ok(Storage {
+        let moderation = Self::load_moderation(data_dir).await?;
+        let mut storage = Storage {
             data_dir: data_dir.to_string(),
             argon2,
             locked_topics: locked,
             topic_levels: HashMap::new(),
             max_message_bytes: 230,
             runtime_topics,
-        })
+            mail_quota: DEFAULT_MAIL_QUOTA,
+            notify_daily_cap: subscriptions::DEFAULT_NOTIFY_DAILY_CAP,
+            index: None,
+            moderation,
+        };
+        storage.attach_message_index().await;
+        Ok(storage)
     }
 
     #[allow(dead_code)]
@@ -290,6 +399,193 @@ impl Storage {
     pub fn set_max_message_bytes(&mut self, max: usize) {
         self.max_message_bytes = max.min(230);
     }
+    /// Override the per-folder mailbox quota (minimum 1)
+    pub fn set_mail_quota(&mut self, quota: usize) {
+        self.mail_quota = quota.max(1);
+    }
+    /// Override how many reply summaries a user may receive per day (0 disables them)
+    pub fn set_notify_daily_cap(&mut self, cap: u32) {
+        self.notify_daily_cap = cap;
+    }
+
+    /// Open the message index, building it from the JSON files on first use.
+    /// If another process already holds it (e.g. CLI commands while the server
+    /// runs), reads fall back to directory scans.
+    async fn attach_message_index(&mut self) {
+        let index = match index::MessageIndex::open(&self.data_dir) {
+            Ok(index) => index,
+            Err(e) => {
+                warn!(
+                    "Message index unavailable, falling back to directory scans: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let current = index.is_current().unwrap_or(false);
+        self.index = Some(index);
+        if !current {
+            match self.rebuild_message_index().await {
+                Ok(count) => log::info!("Message index built: {} messages", count),
+                Err(e) => {
+                    warn!("Message index build failed, using directory scans: {}", e);
+                    self.index = None;
+                }
+            }
+        }
+    }
+
+    /// Rebuild the message index from the JSON files. Returns the number of
+    /// messages indexed.
+    pub async fn rebuild_message_index(&self) -> Result<usize> {
+        let index = self
+            .index
+            .as_ref()
+            .ok_or_else(|| anyhow!("Message index is in use by another process"))?;
+        index.clear()?;
+        let mut total = 0;
+        for topic in self.message_topic_dirs().await? {
+            self.sync_topic_index(index, &topic).await?;
+            total += index.topic_mark(&topic)?.count as usize;
+        }
+        index.mark_current()?;
+        Ok(total)
+    }
+
+    /// Whether reads are served from the message index
+    pub fn message_index_active(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// Topic directory names under `messages/` (invalid names skipped)
+    async fn message_topic_dirs(&self) -> Result<Vec<String>> {
+        let messages_dir = Path::new(&self.data_dir).join("messages");
+        let mut topics = Vec::new();
+        if !messages_dir.exists() {
+            return Ok(topics);
+        }
+        let mut entries = fs::read_dir(&messages_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if !entry.file_type().await?.is_dir() {
+                continue;
+            }
+            match entry.file_name().to_str() {
+                Some(name) if validate_topic_name(name).is_ok() => topics.push(name.to_string()),
+                _ => warn!("Skipping invalid area directory: {:?}", entry.path()),
+            }
+        }
+        Ok(topics)
+    }
+
+    /// Bring a topic's index entries in line with its directory. Skipped when the
+    /// directory mtime matches the stamp recorded at the last reconcile; otherwise
+    /// only files added or removed since then are read.
+    async fn sync_topic_index(&self, index: &index::MessageIndex, topic: &str) -> Result<()> {
+        let topic_dir = secure_topic_path(&self.data_dir, topic)
+            .map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+        let stamp = match fs::metadata(&topic_dir).await {
+            Ok(meta) => Self::settled_dir_stamp(&meta),
+            Err(_) => return index.clear_topic(topic),
+        };
+        if stamp != 0 && index.dir_stamp(topic)? == stamp {
+            return Ok(());
+        }
+
+        let mut on_disk = HashSet::new();
+        let mut entries = fs::read_dir(&topic_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                on_disk.insert(stem.to_string());
+            }
+        }
+        let indexed = index.topic_ids(topic)?;
+        for id in indexed.difference(&on_disk) {
+            index.remove(topic, id)?;
+        }
+        for id in on_disk.difference(&indexed) {
+            let path = topic_dir.join(format!("{}.json", id));
+            match Self::read_message_file(&path).await {
+                Some(msg) if msg.id == *id => {
+                    index.insert(topic, &msg.id, &msg.author, &msg.timestamp)?;
+                    index.set_terms(topic, &msg.id, &index::term_weights(&msg))?;
+                    if let Some(message_id) = &msg.message_id {
+                        index.set_sync_id(topic, &msg.id, message_id)?;
+                    }
+                }
+                Some(_) => warn!("Message ID mismatch in file: {:?}", path),
+                None => {}
+            }
+        }
+        index.set_dir_stamp(topic, stamp)
+    }
+
+    /// Directory mtime in nanoseconds, or 0 when it is unavailable or so recent
+    /// that another write could land within the same mtime tick.
+    fn settled_dir_stamp(meta: &std::fs::Metadata) -> u64 {
+        let Ok(modified) = meta.modified() else {
+            return 0;
+        };
+        let settled = modified
+            .elapsed()
+            .map(|age| age >= std::time::Duration::from_secs(2))
+            .unwrap_or(false);
+        if !settled {
+            return 0;
+        }
+        modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Read and parse one message file, skipping oversized or corrupt files
+    async fn read_message_file(path: &Path) -> Option<Message> {
+        let metadata = fs::metadata(path).await.ok()?;
+        if metadata.len() > 1_000_000 {
+            // 1MB limit per message file
+            warn!("Skipping oversized message file: {:?}", path);
+            return None;
+        }
+        let Ok(content) = fs::read_to_string(path).await else {
+            warn!("Failed to read message file: {:?}", path);
+            return None;
+        };
+        match serde_json::from_str::<Message>(&content) {
+            Ok(message) => Some(message),
+            Err(_) => {
+                warn!("Failed to parse message file: {:?}", path);
+                None
+            }
+        }
+    }
+
+    /// Record a newly written or edited message in the index (best-effort; a
+    /// missed new entry is picked up by the next directory reconcile)
+    fn index_message(&self, message: &Message) {
+        if let Some(index) = &self.index {
+            let result = index
+                .insert(
+                    &message.topic,
+                    &message.id,
+                    &message.author,
+                    &message.timestamp,
+                )
+                .and_then(|_| {
+                    index.set_terms(&message.topic, &message.id, &index::term_weights(message))
+                })
+                .and_then(|_| match &message.message_id {
+                    Some(message_id) => index.set_sync_id(&message.topic, &message.id, message_id),
+                    None => Ok(()),
+                });
+            if let Err(e) = result {
+                warn!("Failed to index message {}: {}", message.id, e);
+            }
+        }
+    }
 
     async fn load_locked_topics(data_dir: &str) -> Result<HashSet<String>> {
         let path = Path::new(data_dir).join("locked_topics.json");
@@ -305,6 +601,19 @@ impl Storage {
         }
     }
 
+    async fn load_moderation(data_dir: &str) -> Result<moderation::ModerationList> {
+        let path = Path::new(data_dir).join("moderation.json");
+        match fs::read_to_string(&path).await {
+            Ok(data) => {
+                let cleaned = data.trim_start_matches('\0');
+                serde_json::from_str(cleaned)
+                    .map_err(|e| anyhow!("Failed to parse moderation.json: {}", e))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(moderation::ModerationList::default()),
+            Err(e) => Err(anyhow!("Failed reading moderation.json: {e}")),
+        }
+    }
+
     /// Load runtime topic configurations from topics.json
     async fn load_runtime_topics(data_dir: &str) -> Result<RuntimeTopicsConfig> {
         let path = Path::new(data_dir).join("topics.json");
@@ -467,7 +776,7 @@ impl Storage {
 
     /// Calculate CRC-16 checksum for message integrity verification
     /// Uses CRC-16-IBM-SDLC (polynomial 0x1021, also known as CRC-16-CCITT)
-    fn calculate_message_crc(
+    pub fn calculate_message_crc(
         topic: &str,
         author: &str,
         content: &str,
@@ -494,6 +803,14 @@ impl Storage {
         Ok(())
     }
 
+    async fn persist_moderation(&mut self) -> Result<()> {
+        self.moderation.prune_expired(Utc::now());
+        let path = Path::new(&self.data_dir).join("moderation.json");
+        let data = serde_json::to_string_pretty(&self.moderation)?;
+        Self::write_file_locked(&path, &data).await?;
+        Ok(())
+    }
+
     /// Return the base data directory path used by this storage instance
     pub fn base_dir(&self) -> &str {
         &self.data_dir
@@ -766,6 +1083,10 @@ impl Storage {
             return Err(anyhow!("Topic locked"));
         }
 
+        if self.mute_for(author, &validated_topic).is_some() {
+            return Err(anyhow!("{} is muted in {}", author, validated_topic));
+        }
+
         // Check posting permission using runtime topic config
         if let Some(topic_config) = self.get_topic_config(&validated_topic) {
             let author_level = if let Some(user) = self.get_user(author).await? {
@@ -814,6 +1135,7 @@ impl Storage {
             pinned: false,
             message_id: Some(message_id),
             crc16: Some(crc16),
+            poll: None,
         };
 
         // Topic directory should already exist (created by create_topic)
@@ -832,15 +1154,35 @@ impl Storage {
         let json_content = serde_json::to_string_pretty(&message)?;
 
         Self::write_file_locked(&message_file, &json_content).await?;
+        self.index_message(&message);
+
+        // Authors hear about replies to their own threads
+        if self.get_user(author).await?.is_some() {
+            let target = subscriptions::SubscriptionTarget::Thread {
+                topic: validated_topic,
+                id: message.id.clone(),
+            };
+            if let Err(e) = self.subscribe(author, target).await {
+                warn!("Failed to subscribe {} to their thread: {}", author, e);
+            }
+        }
 
         Ok(message.id)
     }
 
     /// Count messages whose timestamp is strictly greater than the supplied instant.
-    /// This performs a linear scan of all message JSON files. For typical small BBS
-    /// deployments this is acceptable; if performance becomes an issue we can
-    /// introduce a lightweight global index or per-topic cached high-water marks.
+    /// Served from the message index (topics whose high-water mark is not newer
+    /// than `since` are skipped); falls back to a linear scan of all message JSON
+    /// files when the index is unavailable.
     pub async fn count_messages_since(&self, since: DateTime<Utc>) -> Result<u32> {
+        if let Some(index) = &self.index {
+            let mut count = 0;
+            for topic in self.message_topic_dirs().await? {
+                self.sync_topic_index(index, &topic).await?;
+                count += index.count_since(&topic, &since)?;
+            }
+            return Ok(count);
+        }
         let mut count: u32 = 0;
         let messages_dir = Path::new(&self.data_dir).join("messages");
         if !messages_dir.exists() {
@@ -899,6 +1241,13 @@ impl Storage {
         topic: &str,
         since: DateTime<Utc>,
     ) -> Result<u32> {
+        if let Some(index) = &self.index {
+            if validate_topic_name(topic).is_err() {
+                return Ok(0);
+            }
+            self.sync_topic_index(index, topic).await?;
+            return index.count_since(topic, &since);
+        }
         let mut count: u32 = 0;
         let topic_dir = Path::new(&self.data_dir)
             .join("messages")
@@ -958,6 +1307,11 @@ impl Storage {
 
         if message_file.exists() {
             fs::remove_file(message_file).await?;
+            if let Some(index) = &self.index {
+                if let Err(e) = index.remove(topic, id) {
+                    warn!("Failed to unindex message {}: {}", id, e);
+                }
+            }
             return Ok(true);
         }
         Ok(false)
@@ -1079,6 +1433,153 @@ impl Storage {
         self.persist_locked_topics().await
     }
 
+    /// Active ban on a username, if any
+    pub fn user_ban(&self, username: &str) -> Option<&moderation::BanEntry> {
+        self.moderation.ban_for(
+            &moderation::BanTarget::User(username.to_string()),
+            Utc::now(),
+        )
+    }
+
+    /// Active ban on a Meshtastic node id, if any
+    pub fn node_ban(&self, node_id: u32) -> Option<&moderation::BanEntry> {
+        self.moderation
+            .ban_for(&moderation::BanTarget::Node(node_id), Utc::now())
+    }
+
+    /// Active bans, oldest first
+    pub fn list_bans(&self) -> Vec<&moderation::BanEntry> {
+        let now = Utc::now();
+        self.moderation
+            .bans
+            .iter()
+            .filter(|b| b.is_active(now))
+            .collect()
+    }
+
+    /// Ban a user or node (replacing any existing ban on it), persist and audit
+    pub async fn ban(
+        &mut self,
+        target: moderation::BanTarget,
+        actor: &str,
+        reason: Option<&str>,
+        duration: Option<chrono::Duration>,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let expires_at = duration.map(|d| now + d);
+        let details = moderation_details(reason, expires_at);
+        let target_label = target.to_string();
+        self.moderation.add_ban(moderation::BanEntry {
+            target,
+            actor: actor.to_string(),
+            reason: reason.map(str::to_string),
+            created_at: now,
+            expires_at,
+        });
+        self.persist_moderation().await?;
+        self.log_admin_action("BAN", Some(&target_label), actor, details.as_deref())
+            .await
+    }
+
+    /// Lift a ban. Returns false when no active ban matched.
+    pub async fn unban(&mut self, target: &moderation::BanTarget, actor: &str) -> Result<bool> {
+        if !self.moderation.remove_ban(target, Utc::now()) {
+            return Ok(false);
+        }
+        self.persist_moderation().await?;
+        self.log_admin_action("UNBAN", Some(&target.to_string()), actor, None)
+            .await?;
+        Ok(true)
+    }
+
+    /// Active mute stopping `username` from posting in `topic`, if any
+    pub fn mute_for(&self, username: &str, topic: &str) -> Option<&moderation::MuteEntry> {
+        self.moderation.mute_for(username, topic, Utc::now())
+    }
+
+    /// Active mutes, oldest first
+    pub fn list_mutes(&self) -> Vec<&moderation::MuteEntry> {
+        let now = Utc::now();
+        self.moderation
+            .mutes
+            .iter()
+            .filter(|m| m.is_active(now))
+            .collect()
+    }
+
+    /// Mute a user in one topic, or everywhere when `topic` is None; persist and audit
+    pub async fn mute_user(
+        &mut self,
+        username: &str,
+        topic: Option<&str>,
+        actor: &str,
+        reason: Option<&str>,
+        duration: Option<chrono::Duration>,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let expires_at = duration.map(|d| now + d);
+        let mut details = moderation_details(reason, expires_at).unwrap_or_default();
+        details.insert_str(0, &format!("topic={} ", topic.unwrap_or("*")));
+        self.moderation.add_mute(moderation::MuteEntry {
+            username: username.to_string(),
+            topic: topic.map(str::to_string),
+            actor: actor.to_string(),
+            reason: reason.map(str::to_string),
+            created_at: now,
+            expires_at,
+        });
+        self.persist_moderation().await?;
+        self.log_admin_action("MUTE", Some(username), actor, Some(details.trim_end()))
+            .await
+    }
+
+    /// Lift a mute. Returns false when no active mute matched.
+    pub async fn unmute_user(
+        &mut self,
+        username: &str,
+        topic: Option<&str>,
+        actor: &str,
+    ) -> Result<bool> {
+        if !self.moderation.remove_mute(username, topic, Utc::now()) {
+            return Ok(false);
+        }
+        self.persist_moderation().await?;
+        let details = format!("topic={}", topic.unwrap_or("*"));
+        self.log_admin_action("UNMUTE", Some(username), actor, Some(&details))
+            .await?;
+        Ok(true)
+    }
+
+    /// Nodes whose public channel commands are ignored
+    pub fn ignored_nodes(&self) -> &[u32] {
+        &self.moderation.ignored_nodes
+    }
+
+    /// Add or remove a node from the public ignore list; persist and audit.
+    /// Returns false when the list already had the requested state.
+    pub async fn set_node_ignored(
+        &mut self,
+        node_id: u32,
+        ignored: bool,
+        actor: &str,
+    ) -> Result<bool> {
+        let list = &mut self.moderation.ignored_nodes;
+        let present = list.contains(&node_id);
+        if present == ignored {
+            return Ok(false);
+        }
+        if ignored {
+            list.push(node_id);
+        } else {
+            list.retain(|n| *n != node_id);
+        }
+        self.persist_moderation().await?;
+        let action = if ignored { "IGNORE" } else { "UNIGNORE" };
+        self.log_admin_action(action, Some(&format!("!{:08x}", node_id)), actor, None)
+            .await?;
+        Ok(true)
+    }
+
     /// Get recent messages from a topic
     pub async fn get_messages(&self, topic: &str, limit: usize) -> Result<Vec<Message>> {
         // Validate topic name to prevent path traversal
@@ -1089,6 +1590,18 @@ impl Storage {
             return Ok(Vec::new());
         }
 
+        if let Some(index) = &self.index {
+            self.sync_topic_index(index, topic).await?;
+            let mut messages = Vec::new();
+            for id in index.newest_ids(topic, limit)? {
+                let path = topic_dir.join(format!("{}.json", id));
+                if let Some(message) = Self::read_message_file(&path).await {
+                    messages.push(message);
+                }
+            }
+            return Ok(messages);
+        }
+
         let mut messages = Vec::new();
         let mut entries = fs::read_dir(&topic_dir).await?;
 
@@ -1128,6 +1641,107 @@ impl Storage {
         Ok(messages)
     }
 
+    /// Load a single message by topic and id
+    pub async fn get_message(&self, topic: &str, id: &str) -> Result<Option<Message>> {
+        let message_file = secure_message_path(&self.data_dir, topic, id)
+            .map_err(|e| anyhow!("Invalid path parameters: {}", e))?;
+        if !message_file.exists() {
+            return Ok(None);
+        }
+        Ok(Self::read_message_file(&message_file).await)
+    }
+
+    /// Load the message carrying replication id `message_id` from a topic
+    pub async fn get_replicated_message(
+        &self,
+        topic: &str,
+        message_id: &str,
+    ) -> Result<Option<Message>> {
+        if let Some(index) = &self.index {
+            self.sync_topic_index(index, topic).await?;
+            return match index.id_for_sync_id(topic, message_id)? {
+                Some(id) => self.get_message(topic, &id).await,
+                None => Ok(None),
+            };
+        }
+        Ok(self
+            .get_messages(topic, usize::MAX)
+            .await?
+            .into_iter()
+            .find(|m| m.message_id.as_deref() == Some(message_id)))
+    }
+
+    /// Full-text search over titles, bodies and replies in topics accepted by
+    /// `can_read`. Every query word must match (whole word or prefix). Hits are
+    /// ranked by score, then newest first.
+    pub async fn search_messages<F: Fn(&str) -> bool>(
+        &self,
+        query: &str,
+        can_read: F,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let terms = index::tokenize(query);
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut ranked: Vec<index::SearchRow> = Vec::new();
+        let topics = self.message_topic_dirs().await?;
+        if let Some(index) = &self.index {
+            for topic in &topics {
+                self.sync_topic_index(index, topic).await?;
+            }
+            ranked = index
+                .search(&terms)?
+                .into_iter()
+                .filter(|(topic, ..)| can_read(topic))
+                .collect();
+        } else {
+            for topic in topics.iter().filter(|t| can_read(t)) {
+                for m in self.get_messages(topic, usize::MAX).await? {
+                    let weights = index::term_weights(&m);
+                    let doc = weights.iter().map(|(t, w)| (t.as_str(), *w));
+                    if let Some(score) = index::score_terms(doc, &terms) {
+                        ranked.push((m.topic.clone(), m.id.clone(), score, m.timestamp));
+                    }
+                }
+            }
+        }
+        ranked.sort_by(|a, b| b.2.cmp(&a.2).then(b.3.cmp(&a.3)));
+
+        let mut hits = Vec::new();
+        for (topic, id, score, _) in ranked.into_iter().take(limit) {
+            if let Some(m) = self.get_message(&topic, &id).await? {
+                let title = m
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| m.content.lines().next().unwrap_or("").to_string());
+                hits.push(SearchHit {
+                    topic,
+                    id,
+                    title,
+                    author: m.author,
+                    timestamp: m.timestamp,
+                    score,
+                });
+            }
+        }
+        Ok(hits)
+    }
+
+    /// Timestamp of the newest message in a topic
+    pub async fn topic_high_water(&self, topic: &str) -> Result<Option<DateTime<Utc>>> {
+        if let Some(index) = &self.index {
+            validate_topic_name(topic).map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+            self.sync_topic_index(index, topic).await?;
+            return Ok(index.topic_mark(topic)?.high_water);
+        }
+        Ok(self
+            .get_messages(topic, 1)
+            .await?
+            .first()
+            .map(|m| m.timestamp))
+    }
+
     /// Append a reply to an existing message (stored inline in the message JSON).
     pub async fn append_reply(
         &self,
@@ -1144,6 +1758,10 @@ impl Storage {
             return Err(anyhow!("Message not found"));
         }
 
+        if self.mute_for(author, topic).is_some() {
+            return Err(anyhow!("{} is muted in {}", author, topic));
+        }
+
         // Read and parse message
         let raw = fs::read_to_string(&message_file).await?;
         let mut msg: Message = secure_json_parse(&raw, 1_000_000)
@@ -1165,6 +1783,7 @@ impl Storage {
         msg.replies.push(ReplyEntry::Reply(reply));
         let json_content = serde_json::to_string_pretty(&msg)?;
         Self::write_file_locked(&message_file, &json_content).await?;
+        self.index_message(&msg);
         Ok(())
     }
 
@@ -1208,6 +1827,8 @@ impl Storage {
             created_by: creator.to_string(),
             created_at: Utc::now(),
             parent: None,
+            replicate: false,
+            retention: None,
         };
 
         // Add to runtime topics
@@ -1263,6 +1884,200 @@ impl Storage {
         Ok(())
     }
 
+    /// Opt a topic in or out of store-and-forward replication (sysop only)
+    pub async fn set_topic_replication(&mut self, topic_id: &str, enabled: bool) -> Result<()> {
+        let topic_config = self
+            .runtime_topics
+            .topics
+            .get_mut(topic_id)
+            .ok_or_else(|| anyhow!("Topic '{}' not found", topic_id))?;
+        topic_config.replicate = enabled;
+        self.save_runtime_topics().await
+    }
+
+    /// Set or clear a topic's retention policy (sysop only)
+    pub async fn set_topic_retention(
+        &mut self,
+        topic_id: &str,
+        retention: Option<retention::TopicRetention>,
+    ) -> Result<()> {
+        let topic_config = self
+            .runtime_topics
+            .topics
+            .get_mut(topic_id)
+            .ok_or_else(|| anyhow!("Topic '{}' not found", topic_id))?;
+        topic_config.retention = retention.filter(|r| !r.is_unlimited());
+        self.save_runtime_topics().await
+    }
+
+    /// Archive expired threads in every topic with a retention policy.
+    ///
+    /// Returns `(topic, threads archived)` for each topic that lost threads;
+    /// each of those also gets an `ARCHIVE` entry in the admin audit log.
+    pub async fn sweep_retention(&mut self, now: DateTime<Utc>) -> Result<Vec<(String, usize)>> {
+        let mut policies: Vec<(String, retention::TopicRetention)> = self
+            .runtime_topics
+            .topics
+            .iter()
+            .filter_map(|(id, cfg)| cfg.retention.clone().map(|r| (id.clone(), r)))
+            .collect();
+        policies.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut swept = Vec::new();
+        for (topic, policy) in policies {
+            let messages = self.get_messages(&topic, usize::MAX).await?;
+            let expired: Vec<Message> = retention::expired(&policy, &messages, now)
+                .into_iter()
+                .cloned()
+                .collect();
+            if expired.is_empty() {
+                continue;
+            }
+            // Archive first so a failure part way never loses a thread. The
+            // tar.gz is rewritten whole, so keep the compression off the runtime.
+            let path = retention::archive_path(&self.data_dir, &topic);
+            let mut archived = retention::load_archive(path.clone()).await?;
+            archived.extend(expired.iter().cloned());
+            retention::store_archive(path, archived).await?;
+            for message in &expired {
+                self.delete_message(&topic, &message.id).await?;
+            }
+            self.log_admin_action(
+                "ARCHIVE",
+                Some(&topic),
+                "system",
+                Some(&format!("{} threads ({})", expired.len(), policy)),
+            )
+            .await?;
+            swept.push((topic, expired.len()));
+        }
+        Ok(swept)
+    }
+
+    /// Archived threads in a topic, most recently active first
+    pub async fn list_archived(&self, topic: &str) -> Result<Vec<Message>> {
+        let topic = validate_topic_name(topic).map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+        let mut archived =
+            retention::load_archive(retention::archive_path(&self.data_dir, &topic)).await?;
+        archived.sort_by_key(|m| std::cmp::Reverse(retention::last_activity(m)));
+        Ok(archived)
+    }
+
+    /// Move an archived thread back into its topic. `id` may be any unique
+    /// prefix of the message id. Returns the restored thread, or `None` when
+    /// nothing in the archive matches.
+    pub async fn restore_archived(
+        &mut self,
+        topic: &str,
+        id: &str,
+        actor: &str,
+    ) -> Result<Option<Message>> {
+        let topic = validate_topic_name(topic).map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+        if id.is_empty() {
+            return Ok(None);
+        }
+        let path = retention::archive_path(&self.data_dir, &topic);
+        let mut archived = retention::load_archive(path.clone()).await?;
+        let matches: Vec<usize> = archived
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.id.starts_with(id))
+            .map(|(i, _)| i)
+            .collect();
+        let index = match matches.as_slice() {
+            [] => return Ok(None),
+            [index] => *index,
+            _ => return Err(anyhow!("'{}' matches {} threads", id, matches.len())),
+        };
+        let message = archived.remove(index);
+
+        let message_file = secure_message_path(&self.data_dir, &topic, &message.id)
+            .map_err(|e| anyhow!("Message path validation failed: {}", e))?;
+        if let Some(parent) = message_file.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let json_content = serde_json::to_string_pretty(&message)?;
+        Self::write_file_locked(&message_file, &json_content).await?;
+        self.index_message(&message);
+        retention::store_archive(path, archived).await?;
+        self.log_admin_action("UNARCHIVE", Some(&topic), actor, Some(&message.id))
+            .await?;
+        Ok(Some(message))
+    }
+
+    /// Topics opted in to replication (sorted by id)
+    pub fn list_replicated_topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self
+            .runtime_topics
+            .topics
+            .iter()
+            .filter(|(_, cfg)| cfg.replicate)
+            .map(|(id, _)| id.clone())
+            .collect();
+        topics.sort();
+        topics
+    }
+
+    /// Store a message received from a replication peer, keeping its original
+    /// author, timestamp, `message_id` and `crc16`.
+    ///
+    /// Returns `Ok(false)` without writing when a message with the same
+    /// `message_id` already exists in the topic, or the topic is not opted in.
+    /// Fails when the author is banned or muted in the topic, as local posts do.
+    pub async fn import_replicated_message(&mut self, incoming: &Message) -> Result<bool> {
+        let validated_topic = validate_topic_name(&incoming.topic)
+            .map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+        let Some(message_id) = incoming.message_id.clone() else {
+            return Err(anyhow!("Replicated message has no message_id"));
+        };
+        if !self
+            .get_topic_config(&validated_topic)
+            .map(|cfg| cfg.replicate)
+            .unwrap_or(false)
+        {
+            return Ok(false);
+        }
+        if self
+            .get_replicated_message(&validated_topic, &message_id)
+            .await?
+            .is_some()
+        {
+            return Ok(false);
+        }
+        if self.user_ban(&incoming.author).is_some() {
+            return Err(anyhow!("{} is banned", incoming.author));
+        }
+        if self.mute_for(&incoming.author, &validated_topic).is_some() {
+            return Err(anyhow!(
+                "{} is muted in {}",
+                incoming.author,
+                validated_topic
+            ));
+        }
+
+        let sanitized_content = sanitize_message_content(&incoming.content, self.max_message_bytes)
+            .map_err(|e| anyhow!("Invalid message content: {}", e))?;
+        let message = Message {
+            id: Uuid::new_v4().to_string(),
+            topic: validated_topic.clone(),
+            author: incoming.author.clone(),
+            title: incoming.title.clone(),
+            content: sanitized_content,
+            timestamp: incoming.timestamp,
+            replies: Vec::new(),
+            pinned: false,
+            message_id: Some(message_id),
+            crc16: incoming.crc16,
+            poll: None,
+        };
+        let message_file = secure_message_path(&self.data_dir, &validated_topic, &message.id)
+            .map_err(|e| anyhow!("Message path validation failed: {}", e))?;
+        let json_content = serde_json::to_string_pretty(&message)?;
+        Self::write_file_locked(&message_file, &json_content).await?;
+        self.index_message(&message);
+        Ok(true)
+    }
+
     /// Delete a topic (sysop only)
     pub async fn delete_topic(&mut self, topic_id: &str) -> Result<()> {
         // Check if topic exists
@@ -1280,6 +2095,9 @@ impl Storage {
                 .await
                 .map_err(|e| anyhow!("Failed to remove topic directory: {}", e))?;
         }
+        if let Some(index) = &self.index {
+            index.clear_topic(topic_id)?;
+        }
 
         // Persist to disk
         self.save_runtime_topics().await?;
@@ -1452,6 +2270,12 @@ impl Storage {
 
     /// Count total posts by a specific user across all topics
     pub async fn count_user_posts(&self, username: &str) -> Result<u32> {
+        if let Some(index) = &self.index {
+            for topic in self.message_topic_dirs().await? {
+                self.sync_topic_index(index, &topic).await?;
+            }
+            return index.user_posts(username);
+        }
         let messages_dir = Path::new(&self.data_dir).join("messages");
         let mut post_count = 0;
 
@@ -1552,6 +2376,8 @@ impl Storage {
             created_by: creator.to_string(),
             created_at: Utc::now(),
             parent: Some(parent_id.to_string()),
+            replicate: false,
+            retention: None,
         };
         self.runtime_topics
             .topics
@@ -1609,6 +2435,578 @@ impl Storage {
         msg.title = title.map(|t| t.to_string());
         let json_content = serde_json::to_string_pretty(&msg)?;
         Self::write_file_locked(&message_file, &json_content).await?;
+        self.index_message(&msg);
+        Ok(())
+    }
+
+    /// Read a message, apply `edit` and write it back if the edit succeeds
+    async fn update_message<F>(&self, topic: &str, id: &str, edit: F) -> Result<Message>
+    where
+        F: FnOnce(&mut Message) -> Result<()>,
+    {
+        let message_file = secure_message_path(&self.data_dir, topic, id)
+            .map_err(|e| anyhow!("Invalid path parameters: {}", e))?;
+        if !message_file.exists() {
+            return Err(anyhow!("Message not found"));
+        }
+        let raw = fs::read_to_string(&message_file).await?;
+        let mut msg: Message = secure_json_parse(&raw, 1_000_000)
+            .map_err(|e| anyhow!("Corrupt message file: {:?}", e))?;
+        edit(&mut msg)?;
+        let json_content = serde_json::to_string_pretty(&msg)?;
+        Self::write_file_locked(&message_file, &json_content).await?;
+        Ok(msg)
+    }
+
+    /// Start a poll thread in `topic`. The question is posted as the thread body,
+    /// so the usual topic lock, mute and post level checks apply.
+    pub async fn create_poll(
+        &mut self,
+        topic: &str,
+        author: &str,
+        poll: polls::Poll,
+    ) -> Result<String> {
+        let id = self.store_message(topic, author, &poll.question).await?;
+        let topic = topic.to_lowercase();
+        self.update_message(&topic, &id, |m| {
+            m.poll = Some(poll);
+            Ok(())
+        })
+        .await?;
+        self.log_admin_action("POLL", Some(&topic), author, Some(&id))
+            .await?;
+        Ok(id)
+    }
+
+    /// Record a registered user's vote (`choice` is 1-based) and return the
+    /// updated poll
+    pub async fn vote_poll(
+        &self,
+        topic: &str,
+        id: &str,
+        username: &str,
+        choice: usize,
+    ) -> Result<polls::Poll> {
+        if self.get_user(username).await?.is_none() {
+            return Err(anyhow!("Only registered users can vote"));
+        }
+        let msg = self
+            .update_message(topic, id, |m| match m.poll.as_mut() {
+                Some(poll) => poll.vote(username, choice, Utc::now()),
+                None => Err(anyhow!("This thread is not a poll")),
+            })
+            .await?;
+        msg.poll.ok_or_else(|| anyhow!("This thread is not a poll"))
+    }
+
+    /// Close a poll early. `id` may be any unique prefix of the thread id.
+    /// Returns the closed thread, or `None` when no poll in the topic matches.
+    pub async fn close_poll(
+        &mut self,
+        topic: &str,
+        id: &str,
+        actor: &str,
+    ) -> Result<Option<Message>> {
+        let topic = validate_topic_name(topic).map_err(|e| anyhow!("Invalid topic name: {}", e))?;
+        if id.is_empty() {
+            return Ok(None);
+        }
+        let matches: Vec<Message> = self
+            .get_messages(&topic, usize::MAX)
+            .await?
+            .into_iter()
+            .filter(|m| m.poll.is_some() && m.id.starts_with(id))
+            .collect();
+        let thread_id = match matches.as_slice() {
+            [] => return Ok(None),
+            [m] => m.id.clone(),
+            _ => return Err(anyhow!("'{}' matches {} polls", id, matches.len())),
+        };
+        let now = Utc::now();
+        let msg = self
+            .update_message(&topic, &thread_id, |m| match m.poll.as_mut() {
+                Some(poll) if poll.is_open(now) => {
+                    poll.closed_at = Some(now);
+                    Ok(())
+                }
+                _ => Err(anyhow!("Poll already closed")),
+            })
+            .await?;
+        self.log_admin_action("POLLCLOSE", Some(&topic), actor, Some(&thread_id))
+            .await?;
+        Ok(Some(msg))
+    }
+
+    /// Newest open poll thread across the topics accepted by `can_read`
+    pub async fn latest_open_poll<F: Fn(&str) -> bool>(
+        &self,
+        can_read: F,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Message>> {
+        let mut latest: Option<Message> = None;
+        for topic in self.message_topic_dirs().await? {
+            if !can_read(&topic) {
+                continue;
+            }
+            for m in self.get_messages(&topic, 200).await? {
+                let open = m.poll.as_ref().is_some_and(|p| p.is_open(now));
+                if open && latest.as_ref().is_none_or(|l| m.timestamp > l.timestamp) {
+                    latest = Some(m);
+                }
+            }
+        }
+        Ok(latest)
+    }
+
+    fn mailbox_path(&self, username: &str) -> std::path::PathBuf {
+        Path::new(&self.data_dir)
+            .join("mail")
+            .join(format!("{}.json", safe_filename(username)))
+    }
+
+    async fn load_mailbox(&self, username: &str) -> Result<Mailbox> {
+        let path = self.mailbox_path(username);
+        if !path.exists() {
+            return Ok(Mailbox::default());
+        }
+        let metadata = fs::metadata(&path).await?;
+        validate_file_size(metadata.len(), 1_000_000)
+            .map_err(|e| anyhow!("Mailbox file too large: {:?}", e))?;
+        let content = fs::read_to_string(&path).await?;
+        secure_json_parse(&content, 1_000_000)
+            .map_err(|e| anyhow!("Failed to parse mailbox: {:?}", e))
+    }
+
+    async fn save_mailbox(&self, username: &str, mailbox: &Mailbox) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(mailbox)?;
+        Self::write_file_locked(&self.mailbox_path(username), &json_content).await
+    }
+
+    /// Send private mail from one registered user to another.
+    ///
+    /// The message lands in the recipient's inbox and a read copy is kept in the
+    /// sender's sent folder. A full inbox rejects the message; a full sent folder
+    /// drops its oldest copy instead.
+    pub async fn send_mail(&mut self, from: &str, to: &str, content: &str) -> Result<String> {
+        let recipient =
+            validate_user_name(to.trim()).map_err(|e| anyhow!("Invalid username: {}", e))?;
+        let recipient = match self.get_user(&recipient).await? {
+            Some(user) => user.username,
+            None => return Err(anyhow!("No such user: {}", recipient)),
+        };
+        if recipient.eq_ignore_ascii_case(from) {
+            return Err(anyhow!("You cannot mail yourself"));
+        }
+        let sanitized_content = sanitize_message_content(content, self.max_message_bytes)
+            .map_err(|e| anyhow!("Invalid message content: {}", e))?;
+        if sanitized_content.trim().is_empty() {
+            return Err(anyhow!("Message body required"));
+        }
+
+        let mut inbox = self.load_mailbox(&recipient).await?;
+        if inbox.inbox.len() >= self.mail_quota {
+            return Err(anyhow!("Mailbox full for {}", recipient));
+        }
+
+        let message = MailMessage {
+            id: Uuid::new_v4().to_string(),
+            from: from.to_string(),
+            to: recipient.clone(),
+            content: sanitized_content,
+            timestamp: Utc::now(),
+            read: false,
+        };
+        inbox.inbox.push(message.clone());
+        self.save_mailbox(&recipient, &inbox).await?;
+
+        let mut outbox = self.load_mailbox(from).await?;
+        outbox.sent.push(MailMessage {
+            read: true,
+            ..message.clone()
+        });
+        let overflow = outbox.sent.len().saturating_sub(self.mail_quota);
+        outbox.sent.drain(..overflow);
+        self.save_mailbox(from, &outbox).await?;
+
+        Ok(message.id)
+    }
+
+    /// List a mailbox folder, newest first
+    pub async fn get_mail(&self, username: &str, folder: MailFolder) -> Result<Vec<MailMessage>> {
+        let mailbox = self.load_mailbox(username).await?;
+        let mut messages = mailbox.folder(folder).clone();
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// Mark an inbox message as read; returns false when it does not exist
+    pub async fn mark_mail_read(&self, username: &str, id: &str) -> Result<bool> {
+        let mut mailbox = self.load_mailbox(username).await?;
+        match mailbox.inbox.iter_mut().find(|m| m.id == id) {
+            Some(message) if !message.read => {
+                message.read = true;
+                self.save_mailbox(username, &mailbox).await?;
+                Ok(true)
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    /// Delete a message from one of the user's folders
+    pub async fn delete_mail(&self, username: &str, folder: MailFolder, id: &str) -> Result<bool> {
+        let mut mailbox = self.load_mailbox(username).await?;
+        let messages = mailbox.folder_mut(folder);
+        let before = messages.len();
+        messages.retain(|m| m.id != id);
+        if messages.len() == before {
+            return Ok(false);
+        }
+        self.save_mailbox(username, &mailbox).await?;
+        Ok(true)
+    }
+
+    /// Number of unread messages in the user's inbox
+    pub async fn count_unread_mail(&self, username: &str) -> Result<u32> {
+        let mailbox = self.load_mailbox(username).await?;
+        Ok(mailbox.inbox.iter().filter(|m| !m.read).count() as u32)
+    }
+
+    /// Per-folder mailbox quota currently enforced
+    pub fn mail_quota(&self) -> usize {
+        self.mail_quota
+    }
+
+    fn subscriptions_path(&self, username: &str) -> std::path::PathBuf {
+        Path::new(&self.data_dir)
+            .join("subscriptions")
+            .join(format!("{}.json", safe_filename(&username.to_lowercase())))
+    }
+
+    /// A user's subscriptions (empty, starting now, when they have none)
+    pub async fn get_subscriptions(&self, username: &str) -> Result<subscriptions::Subscriptions> {
+        let path = self.subscriptions_path(username);
+        if !path.exists() {
+            return Ok(subscriptions::Subscriptions::new(Utc::now()));
+        }
+        let metadata = fs::metadata(&path).await?;
+        validate_file_size(metadata.len(), 1_000_000)
+            .map_err(|e| anyhow!("Subscriptions file too large: {:?}", e))?;
+        let content = fs::read_to_string(&path).await?;
+        secure_json_parse(&content, 1_000_000)
+            .map_err(|e| anyhow!("Failed to parse subscriptions: {:?}", e))
+    }
+
+    async fn save_subscriptions(
+        &self,
+        username: &str,
+        subs: &subscriptions::Subscriptions,
+    ) -> Result<()> {
+        let path = self.subscriptions_path(username);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let json_content = serde_json::to_string_pretty(subs)?;
+        Self::write_file_locked(&path, &json_content).await
+    }
+
+    /// Follow a thread or topic. Returns false when already following it.
+    pub async fn subscribe(
+        &self,
+        username: &str,
+        target: subscriptions::SubscriptionTarget,
+    ) -> Result<bool> {
+        let mut subs = self.get_subscriptions(username).await?;
+        if subs.targets.contains(&target) {
+            return Ok(false);
+        }
+        subs.targets.push(target);
+        self.save_subscriptions(username, &subs).await?;
+        Ok(true)
+    }
+
+    /// Stop following a thread or topic. Returns false when it was not followed.
+    pub async fn unsubscribe(
+        &self,
+        username: &str,
+        target: &subscriptions::SubscriptionTarget,
+    ) -> Result<bool> {
+        let mut subs = self.get_subscriptions(username).await?;
+        let before = subs.targets.len();
+        subs.targets.retain(|t| t != target);
+        if subs.targets.len() == before {
+            return Ok(false);
+        }
+        self.save_subscriptions(username, &subs).await?;
+        Ok(true)
+    }
+
+    /// Turn reply summaries on or off without touching subscriptions
+    pub async fn set_notifications(&self, username: &str, enabled: bool) -> Result<()> {
+        let mut subs = self.get_subscriptions(username).await?;
+        subs.muted = !enabled;
+        if enabled {
+            // Do not replay everything that happened while muted
+            subs.since = Utc::now();
+        }
+        self.save_subscriptions(username, &subs).await
+    }
+
+    /// Activity in a user's subscriptions after `since`, ignoring their own posts
+    pub async fn subscription_activity(
+        &self,
+        username: &str,
+        subs: &subscriptions::Subscriptions,
+    ) -> Result<Vec<subscriptions::ActivityLine>> {
+        use subscriptions::{ActivityLine, SubscriptionTarget};
+        let since = subs.since;
+        let by_others = |author: &str| !author.eq_ignore_ascii_case(username);
+        let new_replies = |m: &Message| {
+            m.replies
+                .iter()
+                .filter(|r| matches!(r, ReplyEntry::Reply(r) if r.timestamp > since && by_others(&r.author)))
+                .count()
+        };
+        let mut followed_threads = HashSet::new();
+        let mut lines = Vec::new();
+        for target in &subs.targets {
+            if let SubscriptionTarget::Thread { topic, id } = target {
+                followed_threads.insert(id.as_str());
+                let Some(message) = self.get_message(topic, id).await? else {
+                    continue;
+                };
+                let replies = new_replies(&message);
+                if replies > 0 {
+                    let title = message
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| message.content.chars().take(24).collect());
+                    lines.push(ActivityLine::Thread { title, replies });
+                }
+            }
+        }
+        for target in &subs.targets {
+            if let SubscriptionTarget::Topic(topic) = target {
+                let posts: usize = self
+                    .get_messages(topic, usize::MAX)
+                    .await?
+                    .iter()
+                    .filter(|m| !followed_threads.contains(m.id.as_str()))
+                    .map(|m| {
+                        let is_new = m.timestamp > since && by_others(&m.author);
+                        new_replies(m) + usize::from(is_new)
+                    })
+                    .sum();
+                if posts > 0 {
+                    lines.push(ActivityLine::Topic {
+                        topic: topic.clone(),
+                        posts,
+                    });
+                }
+            }
+        }
+        Ok(lines)
+    }
+
+    /// Build the reply summary DM for a user and mark it sent, or `None` when
+    /// there is nothing new, they opted out, or today's cap is used up
+    pub async fn take_reply_notification(
+        &self,
+        username: &str,
+        now: DateTime<Utc>,
+        max_bytes: usize,
+    ) -> Result<Option<String>> {
+        let path = self.subscriptions_path(username);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut subs = self.get_subscriptions(username).await?;
+        if subs.muted || subs.sent_on_day(now) >= self.notify_daily_cap {
+            return Ok(None);
+        }
+        let lines = self.subscription_activity(username, &subs).await?;
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        subs.record_sent(now);
+        self.save_subscriptions(username, &subs).await?;
+        Ok(Some(subscriptions::format_summary(&lines, max_bytes)))
+    }
+
+    /// The registered user bound to a node (see [`Storage::bind_user_node`])
+    pub async fn find_user_by_node(&self, node_id: &str) -> Result<Option<User>> {
+        Ok(self
+            .list_all_users()
+            .await?
+            .into_iter()
+            .find(|u| u.node_id.as_deref() == Some(node_id)))
+    }
+
+    /// Load the file library index (empty when nothing was uploaded yet)
+    pub async fn get_file_library(&self) -> Result<files::FileLibrary> {
+        let path = files::index_path(&self.data_dir);
+        if !path.exists() {
+            return Ok(files::FileLibrary::default());
+        }
+        let metadata = fs::metadata(&path).await?;
+        validate_file_size(metadata.len(), files::MAX_LIBRARY_INDEX_BYTES)
+            .map_err(|e| anyhow!("File library index too large: {:?}", e))?;
+        let content = fs::read_to_string(&path).await?;
+        secure_json_parse(&content, files::MAX_LIBRARY_INDEX_BYTES as usize)
+            .map_err(|e| anyhow!("Failed to parse file library index: {:?}", e))
+    }
+
+    async fn save_file_library(&self, library: &files::FileLibrary) -> Result<()> {
+        let json_content = serde_json::to_string_pretty(library)?;
+        Self::write_file_locked(&files::index_path(&self.data_dir), &json_content).await
+    }
+
+    /// Create a file area, or update the description and read level of an
+    /// existing one. Returns true when the area was created.
+    pub async fn set_file_area(
+        &self,
+        area: &str,
+        description: Option<&str>,
+        read_level: Option<u8>,
+        actor: &str,
+    ) -> Result<bool> {
+        let area = validate_topic_name(area)
+            .map_err(|e| anyhow!("Invalid area name: {}", e))?
+            .to_lowercase();
+        let mut library = self.get_file_library().await?;
+        let created = !library.areas.contains_key(&area);
+        let entry = library
+            .areas
+            .entry(area.clone())
+            .or_insert_with(|| files::FileArea {
+                description: String::new(),
+                read_level: 0,
+                created_by: actor.to_string(),
+                created_at: Utc::now(),
+                files: Vec::new(),
+            });
+        if let Some(description) = description {
+            entry.description = description.trim().to_string();
+        }
+        if let Some(level) = read_level {
+            entry.read_level = level;
+        }
+        let details = format!("read={}", entry.read_level);
+        fs::create_dir_all(Path::new(&self.data_dir).join("files").join(&area)).await?;
+        self.save_file_library(&library).await?;
+        self.log_admin_action("FILEAREA", Some(&area), actor, Some(&details))
+            .await?;
+        Ok(created)
+    }
+
+    /// Add a text file to an area, replacing any file of the same name, or
+    /// append to it when `append` is set. The result must stay within
+    /// [`files::MAX_LIBRARY_FILE_BYTES`].
+    pub async fn add_library_file(
+        &self,
+        area: &str,
+        name: &str,
+        content: &str,
+        append: bool,
+        actor: &str,
+    ) -> Result<files::LibraryFile> {
+        let area = area.trim().to_lowercase();
+        let name = validate_topic_name(name)
+            .map_err(|e| anyhow!("Invalid file name: {}", e))?
+            .to_lowercase();
+        let mut library = self.get_file_library().await?;
+        let Some(entry) = library.areas.get_mut(&area) else {
+            return Err(anyhow!("No file area '{}'", area));
+        };
+        let path = files::file_path(&self.data_dir, &area, &name);
+        let mut text = String::new();
+        if append && entry.file(&name).is_some() {
+            text = self.read_library_file(&area, &name).await?;
+            if !text.is_empty() && !text.ends_with('\n') {
+                text.push('\n');
+            }
+        }
+        text.push_str(
+            &sanitize_message_content(content, files::MAX_LIBRARY_FILE_BYTES as usize)
+                .map_err(|e| anyhow!("Invalid file content: {}", e))?,
+        );
+        validate_file_size(text.len() as u64, files::MAX_LIBRARY_FILE_BYTES)
+            .map_err(|e| anyhow!("{}", e))?;
+        if text.trim().is_empty() {
+            return Err(anyhow!("File content required"));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        Self::write_file_locked(&path, &text).await?;
+
+        let file = files::LibraryFile {
+            name: name.clone(),
+            size: text.len() as u64,
+            uploaded_by: actor.to_string(),
+            uploaded_at: Utc::now(),
+            downloads: entry.file(&name).map(|f| f.downloads).unwrap_or(0),
+        };
+        entry.files.retain(|f| f.name != name);
+        entry.files.push(file.clone());
+        entry.files.sort_by(|a, b| a.name.cmp(&b.name));
+        self.save_file_library(&library).await?;
+        let details = format!("{} bytes", file.size);
+        self.log_admin_action(
+            if append { "FILEAPPEND" } else { "FILEADD" },
+            Some(&format!("{}/{}", area, name)),
+            actor,
+            Some(&details),
+        )
+        .await?;
+        Ok(file)
+    }
+
+    /// Remove a file from an area. Returns false when it does not exist.
+    pub async fn delete_library_file(&self, area: &str, name: &str, actor: &str) -> Result<bool> {
+        let area = area.trim().to_lowercase();
+        let name = name.trim().to_lowercase();
+        let mut library = self.get_file_library().await?;
+        let Some(entry) = library.areas.get_mut(&area) else {
+            return Ok(false);
+        };
+        let before = entry.files.len();
+        entry.files.retain(|f| f.name != name);
+        if entry.files.len() == before {
+            return Ok(false);
+        }
+        self.save_file_library(&library).await?;
+        let path = files::file_path(&self.data_dir, &area, &name);
+        if path.exists() {
+            fs::remove_file(&path).await?;
+        }
+        self.log_admin_action("FILEDEL", Some(&format!("{}/{}", area, name)), actor, None)
+            .await?;
+        Ok(true)
+    }
+
+    /// Text of a library file listed in the index
+    pub async fn read_library_file(&self, area: &str, name: &str) -> Result<String> {
+        let area = validate_topic_name(area).map_err(|e| anyhow!("Invalid area name: {}", e))?;
+        let name = validate_topic_name(name).map_err(|e| anyhow!("Invalid file name: {}", e))?;
+        let path = files::file_path(&self.data_dir, &area, &name);
+        let metadata = fs::metadata(&path).await?;
+        validate_file_size(metadata.len(), files::MAX_LIBRARY_FILE_BYTES)
+            .map_err(|e| anyhow!("Library file too large: {:?}", e))?;
+        Ok(fs::read_to_string(&path).await?)
+    }
+
+    /// Count one download of a library file
+    pub async fn record_file_download(&self, area: &str, name: &str) -> Result<()> {
+        let mut library = self.get_file_library().await?;
+        if let Some(file) = library
+            .areas
+            .get_mut(area)
+            .and_then(|entry| entry.file_mut(name))
+        {
+            file.downloads = file.downloads.saturating_add(1);
+            self.save_file_library(&library).await?;
+        }
         Ok(())
     }
 }
@@ -1621,3 +3019,9 @@ fn is_false(b: &bool) -> bool {
 /// Backup & Recovery System
 pub mod backup;
 pub mod backup_scheduler;
+pub mod files;
+pub mod index;
+pub mod moderation;
+pub mod polls;
+pub mod retention;
+pub mod subscriptions;
@@ -0,0 +1,219 @@
+//! Moderation lists: bans, posting mutes and public-channel ignores
+//!
+//! Persisted as `data/moderation.json` and loaded into [`Storage`](super::Storage)
+//! at startup, the same way locked topics are. Entries with an `expires_at` in
+//! the past are treated as absent and dropped on the next save.
+//!
+//! - **Bans** keep a username or a Meshtastic node id out entirely: DMs from a
+//!   banned node are dropped before a session is created and a banned user
+//!   cannot log in
+//! - **Mutes** block posting and replying, either in one topic or everywhere
+//! - **Ignores** make the public channel command handlers skip a node
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Who a ban applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BanTarget {
+    User(String),
+    Node(u32),
+}
+
+impl BanTarget {
+    /// Parse a moderator-supplied target: node ids (`!a1b2c3d4`, `0x...` or
+    /// decimal) become node bans, anything else is a username
+    pub fn parse(raw: &str) -> Self {
+        match parse_node_id(raw) {
+            Some(id) => BanTarget::Node(id),
+            None => BanTarget::User(raw.to_string()),
+        }
+    }
+
+    fn matches(&self, other: &BanTarget) -> bool {
+        match (self, other) {
+            (BanTarget::User(a), BanTarget::User(b)) => a.eq_ignore_ascii_case(b),
+            (BanTarget::Node(a), BanTarget::Node(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BanTarget::User(name) => write!(f, "{}", name),
+            BanTarget::Node(id) => write!(f, "!{:08x}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub target: BanTarget,
+    pub actor: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// None means permanent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuteEntry {
+    pub username: String,
+    /// None mutes the user in every topic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    pub actor: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+fn is_active(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    expires_at.is_none_or(|t| t > now)
+}
+
+impl BanEntry {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        is_active(self.expires_at, now)
+    }
+
+    /// What the banned party is told when turned away
+    pub fn notice(&self) -> String {
+        let mut out = String::from("You are banned");
+        if let Some(t) = self.expires_at {
+            out.push_str(&format!(" until {}", t.format("%Y-%m-%d %H:%M UTC")));
+        }
+        if let Some(r) = &self.reason {
+            out.push_str(&format!(": {}", r));
+        }
+        out.push_str(".\n");
+        out
+    }
+}
+
+impl MuteEntry {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        is_active(self.expires_at, now)
+    }
+}
+
+/// On-disk layout of `moderation.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModerationList {
+    #[serde(default)]
+    pub bans: Vec<BanEntry>,
+    #[serde(default)]
+    pub mutes: Vec<MuteEntry>,
+    #[serde(default)]
+    pub ignored_nodes: Vec<u32>,
+}
+
+impl ModerationList {
+    /// Active ban matching `target`, if any
+    pub fn ban_for(&self, target: &BanTarget, now: DateTime<Utc>) -> Option<&BanEntry> {
+        self.bans
+            .iter()
+            .find(|b| b.target.matches(target) && b.is_active(now))
+    }
+
+    /// Add or replace the ban for `entry.target`
+    pub fn add_ban(&mut self, entry: BanEntry) {
+        self.bans.retain(|b| !b.target.matches(&entry.target));
+        self.bans.push(entry);
+    }
+
+    /// Remove the ban for `target`; true if an active one existed
+    pub fn remove_ban(&mut self, target: &BanTarget, now: DateTime<Utc>) -> bool {
+        let had = self.ban_for(target, now).is_some();
+        self.bans.retain(|b| !b.target.matches(target));
+        had
+    }
+
+    /// Active mute preventing `username` from posting in `topic`
+    pub fn mute_for(&self, username: &str, topic: &str, now: DateTime<Utc>) -> Option<&MuteEntry> {
+        self.mutes.iter().find(|m| {
+            m.username.eq_ignore_ascii_case(username)
+                && m.topic.as_deref().is_none_or(|t| t == topic)
+                && m.is_active(now)
+        })
+    }
+
+    /// Add or replace the mute for `entry.username` in `entry.topic`
+    pub fn add_mute(&mut self, entry: MuteEntry) {
+        self.mutes.retain(|m| {
+            !(m.username.eq_ignore_ascii_case(&entry.username) && m.topic == entry.topic)
+        });
+        self.mutes.push(entry);
+    }
+
+    /// Remove the mute for `username` in `topic` (None = the global mute);
+    /// true if an active one existed
+    pub fn remove_mute(&mut self, username: &str, topic: Option<&str>, now: DateTime<Utc>) -> bool {
+        let matches = |m: &MuteEntry| {
+            m.username.eq_ignore_ascii_case(username) && m.topic.as_deref() == topic
+        };
+        let had = self.mutes.iter().any(|m| matches(m) && m.is_active(now));
+        self.mutes.retain(|m| !matches(m));
+        had
+    }
+
+    /// Drop expired bans and mutes; true if anything was removed
+    pub fn prune_expired(&mut self, now: DateTime<Utc>) -> bool {
+        let before = self.bans.len() + self.mutes.len();
+        self.bans.retain(|b| b.is_active(now));
+        self.mutes.retain(|m| m.is_active(now));
+        before != self.bans.len() + self.mutes.len()
+    }
+}
+
+/// Parse a node id written as `!a1b2c3d4`, `0xa1b2c3d4` or plain decimal
+pub fn parse_node_id(raw: &str) -> Option<u32> {
+    let raw = raw.trim();
+    if let Some(hex) = raw.strip_prefix('!') {
+        return u32::from_str_radix(hex, 16).ok();
+    }
+    if let Some(hex) = raw.strip_prefix("0x").or_else(|| raw.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16).ok();
+    }
+    if !raw.is_empty() && raw.bytes().all(|b| b.is_ascii_digit()) {
+        return raw.parse().ok();
+    }
+    None
+}
+
+/// Parse a ban/mute length such as `30m`, `12h`, `7d` or `2w`
+pub fn parse_duration(raw: &str) -> Option<Duration> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let unit = raw.chars().last()?;
+    let value: i64 = raw[..raw.len() - unit.len_utf8()].parse().ok()?;
+    if !(1..=100_000).contains(&value) {
+        return None;
+    }
+    match unit {
+        'm' => Some(Duration::minutes(value)),
+        'h' => Some(Duration::hours(value)),
+        'd' => Some(Duration::days(value)),
+        'w' => Some(Duration::weeks(value)),
+        _ => None,
+    }
+}
+
+/// Compact length label for replies and listings: `perm`, `45m`, `3h`, `2d`
+/// (rounded to the nearest unit)
+pub fn format_span(span: Option<Duration>) -> String {
+    let Some(span) = span else {
+        return "perm".into();
+    };
+    let secs = span.num_seconds().max(60);
+    match secs {
+        0..=3569 => format!("{}m", (secs + 30) / 60),
+        3570..=86399 => format!("{}h", (secs + 1800) / 3600),
+        _ => format!("{}d", (secs + 43200) / 86400),
+    }
+}
@@ -0,0 +1,251 @@
+//! Poll threads: a topic thread with up to five options to vote on
+//!
+//! The poll is stored with its thread (`Message.poll`), so votes persist in the
+//! message JSON alongside replies. Each registered user votes once; polls close
+//! when a moderator closes them or when their optional close time passes.
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 5;
+/// Longest question, in bytes
+pub const MAX_POLL_QUESTION: usize = 64;
+/// Longest option label, in bytes
+pub const MAX_POLL_OPTION: usize = 24;
+/// Rendered results always fit one frame
+pub const POLL_RESULTS_MAX_BYTES: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Poll {
+    pub question: String,
+    pub options: Vec<String>,
+    /// Voter username to zero-based option index
+    #[serde(default)]
+    pub votes: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closes_at: Option<DateTime<Utc>>,
+    /// Set when a moderator closes the poll
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+impl Poll {
+    /// Validate and build a poll; options are trimmed and must be distinct
+    pub fn new(question: &str, options: &[&str], closes_at: Option<DateTime<Utc>>) -> Result<Self> {
+        let question = question.trim();
+        if question.is_empty() {
+            return Err(anyhow!("Question required"));
+        }
+        if question.len() > MAX_POLL_QUESTION {
+            return Err(anyhow!(
+                "Question too long (max {} bytes)",
+                MAX_POLL_QUESTION
+            ));
+        }
+        let options: Vec<String> = options
+            .iter()
+            .map(|o| o.trim().to_string())
+            .filter(|o| !o.is_empty())
+            .collect();
+        if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&options.len()) {
+            return Err(anyhow!(
+                "A poll needs {}-{} options",
+                MIN_POLL_OPTIONS,
+                MAX_POLL_OPTIONS
+            ));
+        }
+        if let Some(long) = options.iter().find(|o| o.len() > MAX_POLL_OPTION) {
+            return Err(anyhow!(
+                "Option '{}' too long (max {} bytes)",
+                long,
+                MAX_POLL_OPTION
+            ));
+        }
+        for (i, option) in options.iter().enumerate() {
+            if options[..i].iter().any(|o| o.eq_ignore_ascii_case(option)) {
+                return Err(anyhow!("Duplicate option '{}'", option));
+            }
+        }
+        Ok(Poll {
+            question: question.to_string(),
+            options,
+            votes: BTreeMap::new(),
+            closes_at,
+            closed_at: None,
+        })
+    }
+
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.closed_at.is_none() && self.closes_at.is_none_or(|t| now < t)
+    }
+
+    /// Record `user`'s vote for option `choice` (1-based)
+    pub fn vote(&mut self, user: &str, choice: usize, now: DateTime<Utc>) -> Result<()> {
+        if !self.is_open(now) {
+            return Err(anyhow!("This poll is closed"));
+        }
+        if choice == 0 || choice > self.options.len() {
+            return Err(anyhow!("Choose 1-{}", self.options.len()));
+        }
+        if let Some(prev) = self.votes.get(user) {
+            return Err(anyhow!("You already voted for {}", prev + 1));
+        }
+        self.votes.insert(user.to_string(), choice - 1);
+        Ok(())
+    }
+
+    /// Votes per option, in option order
+    pub fn tally(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.options.len()];
+        for &choice in self.votes.values() {
+            if let Some(c) = counts.get_mut(choice) {
+                *c += 1;
+            }
+        }
+        counts
+    }
+
+    /// Question, one `n label count` entry per option and a status trailer, joined
+    /// by `sep`. Labels (then the question) are shortened until the result fits
+    /// `max_bytes`. Once closed, the leading option(s) are marked with `*`.
+    pub fn render(&self, now: DateTime<Utc>, sep: &str, max_bytes: usize) -> String {
+        let counts = self.tally();
+        let total: usize = counts.iter().sum();
+        let open = self.is_open(now);
+        let lead = counts.iter().copied().max().unwrap_or(0);
+        let status = match (open, self.closes_at) {
+            (true, Some(t)) => format!("ends {}", t.format("%m/%d %H:%M")),
+            (true, None) => "open".to_string(),
+            (false, _) => "closed".to_string(),
+        };
+        let trailer = format!(
+            "({} vote{}, {})",
+            total,
+            if total == 1 { "" } else { "s" },
+            status
+        );
+        let build = |q_max: usize, o_max: usize| {
+            let mut parts = vec![clip(&self.question, q_max).to_string()];
+            for (i, (option, count)) in self.options.iter().zip(&counts).enumerate() {
+                let mark = if !open && total > 0 && *count == lead {
+                    "*"
+                } else {
+                    ""
+                };
+                parts.push(format!(
+                    "{} {}{} {}",
+                    i + 1,
+                    clip(option, o_max),
+                    mark,
+                    count
+                ));
+            }
+            parts.push(trailer.clone());
+            parts.join(sep)
+        };
+        let (mut q_max, mut o_max) = (MAX_POLL_QUESTION, MAX_POLL_OPTION);
+        loop {
+            let out = build(q_max, o_max);
+            if out.len() <= max_bytes {
+                return out;
+            }
+            if o_max > 6 {
+                o_max -= 1;
+            } else if q_max > 12 {
+                q_max -= 1;
+            } else {
+                return clip(&out, max_bytes).to_string();
+            }
+        }
+    }
+}
+
+/// Parse a close time such as `48h` or `3d`
+pub fn parse_duration(token: &str) -> Option<chrono::Duration> {
+    let num = token.get(..token.len().checked_sub(1)?)?;
+    let n: i64 = num.parse().ok().filter(|n| *n > 0 && *n <= 24 * 90)?;
+    match token.get(num.len()..)? {
+        "h" | "H" => Some(chrono::Duration::hours(n)),
+        "d" | "D" if n <= 90 => Some(chrono::Duration::days(n)),
+        _ => None,
+    }
+}
+
+fn clip(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn one_vote_per_user_and_results_fit_a_frame() {
+        let now = Utc::now();
+        let err = Poll::new(
+            "Net night?",
+            &["Tuesday after the ARES net", "Monday"],
+            None,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.starts_with("Option 'Tuesday after the ARES net' too long"));
+        assert!(Poll::new("Net night?", &["Monday", "monday"], None).is_err());
+        assert!(Poll::new("Net night?", &["Monday"], None).is_err());
+
+        let options = [
+            "Tuesday after ARES",
+            "Wednesday",
+            "Thursday evening",
+            "Sunday afternoon",
+            "Keep Monday",
+        ];
+        let mut poll = Poll::new(
+            "Which night should the weekly club net move to next month?",
+            &options,
+            Some(now + Duration::hours(48)),
+        )
+        .unwrap();
+        poll.vote("alice", 2, now).unwrap();
+        poll.vote("bob", 2, now).unwrap();
+        poll.vote("carol", 5, now).unwrap();
+        assert_eq!(
+            poll.vote("alice", 1, now).unwrap_err().to_string(),
+            "You already voted for 2"
+        );
+        assert!(poll.vote("dave", 6, now).is_err());
+        assert_eq!(poll.tally(), vec![0, 2, 0, 0, 1]);
+
+        let open = poll.render(now, "\n", POLL_RESULTS_MAX_BYTES);
+        assert!(open.len() <= POLL_RESULTS_MAX_BYTES, "{}", open);
+        assert!(open.contains("\n2 Wednesday 2\n"), "{}", open);
+        assert!(open.contains("(3 votes, ends "), "{}", open);
+
+        let later = now + Duration::hours(49);
+        assert!(poll.vote("dave", 1, later).is_err());
+        let closed = poll.render(later, " | ", 120);
+        assert!(closed.len() <= 120, "{}", closed);
+        assert!(closed.contains("Wednes"), "{}", closed);
+        assert!(closed.contains("* 2"), "{}", closed);
+        assert!(closed.ends_with("(3 votes, closed)"), "{}", closed);
+    }
+
+    #[test]
+    fn close_times_parse_hours_and_days() {
+        assert_eq!(parse_duration("48h"), Some(Duration::hours(48)));
+        assert_eq!(parse_duration("3D"), Some(Duration::days(3)));
+        assert_eq!(parse_duration("0h"), None);
+        assert_eq!(parse_duration("Tuesday"), None);
+        assert_eq!(parse_duration("h"), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Per-topic retention: moving old threads into a compressed archive
+//!
+//! A topic with a [`TopicRetention`] policy in `topics.json` is swept
+//! periodically by the server. Threads that have been quiet longer than
+//! `max_age_days`, or that fall outside the `max_threads` most recently active,
+//! are moved out of `messages/<topic>/` into `archive/<topic>.tar.gz` (one JSON
+//! entry per thread, the same tar/flate2 format as [`backup`](super::backup)).
+//! Moderators can list the archive and restore threads from it.
+use super::{Message, ReplyEntry};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tar::{Archive, Builder, Header};
+
+/// How often the server sweeps topics with a retention policy
+pub const RETENTION_SWEEP_SECONDS: u64 = 3600;
+
+/// Retention policy for one topic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopicRetention {
+    /// Archive threads with no new post or reply for this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    /// Keep at most this many threads (pinned ones included), archiving the
+    /// least recently active first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_threads: Option<usize>,
+    /// Never archive pinned threads
+    #[serde(default = "default_keep_pinned")]
+    pub keep_pinned: bool,
+}
+
+fn default_keep_pinned() -> bool {
+    true
+}
+
+impl Default for TopicRetention {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            max_threads: None,
+            keep_pinned: true,
+        }
+    }
+}
+
+impl TopicRetention {
+    /// True when neither limit is set, so nothing would ever expire
+    pub fn is_unlimited(&self) -> bool {
+        self.max_age_days.is_none() && self.max_threads.is_none()
+    }
+}
+
+impl std::fmt::Display for TopicRetention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(days) = self.max_age_days {
+            parts.push(format!("maxage={}d", days));
+        }
+        if let Some(max) = self.max_threads {
+            parts.push(format!("maxthreads={}", max));
+        }
+        if parts.is_empty() {
+            parts.push("unlimited".to_string());
+        }
+        if !self.keep_pinned {
+            parts.push("pinned=archive".to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Time of the newest post or reply in a thread
+pub fn last_activity(message: &Message) -> DateTime<Utc> {
+    message
+        .replies
+        .iter()
+        .filter_map(|r| match r {
+            ReplyEntry::Reply(reply) => Some(reply.timestamp),
+            ReplyEntry::Legacy(_) => None,
+        })
+        .fold(message.timestamp, |a, b| a.max(b))
+}
+
+/// Threads in `messages` (any order) that `policy` expires at `now`
+pub fn expired<'a>(
+    policy: &TopicRetention,
+    messages: &'a [Message],
+    now: DateTime<Utc>,
+) -> Vec<&'a Message> {
+    let kept_pinned = messages
+        .iter()
+        .filter(|m| m.pinned && policy.keep_pinned)
+        .count();
+    let mut candidates: Vec<&Message> = messages
+        .iter()
+        .filter(|m| !(m.pinned && policy.keep_pinned))
+        .collect();
+    candidates.sort_by_key(|m| std::cmp::Reverse(last_activity(m)));
+    let room = policy
+        .max_threads
+        .map(|max| max.saturating_sub(kept_pinned))
+        .unwrap_or(usize::MAX);
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - Duration::days(days as i64));
+    candidates
+        .into_iter()
+        .enumerate()
+        .filter(|(i, m)| *i >= room || cutoff.is_some_and(|c| last_activity(m) < c))
+        .map(|(_, m)| m)
+        .collect()
+}
+
+/// Archive file holding a topic's expired threads
+pub fn archive_path(data_dir: &str, topic: &str) -> PathBuf {
+    Path::new(data_dir)
+        .join("archive")
+        .join(format!("{}.tar.gz", topic))
+}
+
+/// Read every thread from an archive (empty when it does not exist yet)
+pub fn read_archive(path: &Path) -> Result<Vec<Message>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut archive = Archive::new(GzDecoder::new(std::fs::File::open(path)?));
+    let mut messages = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let mut raw = String::new();
+        entry.read_to_string(&mut raw)?;
+        messages.push(serde_json::from_str(&raw)?);
+    }
+    Ok(messages)
+}
+
+/// Replace an archive with `messages`, removing the file when there are none
+pub fn write_archive(path: &Path, messages: &[Message]) -> Result<()> {
+    if messages.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write next to the archive and rename so a crash never leaves it half written
+    let tmp = path.with_extension("gz.tmp");
+    let mut tar = Builder::new(GzEncoder::new(
+        std::fs::File::create(&tmp)?,
+        Compression::default(),
+    ));
+    for message in messages {
+        let data = serde_json::to_vec_pretty(message)?;
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(last_activity(message).timestamp().max(0) as u64);
+        header.set_cksum();
+        tar.append_data(&mut header, format!("{}.json", message.id), data.as_slice())?;
+    }
+    tar.into_inner()?.finish()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// [`read_archive`] on the blocking pool, for async callers
+pub async fn load_archive(path: PathBuf) -> Result<Vec<Message>> {
+    tokio::task::spawn_blocking(move || read_archive(&path)).await?
+}
+
+/// [`write_archive`] on the blocking pool, for async callers
+pub async fn store_archive(path: PathBuf, messages: Vec<Message>) -> Result<()> {
+    tokio::task::spawn_blocking(move || write_archive(&path, &messages)).await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Reply;
+
+    fn thread(id: &str, days_old: i64, pinned: bool, now: DateTime<Utc>) -> Message {
+        Message {
+            id: id.to_string(),
+            topic: "general".to_string(),
+            author: "alice".to_string(),
+            title: None,
+            content: id.to_string(),
+            timestamp: now - Duration::days(days_old),
+            replies: Vec::new(),
+            pinned,
+            message_id: None,
+            crc16: None,
+            poll: None,
+        }
+    }
+
+    fn ids(messages: Vec<&Message>) -> Vec<&str> {
+        messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn age_and_count_limits_skip_pinned_and_recent_replies() {
+        let now = Utc::now();
+        let mut revived = thread("revived", 40, false, now);
+        revived.replies.push(ReplyEntry::Reply(Reply {
+            author: "bob".to_string(),
+            timestamp: now - Duration::days(1),
+            content: "still here".to_string(),
+        }));
+        let messages = vec![
+            thread("new", 2, false, now),
+            thread("old", 40, false, now),
+            thread("pinned", 90, true, now),
+            revived,
+            thread("older", 50, false, now),
+        ];
+
+        let by_age = TopicRetention {
+            max_age_days: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(ids(expired(&by_age, &messages, now)), vec!["old", "older"]);
+
+        // The pinned thread takes one of the three slots
+        let by_count = TopicRetention {
+            max_threads: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(expired(&by_count, &messages, now)),
+            vec!["old", "older"]
+        );
+
+        let everything = TopicRetention {
+            max_age_days: Some(30),
+            keep_pinned: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(expired(&everything, &messages, now)),
+            vec!["old", "older", "pinned"]
+        );
+        assert!(expired(&TopicRetention::default(), &messages, now).is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Reply notifications: per-user thread and topic subscriptions
+//!
+//! Persisted as `data/subscriptions/<user>.json`. Posting a thread subscribes
+//! its author; users can also follow other threads or whole topics. When a
+//! subscriber logs in or their bound node is heard on the mesh, the server
+//! sends one compact DM summarising activity since the last summary
+//! ([`Storage::take_reply_notification`](super::Storage::take_reply_notification)),
+//! at most [`DEFAULT_NOTIFY_DAILY_CAP`] times a day unless the user opts out.
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Default number of reply summaries a user receives per UTC day
+pub const DEFAULT_NOTIFY_DAILY_CAP: u32 = 3;
+
+/// Something a user follows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionTarget {
+    /// Replies to one thread
+    Thread { topic: String, id: String },
+    /// New threads and replies anywhere in a topic
+    Topic(String),
+}
+
+/// One user's subscriptions and notification bookkeeping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscriptions {
+    #[serde(default)]
+    pub targets: Vec<SubscriptionTarget>,
+    /// Set by `NOTIFY OFF`; subscriptions are kept but no summaries are sent
+    #[serde(default, skip_serializing_if = "super::is_false")]
+    pub muted: bool,
+    /// Activity after this instant has not been summarised yet
+    pub since: DateTime<Utc>,
+    /// UTC day the `sent_today` counter applies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub sent_today: u32,
+}
+
+impl Subscriptions {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            targets: Vec::new(),
+            muted: false,
+            since: now,
+            sent_on: None,
+            sent_today: 0,
+        }
+    }
+
+    /// Summaries already sent on `now`'s UTC day
+    pub fn sent_on_day(&self, now: DateTime<Utc>) -> u32 {
+        if self.sent_on == Some(now.date_naive()) {
+            self.sent_today
+        } else {
+            0
+        }
+    }
+
+    /// Count a summary sent at `now` and start the next one from there
+    pub fn record_sent(&mut self, now: DateTime<Utc>) {
+        self.sent_today = self.sent_on_day(now) + 1;
+        self.sent_on = Some(now.date_naive());
+        self.since = now;
+    }
+}
+
+/// One line of a reply summary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ActivityLine {
+    /// New replies to a followed thread
+    Thread { title: String, replies: usize },
+    /// New threads and replies in a followed topic
+    Topic { topic: String, posts: usize },
+}
+
+/// Render a summary DM of at most `max_bytes`; lines that do not fit are
+/// folded into a trailing "+N more"
+pub fn format_summary(lines: &[ActivityLine], max_bytes: usize) -> String {
+    const HEADER: &str = "New since your last visit:\n";
+    const FOOTER: &str = "NOTIFY OFF to stop\n";
+    let mut out = String::from(HEADER);
+    for (i, line) in lines.iter().enumerate() {
+        let text = match line {
+            ActivityLine::Thread { title, replies } => {
+                let title: String = title.chars().take(24).collect();
+                format!("{} ({} repl{})\n", title, replies, plural_y(*replies))
+            }
+            ActivityLine::Topic { topic, posts } => format!("[{}] {} new\n", topic, posts),
+        };
+        let more = lines.len() - i - 1;
+        // Leave room for the "+N more" line unless this is the last one
+        let reserve = if more > 0 { 12 } else { 0 };
+        if out.len() + text.len() + reserve + FOOTER.len() > max_bytes {
+            out.push_str(&format!("+{} more\n", lines.len() - i));
+            break;
+        }
+        out.push_str(&text);
+    }
+    out.push_str(FOOTER);
+    out
+}
+
+fn plural_y(n: usize) -> &'static str {
+    if n == 1 {
+        "y"
+    } else {
+        "ies"
+    }
+}
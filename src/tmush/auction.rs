@@ -0,0 +1,398 @@
+//! Auction house: asynchronous consignment sales between players
+//!
+//! Trades need both players online at once, which rarely works over a slow
+//! mesh. An [`AuctionListing`] instead sits in the `tinymush_auctions` sled
+//! tree until it ends, and every step works while the other side is offline.
+//!
+//! - Listing charges [`WorldConfig::auction_listing_fee`] (kept even if the
+//!   listing is cancelled) and takes the items out of the seller's inventory
+//! - Each bid is escrowed in an `auction:<id>` account; being outbid refunds
+//!   the previous high bidder straight away and tells them by mail
+//! - A bid at or above the buyout price settles at once. Otherwise the server
+//!   calls [`settle_due`] periodically: the seller is paid and the buyer gets
+//!   the items, or unsold items go back to the seller
+//!
+//! Every outcome is delivered through TinyMUSH mail so it is waiting for
+//! players when they next log in.
+//!
+//! [`WorldConfig::auction_listing_fee`]: crate::tmush::types::WorldConfig::auction_listing_fee
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{
+    AuctionBid, AuctionListing, CurrencyAmount, InventoryConfig, InventoryResult, MailMessage,
+    ObjectFlag, OwnershipReason, OwnershipTransfer, TransactionReason,
+};
+use crate::tmush::TinyMushError;
+
+/// Listing length when `AUCTION SELL` is not given one
+pub const DEFAULT_DURATION_HOURS: i64 = 24;
+/// Longest listing allowed (one week)
+pub const MAX_DURATION_HOURS: i64 = 168;
+
+const AUCTION_SCHEMA_VERSION: u8 = 1;
+
+/// Why a listing or bid was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuctionRefusal {
+    NotOwned,
+    NotTradeable,
+    BadDuration,
+    BadPrice,
+    CantAffordFee(i64),
+    OwnListing,
+    Ended,
+    BidTooLow(i64),
+    NoBuyout,
+    HasBids,
+    NotSeller,
+    InsufficientFunds,
+}
+
+impl std::fmt::Display for AuctionRefusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotOwned => write!(f, "You don't have that many."),
+            Self::NotTradeable => write!(f, "Quest and key items can't be auctioned."),
+            Self::BadDuration => write!(f, "Auctions last 1 to {} hours.", MAX_DURATION_HOURS),
+            Self::BadPrice => write!(f, "The buyout must be at least the minimum bid."),
+            Self::CantAffordFee(fee) => write!(f, "You can't afford the {} listing fee.", fee),
+            Self::OwnListing => write!(f, "You can't bid on your own listing."),
+            Self::Ended => write!(f, "That auction has ended."),
+            Self::BidTooLow(min) => write!(f, "Bids must be at least {}.", min),
+            Self::NoBuyout => write!(f, "That listing has no buyout price."),
+            Self::HasBids => write!(f, "Listings with bids can't be cancelled."),
+            Self::NotSeller => write!(f, "That's not your listing."),
+            Self::InsufficientFunds => write!(f, "You don't have that much."),
+        }
+    }
+}
+
+/// What a bid did to the listing
+#[derive(Debug, Clone, PartialEq)]
+pub enum BidOutcome {
+    /// Now the high bidder
+    Leading,
+    /// Reached the buyout price and won the auction
+    Won,
+}
+
+/// Amount in the same currency system as `like`
+pub fn amount_like(like: &CurrencyAmount, base_units: i64) -> CurrencyAmount {
+    match like {
+        CurrencyAmount::Decimal { .. } => CurrencyAmount::decimal(base_units),
+        CurrencyAmount::MultiTier { .. } => CurrencyAmount::multi_tier(base_units),
+    }
+}
+
+fn escrow_account(listing: &AuctionListing) -> String {
+    format!("auction:{}", listing.id)
+}
+
+fn describe(listing: &AuctionListing) -> String {
+    if listing.quantity > 1 {
+        format!("{} x{}", listing.item_name, listing.quantity)
+    } else {
+        listing.item_name.clone()
+    }
+}
+
+fn mail(
+    store: &TinyMushStore,
+    recipient: &str,
+    subject: &str,
+    body: &str,
+) -> Result<(), TinyMushError> {
+    store
+        .send_mail(MailMessage::new("auction", recipient, subject, body))
+        .map(|_| ())
+}
+
+/// Put `quantity` of the seller's `object_id` up for auction.
+///
+/// Returns the new listing, or why it was refused. Nothing is charged or
+/// taken unless the listing is created.
+pub fn create_listing(
+    store: &TinyMushStore,
+    seller: &str,
+    object_id: &str,
+    quantity: u32,
+    min_bid: i64,
+    buyout: Option<i64>,
+    hours: i64,
+) -> Result<Result<AuctionListing, AuctionRefusal>, TinyMushError> {
+    let player = store.get_player(seller)?;
+    if quantity == 0 || !crate::tmush::inventory::has_item(&player, object_id, quantity) {
+        return Ok(Err(AuctionRefusal::NotOwned));
+    }
+    let object = store.get_object(object_id)?;
+    if object
+        .flags
+        .iter()
+        .any(|f| matches!(f, ObjectFlag::QuestItem | ObjectFlag::KeyItem))
+    {
+        return Ok(Err(AuctionRefusal::NotTradeable));
+    }
+    if !(1..=MAX_DURATION_HOURS).contains(&hours) {
+        return Ok(Err(AuctionRefusal::BadDuration));
+    }
+    if min_bid <= 0 || buyout.is_some_and(|b| b < min_bid) {
+        return Ok(Err(AuctionRefusal::BadPrice));
+    }
+
+    let fee = store.get_world_config()?.auction_listing_fee;
+    if fee > 0 {
+        match store.deduct_currency(
+            seller,
+            &amount_like(&player.currency, fee),
+            TransactionReason::Other {
+                description: format!("Auction listing fee: {}", object.name),
+            },
+        ) {
+            Ok(_) => {}
+            Err(TinyMushError::InsufficientFunds) => {
+                return Ok(Err(AuctionRefusal::CantAffordFee(fee)))
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    if let InventoryResult::Failed { reason } =
+        store.player_remove_item(seller, object_id, quantity)?
+    {
+        return Err(TinyMushError::NotFound(reason));
+    }
+
+    let now = Utc::now();
+    let listing = AuctionListing {
+        id: store.db().generate_id()?,
+        seller: seller.to_string(),
+        object_id: object_id.to_string(),
+        item_name: object.name,
+        quantity,
+        min_bid: amount_like(&player.currency, min_bid),
+        buyout: buyout.map(|b| amount_like(&player.currency, b)),
+        high_bid: None,
+        created_at: now,
+        ends_at: now + Duration::hours(hours),
+        schema_version: AUCTION_SCHEMA_VERSION,
+    };
+    store.put_auction(&listing)?;
+    Ok(Ok(listing))
+}
+
+/// Bid `amount` base units on a listing. The bid is escrowed and the previous
+/// high bidder is refunded; reaching the buyout settles the auction.
+pub fn place_bid(
+    store: &TinyMushStore,
+    bidder: &str,
+    auction_id: u64,
+    amount: i64,
+) -> Result<Result<BidOutcome, AuctionRefusal>, TinyMushError> {
+    let mut listing = store.get_auction(auction_id)?;
+    if listing.seller.eq_ignore_ascii_case(bidder) {
+        return Ok(Err(AuctionRefusal::OwnListing));
+    }
+    // Awaiting settlement; a late bid would be refunded to nobody
+    if listing.is_due(Utc::now()) {
+        return Ok(Err(AuctionRefusal::Ended));
+    }
+    let buyout = listing.buyout.as_ref().map(|b| b.base_value());
+    // Anything past the buyout is just the buyout
+    let amount = buyout.map_or(amount, |b| amount.min(b));
+    if amount < listing.next_min_bid() {
+        return Ok(Err(AuctionRefusal::BidTooLow(listing.next_min_bid())));
+    }
+
+    let account = escrow_account(&listing);
+    let bid = amount_like(&listing.min_bid, amount);
+    match store.escrow_currency(
+        bidder,
+        &account,
+        &bid,
+        TransactionReason::Other {
+            description: format!("Bid on auction #{}", listing.id),
+        },
+    ) {
+        Ok(_) => {}
+        Err(TinyMushError::InsufficientFunds) => return Ok(Err(AuctionRefusal::InsufficientFunds)),
+        Err(e) => return Err(e),
+    }
+
+    if let Some(previous) = listing.high_bid.take() {
+        refund(store, &listing, &previous)?;
+        if !previous.bidder.eq_ignore_ascii_case(bidder) {
+            mail(
+                store,
+                &previous.bidder,
+                &format!("Outbid on {}", describe(&listing)),
+                &format!(
+                    "{} bid {} on auction #{} ({}). Your bid of {} has been refunded.",
+                    bidder,
+                    amount,
+                    listing.id,
+                    describe(&listing),
+                    previous.amount.base_value()
+                ),
+            )?;
+        }
+    }
+    listing.high_bid = Some(AuctionBid {
+        bidder: bidder.to_string(),
+        amount: bid,
+        placed_at: Utc::now(),
+    });
+
+    if buyout == Some(amount) {
+        settle(store, listing)?;
+        return Ok(Ok(BidOutcome::Won));
+    }
+    store.put_auction(&listing)?;
+    Ok(Ok(BidOutcome::Leading))
+}
+
+/// Buy a listing outright at its buyout price
+pub fn buyout(
+    store: &TinyMushStore,
+    buyer: &str,
+    auction_id: u64,
+) -> Result<Result<BidOutcome, AuctionRefusal>, TinyMushError> {
+    let listing = store.get_auction(auction_id)?;
+    match listing.buyout {
+        Some(price) => place_bid(store, buyer, auction_id, price.base_value()),
+        None => Ok(Err(AuctionRefusal::NoBuyout)),
+    }
+}
+
+/// Withdraw a listing that has no bids. The items go back to the seller; the
+/// listing fee is not refunded.
+pub fn cancel(
+    store: &TinyMushStore,
+    seller: &str,
+    auction_id: u64,
+) -> Result<Result<(), AuctionRefusal>, TinyMushError> {
+    let listing = store.get_auction(auction_id)?;
+    if !listing.seller.eq_ignore_ascii_case(seller) {
+        return Ok(Err(AuctionRefusal::NotSeller));
+    }
+    if listing.high_bid.is_some() {
+        return Ok(Err(AuctionRefusal::HasBids));
+    }
+    deliver_items(store, &listing, &listing.seller, OwnershipReason::Reclaimed)?;
+    store.delete_auction(listing.id)?;
+    Ok(Ok(()))
+}
+
+/// Close a listing: pay the seller and hand the items to the high bidder, or
+/// return unsold items to the seller. Both sides are told by mail.
+pub fn settle(store: &TinyMushStore, listing: AuctionListing) -> Result<(), TinyMushError> {
+    let what = describe(&listing);
+    match &listing.high_bid {
+        Some(bid) => {
+            store.release_escrow(
+                &escrow_account(&listing),
+                &listing.seller,
+                &bid.amount,
+                TransactionReason::Trade,
+            )?;
+            deliver_items(store, &listing, &bid.bidder, OwnershipReason::Traded)?;
+            mail(
+                store,
+                &listing.seller,
+                &format!("Sold: {}", what),
+                &format!(
+                    "Auction #{} sold to {} for {}. The money is in your pocket.",
+                    listing.id,
+                    bid.bidder,
+                    bid.amount.base_value()
+                ),
+            )?;
+            mail(
+                store,
+                &bid.bidder,
+                &format!("Won: {}", what),
+                &format!(
+                    "You won auction #{} for {}. {} is in your inventory.",
+                    listing.id,
+                    bid.amount.base_value(),
+                    what
+                ),
+            )?;
+        }
+        None => {
+            deliver_items(store, &listing, &listing.seller, OwnershipReason::Reclaimed)?;
+            mail(
+                store,
+                &listing.seller,
+                &format!("Expired: {}", what),
+                &format!(
+                    "Auction #{} ended without bids. {} is back in your inventory.",
+                    listing.id, what
+                ),
+            )?;
+        }
+    }
+    store.delete_auction(listing.id)
+}
+
+/// Settle every listing whose time is up. Returns the settled listing IDs; a
+/// listing that fails to settle is logged and retried on the next sweep.
+pub fn settle_due(store: &TinyMushStore, now: DateTime<Utc>) -> Result<Vec<u64>, TinyMushError> {
+    let mut settled = Vec::new();
+    for listing in store.list_auctions()? {
+        if listing.is_due(now) {
+            let id = listing.id;
+            match settle(store, listing) {
+                Ok(()) => settled.push(id),
+                Err(e) => log::warn!("Failed to settle auction #{}: {}", id, e),
+            }
+        }
+    }
+    Ok(settled)
+}
+
+fn refund(
+    store: &TinyMushStore,
+    listing: &AuctionListing,
+    bid: &AuctionBid,
+) -> Result<(), TinyMushError> {
+    store
+        .release_escrow(
+            &escrow_account(listing),
+            &bid.bidder,
+            &bid.amount,
+            TransactionReason::Other {
+                description: format!("Outbid on auction #{}", listing.id),
+            },
+        )
+        .map(|_| ())
+}
+
+/// Escrowed items skip the carry limits; the recipient may be offline and
+/// can't refuse them
+fn deliver_items(
+    store: &TinyMushStore,
+    listing: &AuctionListing,
+    recipient: &str,
+    reason: OwnershipReason,
+) -> Result<(), TinyMushError> {
+    let mut object = store.get_object(&listing.object_id)?;
+    let mut player = store.get_player(recipient)?;
+    crate::tmush::inventory::add_item_to_inventory(
+        &mut player,
+        &object,
+        listing.quantity,
+        &InventoryConfig::default(),
+    );
+    player.touch();
+    store.put_player(player)?;
+
+    object.ownership_history.push(OwnershipTransfer {
+        from_owner: Some(listing.seller.clone()),
+        to_owner: recipient.to_string(),
+        timestamp: Utc::now(),
+        reason,
+    });
+    store.put_object(object)
+}
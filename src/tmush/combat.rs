@@ -0,0 +1,501 @@
+//! Turn-based PvE combat for TinyMUSH.
+//!
+//! A fight starts when a player ATTACKs a [`MobRecord`] in their room. The
+//! player's [`PlayerRecord::state`] becomes `PlayerState::InCombat` and each
+//! subsequent ATTACK/DEFEND/FLEE (or USE of an item, or CAST of a spell)
+//! resolves one round: the player acts, then the mob answers. Rolls are made
+//! against [`PlayerStats`] and the mob's [`NpcCombatStats`].
+//!
+//! Outcomes:
+//! - **Victory**: the mob is removed from its room until it respawns, loot is
+//!   rolled into the player's inventory, KillEnemy quest objectives and
+//!   KillCount achievements advance. The player earns XP scaled by the mob's
+//!   stats and practises the combat skill.
+//! - **Defeat**: the player wakes at `town_square` with full HP.
+//! - **Flee**: combat ends without rewards.
+//!
+//! Formulas are intentionally simple so they stay readable over mesh:
+//! - Player damage: `strength + 0..=5 - defense/2` (min 1), 10% critical (x2),
+//!   plus any `CompanionBehavior::CombatAssist` bonus from companions present
+//!   and one point per five ranks of the combat skill.
+//! - Spell damage: the spell's own damage roll; defense does not apply.
+//! - Mob hit: `d20 + attack >= armor_class` (natural 1 misses, natural 20 hits).
+//! - Mob damage: `damage_range - armor_class/4` (min 1), minus `constitution/2`
+//!   while defending.
+//! - Flee chance: `50% + 5% per dexterity point above 10`, clamped to 10-95%.
+
+use chrono::{Duration, Utc};
+use rand::Rng;
+
+use crate::tmush::achievement::check_trigger;
+use crate::tmush::errors::TinyMushError;
+use crate::tmush::events::{RoomEvent, RoomEventKind};
+use crate::tmush::quest::record_enemy_kill;
+use crate::tmush::skills;
+use crate::tmush::state::REQUIRED_START_LOCATION_ID;
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::trigger::execute_on_combat;
+use crate::tmush::types::{
+    AchievementTrigger, CombatState, CompanionBehavior, InventoryConfig, InventoryResult, LootDrop,
+    MobRecord, NpcCombatStats, PlayerRecord, PlayerState, PlayerStats,
+};
+
+/// What the player does on their turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatAction {
+    Attack,
+    Defend,
+    Flee,
+    /// Player spent the turn using an item (the item itself was already applied)
+    UseItem,
+    /// Player cast a damaging spell; the damage has already been rolled
+    Spell(u32),
+}
+
+/// How a round ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombatOutcome {
+    Ongoing,
+    Victory,
+    Defeat,
+    /// Player fled, or the enemy was already defeated by someone else
+    Disengaged,
+}
+
+/// Lines to show the player plus the round outcome
+#[derive(Debug, Clone)]
+pub struct CombatReport {
+    pub lines: Vec<String>,
+    pub outcome: CombatOutcome,
+}
+
+/// Find a mob present in `room_id` by id or (partial) name, case-insensitive.
+pub fn find_mob_in_room(
+    store: &TinyMushStore,
+    room_id: &str,
+    name: &str,
+) -> Result<Option<MobRecord>, TinyMushError> {
+    let needle = name.trim().to_lowercase();
+    let mobs = store.get_mobs_in_room(room_id)?;
+    if let Some(exact) = mobs
+        .iter()
+        .find(|m| m.id.to_lowercase() == needle || m.name.to_lowercase() == needle)
+    {
+        return Ok(Some(exact.clone()));
+    }
+    Ok(mobs
+        .into_iter()
+        .find(|m| m.name.to_lowercase().contains(&needle)))
+}
+
+/// Total `CombatAssist` damage bonus from the player's companions in the room.
+pub fn companion_assist_bonus(store: &TinyMushStore, username: &str, room_id: &str) -> u32 {
+    store
+        .get_player_companions(username)
+        .unwrap_or_default()
+        .iter()
+        .filter(|c| c.room_id == room_id)
+        .flat_map(|c| c.behaviors.iter())
+        .map(|b| match b {
+            CompanionBehavior::CombatAssist { damage_bonus } => *damage_bonus,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Begin a fight with `mob`. Fires `OnCombat` triggers on carried objects.
+pub fn engage(
+    store: &TinyMushStore,
+    username: &str,
+    mob: &MobRecord,
+) -> Result<Vec<String>, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    player.state = PlayerState::InCombat(CombatState {
+        enemy_id: mob.id.clone(),
+        enemy_hp: mob.combat.max_hp,
+        enemy_max_hp: mob.combat.max_hp,
+        round: 0,
+        fled: false,
+        defending: false,
+    });
+    player.in_combat = true;
+    let room_id = player.current_room.clone();
+    let carried: Vec<String> = player
+        .inventory_stacks
+        .iter()
+        .map(|s| s.object_id.clone())
+        .collect();
+    store.put_player(player)?;
+
+    let mut lines = vec![format!("⚔️ You attack the {}!", mob.name)];
+    for object_id in carried {
+        if let Ok(object) = store.get_object(&object_id) {
+            lines.extend(execute_on_combat(&object, username, &room_id, store));
+        }
+    }
+
+    store.room_events().publish(RoomEvent::new(
+        &room_id,
+        username,
+        RoomEventKind::Trigger,
+        &format!("{} attacks the {}!", username, mob.name),
+    ));
+    Ok(lines)
+}
+
+/// Resolve one round of combat for `username`.
+///
+/// Returns `TinyMushError::NotFound` if the player is not in combat.
+pub fn resolve_round<R: Rng>(
+    store: &TinyMushStore,
+    username: &str,
+    action: CombatAction,
+    rng: &mut R,
+) -> Result<CombatReport, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    let mut state = match &player.state {
+        PlayerState::InCombat(state) => state.clone(),
+        _ => {
+            return Err(TinyMushError::NotFound(
+                "You aren't fighting anything.".to_string(),
+            ))
+        }
+    };
+    let room_id = player.current_room.clone();
+
+    let mob = match store.get_mob(&state.enemy_id) {
+        Ok(mob) if mob.is_present(Utc::now()) && mob.room_id == room_id => mob,
+        _ => {
+            end_combat(&mut player);
+            store.put_player(player)?;
+            return Ok(CombatReport {
+                lines: vec!["Your opponent is gone. The fight is over.".to_string()],
+                outcome: CombatOutcome::Disengaged,
+            });
+        }
+    };
+
+    state.round += 1;
+    let mut lines = Vec::new();
+
+    match action {
+        CombatAction::Attack => {
+            let bonus = companion_assist_bonus(store, username, &room_id)
+                + skills::skill_rank(&player, "combat") / 5;
+            let (damage, critical) = player_damage(&player.stats, mob.combat.defense, bonus, rng);
+            if critical {
+                lines.push("💥 Critical hit!".to_string());
+            }
+            lines.push(format!("You hit the {} for {} damage.", mob.name, damage));
+            state.enemy_hp = state.enemy_hp.saturating_sub(damage);
+            if state.enemy_hp == 0 {
+                lines.extend(finish_victory(store, player, mob, rng)?);
+                return Ok(CombatReport {
+                    lines,
+                    outcome: CombatOutcome::Victory,
+                });
+            }
+        }
+        CombatAction::Defend => {
+            state.defending = true;
+            lines.push("🛡️ You brace yourself.".to_string());
+        }
+        CombatAction::Flee => {
+            if rng.gen_range(1..=100) <= flee_chance(&player.stats) {
+                end_combat(&mut player);
+                store.put_player(player)?;
+                store.room_events().publish(RoomEvent::new(
+                    &room_id,
+                    username,
+                    RoomEventKind::Trigger,
+                    &format!("{} flees from the {}.", username, mob.name),
+                ));
+                return Ok(CombatReport {
+                    lines: vec![format!("🏃 You escape from the {}!", mob.name)],
+                    outcome: CombatOutcome::Disengaged,
+                });
+            }
+            lines.push("You fail to get away!".to_string());
+        }
+        CombatAction::UseItem => {}
+        CombatAction::Spell(damage) => {
+            lines.push(format!(
+                "Your spell hits the {} for {} damage.",
+                mob.name, damage
+            ));
+            state.enemy_hp = state.enemy_hp.saturating_sub(damage);
+            if state.enemy_hp == 0 {
+                lines.extend(finish_victory(store, player, mob, rng)?);
+                return Ok(CombatReport {
+                    lines,
+                    outcome: CombatOutcome::Victory,
+                });
+            }
+        }
+    }
+
+    // Enemy turn
+    if enemy_hits(&mob.combat, &player.stats, rng) {
+        let damage = enemy_damage(&mob.combat, &player.stats, state.defending, rng);
+        player.stats.hp = player.stats.hp.saturating_sub(damage);
+        lines.push(format!("The {} hits you for {} damage.", mob.name, damage));
+    } else {
+        lines.push(format!("The {} misses you.", mob.name));
+    }
+    state.defending = false;
+
+    if player.stats.hp == 0 {
+        lines.extend(finish_defeat(store, player, &mob)?);
+        return Ok(CombatReport {
+            lines,
+            outcome: CombatOutcome::Defeat,
+        });
+    }
+
+    lines.push(format!(
+        "HP {}/{} | {} {}/{}",
+        player.stats.hp, player.stats.max_hp, mob.name, state.enemy_hp, state.enemy_max_hp
+    ));
+    player.state = PlayerState::InCombat(state);
+    store.put_player(player)?;
+
+    Ok(CombatReport {
+        lines,
+        outcome: CombatOutcome::Ongoing,
+    })
+}
+
+fn end_combat(player: &mut PlayerRecord) {
+    player.state = PlayerState::Exploring;
+    player.in_combat = false;
+}
+
+fn finish_victory<R: Rng>(
+    store: &TinyMushStore,
+    mut player: PlayerRecord,
+    mut mob: MobRecord,
+    rng: &mut R,
+) -> Result<Vec<String>, TinyMushError> {
+    let username = player.username.clone();
+    let room_id = player.current_room.clone();
+    end_combat(&mut player);
+    let xp = skills::combat_xp(&mob.combat);
+    let mut lines = vec![format!("🎉 You defeated the {}! +{} XP", mob.name, xp)];
+    lines.extend(skills::grant_xp(&mut player, xp));
+    lines.extend(skills::practice(&mut player, "combat", 1));
+    store.put_player(player)?;
+
+    mob.defeated_until = Some(Utc::now() + Duration::seconds(mob.respawn_seconds as i64));
+    store.put_mob(mob.clone())?;
+
+    let config = InventoryConfig::default();
+    for (object_id, quantity) in roll_loot(&mob.combat.loot_table, rng) {
+        let name = match store.get_object(&object_id) {
+            Ok(object) => object.name,
+            Err(_) => continue,
+        };
+        match store.player_add_item(&username, &object_id, quantity, &config)? {
+            InventoryResult::Failed { reason } => {
+                lines.push(format!("You leave the {} behind: {}", name, reason));
+            }
+            _ if quantity > 1 => lines.push(format!("💰 Loot: {} x{}", name, quantity)),
+            _ => lines.push(format!("💰 Loot: {}", name)),
+        }
+    }
+
+    for quest_id in record_enemy_kill(store, &username, &mob.enemy_type)? {
+        let name = store
+            .get_quest(&quest_id)
+            .map(|q| q.name)
+            .unwrap_or(quest_id);
+        lines.push(format!("📜 Quest objectives complete: {}", name));
+    }
+
+    for achievement_id in check_trigger(
+        store,
+        &username,
+        &AchievementTrigger::KillCount { required: 1 },
+    )? {
+        let name = store
+            .get_achievement(&achievement_id)
+            .map(|a| a.name)
+            .unwrap_or(achievement_id);
+        lines.push(format!("🏆 Achievement unlocked: {}", name));
+    }
+
+    store.room_events().publish(RoomEvent::new(
+        &room_id,
+        &username,
+        RoomEventKind::Trigger,
+        &format!("{} defeats the {}.", username, mob.name),
+    ));
+    Ok(lines)
+}
+
+fn finish_defeat(
+    store: &TinyMushStore,
+    mut player: PlayerRecord,
+    mob: &MobRecord,
+) -> Result<Vec<String>, TinyMushError> {
+    let username = player.username.clone();
+    let fallen_in = player.current_room.clone();
+    end_combat(&mut player);
+    player.stats.hp = player.stats.max_hp;
+    player.current_room = REQUIRED_START_LOCATION_ID.to_string();
+    store.put_player(player)?;
+
+    store.room_events().publish(RoomEvent::new(
+        &fallen_in,
+        &username,
+        RoomEventKind::Trigger,
+        &format!("{} falls to the {}!", username, mob.name),
+    ));
+    store.room_events().publish(RoomEvent::new(
+        REQUIRED_START_LOCATION_ID,
+        &username,
+        RoomEventKind::Trigger,
+        &format!("{} staggers in, battered but alive.", username),
+    ));
+
+    Ok(vec![
+        format!("💀 You have been defeated by the {}!", mob.name),
+        "You awaken in Town Square, your wounds tended.".to_string(),
+    ])
+}
+
+/// Damage dealt by the player and whether it was a critical hit.
+pub fn player_damage<R: Rng>(
+    stats: &PlayerStats,
+    enemy_defense: u8,
+    bonus: u32,
+    rng: &mut R,
+) -> (u32, bool) {
+    let base = stats.strength as u32 + rng.gen_range(0..=5);
+    let critical = rng.gen_ratio(1, 10);
+    let mut damage = base.saturating_sub(enemy_defense as u32 / 2).max(1);
+    if critical {
+        damage *= 2;
+    }
+    (damage + bonus, critical)
+}
+
+/// Whether the enemy's attack lands this round.
+pub fn enemy_hits<R: Rng>(combat: &NpcCombatStats, stats: &PlayerStats, rng: &mut R) -> bool {
+    let roll: u32 = rng.gen_range(1..=20);
+    match roll {
+        1 => false,
+        20 => true,
+        _ => roll + combat.attack as u32 >= stats.armor_class as u32,
+    }
+}
+
+/// Damage dealt by the enemy on a hit.
+pub fn enemy_damage<R: Rng>(
+    combat: &NpcCombatStats,
+    stats: &PlayerStats,
+    defending: bool,
+    rng: &mut R,
+) -> u32 {
+    let (min, max) = combat.damage_range;
+    let base = rng.gen_range(min.min(max)..=max.max(min)) as u32;
+    let mut reduction = stats.armor_class as u32 / 4;
+    if defending {
+        reduction += stats.constitution as u32 / 2;
+    }
+    base.saturating_sub(reduction).max(1)
+}
+
+/// Percent chance (10-95) that FLEE succeeds.
+pub fn flee_chance(stats: &PlayerStats) -> u32 {
+    (50 + (stats.dexterity as i32 - 10) * 5).clamp(10, 95) as u32
+}
+
+/// Roll a loot table, returning `(object_id, quantity)` for each drop.
+pub fn roll_loot<R: Rng>(table: &[LootDrop], rng: &mut R) -> Vec<(String, u32)> {
+    let mut drops = Vec::new();
+    for drop in table {
+        if rng.gen::<f32>() >= drop.chance {
+            continue;
+        }
+        let (min, max) = drop.quantity;
+        let quantity = rng.gen_range(min.min(max)..=max.max(min)).max(1);
+        drops.push((drop.object_id.clone(), quantity));
+    }
+    drops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn stats(damage_range: (u8, u8)) -> NpcCombatStats {
+        NpcCombatStats {
+            max_hp: 10,
+            attack: 0,
+            defense: 0,
+            damage_range,
+            loot_table: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn damage_is_never_zero() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let weak = PlayerStats {
+            strength: 0,
+            ..PlayerStats::default()
+        };
+        for _ in 0..50 {
+            assert!(player_damage(&weak, 255, 0, &mut rng).0 >= 1);
+            assert!(enemy_damage(&stats((0, 0)), &PlayerStats::default(), true, &mut rng) >= 1);
+        }
+    }
+
+    #[test]
+    fn defending_reduces_enemy_damage() {
+        let player = PlayerStats::default();
+        let mut a = StdRng::seed_from_u64(1);
+        let mut b = StdRng::seed_from_u64(1);
+        let open = enemy_damage(&stats((12, 12)), &player, false, &mut a);
+        let braced = enemy_damage(&stats((12, 12)), &player, true, &mut b);
+        assert_eq!(open, 10);
+        assert_eq!(braced, 5);
+    }
+
+    #[test]
+    fn flee_chance_scales_with_dexterity_and_clamps() {
+        let mut player = PlayerStats::default();
+        assert_eq!(flee_chance(&player), 50);
+        player.dexterity = 14;
+        assert_eq!(flee_chance(&player), 70);
+        player.dexterity = 30;
+        assert_eq!(flee_chance(&player), 95);
+        player.dexterity = 0;
+        assert_eq!(flee_chance(&player), 10);
+    }
+
+    #[test]
+    fn loot_respects_chance_and_quantity() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let table = vec![
+            LootDrop {
+                object_id: "always".to_string(),
+                chance: 1.0,
+                quantity: (2, 3),
+            },
+            LootDrop {
+                object_id: "never".to_string(),
+                chance: 0.0,
+                quantity: (1, 1),
+            },
+        ];
+        for _ in 0..20 {
+            let drops = roll_loot(&table, &mut rng);
+            assert_eq!(drops.len(), 1);
+            assert_eq!(drops[0].0, "always");
+            assert!((2..=3).contains(&drops[0].1));
+        }
+    }
+}
@@ -12,14 +12,22 @@ use crate::config::Config;
 use crate::logutil::escape_log;
 use crate::metrics;
 use crate::storage::Storage;
+use crate::tmush::combat::{self, CombatAction, CombatOutcome, CombatReport};
+use crate::tmush::auction;
+use crate::tmush::dungeon;
+use crate::tmush::guild;
+use crate::tmush::edit_log::{self, EditTarget, WorldEdit};
+use crate::tmush::events::{RoomEvent, RoomEventKind};
 use crate::tmush::inventory::format_inventory_compact;
+use crate::tmush::magic;
 use crate::tmush::room_manager::RoomManager;
+use crate::tmush::skills;
 use crate::tmush::trigger::{
     execute_on_look, execute_on_poke, execute_on_use, execute_room_on_enter,
 };
 use crate::tmush::types::{
     AchievementCategory, AchievementRecord, AchievementTrigger, BulletinBoard, BulletinMessage,
-    CurrencyAmount, Direction as TmushDirection, ItemStack, ObjectRecord, ObjectTrigger, RoomFlag, TutorialState,
+    CurrencyAmount, Direction as TmushDirection, ItemStack, ObjectRecord, ObjectTrigger, PlayerState, RoomFlag, TutorialState,
     TutorialStep,
 };
 use crate::tmush::{PlayerRecord, TinyMushError, TinyMushStore};
@@ -54,9 +62,20 @@ pub enum TinyMushCommand {
     Pose(String),            // POSE action - strike a pose
     Ooc(String),             // OOC text - out of character
 
+    // Combat
+    Attack(String), // ATTACK mob - start or continue a fight
+    Defend,         // DEFEND - brace, reducing the enemy's next hit
+    Flee,           // FLEE - try to escape combat
+
+    // Magic
+    Cast(String, Option<String>), // CAST spell [target] - spend MP on a spell
+    Learn(String),                // LEARN spell|scroll - from a trainer or a scroll
+    Spells(usize),                // SPELLS [page] - spellbook and MP
+
     // Information
     Who,   // WHO - list online players
     Score, // SCORE - show player stats
+    Skills, // SKILLS - level, experience and skill ranks
     Time,  // TIME - show game time
 
     // Bulletin board commands (Phase 4 feature)
@@ -91,6 +110,7 @@ pub enum TinyMushCommand {
     // Quest commands (Phase 6 Week 2)
     Quest(Option<String>), // QUEST, QUEST LIST, QUEST ACCEPT id - manage quests
     Abandon(String),       // ABANDON quest_id - abandon active quest
+    Reputation(Option<String>), // REPUTATION [faction] - faction standing
 
     // Achievement & Title commands (Phase 6 Week 3)
     Achievements(Option<String>), // ACHIEVEMENTS, ACHIEVEMENTS LIST, ACHIEVEMENTS EARNED - manage achievements
@@ -118,6 +138,15 @@ pub enum TinyMushCommand {
     History(String),      // HISTORY <item> - view ownership audit trail (Phase 5)
     Reclaim(Option<String>), // RECLAIM - view reclaim box, RECLAIM <item> - retrieve item (Phase 6)
 
+    // Dungeon instances
+    Dungeon(Option<String>), // DUNGEON, DUNGEON INVITE <player>, DUNGEON LEAVE - party dungeon instances
+
+    // Guilds
+    Guild(Option<String>), // GUILD [subcommand] [args], GC <message> - guild membership, chat and treasury
+
+    // Auction house
+    Auction(Option<String>), // AUCTION [subcommand] [args] - list, bid on and buy out consigned items
+
     /// Builder Commands (Phase 7 Week 3-4)
     ///
     /// These commands enable world building and modification:
@@ -179,6 +208,7 @@ pub enum TinyMushCommand {
     CompanionAdmin(String, Vec<String>), // @COMPANION <subcommand> [args] - manage companions (admin only)
     RoomAdmin(String, Vec<String>), // @ROOM <subcommand> [args] - manage rooms (admin only)
     ObjectAdmin(String, Vec<String>), // @OBJECT <subcommand> [args] - manage objects (admin only)
+    DungeonAdmin(String, Vec<String>), // @DUNGEON <subcommand> [args] - manage dungeon templates (builder 3 / admin)
 
     /// Admin permission commands (Phase 9.2)
     ///
@@ -288,6 +318,34 @@ pub enum TinyMushCommand {
     DeleteBackup(String),  // @DELETEBACKUP <id> - delete specific backup
     BackupConfig(Vec<String>), // @BACKUPCONFIG [subcommand] - configure automatic backups
 
+    /// World Export & Import
+    ///
+    /// Area files are written under `<data_dir>/world_exports/<name>/`
+    /// (see [`crate::tmush::world_io`]):
+    /// - `@EXPORT [name]`: Export rooms, objects, NPCs, quests, achievements,
+    ///   recipes, shops, companions and housing templates
+    ///   - Requires admin level 2+
+    /// - `@IMPORT <name> [--overwrite] [--dry-run]`: Merge an export into the world
+    ///   - Reports new, unchanged and colliding ids per record kind
+    ///   - Colliding ids are kept unless `--overwrite` is given
+    ///   - `--dry-run` needs admin level 2+, applying needs level 3 (sysop only)
+    ExportWorld(Option<String>), // @EXPORT [name] - write world area files
+    ImportWorld(String, bool, bool), // @IMPORT <name> [--overwrite] [--dry-run]
+
+    /// Builder Edit History
+    ///
+    /// Builder commands record before/after snapshots (see [`crate::tmush::edit_log`]):
+    /// - `@UNDO` / `@REDO`: Step back and forward through your own world edits
+    /// - `@EDITLOG [player|room|here] [page]`: Page through recorded edits
+    ///   - Other builders' edits need builder level 3 or admin level 2+
+    /// - `@ROLLBACK <player> <30m|12h|7d>`: Undo everything a builder changed in
+    ///   the window, newest first
+    ///   - Requires admin level 3 (sysop only)
+    Undo, // @UNDO - revert your last world edit
+    Redo,                           // @REDO - re-apply your last undone edit
+    EditLog(Option<String>, usize), // @EDITLOG [player|room|here] [page]
+    Rollback(String, String),       // @ROLLBACK <player> <window> (sysop only)
+
     // Unrecognized command
     Unknown(String),
 }
@@ -307,6 +365,24 @@ pub enum Direction {
     Southwest,
 }
 
+impl Direction {
+    /// The direction an arriving player appears to come from.
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Northeast => Direction::Southwest,
+            Direction::Northwest => Direction::Southeast,
+            Direction::Southeast => Direction::Northwest,
+            Direction::Southwest => Direction::Northeast,
+        }
+    }
+}
+
 /// TinyMUSH session state and command processor
 pub struct TinyMushProcessor {
     store: TinyMushStore,
@@ -523,6 +599,16 @@ impl TinyMushProcessor {
             TinyMushCommand::Drop(item) => self.handle_drop(session, item, config).await,
             TinyMushCommand::Use(item) => self.handle_use(session, item, config).await,
             TinyMushCommand::Poke(target) => self.handle_poke(session, target, config).await,
+            TinyMushCommand::Attack(target) => self.handle_attack(session, target, config).await,
+            TinyMushCommand::Defend => {
+                self.handle_combat_action(session, CombatAction::Defend).await
+            }
+            TinyMushCommand::Flee => self.handle_combat_action(session, CombatAction::Flee).await,
+            TinyMushCommand::Cast(spell, target) => {
+                self.handle_cast(session, spell, target, config).await
+            }
+            TinyMushCommand::Learn(what) => self.handle_learn(session, what, config).await,
+            TinyMushCommand::Spells(page) => self.handle_spells(session, page, config).await,
             TinyMushCommand::Examine(target) => self.handle_examine(session, target, config).await,
             TinyMushCommand::Craft(recipe) => self.handle_craft(session, recipe, config).await,
             TinyMushCommand::Buy(item, quantity) => {
@@ -534,6 +620,7 @@ impl TinyMushProcessor {
             TinyMushCommand::List => self.handle_list(session, config).await,
             TinyMushCommand::Who => self.handle_who(session, config).await,
             TinyMushCommand::Score => self.handle_score(session, config).await,
+            TinyMushCommand::Skills => self.handle_skills(session, config).await,
             TinyMushCommand::Say(text) => self.handle_say(session, text, config).await,
             TinyMushCommand::Whisper(target, text) => {
                 self.handle_whisper(session, target, text, config).await
@@ -586,6 +673,9 @@ impl TinyMushProcessor {
             TinyMushCommand::Abandon(quest_id) => {
                 self.handle_abandon(session, quest_id, config).await
             }
+            TinyMushCommand::Reputation(faction) => {
+                self.handle_reputation(session, faction, config).await
+            }
             TinyMushCommand::Achievements(subcommand) => {
                 self.handle_achievements(session, subcommand, config).await
             }
@@ -612,6 +702,11 @@ impl TinyMushProcessor {
                 self.handle_home(session, subcommand, config).await
             }
             TinyMushCommand::Invite(player) => self.handle_invite(session, player, config).await,
+            TinyMushCommand::Dungeon(subcommand) => {
+                self.handle_dungeon(session, subcommand, config).await
+            }
+            TinyMushCommand::Guild(args) => self.handle_guild(session, args, config).await,
+            TinyMushCommand::Auction(args) => self.handle_auction(session, args, config).await,
             TinyMushCommand::Uninvite(player) => {
                 self.handle_uninvite(session, player, config).await
             }
@@ -663,6 +758,10 @@ impl TinyMushProcessor {
             TinyMushCommand::RoomAdmin(subcommand, args) => {
                 self.handle_room_admin(session, subcommand, args, config).await
             }
+            TinyMushCommand::DungeonAdmin(subcommand, args) => {
+                self.handle_dungeon_admin(session, subcommand, args, config)
+                    .await
+            }
             TinyMushCommand::ObjectAdmin(subcommand, args) => {
                 self.handle_object_admin(session, subcommand, args, config).await
             }
@@ -708,6 +807,21 @@ impl TinyMushProcessor {
             TinyMushCommand::BackupConfig(args) => {
                 self.handle_backup_config(session, args, config).await
             }
+            TinyMushCommand::ExportWorld(name) => {
+                self.handle_export_world(session, name, config).await
+            }
+            TinyMushCommand::ImportWorld(name, overwrite, dry_run) => {
+                self.handle_import_world(session, name, overwrite, dry_run, config)
+                    .await
+            }
+            TinyMushCommand::Undo => self.handle_undo(session, config).await,
+            TinyMushCommand::Redo => self.handle_redo(session, config).await,
+            TinyMushCommand::EditLog(filter, page) => {
+                self.handle_edit_log(session, filter, page, config).await
+            }
+            TinyMushCommand::Rollback(target, window) => {
+                self.handle_rollback(session, target, window, config).await
+            }
             // Clone monitoring commands (Phase 6 Admin Tools)
             TinyMushCommand::ListClones(username) => {
                 self.handle_list_clones(session, username, config).await
@@ -775,7 +889,10 @@ impl TinyMushProcessor {
         let first_char = input_trimmed.chars().next();
         let first_word = input_upper.split_whitespace().next().unwrap_or("");
         let is_text_command = matches!(first_char, Some('\'') | Some(':') | Some(';'))
-            || matches!(first_word, "SAY" | "EMOTE" | "POSE" | "OOC" | "WHISPER" | "WHIS");
+            || matches!(
+                first_word,
+                "SAY" | "EMOTE" | "POSE" | "OOC" | "WHISPER" | "WHIS" | "GUILD" | "GC"
+            );
         let preserve_case = input_trimmed.starts_with('@') || is_text_command;
         
         let parts: Vec<&str> = if preserve_case {
@@ -831,6 +948,7 @@ impl TinyMushProcessor {
             }
             "MAP" => TinyMushCommand::Map,
             "SCORE" => TinyMushCommand::Score,
+            "SKILLS" | "SKILL" | "XP" => TinyMushCommand::Skills,
             "TIME" => TinyMushCommand::Time,
 
             // Social commands
@@ -916,6 +1034,25 @@ impl TinyMushProcessor {
                 }
             }
 
+            // Combat commands
+            "ATTACK" | "KILL" | "FIGHT" => TinyMushCommand::Attack(parts[1..].join(" ")),
+            "DEFEND" | "BLOCK" => TinyMushCommand::Defend,
+            "FLEE" | "RUN" => TinyMushCommand::Flee,
+
+            // Magic commands
+            "CAST" => {
+                if parts.len() > 1 {
+                    let target = (parts.len() > 2).then(|| parts[2..].join(" "));
+                    TinyMushCommand::Cast(parts[1].to_string(), target)
+                } else {
+                    TinyMushCommand::Spells(1)
+                }
+            }
+            "LEARN" => TinyMushCommand::Learn(parts[1..].join(" ")),
+            "SPELLS" | "SPELLBOOK" => {
+                TinyMushCommand::Spells(parts.get(1).and_then(|p| p.parse().ok()).unwrap_or(1))
+            }
+
             // Economy commands
             "BUY" | "PURCHASE" => {
                 if parts.len() > 1 {
@@ -1108,6 +1245,13 @@ impl TinyMushProcessor {
                     TinyMushCommand::Quest(None)
                 }
             }
+            "REPUTATION" | "REP" | "STANDING" => {
+                if parts.len() > 1 {
+                    TinyMushCommand::Reputation(Some(parts[1..].join(" ")))
+                } else {
+                    TinyMushCommand::Reputation(None)
+                }
+            }
             "ABANDON" | "ABAND" => {
                 if parts.len() > 1 {
                     TinyMushCommand::Abandon(parts[1].to_lowercase())
@@ -1173,6 +1317,28 @@ impl TinyMushProcessor {
             }
 
             // Housing commands (Phase 7 Week 1-2)
+            "DUNGEON" | "DUNGEONS" => {
+                if parts.len() > 1 {
+                    TinyMushCommand::Dungeon(Some(parts[1..].join(" ").to_uppercase()))
+                } else {
+                    TinyMushCommand::Dungeon(None)
+                }
+            }
+            "GUILD" => {
+                if parts.len() > 1 {
+                    TinyMushCommand::Guild(Some(parts[1..].join(" ")))
+                } else {
+                    TinyMushCommand::Guild(None)
+                }
+            }
+            "GC" => TinyMushCommand::Guild(Some(format!("SAY {}", parts[1..].join(" ")))),
+            "AUCTION" | "AH" => {
+                if parts.len() > 1 {
+                    TinyMushCommand::Auction(Some(parts[1..].join(" ")))
+                } else {
+                    TinyMushCommand::Auction(None)
+                }
+            }
             "HOUSING" | "HOUSE" => {
                 if parts.len() > 1 {
                     TinyMushCommand::Housing(Some(parts[1..].join(" ").to_uppercase()))
@@ -1348,7 +1514,7 @@ impl TinyMushProcessor {
                     let args: Vec<String> = parts[2..].iter().map(|s| s.to_string()).collect();
                     TinyMushCommand::NPCAdmin(subcommand, args)
                 } else {
-                    TinyMushCommand::Unknown("Usage: @NPC <subcommand> [args]\n\nSubcommands:\n  CREATE <id> <name> - Create new NPC\n  EDIT <id> NAME <text> - Set NPC name\n  EDIT <id> TITLE <text> - Set NPC title\n  EDIT <id> DESCRIPTION <text> - Set NPC description\n  EDIT <id> ROOM <room_id> - Set NPC location\n  EDIT <id> DIALOG <key> <text> - Add simple dialogue response\n  DELETE <id> - Delete NPC\n  LIST - List all NPCs\n  SHOW <id> - Show NPC details\n\nNPC Flags (use @NPC EDIT <id> FLAG <flag>):\n  VENDOR - NPC can trade items\n  GUARD - NPC provides security\n  TUTORIALNPC - NPC helps with tutorials\n  QUESTGIVER - NPC gives quests\n\nExample: @NPC CREATE blacksmith \"Forge Master Grimm\"\nExample: @NPC EDIT blacksmith ROOM town_forge\nExample: @NPC EDIT blacksmith DIALOG greeting Welcome to my forge!".to_string())
+                    TinyMushCommand::Unknown("Usage: @NPC <subcommand> [args]\n\nSubcommands:\n  CREATE <id> <name> - Create new NPC\n  EDIT <id> NAME <text> - Set NPC name\n  EDIT <id> TITLE <text> - Set NPC title\n  EDIT <id> DESCRIPTION <text> - Set NPC description\n  EDIT <id> ROOM <room_id> - Set NPC location\n  EDIT <id> DIALOG <key> <text> - Add simple dialogue response\n  EDIT <id> PATROL <room> <room>... - Walk a route on the world tick\n  DELETE <id> - Delete NPC\n  LIST - List all NPCs\n  SHOW <id> - Show NPC details\n\nNPC Flags (use @NPC EDIT <id> FLAG <flag>):\n  VENDOR - NPC can trade items\n  GUARD - NPC provides security\n  TUTORIALNPC - NPC helps with tutorials\n  QUESTGIVER - NPC gives quests\n\nExample: @NPC CREATE blacksmith \"Forge Master Grimm\"\nExample: @NPC EDIT blacksmith ROOM town_forge\nExample: @NPC EDIT blacksmith DIALOG greeting Welcome to my forge!".to_string())
                 }
             }
             "@COMPANION" | "@COMPANIONS" | "@PET" => {
@@ -1369,6 +1535,15 @@ impl TinyMushProcessor {
                     TinyMushCommand::Unknown("Usage: @ROOM <subcommand> [args]\n\nSubcommands:\n  CREATE <id> <name> - Create new room\n  EDIT <id> NAME <text> - Set room name\n  EDIT <id> SHORTDESC <text> - Set room short description\n  EDIT <id> LONGDESC <text> - Set room long description\n  EDIT <id> EXIT <direction> <dest_room> - Add exit\n  EDIT <id> EXIT <direction> REMOVE - Remove exit\n  EDIT <id> FLAG <flag> - Add room flag\n  EDIT <id> CAPACITY <number> - Set max occupancy\n  EDIT <id> VISIBILITY <public|private|hidden> - Set room visibility\n  EDIT <id> LOCKED <true|false> - Lock/unlock room\n  EDIT <id> OWNER <player|world> - Transfer ownership\n  EDIT <id> HOUSING_TAGS <tag1,tag2,...> - Set housing filter tags\n  DELETE <id> - Delete room\n  LIST - List all rooms\n  SHOW <id> - Show room details\n\nRoom Flags:\n  SAFE - No combat allowed\n  DARK - Requires light source\n  INDOOR - Protected from weather\n  SHOP - Commercial location\n  QUESTLOCATION - Quest-related room\n  PVPENABLED - PvP combat allowed\n  PLAYERCREATED - Player-made room\n  PRIVATE - Restricted access\n  MODERATED - Admin-monitored\n  INSTANCED - Separate copy per player\n  CROWDED - High traffic area\n  HOUSINGOFFICE - Housing services\n  NOTELEPORTOUT - Cannot teleport out\n\nVisibility Types:\n  PUBLIC - Visible to all, anyone can enter\n  PRIVATE - Visible only to owner/guests\n  HIDDEN - Not listed, requires knowledge of ID\n\nExamples:\n  @ROOM CREATE dark_cave \"Mysterious Cave\"\n  @ROOM EDIT dark_cave FLAG DARK\n  @ROOM EDIT dark_cave CAPACITY 10\n  @ROOM EDIT tavern EXIT NORTH town_square\n  @ROOM EDIT tavern EXIT SOUTH REMOVE\n  @ROOM EDIT private_study VISIBILITY PRIVATE\n  @ROOM EDIT vault LOCKED true\n  @ROOM EDIT player_house OWNER alice\n  @ROOM EDIT housing_office HOUSING_TAGS cozy,small".to_string())
                 }
             }
+            "@DUNGEON" | "@DUNGEONS" => {
+                if parts.len() >= 2 {
+                    let subcommand = parts[1].to_uppercase();
+                    let args: Vec<String> = parts[2..].iter().map(|s| s.to_string()).collect();
+                    TinyMushCommand::DungeonAdmin(subcommand, args)
+                } else {
+                    TinyMushCommand::DungeonAdmin("LIST".to_string(), Vec::new())
+                }
+            }
             "@OBJECT" | "@OBJECTS" | "@OBJ" => {
                 if parts.len() >= 2 {
                     let subcommand = parts[1].to_uppercase();
@@ -1528,6 +1703,46 @@ impl TinyMushProcessor {
                 TinyMushCommand::BackupConfig(args)
             }
 
+            // World export & import
+            "@EXPORT" => TinyMushCommand::ExportWorld(parts.get(1).map(|s| s.to_string())),
+            "@IMPORT" => {
+                let Some(name) = parts.get(1) else {
+                    return TinyMushCommand::Unknown("Usage: @IMPORT <name> [--overwrite] [--dry-run]\nExample: @IMPORT world_20250112_143022 --dry-run".to_string());
+                };
+                let flag = |f: &str| parts[2..].iter().any(|p| p.eq_ignore_ascii_case(f));
+                TinyMushCommand::ImportWorld(
+                    name.to_string(),
+                    flag("--overwrite"),
+                    flag("--dry-run"),
+                )
+            }
+
+            // Builder edit history
+            "@UNDO" => TinyMushCommand::Undo,
+            "@REDO" => TinyMushCommand::Redo,
+            "@EDITLOG" => {
+                // Filter and page number may come in either order
+                let mut filter = None;
+                let mut page = 1;
+                for part in &parts[1..] {
+                    match part.parse::<usize>() {
+                        Ok(n) => page = n.max(1),
+                        Err(_) => filter = Some(part.to_string()),
+                    }
+                }
+                TinyMushCommand::EditLog(filter, page)
+            }
+            "@ROLLBACK" => {
+                if parts.len() > 2 {
+                    TinyMushCommand::Rollback(parts[1].to_lowercase(), parts[2].to_string())
+                } else {
+                    TinyMushCommand::Unknown(
+                        "Usage: @ROLLBACK <player> <30m|12h|7d>\nExample: @ROLLBACK griefer 2h"
+                            .to_string(),
+                    )
+                }
+            }
+
             // Builder permission management commands (Phase 7)
             "@BUILDER" => TinyMushCommand::Builder,
             "@SETBUILDER" => {
@@ -1723,6 +1938,10 @@ impl TinyMushProcessor {
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
+        if player.in_combat {
+            return Ok("You can't leave in the middle of a fight! Try FLEE.".to_string());
+        }
+
         // Track previous location for instance cleanup
         let previous_room_id = player.current_room.clone();
 
@@ -1771,6 +1990,29 @@ impl TinyMushProcessor {
             }
         };
 
+        // Walking into a dungeon entrance lands the player in their party's copy
+        let max_instances = config
+            .games
+            .tinymush_dungeon_max_instances
+            .unwrap_or(dungeon::DEFAULT_MAX_INSTANCES);
+        let destination_id = match dungeon::route_entry(
+            self.store(),
+            &player.username,
+            &destination_id,
+            &previous_room_id,
+            max_instances,
+        ) {
+            Ok(Some(instance_room_id)) => instance_room_id,
+            Ok(None) => destination_id,
+            Err(TinyMushError::InstanceLimit(_)) => {
+                return Ok(
+                    "Too many parties are exploring in there right now. Try again later."
+                        .to_string(),
+                );
+            }
+            Err(e) => return Ok(format!("Movement failed: {}", e)),
+        };
+
         // Get room manager after resolving destination
         let room_manager = self.get_room_manager().await?;
 
@@ -1796,6 +2038,10 @@ impl TinyMushProcessor {
             }
         }
 
+        // First visits earn exploration experience; dungeon copies count as the original room
+        let progress_lines =
+            skills::record_visit(&mut player, dungeon::original_room_of(&destination_id));
+
         // Save updated player state
         if let Err(e) = self.store().put_player(player.clone()) {
             return Ok(format!("Movement failed to save: {}", e));
@@ -1806,6 +2052,23 @@ impl TinyMushProcessor {
             &previous_room_id,
             &player.current_room,
         );
+        dungeon::touch(self.store(), &player.current_room)?;
+
+        // Let occupants of both rooms know about the movement
+        let mover = session.display_name();
+        let events = self.store().room_events();
+        events.publish(RoomEvent::new(
+            &previous_room_id,
+            &mover,
+            RoomEventKind::Depart(Some(format!("{:?}", direction).to_lowercase())),
+            "",
+        ));
+        events.publish(RoomEvent::new(
+            &destination_id,
+            &mover,
+            RoomEventKind::Arrive(Some(format!("{:?}", direction.opposite()).to_lowercase())),
+            "",
+        ));
 
         // Execute OnEnter triggers for all objects in the new room
         let enter_messages = execute_room_on_enter(&player.username, &destination_id, self.store());
@@ -1883,6 +2146,10 @@ impl TinyMushProcessor {
             }
         }
 
+        for line in progress_lines {
+            response.push_str(&format!("\n{}", line));
+        }
+
         // Add tutorial hint if in progress
         response.push_str(&tutorial_message);
 
@@ -2699,6 +2966,16 @@ impl TinyMushProcessor {
             }
         };
 
+        // Check skill requirement
+        let craft_skill = recipe.skill_required.as_deref().unwrap_or("crafting");
+        let craft_rank = skills::effective_skill(self.store(), &player, craft_skill);
+        if craft_rank < recipe.skill_level as u32 {
+            return Ok(format!(
+                "You need {} rank {} to craft {} (you have {}).",
+                craft_skill, recipe.skill_level, recipe.name, craft_rank
+            ));
+        }
+
         // Check if station requirement is met
         if let Some(required_station) = &recipe.requires_station {
             // Check if player has the station in inventory or is in a room with it
@@ -2805,6 +3082,10 @@ Missing: {}",
             }
         }
         
+        let craft_xp = skills::CRAFT_XP + 5 * recipe.skill_level as u32;
+        let mut progress_lines = skills::grant_xp(&mut player, craft_xp);
+        progress_lines.extend(skills::practice(&mut player, craft_skill, 1));
+
         // Save player
         self.store().put_player(player.clone())?;
 
@@ -2838,7 +3119,11 @@ Missing: {}",
             format!("\n{}", recipe.description)
         };
 
-        Ok(format!("{}{}", result_msg, description))
+        let mut response = format!("{} +{} XP{}", result_msg, craft_xp, description);
+        for line in progress_lines {
+            response.push_str(&format!("\n{}", line));
+        }
+        Ok(response)
     }
 
     /// Find a recipe by ID or name (case-insensitive)
@@ -3077,6 +3362,12 @@ Not fancy, but it gets the job done.",
             response.push_str(&msg);
         }
 
+        // Using an item in a fight costs the player's turn
+        if player.in_combat {
+            response.push('\n');
+            response.push_str(&self.combat_turn_after_item(&player.username));
+        }
+
         Ok(response)
     }
 
@@ -3125,9 +3416,207 @@ Not fancy, but it gets the job done.",
             response.push_str(&msg);
         }
 
+        // Using an item in a fight costs the player's turn
+        if player.in_combat {
+            response.push('\n');
+            response.push_str(&self.combat_turn_after_item(&player.username));
+        }
+
+        Ok(response)
+    }
+
+    /// Handle ATTACK command - start a fight with a hostile mob, or press the current one
+    async fn handle_attack(
+        &mut self,
+        session: &Session,
+        target: String,
+        _config: &Config,
+    ) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+
+        // Already fighting: ATTACK continues the current fight regardless of target
+        if matches!(player.state, PlayerState::InCombat(_)) {
+            return self
+                .handle_combat_action(session, CombatAction::Attack)
+                .await;
+        }
+
+        if target.trim().is_empty() {
+            return Ok("Attack what?\nUsage: ATTACK <creature>".to_string());
+        }
+
+        if let Ok(room) = self.store().get_room(&player.current_room) {
+            if room.flags.contains(&RoomFlag::Safe) {
+                return Ok("This is a safe area. No fighting here.".to_string());
+            }
+        }
+
+        let mob = match combat::find_mob_in_room(self.store(), &player.current_room, &target)? {
+            Some(mob) => mob,
+            None => {
+                return Ok(format!(
+                    "You don't see '{}' here to fight.",
+                    target.to_lowercase()
+                ))
+            }
+        };
+
+        let mut lines = combat::engage(self.store(), &player.username, &mob)?;
+        let report = combat::resolve_round(
+            self.store(),
+            &player.username,
+            CombatAction::Attack,
+            &mut rand::thread_rng(),
+        )?;
+        lines.push(Self::format_combat_report(report));
+        Ok(lines.join("\n"))
+    }
+
+    /// Handle DEFEND / FLEE (and ATTACK while already fighting)
+    async fn handle_combat_action(
+        &mut self,
+        session: &Session,
+        action: CombatAction,
+    ) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+
+        if !matches!(player.state, PlayerState::InCombat(_)) {
+            return Ok("You aren't fighting anything.".to_string());
+        }
+
+        let report = combat::resolve_round(
+            self.store(),
+            &player.username,
+            action,
+            &mut rand::thread_rng(),
+        )?;
+        Ok(Self::format_combat_report(report))
+    }
+
+    /// Let the enemy take its turn after the player used an item mid-fight
+    fn combat_turn_after_item(&self, username: &str) -> String {
+        match combat::resolve_round(
+            self.store(),
+            username,
+            CombatAction::UseItem,
+            &mut rand::thread_rng(),
+        ) {
+            Ok(report) => Self::format_combat_report(report),
+            Err(e) => {
+                debug!("combat turn after USE skipped for {}: {}", username, e);
+                String::new()
+            }
+        }
+    }
+
+    fn format_combat_report(report: CombatReport) -> String {
+        let mut output = report.lines.join("\n");
+        if report.outcome == CombatOutcome::Ongoing {
+            output.push_str("\nATTACK | DEFEND | FLEE | USE <item>");
+        }
+        output
+    }
+
+    /// Handle CAST command - spend MP on a known spell
+    async fn handle_cast(
+        &mut self,
+        session: &Session,
+        spell: String,
+        target: Option<String>,
+        _config: &Config,
+    ) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+        let was_fighting = matches!(player.state, PlayerState::InCombat(_));
+
+        let outcome = match magic::cast(
+            self.store(),
+            &player.username,
+            &spell,
+            target.as_deref(),
+            chrono::Utc::now(),
+            &mut rand::thread_rng(),
+        )? {
+            Ok(outcome) => outcome,
+            Err(refusal) => return Ok(refusal.to_string()),
+        };
+
+        let mut response = outcome.lines.join("\n");
+        match outcome.combat {
+            Some(report) => {
+                response.push('\n');
+                response.push_str(&Self::format_combat_report(report));
+            }
+            // Any other spell cast mid-fight costs the player's turn
+            None if was_fighting => {
+                response.push('\n');
+                response.push_str(&self.combat_turn_after_item(&player.username));
+            }
+            None => {}
+        }
         Ok(response)
     }
 
+    /// Handle LEARN command - study a scroll or pay a trainer for a spell
+    async fn handle_learn(
+        &mut self,
+        session: &Session,
+        what: String,
+        _config: &Config,
+    ) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+
+        if what.trim().is_empty() {
+            let taught = magic::spells_taught_here(self.store(), &player.current_room)?;
+            if taught.is_empty() {
+                return Ok(
+                    "Nobody here teaches magic.\nLEARN <scroll> to study a scroll.".to_string(),
+                );
+            }
+            let mut response = "Taught here:\n".to_string();
+            for (spell, teacher) in taught {
+                response.push_str(&format!(
+                    "{} {}mp - {} ({})\n",
+                    spell.name, spell.mp_cost, spell.price, teacher
+                ));
+            }
+            response.push_str("LEARN <spell>");
+            return Ok(response);
+        }
+
+        match magic::learn(self.store(), &player.username, &what)? {
+            Ok((spell, line)) => Ok(format!("{}\nCAST {} to use it.", line, spell.id)),
+            Err(refusal) => Ok(refusal.to_string()),
+        }
+    }
+
+    /// Handle SPELLS command - MP and the spells the player knows
+    async fn handle_spells(
+        &mut self,
+        session: &Session,
+        page: usize,
+        _config: &Config,
+    ) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+        let now = chrono::Utc::now();
+        let player = magic::refresh_mp(self.store(), &player.username, now)?;
+        Ok(magic::format_spellbook(self.store(), &player, now, page))
+    }
+
     /// Handle POKE command - poke/prod an interactive object with trigger execution
     async fn handle_poke(
         &mut self,
@@ -3240,6 +3729,12 @@ Not fancy, but it gets the job done.",
 
         let qty = quantity.unwrap_or(1);
 
+        // Faction standing with the shop's owner can adjust or refuse the sale
+        let (pricing, faction) = crate::tmush::faction::shop_pricing(self.store(), &player, &shop)?;
+        if let Some(refusal) = Self::shop_refusal(&shop, &pricing, faction.as_ref(), &player) {
+            return Ok(refusal);
+        }
+
         // Get the shop item to calculate price
         let shop_item = match shop.get_item(&object.id) {
             Some(item) => item,
@@ -3247,7 +3742,8 @@ Not fancy, but it gets the job done.",
         };
 
         // Calculate total price using shop's pricing logic (includes quantity)
-        let total_price = shop.calculate_buy_price(&object, qty, shop_item);
+        let total_price =
+            shop.calculate_buy_price_with(&object, qty, shop_item, pricing.buy_factor());
 
         // Check if player can afford it
         if !player.currency.can_afford(&total_price) {
@@ -3258,7 +3754,7 @@ Not fancy, but it gets the job done.",
         }
 
         // Process the purchase (updates shop inventory and currency)
-        match shop.process_buy(&object.id, qty, &object) {
+        match shop.process_buy_with(&object.id, qty, &object, pricing.buy_factor()) {
             Ok((price, actual_qty)) => {
                 // Deduct currency from player
                 player.currency = match player.currency.subtract(&price) {
@@ -3309,10 +3805,14 @@ Not fancy, but it gets the job done.",
                     return Ok(format!("Failed to save player: {}", e));
                 }
 
-                Ok(format!(
+                let mut response = format!(
                     "You buy {} x {} for {:?}. Balance: {}",
                     actual_qty, object.name, price, final_balance
-                ))
+                );
+                if let (Some(note), Some(faction)) = (pricing.describe(), faction.as_ref()) {
+                    response.push_str(&format!("\n({} {})", faction.name, note));
+                }
+                Ok(response)
             }
             Err(e) => Ok(format!("Purchase failed: {}", e)),
         }
@@ -3390,11 +3890,18 @@ Not fancy, but it gets the job done.",
             None => return Ok(format!("No shop here buys '{}'.", object.name)),
         };
 
+        // Faction standing with the shop's owner can adjust or refuse the deal
+        let (pricing, faction) = crate::tmush::faction::shop_pricing(self.store(), &player, &shop)?;
+        if let Some(refusal) = Self::shop_refusal(&shop, &pricing, faction.as_ref(), &player) {
+            return Ok(refusal);
+        }
+
         // Get shop item to calculate price
         let shop_item = shop.get_item(&object_id).unwrap(); // safe: we just checked
 
         // Calculate sell price using shop's pricing logic (includes quantity)
-        let total_price = shop.calculate_sell_price(&object, qty, shop_item);
+        let total_price =
+            shop.calculate_sell_price_with(&object, qty, shop_item, pricing.sell_factor());
 
         // Check if shop can afford it
         if !shop.currency.can_afford(&total_price) {
@@ -3405,7 +3912,7 @@ Not fancy, but it gets the job done.",
         }
 
         // Process the sale (updates shop inventory and currency)
-        match shop.process_sell(&object_id, qty, &object) {
+        match shop.process_sell_with(&object_id, qty, &object, pricing.sell_factor()) {
             Ok(price) => {
                 // Add currency to player
                 player.currency = match player.currency.add(&price) {
@@ -3439,7 +3946,7 @@ Not fancy, but it gets the job done.",
 
     /// Handle LIST/WARES command - display shop inventory with prices
     async fn handle_list(&mut self, session: &Session, _config: &Config) -> Result<String> {
-        use crate::tmush::shop::format_shop_listing;
+        use crate::tmush::shop::format_shop_listing_with;
 
         // Get player to determine current location
         let player = match self.get_or_create_player(session).await {
@@ -3471,12 +3978,23 @@ Not fancy, but it gets the job done.",
                 response.push_str(&format!("{}\n\n", shop.description));
             }
 
+            let (pricing, faction) =
+                crate::tmush::faction::shop_pricing(self.store(), &player, shop)?;
+            if let Some(refusal) = Self::shop_refusal(shop, &pricing, faction.as_ref(), &player)
+            {
+                response.push_str(&refusal);
+                continue;
+            }
+            if let (Some(note), Some(faction)) = (pricing.describe(), faction.as_ref()) {
+                response.push_str(&format!("({} {})\n", faction.name, note));
+            }
+
             // Get object resolver closure
             let store = self.store();
             let get_object = |object_id: &str| store.get_object(object_id).ok();
 
             // Use shop formatting function
-            let lines = format_shop_listing(shop, get_object);
+            let lines = format_shop_listing_with(shop, get_object, pricing.buy_factor());
             response.push_str(&lines.join("\n"));
         }
 
@@ -3487,6 +4005,24 @@ Not fancy, but it gets the job done.",
         }
     }
 
+    /// Message shown when a shop's faction refuses to deal with the player
+    fn shop_refusal(
+        shop: &crate::tmush::shop::ShopRecord,
+        pricing: &crate::tmush::shop::FactionPricing,
+        faction: Option<&crate::tmush::types::FactionRecord>,
+        player: &PlayerRecord,
+    ) -> Option<String> {
+        match (pricing, faction) {
+            (crate::tmush::shop::FactionPricing::Refused, Some(faction)) => Some(format!(
+                "{} refuses to deal with you.\n{}: {}",
+                shop.name,
+                faction.name,
+                player.get_reputation_level(&faction.id).display_name()
+            )),
+            _ => None,
+        }
+    }
+
     /// Handle WHO command - list online players
     async fn handle_who(&mut self, _session: &Session, _config: &Config) -> Result<String> {
         let player_ids = match self.store().list_player_ids() {
@@ -3516,9 +4052,14 @@ Not fancy, but it gets the job done.",
             Ok(player) => player,
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
+        let player = magic::refresh_mp(self.store(), &player.username, chrono::Utc::now())?;
 
         let mut response = format!("=== {} ===\n", player.display_name);
         response.push_str(&format!("Location: {}\n", player.current_room));
+        response.push_str(&format!(
+            "Level: {} ({} XP)\n",
+            player.stats.level, player.stats.experience
+        ));
         response.push_str(&format!("Credits: {}\n", player.credits));
         response.push_str(&format!(
             "HP: {}/{}\n",
@@ -3534,6 +4075,16 @@ Not fancy, but it gets the job done.",
         Ok(response)
     }
 
+    /// Handle SKILLS command - level, experience and skill ranks
+    async fn handle_skills(&mut self, session: &Session, _config: &Config) -> Result<String> {
+        let player = match self.get_or_create_player(session).await {
+            Ok(player) => player,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+
+        Ok(skills::format_skills(self.store(), &player))
+    }
+
     /// Handle SAY command - speak to room
     async fn handle_say(
         &mut self,
@@ -3547,28 +4098,21 @@ Not fancy, but it gets the job done.",
             return Ok(world_config.err_say_what);
         }
 
+        let speaker = session.display_name();
         let player = match self.get_or_create_player(session).await {
             Ok(player) => player,
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
-        // Get other players in the same room
-        let room_manager = self.get_room_manager().await?;
-        let players_in_room = room_manager.get_players_in_room(&player.current_room);
-        let others_count = players_in_room.len().saturating_sub(1); // Exclude self
-
-        let mut response = format!("You say: \"{}\"\n", text);
-        if others_count > 0 {
-            response.push_str(&format!(
-                "({} other player{} in room will also see this)\n",
-                others_count,
-                if others_count == 1 { "" } else { "s" }
-            ));
-        } else {
-            response.push_str("(No other players in room)\n");
-        }
+        // Other occupants receive this via the room event bus
+        self.store().room_events().publish(RoomEvent::new(
+            &player.current_room,
+            &speaker,
+            RoomEventKind::Say,
+            &text,
+        ));
 
-        Ok(response)
+        Ok(format!("You say: \"{}\"\n", text))
     }
 
     /// Handle WHISPER command - private message to another player  
@@ -3595,34 +4139,45 @@ Not fancy, but it gets the job done.",
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
-        // Check if target player exists and is in same room
-        let room_manager = self.get_room_manager().await?;
-        let players_in_room = room_manager.get_players_in_room(&player.current_room);
-
+        // Resolve the target from player keys (exact name first, then prefix) and load
+        // only those records, rather than every player, to check they are in this room
         let target_lower = target.to_lowercase();
-        let target_found = players_in_room
+        let player_ids = self.store().list_player_ids()?;
+        let mut candidates: Vec<&String> = player_ids
             .iter()
-            .find(|p| p.to_lowercase().starts_with(&target_lower));
+            .filter(|id| id.starts_with(&target_lower))
+            .collect();
+        candidates.sort_by_key(|id| id.len() != target_lower.len());
+        let target_found = candidates.into_iter().find_map(|id| {
+            self.store()
+                .get_player(id)
+                .ok()
+                .filter(|p| p.current_room == player.current_room)
+                .map(|p| p.username)
+        });
 
-        if let Some(target_player) = target_found {
+        if let Some(target_player) = &target_found {
             if target_player.to_lowercase() == speaker.to_lowercase() {
                 return Ok(world_config.err_whisper_self);
             }
 
+            self.store().room_events().publish(
+                RoomEvent::new(
+                    &player.current_room,
+                    &speaker,
+                    RoomEventKind::Whisper,
+                    &text,
+                )
+                .for_target(target_player),
+            );
+
             Ok(format!(
                 "You whisper to {}: \"{}\"\n(Private message - only {} will see this)",
                 target_player, text, target_player
             ))
         } else {
-            Ok(format!(
-                "Player '{}' not found in this room.\nPlayers here: {}",
-                target,
-                if players_in_room.is_empty() {
-                    "none".to_string()
-                } else {
-                    players_in_room.join(", ")
-                }
-            ))
+            // Listing who is here would load every player record; LOOK shows the room
+            Ok(format!("Player '{}' not found in this room.", target))
         }
     }
 
@@ -3645,23 +4200,14 @@ Not fancy, but it gets the job done.",
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
-        // Get other players in the same room
-        let room_manager = self.get_room_manager().await?;
-        let players_in_room = room_manager.get_players_in_room(&player.current_room);
-        let others_count = players_in_room.len().saturating_sub(1); // Exclude self
+        self.store().room_events().publish(RoomEvent::new(
+            &player.current_room,
+            &speaker,
+            RoomEventKind::Emote,
+            &text,
+        ));
 
-        let mut response = format!("{} {}\n", speaker, text);
-        if others_count > 0 {
-            response.push_str(&format!(
-                "(Action visible to {} other player{})\n",
-                others_count,
-                if others_count == 1 { "" } else { "s" }
-            ));
-        } else {
-            response.push_str("(No other players in room to see your action)\n");
-        }
-
-        Ok(response)
+        Ok(format!("{} {}\n", speaker, text))
     }
 
     /// Handle POSE command - strike a pose  
@@ -3681,24 +4227,15 @@ Not fancy, but it gets the job done.",
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
-        // Get other players in the same room
-        let room_manager = self.get_room_manager().await?;
-        let players_in_room = room_manager.get_players_in_room(&player.current_room);
-        let others_count = players_in_room.len().saturating_sub(1); // Exclude self
-
         // Pose format is different from emote - it's a descriptive action
-        let mut response = format!("{} {}\n", speaker, text);
-        if others_count > 0 {
-            response.push_str(&format!(
-                "(Pose visible to {} other player{})\n",
-                others_count,
-                if others_count == 1 { "" } else { "s" }
-            ));
-        } else {
-            response.push_str("(No other players in room to see your pose)\n");
-        }
+        self.store().room_events().publish(RoomEvent::new(
+            &player.current_room,
+            &speaker,
+            RoomEventKind::Pose,
+            &text,
+        ));
 
-        Ok(response)
+        Ok(format!("{} {}\n", speaker, text))
     }
 
     /// Handle OOC command - out of character communication
@@ -3718,23 +4255,14 @@ Not fancy, but it gets the job done.",
             Err(e) => return Ok(format!("Error loading player: {}", e)),
         };
 
-        // Get other players in the same room
-        let room_manager = self.get_room_manager().await?;
-        let players_in_room = room_manager.get_players_in_room(&player.current_room);
-        let others_count = players_in_room.len().saturating_sub(1); // Exclude self
-
-        let mut response = format!("[OOC] {}: {}\n", speaker, text);
-        if others_count > 0 {
-            response.push_str(&format!(
-                "(OOC message visible to {} other player{})\n",
-                others_count,
-                if others_count == 1 { "" } else { "s" }
-            ));
-        } else {
-            response.push_str("(No other players in room to see your OOC message)\n");
-        }
+        self.store().room_events().publish(RoomEvent::new(
+            &player.current_room,
+            &speaker,
+            RoomEventKind::Ooc,
+            &text,
+        ));
 
-        Ok(response)
+        Ok(format!("[OOC] {}: {}\n", speaker, text))
     }
 
     /// Handle TUTORIAL command - manage tutorial progress
@@ -4282,9 +4810,9 @@ Not fancy, but it gets the job done.",
                 }
 
                 DialogCondition::MinLevel { level } => {
-                    // Level is stored in stats, not directly on player
-                    // For now, just pass (we can add proper level tracking later)
-                    let _ = level; // Suppress unused warning
+                    if player.stats.level < *level {
+                        return Ok(false);
+                    }
                 }
 
                 DialogCondition::QuestStatus { quest_id, status } => {
@@ -4306,6 +4834,18 @@ Not fancy, but it gets the job done.",
                         return Ok(false);
                     }
                 }
+
+                DialogCondition::MinReputation { faction, points } => {
+                    if player.get_reputation(faction) < *points {
+                        return Ok(false);
+                    }
+                }
+
+                DialogCondition::MinSkill { skill, rank } => {
+                    if skills::effective_skill(self.store(), player, skill) < *rank {
+                        return Ok(false);
+                    }
+                }
             }
         }
 
@@ -4475,8 +5015,10 @@ Not fancy, but it gets the job done.",
 
                     let mut completed = false;
                     for quest in &mut player.quests {
-                        if &quest.quest_id == quest_id {
-                            use crate::tmush::types::QuestState;
+                        use crate::tmush::types::QuestState;
+                        if &quest.quest_id == quest_id
+                            && !matches!(quest.state, QuestState::Completed { .. })
+                        {
                             quest.state = QuestState::Completed {
                                 completed_at: chrono::Utc::now(),
                             };
@@ -4488,6 +5030,28 @@ Not fancy, but it gets the job done.",
                     if completed {
                         self.store().put_player(player)?;
                         messages.push(format!("✅ Quest completed: {}", quest_id));
+
+                        if let Ok(quest) = self.store().get_quest(quest_id) {
+                            use crate::tmush::faction::{
+                                apply_reputation_changes, quest_reputation_rewards,
+                            };
+
+                            let reputation = quest_reputation_rewards(self.store(), &quest)?;
+                            messages.extend(apply_reputation_changes(
+                                self.store(),
+                                player_name,
+                                &reputation,
+                            )?);
+
+                            if quest.rewards.experience > 0 {
+                                messages.push(format!("+{} XP", quest.rewards.experience));
+                                messages.extend(skills::award_xp(
+                                    self.store(),
+                                    player_name,
+                                    quest.rewards.experience,
+                                )?);
+                            }
+                        }
                     } else {
                         messages.push("❌ Quest not found or already complete.".to_string());
                     }
@@ -4559,7 +5123,7 @@ Not fancy, but it gets the job done.",
                     {
                         Ok(resolved_room) => {
                             if self.store().get_room(&resolved_room).is_ok() {
-                                player.current_room = resolved_room.clone();
+                                self.store().relocate_player(&mut player, &resolved_room);
                                 self.store().put_player(player)?;
                                 messages.push(format!(
                                     "🌀 You have been teleported to {}!",
@@ -4579,6 +5143,16 @@ Not fancy, but it gets the job done.",
                     // Send a system message
                     messages.push(format!("📢 {}", text));
                 }
+
+                DialogAction::AdjustReputation { faction, amount } => {
+                    use crate::tmush::faction::apply_reputation_changes;
+
+                    messages.extend(apply_reputation_changes(
+                        self.store(),
+                        player_name,
+                        &[(faction.clone(), *amount)],
+                    )?);
+                }
             }
         }
 
@@ -4692,6 +5266,32 @@ Not fancy, but it gets the job done.",
         }
     }
 
+    /// Handle REPUTATION command - show faction standing, or detail for one faction
+    async fn handle_reputation(
+        &mut self,
+        session: &Session,
+        faction: Option<String>,
+        _config: &Config,
+    ) -> Result<String> {
+        use crate::tmush::faction::{find_faction, format_faction_detail, format_standing};
+
+        let player = match self.get_or_create_player(session).await {
+            Ok(p) => p,
+            Err(e) => return Ok(format!("Error loading player: {}", e)),
+        };
+
+        match faction {
+            None => Ok(format_standing(self.store(), &player)?),
+            Some(query) => match find_faction(self.store(), &query)? {
+                Some(faction) => Ok(format_faction_detail(&faction, &player)),
+                None => Ok(format!(
+                    "No faction called '{}'.\nType REPUTATION to list factions.",
+                    query
+                )),
+            },
+        }
+    }
+
     /// Handle ABANDON command - abandon an active quest
     async fn handle_abandon(
         &mut self,
@@ -5686,7 +6286,7 @@ Not fancy, but it gets the job done.",
                 }
 
                 // Teleport
-                player.current_room = target_instance.entry_room_id.clone();
+                store.relocate_player(&mut player, &target_instance.entry_room_id);
                 player.last_teleport = Some(Utc::now());
                 player.touch();
                 store.put_player_async(player.clone()).await?;
@@ -5772,7 +6372,7 @@ Not fancy, but it gets the job done.",
                 }
 
                 // 6. Teleport player to housing entry room
-                player.current_room = target_instance.entry_room_id.clone();
+                store.relocate_player(&mut player, &target_instance.entry_room_id);
                 player.last_teleport = Some(Utc::now());
                 player.touch();
 
@@ -5964,9 +6564,12 @@ Not fancy, but it gets the job done.",
         }
 
         // Update the room
+        let mut edit = WorldEdit::begin(&player.username, "DESCRIBE", &player.current_room);
+        edit.touch(store, EditTarget::Room(player.current_room.clone()))?;
         let mut current_room = store.get_room_async(&player.current_room).await?;
         current_room.long_desc = new_desc;
         store.put_room_async(current_room).await?;
+        edit_log::record(store, edit)?;
 
         Ok(world_config.msg_describe_success.clone())
     }
@@ -6210,7 +6813,7 @@ Not fancy, but it gets the job done.",
             for guest_username in guests_to_kick {
                 let store = self.store();
                 if let Ok(mut guest_player) = store.get_player_async(&guest_username).await {
-                    guest_player.current_room = "town_square".to_string();
+                    store.relocate_player(&mut guest_player, "town_square");
                     let _ = store.put_player_async(guest_player).await;
                 }
             }
@@ -6249,7 +6852,7 @@ Not fancy, but it gets the job done.",
         if was_in_housing {
             let store = self.store();
             if let Ok(mut target_player) = store.get_player_async(&target).await {
-                target_player.current_room = "town_square".to_string();
+                store.relocate_player(&mut target_player, "town_square");
                 store.put_player_async(target_player).await?;
             }
         }
@@ -6424,7 +7027,14 @@ Not fancy, but it gets the job done.",
         room.long_desc = new_description.clone();
 
         // Save to database
+        let mut edit = WorldEdit::begin(
+            &player.username,
+            &format!("@EDITROOM {}", room_id),
+            &player.current_room,
+        );
+        edit.touch(store, EditTarget::Room(room_id.clone()))?;
         store.put_room_async(room).await?;
+        edit_log::record(store, edit)?;
 
         Ok(format!(
             "Room '{}' description updated by {}.\n\n\
@@ -6532,7 +7142,14 @@ Not fancy, but it gets the job done.",
         }
 
         // Save updated NPC
+        let mut edit = WorldEdit::begin(
+            &player.username,
+            &format!("@EDITNPC {} {}", npc_id, field),
+            &player.current_room,
+        );
+        edit.touch(store, EditTarget::Npc(npc_id.clone()))?;
         store.put_npc(npc)?;
+        edit_log::record(store, edit)?;
 
         Ok(format!(
             "NPC '{}' updated by {}.\n\
@@ -7025,7 +7642,37 @@ Not fancy, but it gets the job done.",
 
                         Ok(format!("Recipe requires crafting station: {}", args[2]))
                     }
-                    _ => Ok("Unknown field. Use: MATERIAL, RESULT, DESCRIPTION, or STATION".to_string()),
+                    "SKILL" => {
+                        if args.len() < 3 {
+                            return Ok("Usage: @RECIPE EDIT <id> SKILL <skill> <rank> | SKILL NONE\nExample: @RECIPE EDIT signal_booster SKILL crafting 3".to_string());
+                        }
+
+                        if args[2].eq_ignore_ascii_case("NONE") {
+                            recipe.skill_required = None;
+                            recipe.skill_level = 0;
+                            store.put_recipe(recipe)?;
+                            return Ok(
+                                "Recipe has no skill requirement (practises crafting).".to_string()
+                            );
+                        }
+
+                        let Some(rank) = args.get(3).and_then(|r| r.parse::<u8>().ok()) else {
+                            return Ok("Usage: @RECIPE EDIT <id> SKILL <skill> <rank>".to_string());
+                        };
+                        recipe.skill_required = Some(args[2].to_lowercase());
+                        recipe.skill_level = rank;
+                        store.put_recipe(recipe)?;
+
+                        Ok(format!(
+                            "Recipe requires {} rank {}.",
+                            args[2].to_lowercase(),
+                            rank
+                        ))
+                    }
+                    _ => Ok(
+                        "Unknown field. Use: MATERIAL, RESULT, DESCRIPTION, STATION, or SKILL"
+                            .to_string(),
+                    ),
                 }
             }
             "DELETE" => {
@@ -7120,6 +7767,13 @@ Not fancy, but it gets the job done.",
                     output.push_str(&format!("REQUIRES STATION: {}\n", station));
                 }
 
+                if let Some(skill) = &recipe.skill_required {
+                    output.push_str(&format!(
+                        "REQUIRES SKILL: {} rank {}\n",
+                        skill, recipe.skill_level
+                    ));
+                }
+
                 output.push_str(&format!(
                     "\nCreated by: {} on {}\n",
                     recipe.created_by,
@@ -7843,14 +8497,15 @@ Not fancy, but it gets the job done.",
                 if args.len() < 2 {
                     return Ok(
                         "Usage: @NPC EDIT <npc_id> <field> <value>\n\
-                        Fields: NAME, TITLE, DESCRIPTION, ROOM, DIALOG, FLAG\n\
+                        Fields: NAME, TITLE, DESCRIPTION, ROOM, DIALOG, FLAG, PATROL\n\
                         Examples:\n\
                         @NPC EDIT blacksmith NAME Forge Master Grimm\n\
                         @NPC EDIT blacksmith TITLE Master Blacksmith\n\
                         @NPC EDIT blacksmith DESCRIPTION A burly dwarf with...\n\
                         @NPC EDIT blacksmith ROOM town_forge\n\
                         @NPC EDIT blacksmith DIALOG greeting Welcome to my forge!\n\
-                        @NPC EDIT blacksmith FLAG VENDOR".to_string()
+                        @NPC EDIT blacksmith FLAG VENDOR\n\
+                        @NPC EDIT guard PATROL town_square market_street".to_string()
                     );
                 }
 
@@ -7933,8 +8588,27 @@ Not fancy, but it gets the job done.",
                             Ok(format!("NPC '{}' already has flag {:?}", npc_id, flag))
                         }
                     }
+                    "PATROL" => {
+                        if value_args.is_empty() {
+                            return Ok("Usage: @NPC EDIT <id> PATROL <room> <room> [room...]\n       @NPC EDIT <id> PATROL NONE\nThe NPC moves one room along the route each world tick.".to_string());
+                        }
+                        if value_args.len() == 1 && value_args[0].eq_ignore_ascii_case("NONE") {
+                            npc.patrol.clear();
+                            store.put_npc(npc)?;
+                            return Ok(format!("'{}' no longer patrols", npc_id));
+                        }
+                        for room_id in value_args {
+                            if store.get_room(room_id).is_err() {
+                                return Ok(format!("Room '{}' does not exist", room_id));
+                            }
+                        }
+                        npc.patrol = value_args.to_vec();
+                        let route = npc.patrol.join(" -> ");
+                        store.put_npc(npc)?;
+                        Ok(format!("'{}' patrols {}", npc_id, route))
+                    }
                     _ => Ok(format!(
-                        "Unknown field: {}\nValid fields: NAME, TITLE, DESCRIPTION, ROOM, DIALOG, FLAG",
+                        "Unknown field: {}\nValid fields: NAME, TITLE, DESCRIPTION, ROOM, DIALOG, FLAG, PATROL",
                         field
                     )),
                 }
@@ -7998,6 +8672,10 @@ Not fancy, but it gets the job done.",
                 if !npc.flags.is_empty() {
                     output.push_str(&format!("Flags: {:?}\n", npc.flags));
                 }
+
+                if !npc.patrol.is_empty() {
+                    output.push_str(&format!("Patrol: {}\n", npc.patrol.join(" -> ")));
+                }
                 
                 if !npc.dialog.is_empty() {
                     output.push_str("\nDialogue responses:\n");
@@ -9898,7 +10576,7 @@ Not fancy, but it gets the job done.",
         }
 
         let display_name = target.display_name.clone();
-        target.current_room = room_id.clone();
+        store.relocate_player(&mut target, &room_id);
         store.put_player_async(target).await?;
 
         Ok(format!(
@@ -10186,7 +10864,7 @@ Not fancy, but it gets the job done.",
         // Teleport the admin
         {
             let store = self.store();
-            player.current_room = destination.clone();
+            store.relocate_player(&mut player, &destination);
             store.put_player(player.clone())?;
         }
 
@@ -11033,166 +11711,1392 @@ Not fancy, but it gets the job done.",
     }
 
     // ============================================================================
-    // Builder World Manipulation Commands (Phase 7)
+    // World Export & Import
     // ============================================================================
 
-    /// Handle `/DIG` command - create a new room and link it from current location
-    ///
-    /// Creates a new room with the specified name and automatically creates a bidirectional
-    /// exit connecting the current room to the new room in the specified direction.
-    ///
-    /// # Permission Requirements
-    /// - Requires builder level 2+ (Builder or Architect)
-    ///
-    /// # Example
-    /// ```text
-    /// > /DIG north Mysterious Cave
-    /// ✅ Created room 'mysterious_cave_1234' (Mysterious Cave)
-    /// ✅ Linked north → mysterious_cave_1234
-    /// ✅ Linked mysterious_cave_1234 south → town_square
-    /// ```
-    async fn handle_dig(
+    /// Directory holding `@EXPORT` output; `None` if `name` is not a plain
+    /// file name
+    fn world_export_dir(config: &Config, name: &str) -> Option<std::path::PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        valid.then(|| {
+            std::path::Path::new(&config.storage.data_dir)
+                .join("world_exports")
+                .join(name)
+        })
+    }
+
+    /// Admin level of the session's player, if they are an admin
+    fn session_admin_level(&self, session: &Session) -> Result<Option<u8>> {
+        let username = session
+            .username
+            .as_deref()
+            .unwrap_or("unknown")
+            .to_lowercase();
+        let store = self.store();
+        if !store.is_admin(&username)? {
+            return Ok(None);
+        }
+        Ok(Some(store.get_player(&username)?.admin_level()))
+    }
+
+    /// Handle `@EXPORT` command - write the world to area files
+    async fn handle_export_world(
         &mut self,
         session: &Session,
-        direction_str: String,
-        room_name: String,
-        _config: &Config,
+        name: Option<String>,
+        config: &Config,
     ) -> Result<String> {
-        let username = session.username.as_deref().unwrap_or("unknown");
-        let store = self.store();
+        use crate::tmush::world_io::export_world;
 
-        // Check builder permissions (level 2+ required)
-        let player = store.get_player(&username.to_lowercase())?;
-        if !player.has_builder_level(2) {
-            return Ok(
-                "⛔ Permission denied. Creating rooms requires builder level 2 (Builder)."
-                    .to_string(),
-            );
+        if self.session_admin_level(session)?.unwrap_or(0) < 2 {
+            return Ok("⛔ Permission denied. World export requires admin level 2+.".to_string());
         }
 
-        // Parse direction
-        let direction = match parse_direction_string(&direction_str) {
-            Some(dir) => dir,
-            None => return Ok(format!("❌ Invalid direction: {}", direction_str)),
+        let name =
+            name.unwrap_or_else(|| format!("world_{}", chrono::Utc::now().format("%Y%m%d_%H%M%S")));
+        let Some(dir) = Self::world_export_dir(config, &name) else {
+            return Ok("❌ Export names may only use letters, digits, '-' and '_'.".to_string());
         };
 
-        // Get current room
-        let mut current_room = store.get_room(&player.current_room)?;
+        let manifest = export_world(self.store(), &dir, session.username.as_deref())?;
+        let mut response = format!("✅ World exported as '{}'\n", name);
+        for (kind, count) in &manifest.counts {
+            response.push_str(&format!("{}: {}\n", kind, count));
+        }
+        response.push_str(&format!("Use @IMPORT {} --dry-run to compare.", name));
+        Ok(response)
+    }
 
-        // Check if exit already exists
-        if current_room.exits.contains_key(&direction) {
+    /// Handle `@IMPORT` command - merge area files into the world
+    async fn handle_import_world(
+        &mut self,
+        session: &Session,
+        name: String,
+        overwrite: bool,
+        dry_run: bool,
+        config: &Config,
+    ) -> Result<String> {
+        use crate::tmush::world_io::{import_world, ImportOptions};
+
+        let level = self.session_admin_level(session)?.unwrap_or(0);
+        if level < 2 || (!dry_run && level < 3) {
             return Ok(format!(
-                "❌ An exit already exists to the {}.",
-                direction_str
+                "⛔ Permission denied. @IMPORT --dry-run requires admin level 2+, applying an import requires level 3 (sysop).\nYour admin level: {}",
+                level
             ));
         }
 
-        // Generate unique room ID
-        use chrono::Utc;
-        let timestamp = Utc::now().timestamp_millis();
-        let room_id = format!(
-            "{}_{}",
-            room_name.to_lowercase().replace(" ", "_"),
-            timestamp
-        );
-
-        // Create the new room
-        use crate::tmush::types::{RoomOwner, RoomRecord};
-        let new_room = RoomRecord {
-            id: room_id.clone(),
-            name: room_name.clone(),
-            short_desc: format!("A newly created room: {}", room_name),
-            long_desc: format!(
-                "This is {}. It needs a description.\nUse /DESCRIBE here <text> to customize it.",
-                room_name
-            ),
-            owner: RoomOwner::Player {
-                username: username.to_string(),
-            },
-            created_at: Utc::now(),
-            visibility: crate::tmush::types::RoomVisibility::Public,
-            exits: std::collections::HashMap::new(),
-            items: Vec::new(),
-            flags: vec![crate::tmush::types::RoomFlag::PlayerCreated],
-            max_capacity: 15,
-            housing_filter_tags: Vec::new(),
-            locked: false,
-            schema_version: crate::tmush::types::ROOM_SCHEMA_VERSION,
+        let Some(dir) = Self::world_export_dir(config, &name) else {
+            return Ok("❌ Export names may only use letters, digits, '-' and '_'.".to_string());
         };
+        if !dir.is_dir() {
+            return Ok(format!("❌ No export named '{}'.", name));
+        }
 
-        // Save new room
-        store.put_room(new_room.clone())?;
-
-        // Link current room to new room
-        current_room.exits.insert(direction, room_id.clone());
-        store.put_room(current_room.clone())?;
-
-        // Create reverse exit
-        let mut new_room_with_exit = new_room;
-        let reverse_direction = get_reverse_direction(&direction);
-        new_room_with_exit
-            .exits
-            .insert(reverse_direction, current_room.id.clone());
-        store.put_room(new_room_with_exit)?;
-
-        Ok(format!(
-            "✅ Created room '{}' ({})\n✅ Linked {} → {}\n✅ Linked {} {} → {}",
-            room_id,
-            room_name,
-            direction_str,
-            room_id,
-            room_id,
-            format_direction(&reverse_direction),
-            current_room.id
-        ))
+        match import_world(self.store(), &dir, ImportOptions { overwrite, dry_run }) {
+            Ok(report) => {
+                let mut response = report.summary();
+                if !dry_run {
+                    response.push_str(&format!("✅ {} records written.", report.changes()));
+                }
+                Ok(response)
+            }
+            Err(e) => Ok(format!("❌ Import aborted, nothing written: {}", e)),
+        }
     }
 
-    /// Handle `/DESCRIBE` command for targeting specific rooms or objects
-    ///
-    /// Sets the description of a specified target. Use "here" to describe the current room.
-    ///
-    /// # Permission Requirements
-    /// - Requires builder level 1+ (Apprentice)
-    /// - Can only edit rooms you own or have permission to edit
-    ///
-    /// # Example
-    /// ```text
-    /// > /DESCRIBE here A dark and mysterious cave filled with ancient crystals
-    /// ✅ Updated description for 'Mysterious Cave'
-    /// ```
-    /// Handle `/DESCRIBE` command for targeting specific rooms or objects
-    ///
-    /// Sets the description of a specified target. Use "here" to describe the current room.
-    /// Use an object name to describe objects in the current room.
-    ///
-    /// # Permission Requirements
-    /// - Requires builder level 1+ (Apprentice)
-    /// - Can only edit rooms you own or have permission to edit
-    /// - Can only edit objects you own or have permission to edit
-    ///
-    /// # Example
-    /// ```text
-    /// > /DESCRIBE here A dark and mysterious cave filled with ancient crystals
-    /// ✅ Updated description for 'Mysterious Cave'
-    ///
-    /// > /DESCRIBE sword An ancient blade forged in dragon fire
-    /// ✅ Updated description for 'Ancient Sword'
-    /// ```
-    async fn handle_describe_target(
+    // ============================================================================
+    // Dungeon Instances
+    // ============================================================================
+
+    /// Handle `DUNGEON` command - party status, invites and leaving
+    async fn handle_dungeon(
         &mut self,
         session: &Session,
-        target: String,
-        description: String,
+        subcommand: Option<String>,
         _config: &Config,
     ) -> Result<String> {
-        let username = session.username.as_deref().unwrap_or("unknown");
+        let mut player = self.get_or_create_player(session).await?;
         let store = self.store();
+        let words: Vec<String> = subcommand
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|w| w.to_string())
+            .collect();
 
-        // Check builder permissions (level 1+ required)
-        let player = store.get_player(&username.to_lowercase())?;
-        if !player.has_builder_level(1) {
-            return Ok(
-                "⛔ Permission denied. Setting descriptions requires builder level 1 (Apprentice)."
+        match words.first().map(String::as_str) {
+            None | Some("STATUS") => {
+                let parties: Vec<_> = store
+                    .list_dungeon_instances()?
+                    .into_iter()
+                    .filter(|i| i.is_member(&player.username))
+                    .collect();
+                if parties.is_empty() {
+                    return Ok(
+                        "You're not in a dungeon party.\nWalk into a dungeon entrance to open one."
+                            .to_string(),
+                    );
+                }
+                let now = chrono::Utc::now();
+                let mut response = String::from("Your dungeon parties:\n");
+                for instance in parties {
+                    let name = store
+                        .get_dungeon_template(&instance.template_id)
+                        .map(|t| t.name)
+                        .unwrap_or_else(|_| instance.template_id.clone());
+                    response.push_str(&format!(
+                        "{} - {} (lead {}), idle {}m\n",
+                        name,
+                        instance.members.join(", "),
+                        instance.leader,
+                        (now - instance.last_active).num_minutes()
+                    ));
+                }
+                response.push_str("DUNGEON INVITE <player> | DUNGEON LEAVE");
+                Ok(response)
+            }
+            Some("INVITE") => {
+                let Some(target) = words.get(1).map(|w| w.to_lowercase()) else {
+                    return Ok("Usage: DUNGEON INVITE <player>".to_string());
+                };
+                let Some(instance_id) = dungeon::instance_id_of(&player.current_room) else {
+                    return Ok("You can only invite players from inside your dungeon.".to_string());
+                };
+                let mut instance = store.get_dungeon_instance(instance_id)?;
+                if store.get_player(&target).is_err() {
+                    return Ok(format!("Player '{}' not found.", target));
+                }
+                match dungeon::invite(store, &mut instance, &target) {
+                    Ok(()) => Ok(format!(
+                        "✅ {} joined your party and can follow you in through the entrance.",
+                        target
+                    )),
+                    Err(TinyMushError::InstanceLimit(reason)) => {
+                        Ok(format!("❌ Can't invite {}: {}.", target, reason))
+                    }
+                    // Template deleted while the instance is still running
+                    Err(TinyMushError::NotFound(_)) => {
+                        Ok("❌ This dungeon no longer takes new party members.".to_string())
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Some("LEAVE") => {
+                let mut closed = Vec::new();
+                let mut left = 0;
+                for mut instance in store.list_dungeon_instances()? {
+                    if !instance.is_member(&player.username) {
+                        continue;
+                    }
+                    left += 1;
+                    instance
+                        .members
+                        .retain(|m| !m.eq_ignore_ascii_case(&player.username));
+                    if dungeon::instance_id_of(&player.current_room) == Some(instance.id.as_str()) {
+                        let return_room = match store.get_room(&instance.return_room) {
+                            Ok(room) => room.id,
+                            Err(_) => crate::tmush::state::REQUIRED_START_LOCATION_ID.to_string(),
+                        };
+                        store.relocate_player(&mut player, &return_room);
+                        store.put_player(player.clone())?;
+                    }
+                    if instance.members.is_empty() {
+                        dungeon::teardown(store, &instance)?;
+                        closed.push(instance.id);
+                    } else {
+                        if instance.leader.eq_ignore_ascii_case(&player.username) {
+                            instance.leader = instance.members[0].clone();
+                        }
+                        store.put_dungeon_instance(&instance)?;
+                    }
+                }
+                if left == 0 {
+                    return Ok("You're not in a dungeon party.".to_string());
+                }
+                let room_manager = self.get_room_manager().await?;
+                for instance_id in &closed {
+                    room_manager.forget_instance(instance_id);
+                }
+                Ok(format!(
+                    "You leave your dungeon party. You are in {}.",
+                    player.current_room
+                ))
+            }
+            _ => Ok("Usage: DUNGEON [INVITE <player>|LEAVE]".to_string()),
+        }
+    }
+
+    /// Handle `@DUNGEON` command - manage dungeon templates and running instances
+    async fn handle_dungeon_admin(
+        &mut self,
+        session: &Session,
+        subcommand: String,
+        args: Vec<String>,
+        config: &Config,
+    ) -> Result<String> {
+        let player = self.get_or_create_player(session).await?;
+        if !player.has_builder_level(3) && player.admin_level() < 2 {
+            return Ok("⛔ Permission denied. @DUNGEON requires builder level 3 (Architect) or admin level 2+.".to_string());
+        }
+        let store = self.store();
+
+        match subcommand.as_str() {
+            "LIST" => {
+                let templates = store.list_dungeon_templates()?;
+                let instances = store.list_dungeon_instances()?;
+                if templates.is_empty() {
+                    return Ok("No dungeons defined.\nUsage: @DUNGEON CREATE <id> <entry_room> [room ...]".to_string());
+                }
+                let max_instances = config
+                    .games
+                    .tinymush_dungeon_max_instances
+                    .unwrap_or(dungeon::DEFAULT_MAX_INSTANCES);
+                let mut response = format!(
+                    "Dungeons ({}/{} instances running):\n",
+                    instances.len(),
+                    max_instances
+                );
+                for template in &templates {
+                    response.push_str(&format!(
+                        "{} - {} rooms from {}, party {}\n",
+                        template.id,
+                        template.rooms.len(),
+                        template.entry_room,
+                        template.max_party
+                    ));
+                    for instance in instances.iter().filter(|i| i.template_id == template.id) {
+                        response.push_str(&format!(
+                            "  {} lead {} ({} in party)\n",
+                            instance.id,
+                            instance.leader,
+                            instance.members.len()
+                        ));
+                    }
+                }
+                Ok(response.trim_end().to_string())
+            }
+            "CREATE" => {
+                if args.len() < 2 {
+                    return Ok("Usage: @DUNGEON CREATE <id> <entry_room> [room ...]\nExample: @DUNGEON CREATE old_mine mine_entrance mine_shaft mine_depths".to_string());
+                }
+                let id = args[0].to_lowercase();
+                if !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Ok("❌ Dungeon IDs may only use letters, digits and _".to_string());
+                }
+                if store.get_dungeon_template(&id).is_ok() {
+                    return Ok(format!("❌ Dungeon '{}' already exists.", id));
+                }
+                let mut rooms: Vec<String> = Vec::new();
+                for room_id in args[1..].iter().map(|r| r.to_lowercase()) {
+                    if dungeon::is_instance_copy(&room_id) || store.get_world_room(&room_id).is_err() {
+                        return Ok(format!("❌ '{}' is not a world room.", room_id));
+                    }
+                    if !rooms.contains(&room_id) {
+                        rooms.push(room_id);
+                    }
+                }
+                let entry = rooms[0].clone();
+                if let Some(other) = store.dungeon_template_for_entry(&entry)? {
+                    return Ok(format!(
+                        "❌ {} is already the entrance of dungeon '{}'.",
+                        entry, other.id
+                    ));
+                }
+
+                let name = store.get_world_room(&entry)?.name;
+                let mut template =
+                    crate::tmush::types::DungeonTemplate::new(&id, &name, &entry, &player.username);
+                template.rooms = rooms;
+                store.put_dungeon_template(&template)?;
+                Ok(format!(
+                    "✅ Dungeon '{}' created from {} rooms. Walking into {} now opens a private copy per party.",
+                    id,
+                    template.rooms.len(),
+                    entry
+                ))
+            }
+            "PARTY" => {
+                let (Some(id), Some(size)) = (args.first(), args.get(1)) else {
+                    return Ok("Usage: @DUNGEON PARTY <id> <1-20>".to_string());
+                };
+                let Ok(size @ 1..=20) = size.parse::<u8>() else {
+                    return Ok("❌ Party size must be 1-20.".to_string());
+                };
+                let mut template = match store.get_dungeon_template(&id.to_lowercase()) {
+                    Ok(t) => t,
+                    Err(_) => return Ok(format!("❌ Dungeon '{}' not found.", id)),
+                };
+                template.max_party = size;
+                store.put_dungeon_template(&template)?;
+                Ok(format!(
+                    "✅ Dungeon '{}' parties are now up to {} players.",
+                    template.id, size
+                ))
+            }
+            "DELETE" => {
+                let Some(id) = args.first().map(|a| a.to_lowercase()) else {
+                    return Ok("Usage: @DUNGEON DELETE <id>".to_string());
+                };
+                if store.get_dungeon_template(&id).is_err() {
+                    return Ok(format!("❌ Dungeon '{}' not found.", id));
+                }
+                store.delete_dungeon_template(&id)?;
+                Ok(format!(
+                    "✅ Dungeon '{}' deleted. Running instances close when idle.",
+                    id
+                ))
+            }
+            "CLOSE" => {
+                let Some(instance_id) = args.first() else {
+                    return Ok("Usage: @DUNGEON CLOSE <instance_id>".to_string());
+                };
+                let instance = match store.get_dungeon_instance(instance_id) {
+                    Ok(i) => i,
+                    Err(_) => return Ok(format!("❌ No running instance '{}'.", instance_id)),
+                };
+                let evicted = dungeon::teardown(store, &instance)?;
+                self.get_room_manager()
+                    .await?
+                    .forget_instance(&instance.id);
+                Ok(format!(
+                    "✅ Closed {} ({} players returned to {}).",
+                    instance.id,
+                    evicted.len(),
+                    instance.return_room
+                ))
+            }
+            _ => Ok("Usage: @DUNGEON [LIST|CREATE <id> <entry_room> [room ...]|PARTY <id> <n>|DELETE <id>|CLOSE <instance_id>]".to_string()),
+        }
+    }
+
+    // ============================================================================
+    // Guilds
+    // ============================================================================
+
+    /// Handle `GUILD` command - membership, ranks, chat, treasury and hall
+    async fn handle_guild(
+        &mut self,
+        session: &Session,
+        args: Option<String>,
+        _config: &Config,
+    ) -> Result<String> {
+        use crate::tmush::types::{CurrencyAmount, GuildMember, GuildRecord};
+
+        let player = self.get_or_create_player(session).await?;
+        let store = self.store();
+        let args = args.unwrap_or_default();
+        let words: Vec<&str> = args.split_whitespace().collect();
+        let subcommand = words.first().map(|w| w.to_uppercase()).unwrap_or_default();
+        let rest = words.get(1..).unwrap_or_default().join(" ");
+        let mine = store.guild_for_player(&player.username)?;
+
+        // Subcommands for players outside any guild
+        match subcommand.as_str() {
+            "LIST" => {
+                let guilds = store.list_guilds()?;
+                if guilds.is_empty() {
+                    return Ok("No guilds yet. Found one with GUILD CREATE <name>.".to_string());
+                }
+                let mut response = String::from("Guilds:\n");
+                for guild in guilds {
+                    response.push_str(&format!(
+                        "{} ({} members)\n",
+                        guild.name,
+                        guild.members.len()
+                    ));
+                }
+                return Ok(response.trim_end().to_string());
+            }
+            "CREATE" => {
+                if let Some(guild) = mine {
+                    return Ok(format!("You're already in {}.", guild.name));
+                }
+                let name = rest.trim();
+                if !guild::is_valid_guild_name(name) {
+                    return Ok(format!(
+                        "Usage: GUILD CREATE <name>\nNames are 3-{} letters, digits, spaces, ' or -.",
+                        guild::MAX_GUILD_NAME_LEN
+                    ));
+                }
+                let id = guild::guild_id_for(name);
+                if store.get_guild(&id).is_ok() {
+                    return Ok(format!("❌ A guild named {} already exists.", name));
+                }
+                let treasury = match player.currency {
+                    CurrencyAmount::Decimal { .. } => CurrencyAmount::decimal(0),
+                    CurrencyAmount::MultiTier { .. } => CurrencyAmount::multi_tier(0),
+                };
+                let guild = GuildRecord::new(&id, name, &player.username, treasury);
+                store.put_guild(&guild)?;
+                return Ok(format!(
+                    "✅ You founded {}! Invite members with GUILD INVITE <player>.",
+                    guild.name
+                ));
+            }
+            "JOIN" => {
+                if let Some(guild) = mine {
+                    return Ok(format!("You're already in {}.", guild.name));
+                }
+                if rest.is_empty() {
+                    return Ok("Usage: GUILD JOIN <name>".to_string());
+                }
+                let mut guild = match store.get_guild(&guild::guild_id_for(&rest)) {
+                    Ok(guild) => guild,
+                    Err(TinyMushError::NotFound(_)) => {
+                        return Ok(format!("No guild named {}.", rest))
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                if !guild
+                    .invites
+                    .iter()
+                    .any(|i| i.eq_ignore_ascii_case(&player.username))
+                {
+                    return Ok(format!("You need an invitation to join {}.", guild.name));
+                }
+                guild
+                    .invites
+                    .retain(|i| !i.eq_ignore_ascii_case(&player.username));
+                guild.members.push(GuildMember {
+                    username: player.username.to_lowercase(),
+                    rank: 0,
+                    joined_at: chrono::Utc::now(),
+                });
+                store.put_guild(&guild)?;
+                guild::sync_hall_guests(store, &mut guild)?;
+                guild::publish_chat(
+                    store,
+                    &guild,
+                    &player.username,
+                    &player.current_room,
+                    "joined the guild.",
+                );
+                return Ok(format!(
+                    "✅ Welcome to {}! Talk to your guild with GC <message>.",
+                    guild.name
+                ));
+            }
+            _ => {}
+        }
+
+        let Some(mut guild) = mine else {
+            let invited: Vec<String> = store
+                .list_guilds()?
+                .into_iter()
+                .filter(|g| {
+                    g.invites
+                        .iter()
+                        .any(|i| i.eq_ignore_ascii_case(&player.username))
+                })
+                .map(|g| g.name)
+                .collect();
+            let mut response = String::from("You're not in a guild.\n");
+            if !invited.is_empty() {
+                response.push_str(&format!("Invited to: {}\n", invited.join(", ")));
+            }
+            response.push_str("GUILD LIST | GUILD CREATE <name> | GUILD JOIN <name>");
+            return Ok(response);
+        };
+        let my_rank = guild.member(&player.username).map(|m| m.rank).unwrap_or(0);
+
+        match subcommand.as_str() {
+            "" | "INFO" => {
+                let leader = guild
+                    .leader()
+                    .map(|m| m.username.clone())
+                    .unwrap_or_else(|| "none".to_string());
+                let hall = match &guild.hall_id {
+                    Some(hall_id) => store
+                        .get_housing_instance(hall_id)
+                        .map(|h| format!("{}'s {}", h.owner, h.template_id))
+                        .unwrap_or_else(|_| hall_id.clone()),
+                    None => "none".to_string(),
+                };
+                Ok(format!(
+                    "{} - you are {}\nLeader: {}, {} members\nTreasury: {} ({}+ may withdraw)\nHall: {}\nGUILD MEMBERS | GC <msg> | GUILD BANK",
+                    guild.name,
+                    guild.rank_name(my_rank),
+                    leader,
+                    guild.members.len(),
+                    guild.treasury.base_value(),
+                    guild.rank_name(guild.withdraw_rank),
+                    hall
+                ))
+            }
+            "MEMBERS" | "WHO" => {
+                let mut members = guild.members.clone();
+                members.sort_by(|a, b| b.rank.cmp(&a.rank).then(a.username.cmp(&b.username)));
+                let mut response = format!("{} members:\n", guild.name);
+                for member in members {
+                    response.push_str(&format!(
+                        "{} - {}\n",
+                        member.username,
+                        guild.rank_name(member.rank)
+                    ));
+                }
+                Ok(response.trim_end().to_string())
+            }
+            "SAY" => {
+                if rest.is_empty() {
+                    return Ok("Usage: GC <message>".to_string());
+                }
+                guild::publish_chat(
+                    store,
+                    &guild,
+                    &player.username,
+                    &player.current_room,
+                    &rest,
+                );
+                Ok(format!("[{}] You: {}", guild.name, rest))
+            }
+            "INVITE" => {
+                if !guild::can_manage(&guild, my_rank) {
+                    return Ok(format!(
+                        "Only {}s and up can invite.",
+                        guild.rank_name(guild.leader_rank().saturating_sub(1))
+                    ));
+                }
+                let Some(target) = words.get(1).map(|w| w.to_lowercase()) else {
+                    return Ok("Usage: GUILD INVITE <player>".to_string());
+                };
+                if store.get_player(&target).is_err() {
+                    return Ok(format!("Player '{}' not found.", target));
+                }
+                if let Some(other) = store.guild_for_player(&target)? {
+                    return Ok(format!("{} is already in {}.", target, other.name));
+                }
+                if !guild.invites.contains(&target) {
+                    guild.invites.push(target.clone());
+                    store.put_guild(&guild)?;
+                }
+                Ok(format!(
+                    "✅ Invited {}. They can join with GUILD JOIN {}.",
+                    target, guild.name
+                ))
+            }
+            "LEAVE" => {
+                if guild.members.len() == 1 {
+                    // Last one out disbands the guild and takes the treasury
+                    let refund = guild.treasury.clone();
+                    if !refund.is_zero_or_negative() {
+                        store.guild_withdraw(&guild.id, &player.username, &refund)?;
+                        guild = store.get_guild(&guild.id)?;
+                    }
+                    guild::release_hall(store, &mut guild)?;
+                    store.delete_guild(&guild.id)?;
+                    return Ok(format!(
+                        "You leave {}. With no members left, the guild is disbanded{}.",
+                        guild.name,
+                        if refund.is_zero_or_negative() {
+                            String::new()
+                        } else {
+                            format!(" and its treasury of {} is yours", refund.base_value())
+                        }
+                    ));
+                }
+                if my_rank == guild.leader_rank()
+                    && guild
+                        .members
+                        .iter()
+                        .filter(|m| m.rank == my_rank)
+                        .count()
+                        == 1
+                {
+                    return Ok(format!(
+                        "Promote someone to {} before leaving.",
+                        guild.rank_name(my_rank)
+                    ));
+                }
+                guild
+                    .members
+                    .retain(|m| !m.username.eq_ignore_ascii_case(&player.username));
+                store.put_guild(&guild)?;
+                guild::sync_hall_guests(store, &mut guild)?;
+                guild::publish_chat(
+                    store,
+                    &guild,
+                    &player.username,
+                    &player.current_room,
+                    "left the guild.",
+                );
+                Ok(format!("You leave {}.", guild.name))
+            }
+            "KICK" | "PROMOTE" | "DEMOTE" => {
+                let Some(target) = words.get(1).map(|w| w.to_lowercase()) else {
+                    return Ok(format!("Usage: GUILD {} <player>", subcommand));
+                };
+                if !guild::can_manage(&guild, my_rank) {
+                    return Ok(format!(
+                        "Only {}s and up can manage members.",
+                        guild.rank_name(guild.leader_rank().saturating_sub(1))
+                    ));
+                }
+                let Some(target_rank) = guild.member(&target).map(|m| m.rank) else {
+                    return Ok(format!("{} is not in {}.", target, guild.name));
+                };
+                if target_rank >= my_rank {
+                    return Ok(format!(
+                        "You can only manage members ranked below {}.",
+                        guild.rank_name(my_rank)
+                    ));
+                }
+                let leader_rank = guild.leader_rank();
+                let response = match subcommand.as_str() {
+                    "KICK" => {
+                        guild
+                            .members
+                            .retain(|m| !m.username.eq_ignore_ascii_case(&target));
+                        format!("{} has been removed from {}.", target, guild.name)
+                    }
+                    "PROMOTE" => {
+                        let new_rank = target_rank + 1;
+                        if new_rank == leader_rank {
+                            // Only the leader can hand over leadership, and steps down doing so
+                            if my_rank != leader_rank {
+                                return Ok(format!(
+                                    "Only the {} can promote to {}.",
+                                    guild.rank_name(leader_rank),
+                                    guild.rank_name(leader_rank)
+                                ));
+                            }
+                            if let Some(me) = guild.member_mut(&player.username) {
+                                me.rank = leader_rank - 1;
+                            }
+                        } else if new_rank >= my_rank {
+                            return Ok(format!(
+                                "You can only promote members up to {}.",
+                                guild.rank_name(my_rank - 1)
+                            ));
+                        }
+                        if let Some(member) = guild.member_mut(&target) {
+                            member.rank = new_rank;
+                        }
+                        format!("✅ {} is now {}.", target, guild.rank_name(new_rank))
+                    }
+                    _ => {
+                        if target_rank == 0 {
+                            return Ok(format!(
+                                "{} is already {}.",
+                                target,
+                                guild.rank_name(0)
+                            ));
+                        }
+                        if let Some(member) = guild.member_mut(&target) {
+                            member.rank = target_rank - 1;
+                        }
+                        format!("{} is now {}.", target, guild.rank_name(target_rank - 1))
+                    }
+                };
+                store.put_guild(&guild)?;
+                guild::sync_hall_guests(store, &mut guild)?;
+                Ok(response)
+            }
+            "BANK" | "DEPOSIT" | "WITHDRAW" => {
+                let (action, amount) = if subcommand == "BANK" {
+                    (
+                        words.get(1).map(|w| w.to_uppercase()).unwrap_or_default(),
+                        words.get(2).copied(),
+                    )
+                } else {
+                    (subcommand.clone(), words.get(1).copied())
+                };
+                if action.is_empty() {
+                    return Ok(format!(
+                        "{} treasury: {}\n{}+ may withdraw.\nGUILD DEPOSIT <amount> | GUILD WITHDRAW <amount>",
+                        guild.name,
+                        guild.treasury.base_value(),
+                        guild.rank_name(guild.withdraw_rank)
+                    ));
+                }
+                if action == "RANK" {
+                    if my_rank != guild.leader_rank() {
+                        return Ok(format!(
+                            "Only the {} can set who may withdraw.",
+                            guild.rank_name(guild.leader_rank())
+                        ));
+                    }
+                    let Some(rank) = amount.and_then(|r| {
+                        guild
+                            .ranks
+                            .iter()
+                            .position(|name| name.eq_ignore_ascii_case(r))
+                            .or_else(|| r.parse::<usize>().ok().filter(|n| *n < guild.ranks.len()))
+                    }) else {
+                        return Ok(format!(
+                            "Usage: GUILD BANK RANK <{}>",
+                            guild.ranks.join("|")
+                        ));
+                    };
+                    guild.withdraw_rank = rank as u8;
+                    store.put_guild(&guild)?;
+                    return Ok(format!(
+                        "✅ {}+ may now withdraw from the treasury.",
+                        guild.rank_name(guild.withdraw_rank)
+                    ));
+                }
+                if action != "DEPOSIT" && action != "WITHDRAW" {
+                    return Ok("Usage: GUILD BANK [DEPOSIT <amount>|WITHDRAW <amount>|RANK <rank>]".to_string());
+                }
+                let base_units = match amount.map(|a| a.parse::<i64>()) {
+                    Some(Ok(units)) if units > 0 => units,
+                    _ => return Ok(format!("Usage: GUILD {} <amount>", action)),
+                };
+                // Match the treasury's currency type
+                let amount = match guild.treasury {
+                    CurrencyAmount::Decimal { .. } => CurrencyAmount::decimal(base_units),
+                    CurrencyAmount::MultiTier { .. } => CurrencyAmount::multi_tier(base_units),
+                };
+                let result = if action == "DEPOSIT" {
+                    store.guild_deposit(&guild.id, &player.username, &amount)
+                } else {
+                    if my_rank < guild.withdraw_rank {
+                        return Ok(format!(
+                            "Only {}s and up can withdraw from the treasury.",
+                            guild.rank_name(guild.withdraw_rank)
+                        ));
+                    }
+                    store.guild_withdraw(&guild.id, &player.username, &amount)
+                };
+                match result {
+                    Ok(_) => {
+                        let treasury = store.get_guild(&guild.id)?.treasury.base_value();
+                        Ok(if action == "DEPOSIT" {
+                            format!(
+                                "✅ Deposited {} into the {} treasury (now {}).",
+                                base_units, guild.name, treasury
+                            )
+                        } else {
+                            format!(
+                                "✅ Withdrew {} from the {} treasury (now {}).",
+                                base_units, guild.name, treasury
+                            )
+                        })
+                    }
+                    Err(TinyMushError::InsufficientFunds) => Ok(if action == "DEPOSIT" {
+                        "You don't have that much on hand.".to_string()
+                    } else {
+                        "The treasury doesn't hold that much.".to_string()
+                    }),
+                    Err(e) => Ok(format!("Transaction failed: {}", e)),
+                }
+            }
+            "HALL" => {
+                let action = words.get(1).map(|w| w.to_uppercase()).unwrap_or_default();
+                match action.as_str() {
+                    "" => Ok(match &guild.hall_id {
+                        Some(hall_id) => {
+                            let hall = store.get_housing_instance(hall_id)?;
+                            format!(
+                                "{} hall: {}'s {} - every member is on its guest list.\nFind it under HOME LIST.",
+                                guild.name, hall.owner, hall.template_id
+                            )
+                        }
+                        None => "Your guild has no hall. Stand in your own housing and use GUILD HALL SET.".to_string(),
+                    }),
+                    "SET" => {
+                        if !guild::can_manage(&guild, my_rank) {
+                            return Ok(format!(
+                                "Only {}s and up can choose the hall.",
+                                guild.rank_name(guild.leader_rank().saturating_sub(1))
+                            ));
+                        }
+                        let Some(hall) = store
+                            .get_player_housing_instances(&player.username)?
+                            .into_iter()
+                            .find(|h| h.room_mappings.values().any(|r| r == &player.current_room))
+                        else {
+                            return Ok("Stand in your own housing to make it the guild hall.".to_string());
+                        };
+                        if guild.hall_id.as_deref() != Some(hall.id.as_str()) {
+                            guild::release_hall(store, &mut guild)?;
+                            guild.hall_id = Some(hall.id.clone());
+                            store.put_guild(&guild)?;
+                        }
+                        guild::sync_hall_guests(store, &mut guild)?;
+                        Ok(format!(
+                            "✅ This is now the {} hall. Its guest list follows the guild's members.",
+                            guild.name
+                        ))
+                    }
+                    "CLEAR" => {
+                        if !guild::can_manage(&guild, my_rank) {
+                            return Ok(format!(
+                                "Only {}s and up can give up the hall.",
+                                guild.rank_name(guild.leader_rank().saturating_sub(1))
+                            ));
+                        }
+                        if guild.hall_id.is_none() {
+                            return Ok("Your guild has no hall.".to_string());
+                        }
+                        guild::release_hall(store, &mut guild)?;
+                        Ok(format!("{} no longer has a hall.", guild.name))
+                    }
+                    _ => Ok("Usage: GUILD HALL [SET|CLEAR]".to_string()),
+                }
+            }
+            _ => Ok("Usage: GUILD [INFO|LIST|MEMBERS|CREATE <name>|INVITE <player>|JOIN <name>|LEAVE|KICK|PROMOTE|DEMOTE <player>|BANK|DEPOSIT|WITHDRAW <n>|HALL [SET|CLEAR]]\nChat: GC <message>".to_string()),
+        }
+    }
+
+    // ============================================================================
+    // Auction House
+    // ============================================================================
+
+    /// Handle `AUCTION` command - browse, list, bid, buy out and cancel
+    async fn handle_auction(
+        &mut self,
+        session: &Session,
+        args: Option<String>,
+        _config: &Config,
+    ) -> Result<String> {
+        let player = self.get_or_create_player(session).await?;
+        let args = args.unwrap_or_default();
+        let words: Vec<&str> = args.split_whitespace().collect();
+        let subcommand = words.first().map(|w| w.to_uppercase()).unwrap_or_default();
+        let rest = words.get(1..).unwrap_or_default();
+        let listing_id = rest
+            .first()
+            .and_then(|w| w.trim_start_matches('#').parse::<u64>().ok());
+        let now = chrono::Utc::now();
+
+        let time_left = |listing: &crate::tmush::types::AuctionListing| {
+            let minutes = (listing.ends_at - now).num_minutes().max(0);
+            if minutes >= 60 {
+                format!("{}h", minutes / 60)
+            } else {
+                format!("{}m", minutes)
+            }
+        };
+        let summary = |listing: &crate::tmush::types::AuctionListing| {
+            let item = if listing.quantity > 1 {
+                format!("{} x{}", listing.item_name, listing.quantity)
+            } else {
+                listing.item_name.clone()
+            };
+            let buyout = listing
+                .buyout
+                .as_ref()
+                .map(|b| format!(" buy {}", b.base_value()))
+                .unwrap_or_default();
+            format!(
+                "#{} {} - bid {}{} ({})",
+                listing.id,
+                item,
+                listing.next_min_bid(),
+                buyout,
+                time_left(listing)
+            )
+        };
+
+        match subcommand.as_str() {
+            "" | "LIST" | "BROWSE" | "MINE" => {
+                let mine = subcommand == "MINE";
+                let listings: Vec<_> = self
+                    .store()
+                    .list_auctions()?
+                    .into_iter()
+                    .filter(|l| {
+                        !mine
+                            || l.seller.eq_ignore_ascii_case(&player.username)
+                            || l.high_bid
+                                .as_ref()
+                                .is_some_and(|b| b.bidder.eq_ignore_ascii_case(&player.username))
+                    })
+                    .collect();
+                if listings.is_empty() {
+                    return Ok(if mine {
+                        "You have no listings or bids.".to_string()
+                    } else {
+                        "The auction house is empty. List an item with AUCTION SELL.".to_string()
+                    });
+                }
+                let mut response = String::from("Auctions:\n");
+                for listing in &listings {
+                    response.push_str(&summary(listing));
+                    response.push('\n');
+                }
+                response.push_str("AUCTION INFO|BID|BUY <id>");
+                Ok(response)
+            }
+            "INFO" => {
+                let Some(id) = listing_id else {
+                    return Ok("Usage: AUCTION INFO <id>".to_string());
+                };
+                let listing = match self.store().get_auction(id) {
+                    Ok(listing) => listing,
+                    Err(TinyMushError::NotFound(_)) => return Ok(format!("No auction #{}.", id)),
+                    Err(e) => return Err(e.into()),
+                };
+                let high = match &listing.high_bid {
+                    Some(bid) => format!("{} by {}", bid.amount.base_value(), bid.bidder),
+                    None => "none".to_string(),
+                };
+                let description = self
+                    .store()
+                    .get_object(&listing.object_id)
+                    .map(|o| o.description)
+                    .unwrap_or_default();
+                Ok(format!(
+                    "{}\n{}\nSeller: {}\nHigh bid: {}\nBuyout: {}\nEnds in {}",
+                    summary(&listing),
+                    description,
+                    listing.seller,
+                    high,
+                    listing
+                        .buyout
+                        .as_ref()
+                        .map(|b| b.base_value().to_string())
+                        .unwrap_or_else(|| "none".to_string()),
+                    time_left(&listing)
+                ))
+            }
+            "SELL" => {
+                let usage = format!(
+                    "Usage: AUCTION SELL [qty] <item> <min bid> [buyout] [hours]\nBuyout 0 = none. Default {}h, max {}h. Fee: {}",
+                    auction::DEFAULT_DURATION_HOURS,
+                    auction::MAX_DURATION_HOURS,
+                    self.get_world_config().await?.auction_listing_fee
+                );
+                // Up to three trailing numbers are prices and duration
+                let mut numbers = Vec::new();
+                let mut end = rest.len();
+                while end > 0 && numbers.len() < 3 {
+                    match rest[end - 1].parse::<i64>() {
+                        Ok(n) => numbers.insert(0, n),
+                        Err(_) => break,
+                    }
+                    end -= 1;
+                }
+                let mut item_words = &rest[..end];
+                let mut quantity = 1;
+                if item_words.len() > 1 {
+                    if let Ok(n) = item_words[0].parse::<u32>() {
+                        quantity = n;
+                        item_words = &item_words[1..];
+                    }
+                }
+                if item_words.is_empty() || numbers.is_empty() {
+                    return Ok(usage);
+                }
+                let min_bid = numbers[0];
+                let buyout = numbers.get(1).copied().filter(|b| *b > 0);
+                let hours = numbers
+                    .get(2)
+                    .copied()
+                    .unwrap_or(auction::DEFAULT_DURATION_HOURS);
+
+                let item_name = item_words.join(" ");
+                let inventory_ids: Vec<String> = player
+                    .inventory_stacks
+                    .iter()
+                    .map(|stack| stack.object_id.clone())
+                    .collect();
+                let matches = self.find_objects_by_partial_name(&item_name, &inventory_ids);
+                let object = match matches.len() {
+                    0 => return Ok(format!("You don't have a '{}'.", item_name)),
+                    1 => matches.into_iter().next().unwrap(),
+                    _ => {
+                        let names: Vec<String> = matches.into_iter().map(|o| o.name).collect();
+                        return Ok(format!("Which one? {}", names.join(", ")));
+                    }
+                };
+
+                match auction::create_listing(
+                    self.store(),
+                    &player.username,
+                    &object.id,
+                    quantity,
+                    min_bid,
+                    buyout,
+                    hours,
+                )? {
+                    Ok(listing) => Ok(format!(
+                        "✅ Listed as auction #{}: {}\nResults arrive by mail.",
+                        listing.id,
+                        summary(&listing)
+                    )),
+                    Err(refusal) => Ok(format!("❌ {}", refusal)),
+                }
+            }
+            "BID" | "BUY" => {
+                let Some(id) = listing_id else {
+                    return Ok("Usage: AUCTION BID <id> <amount> | AUCTION BUY <id>".to_string());
+                };
+                let result = if subcommand == "BUY" {
+                    auction::buyout(self.store(), &player.username, id)
+                } else {
+                    let Some(amount) = rest.get(1).and_then(|w| w.parse::<i64>().ok()) else {
+                        return Ok("Usage: AUCTION BID <id> <amount>".to_string());
+                    };
+                    auction::place_bid(self.store(), &player.username, id, amount)
+                };
+                match result {
+                    Ok(Ok(auction::BidOutcome::Won)) => Ok(format!(
+                        "🎉 You won auction #{}! The item is in your inventory.",
+                        id
+                    )),
+                    Ok(Ok(auction::BidOutcome::Leading)) => Ok(format!(
+                        "✅ You're the high bidder on auction #{}. Your bid is held until you win or are outbid.",
+                        id
+                    )),
+                    Ok(Err(refusal)) => Ok(format!("❌ {}", refusal)),
+                    Err(TinyMushError::NotFound(_)) => Ok(format!("No auction #{}.", id)),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            "CANCEL" => {
+                let Some(id) = listing_id else {
+                    return Ok("Usage: AUCTION CANCEL <id>".to_string());
+                };
+                match auction::cancel(self.store(), &player.username, id) {
+                    Ok(Ok(())) => Ok(format!(
+                        "Auction #{} cancelled. The item is back in your inventory.",
+                        id
+                    )),
+                    Ok(Err(refusal)) => Ok(format!("❌ {}", refusal)),
+                    Err(TinyMushError::NotFound(_)) => Ok(format!("No auction #{}.", id)),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            _ => Ok("Usage: AUCTION [LIST|MINE|INFO <id>|SELL [qty] <item> <min> [buyout] [hours]|BID <id> <amount>|BUY <id>|CANCEL <id>]".to_string()),
+        }
+    }
+
+    // ============================================================================
+    // Builder Edit History
+    // ============================================================================
+
+    /// Handle `@UNDO` command - revert the caller's newest world edit
+    async fn handle_undo(&mut self, session: &Session, _config: &Config) -> Result<String> {
+        let username = session.username.as_deref().unwrap_or("unknown");
+        let store = self.store();
+
+        let Some(mut edit) = edit_log::next_undo(store, username)? else {
+            return Ok("Nothing to undo.".to_string());
+        };
+        match edit_log::undo(store, &mut edit, username) {
+            Ok(()) => Ok(format!("↩️ Undid #{}: {}", edit.id, edit.command)),
+            Err(TinyMushError::EditConflict(what)) => Ok(format!(
+                "❌ Can't undo #{}: {} has changed since.",
+                edit.id, what
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handle `@REDO` command - re-apply the caller's last undone edit
+    async fn handle_redo(&mut self, session: &Session, _config: &Config) -> Result<String> {
+        let username = session.username.as_deref().unwrap_or("unknown");
+        let store = self.store();
+
+        let Some(mut edit) = edit_log::next_redo(store, username)? else {
+            return Ok("Nothing to redo.".to_string());
+        };
+        match edit_log::redo(store, &mut edit) {
+            Ok(()) => Ok(format!("↪️ Redid #{}: {}", edit.id, edit.command)),
+            Err(TinyMushError::EditConflict(what)) => Ok(format!(
+                "❌ Can't redo #{}: {} has changed since.",
+                edit.id, what
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Handle `@EDITLOG` command - page through recorded world edits
+    ///
+    /// The filter is a username if such a player exists, otherwise a room ID
+    /// (`here` = current room). Builders below level 3 can only list their own
+    /// edits, which is also their default.
+    async fn handle_edit_log(
+        &mut self,
+        session: &Session,
+        filter: Option<String>,
+        page: usize,
+        _config: &Config,
+    ) -> Result<String> {
+        /// Frame size the pages are packed for
+        const EDITLOG_FRAME_BYTES: usize = 200;
+        /// Reserved for the header and the "more" footer
+        const EDITLOG_CHROME_BYTES: usize = 90;
+
+        let username = session
+            .username
+            .as_deref()
+            .unwrap_or("unknown")
+            .to_lowercase();
+        let store = self.store();
+        let player = store.get_player(&username)?;
+        let can_audit = player.has_builder_level(3) || player.admin_level() >= 2;
+
+        let filter = filter.or_else(|| (!can_audit).then(|| username.clone()));
+        let edits = store.list_world_edits()?;
+        let (label, edits): (String, Vec<WorldEdit>) = match filter.as_deref() {
+            None => ("all".to_string(), edits),
+            Some(f) if store.get_player(&f.to_lowercase()).is_ok() => {
+                let name = f.to_lowercase();
+                if name != username && !can_audit {
+                    return Ok("⛔ Permission denied. Viewing other builders' edits requires builder level 3 (Architect).".to_string());
+                }
+                let edits = edits.into_iter().filter(|e| e.builder == name).collect();
+                (name, edits)
+            }
+            Some(f) => {
+                if !can_audit {
+                    return Ok("⛔ Permission denied. Viewing room history requires builder level 3 (Architect).".to_string());
+                }
+                let room_id = if f.eq_ignore_ascii_case("here") {
+                    player.current_room.clone()
+                } else {
+                    f.to_string()
+                };
+                let edits = edits
+                    .into_iter()
+                    .filter(|e| e.involves_room(&room_id))
+                    .collect();
+                (room_id, edits)
+            }
+        };
+
+        if edits.is_empty() {
+            return Ok(format!("No edits recorded for {}.", label));
+        }
+        let lines: Vec<String> = edits.iter().map(|e| e.summary_line()).collect();
+        let pages = edit_log::paginate(&lines, EDITLOG_FRAME_BYTES - EDITLOG_CHROME_BYTES);
+        let page = page.min(pages.len());
+        let mut response = format!(
+            "Edits {} p{}/{}:\n{}",
+            label,
+            page,
+            pages.len(),
+            pages[page - 1]
+        );
+        if page < pages.len() {
+            let arg = filter.map(|f| format!("{} ", f)).unwrap_or_default();
+            response.push_str(&format!("\n@EDITLOG {}{} for more", arg, page + 1));
+        }
+        Ok(response)
+    }
+
+    /// Handle `@ROLLBACK` command - undo a builder's edits over a time window
+    async fn handle_rollback(
+        &mut self,
+        session: &Session,
+        target: String,
+        window: String,
+        _config: &Config,
+    ) -> Result<String> {
+        let level = self.session_admin_level(session)?.unwrap_or(0);
+        if level < 3 {
+            return Ok(format!(
+                "⛔ Permission denied. @ROLLBACK requires admin level 3 (sysop).\nYour admin level: {}",
+                level
+            ));
+        }
+        let Some(span) = crate::storage::moderation::parse_duration(&window) else {
+            return Ok(format!(
+                "❌ Invalid window: {}\nUse minutes, hours, days or weeks, e.g. 30m, 12h, 7d.",
+                window
+            ));
+        };
+
+        let actor = session.username.as_deref().unwrap_or("unknown");
+        let since = chrono::Utc::now() - span;
+        let report = edit_log::rollback(self.store(), &target, since, actor)?;
+        if report.undone.is_empty() && report.conflicts.is_empty() {
+            return Ok(format!("No edits by {} in the last {}.", target, window));
+        }
+
+        let mut response = format!(
+            "⏪ Rolled back {} edits by {} from the last {}.",
+            report.undone.len(),
+            target,
+            window
+        );
+        if !report.conflicts.is_empty() {
+            response.push_str(&format!(
+                "\nKept {} changed since by others:",
+                report.conflicts.len()
+            ));
+            for (id, what) in &report.conflicts {
+                response.push_str(&format!("\n#{} {}", id, what));
+            }
+        }
+        Ok(response)
+    }
+
+    // ============================================================================
+    // Builder World Manipulation Commands (Phase 7)
+    // ============================================================================
+
+    /// Handle `/DIG` command - create a new room and link it from current location
+    ///
+    /// Creates a new room with the specified name and automatically creates a bidirectional
+    /// exit connecting the current room to the new room in the specified direction.
+    ///
+    /// # Permission Requirements
+    /// - Requires builder level 2+ (Builder or Architect)
+    ///
+    /// # Example
+    /// ```text
+    /// > /DIG north Mysterious Cave
+    /// ✅ Created room 'mysterious_cave_1234' (Mysterious Cave)
+    /// ✅ Linked north → mysterious_cave_1234
+    /// ✅ Linked mysterious_cave_1234 south → town_square
+    /// ```
+    async fn handle_dig(
+        &mut self,
+        session: &Session,
+        direction_str: String,
+        room_name: String,
+        _config: &Config,
+    ) -> Result<String> {
+        let username = session.username.as_deref().unwrap_or("unknown");
+        let store = self.store();
+
+        // Check builder permissions (level 2+ required)
+        let player = store.get_player(&username.to_lowercase())?;
+        if !player.has_builder_level(2) {
+            return Ok(
+                "⛔ Permission denied. Creating rooms requires builder level 2 (Builder)."
+                    .to_string(),
+            );
+        }
+
+        // Parse direction
+        let direction = match parse_direction_string(&direction_str) {
+            Some(dir) => dir,
+            None => return Ok(format!("❌ Invalid direction: {}", direction_str)),
+        };
+
+        // Get current room
+        let mut current_room = store.get_room(&player.current_room)?;
+
+        // Check if exit already exists
+        if current_room.exits.contains_key(&direction) {
+            return Ok(format!(
+                "❌ An exit already exists to the {}.",
+                direction_str
+            ));
+        }
+
+        // Generate unique room ID
+        use chrono::Utc;
+        let timestamp = Utc::now().timestamp_millis();
+        let room_id = format!(
+            "{}_{}",
+            room_name.to_lowercase().replace(" ", "_"),
+            timestamp
+        );
+
+        // Create the new room
+        use crate::tmush::types::{RoomOwner, RoomRecord};
+        let new_room = RoomRecord {
+            id: room_id.clone(),
+            name: room_name.clone(),
+            short_desc: format!("A newly created room: {}", room_name),
+            long_desc: format!(
+                "This is {}. It needs a description.\nUse /DESCRIBE here <text> to customize it.",
+                room_name
+            ),
+            owner: RoomOwner::Player {
+                username: username.to_string(),
+            },
+            created_at: Utc::now(),
+            visibility: crate::tmush::types::RoomVisibility::Public,
+            exits: std::collections::HashMap::new(),
+            items: Vec::new(),
+            flags: vec![crate::tmush::types::RoomFlag::PlayerCreated],
+            max_capacity: 15,
+            housing_filter_tags: Vec::new(),
+            locked: false,
+            schema_version: crate::tmush::types::ROOM_SCHEMA_VERSION,
+        };
+
+        let mut edit = WorldEdit::begin(
+            username,
+            &format!("/DIG {} {}", direction_str, room_name),
+            &current_room.id,
+        );
+        edit.touch(store, EditTarget::Room(current_room.id.clone()))?;
+        edit.touch(store, EditTarget::Room(room_id.clone()))?;
+
+        // Save new room
+        store.put_room(new_room.clone())?;
+
+        // Link current room to new room
+        current_room.exits.insert(direction, room_id.clone());
+        store.put_room(current_room.clone())?;
+
+        // Create reverse exit
+        let mut new_room_with_exit = new_room;
+        let reverse_direction = get_reverse_direction(&direction);
+        new_room_with_exit
+            .exits
+            .insert(reverse_direction, current_room.id.clone());
+        store.put_room(new_room_with_exit)?;
+        edit_log::record(store, edit)?;
+
+        Ok(format!(
+            "✅ Created room '{}' ({})\n✅ Linked {} → {}\n✅ Linked {} {} → {}",
+            room_id,
+            room_name,
+            direction_str,
+            room_id,
+            room_id,
+            format_direction(&reverse_direction),
+            current_room.id
+        ))
+    }
+
+    /// Handle `/DESCRIBE` command for targeting specific rooms or objects
+    ///
+    /// Sets the description of a specified target. Use "here" to describe the current room.
+    ///
+    /// # Permission Requirements
+    /// - Requires builder level 1+ (Apprentice)
+    /// - Can only edit rooms you own or have permission to edit
+    ///
+    /// # Example
+    /// ```text
+    /// > /DESCRIBE here A dark and mysterious cave filled with ancient crystals
+    /// ✅ Updated description for 'Mysterious Cave'
+    /// ```
+    /// Handle `/DESCRIBE` command for targeting specific rooms or objects
+    ///
+    /// Sets the description of a specified target. Use "here" to describe the current room.
+    /// Use an object name to describe objects in the current room.
+    ///
+    /// # Permission Requirements
+    /// - Requires builder level 1+ (Apprentice)
+    /// - Can only edit rooms you own or have permission to edit
+    /// - Can only edit objects you own or have permission to edit
+    ///
+    /// # Example
+    /// ```text
+    /// > /DESCRIBE here A dark and mysterious cave filled with ancient crystals
+    /// ✅ Updated description for 'Mysterious Cave'
+    ///
+    /// > /DESCRIBE sword An ancient blade forged in dragon fire
+    /// ✅ Updated description for 'Ancient Sword'
+    /// ```
+    async fn handle_describe_target(
+        &mut self,
+        session: &Session,
+        target: String,
+        description: String,
+        _config: &Config,
+    ) -> Result<String> {
+        let username = session.username.as_deref().unwrap_or("unknown");
+        let store = self.store();
+
+        // Check builder permissions (level 1+ required)
+        let player = store.get_player(&username.to_lowercase())?;
+        if !player.has_builder_level(1) {
+            return Ok(
+                "⛔ Permission denied. Setting descriptions requires builder level 1 (Apprentice)."
                     .to_string(),
             );
         }
@@ -11213,8 +13117,11 @@ Not fancy, but it gets the job done.",
                 return Ok("⛔ You don't have permission to edit this room.\nOnly the room owner or an Architect can edit it.".to_string());
             }
 
+            let mut edit = WorldEdit::begin(username, "/DESCRIBE here", &room.id);
+            edit.touch(store, EditTarget::Room(room.id.clone()))?;
             room.long_desc = description;
             store.put_room(room.clone())?;
+            edit_log::record(store, edit)?;
 
             return Ok(format!("✅ Updated description for '{}'", room.name));
         }
@@ -11252,8 +13159,15 @@ Not fancy, but it gets the job done.",
                 }
 
                 // Update description
+                let mut edit = WorldEdit::begin(
+                    username,
+                    &format!("/DESCRIBE {}", target),
+                    &player.current_room,
+                );
+                edit.touch(store, EditTarget::Object(object.id.clone()))?;
                 object.description = description;
                 store.put_object(object.clone())?;
+                edit_log::record(store, edit)?;
 
                 Ok(format!("✅ Updated description for '{}'", object.name))
             },
@@ -11334,8 +13248,15 @@ Not fancy, but it gets the job done.",
         }
 
         // Create the exit
+        let mut edit = WorldEdit::begin(
+            username,
+            &format!("/LINK {} {}", direction_str, destination),
+            &current_room.id,
+        );
+        edit.touch(store, EditTarget::Room(current_room.id.clone()))?;
         current_room.exits.insert(direction, destination.clone());
         store.put_room(current_room)?;
+        edit_log::record(store, edit)?;
 
         Ok(format!(
             "✅ Created exit {} → {}",
@@ -11401,8 +13322,15 @@ Not fancy, but it gets the job done.",
         }
 
         // Remove the exit
+        let mut edit = WorldEdit::begin(
+            username,
+            &format!("/UNLINK {}", direction_str),
+            &current_room.id,
+        );
+        edit.touch(store, EditTarget::Room(current_room.id.clone()))?;
         current_room.exits.remove(&direction);
         store.put_room(current_room)?;
+        edit_log::record(store, edit)?;
 
         Ok(format!("✅ Removed exit to the {}", direction_str))
     }
@@ -11492,9 +13420,13 @@ Not fancy, but it gets the job done.",
                 None => return Ok(format!("❌ Unknown room flag: {}\nValid flags: safe, dark, indoor, shop, pvpenabled, private, moderated, noteleportout", flag_name)),
             };
 
+            let mut edit =
+                WorldEdit::begin(username, &format!("/SETFLAG here {}", flag_str), &room.id);
+            edit.touch(store, EditTarget::Room(room.id.clone()))?;
             if remove {
                 room.flags.retain(|f| f != &flag);
                 store.put_room(room.clone())?;
+                edit_log::record(store, edit)?;
                 Ok(format!(
                     "✅ Removed flag '{}' from '{}'",
                     flag_name, room.name
@@ -11504,6 +13436,7 @@ Not fancy, but it gets the job done.",
                     room.flags.push(flag);
                 }
                 store.put_room(room.clone())?;
+                edit_log::record(store, edit)?;
                 Ok(format!("✅ Added flag '{}' to '{}'", flag_name, room.name))
             }
         } else {
@@ -11545,15 +13478,23 @@ Not fancy, but it gets the job done.",
                         None => return Ok(format!("❌ Unknown object flag: {}\nValid flags: questitem, consumable, equipment, keyitem, container, magical, companion", flag_name)),
                     };
 
+                    let mut edit = WorldEdit::begin(
+                        username,
+                        &format!("/SETFLAG {} {}", target, flag_str),
+                        &player.current_room,
+                    );
+                    edit.touch(store, EditTarget::Object(object.id.clone()))?;
                     if remove {
                         object.flags.retain(|f| f != &flag);
                         store.put_object(object.clone())?;
+                        edit_log::record(store, edit)?;
                         Ok(format!("✅ Removed flag '{}' from '{}'", flag_name, object.name))
                     } else {
                         if !object.flags.contains(&flag) {
                             object.flags.push(flag);
                         }
                         store.put_object(object.clone())?;
+                        edit_log::record(store, edit)?;
                         Ok(format!("✅ Added flag '{}' to '{}'", flag_name, object.name))
                     }
                 },
@@ -11617,6 +13558,14 @@ Not fancy, but it gets the job done.",
             OwnershipReason::Created,
         );
 
+        let mut edit = WorldEdit::begin(
+            username,
+            &format!("/CREATE {}", object_name),
+            &player.current_room,
+        );
+        edit.touch(store, EditTarget::Object(object_id.clone()))?;
+        edit.touch(store, EditTarget::Room(player.current_room.clone()))?;
+
         // Save object
         store.put_object(object)?;
 
@@ -11624,6 +13573,7 @@ Not fancy, but it gets the job done.",
         let mut room = store.get_room(&player.current_room)?;
         room.items.push(object_id.clone());
         store.put_room(room)?;
+        edit_log::record(store, edit)?;
 
         Ok(format!(
             "✅ Created object '{}' ({})",
@@ -11714,9 +13664,18 @@ Not fancy, but it gets the job done.",
                 // Get the object to show its name in response
                 let object = store.get_object(&id)?;
 
+                let mut edit = WorldEdit::begin(
+                    username,
+                    &format!("/DESTROY {}", object_name),
+                    &player.current_room,
+                );
+                edit.touch(store, EditTarget::Object(id.clone()))?;
+                edit.touch(store, EditTarget::Room(player.current_room.clone()))?;
+
                 // Attempt to delete the object (handles container safety)
                 match store.delete_object(&id, &player.current_room) {
                     Ok(relocated_items) => {
+                        edit_log::record(store, edit)?;
                         if relocated_items.is_empty() {
                             Ok(format!("✅ Deleted object '{}'", object.name))
                         } else {
@@ -12034,6 +13993,16 @@ Not fancy, but it gets the job done.",
             Some("companion") | Some("COMPANION") | Some("companions") | Some("COMPANIONS") => {
                 Ok(world_config.help_companion)
             }
+            Some("combat") | Some("COMBAT") => Ok(self.help_combat()),
+            Some("reputation") | Some("REPUTATION") | Some("factions") | Some("FACTIONS") => {
+                Ok(self.help_reputation())
+            }
+            Some("skills") | Some("SKILLS") | Some("levels") | Some("LEVELS") => {
+                Ok(self.help_skills())
+            }
+            Some("magic") | Some("MAGIC") | Some("spells") | Some("SPELLS") => {
+                Ok(self.help_magic())
+            }
             None => Ok(world_config.help_main),
             Some(topic) => Ok(format!(
                 "No help available for: {}\nTry: HELP COMMANDS",
@@ -12408,7 +14377,7 @@ Not fancy, but it gets the job done.",
         for username in players_to_teleport {
             let store = self.store();
             if let Ok(mut player) = store.get_player_async(&username).await {
-                player.current_room = "town_square".to_string();
+                store.relocate_player(&mut player, "town_square");
                 store.put_player_async(player).await?;
                 players_teleported += 1;
             }
@@ -12675,6 +14644,16 @@ Obvious exits: (too dark to see clearly)".to_string()
                     }
                 }
 
+                // Show hostile creatures in room
+                if let Ok(mobs) = self.store().get_mobs_in_room(&player.current_room) {
+                    if !mobs.is_empty() {
+                        response.push('\n');
+                        for mob in &mobs {
+                            response.push_str(&format!("⚔️ A {} is here.\n", mob.name));
+                        }
+                    }
+                }
+
                 // Show objects in room
                 if !room.items.is_empty() {
                     response.push('\n');
@@ -12768,6 +14747,52 @@ Obvious exits: (too dark to see clearly)".to_string()
             + "TRAIN <name> <skill> - teach"
     }
 
+    /// Combat help
+    pub fn help_combat(&self) -> String {
+        "=COMBAT=\n".to_string()
+            + "ATTACK <foe> - fight/hit\n"
+            + "DEFEND - brace for a blow\n"
+            + "FLEE - try to escape\n"
+            + "USE/CAST - uses your turn\n"
+            + "Safe areas: no fighting\n"
+            + "Fall in battle: wake in\n"
+            + "Town Square at full HP"
+    }
+
+    /// Reputation help
+    pub fn help_reputation(&self) -> String {
+        "=REPUTATION=\n".to_string()
+            + "REP - standing with factions\n"
+            + "REP <faction> - perks, next lvl\n"
+            + "Earn it via quests & NPC talk\n"
+            + "Faction shops: discounts when\n"
+            + "Friendly+, surcharge when\n"
+            + "Unfriendly, refuse if Hostile"
+    }
+
+    /// Skills and levels help
+    pub fn help_skills(&self) -> String {
+        "=SKILLS=\n".to_string()
+            + "SKILLS - level, XP, ranks\n"
+            + "XP from quests, crafting,\n"
+            + "new rooms and victories\n"
+            + "Levels raise HP, MP, stats\n"
+            + "Skills rank up with use;\n"
+            + "recipes & NPCs may need them"
+    }
+
+    /// Magic help
+    pub fn help_magic(&self) -> String {
+        "=MAGIC=\n".to_string()
+            + "SPELLS [page] - MP & spells\n"
+            + "CAST <spell> [target]\n"
+            + "LEARN - spells taught here\n"
+            + "LEARN <spell> - pay trainer\n"
+            + "LEARN <scroll> - study it\n"
+            + "MP returns 1 per 30s\n"
+            + "Casting trains magic rank"
+    }
+
     /// Handle MAIL command - view mail folders
     async fn handle_mail(
         &mut self,
@@ -0,0 +1,273 @@
+//! Dungeon instances: private per-party copies of a set of world rooms
+//!
+//! Builders mark world rooms as a [`DungeonTemplate`] with `@DUNGEON CREATE`.
+//! Walking into the template's entry room puts a player in their party's
+//! [`DungeonInstance`] instead, spawning one on first entry: every template
+//! room is copied under a `dungeon::<instance>::<room>` ID together with the
+//! objects lying in it and the mobs that live there, and exits between
+//! template rooms are remapped to the copies. Exits leading anywhere else are
+//! kept, so walking out works like leaving any other room.
+//!
+//! - The party is the leader plus whoever a member brings along with
+//!   `DUNGEON INVITE`, up to the template's `max_party`;
+//!   [`RoomManager`](crate::tmush::room_manager::RoomManager) keeps everyone
+//!   else out of the copies
+//! - An instance nobody has moved around in for the idle timeout is torn down
+//!   by the server tick; players still inside are returned to the room the
+//!   party entered from and loot already picked up is kept
+//! - `[games]` settings cap how many instances may run at once and set the
+//!   idle timeout
+
+use chrono::{DateTime, Duration, Utc};
+use log::info;
+use std::collections::HashMap;
+
+use crate::tmush::state::REQUIRED_START_LOCATION_ID;
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{DungeonInstance, DungeonTemplate, ObjectOwner, RoomFlag};
+use crate::tmush::TinyMushError;
+
+/// Prefix of every room, object and mob ID copied into an instance
+pub const DUNGEON_INSTANCE_PREFIX: &str = "dungeon::";
+
+/// Concurrent instance cap when `games.tinymush_dungeon_max_instances` is unset
+pub const DEFAULT_MAX_INSTANCES: usize = 8;
+
+/// Idle timeout when `games.tinymush_dungeon_idle_minutes` is unset
+pub const DEFAULT_IDLE_MINUTES: i64 = 30;
+
+/// Instance that owns a copied room, object or mob ID
+pub fn instance_id_of(id: &str) -> Option<&str> {
+    id.strip_prefix(DUNGEON_INSTANCE_PREFIX)?.split("::").next()
+}
+
+/// Returns true if the ID belongs to a dungeon instance copy
+pub fn is_instance_copy(id: &str) -> bool {
+    id.starts_with(DUNGEON_INSTANCE_PREFIX)
+}
+
+/// Template room a copy was made from; other room IDs are returned unchanged
+pub fn original_room_of(id: &str) -> &str {
+    id.strip_prefix(DUNGEON_INSTANCE_PREFIX)
+        .and_then(|rest| rest.split_once("::"))
+        .map(|(_, original)| original)
+        .unwrap_or(id)
+}
+
+fn copy_id(instance_id: &str, original: &str) -> String {
+    format!("{}{}::{}", DUNGEON_INSTANCE_PREFIX, instance_id, original)
+}
+
+/// Running instance of `template_id` whose party includes `username`
+pub fn instance_for_member(
+    store: &TinyMushStore,
+    template_id: &str,
+    username: &str,
+) -> Result<Option<DungeonInstance>, TinyMushError> {
+    Ok(store
+        .list_dungeon_instances()?
+        .into_iter()
+        .find(|i| i.template_id == template_id && i.is_member(username)))
+}
+
+/// Copy the template's rooms, objects and mobs into a new instance led by `leader`
+pub fn spawn_instance(
+    store: &TinyMushStore,
+    template: &DungeonTemplate,
+    leader: &str,
+    return_room: &str,
+) -> Result<DungeonInstance, TinyMushError> {
+    let id = format!("{}-{}", template.id, store.db().generate_id()?);
+    let room_mappings: HashMap<String, String> = template
+        .rooms
+        .iter()
+        .map(|room_id| (room_id.clone(), copy_id(&id, room_id)))
+        .collect();
+
+    let mut mob_ids = Vec::new();
+    for room_id in &template.rooms {
+        let mut room = store.get_room(room_id)?;
+        room.id = room_mappings[room_id].clone();
+        for target in room.exits.values_mut() {
+            if let Some(copy) = room_mappings.get(target) {
+                *target = copy.clone();
+            }
+        }
+        if !room.flags.contains(&RoomFlag::Instanced) {
+            room.flags.push(RoomFlag::Instanced);
+        }
+
+        let mut items = Vec::with_capacity(room.items.len());
+        for item_id in &room.items {
+            let mut object = match store.get_object(item_id) {
+                Ok(object) => object,
+                Err(TinyMushError::NotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            object.id = copy_id(&id, item_id);
+            object.owner = ObjectOwner::World;
+            items.push(object.id.clone());
+            store.put_object(object)?;
+        }
+        room.items = items;
+        store.put_room(room)?;
+
+        for mob_id in store.list_mob_ids()? {
+            let mut mob = store.get_mob(&mob_id)?;
+            if &mob.room_id != room_id {
+                continue;
+            }
+            mob.id = copy_id(&id, &mob_id);
+            mob.room_id = room_mappings[room_id].clone();
+            mob.defeated_until = None;
+            mob_ids.push(mob.id.clone());
+            store.put_mob(mob)?;
+        }
+    }
+
+    let now = Utc::now();
+    let instance = DungeonInstance {
+        id,
+        template_id: template.id.clone(),
+        leader: leader.to_lowercase(),
+        members: vec![leader.to_lowercase()],
+        room_mappings,
+        mob_ids,
+        return_room: return_room.to_string(),
+        created_at: now,
+        last_active: now,
+        schema_version: 1,
+    };
+    store.put_dungeon_instance(&instance)?;
+    info!(
+        "Dungeon instance {} of {} opened by {}",
+        instance.id, template.id, leader
+    );
+    Ok(instance)
+}
+
+/// If `room_id` is a dungeon entrance, the room `username` lands in instead:
+/// the entrance of their party's instance, spawned if they have none yet
+pub fn route_entry(
+    store: &TinyMushStore,
+    username: &str,
+    room_id: &str,
+    from_room: &str,
+    max_instances: usize,
+) -> Result<Option<String>, TinyMushError> {
+    let Some(template) = store.dungeon_template_for_entry(room_id)? else {
+        return Ok(None);
+    };
+    let instance = match instance_for_member(store, &template.id, username)? {
+        Some(instance) => instance,
+        None => {
+            let running = store.list_dungeon_instances()?.len();
+            if running >= max_instances {
+                return Err(TinyMushError::InstanceLimit(format!(
+                    "{} dungeon instances already running",
+                    running
+                )));
+            }
+            spawn_instance(store, &template, username, from_room)?
+        }
+    };
+    Ok(instance.room_mappings.get(&template.entry_room).cloned())
+}
+
+/// Record activity in the instance owning `room_id`, if any
+pub fn touch(store: &TinyMushStore, room_id: &str) -> Result<(), TinyMushError> {
+    let Some(instance_id) = instance_id_of(room_id) else {
+        return Ok(());
+    };
+    match store.get_dungeon_instance(instance_id) {
+        Ok(mut instance) => {
+            instance.last_active = Utc::now();
+            store.put_dungeon_instance(&instance)
+        }
+        Err(TinyMushError::NotFound(_)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Add `username` to the party of `instance`
+pub fn invite(
+    store: &TinyMushStore,
+    instance: &mut DungeonInstance,
+    username: &str,
+) -> Result<(), TinyMushError> {
+    if instance.is_member(username) {
+        return Ok(());
+    }
+    let max_party = store.get_dungeon_template(&instance.template_id)?.max_party;
+    if instance.members.len() >= max_party as usize {
+        return Err(TinyMushError::InstanceLimit(format!(
+            "party is full ({} players)",
+            max_party
+        )));
+    }
+    instance.members.push(username.to_lowercase());
+    store.put_dungeon_instance(instance)
+}
+
+/// Remove the instance: players still inside go back to where the party
+/// entered from, then the copied rooms, mobs and any objects left lying in
+/// them are deleted. Returns the players that were moved out.
+pub fn teardown(
+    store: &TinyMushStore,
+    instance: &DungeonInstance,
+) -> Result<Vec<String>, TinyMushError> {
+    let return_room = match store.get_room(&instance.return_room) {
+        Ok(_) if !is_instance_copy(&instance.return_room) => instance.return_room.clone(),
+        _ => REQUIRED_START_LOCATION_ID.to_string(),
+    };
+
+    let mut evicted = Vec::new();
+    for member in &instance.members {
+        let Ok(mut player) = store.get_player(member) else {
+            continue;
+        };
+        if instance_id_of(&player.current_room) == Some(instance.id.as_str()) {
+            store.relocate_player(&mut player, &return_room);
+            store.put_player(player)?;
+            evicted.push(member.clone());
+        }
+    }
+
+    for mob_id in &instance.mob_ids {
+        store.delete_mob(mob_id)?;
+    }
+    for room_id in instance.room_mappings.values() {
+        let room = match store.get_room(room_id) {
+            Ok(room) => room,
+            Err(TinyMushError::NotFound(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        for item_id in room.items.iter().filter(|id| is_instance_copy(id)) {
+            store.delete_object_world(item_id)?;
+        }
+        store.delete_room(room_id)?;
+    }
+    store.delete_dungeon_instance(&instance.id)?;
+    info!(
+        "Dungeon instance {} torn down ({} players returned)",
+        instance.id,
+        evicted.len()
+    );
+    Ok(evicted)
+}
+
+/// Tear down every instance without activity since `now - idle`
+pub fn reap_idle(
+    store: &TinyMushStore,
+    idle: Duration,
+    now: DateTime<Utc>,
+) -> Result<Vec<DungeonInstance>, TinyMushError> {
+    let mut reaped = Vec::new();
+    for instance in store.list_dungeon_instances()? {
+        if now - instance.last_active >= idle {
+            teardown(store, &instance)?;
+            reaped.push(instance);
+        }
+    }
+    Ok(reaped)
+}
@@ -0,0 +1,297 @@
+//! Builder edit history: undo, redo, audit and rollback
+//!
+//! Every builder command that changes the world (`/DIG`, `/LINK`, `/UNLINK`,
+//! `/SETFLAG`, `/DESCRIBE`, `/CREATE`, `/DESTROY`, housing `DESCRIBE`,
+//! `@EDITROOM`, `@EDITNPC`) is recorded as a [`WorldEdit`]: who ran it, the
+//! command, the room it was run in, and a before/after snapshot of every room,
+//! object and NPC it touched. Edits are kept oldest first in the
+//! `tinymush_world_edits` sled tree.
+//!
+//! - `@UNDO` / `@REDO` step back and forward through the caller's own edits;
+//!   a new edit clears the redo run
+//! - `@EDITLOG [player|room|here] [page]` pages through the history
+//! - `@ROLLBACK <player> <30m|12h|7d>` (sysop) undoes everything a builder did
+//!   in the window, newest first
+//!
+//! An edit is only reverted when every record it touched is still exactly as
+//! the edit left it. Otherwise [`TinyMushError::EditConflict`] names the first
+//! record that changed since and nothing is written.
+
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{NpcRecord, ObjectRecord, RoomRecord};
+use crate::tmush::TinyMushError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Room, object or NPC touched by an edit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditTarget {
+    Room(String),
+    Object(String),
+    Npc(String),
+}
+
+impl std::fmt::Display for EditTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditTarget::Room(id) => write!(f, "room {}", id),
+            EditTarget::Object(id) => write!(f, "object {}", id),
+            EditTarget::Npc(id) => write!(f, "NPC {}", id),
+        }
+    }
+}
+
+/// Full copy of a record at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EditSnapshot {
+    Room(RoomRecord),
+    Object(ObjectRecord),
+    Npc(NpcRecord),
+}
+
+/// State of one record before and after an edit (`None` = did not exist)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditChange {
+    pub target: EditTarget,
+    pub before: Option<EditSnapshot>,
+    pub after: Option<EditSnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldEdit {
+    /// Assigned by the store when first saved
+    pub id: u64,
+    pub builder: String,
+    pub command: String,
+    /// Room the builder was standing in
+    pub room_id: String,
+    pub created_at: DateTime<Utc>,
+    pub changes: Vec<EditChange>,
+    /// Who reverted this edit: the builder via `@UNDO` or a sysop via `@ROLLBACK`
+    pub undone_by: Option<String>,
+}
+
+impl WorldEdit {
+    /// Start recording an edit; call [`WorldEdit::touch`] for each record
+    /// before modifying it and [`record`] afterwards
+    pub fn begin(builder: &str, command: &str, room_id: &str) -> Self {
+        Self {
+            id: 0,
+            builder: builder.to_lowercase(),
+            command: command.to_string(),
+            room_id: room_id.to_string(),
+            created_at: Utc::now(),
+            changes: Vec::new(),
+            undone_by: None,
+        }
+    }
+
+    /// Snapshot `target` as it is now. Only the first call per target counts.
+    pub fn touch(
+        &mut self,
+        store: &TinyMushStore,
+        target: EditTarget,
+    ) -> Result<(), TinyMushError> {
+        if self.changes.iter().any(|c| c.target == target) {
+            return Ok(());
+        }
+        let before = snapshot(store, &target)?;
+        self.changes.push(EditChange {
+            target,
+            before,
+            after: None,
+        });
+        Ok(())
+    }
+
+    pub fn is_undone(&self) -> bool {
+        self.undone_by.is_some()
+    }
+
+    /// True if the edit was made in `room_id` or changed that room
+    pub fn involves_room(&self, room_id: &str) -> bool {
+        self.room_id == room_id
+            || self
+                .changes
+                .iter()
+                .any(|c| matches!(&c.target, EditTarget::Room(id) if id == room_id))
+    }
+
+    /// One line for `@EDITLOG`
+    pub fn summary_line(&self) -> String {
+        let mut command: String = self.command.chars().take(28).collect();
+        if command.len() < self.command.len() {
+            command.push('…');
+        }
+        let mut line = format!(
+            "#{} {} {} {}",
+            self.id,
+            self.created_at.format("%m-%d %H:%M"),
+            self.builder,
+            command
+        );
+        if self.is_undone() {
+            line.push_str(" (undone)");
+        }
+        line
+    }
+}
+
+fn found<T>(res: Result<T, TinyMushError>) -> Result<Option<T>, TinyMushError> {
+    match res {
+        Ok(v) => Ok(Some(v)),
+        Err(TinyMushError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn snapshot(
+    store: &TinyMushStore,
+    target: &EditTarget,
+) -> Result<Option<EditSnapshot>, TinyMushError> {
+    Ok(match target {
+        EditTarget::Room(id) => store.find_room_any_owner(id)?.map(EditSnapshot::Room),
+        EditTarget::Object(id) => found(store.get_object(id))?.map(EditSnapshot::Object),
+        EditTarget::Npc(id) => found(store.get_npc(id))?.map(EditSnapshot::Npc),
+    })
+}
+
+/// Replace whatever is stored for a target with `wanted`
+fn restore(
+    store: &TinyMushStore,
+    current: Option<EditSnapshot>,
+    wanted: Option<&EditSnapshot>,
+) -> Result<(), TinyMushError> {
+    // Records are keyed by owner, so clear the current copy first
+    match current {
+        Some(EditSnapshot::Room(room)) => store.remove_room_record(&room)?,
+        Some(EditSnapshot::Object(object)) => store.remove_object_record(&object)?,
+        Some(EditSnapshot::Npc(npc)) => store.delete_npc(&npc.id)?,
+        None => {}
+    }
+    match wanted.cloned() {
+        Some(EditSnapshot::Room(room)) => store.put_room(room),
+        Some(EditSnapshot::Object(object)) => store.put_object(object),
+        Some(EditSnapshot::Npc(npc)) => store.put_npc(npc),
+        None => Ok(()),
+    }
+}
+
+/// Capture the after state of every touched record and save the edit.
+/// Returns the edit id, or `None` if nothing actually changed.
+pub fn record(store: &TinyMushStore, mut edit: WorldEdit) -> Result<Option<u64>, TinyMushError> {
+    for change in &mut edit.changes {
+        change.after = snapshot(store, &change.target)?;
+    }
+    edit.changes.retain(|c| c.before != c.after);
+    if edit.changes.is_empty() {
+        return Ok(None);
+    }
+    store.put_world_edit(&mut edit)?;
+    Ok(Some(edit.id))
+}
+
+/// Move every record of `edit` from one side to the other, if they all
+/// still match the expected side
+fn apply(store: &TinyMushStore, edit: &WorldEdit, undo: bool) -> Result<(), TinyMushError> {
+    let mut current = Vec::with_capacity(edit.changes.len());
+    for change in &edit.changes {
+        let expected = if undo { &change.after } else { &change.before };
+        let now = snapshot(store, &change.target)?;
+        if &now != expected {
+            return Err(TinyMushError::EditConflict(change.target.to_string()));
+        }
+        current.push(now);
+    }
+    for (change, now) in edit.changes.iter().zip(current) {
+        let wanted = if undo { &change.before } else { &change.after };
+        restore(store, now, wanted.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Revert `edit` and mark it undone by `actor`
+pub fn undo(store: &TinyMushStore, edit: &mut WorldEdit, actor: &str) -> Result<(), TinyMushError> {
+    apply(store, edit, true)?;
+    edit.undone_by = Some(actor.to_lowercase());
+    store.put_world_edit(edit)
+}
+
+/// Re-apply an undone `edit`
+pub fn redo(store: &TinyMushStore, edit: &mut WorldEdit) -> Result<(), TinyMushError> {
+    apply(store, edit, false)?;
+    edit.undone_by = None;
+    store.put_world_edit(edit)
+}
+
+/// Newest edit by `builder` that is still in effect
+pub fn next_undo(store: &TinyMushStore, builder: &str) -> Result<Option<WorldEdit>, TinyMushError> {
+    let builder = builder.to_lowercase();
+    Ok(store
+        .list_world_edits()?
+        .into_iter()
+        .find(|e| e.builder == builder && !e.is_undone()))
+}
+
+/// Oldest edit in the run of edits `builder` undid themselves since their
+/// last edit still in effect
+pub fn next_redo(store: &TinyMushStore, builder: &str) -> Result<Option<WorldEdit>, TinyMushError> {
+    let builder = builder.to_lowercase();
+    Ok(store
+        .list_world_edits()?
+        .into_iter()
+        .filter(|e| e.builder == builder)
+        .take_while(|e| e.undone_by.as_deref() == Some(builder.as_str()))
+        .last())
+}
+
+/// Pack `lines` into pages of at most `budget` bytes each (a line longer
+/// than the budget gets a page of its own)
+pub fn paginate(lines: &[String], budget: usize) -> Vec<String> {
+    let mut pages: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + 1 + line.len() > budget {
+            pages.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        pages.push(current);
+    }
+    pages
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RollbackReport {
+    pub undone: Vec<u64>,
+    /// Edits left in place because a later change touched the same record
+    pub conflicts: Vec<(u64, String)>,
+}
+
+/// Undo every edit `builder` made since `since`, newest first
+pub fn rollback(
+    store: &TinyMushStore,
+    builder: &str,
+    since: DateTime<Utc>,
+    actor: &str,
+) -> Result<RollbackReport, TinyMushError> {
+    let builder = builder.to_lowercase();
+    let mut report = RollbackReport::default();
+    let edits = store
+        .list_world_edits()?
+        .into_iter()
+        .take_while(|e| e.created_at >= since)
+        .filter(|e| e.builder == builder && !e.is_undone());
+    for mut edit in edits {
+        match undo(store, &mut edit, actor) {
+            Ok(()) => report.undone.push(edit.id),
+            Err(TinyMushError::EditConflict(what)) => report.conflicts.push((edit.id, what)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(report)
+}
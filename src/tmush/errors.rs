@@ -51,6 +51,15 @@ pub enum TinyMushError {
     #[error("container not empty: {0}")]
     ContainerNotEmpty(String),
 
+    /// A builder edit cannot be undone or redone because a record it touched
+    /// has been changed since
+    #[error("{0} has changed since that edit")]
+    EditConflict(String),
+
+    /// A dungeon instance could not be opened or joined
+    #[error("instance limit reached: {0}")]
+    InstanceLimit(String),
+
     /// Internal error (task join errors, unexpected conditions)
     #[error("internal error: {0}")]
     Internal(String),
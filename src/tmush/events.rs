@@ -0,0 +1,327 @@
+//! Room event bus for TinyMUSH real-time fanout.
+//!
+//! Command handlers and trigger scripts publish [`RoomEvent`]s describing what
+//! other occupants of a room should see (speech, emotes, arrivals, trigger
+//! output). The bus lives on the shared [`TinyMushStore`](crate::tmush::TinyMushStore)
+//! so every short-lived `TinyMushProcessor` publishes into the same queue. The
+//! BBS server drains it after each command and on its periodic tick, resolves
+//! which logged-in sessions are standing in each room, and delivers the output
+//! as direct messages. Guild chat rides the same bus as one targeted event per
+//! member that is delivered wherever that member is standing.
+//!
+//! The world tick publishes ambient activity on the same bus; the server sends
+//! it at lower scheduler priorities than speech so it only uses spare airtime.
+//!
+//! Delivery is airtime-aware: [`plan_fanout`] coalesces all pending lines for a
+//! recipient into as few frames as fit the configured message budget, so a burst
+//! of chatter costs one frame per listener instead of one per event.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Upper bound on undelivered events; oldest are discarded first when exceeded.
+pub const MAX_PENDING_ROOM_EVENTS: usize = 256;
+
+/// What kind of activity a room event describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomEventKind {
+    Say,
+    Emote,
+    Pose,
+    Ooc,
+    /// Private line visible only to `RoomEvent::target`
+    Whisper,
+    /// Actor entered the room (optionally from a direction)
+    Arrive(Option<String>),
+    /// Actor left the room (optionally via a direction)
+    Depart(Option<String>),
+    /// Output of a `message_room(...)` trigger action
+    Trigger,
+    /// Guild chat line (guild name); reaches the target wherever they are
+    Guild(String),
+    /// World tick activity: NPC patrols, `OnIdle` trigger output, companion
+    /// healing and alerts. Delivered at Low priority.
+    Ambient,
+    /// Companion idle chatter, delivered at Background priority
+    Chatter,
+}
+
+/// A single piece of room activity awaiting delivery to other occupants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomEvent {
+    pub room_id: String,
+    /// Username of the player who caused the event (never receives it)
+    pub actor: String,
+    /// Restrict delivery to a single username (whispers)
+    pub target: Option<String>,
+    pub kind: RoomEventKind,
+    pub text: String,
+}
+
+impl RoomEvent {
+    pub fn new(room_id: &str, actor: &str, kind: RoomEventKind, text: &str) -> Self {
+        Self {
+            room_id: room_id.to_string(),
+            actor: actor.to_string(),
+            target: None,
+            kind,
+            text: text.to_string(),
+        }
+    }
+
+    /// Restrict delivery of this event to one player.
+    pub fn for_target(mut self, username: &str) -> Self {
+        self.target = Some(username.to_string());
+        self
+    }
+
+    /// Render the line shown to other players in the room.
+    pub fn render(&self) -> String {
+        match &self.kind {
+            RoomEventKind::Say => format!("{} says: \"{}\"", self.actor, self.text),
+            RoomEventKind::Emote | RoomEventKind::Pose => format!("{} {}", self.actor, self.text),
+            RoomEventKind::Ooc => format!("[OOC] {}: {}", self.actor, self.text),
+            RoomEventKind::Whisper => format!("{} whispers: \"{}\"", self.actor, self.text),
+            RoomEventKind::Arrive(Some(from)) => match from.as_str() {
+                "up" => format!("{} arrives from above.", self.actor),
+                "down" => format!("{} arrives from below.", self.actor),
+                _ => format!("{} arrives from the {}.", self.actor, from),
+            },
+            RoomEventKind::Arrive(None) => format!("{} arrives.", self.actor),
+            RoomEventKind::Depart(Some(dir)) => format!("{} leaves {}.", self.actor, dir),
+            RoomEventKind::Depart(None) => format!("{} leaves.", self.actor),
+            RoomEventKind::Trigger | RoomEventKind::Ambient | RoomEventKind::Chatter => {
+                self.text.clone()
+            }
+            RoomEventKind::Guild(guild) => format!("[{}] {}: {}", guild, self.actor, self.text),
+        }
+    }
+
+    /// Whether a listener standing in `room_id` can receive this event.
+    pub fn reaches_room(&self, room_id: &str) -> bool {
+        matches!(self.kind, RoomEventKind::Guild(_)) || self.room_id == room_id
+    }
+
+    /// Whether `username` should receive this event.
+    pub fn is_visible_to(&self, username: &str) -> bool {
+        if username.eq_ignore_ascii_case(&self.actor) {
+            return false;
+        }
+        match &self.target {
+            Some(t) => t.eq_ignore_ascii_case(username),
+            None => true,
+        }
+    }
+}
+
+/// Shared FIFO of pending room events.
+///
+/// Cheap to clone; all clones refer to the same queue.
+#[derive(Debug, Clone, Default)]
+pub struct RoomEventBus {
+    queue: Arc<Mutex<VecDeque<RoomEvent>>>,
+}
+
+impl RoomEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event for delivery, dropping the oldest if the bus is full.
+    pub fn publish(&self, event: RoomEvent) {
+        if let Ok(mut q) = self.queue.lock() {
+            if q.len() >= MAX_PENDING_ROOM_EVENTS {
+                q.pop_front();
+            }
+            q.push_back(event);
+        }
+    }
+
+    /// Take all pending events in publish order.
+    pub fn drain(&self) -> Vec<RoomEvent> {
+        match self.queue.lock() {
+            Ok(mut q) => q.drain(..).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().map(|q| q.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A session currently inside TinyMUSH that may receive room events.
+#[derive(Debug, Clone)]
+pub struct RoomListener {
+    pub node_key: String,
+    pub username: String,
+    pub room_id: String,
+}
+
+/// Group events by recipient and pack each recipient's lines into frames of at
+/// most `max_bytes`. Returns `(node_key, frames)` in first-seen recipient order.
+pub fn plan_fanout(
+    events: &[RoomEvent],
+    listeners: &[RoomListener],
+    max_bytes: usize,
+) -> Vec<(String, Vec<String>)> {
+    plan_fanout_by(
+        events,
+        listeners,
+        max_bytes,
+        |listener| &listener.node_key,
+        |event, listener| {
+            event.reaches_room(&listener.room_id) && event.is_visible_to(&listener.username)
+        },
+        RoomEvent::render,
+    )
+}
+
+/// [`plan_fanout`] for any event and recipient type: `delivers(event, recipient)`
+/// decides who hears each event, `node_key` names the recipient's session and
+/// `render` produces the line. BBS chat rooms reuse this for their fanout.
+pub fn plan_fanout_by<E, R>(
+    events: &[E],
+    recipients: &[R],
+    max_bytes: usize,
+    node_key: impl Fn(&R) -> &str,
+    delivers: impl Fn(&E, &R) -> bool,
+    render: impl Fn(&E) -> String,
+) -> Vec<(String, Vec<String>)> {
+    let mut per_recipient: Vec<(String, Vec<String>)> = Vec::new();
+    for event in events {
+        let line = render(event);
+        for recipient in recipients {
+            if !delivers(event, recipient) {
+                continue;
+            }
+            let key = node_key(recipient);
+            match per_recipient.iter_mut().find(|(k, _)| k == key) {
+                Some((_, lines)) => lines.push(line.clone()),
+                None => per_recipient.push((key.to_string(), vec![line.clone()])),
+            }
+        }
+    }
+    per_recipient
+        .into_iter()
+        .map(|(key, lines)| (key, coalesce_lines(&lines, max_bytes)))
+        .collect()
+}
+
+/// Join lines with newlines into as few frames as fit `max_bytes` each.
+/// Oversized single lines are truncated on a UTF-8 boundary.
+pub fn coalesce_lines(lines: &[String], max_bytes: usize) -> Vec<String> {
+    let mut frames = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        let line = clamp_utf8(line, max_bytes);
+        let needed = if current.is_empty() {
+            line.len()
+        } else {
+            current.len() + 1 + line.len()
+        };
+        if needed > max_bytes && !current.is_empty() {
+            frames.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() {
+        frames.push(current);
+    }
+    frames
+}
+
+fn clamp_utf8(s: &str, max_bytes: usize) -> String {
+    if s.len() <= max_bytes {
+        return s.to_string();
+    }
+    let mut end = max_bytes;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s[..end].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener(key: &str, user: &str, room: &str) -> RoomListener {
+        RoomListener {
+            node_key: key.to_string(),
+            username: user.to_string(),
+            room_id: room.to_string(),
+        }
+    }
+
+    #[test]
+    fn bus_drains_in_order_and_caps() {
+        let bus = RoomEventBus::new();
+        for i in 0..(MAX_PENDING_ROOM_EVENTS + 3) {
+            bus.publish(RoomEvent::new("r", "a", RoomEventKind::Say, &i.to_string()));
+        }
+        let drained = bus.drain();
+        assert_eq!(drained.len(), MAX_PENDING_ROOM_EVENTS);
+        assert_eq!(drained[0].text, "3");
+        assert!(bus.is_empty());
+    }
+
+    #[test]
+    fn fanout_skips_actor_other_rooms_and_non_targets() {
+        let events = vec![
+            RoomEvent::new("square", "alice", RoomEventKind::Say, "hi"),
+            RoomEvent::new("square", "alice", RoomEventKind::Whisper, "psst").for_target("bob"),
+            RoomEvent::new("market", "carol", RoomEventKind::Emote, "waves"),
+        ];
+        let listeners = vec![
+            listener("1", "alice", "square"),
+            listener("2", "bob", "square"),
+            listener("3", "dave", "square"),
+            listener("4", "erin", "market"),
+        ];
+        let plan = plan_fanout(&events, &listeners, 200);
+        assert_eq!(plan.len(), 3);
+        assert_eq!(plan[0].0, "2");
+        assert_eq!(
+            plan[0].1,
+            vec!["alice says: \"hi\"\nalice whispers: \"psst\"".to_string()]
+        );
+        assert_eq!(plan[1].0, "3");
+        assert_eq!(plan[1].1, vec!["alice says: \"hi\"".to_string()]);
+        assert_eq!(plan[2].1, vec!["carol waves".to_string()]);
+    }
+
+    #[test]
+    fn guild_lines_reach_members_in_any_room() {
+        let guild = RoomEventKind::Guild("Iron Wolves".to_string());
+        let events = vec![
+            RoomEvent::new("square", "alice", guild.clone(), "rally!").for_target("bob"),
+            RoomEvent::new("square", "alice", guild, "rally!").for_target("erin"),
+        ];
+        let listeners = vec![
+            listener("2", "bob", "market"),
+            listener("3", "dave", "square"),
+            listener("4", "erin", "square"),
+        ];
+        let plan = plan_fanout(&events, &listeners, 200);
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].0, "2");
+        assert_eq!(plan[0].1, vec!["[Iron Wolves] alice: rally!".to_string()]);
+        assert_eq!(plan[1].0, "4");
+    }
+
+    #[test]
+    fn coalesce_splits_at_budget() {
+        let lines = vec!["a".repeat(60), "b".repeat(60), "c".repeat(60)];
+        let frames = coalesce_lines(&lines, 130);
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|f| f.len() <= 130));
+    }
+}
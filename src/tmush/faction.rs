@@ -0,0 +1,242 @@
+/// Faction reputation logic for Phase 5
+///
+/// This module connects `PlayerRecord::faction_reputation` to the rest of the
+/// game: quest rewards and NPC dialogue adjust standing, dialogue branches can
+/// require it, and shops run by faction members price their goods by it (see
+/// [`FactionPricing`]).
+use crate::tmush::errors::TinyMushError;
+use crate::tmush::shop::{FactionPricing, ShopRecord};
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{FactionRecord, PlayerRecord, QuestRecord, ReputationLevel};
+
+/// Reputation granted by a faction's `reputation_quests` when the quest
+/// itself does not specify an amount for that faction
+pub const DEFAULT_QUEST_REPUTATION: i32 = 10;
+
+/// Find a faction by ID or (partial) display name, case-insensitive
+pub fn find_faction(
+    store: &TinyMushStore,
+    query: &str,
+) -> Result<Option<FactionRecord>, TinyMushError> {
+    let needle = query.trim().to_lowercase();
+    let factions = store.list_factions()?;
+    if let Some(exact) = factions
+        .iter()
+        .find(|f| f.id == needle || f.name.to_lowercase() == needle)
+    {
+        return Ok(Some(exact.clone()));
+    }
+    Ok(factions
+        .into_iter()
+        .find(|f| f.name.to_lowercase().contains(&needle)))
+}
+
+/// The faction an NPC belongs to, if any
+pub fn faction_for_npc(
+    store: &TinyMushStore,
+    npc_id: &str,
+) -> Result<Option<FactionRecord>, TinyMushError> {
+    Ok(store
+        .list_factions()?
+        .into_iter()
+        .find(|f| f.npc_members.iter().any(|m| m == npc_id)))
+}
+
+/// Pricing a shop offers a player, based on the faction of the shop's owner.
+///
+/// Shops whose owner is not a faction member always use neutral pricing.
+pub fn shop_pricing(
+    store: &TinyMushStore,
+    player: &PlayerRecord,
+    shop: &ShopRecord,
+) -> Result<(FactionPricing, Option<FactionRecord>), TinyMushError> {
+    match faction_for_npc(store, &shop.owner)? {
+        Some(faction) => {
+            let level = player.get_reputation_level(&faction.id);
+            Ok((FactionPricing::for_level(level), Some(faction)))
+        }
+        None => Ok((FactionPricing::NEUTRAL, None)),
+    }
+}
+
+/// Reputation changes granted for completing `quest`.
+///
+/// Explicit `QuestRewards::reputation` entries win; factions that list the
+/// quest in `reputation_quests` add [`DEFAULT_QUEST_REPUTATION`] otherwise.
+pub fn quest_reputation_rewards(
+    store: &TinyMushStore,
+    quest: &QuestRecord,
+) -> Result<Vec<(String, i32)>, TinyMushError> {
+    let mut changes: Vec<(String, i32)> = quest
+        .rewards
+        .reputation
+        .iter()
+        .map(|(faction_id, points)| (faction_id.clone(), *points))
+        .collect();
+
+    for faction in store.list_factions()? {
+        if faction.reputation_quests.contains(&quest.id)
+            && !changes.iter().any(|(id, _)| *id == faction.id)
+        {
+            changes.push((faction.id, DEFAULT_QUEST_REPUTATION));
+        }
+    }
+
+    changes.sort();
+    Ok(changes)
+}
+
+/// Apply reputation changes to a player, returning one line per change
+pub fn apply_reputation_changes(
+    store: &TinyMushStore,
+    username: &str,
+    changes: &[(String, i32)],
+) -> Result<Vec<String>, TinyMushError> {
+    if changes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut player = store.get_player(username)?;
+    let mut lines = Vec::new();
+    for (faction_id, amount) in changes {
+        if *amount == 0 {
+            continue;
+        }
+        let name = store
+            .get_faction(faction_id)
+            .map(|f| f.name)
+            .unwrap_or_else(|_| faction_id.clone());
+        let before = player.get_reputation_level(faction_id);
+        player.add_reputation(faction_id, *amount);
+        let after = player.get_reputation_level(faction_id);
+        lines.push(format_reputation_change(&name, *amount, before, after));
+    }
+    store.put_player(player)?;
+    Ok(lines)
+}
+
+/// Format a single reputation change, calling out level transitions
+pub fn format_reputation_change(
+    faction_name: &str,
+    amount: i32,
+    before: ReputationLevel,
+    after: ReputationLevel,
+) -> String {
+    let icon = if amount >= 0 { "⭐" } else { "🔻" };
+    if before != after {
+        format!(
+            "{} {} {:+}: now {} {}",
+            icon,
+            faction_name,
+            amount,
+            after.color_code(),
+            after.display_name()
+        )
+    } else {
+        format!(
+            "{} {} {:+} ({})",
+            icon,
+            faction_name,
+            amount,
+            after.display_name()
+        )
+    }
+}
+
+/// Compact standing summary across all factions (fits a single message)
+pub fn format_standing(
+    store: &TinyMushStore,
+    player: &PlayerRecord,
+) -> Result<String, TinyMushError> {
+    let mut rows: Vec<(i32, String)> = store
+        .list_factions()?
+        .into_iter()
+        .map(|f| (player.get_reputation(&f.id), f.name))
+        .collect();
+    rows.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let mut out = "=STANDING=\n".to_string();
+    for (points, name) in rows {
+        let level = ReputationLevel::from_points(points);
+        out.push_str(&format!("{} {} {}\n", level.color_code(), name, points));
+    }
+    out.push_str("REP <faction> for perks");
+    Ok(out)
+}
+
+/// Standing with one faction, current perk and the next level to reach
+pub fn format_faction_detail(faction: &FactionRecord, player: &PlayerRecord) -> String {
+    let points = player.get_reputation(&faction.id);
+    let level = ReputationLevel::from_points(points);
+
+    let mut out = format!("={}=\n", faction.name);
+    out.push_str(&format!(
+        "{} {} ({})\n",
+        level.color_code(),
+        level.display_name(),
+        points
+    ));
+    match FactionPricing::for_level(level) {
+        FactionPricing::Refused => out.push_str("Their shops refuse you\n"),
+        pricing => {
+            if let Some(note) = pricing.describe() {
+                out.push_str(&format!("Shops: {}\n", note));
+            }
+        }
+    }
+    if let Some(benefit) = faction.benefits.get(level.display_name()) {
+        out.push_str(&format!("Now: {}\n", benefit));
+    }
+    if let Some((next, threshold)) = next_level(level) {
+        out.push_str(&format!("Next: {} at {}", next.display_name(), threshold));
+    }
+    out.trim_end().to_string()
+}
+
+/// The next reputation level above `level` and the points needed for it
+pub fn next_level(level: ReputationLevel) -> Option<(ReputationLevel, i32)> {
+    match level {
+        ReputationLevel::Hated => Some((ReputationLevel::Hostile, -74)),
+        ReputationLevel::Hostile => Some((ReputationLevel::Unfriendly, -49)),
+        ReputationLevel::Unfriendly => Some((ReputationLevel::Neutral, -24)),
+        ReputationLevel::Neutral => Some((ReputationLevel::Friendly, 25)),
+        ReputationLevel::Friendly => Some((ReputationLevel::Honored, 50)),
+        ReputationLevel::Honored => Some((ReputationLevel::Revered, 75)),
+        ReputationLevel::Revered => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_level_thresholds_match_from_points() {
+        let mut level = ReputationLevel::Hated;
+        while let Some((next, threshold)) = next_level(level) {
+            assert_eq!(ReputationLevel::from_points(threshold), next);
+            assert_eq!(ReputationLevel::from_points(threshold - 1), level);
+            level = next;
+        }
+        assert_eq!(level, ReputationLevel::Revered);
+    }
+
+    #[test]
+    fn change_line_calls_out_new_level() {
+        let line = format_reputation_change(
+            "Tinkers Guild",
+            15,
+            ReputationLevel::Neutral,
+            ReputationLevel::Friendly,
+        );
+        assert_eq!(line, "⭐ Tinkers Guild +15: now 🟢 Friendly");
+
+        let line = format_reputation_change(
+            "Tinkers Guild",
+            -5,
+            ReputationLevel::Neutral,
+            ReputationLevel::Neutral,
+        );
+        assert_eq!(line, "🔻 Tinkers Guild -5 (Neutral)");
+    }
+}
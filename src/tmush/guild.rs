@@ -0,0 +1,108 @@
+//! Player guilds: ranks, guild chat, a shared treasury and a guild hall
+//!
+//! A [`GuildRecord`] lives in the `tinymush_guilds` sled tree and lists its
+//! members with a rank each. Ranks run from `Recruit` up to `Leader`; the rank
+//! just below the leader (`Officer`) may invite, kick and promote, and the
+//! leader decides which rank may withdraw from the treasury.
+//!
+//! - `GUILD SAY` (or `GC`) publishes one targeted [`RoomEvent`] per member on
+//!   the room event bus; the server delivers it to members who are online,
+//!   wherever they are standing
+//! - The treasury moves money in and out of members' pockets through
+//!   [`TinyMushStore::guild_deposit`] and [`TinyMushStore::guild_withdraw`],
+//!   logged as `guild:<id>` transactions
+//! - A member can register one of their housing instances as the guild hall;
+//!   its guest list is rewritten from the member list whenever membership
+//!   changes
+
+use crate::tmush::events::{RoomEvent, RoomEventKind};
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::GuildRecord;
+use crate::tmush::TinyMushError;
+
+/// Longest guild name accepted by `GUILD CREATE`
+pub const MAX_GUILD_NAME_LEN: usize = 24;
+
+/// Storage key for a guild name ("Iron Wolves" -> "iron_wolves")
+pub fn guild_id_for(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_lowercase()
+}
+
+/// Guild names are 3-24 letters, digits, spaces, `'` or `-`
+pub fn is_valid_guild_name(name: &str) -> bool {
+    let len = name.chars().count();
+    (3..=MAX_GUILD_NAME_LEN).contains(&len)
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '\'' | '-'))
+        && name.chars().any(|c| c.is_ascii_alphanumeric())
+}
+
+/// Officers and the leader may invite, kick and promote
+pub fn can_manage(guild: &GuildRecord, rank: u8) -> bool {
+    rank + 1 >= guild.leader_rank()
+}
+
+/// Rewrite the hall's guest list from the member list. A hall whose housing
+/// instance is gone is unregistered.
+pub fn sync_hall_guests(
+    store: &TinyMushStore,
+    guild: &mut GuildRecord,
+) -> Result<(), TinyMushError> {
+    let Some(hall_id) = guild.hall_id.clone() else {
+        return Ok(());
+    };
+    let mut hall = match store.get_housing_instance(&hall_id) {
+        Ok(hall) => hall,
+        Err(TinyMushError::NotFound(_)) => {
+            guild.hall_id = None;
+            return store.put_guild(guild);
+        }
+        Err(e) => return Err(e),
+    };
+    if guild.member(&hall.owner).is_none() {
+        // The owner left the guild and took the hall with them
+        return release_hall(store, guild);
+    }
+    hall.guests = guild
+        .members
+        .iter()
+        .map(|m| m.username.clone())
+        .filter(|m| !m.eq_ignore_ascii_case(&hall.owner))
+        .collect();
+    store.put_housing_instance(&hall)
+}
+
+/// Unregister the hall and clear the guest list the guild gave it
+pub fn release_hall(store: &TinyMushStore, guild: &mut GuildRecord) -> Result<(), TinyMushError> {
+    let Some(hall_id) = guild.hall_id.take() else {
+        return Ok(());
+    };
+    if let Ok(mut hall) = store.get_housing_instance(&hall_id) {
+        hall.guests.clear();
+        store.put_housing_instance(&hall)?;
+    }
+    store.put_guild(guild)
+}
+
+/// Queue a guild chat line for every other member
+pub fn publish_chat(
+    store: &TinyMushStore,
+    guild: &GuildRecord,
+    speaker: &str,
+    room_id: &str,
+    text: &str,
+) {
+    let kind = RoomEventKind::Guild(guild.name.clone());
+    for member in &guild.members {
+        if member.username.eq_ignore_ascii_case(speaker) {
+            continue;
+        }
+        store.room_events().publish(
+            RoomEvent::new(room_id, speaker, kind.clone(), text).for_target(&member.username),
+        );
+    }
+}
@@ -0,0 +1,611 @@
+//! Spells, mana and casting
+//!
+//! Spells are [`SpellRecord`]s seeded from `data/seeds/spells.json` into the
+//! `tinymush_spells` sled tree. Players LEARN them from a scroll in their
+//! inventory (the scroll is used up) or from a trainer NPC in the room (for a
+//! fee), and CAST them for MP:
+//!
+//! - The spell's `effect` is a trigger DSL script run as if the target player
+//!   had set it off, so `heal(12)` heals the target and carried `OnHeal`
+//!   triggers then fire for them
+//! - Mob spells roll `damage` and take the caster's combat turn, starting a
+//!   fight if there isn't one already. Any other spell cast mid-fight also
+//!   costs the turn (the command layer lets the mob answer)
+//! - Targets are looked up by name: players standing in the room, mobs in the
+//!   room, or objects through the [`resolver`](crate::tmush::resolver)
+//! - Each cast practises the `magic` skill, whose rank gates `min_rank` spells
+//!
+//! MP comes back at one point every [`MP_REGEN_SECONDS`]. Nothing runs in the
+//! background for it: [`regen_mp`] catches the player up whenever MP is read.
+
+use chrono::{DateTime, Duration, Utc};
+use log::warn;
+use rand::Rng;
+
+use crate::tmush::auction::amount_like;
+use crate::tmush::combat::{self, CombatAction, CombatReport};
+use crate::tmush::events::{RoomEvent, RoomEventKind};
+use crate::tmush::resolver::{resolve_object_name, ResolutionContext, ResolveResult};
+use crate::tmush::skills;
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::trigger::{execute_on_heal, execute_trigger, TriggerContext, TriggerResult};
+use crate::tmush::types::{
+    InventoryResult, MobRecord, ObjectRecord, ObjectTrigger, PlayerRecord, PlayerState, RoomFlag,
+    SpellRecord, SpellTarget, TransactionReason,
+};
+use crate::tmush::TinyMushError;
+
+/// Seconds to regain one MP
+pub const MP_REGEN_SECONDS: i64 = 30;
+
+/// Longest SPELLS page, to fit one mesh frame
+pub const SPELLBOOK_PAGE_BYTES: usize = 200;
+
+/// Why a LEARN or CAST was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum MagicRefusal {
+    UnknownSpell,
+    AlreadyKnown(String),
+    NoTrainer(String),
+    CantAfford(i64),
+    NeedRank(u32),
+    NotKnown,
+    OnCooldown(i64),
+    NotEnoughMp { need: u32, have: u32 },
+    NeedTarget,
+    NoTarget(String),
+    Ambiguous(Vec<String>),
+    SafeRoom,
+}
+
+impl std::fmt::Display for MagicRefusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSpell => write!(f, "There's no spell or scroll by that name."),
+            Self::AlreadyKnown(name) => write!(f, "You already know {}.", name),
+            Self::NoTrainer(name) => write!(f, "Nobody here teaches {}.", name),
+            Self::CantAfford(price) => write!(f, "Training costs {}.", price),
+            Self::NeedRank(rank) => write!(f, "You need magic rank {}.", rank),
+            Self::NotKnown => write!(f, "You don't know that spell. SPELLS lists yours."),
+            Self::OnCooldown(secs) => write!(f, "Not ready yet ({}s).", secs),
+            Self::NotEnoughMp { need, have } => {
+                write!(f, "Not enough MP ({} needed, you have {}).", need, have)
+            }
+            Self::NeedTarget => write!(f, "Cast it on what?"),
+            Self::NoTarget(name) => write!(f, "You don't see '{}' here.", name),
+            Self::Ambiguous(names) => write!(f, "Which one? {}", names.join(", ")),
+            Self::SafeRoom => write!(f, "This is a safe area. No fighting here."),
+        }
+    }
+}
+
+/// Result of a successful CAST
+#[derive(Debug, Clone)]
+pub struct CastOutcome {
+    pub lines: Vec<String>,
+    /// The round played out when the spell struck a mob
+    pub combat: Option<CombatReport>,
+}
+
+/// Catch up MP regeneration. Returns the MP gained.
+pub fn regen_mp(player: &mut PlayerRecord, now: DateTime<Utc>) -> u32 {
+    let stats = &mut player.stats;
+    if stats.mp >= stats.max_mp {
+        player.mp_regen_at = None;
+        return 0;
+    }
+    let Some(since) = player.mp_regen_at else {
+        player.mp_regen_at = Some(now);
+        return 0;
+    };
+
+    let ticks = (now - since).num_seconds().max(0) / MP_REGEN_SECONDS;
+    let gained = (ticks.min(u32::MAX as i64) as u32).min(stats.max_mp - stats.mp);
+    stats.mp += gained;
+    player.mp_regen_at = if stats.mp >= stats.max_mp {
+        None
+    } else {
+        Some(since + Duration::seconds(ticks * MP_REGEN_SECONDS))
+    };
+    gained
+}
+
+/// Load, catch up MP regeneration and save if anything changed
+pub fn refresh_mp(
+    store: &TinyMushStore,
+    username: &str,
+    now: DateTime<Utc>,
+) -> Result<PlayerRecord, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    let before = (player.stats.mp, player.mp_regen_at);
+    regen_mp(&mut player, now);
+    if (player.stats.mp, player.mp_regen_at) != before {
+        store.put_player(player.clone())?;
+    }
+    Ok(player)
+}
+
+fn matches_spell(spell: &SpellRecord, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    spell.id.eq_ignore_ascii_case(&query) || spell.name.to_lowercase().starts_with(&query)
+}
+
+/// Find a spell by ID or (start of its) name
+pub fn find_spell(
+    store: &TinyMushStore,
+    query: &str,
+) -> Result<Option<SpellRecord>, TinyMushError> {
+    Ok(store
+        .list_spells()?
+        .into_iter()
+        .find(|spell| matches_spell(spell, query)))
+}
+
+/// Spells taught by NPCs standing in `room_id`, with the teacher's name
+pub fn spells_taught_here(
+    store: &TinyMushStore,
+    room_id: &str,
+) -> Result<Vec<(SpellRecord, String)>, TinyMushError> {
+    let npcs = store.get_npcs_in_room(room_id)?;
+    Ok(store
+        .list_spells()?
+        .into_iter()
+        .filter_map(|spell| {
+            let teacher = npcs.iter().find(|npc| spell.trainers.contains(&npc.id))?;
+            let name = teacher.name.clone();
+            Some((spell, name))
+        })
+        .collect())
+}
+
+/// LEARN: study a carried scroll matching `query`, or pay a trainer in the
+/// room for the spell named `query`. Returns the spell and a line describing
+/// how it was learned.
+pub fn learn(
+    store: &TinyMushStore,
+    username: &str,
+    query: &str,
+) -> Result<Result<(SpellRecord, String), MagicRefusal>, TinyMushError> {
+    let player = store.get_player(username)?;
+    let needle = query.trim().to_lowercase();
+    let spells = store.list_spells()?;
+
+    for stack in &player.inventory_stacks {
+        let Ok(object) = store.get_object(&stack.object_id) else {
+            continue;
+        };
+        if object.id != needle && !object.name.to_lowercase().contains(&needle) {
+            continue;
+        }
+        let Some(spell) = spells
+            .iter()
+            .find(|s| s.scroll.as_deref() == Some(object.id.as_str()))
+        else {
+            continue;
+        };
+        if let Some(refusal) = can_learn(store, &player, spell) {
+            return Ok(Err(refusal));
+        }
+        if let InventoryResult::Failed { reason } =
+            store.player_remove_item(username, &object.id, 1)?
+        {
+            return Err(TinyMushError::NotFound(reason));
+        }
+        add_spell(store, username, spell)?;
+        let line = format!(
+            "You study the {}. It crumbles to dust as you learn {}.",
+            object.name, spell.name
+        );
+        return Ok(Ok((spell.clone(), line)));
+    }
+
+    let Some(spell) = spells.into_iter().find(|s| matches_spell(s, query)) else {
+        return Ok(Err(MagicRefusal::UnknownSpell));
+    };
+    let taught = spells_taught_here(store, &player.current_room)?;
+    let Some((_, teacher)) = taught.iter().find(|(s, _)| s.id == spell.id) else {
+        return Ok(Err(MagicRefusal::NoTrainer(spell.name)));
+    };
+    if let Some(refusal) = can_learn(store, &player, &spell) {
+        return Ok(Err(refusal));
+    }
+    if spell.price > 0 {
+        match store.deduct_currency(
+            username,
+            &amount_like(&player.currency, spell.price),
+            TransactionReason::Purchase,
+        ) {
+            Ok(_) => {}
+            Err(TinyMushError::InsufficientFunds) => {
+                return Ok(Err(MagicRefusal::CantAfford(spell.price)))
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    add_spell(store, username, &spell)?;
+    let line = if spell.price > 0 {
+        format!(
+            "{} teaches you {} for {}.",
+            teacher, spell.name, spell.price
+        )
+    } else {
+        format!("{} teaches you {}.", teacher, spell.name)
+    };
+    Ok(Ok((spell, line)))
+}
+
+fn can_learn(
+    store: &TinyMushStore,
+    player: &PlayerRecord,
+    spell: &SpellRecord,
+) -> Option<MagicRefusal> {
+    if player.spells.contains(&spell.id) {
+        return Some(MagicRefusal::AlreadyKnown(spell.name.clone()));
+    }
+    if skills::effective_skill(store, player, "magic") < spell.min_rank {
+        return Some(MagicRefusal::NeedRank(spell.min_rank));
+    }
+    None
+}
+
+fn add_spell(
+    store: &TinyMushStore,
+    username: &str,
+    spell: &SpellRecord,
+) -> Result<(), TinyMushError> {
+    let mut player = store.get_player(username)?;
+    player.spells.push(spell.id.clone());
+    player.touch();
+    store.put_player(player)
+}
+
+/// What a spell ended up aimed at
+enum Target {
+    Player(Box<PlayerRecord>),
+    Mob(Box<MobRecord>),
+    Object(Box<ObjectRecord>),
+}
+
+/// CAST one of the player's spells, optionally at a named target
+pub fn cast<R: Rng>(
+    store: &TinyMushStore,
+    username: &str,
+    query: &str,
+    target: Option<&str>,
+    now: DateTime<Utc>,
+    rng: &mut R,
+) -> Result<Result<CastOutcome, MagicRefusal>, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    regen_mp(&mut player, now);
+
+    let mut known = Vec::new();
+    for id in &player.spells {
+        if let Ok(spell) = store.get_spell(id) {
+            known.push(spell);
+        }
+    }
+    let Some(spell) = known.into_iter().find(|s| matches_spell(s, query)) else {
+        return Ok(Err(MagicRefusal::NotKnown));
+    };
+    if skills::effective_skill(store, &player, "magic") < spell.min_rank {
+        return Ok(Err(MagicRefusal::NeedRank(spell.min_rank)));
+    }
+    player.spell_cooldowns.retain(|_, ready| *ready > now);
+    if let Some(ready) = player.spell_cooldowns.get(&spell.id) {
+        return Ok(Err(MagicRefusal::OnCooldown(
+            (*ready - now).num_seconds().max(1),
+        )));
+    }
+    if player.stats.mp < spell.mp_cost {
+        return Ok(Err(MagicRefusal::NotEnoughMp {
+            need: spell.mp_cost,
+            have: player.stats.mp,
+        }));
+    }
+    let target = match find_target(store, &player, &spell, target)? {
+        Ok(target) => target,
+        Err(refusal) => return Ok(Err(refusal)),
+    };
+
+    player.stats.mp -= spell.mp_cost;
+    if player.mp_regen_at.is_none() {
+        player.mp_regen_at = Some(now);
+    }
+    if spell.cooldown_seconds > 0 {
+        player.spell_cooldowns.insert(
+            spell.id.clone(),
+            now + Duration::seconds(spell.cooldown_seconds as i64),
+        );
+    }
+    let mut practice = skills::practice(&mut player, "magic", 1);
+    player.touch();
+    let room_id = player.current_room.clone();
+    store.put_player(player.clone())?;
+
+    let mut outcome = CastOutcome {
+        lines: Vec::new(),
+        combat: None,
+    };
+    match target {
+        Target::Mob(mob) => {
+            outcome
+                .lines
+                .push(format!("You cast {} at the {}.", spell.name, mob.name));
+            if !matches!(player.state, PlayerState::InCombat(_)) {
+                outcome.lines.extend(combat::engage(store, username, &mob)?);
+            }
+            outcome
+                .lines
+                .extend(run_effect(store, &spell, &player, None, &room_id));
+            let damage = spell
+                .damage
+                .map_or(0, |(min, max)| rng.gen_range(min.min(max)..=max.max(min)));
+            outcome.combat = Some(combat::resolve_round(
+                store,
+                username,
+                CombatAction::Spell(damage),
+                rng,
+            )?);
+        }
+        Target::Object(object) => {
+            outcome
+                .lines
+                .push(format!("You cast {} on the {}.", spell.name, object.name));
+            outcome.lines.extend(run_effect(
+                store,
+                &spell,
+                &player,
+                Some(&object.name),
+                &room_id,
+            ));
+        }
+        Target::Player(target) if target.username == player.username => {
+            outcome.lines.push(format!("You cast {}.", spell.name));
+            outcome
+                .lines
+                .extend(run_effect(store, &spell, &target, None, &room_id));
+        }
+        Target::Player(target) => {
+            outcome.lines.push(format!(
+                "You cast {} on {}.",
+                spell.name, target.display_name
+            ));
+            let effect = run_effect(store, &spell, &target, None, &room_id);
+            let mut theirs = vec![format!(
+                "{} casts {} on you.",
+                player.display_name, spell.name
+            )];
+            theirs.extend(effect.iter().cloned());
+            store.room_events().publish(
+                RoomEvent::new(
+                    &room_id,
+                    username,
+                    RoomEventKind::Trigger,
+                    &theirs.join("\n"),
+                )
+                .for_target(&target.username),
+            );
+            // Room lines already reach the caster through the event bus
+            outcome
+                .lines
+                .extend(effect.into_iter().filter(|line| !line.starts_with("🔊")));
+        }
+    }
+    outcome.lines.append(&mut practice);
+    Ok(Ok(outcome))
+}
+
+fn find_target(
+    store: &TinyMushStore,
+    player: &PlayerRecord,
+    spell: &SpellRecord,
+    target: Option<&str>,
+) -> Result<Result<Target, MagicRefusal>, TinyMushError> {
+    let target = target.map(str::trim).filter(|t| !t.is_empty());
+    match spell.target {
+        SpellTarget::Caster => Ok(Ok(Target::Player(Box::new(player.clone())))),
+        SpellTarget::Player => {
+            let Some(name) = target else {
+                return Ok(Ok(Target::Player(Box::new(player.clone()))));
+            };
+            let name = name.to_lowercase();
+            for username in store.list_players_in_room(&player.current_room)? {
+                let other = store.get_player(&username)?;
+                if other.username.to_lowercase().starts_with(&name)
+                    || other.display_name.to_lowercase().starts_with(&name)
+                {
+                    return Ok(Ok(Target::Player(Box::new(other))));
+                }
+            }
+            Ok(Err(MagicRefusal::NoTarget(name)))
+        }
+        SpellTarget::Mob => {
+            // Mid-fight, like ATTACK, the spell goes at the current opponent
+            if let PlayerState::InCombat(state) = &player.state {
+                if let Ok(mob) = store.get_mob(&state.enemy_id) {
+                    return Ok(Ok(Target::Mob(Box::new(mob))));
+                }
+            }
+            let Some(name) = target else {
+                return Ok(Err(MagicRefusal::NeedTarget));
+            };
+            if store
+                .get_room(&player.current_room)
+                .is_ok_and(|room| room.flags.contains(&RoomFlag::Safe))
+            {
+                return Ok(Err(MagicRefusal::SafeRoom));
+            }
+            match combat::find_mob_in_room(store, &player.current_room, name)? {
+                Some(mob) => Ok(Ok(Target::Mob(Box::new(mob)))),
+                None => Ok(Err(MagicRefusal::NoTarget(name.to_lowercase()))),
+            }
+        }
+        SpellTarget::Object => {
+            let Some(name) = target else {
+                return Ok(Err(MagicRefusal::NeedTarget));
+            };
+            let context =
+                ResolutionContext::new(player.username.clone(), player.current_room.clone(), None);
+            match resolve_object_name(&context, name, store)? {
+                ResolveResult::Found(id) => match store.get_object(&id) {
+                    Ok(object) => Ok(Ok(Target::Object(Box::new(object)))),
+                    Err(_) => Ok(Err(MagicRefusal::NoTarget(name.to_lowercase()))),
+                },
+                ResolveResult::Ambiguous(matches) => Ok(Err(MagicRefusal::Ambiguous(
+                    matches.into_iter().map(|m| m.name).collect(),
+                ))),
+                ResolveResult::NotFound => Ok(Err(MagicRefusal::NoTarget(name.to_lowercase()))),
+            }
+        }
+    }
+}
+
+/// Run the spell's effect script with `target` as the triggering player. When
+/// it heals them, their carried `OnHeal` triggers fire too.
+fn run_effect(
+    store: &TinyMushStore,
+    spell: &SpellRecord,
+    target: &PlayerRecord,
+    object_name: Option<&str>,
+    room_id: &str,
+) -> Vec<String> {
+    if spell.effect.is_empty() {
+        return Vec::new();
+    }
+    let room_name = store
+        .get_room(room_id)
+        .map(|room| room.name)
+        .unwrap_or_else(|_| room_id.to_string());
+    let mut context = TriggerContext {
+        player_username: target.username.clone(),
+        player_name: target.display_name.clone(),
+        object_id: format!("spell:{}", spell.id),
+        object_name: object_name.unwrap_or(&spell.name).to_string(),
+        room_id: room_id.to_string(),
+        room_name,
+        started_at: std::time::Instant::now(),
+        action_count: 0,
+        message_count: 0,
+        depth: 0,
+    };
+    // The effect runs on the target's behalf, so a heal shows up in their HP
+    let hp_before = target.stats.hp;
+    let mut lines = match execute_trigger(ObjectTrigger::OnUse, &spell.effect, &mut context, store)
+    {
+        Ok(TriggerResult::Success(messages)) => messages,
+        Ok(TriggerResult::Failed(reason)) => {
+            warn!("Spell {} failed: {}", spell.id, reason);
+            vec!["The spell fizzles.".to_string()]
+        }
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            warn!("Spell {} failed: {}", spell.id, e);
+            vec!["The spell fizzles.".to_string()]
+        }
+    };
+
+    if let Ok(healed) = store.get_player(&target.username) {
+        if healed.stats.hp > hp_before {
+            for stack in &healed.inventory_stacks {
+                if let Ok(object) = store.get_object(&stack.object_id) {
+                    lines.extend(execute_on_heal(
+                        &object,
+                        &healed.username,
+                        &healed.current_room,
+                        store,
+                    ));
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// One SPELLS line: name, cost, what it targets and any cooldown left
+fn spell_line(spell: &SpellRecord, player: &PlayerRecord, now: DateTime<Utc>) -> String {
+    let aim = match spell.target {
+        SpellTarget::Caster => "",
+        SpellTarget::Player => " ally",
+        SpellTarget::Mob => " foe",
+        SpellTarget::Object => " item",
+    };
+    let wait = match player.spell_cooldowns.get(&spell.id) {
+        Some(ready) if *ready > now => format!(" ({}s)", (*ready - now).num_seconds().max(1)),
+        _ => String::new(),
+    };
+    format!("{} {}mp{}{}", spell.name, spell.mp_cost, aim, wait)
+}
+
+/// SPELLS: MP and known spells, one frame per page (pages count from 1)
+pub fn format_spellbook(
+    store: &TinyMushStore,
+    player: &PlayerRecord,
+    now: DateTime<Utc>,
+    page: usize,
+) -> String {
+    let mp = format!("MP {}/{}", player.stats.mp, player.stats.max_mp);
+    let mut spells: Vec<SpellRecord> = player
+        .spells
+        .iter()
+        .filter_map(|id| store.get_spell(id).ok())
+        .collect();
+    if spells.is_empty() {
+        return format!(
+            "{}\nYou know no spells.\nLEARN from scrolls or trainers.",
+            mp
+        );
+    }
+    spells.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Leave room for the header and the "next page" footer
+    let budget = SPELLBOOK_PAGE_BYTES - mp.len() - 40;
+    let mut pages: Vec<Vec<String>> = vec![Vec::new()];
+    let mut used = 0;
+    for spell in &spells {
+        let line = spell_line(spell, player, now);
+        if used + line.len() + 1 > budget && !pages.last().unwrap().is_empty() {
+            pages.push(Vec::new());
+            used = 0;
+        }
+        used += line.len() + 1;
+        pages.last_mut().unwrap().push(line);
+    }
+
+    let page = page.clamp(1, pages.len());
+    let mut out = format!("{} | Spells {}/{}\n", mp, page, pages.len());
+    out.push_str(&pages[page - 1].join("\n"));
+    if page < pages.len() {
+        out.push_str(&format!("\nSPELLS {} for more", page + 1));
+    } else {
+        out.push_str("\nCAST <spell> [target]");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mana_returns_one_point_per_interval() {
+        let now = Utc::now();
+        let mut player = PlayerRecord::new("alice", "Alice", "town_square");
+        player.stats.mp = 4;
+        assert_eq!(regen_mp(&mut player, now), 0);
+        assert_eq!(player.mp_regen_at, Some(now));
+
+        let later = now + Duration::seconds(MP_REGEN_SECONDS * 3 + 10);
+        assert_eq!(regen_mp(&mut player, later), 3);
+        assert_eq!(player.stats.mp, 7);
+        // The partial interval carries over
+        assert_eq!(
+            player.mp_regen_at,
+            Some(now + Duration::seconds(MP_REGEN_SECONDS * 3))
+        );
+
+        let much_later = now + Duration::hours(1);
+        assert_eq!(regen_mp(&mut player, much_later), 3);
+        assert_eq!(player.stats.mp, player.stats.max_mp);
+        assert_eq!(player.mp_regen_at, None);
+    }
+}
@@ -17,21 +17,29 @@
 //! 1. Increment `CURRENT_*_SCHEMA_VERSION` constant
 //! 2. Add migration logic to `migrate_*_from_v*_to_v*()` function
 //! 3. Update `migrate_*()` function to call new migration
-//! 4. Add tests for the migration
-//! 5. Document the schema change in CHANGELOG.md
+//! 4. If fields were added anywhere but at the end of the record, bincode can
+//!    no longer read the old bytes: keep a `Legacy*` copy of the old layout and
+//!    decode it in [`Migratable::decode_legacy`]
+//! 5. Add tests for the migration
+//! 6. Document the schema change in CHANGELOG.md
 
 use crate::tmush::types::*;
 use anyhow::{anyhow, Result};
+use bincode::Options;
+use chrono::{DateTime, Utc};
 use log::info;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// Current schema versions for each data structure
-pub const CURRENT_PLAYER_SCHEMA_VERSION: u8 = 2;
-pub const CURRENT_NPC_SCHEMA_VERSION: u8 = 1;
+pub const CURRENT_PLAYER_SCHEMA_VERSION: u8 = 3;
+pub const CURRENT_NPC_SCHEMA_VERSION: u8 = 2;
 pub const CURRENT_ROOM_SCHEMA_VERSION: u8 = 2;
 pub const CURRENT_OBJECT_SCHEMA_VERSION: u8 = 2;
 pub const CURRENT_QUEST_SCHEMA_VERSION: u8 = 1;
 pub const CURRENT_ACHIEVEMENT_SCHEMA_VERSION: u8 = 1;
+pub const CURRENT_COMPANION_SCHEMA_VERSION: u8 = 2;
+pub const CURRENT_WORLD_CONFIG_VERSION: u8 = 2;
 
 /// Trait for types that support schema migration
 pub trait Migratable: Sized {
@@ -48,6 +56,20 @@ pub trait Migratable: Sized {
     fn needs_migration(&self) -> bool {
         self.schema_version() < Self::current_schema_version()
     }
+
+    /// Decode bytes written with an older field layout. `None` when `data`
+    /// is not in a legacy layout and decodes as `Self`.
+    fn decode_legacy(_data: &[u8]) -> Option<Self> {
+        None
+    }
+}
+
+/// Decode `data` as exactly one `T` with the store's bincode settings,
+/// rejecting leftover bytes so a shorter legacy layout can't pass for `T`
+fn decode_exact<T: for<'de> Deserialize<'de>>(data: &[u8]) -> bincode::Result<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .deserialize(data)
 }
 
 /// Migration result tracking for logging and debugging
@@ -116,11 +138,9 @@ impl Migratable for PlayerRecord {
         if self.schema_version < 2 {
             self = migrate_player_from_v1_to_v2(self)?;
         }
-
-        // Future migrations go here:
-        // if self.schema_version < 3 {
-        //     self = migrate_player_from_v2_to_v3(self)?;
-        // }
+        if self.schema_version < 3 {
+            self = migrate_player_from_v2_to_v3(self)?;
+        }
 
         self.schema_version = Self::current_schema_version();
 
@@ -131,6 +151,16 @@ impl Migratable for PlayerRecord {
 
         Ok(self)
     }
+
+    fn decode_legacy(data: &[u8]) -> Option<Self> {
+        // schema_version is the last field
+        if data.last().is_none_or(|v| *v >= 3) || decode_exact::<Self>(data).is_ok() {
+            return None;
+        }
+        decode_exact::<LegacyPlayerRecordV2>(data)
+            .ok()
+            .map(Self::from)
+    }
 }
 
 /// Migrate PlayerRecord from v1 to v2
@@ -147,6 +177,154 @@ fn migrate_player_from_v1_to_v2(mut player: PlayerRecord) -> Result<PlayerRecord
     Ok(player)
 }
 
+/// Migrate PlayerRecord from v2 to v3
+///
+/// Changes in v3 (the new fields are filled in by [`LegacyPlayerRecordV2`]):
+/// - Added PlayerStats level and experience
+/// - Added CombatState defending
+/// - Added skills and explored_rooms
+/// - Added spells, spell_cooldowns and mp_regen_at
+fn migrate_player_from_v2_to_v3(mut player: PlayerRecord) -> Result<PlayerRecord> {
+    player.schema_version = 3;
+    Ok(player)
+}
+
+/// PlayerRecord as stored up to v2
+#[derive(Deserialize)]
+struct LegacyPlayerRecordV2 {
+    username: String,
+    display_name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    current_room: String,
+    state: LegacyPlayerStateV2,
+    stats: LegacyPlayerStatsV2,
+    inventory_stacks: Vec<ItemStack>,
+    inventory: Vec<String>,
+    currency: CurrencyAmount,
+    banked_currency: CurrencyAmount,
+    credits: u32,
+    tutorial_state: TutorialState,
+    quests: Vec<PlayerQuest>,
+    achievements: Vec<PlayerAchievement>,
+    equipped_title: Option<String>,
+    companions: Vec<String>,
+    mounted_companion: Option<String>,
+    primary_housing_id: Option<String>,
+    last_teleport: Option<DateTime<Utc>>,
+    in_combat: bool,
+    is_admin: bool,
+    admin_level: Option<u8>,
+    builder_level: Option<u8>,
+    clone_quota: u32,
+    last_clone_time: u64,
+    total_objects_owned: u32,
+    examined_symbol_sequence: Vec<String>,
+    faction_reputation: HashMap<String, i32>,
+    schema_version: u8,
+}
+
+#[derive(Deserialize)]
+enum LegacyPlayerStateV2 {
+    Exploring,
+    InDialog(String),
+    InCombat(LegacyCombatStateV2),
+    Shopping(String),
+    ViewingInventory,
+    Dead,
+}
+
+#[derive(Deserialize)]
+struct LegacyCombatStateV2 {
+    enemy_id: String,
+    enemy_hp: u32,
+    enemy_max_hp: u32,
+    round: u32,
+    fled: bool,
+}
+
+#[derive(Deserialize)]
+struct LegacyPlayerStatsV2 {
+    hp: u32,
+    max_hp: u32,
+    mp: u32,
+    max_mp: u32,
+    strength: u8,
+    dexterity: u8,
+    intelligence: u8,
+    constitution: u8,
+    armor_class: u8,
+}
+
+impl From<LegacyPlayerRecordV2> for PlayerRecord {
+    fn from(old: LegacyPlayerRecordV2) -> Self {
+        let state = match old.state {
+            LegacyPlayerStateV2::Exploring => PlayerState::Exploring,
+            LegacyPlayerStateV2::InDialog(npc) => PlayerState::InDialog(npc),
+            LegacyPlayerStateV2::InCombat(c) => PlayerState::InCombat(CombatState {
+                enemy_id: c.enemy_id,
+                enemy_hp: c.enemy_hp,
+                enemy_max_hp: c.enemy_max_hp,
+                round: c.round,
+                fled: c.fled,
+                defending: false,
+            }),
+            LegacyPlayerStateV2::Shopping(shop) => PlayerState::Shopping(shop),
+            LegacyPlayerStateV2::ViewingInventory => PlayerState::ViewingInventory,
+            LegacyPlayerStateV2::Dead => PlayerState::Dead,
+        };
+        let s = old.stats;
+        Self {
+            username: old.username,
+            display_name: old.display_name,
+            created_at: old.created_at,
+            updated_at: old.updated_at,
+            current_room: old.current_room,
+            state,
+            stats: PlayerStats {
+                hp: s.hp,
+                max_hp: s.max_hp,
+                mp: s.mp,
+                max_mp: s.max_mp,
+                strength: s.strength,
+                dexterity: s.dexterity,
+                intelligence: s.intelligence,
+                constitution: s.constitution,
+                armor_class: s.armor_class,
+                ..PlayerStats::default()
+            },
+            inventory_stacks: old.inventory_stacks,
+            inventory: old.inventory,
+            currency: old.currency,
+            banked_currency: old.banked_currency,
+            credits: old.credits,
+            tutorial_state: old.tutorial_state,
+            quests: old.quests,
+            achievements: old.achievements,
+            equipped_title: old.equipped_title,
+            companions: old.companions,
+            mounted_companion: old.mounted_companion,
+            primary_housing_id: old.primary_housing_id,
+            last_teleport: old.last_teleport,
+            in_combat: old.in_combat,
+            is_admin: old.is_admin,
+            admin_level: old.admin_level,
+            builder_level: old.builder_level,
+            clone_quota: old.clone_quota,
+            last_clone_time: old.last_clone_time,
+            total_objects_owned: old.total_objects_owned,
+            examined_symbol_sequence: old.examined_symbol_sequence,
+            faction_reputation: old.faction_reputation,
+            skills: HashMap::new(),
+            explored_rooms: HashSet::new(),
+            spells: Vec::new(),
+            spell_cooldowns: HashMap::new(),
+            mp_regen_at: None,
+            schema_version: old.schema_version,
+        }
+    }
+}
+
 // ============================================================================
 // NpcRecord Migration
 // ============================================================================
@@ -174,11 +352,9 @@ impl Migratable for NpcRecord {
             Self::current_schema_version()
         );
 
-        // Currently at v1, no migrations needed yet
-        // Future migrations:
-        // if self.schema_version < 2 {
-        //     self = migrate_npc_from_v1_to_v2(self)?;
-        // }
+        if self.schema_version < 2 {
+            self = migrate_npc_from_v1_to_v2(self)?;
+        }
 
         self.schema_version = Self::current_schema_version();
 
@@ -189,6 +365,158 @@ impl Migratable for NpcRecord {
 
         Ok(self)
     }
+
+    fn decode_legacy(data: &[u8]) -> Option<Self> {
+        // schema_version is the last field
+        if data.last().is_none_or(|v| *v >= 2) || decode_exact::<Self>(data).is_ok() {
+            return None;
+        }
+        decode_exact::<LegacyNpcRecordV1>(data).ok().map(Self::from)
+    }
+}
+
+/// Migrate NpcRecord from v1 to v2
+///
+/// Changes in v2:
+/// - Added patrol route (before created_at, read through [`LegacyNpcRecordV1`])
+fn migrate_npc_from_v1_to_v2(mut npc: NpcRecord) -> Result<NpcRecord> {
+    npc.schema_version = 2;
+    Ok(npc)
+}
+
+/// NpcRecord as stored in v1
+#[derive(Deserialize)]
+struct LegacyNpcRecordV1 {
+    id: String,
+    name: String,
+    title: String,
+    description: String,
+    room_id: String,
+    dialog: HashMap<String, String>,
+    dialog_tree: HashMap<String, DialogNode>,
+    flags: Vec<NpcFlag>,
+    created_at: DateTime<Utc>,
+    schema_version: u8,
+}
+
+impl From<LegacyNpcRecordV1> for NpcRecord {
+    fn from(old: LegacyNpcRecordV1) -> Self {
+        Self {
+            id: old.id,
+            name: old.name,
+            title: old.title,
+            description: old.description,
+            room_id: old.room_id,
+            dialog: old.dialog,
+            dialog_tree: old.dialog_tree,
+            flags: old.flags,
+            patrol: Vec::new(),
+            created_at: old.created_at,
+            schema_version: old.schema_version,
+        }
+    }
+}
+
+// ============================================================================
+// CompanionRecord Migration
+// ============================================================================
+
+impl Migratable for CompanionRecord {
+    fn current_schema_version() -> u8 {
+        CURRENT_COMPANION_SCHEMA_VERSION
+    }
+
+    fn schema_version(&self) -> u8 {
+        self.schema_version
+    }
+
+    fn migrate(mut self) -> Result<Self> {
+        let original_version = self.schema_version;
+
+        if !self.needs_migration() {
+            return Ok(self);
+        }
+
+        info!(
+            "Migrating CompanionRecord '{}' from schema v{} to v{}",
+            self.id,
+            original_version,
+            Self::current_schema_version()
+        );
+
+        if self.schema_version < 2 {
+            self = migrate_companion_from_v1_to_v2(self)?;
+        }
+
+        self.schema_version = Self::current_schema_version();
+
+        info!(
+            "Successfully migrated CompanionRecord '{}' from v{} to v{}",
+            self.id, original_version, self.schema_version
+        );
+
+        Ok(self)
+    }
+
+    fn decode_legacy(data: &[u8]) -> Option<Self> {
+        // schema_version is the last field
+        if data.last().is_none_or(|v| *v >= 2) || decode_exact::<Self>(data).is_ok() {
+            return None;
+        }
+        decode_exact::<LegacyCompanionRecordV1>(data)
+            .ok()
+            .map(Self::from)
+    }
+}
+
+/// Migrate CompanionRecord from v1 to v2
+///
+/// Changes in v2:
+/// - Added last_healed (before created_at, read through [`LegacyCompanionRecordV1`])
+fn migrate_companion_from_v1_to_v2(mut companion: CompanionRecord) -> Result<CompanionRecord> {
+    companion.schema_version = 2;
+    Ok(companion)
+}
+
+/// CompanionRecord as stored in v1
+#[derive(Deserialize)]
+struct LegacyCompanionRecordV1 {
+    id: String,
+    name: String,
+    companion_type: CompanionType,
+    description: String,
+    owner: Option<String>,
+    room_id: String,
+    loyalty: u32,
+    happiness: u32,
+    last_fed: Option<DateTime<Utc>>,
+    behaviors: Vec<CompanionBehavior>,
+    inventory: Vec<String>,
+    is_mounted: bool,
+    created_at: DateTime<Utc>,
+    schema_version: u8,
+}
+
+impl From<LegacyCompanionRecordV1> for CompanionRecord {
+    fn from(old: LegacyCompanionRecordV1) -> Self {
+        Self {
+            id: old.id,
+            name: old.name,
+            companion_type: old.companion_type,
+            description: old.description,
+            owner: old.owner,
+            room_id: old.room_id,
+            loyalty: old.loyalty,
+            happiness: old.happiness,
+            last_fed: old.last_fed,
+            behaviors: old.behaviors,
+            inventory: old.inventory,
+            is_mounted: old.is_mounted,
+            last_healed: None,
+            created_at: old.created_at,
+            schema_version: old.schema_version,
+        }
+    }
 }
 
 // ============================================================================
@@ -307,6 +635,43 @@ fn migrate_object_from_v1_to_v2(mut object: ObjectRecord) -> Result<ObjectRecord
     Ok(object)
 }
 
+// ============================================================================
+// WorldConfig Migration
+// ============================================================================
+
+/// Decode the stored WorldConfig, upgrading older layouts. Returns the config
+/// and whether it should be written back.
+///
+/// Changes in v2:
+/// - Appended auction_listing_fee. A v1 record is a byte prefix of the v2
+///   layout, so the default fee is appended before decoding.
+pub fn load_world_config(data: &[u8]) -> Result<(WorldConfig, bool)> {
+    if let Ok(mut config) = bincode::deserialize::<WorldConfig>(data) {
+        let outdated = config.version < CURRENT_WORLD_CONFIG_VERSION;
+        config.version = config.version.max(CURRENT_WORLD_CONFIG_VERSION);
+        return Ok((config, outdated));
+    }
+
+    let from_version = data.first().copied().unwrap_or(0);
+    if from_version >= CURRENT_WORLD_CONFIG_VERSION {
+        return Err(anyhow!(
+            "Failed to deserialize WorldConfig v{}",
+            from_version
+        ));
+    }
+    let default_fee = WorldConfig::default().auction_listing_fee;
+    let mut upgraded = data.to_vec();
+    upgraded.extend(bincode::serialize(&default_fee)?);
+    let mut config: WorldConfig = bincode::deserialize(&upgraded)
+        .map_err(|e| anyhow!("Failed to deserialize WorldConfig v{}: {}", from_version, e))?;
+    config.version = CURRENT_WORLD_CONFIG_VERSION;
+    info!(
+        "Migrated WorldConfig from v{} to v{}",
+        from_version, config.version
+    );
+    Ok((config, true))
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -322,15 +687,18 @@ pub fn load_and_migrate<T>(data: &[u8], record_id: &str) -> Result<(T, bool)>
 where
     T: Migratable + for<'de> Deserialize<'de> + Serialize,
 {
-    // Attempt to deserialize
-    let mut record: T = bincode::deserialize(data).map_err(|e| {
-        anyhow!(
-            "Failed to deserialize {} '{}': {}",
-            std::any::type_name::<T>(),
-            record_id,
-            e
-        )
-    })?;
+    // Attempt to deserialize, reading older field layouts where the type keeps them
+    let mut record: T = match T::decode_legacy(data) {
+        Some(record) => record,
+        None => bincode::deserialize(data).map_err(|e| {
+            anyhow!(
+                "Failed to deserialize {} '{}': {}",
+                std::any::type_name::<T>(),
+                record_id,
+                e
+            )
+        })?,
+    };
 
     // Check if migration is needed
     let needs_migration = record.needs_migration();
@@ -358,6 +726,9 @@ pub fn check_needs_migration<T>(data: &[u8]) -> Result<bool>
 where
     T: Migratable + for<'de> Deserialize<'de>,
 {
+    if T::decode_legacy(data).is_some() {
+        return Ok(true);
+    }
     // Try to deserialize just the schema_version field
     // This is a simple heuristic - full deserialization may still be needed
     let record: T = bincode::deserialize(data)?;
@@ -382,6 +753,101 @@ mod tests {
         assert_eq!(migrated.username, "testuser");
     }
 
+    /// Bytes of a PlayerRecord as v2 builds stored it, before the level,
+    /// skill and spell fields, fighting a wolf
+    fn v2_player_bytes(p: &PlayerRecord) -> Vec<u8> {
+        let s = &p.stats;
+        let stats = (
+            s.hp,
+            s.max_hp,
+            s.mp,
+            s.max_mp,
+            s.strength,
+            s.dexterity,
+            s.intelligence,
+            s.constitution,
+            s.armor_class,
+        );
+        // PlayerState::InCombat is variant 2
+        let state = (2u32, ("wolf", 7u32, 10u32, 3u32, false));
+        let head = (
+            &p.username,
+            &p.display_name,
+            &p.created_at,
+            &p.updated_at,
+            &p.current_room,
+            state,
+            stats,
+        );
+        let middle = (
+            &p.inventory_stacks,
+            &p.inventory,
+            &p.currency,
+            &p.banked_currency,
+            p.credits,
+            &p.tutorial_state,
+            &p.quests,
+            &p.achievements,
+            &p.equipped_title,
+            &p.companions,
+            &p.mounted_companion,
+            &p.primary_housing_id,
+            &p.last_teleport,
+        );
+        let tail = (
+            p.in_combat,
+            p.is_admin,
+            p.admin_level,
+            p.builder_level,
+            p.clone_quota,
+            p.last_clone_time,
+            p.total_objects_owned,
+            &p.examined_symbol_sequence,
+            &p.faction_reputation,
+            2u8,
+        );
+        bincode::serialize(&(head, middle, tail)).unwrap()
+    }
+
+    #[test]
+    fn test_player_v2_layout_loads() {
+        let mut player = PlayerRecord::new("olduser", "Old User", "town_square");
+        player.stats.strength = 14;
+        player.inventory.push("torch".to_string());
+        player.faction_reputation.insert("guild".to_string(), 25);
+
+        let (loaded, migrated) =
+            load_and_migrate::<PlayerRecord>(&v2_player_bytes(&player), "olduser")
+                .expect("v2 record loads");
+        assert!(migrated);
+        assert_eq!(loaded.schema_version, CURRENT_PLAYER_SCHEMA_VERSION);
+        assert_eq!(loaded.username, "olduser");
+        assert_eq!(loaded.stats.strength, 14);
+        assert_eq!(loaded.inventory, vec!["torch".to_string()]);
+        assert_eq!(loaded.faction_reputation["guild"], 25);
+        match &loaded.state {
+            PlayerState::InCombat(combat) => {
+                assert_eq!(combat.enemy_id, "wolf");
+                assert_eq!(combat.round, 3);
+                assert!(!combat.defending);
+            }
+            other => panic!("expected combat state, got {:?}", other),
+        }
+        assert_eq!(loaded.stats.level, 1);
+        assert_eq!(loaded.stats.experience, 0);
+        assert!(loaded.skills.is_empty());
+        assert!(loaded.explored_rooms.is_empty());
+        assert!(loaded.spells.is_empty());
+        assert!(loaded.spell_cooldowns.is_empty());
+        assert_eq!(loaded.mp_regen_at, None);
+
+        // Current bytes never go through the legacy layout
+        let current = bincode::serialize(&loaded).unwrap();
+        let (again, migrated) = load_and_migrate::<PlayerRecord>(&current, "olduser").unwrap();
+        assert!(!migrated);
+        assert_eq!(again, loaded);
+    }
+
     #[test]
     fn test_current_version_no_migration() {
         // Create a current version record
@@ -417,18 +883,74 @@ mod tests {
     }
 
     #[test]
-    fn test_npc_no_migrations_yet() {
-        // NpcRecord is at v1, no migrations defined yet
-        let npc = NpcRecord::new(
+    fn test_npc_v1_layout_loads() {
+        let mut npc = NpcRecord::new(
             "test_npc",
             "Test NPC",
             "Tester",
             "A test NPC",
             "town_square",
         );
-
         assert!(!npc.needs_migration());
-        assert_eq!(npc.schema_version(), CURRENT_NPC_SCHEMA_VERSION);
+        npc.dialog
+            .insert("hello".to_string(), "Hi there".to_string());
+        npc.flags.push(NpcFlag::Vendor);
+
+        // v1 had no patrol between flags and created_at
+        let v1 = bincode::serialize(&(
+            &npc.id,
+            &npc.name,
+            &npc.title,
+            &npc.description,
+            &npc.room_id,
+            &npc.dialog,
+            &npc.dialog_tree,
+            &npc.flags,
+            &npc.created_at,
+            1u8,
+        ))
+        .unwrap();
+        let (loaded, migrated) = load_and_migrate::<NpcRecord>(&v1, "test_npc").unwrap();
+        assert!(migrated);
+        assert_eq!(loaded.schema_version, CURRENT_NPC_SCHEMA_VERSION);
+        assert_eq!(loaded.dialog["hello"], "Hi there");
+        assert_eq!(loaded.flags, vec![NpcFlag::Vendor]);
+        assert!(loaded.patrol.is_empty());
+        assert_eq!(loaded.created_at, npc.created_at);
+    }
+
+    #[test]
+    fn test_companion_v1_layout_loads() {
+        let mut pet = CompanionRecord::new("rex", "Rex", CompanionType::Dog, "town_square");
+        assert!(!pet.needs_migration());
+        pet.owner = Some("alice".to_string());
+        pet.loyalty = 80;
+
+        // v1 had no last_healed between is_mounted and created_at
+        let v1 = bincode::serialize(&(
+            &pet.id,
+            &pet.name,
+            pet.companion_type,
+            &pet.description,
+            &pet.owner,
+            &pet.room_id,
+            pet.loyalty,
+            pet.happiness,
+            &pet.last_fed,
+            &pet.behaviors,
+            &pet.inventory,
+            pet.is_mounted,
+            &pet.created_at,
+            1u8,
+        ))
+        .unwrap();
+        let (loaded, migrated) = load_and_migrate::<CompanionRecord>(&v1, "rex").unwrap();
+        assert!(migrated);
+        assert_eq!(loaded.schema_version, CURRENT_COMPANION_SCHEMA_VERSION);
+        assert_eq!(loaded.owner.as_deref(), Some("alice"));
+        assert_eq!(loaded.loyalty, 80);
+        assert_eq!(loaded.behaviors, pet.behaviors);
+        assert_eq!(loaded.last_healed, None);
     }
 
     #[test]
@@ -447,4 +969,31 @@ mod tests {
         assert!(!failure.success);
         assert!(failure.error.is_some());
     }
+
+    #[test]
+    fn test_world_config_v1_layout_upgrades() {
+        let config = WorldConfig {
+            version: 1,
+            welcome_message: "Custom welcome".to_string(),
+            auction_listing_fee: 40,
+            ..WorldConfig::default()
+        };
+        let mut v1 = bincode::serialize(&config).unwrap();
+        // v1 ended before auction_listing_fee
+        v1.truncate(v1.len() - std::mem::size_of::<i64>());
+
+        let (loaded, rewrite) = load_world_config(&v1).expect("v1 config loads");
+        assert!(rewrite);
+        assert_eq!(loaded.version, CURRENT_WORLD_CONFIG_VERSION);
+        assert_eq!(loaded.welcome_message, "Custom welcome");
+        assert_eq!(
+            loaded.auction_listing_fee,
+            WorldConfig::default().auction_listing_fee
+        );
+
+        let current = bincode::serialize(&loaded).unwrap();
+        let (again, rewrite) = load_world_config(&current).unwrap();
+        assert!(!rewrite);
+        assert_eq!(again.welcome_message, "Custom welcome");
+    }
 }
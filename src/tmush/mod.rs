@@ -12,8 +12,13 @@
 //! - **Quest System**: Epic multi-stage quests with reputation rewards
 //! - **Crafting System**: Recipe-based item creation with material requirements
 //! - **Achievement System**: 17+ achievements across 6 categories
+//! - **Skills and Levels**: Experience, levels and skills that improve with use (see [`skills`])
+//! - **Magic**: Spells learned from scrolls or trainers and cast with MP (see [`magic`])
 //! - **Economy**: Multi-tier currency system with shops and trading
+//! - **Auction House**: Offline bidding with escrow and settlement by mail (see [`auction`])
+//! - **Guilds**: Player guilds with ranks, guild chat, a shared treasury and hall (see [`guild`])
 //! - **Trigger System**: Event-driven actions with conditional logic
+//! - **World Tick**: NPC patrols, idle triggers and companion behaviors between commands (see [`world_tick`])
 //! - **Builder Commands**: Runtime world editing with @ROOM, @OBJECT, @NPC, etc.
 //! - **Data-Driven**: All content loaded from JSON files in `data/seeds/`
 //!
@@ -26,6 +31,8 @@
 //! - `@OBJECT` - Object management
 //! - `@QUEST` - Quest creation and assignment
 //! - `@RECIPE` - Crafting recipe management
+//! - `@EXPORT` / `@IMPORT` - World content as JSON area files (see [`world_io`])
+//! - `@DUNGEON` - Rooms copied per party as dungeon instances (see [`dungeon`])
 //!
 //! ## Storage
 //!
@@ -56,26 +63,37 @@
 //! ```
 
 pub mod achievement;
+pub mod auction;
 pub mod builder_commands;
 pub mod clone;
+pub mod combat;
 pub mod commands;
 pub mod companion;
 pub mod currency;
 pub mod currency_migration;
+pub mod dungeon;
+pub mod edit_log;
 pub mod errors;
+pub mod events;
+pub mod faction;
+pub mod guild;
 pub mod housing_cleanup;
 pub mod inventory;
+pub mod magic;
 pub mod migration;
 pub mod quest;
 pub mod resolver;
 pub mod room_manager;
 pub mod seed_loader;
 pub mod shop;
+pub mod skills;
 pub mod state;
 pub mod storage;
 pub mod trigger;
 pub mod tutorial;
 pub mod types;
+pub mod world_io;
+pub mod world_tick;
 
 pub use achievement::{
     award_achievement, check_trigger, get_achievements_by_category, get_available_achievements,
@@ -101,6 +119,11 @@ pub use currency::{
     STANDARD_CONVERSION_RATIO,
 };
 pub use errors::TinyMushError;
+pub use events::{RoomEvent, RoomEventBus, RoomEventKind};
+pub use faction::{
+    apply_reputation_changes, find_faction, format_standing, quest_reputation_rewards,
+    shop_pricing, DEFAULT_QUEST_REPUTATION,
+};
 pub use housing_cleanup::{
     check_and_cleanup_housing, list_abandoned_housing, AbandonedHousingInfo, CleanupConfig,
     CleanupStats,
@@ -112,18 +135,22 @@ pub use inventory::{
 pub use quest::{
     abandon_quest, accept_quest, can_accept_quest, complete_quest, format_quest_list,
     format_quest_status, get_active_quests, get_available_quests, get_completed_quests,
-    update_quest_objective,
+    record_enemy_kill, update_quest_objective,
 };
 pub use resolver::{format_disambiguation_prompt, resolve_object_name, ObjectMatch, ResolveResult};
 pub use seed_loader::{
-    load_achievements_from_json, load_companions_from_json, load_npcs_from_json,
-    load_quests_from_json, load_recipes_from_json, load_rooms_from_json,
+    load_achievements_from_json, load_companions_from_json, load_mobs_from_json,
+    load_npcs_from_json, load_quests_from_json, load_recipes_from_json, load_rooms_from_json,
+    load_spells_from_json,
+};
+pub use shop::{
+    format_shop_item_detail, format_shop_listing, format_shop_listing_with, FactionPricing, ShopConfig,
+    ShopItem, ShopRecord,
 };
-pub use shop::{format_shop_item_detail, format_shop_listing, ShopConfig, ShopItem, ShopRecord};
 pub use state::{
-    canonical_world_seed, seed_starter_achievements, seed_starter_companions, seed_starter_npcs,
-    seed_starter_quests, OLD_TOWNE_WORLD_ROOM_IDS, REQUIRED_LANDING_LOCATION_ID,
-    REQUIRED_START_LOCATION_ID,
+    canonical_world_seed, seed_starter_achievements, seed_starter_companions, seed_starter_mobs,
+    seed_starter_npcs, seed_starter_quests, seed_starter_spells, OLD_TOWNE_WORLD_ROOM_IDS,
+    REQUIRED_LANDING_LOCATION_ID, REQUIRED_START_LOCATION_ID,
 };
 pub use storage::{TinyMushStore, TinyMushStoreBuilder};
 pub use tutorial::{
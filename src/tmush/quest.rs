@@ -3,6 +3,8 @@
 /// This module provides functions for quest lifecycle management including
 /// accepting quests, tracking objective progress, and completing quests.
 use crate::tmush::errors::TinyMushError;
+use crate::tmush::faction::{apply_reputation_changes, quest_reputation_rewards};
+use crate::tmush::skills::award_xp;
 use crate::tmush::storage::TinyMushStore;
 use crate::tmush::types::{InventoryConfig, ObjectiveType, PlayerQuest, TransactionReason};
 
@@ -99,6 +101,43 @@ pub fn update_quest_objective(
     Ok(false)
 }
 
+/// Record a combat kill against every active quest with a matching KillEnemy objective
+///
+/// Matching is case-insensitive on `enemy_type`. Returns the IDs of quests whose
+/// objectives became fully complete as a result.
+pub fn record_enemy_kill(
+    store: &TinyMushStore,
+    username: &str,
+    enemy_type: &str,
+) -> Result<Vec<String>, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    let mut completed = Vec::new();
+    let mut changed = false;
+
+    for quest in player.quests.iter_mut().filter(|pq| pq.is_active()) {
+        let mut progressed = false;
+        for objective in &mut quest.objectives {
+            if let ObjectiveType::KillEnemy { enemy_type: target, .. } = &objective.objective_type {
+                if target.eq_ignore_ascii_case(enemy_type) && !objective.is_complete() {
+                    objective.increment_progress(1);
+                    progressed = true;
+                }
+            }
+        }
+        if progressed {
+            changed = true;
+            if quest.all_objectives_complete() {
+                completed.push(quest.quest_id.clone());
+            }
+        }
+    }
+
+    if changed {
+        store.put_player(player)?;
+    }
+    Ok(completed)
+}
+
 /// Mark quest as complete and distribute rewards
 pub fn complete_quest(
     store: &TinyMushStore,
@@ -130,6 +169,12 @@ pub fn complete_quest(
             let _ = store.player_add_item(username, item_id, 1, &config);
         }
 
+        // Grant faction reputation
+        let reputation = quest_reputation_rewards(store, &quest)?;
+        apply_reputation_changes(store, username, &reputation)?;
+
+        award_xp(store, username, quest.rewards.experience)?;
+
         // Reload player after rewards, mark quest complete, and save
         player = store.get_player(username)?;
         player.quests[pos].mark_complete();
@@ -371,6 +416,36 @@ mod tests {
         let player = store.get_player("alice").unwrap();
         assert!(player.quests[0].is_complete());
         assert_eq!(player.currency.base_value(), 500); // Reward granted
+        assert_eq!(player.stats.experience, 100);
+        assert_eq!(player.stats.level, 2);
+    }
+
+    #[test]
+    fn test_complete_quest_grants_reputation() {
+        let (_dir, store) = setup_test_store();
+        create_test_player(&store, "alice");
+        let quest = create_test_quest(&store, "quest1", "npc1").with_reward_reputation("tinkers", 30);
+        store.put_quest(quest).expect("put quest");
+        store
+            .put_faction(
+                crate::tmush::types::FactionRecord::new("scholars", "Scholars Circle", "")
+                    .with_quest("quest1"),
+            )
+            .expect("put faction");
+
+        accept_quest(&store, "alice", "quest1").unwrap();
+        let objective_type = ObjectiveType::VisitLocation {
+            room_id: "town_square".to_string(),
+        };
+        update_quest_objective(&store, "alice", "quest1", &objective_type, 1).unwrap();
+        complete_quest(&store, "alice", "quest1").unwrap();
+
+        let player = store.get_player("alice").unwrap();
+        assert_eq!(player.get_reputation("tinkers"), 30);
+        assert_eq!(
+            player.get_reputation("scholars"),
+            crate::tmush::faction::DEFAULT_QUEST_REPUTATION
+        );
     }
 
     #[test]
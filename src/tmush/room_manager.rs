@@ -2,13 +2,14 @@
 //!
 //! This module provides efficient room access with caching and enforces room
 //! capacity limits. It supports both world rooms and instanced rooms (apartments,
-//! hotel rooms) with proper permissions and quotas.
+//! hotel rooms, dungeon instances) with proper permissions and quotas.
 
 use anyhow::Result;
 use log::debug;
 use std::collections::HashMap;
 use std::time::Instant;
 
+use crate::tmush::dungeon;
 use crate::tmush::storage::TinyMushStore;
 use crate::tmush::types::{RoomFlag, RoomRecord};
 use crate::tmush::{PlayerRecord, TinyMushError};
@@ -89,6 +90,22 @@ impl RoomManager {
     ) -> Result<bool, TinyMushError> {
         let room = self.get_room(room_id)?;
 
+        // Dungeon instance rooms only admit their own party
+        if let Some(instance_id) = dungeon::instance_id_of(room_id) {
+            let admitted = match self.store.get_dungeon_instance(instance_id) {
+                Ok(instance) => instance.is_member(&player.username),
+                Err(TinyMushError::NotFound(_)) => false,
+                Err(e) => return Err(e),
+            };
+            if !admitted {
+                debug!(
+                    "Player {} denied access to dungeon instance {}",
+                    player.username, instance_id
+                );
+                return Ok(false);
+            }
+        }
+
         // Check room capacity
         let current_occupancy = self.get_room_occupancy(room_id);
         let capacity_limit = self.get_room_capacity_limit(&room);
@@ -263,6 +280,14 @@ impl RoomManager {
         }
     }
 
+    /// Drop cached rooms and tracked players of a torn down dungeon instance
+    pub fn forget_instance(&mut self, instance_id: &str) {
+        let owned = |room_id: &str| dungeon::instance_id_of(room_id) == Some(instance_id);
+        self.cache.retain(|room_id, _| !owned(room_id));
+        self.player_locations.retain(|_, room_id| !owned(room_id));
+        debug!("Forgot dungeon instance {}", instance_id);
+    }
+
     /// Clear the cache (useful for testing)
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -5,7 +5,7 @@
 
 use crate::tmush::types::{
     AchievementCategory, AchievementRecord, AchievementTrigger, CompanionRecord, CraftingRecipe,
-    NpcRecord, QuestRecord, RoomRecord,
+    MobRecord, NpcCombatStats, NpcRecord, QuestRecord, RoomRecord, SpellRecord,
 };
 use crate::tmush::TinyMushError;
 use serde::{Deserialize, Serialize};
@@ -236,6 +236,50 @@ pub fn load_recipes_from_json<P: AsRef<Path>>(
     Ok(recipes)
 }
 
+/// Load hostile mobs from data/seeds/mobs.json
+pub fn load_mobs_from_json<P: AsRef<Path>>(path: P) -> Result<Vec<MobRecord>, TinyMushError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    let mobs: Vec<MobSeed> = serde_json::from_str(&contents)
+        .map_err(|e| TinyMushError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Failed to parse {}: {}", path.display(), e),
+        )))?;
+
+    // Convert seed format to MobRecord
+    let records: Vec<MobRecord> = mobs
+        .into_iter()
+        .map(|seed| {
+            let mut mob = MobRecord::new(&seed.id, &seed.name, &seed.description, &seed.location, seed.combat);
+            if let Some(enemy_type) = seed.enemy_type {
+                mob = mob.with_enemy_type(&enemy_type);
+            }
+            if let Some(seconds) = seed.respawn_seconds {
+                mob = mob.with_respawn_seconds(seconds);
+            }
+            mob
+        })
+        .collect();
+
+    Ok(records)
+}
+
+/// Load spells from data/seeds/spells.json
+pub fn load_spells_from_json<P: AsRef<Path>>(path: P) -> Result<Vec<SpellRecord>, TinyMushError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)?;
+
+    // SpellRecord defaults every optional field, so seeds stay short
+    let spells: Vec<SpellRecord> = serde_json::from_str(&contents)
+        .map_err(|e| TinyMushError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Failed to parse {}: {}", path.display(), e),
+        )))?;
+
+    Ok(spells)
+}
+
 // ============================================================================
 // Seed data structures that match JSON format
 // ============================================================================
@@ -287,6 +331,17 @@ struct AchievementSeed {
     hidden: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct MobSeed {
+    id: String,
+    name: String,
+    description: String,
+    location: String,
+    enemy_type: Option<String>,
+    combat: NpcCombatStats,
+    respawn_seconds: Option<u64>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,5 +352,36 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_load_mobs_from_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mobs.json");
+        fs::write(
+            &path,
+            r#"[{
+                "id": "sewer_rat",
+                "name": "Sewer Rat",
+                "description": "A rat the size of a small dog.",
+                "location": "maintenance_tunnels",
+                "enemy_type": "rat",
+                "combat": {
+                    "max_hp": 12,
+                    "attack": 2,
+                    "defense": 2,
+                    "damage_range": [1, 4],
+                    "loot_table": [{"object_id": "copper_wire", "chance": 0.5}]
+                }
+            }]"#,
+        )
+        .unwrap();
+
+        let mobs = load_mobs_from_json(&path).unwrap();
+        assert_eq!(mobs.len(), 1);
+        assert_eq!(mobs[0].enemy_type, "rat");
+        assert_eq!(mobs[0].combat.damage_range, (1, 4));
+        assert_eq!(mobs[0].combat.loot_table[0].quantity, (1, 1));
+        assert_eq!(mobs[0].respawn_seconds, 300);
+    }
+
     // Additional tests should be added once actual seed files exist
 }
@@ -5,6 +5,7 @@
 //! - Dynamic pricing with markup/markdown
 //! - Buy/sell operations with currency conversion
 //! - Stock management and restocking
+//! - Faction standing discounts, surcharges and refusals
 //! - Shop persistence
 
 use anyhow::{anyhow, Result};
@@ -12,7 +13,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::tmush::types::{CurrencyAmount, ObjectRecord};
+use crate::tmush::types::{CurrencyAmount, ObjectRecord, ReputationLevel};
 
 /// Configuration for shop behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +45,74 @@ impl Default for ShopConfig {
     }
 }
 
+/// How a shop treats a customer given their standing with the shop's faction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FactionPricing {
+    /// Hostile (or worse) customers are turned away
+    Refused,
+    /// Factors applied on top of markup (buy) and markdown (sell)
+    Adjusted { buy: f64, sell: f64 },
+}
+
+impl FactionPricing {
+    /// Pricing for customers the shop has no opinion about
+    pub const NEUTRAL: FactionPricing = FactionPricing::Adjusted {
+        buy: 1.0,
+        sell: 1.0,
+    };
+
+    /// Pricing for a reputation level with the shop's faction
+    pub fn for_level(level: ReputationLevel) -> Self {
+        match level {
+            ReputationLevel::Hated | ReputationLevel::Hostile => FactionPricing::Refused,
+            ReputationLevel::Unfriendly => FactionPricing::Adjusted {
+                buy: 1.15,
+                sell: 0.9,
+            },
+            ReputationLevel::Neutral => Self::NEUTRAL,
+            ReputationLevel::Friendly => FactionPricing::Adjusted {
+                buy: 0.9,
+                sell: 1.05,
+            },
+            ReputationLevel::Honored => FactionPricing::Adjusted {
+                buy: 0.85,
+                sell: 1.1,
+            },
+            ReputationLevel::Revered => FactionPricing::Adjusted {
+                buy: 0.8,
+                sell: 1.15,
+            },
+        }
+    }
+
+    /// Factor applied to buy prices (1.0 when refused; callers check first)
+    pub fn buy_factor(&self) -> f64 {
+        match self {
+            FactionPricing::Refused => 1.0,
+            FactionPricing::Adjusted { buy, .. } => *buy,
+        }
+    }
+
+    /// Factor applied to sell prices (1.0 when refused; callers check first)
+    pub fn sell_factor(&self) -> f64 {
+        match self {
+            FactionPricing::Refused => 1.0,
+            FactionPricing::Adjusted { sell, .. } => *sell,
+        }
+    }
+
+    /// Short note describing the buy-side adjustment, e.g. "10% discount"
+    pub fn describe(&self) -> Option<String> {
+        let percent = ((self.buy_factor() - 1.0) * 100.0).round() as i64;
+        match self {
+            FactionPricing::Refused => None,
+            _ if percent < 0 => Some(format!("{}% discount", -percent)),
+            _ if percent > 0 => Some(format!("{}% surcharge", percent)),
+            _ => None,
+        }
+    }
+}
+
 /// An item available in a shop's inventory
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShopItem {
@@ -245,7 +314,18 @@ impl ShopRecord {
         quantity: u32,
         shop_item: &ShopItem,
     ) -> CurrencyAmount {
-        let markup = shop_item.markup.unwrap_or(self.config.default_buy_markup);
+        self.calculate_buy_price_with(object, quantity, shop_item, 1.0)
+    }
+
+    /// Calculate buy price with an extra factor (e.g. [`FactionPricing::buy_factor`])
+    pub fn calculate_buy_price_with(
+        &self,
+        object: &ObjectRecord,
+        quantity: u32,
+        shop_item: &ShopItem,
+        price_factor: f64,
+    ) -> CurrencyAmount {
+        let markup = shop_item.markup.unwrap_or(self.config.default_buy_markup) * price_factor;
 
         // Use currency_value if set, otherwise fall back to legacy value
         let base_value = if object.currency_value.is_positive() {
@@ -270,10 +350,22 @@ impl ShopRecord {
         object: &ObjectRecord,
         quantity: u32,
         shop_item: &ShopItem,
+    ) -> CurrencyAmount {
+        self.calculate_sell_price_with(object, quantity, shop_item, 1.0)
+    }
+
+    /// Calculate sell price with an extra factor (e.g. [`FactionPricing::sell_factor`])
+    pub fn calculate_sell_price_with(
+        &self,
+        object: &ObjectRecord,
+        quantity: u32,
+        shop_item: &ShopItem,
+        price_factor: f64,
     ) -> CurrencyAmount {
         let markdown = shop_item
             .markdown
-            .unwrap_or(self.config.default_sell_markdown);
+            .unwrap_or(self.config.default_sell_markdown)
+            * price_factor;
 
         // Use currency_value if set, otherwise fall back to legacy value
         let base_value = if object.currency_value.is_positive() {
@@ -299,6 +391,17 @@ impl ShopRecord {
         object_id: &str,
         quantity: u32,
         object: &ObjectRecord,
+    ) -> Result<(CurrencyAmount, u32)> {
+        self.process_buy_with(object_id, quantity, object, 1.0)
+    }
+
+    /// Process a buy transaction with an extra price factor
+    pub fn process_buy_with(
+        &mut self,
+        object_id: &str,
+        quantity: u32,
+        object: &ObjectRecord,
+        price_factor: f64,
     ) -> Result<(CurrencyAmount, u32)> {
         // First check if item exists and get quantity info
         let (in_stock, available_qty) = {
@@ -326,7 +429,7 @@ impl ShopRecord {
         // Calculate price before mutating
         let price = {
             let shop_item = self.get_item(object_id).unwrap();
-            self.calculate_buy_price(object, actual_qty, shop_item)
+            self.calculate_buy_price_with(object, actual_qty, shop_item, price_factor)
         };
 
         // Now mutate: reduce shop stock
@@ -350,13 +453,24 @@ impl ShopRecord {
         object_id: &str,
         quantity: u32,
         object: &ObjectRecord,
+    ) -> Result<CurrencyAmount> {
+        self.process_sell_with(object_id, quantity, object, 1.0)
+    }
+
+    /// Process a sell transaction with an extra price factor
+    pub fn process_sell_with(
+        &mut self,
+        object_id: &str,
+        quantity: u32,
+        object: &ObjectRecord,
+        price_factor: f64,
     ) -> Result<CurrencyAmount> {
         // Check if shop accepts this item and calculate price before mutating
         let price = {
             let shop_item = self
                 .get_item(object_id)
                 .ok_or_else(|| anyhow!("Shop does not buy this item"))?;
-            self.calculate_sell_price(object, quantity, shop_item)
+            self.calculate_sell_price_with(object, quantity, shop_item, price_factor)
         };
 
         // Check if shop has enough currency
@@ -402,6 +516,15 @@ impl ShopRecord {
 pub fn format_shop_listing(
     shop: &ShopRecord,
     get_object: impl Fn(&str) -> Option<ObjectRecord>,
+) -> Vec<String> {
+    format_shop_listing_with(shop, get_object, 1.0)
+}
+
+/// Format shop inventory listing with buy prices scaled by `price_factor`
+pub fn format_shop_listing_with(
+    shop: &ShopRecord,
+    get_object: impl Fn(&str) -> Option<ObjectRecord>,
+    price_factor: f64,
 ) -> Vec<String> {
     let mut lines = Vec::new();
 
@@ -414,7 +537,7 @@ pub fn format_shop_listing(
 
     for (idx, (object_id, shop_item)) in shop.inventory.iter().enumerate() {
         if let Some(object) = get_object(object_id) {
-            let price = shop.calculate_buy_price(&object, 1, shop_item);
+            let price = shop.calculate_buy_price_with(&object, 1, shop_item, price_factor);
             let stock_str = if let Some(qty) = shop_item.quantity {
                 format!(" ({})", qty)
             } else {
@@ -655,6 +778,40 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_faction_pricing_adjusts_and_refuses() {
+        let shop = ShopRecord::new(
+            "shop1".to_string(),
+            "Weapon Shop".to_string(),
+            "room1".to_string(),
+            "vendor1".to_string(),
+        );
+        let object = test_object();
+        let shop_item = ShopItem::limited("sword1".to_string(), 5);
+
+        let friendly = FactionPricing::for_level(ReputationLevel::Friendly);
+        let buy = shop.calculate_buy_price_with(&object, 1, &shop_item, friendly.buy_factor());
+        assert_eq!(buy.base_value(), 108); // 120 * 0.9
+        let sell = shop.calculate_sell_price_with(&object, 1, &shop_item, friendly.sell_factor());
+        assert_eq!(sell.base_value(), 74); // 70 * 1.05
+        assert_eq!(friendly.describe().as_deref(), Some("10% discount"));
+
+        let unfriendly = FactionPricing::for_level(ReputationLevel::Unfriendly);
+        let buy = shop.calculate_buy_price_with(&object, 1, &shop_item, unfriendly.buy_factor());
+        assert_eq!(buy.base_value(), 138); // 120 * 1.15
+        assert_eq!(unfriendly.describe().as_deref(), Some("15% surcharge"));
+
+        assert_eq!(FactionPricing::NEUTRAL.describe(), None);
+        assert_eq!(
+            FactionPricing::for_level(ReputationLevel::Hostile),
+            FactionPricing::Refused
+        );
+        assert_eq!(
+            FactionPricing::for_level(ReputationLevel::Hated),
+            FactionPricing::Refused
+        );
+    }
+
     #[test]
     fn test_shop_restocking() {
         let mut item = ShopItem::limited("sword1".to_string(), 2);
@@ -0,0 +1,262 @@
+//! Experience, levels and named skills
+//!
+//! Players earn experience (XP) by completing quests, crafting, exploring a
+//! room for the first time and winning fights. XP is kept in
+//! [`PlayerStats::experience`]; each level reached raises
+//! [`PlayerStats::level`] along with hit points, mana and, every other level,
+//! the four attributes.
+//!
+//! Skills improve with use. Each use adds practice points, and once a skill
+//! has `10 × (rank + 1)` points its rank goes up by one. Ranks are checked by
+//! crafting recipes (`skill_required` / `skill_level`), NPC dialogue
+//! ([`DialogCondition::MinSkill`]) and trigger scripts
+//! (`skill("lockpicking") >= 2`, `practice("lockpicking")`). A companion with
+//! `SkillBoost` adds its bonus while it is in the same room.
+//!
+//! [`DialogCondition::MinSkill`]: crate::tmush::types::DialogCondition::MinSkill
+
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{CompanionBehavior, NpcCombatStats, PlayerRecord, PlayerStats};
+use crate::tmush::TinyMushError;
+
+/// Skills every player has, with a one-line description for `SKILLS`
+pub const SKILLS: &[(&str, &str)] = &[
+    ("combat", "fighting hostile creatures"),
+    ("crafting", "making things from recipes"),
+    ("lockpicking", "opening locks without the key"),
+    ("lore", "knowing what things are"),
+    ("magic", "working spells and charms"),
+    ("survival", "finding your way in new places"),
+];
+
+/// Highest level a player can reach
+pub const MAX_LEVEL: u32 = 50;
+/// Highest rank in any skill
+pub const MAX_SKILL_RANK: u32 = 100;
+
+/// XP for the first visit to a room
+pub const EXPLORE_XP: u32 = 5;
+/// XP for crafting a recipe with no skill requirement
+pub const CRAFT_XP: u32 = 10;
+
+/// Total XP needed to reach `level` (100 for level 2, 300 for 3, 600 for 4 ...)
+pub fn xp_for_level(level: u32) -> u64 {
+    let level = level.max(1) as u64;
+    50 * (level - 1) * level
+}
+
+/// XP for defeating a mob, scaled by how tough it is
+pub fn combat_xp(combat: &NpcCombatStats) -> u32 {
+    (combat.max_hp / 2 + combat.attack as u32 + combat.defense as u32).max(1)
+}
+
+/// Practice points needed to go from `rank` to `rank + 1`
+pub fn practice_for_rank(rank: u32) -> u32 {
+    10 * (rank + 1)
+}
+
+/// Add experience, raising the player's level as many times as it covers.
+/// Returns a line per level gained.
+pub fn grant_xp(player: &mut PlayerRecord, amount: u32) -> Vec<String> {
+    let stats = &mut player.stats;
+    stats.experience = stats.experience.saturating_add(amount as u64);
+
+    let mut lines = Vec::new();
+    while stats.level < MAX_LEVEL && stats.experience >= xp_for_level(stats.level + 1) {
+        raise_level(stats);
+        lines.push(format!(
+            "🎉 You reached level {}! HP {} MP {}",
+            stats.level, stats.max_hp, stats.max_mp
+        ));
+    }
+    lines
+}
+
+/// One level's worth of stat gains; hit points and mana are refilled
+fn raise_level(stats: &mut PlayerStats) {
+    stats.level += 1;
+    stats.max_hp += 5;
+    stats.max_mp += 2;
+    stats.hp = stats.max_hp;
+    stats.mp = stats.max_mp;
+    if stats.level.is_multiple_of(2) {
+        stats.strength = stats.strength.saturating_add(1);
+        stats.dexterity = stats.dexterity.saturating_add(1);
+        stats.intelligence = stats.intelligence.saturating_add(1);
+        stats.constitution = stats.constitution.saturating_add(1);
+    }
+}
+
+/// Practise a skill. Returns a line per rank gained.
+pub fn practice(player: &mut PlayerRecord, skill: &str, points: u32) -> Vec<String> {
+    let skill = skill.to_lowercase();
+    let progress = player.skills.entry(skill.clone()).or_default();
+    progress.practice = progress.practice.saturating_add(points);
+
+    let mut lines = Vec::new();
+    while progress.rank < MAX_SKILL_RANK && progress.practice >= practice_for_rank(progress.rank) {
+        progress.practice -= practice_for_rank(progress.rank);
+        progress.rank += 1;
+        lines.push(format!(
+            "📈 Your {} improves to rank {}.",
+            skill, progress.rank
+        ));
+    }
+    if progress.rank >= MAX_SKILL_RANK {
+        progress.practice = 0;
+    }
+    lines
+}
+
+/// Rank the player has earned in a skill
+pub fn skill_rank(player: &PlayerRecord, skill: &str) -> u32 {
+    player
+        .skills
+        .get(&skill.to_lowercase())
+        .map(|s| s.rank)
+        .unwrap_or(0)
+}
+
+/// Earned rank plus `SkillBoost` from companions in the player's room
+pub fn effective_skill(store: &TinyMushStore, player: &PlayerRecord, skill: &str) -> u32 {
+    let boost: u32 = store
+        .get_player_companions(&player.username)
+        .unwrap_or_default()
+        .iter()
+        .filter(|c| c.room_id == player.current_room)
+        .flat_map(|c| c.behaviors.iter())
+        .map(|b| match b {
+            CompanionBehavior::SkillBoost { skill: s, bonus } if s.eq_ignore_ascii_case(skill) => {
+                *bonus
+            }
+            _ => 0,
+        })
+        .sum();
+    skill_rank(player, skill) + boost
+}
+
+/// Mark a room as explored. The first visit earns [`EXPLORE_XP`] and a point
+/// of survival; returns any level or rank lines.
+pub fn record_visit(player: &mut PlayerRecord, room_id: &str) -> Vec<String> {
+    if !player.explored_rooms.insert(room_id.to_string()) {
+        return Vec::new();
+    }
+    let mut lines = grant_xp(player, EXPLORE_XP);
+    lines.extend(practice(player, "survival", 1));
+    lines
+}
+
+/// Load, grant XP and save. For callers that don't hold the player record.
+pub fn award_xp(
+    store: &TinyMushStore,
+    username: &str,
+    amount: u32,
+) -> Result<Vec<String>, TinyMushError> {
+    if amount == 0 {
+        return Ok(Vec::new());
+    }
+    let mut player = store.get_player(username)?;
+    let lines = grant_xp(&mut player, amount);
+    player.touch();
+    store.put_player(player)?;
+    Ok(lines)
+}
+
+/// Load, practise a skill and save
+pub fn award_practice(
+    store: &TinyMushStore,
+    username: &str,
+    skill: &str,
+    points: u32,
+) -> Result<Vec<String>, TinyMushError> {
+    let mut player = store.get_player(username)?;
+    let lines = practice(&mut player, skill, points);
+    player.touch();
+    store.put_player(player)?;
+    Ok(lines)
+}
+
+/// Level, XP and every skill the player has or could learn, for `SKILLS`
+pub fn format_skills(store: &TinyMushStore, player: &PlayerRecord) -> String {
+    let stats = &player.stats;
+    let mut response = if stats.level >= MAX_LEVEL {
+        format!("Level {} (max) - {} XP\n", stats.level, stats.experience)
+    } else {
+        format!(
+            "Level {} - {}/{} XP\n",
+            stats.level,
+            stats.experience,
+            xp_for_level(stats.level + 1)
+        )
+    };
+    response.push_str("Skills:\n");
+
+    let mut names: Vec<String> = SKILLS.iter().map(|(name, _)| name.to_string()).collect();
+    let mut extra: Vec<String> = player
+        .skills
+        .keys()
+        .filter(|k| !names.contains(k))
+        .cloned()
+        .collect();
+    extra.sort();
+    names.extend(extra);
+
+    for name in names {
+        let progress = player.skills.get(&name).cloned().unwrap_or_default();
+        let boost = effective_skill(store, player, &name) - progress.rank;
+        let boost = if boost > 0 {
+            format!(" +{}", boost)
+        } else {
+            String::new()
+        };
+        response.push_str(&format!(
+            "{} {}{} ({}/{})\n",
+            name,
+            progress.rank,
+            boost,
+            progress.practice,
+            practice_for_rank(progress.rank)
+        ));
+    }
+    response.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_follow_the_xp_curve() {
+        assert_eq!(xp_for_level(1), 0);
+        assert_eq!(xp_for_level(2), 100);
+        assert_eq!(xp_for_level(3), 300);
+
+        let mut player = PlayerRecord::new("alice", "Alice", "town_square");
+        assert!(grant_xp(&mut player, 99).is_empty());
+        let lines = grant_xp(&mut player, 201);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(player.stats.level, 3);
+        assert_eq!(player.stats.max_hp, 30);
+        assert_eq!(player.stats.hp, 30);
+        assert_eq!(player.stats.strength, 11);
+    }
+
+    #[test]
+    fn practice_raises_rank_and_carries_over() {
+        let mut player = PlayerRecord::new("alice", "Alice", "town_square");
+        assert!(practice(&mut player, "Lore", 9).is_empty());
+        let lines = practice(&mut player, "lore", 25);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(skill_rank(&player, "LORE"), 2);
+        assert_eq!(player.skills["lore"].practice, 4);
+    }
+
+    #[test]
+    fn only_the_first_visit_counts() {
+        let mut player = PlayerRecord::new("alice", "Alice", "town_square");
+        record_visit(&mut player, "town_square");
+        record_visit(&mut player, "town_square");
+        assert_eq!(player.stats.experience, EXPLORE_XP as u64);
+        assert_eq!(player.skills["survival"].practice, 1);
+    }
+}
@@ -869,6 +869,122 @@ pub fn seed_default_recipes() -> Vec<crate::tmush::types::CraftingRecipe> {
     recipes
 }
 
+/// Seed starter hostile mobs for the combat system
+///
+/// Mobs live in the dangerous underground areas; the surface rooms are flagged
+/// Safe. Loot tables reference crafting materials so victories feed into crafting.
+pub fn seed_starter_mobs() -> Vec<crate::tmush::types::MobRecord> {
+    use crate::tmush::types::{LootDrop, MobRecord, NpcCombatStats};
+
+    let mut mobs = Vec::new();
+
+    let tunnel_rat = MobRecord::new(
+        "tunnel_rat",
+        "Tunnel Rat",
+        "A mangy rat the size of a small dog, gnawing on a mesh cable.",
+        "maintenance_tunnels",
+        NpcCombatStats {
+            max_hp: 14,
+            attack: 2,
+            defense: 2,
+            damage_range: (4, 7),
+            loot_table: vec![LootDrop {
+                object_id: "copper_wire".to_string(),
+                chance: 0.5,
+                quantity: (1, 2),
+            }],
+        },
+    )
+    .with_enemy_type("rat")
+    .with_respawn_seconds(120);
+    mobs.push(tunnel_rat);
+
+    let cave_spider = MobRecord::new(
+        "cave_spider",
+        "Cave Spider",
+        "A pale spider with glittering eyes, nested between the cavern stones.",
+        "deep_caverns_entrance",
+        NpcCombatStats {
+            max_hp: 22,
+            attack: 4,
+            defense: 4,
+            damage_range: (5, 9),
+            loot_table: vec![LootDrop {
+                object_id: "crystal_shard".to_string(),
+                chance: 0.4,
+                quantity: (1, 1),
+            }],
+        },
+    )
+    .with_enemy_type("spider")
+    .with_respawn_seconds(300);
+    mobs.push(cave_spider);
+
+    let rogue_drone = MobRecord::new(
+        "rogue_drone",
+        "Rogue Drone",
+        "A battered survey drone, its firmware corrupted, buzzing angrily at intruders.",
+        "ruins_dark_passage",
+        NpcCombatStats {
+            max_hp: 30,
+            attack: 5,
+            defense: 8,
+            damage_range: (6, 11),
+            loot_table: vec![
+                LootDrop {
+                    object_id: "circuit_board".to_string(),
+                    chance: 0.6,
+                    quantity: (1, 1),
+                },
+                LootDrop {
+                    object_id: "power_cell".to_string(),
+                    chance: 0.3,
+                    quantity: (1, 1),
+                },
+            ],
+        },
+    )
+    .with_enemy_type("drone")
+    .with_respawn_seconds(600);
+    mobs.push(rogue_drone);
+
+    mobs
+}
+
+/// Seed starter spells for the magic system
+///
+/// Old Elm teaches the gentle spells and Old Graybeard the tower's spark; Recall
+/// only comes from a scroll and needs some practice with magic first.
+pub fn seed_starter_spells() -> Vec<crate::tmush::types::SpellRecord> {
+    use crate::tmush::types::{SpellRecord, SpellTarget};
+
+    vec![
+        SpellRecord::new("mend", "Mend", 5)
+            .with_description("Knit small wounds closed.")
+            .with_target(SpellTarget::Player)
+            .with_effect("heal(12)")
+            .with_cooldown(10)
+            .with_trainer("old_elm", 50),
+        SpellRecord::new("glow", "Glow", 2)
+            .with_description("Make something shine for a while.")
+            .with_target(SpellTarget::Object)
+            .with_effect("message_room(\"$object glows with a soft light.\")")
+            .with_trainer("old_elm", 20),
+        SpellRecord::new("spark", "Spark", 4)
+            .with_description("Throw a crackle of static at a foe.")
+            .with_target(SpellTarget::Mob)
+            .with_damage(4, 9)
+            .with_cooldown(5)
+            .with_trainer("old_graybeard", 75),
+        SpellRecord::new("recall", "Recall", 8)
+            .with_description("Return to the town square.")
+            .with_effect(&format!("teleport(\"{}\")", REQUIRED_START_LOCATION_ID))
+            .with_cooldown(300)
+            .with_min_rank(2)
+            .with_scroll("scroll_of_recall"),
+    ]
+}
+
 /// Seed starter NPCs for Old Towne Mesh
 pub fn seed_starter_npcs() -> Vec<crate::tmush::types::NpcRecord> {
     use crate::tmush::types::NpcRecord;
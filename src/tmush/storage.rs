@@ -6,14 +6,17 @@ use chrono::Utc;
 use sled::IVec;
 
 use crate::tmush::errors::TinyMushError;
+use crate::tmush::events::{RoomEvent, RoomEventBus, RoomEventKind};
 use crate::tmush::shop::ShopRecord;
 use crate::tmush::state::canonical_world_seed;
 use crate::tmush::types::{
-    BulletinBoard, BulletinMessage, CompanionRecord, CurrencyAmount, CurrencyTransaction,
-    HousingInstance, HousingTemplate, MailMessage, MailStatus, NpcRecord, ObjectOwner,
-    ObjectRecord, PlayerRecord, QuestRecord, RoomFlag, RoomOwner, RoomRecord, TradeSession,
-    TransactionReason, WorldConfig, BULLETIN_SCHEMA_VERSION, MAIL_SCHEMA_VERSION,
-    OBJECT_SCHEMA_VERSION, PLAYER_SCHEMA_VERSION, ROOM_SCHEMA_VERSION,
+    AuctionListing, BulletinBoard, BulletinMessage, CompanionRecord, CurrencyAmount,
+    CurrencyTransaction, DungeonInstance, DungeonTemplate, FactionRecord, GuildRecord,
+    HousingInstance, HousingTemplate, MailMessage, MailStatus, MobRecord, NpcRecord, ObjectOwner,
+    ObjectRecord, PlayerRecord, QuestRecord, RoomFlag, RoomOwner, RoomRecord, SpellRecord,
+    TradeSession, TransactionReason, WorldConfig, BULLETIN_SCHEMA_VERSION,
+    COMPANION_SCHEMA_VERSION, MAIL_SCHEMA_VERSION, OBJECT_SCHEMA_VERSION, PLAYER_SCHEMA_VERSION,
+    ROOM_SCHEMA_VERSION,
 };
 
 const TREE_PRIMARY: &str = "tinymush";
@@ -30,6 +33,14 @@ const TREE_COMPANIONS: &str = "tinymush_companions";
 const TREE_CONFIG: &str = "tinymush_config";
 const TREE_HOUSING_TEMPLATES: &str = "tinymush_housing_templates";
 const TREE_HOUSING_INSTANCES: &str = "tinymush_housing_instances";
+const TREE_MOBS: &str = "tinymush_mobs";
+const TREE_FACTIONS: &str = "tinymush_factions";
+const TREE_WORLD_EDITS: &str = "tinymush_world_edits";
+const TREE_DUNGEON_TEMPLATES: &str = "tinymush_dungeon_templates";
+const TREE_DUNGEON_INSTANCES: &str = "tinymush_dungeon_instances";
+const TREE_GUILDS: &str = "tinymush_guilds";
+const TREE_AUCTIONS: &str = "tinymush_auctions";
+const TREE_SPELLS: &str = "tinymush_spells";
 
 // Secondary indexes for O(1) lookups (performance optimization for scale)
 const TREE_OBJECT_INDEX: &str = "tinymush_object_index";
@@ -108,6 +119,14 @@ pub struct TinyMushStore {
     config: sled::Tree,
     housing_templates: sled::Tree,
     housing_instances: sled::Tree,
+    mobs: sled::Tree,
+    factions: sled::Tree,
+    world_edits: sled::Tree,
+    dungeon_templates: sled::Tree,
+    dungeon_instances: sled::Tree,
+    guilds: sled::Tree,
+    auctions: sled::Tree,
+    spells: sled::Tree,
 
     // Secondary indexes for O(1) lookups (performance optimization)
     object_index: sled::Tree,       // oid:{id} → full_key
@@ -121,6 +140,9 @@ pub struct TinyMushStore {
     // In-memory instanced room cache (not persisted across restarts)
     instanced_rooms: Arc<RwLock<HashMap<String, RoomRecord>>>,
     landing_instances: Arc<RwLock<HashMap<String, String>>>,
+
+    // Pending room activity for real-time fanout (shared by all clones)
+    room_events: RoomEventBus,
 }
 
 impl TinyMushStore {
@@ -142,6 +164,34 @@ impl TinyMushStore {
         &self.primary
     }
 
+    /// Shared room event bus used to fan out speech, emotes and arrivals to
+    /// other players. Drained by the BBS server.
+    pub fn room_events(&self) -> &RoomEventBus {
+        &self.room_events
+    }
+
+    /// Move `player` to `room_id` without walking an exit (teleports, dungeon
+    /// exits, respawns, evictions) and tell both rooms. The caller persists the
+    /// record.
+    pub fn relocate_player(&self, player: &mut PlayerRecord, room_id: &str) {
+        if player.current_room == room_id {
+            return;
+        }
+        let previous = std::mem::replace(&mut player.current_room, room_id.to_string());
+        self.room_events.publish(RoomEvent::new(
+            &previous,
+            &player.username,
+            RoomEventKind::Depart(None),
+            "",
+        ));
+        self.room_events.publish(RoomEvent::new(
+            room_id,
+            &player.username,
+            RoomEventKind::Arrive(None),
+            "",
+        ));
+    }
+
     /// Open (or create) the TinyMUSH store rooted at `path`. When `seed_world` is true the
     /// canonical "Old Towne Mesh" rooms are inserted if no world rooms exist yet.
     /// Uses "admin" as the default admin username.
@@ -179,6 +229,14 @@ impl TinyMushStore {
         let config = db.open_tree(TREE_CONFIG)?;
         let housing_templates = db.open_tree(TREE_HOUSING_TEMPLATES)?;
         let housing_instances = db.open_tree(TREE_HOUSING_INSTANCES)?;
+        let mobs = db.open_tree(TREE_MOBS)?;
+        let factions = db.open_tree(TREE_FACTIONS)?;
+        let world_edits = db.open_tree(TREE_WORLD_EDITS)?;
+        let dungeon_templates = db.open_tree(TREE_DUNGEON_TEMPLATES)?;
+        let dungeon_instances = db.open_tree(TREE_DUNGEON_INSTANCES)?;
+        let guilds = db.open_tree(TREE_GUILDS)?;
+        let auctions = db.open_tree(TREE_AUCTIONS)?;
+        let spells = db.open_tree(TREE_SPELLS)?;
 
         // Open secondary index trees
         let object_index = db.open_tree(TREE_OBJECT_INDEX)?;
@@ -202,6 +260,14 @@ impl TinyMushStore {
             config,
             housing_templates,
             housing_instances,
+            mobs,
+            factions,
+            world_edits,
+            dungeon_templates,
+            dungeon_instances,
+            guilds,
+            auctions,
+            spells,
             object_index,
             housing_guests,
             player_trades,
@@ -209,6 +275,7 @@ impl TinyMushStore {
             data_dir,
             instanced_rooms: Arc::new(RwLock::new(HashMap::new())),
             landing_instances: Arc::new(RwLock::new(HashMap::new())),
+            room_events: RoomEventBus::new(),
         };
 
         if seed_world {
@@ -218,6 +285,9 @@ impl TinyMushStore {
             store.seed_companions_if_needed()?;
             store.seed_recipes_if_needed()?;
             store.seed_npcs_if_needed()?;
+            store.seed_mobs_if_needed()?;
+            store.seed_spells_if_needed()?;
+            store.seed_factions_if_needed()?;
             store.seed_shops_if_needed()?;
 
             // Seed full dialogue trees for NPCs
@@ -343,6 +413,19 @@ impl TinyMushStore {
         Ok(ids)
     }
 
+    /// List usernames of all stored players whose current location is `room_id`.
+    pub fn list_players_in_room(&self, room_id: &str) -> Result<Vec<String>, TinyMushError> {
+        let mut players = Vec::new();
+        for username in self.list_player_ids()? {
+            if let Ok(player) = self.get_player(&username) {
+                if player.current_room == room_id {
+                    players.push(player.username);
+                }
+            }
+        }
+        Ok(players)
+    }
+
     // ============================================================================
     // Admin Helper Functions
     // ============================================================================
@@ -869,6 +952,629 @@ impl TinyMushStore {
         Ok(())
     }
 
+    // ============================================================================
+    // Mob Storage (Combat)
+    // ============================================================================
+
+    /// Store or update a hostile mob definition
+    pub fn put_mob(&self, mob: MobRecord) -> Result<(), TinyMushError> {
+        let key = format!("mobs:{}", mob.id).into_bytes();
+        let value = Self::serialize(&mob)?;
+        self.mobs.insert(key, value)?;
+        self.mobs.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve a mob by ID
+    pub fn get_mob(&self, mob_id: &str) -> Result<MobRecord, TinyMushError> {
+        let key = format!("mobs:{}", mob_id).into_bytes();
+        let bytes = self
+            .mobs
+            .get(key)?
+            .ok_or_else(|| TinyMushError::NotFound(format!("Mob not found: {}", mob_id)))?;
+        Self::deserialize(bytes)
+    }
+
+    /// List all mob IDs
+    pub fn list_mob_ids(&self) -> Result<Vec<String>, TinyMushError> {
+        let mut ids = Vec::new();
+        for item in self.mobs.scan_prefix(b"mobs:") {
+            let (key, _) = item?;
+            if let Ok(key_str) = std::str::from_utf8(&key) {
+                if let Some(id) = key_str.strip_prefix("mobs:") {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Get mobs currently present in a room (defeated mobs awaiting respawn are skipped)
+    pub fn get_mobs_in_room(&self, room_id: &str) -> Result<Vec<MobRecord>, TinyMushError> {
+        let now = Utc::now();
+        let mut mobs = Vec::new();
+        for item in self.mobs.scan_prefix(b"mobs:") {
+            let (_, value) = item?;
+            let mob: MobRecord = Self::deserialize(value)?;
+            if mob.room_id == room_id && mob.is_present(now) {
+                mobs.push(mob);
+            }
+        }
+        mobs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(mobs)
+    }
+
+    /// Delete a mob by ID
+    pub fn delete_mob(&self, mob_id: &str) -> Result<(), TinyMushError> {
+        let key = format!("mobs:{}", mob_id).into_bytes();
+        self.mobs.remove(key)?;
+        self.mobs.flush()?;
+        Ok(())
+    }
+
+    /// Seed hostile mobs if none exist
+    pub fn seed_mobs_if_needed(&self) -> Result<usize, TinyMushError> {
+        if self.mobs.scan_prefix(b"mobs:").next().is_some() {
+            return Ok(0);
+        }
+
+        // Load mobs from JSON seed file
+        let seed_path = std::path::Path::new(&self.data_dir).join("seeds/mobs.json");
+        let mobs = match crate::tmush::load_mobs_from_json(&seed_path) {
+            Ok(mobs) => mobs,
+            Err(e) => {
+                log::warn!(
+                    "Failed to load mobs from {}: {}. Falling back to hardcoded seeds.",
+                    seed_path.display(),
+                    e
+                );
+                // Fallback to hardcoded seeds if JSON file doesn't exist
+                crate::tmush::seed_starter_mobs()
+            }
+        };
+
+        let mut inserted = 0usize;
+        for mob in mobs {
+            self.put_mob(mob)?;
+            inserted += 1;
+        }
+        Ok(inserted)
+    }
+
+    // ============================================================================
+    // Spell Storage (Magic)
+    // ============================================================================
+
+    /// Store or update a spell definition
+    pub fn put_spell(&self, spell: SpellRecord) -> Result<(), TinyMushError> {
+        let key = format!("spells:{}", spell.id).into_bytes();
+        let value = Self::serialize(&spell)?;
+        self.spells.insert(key, value)?;
+        self.spells.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve a spell by ID
+    pub fn get_spell(&self, spell_id: &str) -> Result<SpellRecord, TinyMushError> {
+        let key = format!("spells:{}", spell_id).into_bytes();
+        let bytes = self
+            .spells
+            .get(key)?
+            .ok_or_else(|| TinyMushError::NotFound(format!("Spell not found: {}", spell_id)))?;
+        Self::deserialize(bytes)
+    }
+
+    /// All spell definitions, sorted by name
+    pub fn list_spells(&self) -> Result<Vec<SpellRecord>, TinyMushError> {
+        let mut spells = Vec::new();
+        for item in self.spells.scan_prefix(b"spells:") {
+            let (_, value) = item?;
+            spells.push(Self::deserialize::<SpellRecord>(value)?);
+        }
+        spells.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(spells)
+    }
+
+    /// Seed spells if none exist
+    pub fn seed_spells_if_needed(&self) -> Result<usize, TinyMushError> {
+        if self.spells.scan_prefix(b"spells:").next().is_some() {
+            return Ok(0);
+        }
+
+        let seed_path = std::path::Path::new(&self.data_dir).join("seeds/spells.json");
+        let spells = match crate::tmush::load_spells_from_json(&seed_path) {
+            Ok(spells) => spells,
+            Err(e) => {
+                log::warn!(
+                    "Failed to load spells from {}: {}. Falling back to hardcoded seeds.",
+                    seed_path.display(),
+                    e
+                );
+                crate::tmush::seed_starter_spells()
+            }
+        };
+
+        let mut inserted = 0usize;
+        for spell in spells {
+            self.put_spell(spell)?;
+            inserted += 1;
+        }
+        Ok(inserted)
+    }
+
+    // ============================================================================
+    // Dungeon Instances
+    // ============================================================================
+
+    /// Save a dungeon template
+    pub fn put_dungeon_template(&self, template: &DungeonTemplate) -> Result<(), TinyMushError> {
+        let key = format!("template:{}", template.id);
+        self.dungeon_templates
+            .insert(key.as_bytes(), Self::serialize(template)?)?;
+        self.dungeon_templates.flush()?;
+        Ok(())
+    }
+
+    /// Get a dungeon template by ID
+    pub fn get_dungeon_template(
+        &self,
+        template_id: &str,
+    ) -> Result<DungeonTemplate, TinyMushError> {
+        let key = format!("template:{}", template_id);
+        match self.dungeon_templates.get(key.as_bytes())? {
+            Some(data) => Self::deserialize(data),
+            None => Err(TinyMushError::NotFound(format!(
+                "Dungeon template not found: {}",
+                template_id
+            ))),
+        }
+    }
+
+    /// List all dungeon templates, sorted by ID
+    pub fn list_dungeon_templates(&self) -> Result<Vec<DungeonTemplate>, TinyMushError> {
+        let mut templates = Vec::new();
+        for item in self.dungeon_templates.scan_prefix(b"template:") {
+            let (_, value) = item?;
+            templates.push(Self::deserialize(value)?);
+        }
+        Ok(templates)
+    }
+
+    /// Template whose entry room is `room_id`, if any
+    pub fn dungeon_template_for_entry(
+        &self,
+        room_id: &str,
+    ) -> Result<Option<DungeonTemplate>, TinyMushError> {
+        Ok(self
+            .list_dungeon_templates()?
+            .into_iter()
+            .find(|t| t.entry_room == room_id))
+    }
+
+    /// Delete a dungeon template (running instances are left to expire)
+    pub fn delete_dungeon_template(&self, template_id: &str) -> Result<(), TinyMushError> {
+        let key = format!("template:{}", template_id);
+        self.dungeon_templates.remove(key.as_bytes())?;
+        self.dungeon_templates.flush()?;
+        Ok(())
+    }
+
+    /// Save a dungeon instance
+    pub fn put_dungeon_instance(&self, instance: &DungeonInstance) -> Result<(), TinyMushError> {
+        let key = format!("instance:{}", instance.id);
+        self.dungeon_instances
+            .insert(key.as_bytes(), Self::serialize(instance)?)?;
+        self.dungeon_instances.flush()?;
+        Ok(())
+    }
+
+    /// Get a dungeon instance by ID
+    pub fn get_dungeon_instance(
+        &self,
+        instance_id: &str,
+    ) -> Result<DungeonInstance, TinyMushError> {
+        let key = format!("instance:{}", instance_id);
+        match self.dungeon_instances.get(key.as_bytes())? {
+            Some(data) => Self::deserialize(data),
+            None => Err(TinyMushError::NotFound(format!(
+                "Dungeon instance not found: {}",
+                instance_id
+            ))),
+        }
+    }
+
+    /// List all running dungeon instances
+    pub fn list_dungeon_instances(&self) -> Result<Vec<DungeonInstance>, TinyMushError> {
+        let mut instances = Vec::new();
+        for item in self.dungeon_instances.scan_prefix(b"instance:") {
+            let (_, value) = item?;
+            instances.push(Self::deserialize(value)?);
+        }
+        Ok(instances)
+    }
+
+    /// Delete a dungeon instance record (its rooms are removed by the caller)
+    pub fn delete_dungeon_instance(&self, instance_id: &str) -> Result<(), TinyMushError> {
+        let key = format!("instance:{}", instance_id);
+        self.dungeon_instances.remove(key.as_bytes())?;
+        self.dungeon_instances.flush()?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // Guilds
+    // ============================================================================
+
+    /// Save a guild
+    pub fn put_guild(&self, guild: &GuildRecord) -> Result<(), TinyMushError> {
+        let key = format!("guild:{}", guild.id);
+        self.guilds
+            .insert(key.as_bytes(), Self::serialize(guild)?)?;
+        self.guilds.flush()?;
+        Ok(())
+    }
+
+    /// Get a guild by ID
+    pub fn get_guild(&self, guild_id: &str) -> Result<GuildRecord, TinyMushError> {
+        let key = format!("guild:{}", guild_id);
+        match self.guilds.get(key.as_bytes())? {
+            Some(data) => Self::deserialize(data),
+            None => Err(TinyMushError::NotFound(format!(
+                "Guild not found: {}",
+                guild_id
+            ))),
+        }
+    }
+
+    /// List all guilds, sorted by ID
+    pub fn list_guilds(&self) -> Result<Vec<GuildRecord>, TinyMushError> {
+        let mut guilds = Vec::new();
+        for item in self.guilds.scan_prefix(b"guild:") {
+            let (_, value) = item?;
+            guilds.push(Self::deserialize(value)?);
+        }
+        Ok(guilds)
+    }
+
+    /// Guild `username` belongs to, if any
+    pub fn guild_for_player(&self, username: &str) -> Result<Option<GuildRecord>, TinyMushError> {
+        Ok(self
+            .list_guilds()?
+            .into_iter()
+            .find(|g| g.member(username).is_some()))
+    }
+
+    /// Delete a guild
+    pub fn delete_guild(&self, guild_id: &str) -> Result<(), TinyMushError> {
+        let key = format!("guild:{}", guild_id);
+        self.guilds.remove(key.as_bytes())?;
+        self.guilds.flush()?;
+        Ok(())
+    }
+
+    /// Move currency from a member's pocket into the guild treasury
+    pub fn guild_deposit(
+        &self,
+        guild_id: &str,
+        username: &str,
+        amount: &CurrencyAmount,
+    ) -> Result<CurrencyTransaction, TinyMushError> {
+        if amount.is_zero_or_negative() {
+            return Err(TinyMushError::InvalidCurrency(
+                "Deposit amount must be positive".to_string(),
+            ));
+        }
+
+        let mut guild = self.get_guild(guild_id)?;
+        let mut player = self.get_player(username)?;
+        if !player.currency.can_afford(amount) {
+            return Err(TinyMushError::InsufficientFunds);
+        }
+
+        player.currency = player.currency.subtract(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency subtraction failed: {}", e))
+        })?;
+        guild.treasury = guild.treasury.add(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency addition failed: {}", e))
+        })?;
+
+        self.put_player(player)?;
+        self.put_guild(&guild)?;
+
+        let transaction = CurrencyTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            from: Some(username.to_string()),
+            to: Some(format!("guild:{}", guild_id)),
+            amount: amount.clone(),
+            reason: TransactionReason::BankDeposit,
+            rolled_back: false,
+        };
+
+        self.log_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    /// Move currency from the guild treasury into a member's pocket.
+    /// Rank checks are the caller's job.
+    pub fn guild_withdraw(
+        &self,
+        guild_id: &str,
+        username: &str,
+        amount: &CurrencyAmount,
+    ) -> Result<CurrencyTransaction, TinyMushError> {
+        if amount.is_zero_or_negative() {
+            return Err(TinyMushError::InvalidCurrency(
+                "Withdrawal amount must be positive".to_string(),
+            ));
+        }
+
+        let mut guild = self.get_guild(guild_id)?;
+        let mut player = self.get_player(username)?;
+        if !guild.treasury.can_afford(amount) {
+            return Err(TinyMushError::InsufficientFunds);
+        }
+
+        guild.treasury = guild.treasury.subtract(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency subtraction failed: {}", e))
+        })?;
+        player.currency = player.currency.add(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency addition failed: {}", e))
+        })?;
+
+        self.put_guild(&guild)?;
+        self.put_player(player)?;
+
+        let transaction = CurrencyTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            from: Some(format!("guild:{}", guild_id)),
+            to: Some(username.to_string()),
+            amount: amount.clone(),
+            reason: TransactionReason::BankWithdrawal,
+            rolled_back: false,
+        };
+
+        self.log_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    // ============================================================================
+    // Auction House
+    // ============================================================================
+
+    /// Save an auction listing
+    pub fn put_auction(&self, listing: &AuctionListing) -> Result<(), TinyMushError> {
+        let key = format!("auction:{:020}", listing.id);
+        self.auctions
+            .insert(key.as_bytes(), Self::serialize(listing)?)?;
+        self.auctions.flush()?;
+        Ok(())
+    }
+
+    /// Get an auction listing by ID
+    pub fn get_auction(&self, auction_id: u64) -> Result<AuctionListing, TinyMushError> {
+        let key = format!("auction:{:020}", auction_id);
+        match self.auctions.get(key.as_bytes())? {
+            Some(data) => Self::deserialize(data),
+            None => Err(TinyMushError::NotFound(format!(
+                "Auction not found: {}",
+                auction_id
+            ))),
+        }
+    }
+
+    /// List all open auction listings, oldest first
+    pub fn list_auctions(&self) -> Result<Vec<AuctionListing>, TinyMushError> {
+        let mut listings = Vec::new();
+        for item in self.auctions.scan_prefix(b"auction:") {
+            let (_, value) = item?;
+            listings.push(Self::deserialize(value)?);
+        }
+        Ok(listings)
+    }
+
+    /// Remove a settled, expired or cancelled listing
+    pub fn delete_auction(&self, auction_id: u64) -> Result<(), TinyMushError> {
+        let key = format!("auction:{:020}", auction_id);
+        self.auctions.remove(key.as_bytes())?;
+        self.auctions.flush()?;
+        Ok(())
+    }
+
+    /// Move currency from a player's pocket into an escrow account such as
+    /// `auction:12`. The caller keeps track of what the account holds.
+    pub fn escrow_currency(
+        &self,
+        username: &str,
+        account: &str,
+        amount: &CurrencyAmount,
+        reason: TransactionReason,
+    ) -> Result<CurrencyTransaction, TinyMushError> {
+        if amount.is_zero_or_negative() {
+            return Err(TinyMushError::InvalidCurrency(
+                "Escrow amount must be positive".to_string(),
+            ));
+        }
+
+        let mut player = self.get_player(username)?;
+        if !player.currency.can_afford(amount) {
+            return Err(TinyMushError::InsufficientFunds);
+        }
+        player.currency = player.currency.subtract(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency subtraction failed: {}", e))
+        })?;
+        self.put_player(player)?;
+
+        let transaction = CurrencyTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            from: Some(username.to_string()),
+            to: Some(account.to_string()),
+            amount: amount.clone(),
+            reason,
+            rolled_back: false,
+        };
+
+        self.log_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    /// Pay currency held in an escrow account out to a player's pocket
+    pub fn release_escrow(
+        &self,
+        account: &str,
+        username: &str,
+        amount: &CurrencyAmount,
+        reason: TransactionReason,
+    ) -> Result<CurrencyTransaction, TinyMushError> {
+        if amount.is_zero_or_negative() {
+            return Err(TinyMushError::InvalidCurrency(
+                "Escrow amount must be positive".to_string(),
+            ));
+        }
+
+        let mut player = self.get_player(username)?;
+        player.currency = player.currency.add(amount).map_err(|e| {
+            TinyMushError::InvalidCurrency(format!("Currency addition failed: {}", e))
+        })?;
+        self.put_player(player)?;
+
+        let transaction = CurrencyTransaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            from: Some(account.to_string()),
+            to: Some(username.to_string()),
+            amount: amount.clone(),
+            reason,
+            rolled_back: false,
+        };
+
+        self.log_transaction(&transaction)?;
+        Ok(transaction)
+    }
+
+    // ============================================================================
+    // Builder Edit Log
+    // ============================================================================
+
+    /// Look up a room under any owner, including player-created rooms that
+    /// `get_room` does not see
+    pub fn find_room_any_owner(&self, room_id: &str) -> Result<Option<RoomRecord>, TinyMushError> {
+        match self.get_room(room_id) {
+            Ok(room) => return Ok(Some(room)),
+            Err(TinyMushError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        let suffix = format!(":{}", room_id);
+        for kv in self.primary.scan_prefix(b"rooms:player:") {
+            let (key, value) = kv?;
+            if key.ends_with(suffix.as_bytes()) {
+                let room: RoomRecord = Self::deserialize(value)?;
+                if room.id == room_id {
+                    return Ok(Some(room));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Remove a room record under its owner key. Exits pointing at it and
+    /// occupants are left alone.
+    pub fn remove_room_record(&self, room: &RoomRecord) -> Result<(), TinyMushError> {
+        if crate::tmush::state::is_personal_landing(&room.id) {
+            self.instanced_rooms.write().unwrap().remove(&room.id);
+            return Ok(());
+        }
+        self.primary.remove(Self::room_key(room))?;
+        self.primary.flush()?;
+        Ok(())
+    }
+
+    /// Remove an object record and its index entry. Room item lists and
+    /// inventories are left alone.
+    pub fn remove_object_record(&self, object: &ObjectRecord) -> Result<(), TinyMushError> {
+        self.objects.remove(Self::object_key(object))?;
+        let index_key = format!("oid:{}", object.id);
+        self.object_index.remove(index_key.as_bytes())?;
+        self.objects.flush()?;
+        Ok(())
+    }
+
+    /// Insert or update a builder edit, assigning its id on first save
+    pub fn put_world_edit(
+        &self,
+        edit: &mut crate::tmush::edit_log::WorldEdit,
+    ) -> Result<(), TinyMushError> {
+        if edit.id == 0 {
+            edit.id = self._db.generate_id()? + 1;
+        }
+        let key = format!("edit:{:020}", edit.id).into_bytes();
+        self.world_edits.insert(key, Self::serialize(edit)?)?;
+        self.world_edits.flush()?;
+        Ok(())
+    }
+
+    /// All builder edits, newest first
+    pub fn list_world_edits(
+        &self,
+    ) -> Result<Vec<crate::tmush::edit_log::WorldEdit>, TinyMushError> {
+        let mut edits = Vec::new();
+        for kv in self.world_edits.scan_prefix(b"edit:").rev() {
+            let (_, value) = kv?;
+            edits.push(Self::deserialize(value)?);
+        }
+        Ok(edits)
+    }
+
+    // ============================================================================
+    // Faction Storage (Reputation)
+    // ============================================================================
+
+    /// Store or update a faction definition
+    pub fn put_faction(&self, faction: FactionRecord) -> Result<(), TinyMushError> {
+        let key = format!("factions:{}", faction.id).into_bytes();
+        let value = Self::serialize(&faction)?;
+        self.factions.insert(key, value)?;
+        self.factions.flush()?;
+        Ok(())
+    }
+
+    /// Retrieve a faction by ID
+    pub fn get_faction(&self, faction_id: &str) -> Result<FactionRecord, TinyMushError> {
+        let key = format!("factions:{}", faction_id).into_bytes();
+        let bytes = self
+            .factions
+            .get(key)?
+            .ok_or_else(|| TinyMushError::NotFound(format!("Faction not found: {}", faction_id)))?;
+        Self::deserialize(bytes)
+    }
+
+    /// List all factions, sorted by ID
+    pub fn list_factions(&self) -> Result<Vec<FactionRecord>, TinyMushError> {
+        let mut factions = Vec::new();
+        for item in self.factions.scan_prefix(b"factions:") {
+            let (_, value) = item?;
+            factions.push(Self::deserialize::<FactionRecord>(value)?);
+        }
+        factions.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(factions)
+    }
+
+    /// Seed the starter factions if none exist
+    pub fn seed_factions_if_needed(&self) -> Result<usize, TinyMushError> {
+        if self.factions.scan_prefix(b"factions:").next().is_some() {
+            return Ok(0);
+        }
+
+        let mut inserted = 0usize;
+        for faction in crate::tmush::state::create_factions() {
+            self.put_faction(faction)?;
+            inserted += 1;
+        }
+        Ok(inserted)
+    }
+
     // ============================================================================
     // NPC Storage
     // ============================================================================
@@ -2561,7 +3267,7 @@ impl TinyMushStore {
 
     /// Store or update a companion
     pub fn put_companion(&self, mut companion: CompanionRecord) -> Result<(), TinyMushError> {
-        companion.schema_version = 1;
+        companion.schema_version = COMPANION_SCHEMA_VERSION;
         let key = format!("companion:{}", companion.id).into_bytes();
         let bytes = Self::serialize(&companion)?;
         self.companions.insert(key, bytes)?;
@@ -2576,7 +3282,31 @@ impl TinyMushStore {
             .companions
             .get(key.as_bytes())?
             .ok_or_else(|| TinyMushError::NotFound(format!("Companion {}", companion_id)))?;
-        Self::deserialize(bytes)
+        self.load_companion(companion_id, &bytes)
+    }
+
+    /// Decode a stored companion, saving it back if it was migrated
+    fn load_companion(
+        &self,
+        companion_id: &str,
+        bytes: &[u8],
+    ) -> Result<CompanionRecord, TinyMushError> {
+        use crate::tmush::migration::load_and_migrate;
+        let (companion, was_migrated): (CompanionRecord, bool) =
+            load_and_migrate(bytes, companion_id).map_err(|e| {
+                TinyMushError::Bincode(bincode::Error::from(bincode::ErrorKind::Custom(format!(
+                    "Failed to load companion: {}",
+                    e
+                ))))
+            })?;
+        if was_migrated {
+            log::info!(
+                "Auto-migrated CompanionRecord '{}', saving updated version",
+                companion_id
+            );
+            self.put_companion(companion.clone())?;
+        }
+        Ok(companion)
     }
 
     /// List all companion IDs
@@ -2601,8 +3331,12 @@ impl TinyMushStore {
     ) -> Result<Vec<CompanionRecord>, TinyMushError> {
         let mut companions = Vec::new();
         for kv in self.companions.iter() {
-            let (_key, value) = kv?;
-            let companion: CompanionRecord = Self::deserialize(value)?;
+            let (key, value) = kv?;
+            let id = std::str::from_utf8(&key)
+                .ok()
+                .and_then(|s| s.strip_prefix("companion:"))
+                .unwrap_or("unknown");
+            let companion = self.load_companion(id, &value)?;
             if companion.room_id == room_id {
                 companions.push(companion);
             }
@@ -2617,8 +3351,12 @@ impl TinyMushStore {
     ) -> Result<Vec<CompanionRecord>, TinyMushError> {
         let mut companions = Vec::new();
         for kv in self.companions.iter() {
-            let (_key, value) = kv?;
-            let companion: CompanionRecord = Self::deserialize(value)?;
+            let (key, value) = kv?;
+            let id = std::str::from_utf8(&key)
+                .ok()
+                .and_then(|s| s.strip_prefix("companion:"))
+                .unwrap_or("unknown");
+            let companion = self.load_companion(id, &value)?;
             if companion.owner.as_deref() == Some(username) {
                 companions.push(companion);
             }
@@ -2633,8 +3371,12 @@ impl TinyMushStore {
     ) -> Result<Vec<CompanionRecord>, TinyMushError> {
         let mut companions = Vec::new();
         for kv in self.companions.iter() {
-            let (_key, value) = kv?;
-            let companion: CompanionRecord = Self::deserialize(value)?;
+            let (key, value) = kv?;
+            let id = std::str::from_utf8(&key)
+                .ok()
+                .and_then(|s| s.strip_prefix("companion:"))
+                .unwrap_or("unknown");
+            let companion = self.load_companion(id, &value)?;
             if companion.room_id == room_id && companion.owner.is_none() {
                 companions.push(companion);
             }
@@ -2913,7 +3655,18 @@ impl TinyMushStore {
     pub fn get_world_config(&self) -> Result<WorldConfig, TinyMushError> {
         let key = b"world_config";
         match self.config.get(key)? {
-            Some(value) => Ok(Self::deserialize(value)?),
+            Some(value) => {
+                use crate::tmush::migration::load_world_config;
+                let (config, migrated) = load_world_config(&value).map_err(|e| {
+                    TinyMushError::Bincode(bincode::Error::from(bincode::ErrorKind::Custom(
+                        e.to_string(),
+                    )))
+                })?;
+                if migrated {
+                    self.put_world_config(&config)?;
+                }
+                Ok(config)
+            }
             None => {
                 // Initialize with default if not found
                 let default = WorldConfig::default();
@@ -3700,6 +4453,20 @@ impl TinyMushStore {
             "err_movement_failed" => config.err_movement_failed = value.to_string(),
             "err_movement_save_failed" => config.err_movement_save_failed = value.to_string(),
 
+            // Auction house
+            "auction_listing_fee" => {
+                config.auction_listing_fee = value
+                    .parse()
+                    .ok()
+                    .filter(|fee: &i64| *fee >= 0)
+                    .ok_or_else(|| {
+                        TinyMushError::NotFound(format!(
+                            "Invalid number for auction_listing_fee: {}",
+                            value
+                        ))
+                    })?;
+            }
+
             _ => {
                 return Err(TinyMushError::NotFound(format!(
                     "Unknown config field: {}",
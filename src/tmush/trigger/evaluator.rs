@@ -4,6 +4,8 @@
 /// Executes actions and evaluates conditions against game state.
 use super::{AstNode, BinaryOperator, TriggerContext};
 use super::{MAX_ACTIONS_PER_TRIGGER, MAX_MESSAGES_PER_TRIGGER};
+use crate::tmush::events::{RoomEvent, RoomEventKind};
+use crate::tmush::skills;
 use crate::tmush::storage::TinyMushStore;
 
 /// Value type for evaluation results
@@ -204,6 +206,8 @@ impl<'a> Evaluator<'a> {
             "room_flag" => self.condition_room_flag(args),
             "current_room" => Ok(Value::String(self.context.room_id.clone())),
             "random_chance" => self.condition_random_chance(args),
+            "level" => self.condition_level(args),
+            "skill" => self.condition_skill(args),
 
             // Actions that modify game state (will implement in Phase 4)
             "teleport" => self.action_teleport(args),
@@ -212,6 +216,7 @@ impl<'a> Evaluator<'a> {
             "heal" => self.action_heal(args),
             "unlock_exit" => self.action_unlock_exit(args),
             "lock_exit" => self.action_lock_exit(args),
+            "practice" => self.action_practice(args),
 
             _ => Err(format!("Unknown action: {}", name)),
         }
@@ -258,6 +263,13 @@ impl<'a> Evaluator<'a> {
         let text = self.evaluate(&args[0])?;
         let msg = format!("🔊 {}", self.substitute_variables(&text.as_string()));
 
+        // The triggering player sees it in their reply; everyone else via the room bus
+        self.store.room_events().publish(RoomEvent::new(
+            &self.context.room_id,
+            &self.context.player_username,
+            RoomEventKind::Trigger,
+            &msg,
+        ));
         self.messages.push(msg);
         self.context.increment_message();
 
@@ -309,6 +321,34 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Condition: Player's level as a number
+    fn condition_level(&mut self, args: &[AstNode]) -> Result<Value, String> {
+        if !args.is_empty() {
+            return Err(format!("level() expects 0 arguments, got {}", args.len()));
+        }
+
+        match self.store.get_player(&self.context.player_username) {
+            Ok(player) => Ok(Value::Number(player.stats.level as i64)),
+            Err(_) => Ok(Value::Number(0)),
+        }
+    }
+
+    /// Condition: Player's rank in a skill, companion boosts included
+    fn condition_skill(&mut self, args: &[AstNode]) -> Result<Value, String> {
+        if args.len() != 1 {
+            return Err(format!("skill() expects 1 argument, got {}", args.len()));
+        }
+
+        let skill = self.evaluate(&args[0])?.as_string();
+
+        match self.store.get_player(&self.context.player_username) {
+            Ok(player) => Ok(Value::Number(
+                skills::effective_skill(self.store, &player, &skill) as i64,
+            )),
+            Err(_) => Ok(Value::Number(0)),
+        }
+    }
+
     /// Condition: Check if object/room has flag
     fn condition_flag_set(&mut self, args: &[AstNode]) -> Result<Value, String> {
         if args.len() != 1 {
@@ -393,7 +433,7 @@ impl<'a> Evaluator<'a> {
         // Update player's current room
         match self.store.get_player(&self.context.player_username) {
             Ok(mut player) => {
-                player.current_room = room_id.clone();
+                self.store.relocate_player(&mut player, &room_id);
                 player.updated_at = chrono::Utc::now();
 
                 if let Err(e) = self.store.put_player(player) {
@@ -500,10 +540,18 @@ impl<'a> Evaluator<'a> {
         }
         let amount = self.evaluate(&args[0])?;
 
-        // Note: Combat system with HP doesn't exist yet
-        // For now, just acknowledge the heal with a message
         match amount {
             Value::Number(n) if n > 0 => {
+                // Restore HP (capped at max) when the player record is available
+                if let Ok(mut player) = self.store.get_player(&self.context.player_username) {
+                    let amount = n.min(u32::MAX as i64) as u32;
+                    let healed = player.stats.hp.saturating_add(amount);
+                    player.stats.hp = healed.min(player.stats.max_hp);
+                    player.updated_at = chrono::Utc::now();
+                    if let Err(e) = self.store.put_player(player) {
+                        return Err(format!("Failed to heal: {}", e));
+                    }
+                }
                 self.messages.push(format!("💚 Healed for {} HP!", n));
                 Ok(Value::Boolean(true))
             }
@@ -511,6 +559,23 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Action: Add a practice point to a skill (e.g. after a failed lockpick)
+    fn action_practice(&mut self, args: &[AstNode]) -> Result<Value, String> {
+        if args.len() != 1 {
+            return Err(format!("practice() expects 1 argument, got {}", args.len()));
+        }
+
+        let skill = self.evaluate(&args[0])?.as_string();
+
+        match skills::award_practice(self.store, &self.context.player_username, &skill, 1) {
+            Ok(lines) => {
+                self.messages.extend(lines);
+                Ok(Value::Boolean(true))
+            }
+            Err(e) => Err(format!("Failed to practice {}: {}", skill, e)),
+        }
+    }
+
     fn action_unlock_exit(&mut self, args: &[AstNode]) -> Result<Value, String> {
         if args.len() != 1 {
             return Err(format!(
@@ -951,4 +1016,28 @@ mod tests {
         assert!(evaluator.messages().len() > 0);
         assert!(evaluator.messages()[0].contains("Healed for 50 HP"));
     }
+
+    #[test]
+    fn test_skill_checks_and_practice() {
+        let (_temp, store, mut context) = create_test_setup();
+        let mut player = PlayerRecord::new("test_player", "Test Player", "test_room");
+        player.stats.level = 4;
+        crate::tmush::skills::practice(&mut player, "lockpicking", 9);
+        store.put_player(player).unwrap();
+
+        // The failed attempt is the tenth point of practice, the next one works
+        let script = r#"skill("lockpicking") >= 1 ? message("Click.") : practice("lockpicking")"#;
+        let ast = crate::tmush::trigger::parser::parse_script(script).unwrap();
+        let mut evaluator = Evaluator::new(&mut context, &store);
+        evaluator.evaluate(&ast).unwrap();
+        assert_eq!(
+            evaluator.messages(),
+            ["📈 Your lockpicking improves to rank 1."]
+        );
+        evaluator.evaluate(&ast).unwrap();
+        assert_eq!(evaluator.messages()[1], "Click.");
+
+        let level = crate::tmush::trigger::parser::parse_script("level() > 3").unwrap();
+        assert_eq!(evaluator.evaluate(&level).unwrap(), Value::Boolean(true));
+    }
 }
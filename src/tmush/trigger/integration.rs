@@ -310,6 +310,180 @@ pub fn execute_on_poke(
     }
 }
 
+/// Execute OnCombat trigger when a player carrying the object enters combat
+///
+/// # Arguments
+/// * `object` - The carried object
+/// * `player_username` - Username of the player fighting
+/// * `room_id` - Room where the fight takes place
+/// * `store` - Storage reference
+///
+/// # Returns
+/// Vec of messages to display to the player
+pub fn execute_on_combat(
+    object: &ObjectRecord,
+    player_username: &str,
+    room_id: &str,
+    store: &TinyMushStore,
+) -> Vec<String> {
+    let script = match object.actions.get(&ObjectTrigger::OnCombat) {
+        Some(s) => s,
+        None => return vec![],
+    };
+
+    let player = match store.get_player(player_username) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!(
+                "execute_on_combat: Failed to get player {}: {}",
+                player_username, e
+            );
+            return vec![];
+        }
+    };
+
+    let room = match store.get_room(room_id) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("execute_on_combat: Failed to get room {}: {}", room_id, e);
+            return vec![];
+        }
+    };
+
+    let mut context = TriggerContext::new(&player, object, &room);
+
+    match execute_trigger(ObjectTrigger::OnCombat, script, &mut context, store) {
+        Ok(TriggerResult::Success(messages)) => messages,
+        Ok(TriggerResult::NoScript) => vec![],
+        Ok(TriggerResult::Skipped) => vec![],
+        Ok(TriggerResult::RateLimited) => vec![],
+        Ok(TriggerResult::Failed(_)) => vec![],
+        Ok(TriggerResult::TimedOut) => vec![],
+        Err(e) => {
+            error!("execute_on_combat: Trigger execution failed: {}", e);
+            vec![]
+        }
+    }
+}
+
+/// Execute OnHeal trigger when a player carrying the object is healed by a spell
+///
+/// # Arguments
+/// * `object` - The carried object
+/// * `player_username` - Username of the player who was healed
+/// * `room_id` - Room the player is in
+/// * `store` - Storage reference
+///
+/// # Returns
+/// Vec of messages to display to the player
+pub fn execute_on_heal(
+    object: &ObjectRecord,
+    player_username: &str,
+    room_id: &str,
+    store: &TinyMushStore,
+) -> Vec<String> {
+    let script = match object.actions.get(&ObjectTrigger::OnHeal) {
+        Some(s) => s,
+        None => return vec![],
+    };
+
+    let player = match store.get_player(player_username) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!(
+                "execute_on_heal: Failed to get player {}: {}",
+                player_username, e
+            );
+            return vec![];
+        }
+    };
+
+    let room = match store.get_room(room_id) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("execute_on_heal: Failed to get room {}: {}", room_id, e);
+            return vec![];
+        }
+    };
+
+    let mut context = TriggerContext::new(&player, object, &room);
+
+    match execute_trigger(ObjectTrigger::OnHeal, script, &mut context, store) {
+        Ok(TriggerResult::Success(messages)) => messages,
+        Ok(TriggerResult::NoScript) => vec![],
+        Ok(TriggerResult::Skipped) => vec![],
+        Ok(TriggerResult::RateLimited) => vec![],
+        Ok(TriggerResult::Failed(_)) => vec![],
+        Ok(TriggerResult::TimedOut) => vec![],
+        Err(e) => {
+            error!("execute_on_heal: Trigger execution failed: {}", e);
+            vec![]
+        }
+    }
+}
+
+/// Execute OnIdle trigger for an object in an occupied room (world tick)
+///
+/// Nobody caused an idle trigger, so `player_username` is simply one of the
+/// players present. Rate limiting is keyed on the object alone so several idle
+/// objects in one room can all fire on the same tick.
+///
+/// # Returns
+/// Vec of messages to show everyone in the room
+pub fn execute_on_idle(
+    object: &ObjectRecord,
+    player_username: &str,
+    room_id: &str,
+    store: &TinyMushStore,
+) -> Vec<String> {
+    let script = match object.actions.get(&ObjectTrigger::OnIdle) {
+        Some(s) => s,
+        None => return vec![],
+    };
+
+    if let Err(reason) = check_trigger_allowed(&object.id, &object.id) {
+        warn!("execute_on_idle: Rate limited - {}", reason);
+        return vec![];
+    }
+
+    let player = match store.get_player(player_username) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!(
+                "execute_on_idle: Failed to get player {}: {}",
+                player_username, e
+            );
+            return vec![];
+        }
+    };
+
+    let room = match store.get_room(room_id) {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("execute_on_idle: Failed to get room {}: {}", room_id, e);
+            return vec![];
+        }
+    };
+
+    let mut context = TriggerContext::new(&player, object, &room);
+
+    match execute_trigger(ObjectTrigger::OnIdle, script, &mut context, store) {
+        Ok(TriggerResult::Success(messages)) => {
+            record_trigger_execution(&object.id, &object.id);
+            messages
+        }
+        Ok(TriggerResult::NoScript) => vec![],
+        Ok(TriggerResult::Skipped) => vec![],
+        Ok(TriggerResult::RateLimited) => vec![],
+        Ok(TriggerResult::Failed(_)) => vec![],
+        Ok(TriggerResult::TimedOut) => vec![],
+        Err(e) => {
+            error!("execute_on_idle: Trigger execution failed: {}", e);
+            vec![]
+        }
+    }
+}
+
 /// Execute all OnEnter triggers for objects in a room
 ///
 /// This fires when a player enters a room, checking all objects in the room
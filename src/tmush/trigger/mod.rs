@@ -34,8 +34,8 @@ pub use admin::{
 };
 pub use evaluator::{Evaluator, Value};
 pub use integration::{
-    execute_on_drop, execute_on_look, execute_on_poke, execute_on_take, execute_on_use,
-    execute_room_on_enter,
+    execute_on_combat, execute_on_drop, execute_on_heal, execute_on_idle, execute_on_look,
+    execute_on_poke, execute_on_take, execute_on_use, execute_room_on_enter,
 };
 pub use parser::{parse_script, AstNode, BinaryOperator};
 pub use rate_limit::{RateLimitReason, RateLimitStats, TriggerRateLimiter};
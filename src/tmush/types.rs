@@ -1,16 +1,18 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // Schema versions are now managed in migration.rs
 // These constants remain for backward compatibility but should reference migration constants
 pub use crate::tmush::migration::{
     CURRENT_ACHIEVEMENT_SCHEMA_VERSION as ACHIEVEMENT_SCHEMA_VERSION,
+    CURRENT_COMPANION_SCHEMA_VERSION as COMPANION_SCHEMA_VERSION,
     CURRENT_NPC_SCHEMA_VERSION as NPC_SCHEMA_VERSION,
     CURRENT_OBJECT_SCHEMA_VERSION as OBJECT_SCHEMA_VERSION,
     CURRENT_PLAYER_SCHEMA_VERSION as PLAYER_SCHEMA_VERSION,
     CURRENT_QUEST_SCHEMA_VERSION as QUEST_SCHEMA_VERSION,
     CURRENT_ROOM_SCHEMA_VERSION as ROOM_SCHEMA_VERSION,
+    CURRENT_WORLD_CONFIG_VERSION as WORLD_CONFIG_VERSION,
 };
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -314,6 +316,9 @@ pub struct NpcRecord {
     pub dialog_tree: HashMap<String, DialogNode>, // Advanced branching dialogue
     #[serde(default)]
     pub flags: Vec<NpcFlag>,
+    /// Rooms visited in order, one step per world tick (empty = stays put)
+    #[serde(default)]
+    pub patrol: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub schema_version: u8,
 }
@@ -329,6 +334,7 @@ impl NpcRecord {
             dialog: HashMap::new(),
             dialog_tree: HashMap::new(),
             flags: Vec::new(),
+            patrol: Vec::new(),
             created_at: Utc::now(),
             schema_version: NPC_SCHEMA_VERSION,
         }
@@ -347,6 +353,222 @@ impl NpcRecord {
     }
 }
 
+// ============================================================================
+// Combat System (Hostile Mobs)
+// ============================================================================
+
+pub const MOB_SCHEMA_VERSION: u8 = 1;
+
+/// Item dropped by a defeated mob
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LootDrop {
+    /// Object ID granted to the victor
+    pub object_id: String,
+    /// Drop probability (0.0-1.0)
+    pub chance: f32,
+    /// Quantity range (min, max)
+    #[serde(default = "default_loot_quantity")]
+    pub quantity: (u32, u32),
+}
+
+fn default_loot_quantity() -> (u32, u32) {
+    (1, 1)
+}
+
+/// Combat statistics for a hostile creature
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NpcCombatStats {
+    pub max_hp: u32,
+    /// Added to a d20 roll when attacking; hits if the total reaches the player's armor class
+    pub attack: u8,
+    /// Subtracted (halved) from incoming player damage
+    pub defense: u8,
+    /// Damage dealt on a hit (min, max)
+    pub damage_range: (u8, u8),
+    #[serde(default)]
+    pub loot_table: Vec<LootDrop>,
+}
+
+/// Hostile creature that players can fight with ATTACK
+///
+/// Mobs are kept separate from conversational NPCs: each player fights their
+/// own copy (HP lives in `CombatState`), and the first player to defeat a mob
+/// removes it from the room until `respawn_seconds` have passed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MobRecord {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub room_id: String,
+    /// Matched against `ObjectiveType::KillEnemy::enemy_type` (falls back to `id`)
+    pub enemy_type: String,
+    pub combat: NpcCombatStats,
+    pub respawn_seconds: u64,
+    /// Set when defeated; the mob is absent from its room until this time
+    #[serde(default)]
+    pub defeated_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub schema_version: u8,
+}
+
+impl MobRecord {
+    pub fn new(id: &str, name: &str, description: &str, room_id: &str, combat: NpcCombatStats) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            room_id: room_id.to_string(),
+            enemy_type: id.to_string(),
+            combat,
+            respawn_seconds: 300,
+            defeated_until: None,
+            created_at: Utc::now(),
+            schema_version: MOB_SCHEMA_VERSION,
+        }
+    }
+
+    pub fn with_enemy_type(mut self, enemy_type: &str) -> Self {
+        self.enemy_type = enemy_type.to_string();
+        self
+    }
+
+    pub fn with_respawn_seconds(mut self, seconds: u64) -> Self {
+        self.respawn_seconds = seconds;
+        self
+    }
+
+    /// Whether the mob is present in its room (not awaiting respawn)
+    pub fn is_present(&self, now: DateTime<Utc>) -> bool {
+        match self.defeated_until {
+            Some(until) => now >= until,
+            None => true,
+        }
+    }
+}
+
+// ============================================================================
+// Magic (Spells)
+// ============================================================================
+
+pub const SPELL_SCHEMA_VERSION: u8 = 1;
+
+fn default_spell_schema_version() -> u8 {
+    SPELL_SCHEMA_VERSION
+}
+
+/// What a spell can be cast on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SpellTarget {
+    /// Always the caster
+    #[default]
+    Caster,
+    /// The caster or another player in the same room (defaults to the caster)
+    Player,
+    /// A hostile mob in the room, or the one already being fought
+    Mob,
+    /// An object in the room or inventory, found by name
+    Object,
+}
+
+/// Spell definition, seeded from `data/seeds/spells.json`
+///
+/// `effect` is a trigger DSL script run as if the target player had set it
+/// off, so `heal(10)` heals the target and `teleport("town_square")` moves
+/// them. Mob spells deal `damage` instead and take the caster's combat turn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpellRecord {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub mp_cost: u32,
+    #[serde(default)]
+    pub cooldown_seconds: u64,
+    #[serde(default)]
+    pub target: SpellTarget,
+    #[serde(default)]
+    pub effect: String,
+    /// Damage dealt to a mob target (min, max)
+    #[serde(default)]
+    pub damage: Option<(u32, u32)>,
+    /// Magic skill rank needed to learn and cast
+    #[serde(default)]
+    pub min_rank: u32,
+    /// Object ID of a scroll that teaches the spell (consumed by LEARN)
+    #[serde(default)]
+    pub scroll: Option<String>,
+    /// NPC IDs that teach the spell for `price`
+    #[serde(default)]
+    pub trainers: Vec<String>,
+    /// Trainer fee in base currency units
+    #[serde(default)]
+    pub price: i64,
+    #[serde(default = "default_spell_schema_version")]
+    pub schema_version: u8,
+}
+
+impl SpellRecord {
+    pub fn new(id: &str, name: &str, mp_cost: u32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            mp_cost,
+            cooldown_seconds: 0,
+            target: SpellTarget::Caster,
+            effect: String::new(),
+            damage: None,
+            min_rank: 0,
+            scroll: None,
+            trainers: Vec::new(),
+            price: 0,
+            schema_version: SPELL_SCHEMA_VERSION,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_target(mut self, target: SpellTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_effect(mut self, effect: &str) -> Self {
+        self.effect = effect.to_string();
+        self
+    }
+
+    pub fn with_damage(mut self, min: u32, max: u32) -> Self {
+        self.damage = Some((min, max));
+        self
+    }
+
+    pub fn with_cooldown(mut self, seconds: u64) -> Self {
+        self.cooldown_seconds = seconds;
+        self
+    }
+
+    pub fn with_min_rank(mut self, rank: u32) -> Self {
+        self.min_rank = rank;
+        self
+    }
+
+    pub fn with_scroll(mut self, object_id: &str) -> Self {
+        self.scroll = Some(object_id.to_string());
+        self
+    }
+
+    pub fn with_trainer(mut self, npc_id: &str, price: i64) -> Self {
+        self.trainers.push(npc_id.to_string());
+        self.price = price;
+        self
+    }
+}
+
 /// Conversation state tracking for player-NPC interactions (Phase 8.5)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ConversationState {
@@ -454,6 +676,10 @@ pub enum DialogCondition {
     HasAchievement { achievement_id: String },
     /// Always true (for default fallback)
     Always,
+    /// Check player has at least `points` reputation with a faction
+    MinReputation { faction: String, points: i32 },
+    /// Check player's rank in a named skill, companion boosts included
+    MinSkill { skill: String, rank: u32 },
 }
 
 /// Action to execute when dialogue node is reached (Phase 8.5)
@@ -479,6 +705,8 @@ pub enum DialogAction {
     Teleport { room_id: String },
     /// Send a system message to the player
     SendMessage { text: String },
+    /// Raise (or lower, if negative) the player's reputation with a faction
+    AdjustReputation { faction: String, amount: i32 },
 }
 
 /// Choice in a dialogue tree
@@ -707,6 +935,9 @@ pub struct CompanionRecord {
     /// Whether player is currently mounted (for horses)
     #[serde(default)]
     pub is_mounted: bool,
+    /// Last time a `Healing` behavior healed the owner
+    #[serde(default)]
+    pub last_healed: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub schema_version: u8,
 }
@@ -726,8 +957,9 @@ impl CompanionRecord {
             behaviors: Self::default_behaviors(companion_type),
             inventory: Vec::new(),
             is_mounted: false,
+            last_healed: None,
             created_at: Utc::now(),
-            schema_version: 1, // CompanionRecord doesn't have a schema version constant yet
+            schema_version: COMPANION_SCHEMA_VERSION,
         }
     }
 
@@ -973,10 +1205,10 @@ pub struct CraftingRecipe {
     pub result_quantity: u32,
     /// Materials required
     pub materials: Vec<RecipeMaterial>,
-    /// Optional skill required (future feature)
+    /// Skill checked before crafting and practised by it (default: crafting)
     #[serde(default)]
     pub skill_required: Option<String>,
-    /// Minimum skill level (future feature)
+    /// Minimum rank in `skill_required`
     #[serde(default)]
     pub skill_level: u8,
     /// Crafting time in seconds (0 = instant, >0 = async future feature)
@@ -1279,6 +1511,196 @@ impl HousingInstance {
     }
 }
 
+/// Set of world rooms that parties explore in private copies
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DungeonTemplate {
+    /// Unique template ID (e.g., "old_mine")
+    pub id: String,
+    pub name: String,
+    /// World room that leads into the dungeon; walking into it enters an instance
+    pub entry_room: String,
+    /// World room IDs copied into each instance (includes `entry_room`)
+    pub rooms: Vec<String>,
+    /// Maximum party size per instance
+    pub max_party: u8,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    pub schema_version: u8,
+}
+
+impl DungeonTemplate {
+    pub fn new(id: &str, name: &str, entry_room: &str, created_by: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            entry_room: entry_room.to_string(),
+            rooms: vec![entry_room.to_string()],
+            max_party: 4,
+            created_at: Utc::now(),
+            created_by: created_by.to_string(),
+            schema_version: 1,
+        }
+    }
+}
+
+/// A party's private copy of a [`DungeonTemplate`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DungeonInstance {
+    /// Unique instance ID
+    pub id: String,
+    pub template_id: String,
+    /// Player who opened the instance
+    pub leader: String,
+    /// Party members allowed in (includes the leader)
+    pub members: Vec<String>,
+    /// Template room ID -> instance room ID
+    pub room_mappings: HashMap<String, String>,
+    /// Copied mob IDs, removed on teardown
+    pub mob_ids: Vec<String>,
+    /// Where players still inside are sent when the instance is torn down
+    pub return_room: String,
+    pub created_at: DateTime<Utc>,
+    /// Last time a member moved inside the instance
+    pub last_active: DateTime<Utc>,
+    pub schema_version: u8,
+}
+
+impl DungeonInstance {
+    pub fn is_member(&self, username: &str) -> bool {
+        self.members
+            .iter()
+            .any(|m| m.eq_ignore_ascii_case(username))
+    }
+}
+
+/// Default guild ranks, lowest first; the last one is the guild leader
+pub const DEFAULT_GUILD_RANKS: [&str; 4] = ["Recruit", "Member", "Officer", "Leader"];
+
+/// One player's membership in a guild
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GuildMember {
+    pub username: String,
+    /// Index into [`GuildRecord::ranks`]
+    pub rank: u8,
+    pub joined_at: DateTime<Utc>,
+}
+
+/// Persistent player group with ranks, a shared treasury and an optional hall
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GuildRecord {
+    /// Lowercase key derived from the name (e.g., "iron_wolves")
+    pub id: String,
+    pub name: String,
+    pub founder: String,
+    /// Rank titles, lowest first; the last rank leads the guild
+    pub ranks: Vec<String>,
+    pub members: Vec<GuildMember>,
+    /// Players invited but not yet joined
+    pub invites: Vec<String>,
+    pub treasury: CurrencyAmount,
+    /// Lowest rank allowed to withdraw from the treasury
+    pub withdraw_rank: u8,
+    /// Housing instance shared with all members as the guild hall
+    pub hall_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub schema_version: u8,
+}
+
+impl GuildRecord {
+    pub fn new(id: &str, name: &str, founder: &str, treasury: CurrencyAmount) -> Self {
+        let ranks: Vec<String> = DEFAULT_GUILD_RANKS.iter().map(|r| r.to_string()).collect();
+        let leader_rank = (ranks.len() - 1) as u8;
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            founder: founder.to_lowercase(),
+            members: vec![GuildMember {
+                username: founder.to_lowercase(),
+                rank: leader_rank,
+                joined_at: Utc::now(),
+            }],
+            ranks,
+            invites: Vec::new(),
+            treasury,
+            withdraw_rank: leader_rank.saturating_sub(1),
+            hall_id: None,
+            created_at: Utc::now(),
+            schema_version: 1,
+        }
+    }
+
+    pub fn leader_rank(&self) -> u8 {
+        self.ranks.len().saturating_sub(1) as u8
+    }
+
+    pub fn member(&self, username: &str) -> Option<&GuildMember> {
+        self.members
+            .iter()
+            .find(|m| m.username.eq_ignore_ascii_case(username))
+    }
+
+    pub fn member_mut(&mut self, username: &str) -> Option<&mut GuildMember> {
+        self.members
+            .iter_mut()
+            .find(|m| m.username.eq_ignore_ascii_case(username))
+    }
+
+    pub fn rank_name(&self, rank: u8) -> &str {
+        self.ranks
+            .get(rank as usize)
+            .map(String::as_str)
+            .unwrap_or("?")
+    }
+
+    /// Current leader (the first member holding the top rank)
+    pub fn leader(&self) -> Option<&GuildMember> {
+        let top = self.leader_rank();
+        self.members.iter().find(|m| m.rank == top)
+    }
+}
+
+/// Highest bid on an auction listing; the amount is held in escrow
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuctionBid {
+    pub bidder: String,
+    pub amount: CurrencyAmount,
+    pub placed_at: DateTime<Utc>,
+}
+
+/// Item consigned to the auction house. The item stack is held by the listing
+/// until it sells or expires.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuctionListing {
+    /// Short numeric ID players type (`AUCTION BID 12 ...`)
+    pub id: u64,
+    pub seller: String,
+    pub object_id: String,
+    /// Item name when listed, for browsing without loading the object
+    pub item_name: String,
+    pub quantity: u32,
+    pub min_bid: CurrencyAmount,
+    /// Price that ends the auction immediately
+    pub buyout: Option<CurrencyAmount>,
+    pub high_bid: Option<AuctionBid>,
+    pub created_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub schema_version: u8,
+}
+
+impl AuctionListing {
+    /// Smallest bid that would be accepted now
+    pub fn next_min_bid(&self) -> i64 {
+        match &self.high_bid {
+            Some(bid) => bid.amount.base_value() + 1,
+            None => self.min_bid.base_value(),
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        now >= self.ends_at
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CombatState {
     pub enemy_id: String,
@@ -1286,6 +1708,9 @@ pub struct CombatState {
     pub enemy_max_hp: u32,
     pub round: u32,
     pub fled: bool,
+    /// Player chose DEFEND this round; cleared after the enemy's turn
+    #[serde(default)]
+    pub defending: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1309,6 +1734,16 @@ pub struct PlayerStats {
     pub intelligence: u8,
     pub constitution: u8,
     pub armor_class: u8,
+    /// Character level, raised by earning experience (see `tmush::skills`)
+    #[serde(default = "default_level")]
+    pub level: u32,
+    /// Total experience earned
+    #[serde(default)]
+    pub experience: u64,
+}
+
+fn default_level() -> u32 {
+    1
 }
 
 impl Default for PlayerStats {
@@ -1323,10 +1758,20 @@ impl Default for PlayerStats {
             intelligence: 10,
             constitution: 10,
             armor_class: 10,
+            level: 1,
+            experience: 0,
         }
     }
 }
 
+/// Rank and practice in one named skill. Practice accumulates with use and
+/// resets each time the rank goes up.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SkillProgress {
+    pub rank: u32,
+    pub practice: u32,
+}
+
 /// Quest system data structures for Phase 6 Week 2
 ///
 /// Quest state tracking for player quest progress
@@ -1482,6 +1927,11 @@ impl QuestRecord {
         self
     }
 
+    pub fn with_reward_reputation(mut self, faction_id: &str, points: i32) -> Self {
+        self.rewards.reputation.insert(faction_id.to_string(), points);
+        self
+    }
+
     pub fn with_prerequisite(mut self, quest_id: &str) -> Self {
         self.prerequisites.push(quest_id.to_string());
         self
@@ -1868,6 +2318,21 @@ pub struct PlayerRecord {
     /// Maps faction_id to reputation points (-100 to +100)
     #[serde(default)]
     pub faction_reputation: HashMap<String, i32>,
+    /// Named skills (lowercase) and how far they have been practised
+    #[serde(default)]
+    pub skills: HashMap<String, SkillProgress>,
+    /// Rooms already visited, for first-visit exploration experience
+    #[serde(default)]
+    pub explored_rooms: HashSet<String>,
+    /// Spell IDs the player has learned
+    #[serde(default)]
+    pub spells: Vec<String>,
+    /// Spell ID -> when it can be cast again
+    #[serde(default)]
+    pub spell_cooldowns: HashMap<String, DateTime<Utc>>,
+    /// When mana regeneration was last counted from (None while MP is full)
+    #[serde(default)]
+    pub mp_regen_at: Option<DateTime<Utc>>,
     pub schema_version: u8,
 }
 
@@ -1909,6 +2374,11 @@ impl PlayerRecord {
             total_objects_owned: 0,
             examined_symbol_sequence: Vec::new(),
             faction_reputation: HashMap::new(),
+            skills: HashMap::new(),
+            explored_rooms: HashSet::new(),
+            spells: Vec::new(),
+            spell_cooldowns: HashMap::new(),
+            mp_regen_at: None,
             schema_version: PLAYER_SCHEMA_VERSION,
         }
     }
@@ -2785,12 +3255,16 @@ pub struct WorldConfig {
     pub err_player_list_failed: String,
     pub err_movement_failed: String,
     pub err_movement_save_failed: String,
+
+    // === AUCTION HOUSE ===
+    /// Fee in base currency units charged when listing an item (not refunded)
+    pub auction_listing_fee: i64,
 }
 
 impl Default for WorldConfig {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: WORLD_CONFIG_VERSION,
             updated_at: Utc::now(),
             updated_by: "system".to_string(),
 
@@ -3013,6 +3487,9 @@ impl Default for WorldConfig {
             err_player_list_failed: "Error listing players: {error}".to_string(),
             err_movement_failed: "Movement failed: {error}".to_string(),
             err_movement_save_failed: "Movement failed to save: {error}".to_string(),
+
+            // Auction house
+            auction_listing_fee: 5,
         }
     }
 }
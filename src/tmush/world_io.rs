@@ -0,0 +1,675 @@
+//! World export/import as versioned JSON area files
+//!
+//! Seed files are only read once, when the sled database is empty. This module
+//! gets a live world back out: [`export_world`] writes the builder-authored
+//! content of a [`TinyMushStore`] into a directory with one JSON area file per
+//! record kind, plus a `manifest.json` recording the export format and the
+//! schema version of every kind (see [`crate::tmush::migration`]).
+//!
+//! ```text
+//! world/
+//!   manifest.json
+//!   rooms.json  objects.json  npcs.json  quests.json  achievements.json
+//!   recipes.json  shops.json  companions.json  housing_templates.json
+//!   dungeon_templates.json  mobs.json  factions.json  spells.json
+//! ```
+//!
+//! [`import_world`] reads such a directory back and compares every record with
+//! what is already stored:
+//!
+//! - ids not in the store are added
+//! - identical records are left alone
+//! - ids present with different content are **collisions**: reported and
+//!   skipped, or replaced when [`ImportOptions::overwrite`] is set
+//!
+//! Everything is parsed and checked before the first write, so a bad file
+//! leaves the store untouched. Records from an older schema are upgraded
+//! through [`Migratable`]; a file or record from a newer schema than this
+//! build knows aborts the import with [`TinyMushError::SchemaMismatch`].
+//!
+//! Player state (players, player-owned rooms and objects, tamed companions,
+//! housing instances) and running dungeon instances are never exported.
+
+use crate::tmush::dungeon::is_instance_copy;
+use crate::tmush::migration::{
+    Migratable, CURRENT_ACHIEVEMENT_SCHEMA_VERSION, CURRENT_COMPANION_SCHEMA_VERSION,
+    CURRENT_NPC_SCHEMA_VERSION, CURRENT_OBJECT_SCHEMA_VERSION, CURRENT_QUEST_SCHEMA_VERSION,
+    CURRENT_ROOM_SCHEMA_VERSION,
+};
+use crate::tmush::shop::ShopRecord;
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::types::{
+    AchievementRecord, CompanionRecord, CraftingRecipe, DungeonTemplate, FactionRecord,
+    HousingTemplate, MobRecord, NpcRecord, ObjectOwner, ObjectRecord, QuestRecord, RoomOwner,
+    RoomRecord, SpellRecord, MOB_SCHEMA_VERSION, RECIPE_SCHEMA_VERSION, SPELL_SCHEMA_VERSION,
+};
+use crate::tmush::TinyMushError;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Version of the directory layout and area file envelope
+pub const WORLD_FORMAT_VERSION: u8 = 1;
+
+/// Name of the manifest inside an export directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Contents of `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldManifest {
+    pub format_version: u8,
+    pub exported_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exported_by: Option<String>,
+    /// Schema version of each record kind at export time
+    #[serde(default)]
+    pub schema_versions: BTreeMap<String, u8>,
+    /// Number of records written per kind
+    #[serde(default)]
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Envelope of a single area file
+#[derive(Serialize, Deserialize)]
+struct AreaFile<T> {
+    kind: String,
+    schema_version: u8,
+    records: Vec<T>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    /// Replace records whose id already exists with different content
+    pub overwrite: bool,
+    /// Only compute the report; write nothing
+    pub dry_run: bool,
+}
+
+/// What an import did (or would do) for one record kind
+#[derive(Debug, Clone, Default)]
+pub struct KindReport {
+    pub kind: &'static str,
+    pub added: Vec<String>,
+    /// Collisions that were overwritten
+    pub replaced: Vec<String>,
+    pub unchanged: usize,
+    /// Ids already stored with different content, left as they were
+    pub collisions: Vec<String>,
+    /// Ids listed more than once in the file; the first copy is used
+    pub duplicates: Vec<String>,
+    /// Records upgraded from an older schema version
+    pub migrated: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub kinds: Vec<KindReport>,
+}
+
+impl ImportReport {
+    /// Records added or replaced
+    pub fn changes(&self) -> usize {
+        self.kinds
+            .iter()
+            .map(|k| k.added.len() + k.replaced.len())
+            .sum()
+    }
+
+    /// Collisions that were skipped
+    pub fn collisions(&self) -> usize {
+        self.kinds.iter().map(|k| k.collisions.len()).sum()
+    }
+
+    /// Human readable per-kind diff, one line per kind plus id lists
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        if self.dry_run {
+            out.push_str("Dry run - nothing written.\n");
+        }
+        for k in &self.kinds {
+            out.push_str(&format!(
+                "{}: {} new, {} replaced, {} same, {} collisions",
+                k.kind,
+                k.added.len(),
+                k.replaced.len(),
+                k.unchanged,
+                k.collisions.len()
+            ));
+            if k.migrated > 0 {
+                out.push_str(&format!(" ({} migrated)", k.migrated));
+            }
+            out.push('\n');
+            if !k.collisions.is_empty() {
+                out.push_str(&format!("  collide: {}\n", k.collisions.join(", ")));
+            }
+            if !k.duplicates.is_empty() {
+                out.push_str(&format!("  duplicate: {}\n", k.duplicates.join(", ")));
+            }
+        }
+        if self.kinds.is_empty() {
+            out.push_str("No area files found.\n");
+        }
+        if self.collisions() > 0 {
+            out.push_str("Colliding records were kept; import with overwrite to replace them.\n");
+        }
+        out
+    }
+}
+
+/// A record kind that round-trips through an area file
+trait AreaRecord: Serialize + DeserializeOwned + 'static {
+    /// File stem and manifest key
+    const KIND: &'static str;
+    /// Schema version this build reads and writes
+    const SCHEMA_VERSION: u8;
+
+    fn id(&self) -> &str;
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError>;
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError>;
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError>;
+
+    /// Bring a record written by an older schema up to date
+    fn upgrade(self) -> Result<Self, TinyMushError> {
+        Ok(self)
+    }
+}
+
+/// Map `NotFound` to `None`
+fn found<T>(res: Result<T, TinyMushError>) -> Result<Option<T>, TinyMushError> {
+    match res {
+        Ok(v) => Ok(Some(v)),
+        Err(TinyMushError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn migrate<T: Migratable>(record: T) -> Result<T, TinyMushError> {
+    record
+        .migrate()
+        .map_err(|e| TinyMushError::Internal(e.to_string()))
+}
+
+fn load_each<T>(
+    ids: Vec<String>,
+    get: impl Fn(&str) -> Result<T, TinyMushError>,
+) -> Result<Vec<T>, TinyMushError> {
+    ids.iter().map(|id| get(id)).collect()
+}
+
+impl AreaRecord for RoomRecord {
+    const KIND: &'static str = "rooms";
+    const SCHEMA_VERSION: u8 = CURRENT_ROOM_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        let rooms = load_each(store.list_room_ids()?, |id| store.get_room(id))?;
+        Ok(rooms
+            .into_iter()
+            .filter(|r| r.owner == RoomOwner::World && !is_instance_copy(&r.id))
+            .collect())
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_room(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_room(self)
+    }
+    fn upgrade(self) -> Result<Self, TinyMushError> {
+        migrate(self)
+    }
+}
+
+impl AreaRecord for ObjectRecord {
+    const KIND: &'static str = "objects";
+    const SCHEMA_VERSION: u8 = CURRENT_OBJECT_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        let objects = load_each(store.list_object_ids()?, |id| store.get_object(id))?;
+        Ok(objects
+            .into_iter()
+            .filter(|o| o.owner == ObjectOwner::World && !is_instance_copy(&o.id))
+            .collect())
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_object(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_object(self)
+    }
+    fn upgrade(self) -> Result<Self, TinyMushError> {
+        migrate(self)
+    }
+}
+
+impl AreaRecord for NpcRecord {
+    const KIND: &'static str = "npcs";
+    const SCHEMA_VERSION: u8 = CURRENT_NPC_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        load_each(store.list_npc_ids()?, |id| store.get_npc(id))
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_npc(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_npc(self)
+    }
+    fn upgrade(self) -> Result<Self, TinyMushError> {
+        migrate(self)
+    }
+}
+
+impl AreaRecord for QuestRecord {
+    const KIND: &'static str = "quests";
+    const SCHEMA_VERSION: u8 = CURRENT_QUEST_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        load_each(store.list_quest_ids()?, |id| store.get_quest(id))
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_quest(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_quest(self)
+    }
+}
+
+impl AreaRecord for AchievementRecord {
+    const KIND: &'static str = "achievements";
+    const SCHEMA_VERSION: u8 = CURRENT_ACHIEVEMENT_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        load_each(store.list_achievement_ids()?, |id| {
+            store.get_achievement(id)
+        })
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_achievement(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_achievement(self)
+    }
+}
+
+impl AreaRecord for CraftingRecipe {
+    const KIND: &'static str = "recipes";
+    const SCHEMA_VERSION: u8 = RECIPE_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        store.list_recipes(None)
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_recipe(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_recipe(self)
+    }
+}
+
+impl AreaRecord for ShopRecord {
+    const KIND: &'static str = "shops";
+    // Shops carry no per-record version yet
+    const SCHEMA_VERSION: u8 = 1;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        load_each(store.list_shop_ids()?, |id| store.get_shop(id))
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_shop(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_shop(self)
+    }
+}
+
+impl AreaRecord for CompanionRecord {
+    const KIND: &'static str = "companions";
+    const SCHEMA_VERSION: u8 = CURRENT_COMPANION_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    /// Wild companions only; tamed ones belong to their owner
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        let companions = load_each(store.list_companion_ids()?, |id| store.get_companion(id))?;
+        Ok(companions
+            .into_iter()
+            .filter(|c| c.owner.is_none())
+            .collect())
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_companion(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_companion(self)
+    }
+    fn upgrade(self) -> Result<Self, TinyMushError> {
+        migrate(self)
+    }
+}
+
+impl AreaRecord for HousingTemplate {
+    const KIND: &'static str = "housing_templates";
+    const SCHEMA_VERSION: u8 = 1;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        let mut ids = store.list_housing_templates()?;
+        ids.sort();
+        load_each(ids, |id| store.get_housing_template(id))
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_housing_template(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_housing_template(&self)
+    }
+}
+
+impl AreaRecord for DungeonTemplate {
+    const KIND: &'static str = "dungeon_templates";
+    const SCHEMA_VERSION: u8 = 1;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        store.list_dungeon_templates()
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_dungeon_template(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_dungeon_template(&self)
+    }
+}
+
+/// A defeat is runtime state, not part of the authored mob
+fn without_respawn_timer(mut mob: MobRecord) -> MobRecord {
+    mob.defeated_until = None;
+    mob
+}
+
+impl AreaRecord for MobRecord {
+    const KIND: &'static str = "mobs";
+    const SCHEMA_VERSION: u8 = MOB_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    /// World mobs only, with any pending respawn timer dropped
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        let ids = store
+            .list_mob_ids()?
+            .into_iter()
+            .filter(|id| !is_instance_copy(id))
+            .collect();
+        let mobs = load_each(ids, |id| store.get_mob(id))?;
+        Ok(mobs.into_iter().map(without_respawn_timer).collect())
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        Ok(found(store.get_mob(id))?.map(without_respawn_timer))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_mob(self)
+    }
+}
+
+impl AreaRecord for FactionRecord {
+    const KIND: &'static str = "factions";
+    // Factions carry no per-record version yet
+    const SCHEMA_VERSION: u8 = 1;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        store.list_factions()
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_faction(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_faction(self)
+    }
+}
+
+impl AreaRecord for SpellRecord {
+    const KIND: &'static str = "spells";
+    const SCHEMA_VERSION: u8 = SPELL_SCHEMA_VERSION;
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+    fn load_all(store: &TinyMushStore) -> Result<Vec<Self>, TinyMushError> {
+        store.list_spells()
+    }
+    fn load(store: &TinyMushStore, id: &str) -> Result<Option<Self>, TinyMushError> {
+        found(store.get_spell(id))
+    }
+    fn save(self, store: &TinyMushStore) -> Result<(), TinyMushError> {
+        store.put_spell(self)
+    }
+}
+
+fn invalid_data(path: &Path, e: impl std::fmt::Display) -> TinyMushError {
+    TinyMushError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Failed to parse {}: {}", path.display(), e),
+    ))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, TinyMushError> {
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|e| invalid_data(path, e))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), TinyMushError> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| invalid_data(path, e))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+fn to_value<T: Serialize>(record: &T) -> Result<serde_json::Value, TinyMushError> {
+    serde_json::to_value(record).map_err(|e| TinyMushError::Internal(e.to_string()))
+}
+
+fn export_kind<T: AreaRecord>(
+    store: &TinyMushStore,
+    dir: &Path,
+    manifest: &mut WorldManifest,
+) -> Result<(), TinyMushError> {
+    let mut records = T::load_all(store)?;
+    records.sort_by(|a, b| a.id().cmp(b.id()));
+    manifest
+        .schema_versions
+        .insert(T::KIND.to_string(), T::SCHEMA_VERSION);
+    manifest.counts.insert(T::KIND.to_string(), records.len());
+    let file = AreaFile {
+        kind: T::KIND.to_string(),
+        schema_version: T::SCHEMA_VERSION,
+        records,
+    };
+    write_json(&dir.join(format!("{}.json", T::KIND)), &file)
+}
+
+/// Write every world record kind plus the manifest into `dir` (created if
+/// missing; existing area files are replaced)
+pub fn export_world(
+    store: &TinyMushStore,
+    dir: &Path,
+    exported_by: Option<&str>,
+) -> Result<WorldManifest, TinyMushError> {
+    fs::create_dir_all(dir)?;
+    let mut manifest = WorldManifest {
+        format_version: WORLD_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        exported_by: exported_by.map(str::to_string),
+        schema_versions: BTreeMap::new(),
+        counts: BTreeMap::new(),
+    };
+    export_kind::<RoomRecord>(store, dir, &mut manifest)?;
+    export_kind::<ObjectRecord>(store, dir, &mut manifest)?;
+    export_kind::<NpcRecord>(store, dir, &mut manifest)?;
+    export_kind::<QuestRecord>(store, dir, &mut manifest)?;
+    export_kind::<AchievementRecord>(store, dir, &mut manifest)?;
+    export_kind::<CraftingRecipe>(store, dir, &mut manifest)?;
+    export_kind::<ShopRecord>(store, dir, &mut manifest)?;
+    export_kind::<CompanionRecord>(store, dir, &mut manifest)?;
+    export_kind::<HousingTemplate>(store, dir, &mut manifest)?;
+    export_kind::<DungeonTemplate>(store, dir, &mut manifest)?;
+    export_kind::<MobRecord>(store, dir, &mut manifest)?;
+    export_kind::<FactionRecord>(store, dir, &mut manifest)?;
+    export_kind::<SpellRecord>(store, dir, &mut manifest)?;
+    write_json(&dir.join(MANIFEST_FILE), &manifest)?;
+    Ok(manifest)
+}
+
+type PendingWrite = Box<dyn FnOnce(&TinyMushStore) -> Result<(), TinyMushError>>;
+
+/// Parse, schema-check and diff one area file. Returns `None` when the file
+/// is absent.
+fn plan_kind<T: AreaRecord>(
+    store: &TinyMushStore,
+    dir: &Path,
+    opts: ImportOptions,
+) -> Result<Option<(KindReport, PendingWrite)>, TinyMushError> {
+    let path = dir.join(format!("{}.json", T::KIND));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file: AreaFile<serde_json::Value> = read_json(&path)?;
+    if file.kind != T::KIND {
+        return Err(invalid_data(
+            &path,
+            format!("expected kind '{}', found '{}'", T::KIND, file.kind),
+        ));
+    }
+    let too_new = |found: u8| TinyMushError::SchemaMismatch {
+        entity: T::KIND,
+        expected: T::SCHEMA_VERSION,
+        found,
+    };
+    if file.schema_version > T::SCHEMA_VERSION {
+        return Err(too_new(file.schema_version));
+    }
+
+    let mut report = KindReport {
+        kind: T::KIND,
+        ..Default::default()
+    };
+    let mut seen = HashSet::new();
+    let mut writes = Vec::new();
+    for raw in file.records {
+        let version = raw
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .map(|v| v.min(u8::MAX as u64) as u8);
+        if let Some(v) = version.filter(|v| *v > T::SCHEMA_VERSION) {
+            return Err(too_new(v));
+        }
+        let mut record: T = serde_json::from_value(raw).map_err(|e| invalid_data(&path, e))?;
+        if version.is_some_and(|v| v < T::SCHEMA_VERSION) {
+            record = record.upgrade()?;
+            report.migrated += 1;
+        }
+
+        let id = record.id().to_string();
+        if !seen.insert(id.clone()) {
+            report.duplicates.push(id);
+            continue;
+        }
+        match T::load(store, &id)? {
+            None => report.added.push(id),
+            Some(current) if to_value(&current)? == to_value(&record)? => {
+                report.unchanged += 1;
+                continue;
+            }
+            Some(_) if opts.overwrite => report.replaced.push(id),
+            Some(_) => {
+                report.collisions.push(id);
+                continue;
+            }
+        }
+        writes.push(record);
+    }
+
+    let write: PendingWrite = Box::new(move |store| {
+        for record in writes {
+            record.save(store)?;
+        }
+        Ok(())
+    });
+    Ok(Some((report, write)))
+}
+
+/// Import an export directory into `store`. Nothing is written if any file
+/// fails to parse or comes from a newer format or schema.
+pub fn import_world(
+    store: &TinyMushStore,
+    dir: &Path,
+    opts: ImportOptions,
+) -> Result<ImportReport, TinyMushError> {
+    let manifest: WorldManifest = read_json(&dir.join(MANIFEST_FILE))?;
+    if manifest.format_version > WORLD_FORMAT_VERSION {
+        return Err(TinyMushError::SchemaMismatch {
+            entity: "world export",
+            expected: WORLD_FORMAT_VERSION,
+            found: manifest.format_version,
+        });
+    }
+
+    let planned = [
+        plan_kind::<RoomRecord>(store, dir, opts)?,
+        plan_kind::<ObjectRecord>(store, dir, opts)?,
+        plan_kind::<NpcRecord>(store, dir, opts)?,
+        plan_kind::<QuestRecord>(store, dir, opts)?,
+        plan_kind::<AchievementRecord>(store, dir, opts)?,
+        plan_kind::<CraftingRecipe>(store, dir, opts)?,
+        plan_kind::<ShopRecord>(store, dir, opts)?,
+        plan_kind::<CompanionRecord>(store, dir, opts)?,
+        plan_kind::<HousingTemplate>(store, dir, opts)?,
+        plan_kind::<DungeonTemplate>(store, dir, opts)?,
+        plan_kind::<MobRecord>(store, dir, opts)?,
+        plan_kind::<FactionRecord>(store, dir, opts)?,
+        plan_kind::<SpellRecord>(store, dir, opts)?,
+    ];
+
+    let mut report = ImportReport {
+        dry_run: opts.dry_run,
+        kinds: Vec::new(),
+    };
+    for (kind, write) in planned.into_iter().flatten() {
+        if !opts.dry_run {
+            write(store)?;
+        }
+        report.kinds.push(kind);
+    }
+    Ok(report)
+}
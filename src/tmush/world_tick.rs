@@ -0,0 +1,341 @@
+//! Scheduled world tick for TinyMUSH.
+//!
+//! Without a tick the world only moves when a player types a command. The BBS
+//! server calls [`WorldTick::run`] every `games.tinymush_world_tick_seconds`
+//! (default 60) while anyone is in the game, and each run:
+//!
+//! - Moves NPCs one step along their `patrol` route
+//! - Fires `OnIdle` triggers on objects in rooms with players in them
+//! - Lets companions standing with their owner act: `Healing` restores HP once
+//!   its cooldown has passed, `IdleChatter` sometimes says a line, and
+//!   `AlertDanger` warns when a hostile mob is in the room or next door
+//!
+//! Everything is published on the room event bus as
+//! [`RoomEventKind::Ambient`] or [`RoomEventKind::Chatter`], which the server
+//! delivers at Low and Background priority. Every NPC, object and companion
+//! goes through the trigger rate limiter, so `@trigger/disable <id>` freezes
+//! one and `@trigger/global off` stops the tick altogether. At most
+//! [`MAX_AMBIENT_LINES_PER_ROOM`] lines reach a room per tick.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use log::debug;
+use rand::Rng;
+
+use crate::tmush::errors::TinyMushError;
+use crate::tmush::events::{RoomEvent, RoomEventKind};
+use crate::tmush::storage::TinyMushStore;
+use crate::tmush::trigger::{check_trigger_allowed, execute_on_idle, record_trigger_execution};
+use crate::tmush::types::{
+    CompanionBehavior, CompanionRecord, NpcRecord, ObjectTrigger, PlayerRecord,
+};
+
+/// Tick interval when `games.tinymush_world_tick_seconds` is unset
+pub const DEFAULT_WORLD_TICK_SECONDS: u64 = 60;
+
+/// Most world tick lines delivered to one room per tick
+pub const MAX_AMBIENT_LINES_PER_ROOM: usize = 3;
+
+/// Chance per tick that a companion with `IdleChatter` says something
+pub const IDLE_CHATTER_CHANCE: f64 = 0.25;
+
+/// What one tick did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TickReport {
+    pub npcs_moved: usize,
+    pub idle_triggers: usize,
+    pub heals: usize,
+    pub chatter: usize,
+    pub alerts: usize,
+}
+
+/// State carried between ticks
+#[derive(Debug, Default)]
+pub struct WorldTick {
+    /// Companion ID -> room it last warned its owner in, so a lurking mob
+    /// only raises the alarm once
+    alerted: HashMap<String, String>,
+}
+
+impl WorldTick {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run one tick for the players in `online` (usernames of sessions in the game)
+    pub fn run(
+        &mut self,
+        store: &TinyMushStore,
+        online: &[String],
+        now: DateTime<Utc>,
+        rng: &mut impl Rng,
+    ) -> Result<TickReport, TinyMushError> {
+        let mut report = TickReport::default();
+        let players: Vec<PlayerRecord> = online
+            .iter()
+            .filter_map(|name| store.get_player(name).ok())
+            .collect();
+        if players.is_empty() {
+            return Ok(report);
+        }
+
+        // Room -> usernames standing in it
+        let mut occupied: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for player in &players {
+            occupied
+                .entry(player.current_room.clone())
+                .or_default()
+                .push(player.username.clone());
+        }
+        let mut budget = RoomBudget::new(occupied.keys().cloned());
+
+        for npc_id in store.list_npc_ids()? {
+            let Ok(mut npc) = store.get_npc(&npc_id) else {
+                continue;
+            };
+            let Some(next) = next_patrol_room(&npc).map(str::to_string) else {
+                continue;
+            };
+            if !allowed(&npc.id) {
+                continue;
+            }
+            let from = std::mem::replace(&mut npc.room_id, next.clone());
+            let leaves = match store.get_room(&from).ok().and_then(|room| {
+                room.exits
+                    .iter()
+                    .find(|(_, to)| **to == next)
+                    .map(|(dir, _)| format!("{:?}", dir).to_lowercase())
+            }) {
+                Some(dir) => format!("{} leaves {}.", npc.name, dir),
+                None => format!("{} wanders off.", npc.name),
+            };
+            budget.publish(
+                store,
+                RoomEvent::new(&from, &npc.id, RoomEventKind::Ambient, &leaves),
+            );
+            let arrives = format!("{} arrives.", npc.name);
+            budget.publish(
+                store,
+                RoomEvent::new(&next, &npc.id, RoomEventKind::Ambient, &arrives),
+            );
+            store.put_npc(npc)?;
+            report.npcs_moved += 1;
+        }
+
+        for (room_id, usernames) in &occupied {
+            let Ok(room) = store.get_room(room_id) else {
+                continue;
+            };
+            for object_id in &room.items {
+                let Ok(object) = store.get_object(object_id) else {
+                    continue;
+                };
+                if !object.actions.contains_key(&ObjectTrigger::OnIdle) {
+                    continue;
+                }
+                let lines = execute_on_idle(&object, &usernames[0], room_id, store);
+                if lines.is_empty() {
+                    continue;
+                }
+                report.idle_triggers += 1;
+                for line in lines {
+                    budget.publish(
+                        store,
+                        RoomEvent::new(room_id, &object.id, RoomEventKind::Ambient, &line),
+                    );
+                }
+            }
+        }
+
+        // Reloaded: idle triggers may have changed the player
+        for username in players.into_iter().map(|p| p.username) {
+            let mut player = store.get_player(&username)?;
+            let companions = store.get_player_companions(&player.username)?;
+            for mut companion in companions {
+                if companion.room_id != player.current_room || !allowed(&companion.id) {
+                    continue;
+                }
+                let mut changed = false;
+                for behavior in companion.behaviors.clone() {
+                    match behavior {
+                        CompanionBehavior::Healing {
+                            heal_amount,
+                            cooldown_seconds,
+                        } => {
+                            let ready = companion.last_healed.is_none_or(|last| {
+                                (now - last).num_seconds() >= cooldown_seconds as i64
+                            });
+                            let stats = &mut player.stats;
+                            if !ready || stats.hp >= stats.max_hp {
+                                continue;
+                            }
+                            let healed = heal_amount.min(stats.max_hp - stats.hp);
+                            stats.hp += healed;
+                            let line = format!(
+                                "{} tends to you. +{} HP ({}/{})",
+                                companion.name, healed, stats.hp, stats.max_hp
+                            );
+                            store.put_player(player.clone())?;
+                            companion.last_healed = Some(now);
+                            changed = true;
+                            report.heals += 1;
+                            budget.publish(
+                                store,
+                                RoomEvent::new(
+                                    &player.current_room,
+                                    &companion.id,
+                                    RoomEventKind::Ambient,
+                                    &line,
+                                )
+                                .for_target(&player.username),
+                            );
+                        }
+                        CompanionBehavior::IdleChatter { messages } => {
+                            if messages.is_empty() || !rng.gen_bool(IDLE_CHATTER_CHANCE) {
+                                continue;
+                            }
+                            let line = &messages[rng.gen_range(0..messages.len())];
+                            report.chatter += 1;
+                            budget.publish(
+                                store,
+                                RoomEvent::new(
+                                    &player.current_room,
+                                    &companion.id,
+                                    RoomEventKind::Chatter,
+                                    &format!("{} {}", companion.name, line),
+                                ),
+                            );
+                        }
+                        CompanionBehavior::AlertDanger => {
+                            if let Some(line) = self.alert(store, &companion)? {
+                                report.alerts += 1;
+                                budget.publish(
+                                    store,
+                                    RoomEvent::new(
+                                        &player.current_room,
+                                        &companion.id,
+                                        RoomEventKind::Ambient,
+                                        &line,
+                                    )
+                                    .for_target(&player.username),
+                                );
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if changed {
+                    store.put_companion(companion)?;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Warning line the first time a hostile mob is in, or next to, the
+    /// companion's room
+    fn alert(
+        &mut self,
+        store: &TinyMushStore,
+        companion: &CompanionRecord,
+    ) -> Result<Option<String>, TinyMushError> {
+        let room = store.get_room(&companion.room_id)?;
+        let danger = match store.get_mobs_in_room(&room.id)?.first() {
+            Some(mob) => Some(format!("{} bristles at the {}!", companion.name, mob.name)),
+            None => {
+                let mut found = None;
+                for (dir, to) in &room.exits {
+                    if let Some(mob) = store.get_mobs_in_room(to)?.first() {
+                        found = Some(format!(
+                            "{} growls toward the {}. Something is there: {}.",
+                            companion.name,
+                            format!("{:?}", dir).to_lowercase(),
+                            mob.name
+                        ));
+                        break;
+                    }
+                }
+                found
+            }
+        };
+
+        let Some(line) = danger else {
+            self.alerted.remove(&companion.id);
+            return Ok(None);
+        };
+        if self.alerted.get(&companion.id) == Some(&room.id) {
+            return Ok(None);
+        }
+        self.alerted.insert(companion.id.clone(), room.id);
+        Ok(Some(line))
+    }
+}
+
+/// Where a patrolling NPC goes next: the room after its current one in the
+/// route (wrapping around), or the start of the route if it is off it
+pub fn next_patrol_room(npc: &NpcRecord) -> Option<&str> {
+    let next = match npc.patrol.iter().position(|room| *room == npc.room_id) {
+        Some(i) => &npc.patrol[(i + 1) % npc.patrol.len()],
+        None => npc.patrol.first()?,
+    };
+    (*next != npc.room_id).then_some(next.as_str())
+}
+
+/// Ask the trigger rate limiter whether `source` may act, recording it if so
+fn allowed(source: &str) -> bool {
+    match check_trigger_allowed(source, source) {
+        Ok(()) => {
+            record_trigger_execution(source, source);
+            true
+        }
+        Err(reason) => {
+            debug!("World tick skipped {}: {}", source, reason);
+            false
+        }
+    }
+}
+
+/// Drops lines for empty rooms and caps how many each occupied room gets per tick
+struct RoomBudget {
+    sent: HashMap<String, usize>,
+}
+
+impl RoomBudget {
+    fn new(occupied: impl Iterator<Item = String>) -> Self {
+        Self {
+            sent: occupied.map(|room| (room, 0)).collect(),
+        }
+    }
+
+    fn publish(&mut self, store: &TinyMushStore, event: RoomEvent) {
+        if let Some(sent) = self.sent.get_mut(&event.room_id) {
+            if *sent < MAX_AMBIENT_LINES_PER_ROOM {
+                *sent += 1;
+                store.room_events().publish(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patrols_loop_and_rejoin_their_route() {
+        let mut npc = NpcRecord::new("guard", "Guard", "Town Guard", "A guard.", "gate");
+        assert_eq!(next_patrol_room(&npc), None);
+
+        npc.patrol = vec!["gate".into(), "market".into(), "well".into()];
+        assert_eq!(next_patrol_room(&npc), Some("market"));
+        npc.room_id = "well".into();
+        assert_eq!(next_patrol_room(&npc), Some("gate"));
+        npc.room_id = "tavern".into();
+        assert_eq!(next_patrol_room(&npc), Some("gate"));
+
+        npc.patrol = vec!["tavern".into()];
+        assert_eq!(next_patrol_room(&npc), None);
+    }
+}
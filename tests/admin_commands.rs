@@ -64,6 +64,7 @@ async fn base_config() -> Config {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     }
 }
 
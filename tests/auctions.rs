@@ -0,0 +1,199 @@
+//! Auction house (AUCTION): listing fee, escrow, offline bidding and settlement by mail.
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::auction;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::types::{CurrencyAmount, ItemStack, ObjectRecord, TransactionReason};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+/// Alice holds a lantern, everyone has 1000 to spend
+async fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    store
+        .put_object(ObjectRecord::new_world(
+            "brass_lantern",
+            "Brass Lantern",
+            "A dented lantern.",
+        ))
+        .unwrap();
+    for name in ["alice", "bob", "carol"] {
+        let mut player = PlayerRecord::new(name, name, "town_square");
+        if name == "alice" {
+            player
+                .inventory_stacks
+                .push(ItemStack::new("brass_lantern".to_string(), 1));
+        }
+        store.put_player(player).unwrap();
+        store
+            .grant_currency(
+                name,
+                &CurrencyAmount::decimal(1000),
+                TransactionReason::AdminGrant,
+            )
+            .unwrap();
+    }
+    (store, config)
+}
+
+fn listing_id(out: &str) -> u64 {
+    let start = out.find('#').expect(out) + 1;
+    out[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+fn balance(store: &TinyMushStore, user: &str) -> i64 {
+    store.get_player(user).unwrap().currency.base_value()
+}
+
+fn lanterns(store: &TinyMushStore, user: &str) -> u32 {
+    store.player_item_quantity(user, "brass_lantern").unwrap()
+}
+
+fn inbox(store: &TinyMushStore, user: &str) -> Vec<String> {
+    store
+        .list_mail("inbox", user, 0, 20)
+        .unwrap()
+        .into_iter()
+        .map(|m| m.subject)
+        .collect()
+}
+
+#[tokio::test]
+async fn listing_charges_fee_and_escrows_the_item() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    let out = run(&store, &config, "alice", "AUCTION SELL lantern 50 0 200").await;
+    assert!(out.contains("Auctions last 1 to 168 hours"), "{}", out);
+    let out = run(&store, &config, "alice", "AUCTION SELL lantern 50 200 12").await;
+    assert!(out.contains("Listed as auction #"), "{}", out);
+    assert_eq!(balance(&store, "alice"), 995);
+    assert_eq!(lanterns(&store, "alice"), 0);
+
+    let out = run(&store, &config, "bob", "AUCTION").await;
+    assert!(out.contains("Brass Lantern - bid 50 buy 200"), "{}", out);
+
+    // Cancelling returns the item but keeps the fee
+    let id = listing_id(&out);
+    let out = run(&store, &config, "bob", &format!("AUCTION CANCEL {}", id)).await;
+    assert!(out.contains("not your listing"), "{}", out);
+    run(&store, &config, "alice", &format!("AUCTION CANCEL {}", id)).await;
+    assert_eq!(lanterns(&store, "alice"), 1);
+    assert_eq!(balance(&store, "alice"), 995);
+    assert!(store.list_auctions().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn outbid_bidders_are_refunded_and_mailed() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let out = run(&store, &config, "alice", "AUCTION SELL lantern 50").await;
+    let id = listing_id(&out);
+
+    let out = run(&store, &config, "alice", &format!("AUCTION BID {} 60", id)).await;
+    assert!(out.contains("your own listing"), "{}", out);
+    let out = run(&store, &config, "bob", &format!("AUCTION BID {} 40", id)).await;
+    assert!(out.contains("at least 50"), "{}", out);
+    let out = run(&store, &config, "bob", &format!("AUCTION BID {} 60", id)).await;
+    assert!(out.contains("high bidder"), "{}", out);
+    assert_eq!(balance(&store, "bob"), 940);
+
+    let out = run(&store, &config, "carol", &format!("AUCTION BID {} 60", id)).await;
+    assert!(out.contains("at least 61"), "{}", out);
+    run(&store, &config, "carol", &format!("AUCTION BID {} 75", id)).await;
+    assert_eq!(balance(&store, "carol"), 925);
+    assert_eq!(balance(&store, "bob"), 1000);
+    assert_eq!(inbox(&store, "bob"), vec!["Outbid on Brass Lantern"]);
+    assert_eq!(
+        store.get_auction(id).unwrap().high_bid.unwrap().bidder,
+        "carol"
+    );
+}
+
+#[tokio::test]
+async fn ended_auctions_refuse_bids() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let id = listing_id(&run(&store, &config, "alice", "AUCTION SELL lantern 50 200").await);
+    let mut listing = store.get_auction(id).unwrap();
+    listing.ends_at = chrono::Utc::now() - chrono::Duration::minutes(1);
+    store.put_auction(&listing).unwrap();
+
+    let out = run(&store, &config, "bob", &format!("AUCTION BID {} 60", id)).await;
+    assert!(out.contains("has ended"), "{}", out);
+    let out = run(&store, &config, "bob", &format!("AUCTION BUY {}", id)).await;
+    assert!(out.contains("has ended"), "{}", out);
+    assert_eq!(balance(&store, "bob"), 1000);
+}
+
+#[tokio::test]
+async fn buyout_settles_immediately() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let out = run(&store, &config, "alice", "AUCTION SELL lantern 50 200").await;
+    let id = listing_id(&out);
+    run(&store, &config, "bob", &format!("AUCTION BID {} 100", id)).await;
+
+    let out = run(&store, &config, "carol", &format!("AUCTION BUY {}", id)).await;
+    assert!(out.contains("You won auction"), "{}", out);
+    assert_eq!(lanterns(&store, "carol"), 1);
+    assert_eq!(balance(&store, "carol"), 800);
+    assert_eq!(balance(&store, "bob"), 1000);
+    assert_eq!(balance(&store, "alice"), 995 + 200);
+    assert_eq!(inbox(&store, "alice"), vec!["Sold: Brass Lantern"]);
+    assert!(inbox(&store, "carol").contains(&"Won: Brass Lantern".to_string()));
+    assert!(store.list_auctions().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn finished_auctions_settle_or_return_unsold_items() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    store
+        .put_object(ObjectRecord::new_world("rope", "Coil of Rope", "Rope."))
+        .unwrap();
+    let mut alice = store.get_player("alice").unwrap();
+    alice
+        .inventory_stacks
+        .push(ItemStack::new("rope".to_string(), 3));
+    store.put_player(alice).unwrap();
+
+    let sold = listing_id(&run(&store, &config, "alice", "AUCTION SELL lantern 50 0 1").await);
+    let unsold = run(&store, &config, "alice", "AUCTION SELL 2 rope 10 0 2").await;
+    assert!(unsold.contains("Coil of Rope x2"), "{}", unsold);
+    run(&store, &config, "bob", &format!("AUCTION BID {} 70", sold)).await;
+
+    let now = chrono::Utc::now();
+    assert!(auction::settle_due(&store, now).unwrap().is_empty());
+    let settled = auction::settle_due(&store, now + chrono::Duration::hours(1)).unwrap();
+    assert_eq!(settled, vec![sold]);
+    assert_eq!(lanterns(&store, "bob"), 1);
+    assert_eq!(balance(&store, "alice"), 990 + 70);
+
+    auction::settle_due(&store, now + chrono::Duration::hours(2)).unwrap();
+    assert_eq!(store.player_item_quantity("alice", "rope").unwrap(), 3);
+    assert!(inbox(&store, "alice").contains(&"Expired: Coil of Rope x2".to_string()));
+    assert!(store.list_auctions().unwrap().is_empty());
+}
@@ -0,0 +1,153 @@
+//! Integration tests for private BBS mail.
+//!
+//! Covers the storage mailbox (quota, read state, delete) and the [E]mail flow
+//! driven through the server: compose, inbox paging, slice reading and the
+//! unread mail line in the login banner.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::storage::{MailFolder, Storage};
+
+fn last_for_node<'a>(msgs: &'a [(String, String)], node: &str) -> Option<&'a String> {
+    msgs.iter().rev().find(|(to, _)| to == node).map(|(_, m)| m)
+}
+
+#[tokio::test]
+async fn mailbox_quota_read_state_and_delete() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut storage = Storage::new(&tmp.path().to_string_lossy()).await.unwrap();
+    storage
+        .register_user("alice", "password123", None)
+        .await
+        .unwrap();
+    storage
+        .register_user("bob", "password123", None)
+        .await
+        .unwrap();
+    storage.set_mail_quota(2);
+
+    assert!(storage.send_mail("alice", "nobody", "hi").await.is_err());
+    assert!(storage.send_mail("alice", "alice", "hi").await.is_err());
+
+    let first = storage.send_mail("alice", "bob", "first").await.unwrap();
+    storage.send_mail("alice", "bob", "second").await.unwrap();
+    let err = storage
+        .send_mail("alice", "bob", "third")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Mailbox full"), "{}", err);
+
+    let inbox = storage.get_mail("bob", MailFolder::Inbox).await.unwrap();
+    assert_eq!(inbox.len(), 2);
+    assert_eq!(inbox[0].content, "second", "newest first");
+    assert_eq!(storage.count_unread_mail("bob").await.unwrap(), 2);
+    assert_eq!(
+        storage
+            .get_mail("alice", MailFolder::Sent)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert!(storage.mark_mail_read("bob", &first).await.unwrap());
+    assert_eq!(storage.count_unread_mail("bob").await.unwrap(), 1);
+
+    assert!(storage
+        .delete_mail("bob", MailFolder::Inbox, &first)
+        .await
+        .unwrap());
+    assert!(!storage
+        .delete_mail("bob", MailFolder::Inbox, &first)
+        .await
+        .unwrap());
+    // Sender's copy is independent of the recipient's inbox
+    assert_eq!(
+        storage
+            .get_mail("alice", MailFolder::Sent)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    storage.send_mail("alice", "bob", "third").await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compose_read_reply_and_login_banner() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.storage.mail_quota = 20;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server.test_register("alice", "password123").await.unwrap();
+    server.test_register("bob", "password123").await.unwrap();
+
+    let (a, b) = ("node_a", "node_b");
+    server
+        .route_test_text_direct(a, "LOGIN alice")
+        .await
+        .unwrap();
+    server.route_test_text_direct(a, "E").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Inbox 0/20 (0 new)"), "{}", m);
+
+    server.route_test_text_direct(a, "C").await.unwrap();
+    server.route_test_text_direct(a, "carol").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("No such user: carol"), "{}", m);
+
+    server.route_test_text_direct(a, "bob").await.unwrap();
+    let body = "Meet at the repeater site on Saturday. ".repeat(5);
+    server.route_test_text_direct(a, body.trim()).await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Mail sent to bob."), "{}", m);
+
+    server.route_test_text_direct(b, "LOGIN bob").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("You have 1 unread mail (E)."), "{}", m);
+
+    server.route_test_text_direct(b, "E").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("1 alice: Meet at the repeater…*"), "{}", m);
+
+    server.route_test_text_direct(b, "1").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("[Mail] From alice"), "{}", m);
+    assert!(m.contains("s1/3"), "{}", m);
+
+    server.route_test_text_direct(b, "+").await.unwrap();
+    server.route_test_text_direct(b, "+").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("s3/3"), "{}", m);
+    server.route_test_text_direct(b, "+").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("End of mail"), "{}", m);
+
+    server.route_test_text_direct(b, "R").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("Mail to alice"), "{}", m);
+    server
+        .route_test_text_direct(b, "See you there")
+        .await
+        .unwrap();
+
+    server.route_test_text_direct(b, "1").await.unwrap();
+    server.route_test_text_direct(b, "D").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("Deleted.") && m.contains("No mail."), "{}", m);
+
+    server.route_test_text_direct(b, "S").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("1 >alice: See you there"), "{}", m);
+
+    // Q leaves mail for the main menu from the list as well as the read view
+    server.route_test_text_direct(b, "Q").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("Main Menu:"), "{}", m);
+    server.route_test_text_direct(b, "E").await.unwrap();
+    server.route_test_text_direct(b, "S").await.unwrap();
+    server.route_test_text_direct(b, "1").await.unwrap();
+    server.route_test_text_direct(b, "Q").await.unwrap();
+    let m = last_for_node(server.test_messages(), b).unwrap();
+    assert!(m.contains("Main Menu:"), "{}", m);
+}
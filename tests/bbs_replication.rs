@@ -0,0 +1,178 @@
+//! Integration tests for store-and-forward topic replication.
+//!
+//! Two servers exchange sync frames by shuttling each other's outbound test
+//! messages, covering inventory, HAVE/WANT negotiation, chunked message
+//! transfer, deduplication and the per-topic opt-in.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::storage::Storage;
+
+const NODE_A: u32 = 0x1111;
+const NODE_B: u32 = 0x2222;
+
+async fn peer_server(dir: &tempfile::TempDir, peer: u32, sync_topics: &[&str]) -> BbsServer {
+    let data_dir = dir.path().to_string_lossy().to_string();
+    {
+        let mut storage = Storage::new(&data_dir).await.unwrap();
+        for topic in ["general", "local"] {
+            storage
+                .create_topic(topic, topic, "test", 0, 0, "sysop")
+                .await
+                .unwrap();
+        }
+        for topic in sync_topics {
+            storage.set_topic_replication(topic, true).await.unwrap();
+        }
+    }
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = data_dir;
+    cfg.replication.enabled = true;
+    cfg.replication.peers = vec![format!("!{:08x}", peer)];
+    BbsServer::new(cfg).await.expect("server")
+}
+
+/// Deliver every frame `from` has queued for `to_id` since `seen`, returning the new cursor
+async fn shuttle(
+    from: &BbsServer,
+    from_id: u32,
+    to: &mut BbsServer,
+    to_id: u32,
+    seen: usize,
+) -> usize {
+    let frames: Vec<String> = from.test_messages()[seen..]
+        .iter()
+        .filter(|(node, _)| *node == to_id.to_string())
+        .map(|(_, frame)| frame.clone())
+        .collect();
+    for frame in &frames {
+        assert!(frame.starts_with("~SY1 "), "{}", frame);
+        assert!(frame.len() <= 200, "frame too long: {}", frame.len());
+        to.test_replication_frame(from_id, frame).await.unwrap();
+    }
+    from.test_messages().len()
+}
+
+/// Pump frames back and forth until both sides go quiet
+async fn converge(a: &mut BbsServer, b: &mut BbsServer) {
+    let (mut seen_a, mut seen_b) = (0, 0);
+    for _ in 0..10 {
+        let (len_a, len_b) = (a.test_messages().len(), b.test_messages().len());
+        if seen_a == len_a && seen_b == len_b {
+            return;
+        }
+        seen_a = shuttle(a, NODE_A, b, NODE_B, seen_a).await;
+        seen_b = shuttle(b, NODE_B, a, NODE_A, seen_b).await;
+    }
+    panic!("replication did not converge");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn topics_sync_both_ways_and_dedupe() {
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    let mut a = peer_server(&dir_a, NODE_B, &["general"]).await;
+    let mut b = peer_server(&dir_b, NODE_A, &["general"]).await;
+
+    let long = "Net check-in tonight at 8pm on the county repeater. ".repeat(4);
+    a.test_store_message("general", "alice", long.trim())
+        .await
+        .unwrap();
+    a.test_store_message("general", "alice", "Short note from A")
+        .await
+        .unwrap();
+    a.test_store_message("local", "alice", "Stays on A")
+        .await
+        .unwrap();
+    b.test_store_message("general", "bob", "Hello from B")
+        .await
+        .unwrap();
+
+    a.test_replication_round().await.unwrap();
+    b.test_replication_round().await.unwrap();
+    converge(&mut a, &mut b).await;
+
+    for server in [&a, &b] {
+        let general = server.test_get_messages("general", 10).await.unwrap();
+        assert_eq!(general.len(), 3, "{:?}", general);
+        assert!(general
+            .iter()
+            .any(|m| m.author == "alice" && m.content == long.trim()));
+        assert!(general.iter().any(|m| m.author == "bob"));
+    }
+    assert!(b.test_get_messages("local", 10).await.unwrap().is_empty());
+
+    let original = a.test_get_messages("general", 10).await.unwrap();
+    let copy = b.test_get_messages("general", 10).await.unwrap();
+    for m in original.iter().filter(|m| m.author == "alice") {
+        let twin = copy
+            .iter()
+            .find(|c| c.message_id == m.message_id)
+            .expect("replicated copy");
+        assert_eq!(twin.timestamp, m.timestamp);
+        assert_eq!(twin.crc16, m.crc16);
+    }
+    assert_eq!(b.test_replication_stats().messages_imported, 2);
+    assert_eq!(b.test_replication_stats().crc_failures, 0);
+
+    // A second round finds matching digests and moves no messages
+    let sent_before = a.test_messages().len();
+    a.test_replication_frame(NODE_B, "~SY1 INV general:3:0000")
+        .await
+        .unwrap();
+    let replies: Vec<_> = a.test_messages()[sent_before..].to_vec();
+    assert!(replies
+        .iter()
+        .all(|(_, f)| f.starts_with("~SY1 HAVE general ")));
+    let sent_before = b.test_messages().len();
+    for (_, frame) in &replies {
+        b.test_replication_frame(NODE_A, frame).await.unwrap();
+    }
+    assert_eq!(b.test_messages().len(), sent_before, "nothing is wanted");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn corrupt_and_unknown_peer_frames_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut a = peer_server(&dir, NODE_B, &["general"]).await;
+
+    assert!(a
+        .test_replication_frame(0x9999, "~SY1 INV general:1:0001")
+        .await
+        .is_err());
+
+    let forged = r#"{"id":"0123456789ab","topic":"general","author":"mallory","content":"hi","ts":"2025-01-01T00:00:00Z","crc":1}"#;
+    a.test_replication_frame(NODE_B, &format!("~SY1 MSG 0123456789ab 1/1 {}", forged))
+        .await
+        .unwrap();
+    assert!(a.test_get_messages("general", 10).await.unwrap().is_empty());
+    assert_eq!(a.test_replication_stats().crc_failures, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn muted_authors_are_not_imported() {
+    let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+    {
+        let mut storage = Storage::new(&dir_b.path().to_string_lossy()).await.unwrap();
+        storage
+            .mute_user("alice", Some("general"), "sysop", None, None)
+            .await
+            .unwrap();
+    }
+    let mut a = peer_server(&dir_a, NODE_B, &["general"]).await;
+    let mut b = peer_server(&dir_b, NODE_A, &["general"]).await;
+
+    a.test_store_message("general", "alice", "Muted on B")
+        .await
+        .unwrap();
+    a.test_store_message("general", "carol", "Fine everywhere")
+        .await
+        .unwrap();
+
+    a.test_replication_round().await.unwrap();
+    b.test_replication_round().await.unwrap();
+    converge(&mut a, &mut b).await;
+
+    let general = b.test_get_messages("general", 10).await.unwrap();
+    assert_eq!(general.len(), 1, "{:?}", general);
+    assert_eq!(general[0].author, "carol");
+    assert_eq!(b.test_replication_stats().rejected, 1);
+}
@@ -0,0 +1,188 @@
+//! Integration tests for the global SEARCH command.
+//!
+//! Covers ranking and index maintenance in storage (replies, title edits,
+//! deletes, rebuild) and the flat-mode flow: results paging, read level
+//! filtering, jumping into a thread and returning to the hit list.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::storage::Storage;
+
+fn last_for_node<'a>(msgs: &'a [(String, String)], node: &str) -> Option<&'a String> {
+    msgs.iter().rev().find(|(to, _)| to == node).map(|(_, m)| m)
+}
+
+#[tokio::test]
+async fn search_ranks_and_follows_edits() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut storage = Storage::new(&tmp.path().to_string_lossy()).await.unwrap();
+    for topic in ["general", "radio"] {
+        storage
+            .create_topic(topic, topic, "", 0, 0, "sysop")
+            .await
+            .unwrap();
+    }
+    let body_hit = storage
+        .store_message(
+            "general",
+            "alice",
+            "Weekend plans\n\nBring the antenna mast",
+        )
+        .await
+        .unwrap();
+    let title_hit = storage
+        .store_message("radio", "bob", "Antenna tuning\n\nSWR notes")
+        .await
+        .unwrap();
+    storage
+        .store_message("general", "carol", "Coffee\n\nNothing to see")
+        .await
+        .unwrap();
+
+    let all = |_: &str| true;
+    let hits = storage.search_messages("antenna", all, 10).await.unwrap();
+    let ids: Vec<_> = hits.iter().map(|h| h.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![title_hit.as_str(), body_hit.as_str()],
+        "title ranks first"
+    );
+    assert_eq!(hits[0].title, "Antenna tuning");
+
+    // Prefixes match, every word must match, readable topics only
+    assert_eq!(
+        storage
+            .search_messages("ant mast", all, 10)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(storage
+        .search_messages("antenna coffee", all, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    let general_only = |t: &str| t == "general";
+    assert_eq!(
+        storage
+            .search_messages("antenna", general_only, 10)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Replies and title edits are searchable; deletes drop out
+    storage
+        .append_reply("general", &body_hit, "dave", "Try the dipole instead")
+        .await
+        .unwrap();
+    let hits = storage.search_messages("dipole", all, 10).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, body_hit);
+    storage
+        .set_message_title("radio", &title_hit, Some("Yagi build"))
+        .await
+        .unwrap();
+    assert_eq!(
+        storage
+            .search_messages("yagi", all, 10)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(storage.delete_message("general", &body_hit).await.unwrap());
+    assert!(storage
+        .search_messages("dipole", all, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Rebuilding the index keeps results identical
+    storage.rebuild_message_index().await.unwrap();
+    assert_eq!(
+        storage.search_messages("swr", all, 10).await.unwrap().len(),
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn search_flow_respects_read_levels() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    {
+        let mut storage = Storage::new(&data_dir).await.unwrap();
+        storage
+            .create_topic("general", "General", "", 0, 0, "sysop")
+            .await
+            .unwrap();
+        storage
+            .create_topic("staff", "Staff", "", 5, 0, "sysop")
+            .await
+            .unwrap();
+    }
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = data_dir;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server.test_register("alice", "password123").await.unwrap();
+    for i in 0..7 {
+        server
+            .test_store_message(
+                "general",
+                "bob",
+                &format!("Repeater log {}\n\nAll quiet", i),
+            )
+            .await
+            .unwrap();
+    }
+    server
+        .test_store_message("staff", "sysop", "Repeater keys\n\nSecret")
+        .await
+        .unwrap();
+
+    let a = "node_a";
+    server
+        .route_test_text_direct(a, "LOGIN alice")
+        .await
+        .unwrap();
+    server.route_test_text_direct(a, "SEARCH").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Usage: SEARCH"), "{}", m);
+
+    server
+        .route_test_text_direct(a, "search repeater")
+        .await
+        .unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Search: 7 hits"), "{}", m);
+    assert!(!m.contains("keys"), "staff topic is hidden: {}", m);
+    assert!(m.len() <= 200, "{}", m.len());
+
+    server.route_test_text_direct(a, "L").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(
+        m.contains("1 general:Repeater log") && !m.contains("3 "),
+        "{}",
+        m
+    );
+
+    server.route_test_text_direct(a, "2").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("[BBS][General > Repeater log 0]"), "{}", m);
+    assert!(m.contains("All quiet"), "{}", m);
+
+    server.route_test_text_direct(a, "B").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Search: 7 hits"), "{}", m);
+
+    server
+        .route_test_text_direct(a, "SEARCH nothing")
+        .await
+        .unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("No hits for \"nothing\""), "{}", m);
+    server.route_test_text_direct(a, "B").await.unwrap();
+    let m = last_for_node(server.test_messages(), a).unwrap();
+    assert!(m.contains("Topics"), "{}", m);
+}
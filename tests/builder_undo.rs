@@ -0,0 +1,167 @@
+//! Builder undo/redo, edit log and rollback (@UNDO, @REDO, @EDITLOG, @ROLLBACK).
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::edit_log;
+use meshbbs::tmush::types::{Direction, RoomFlag};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    for (name, level) in [("alice", 3), ("bob", 2)] {
+        let mut player = PlayerRecord::new(name, name, "town_square");
+        player.grant_builder(level);
+        store.put_player(player).unwrap();
+    }
+    (store, config)
+}
+
+fn square_has(store: &TinyMushStore, flag: RoomFlag) -> bool {
+    store.get_room("town_square").unwrap().flags.contains(&flag)
+}
+
+#[tokio::test]
+async fn undo_and_redo_step_through_own_edits() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp);
+
+    let out = run(&store, &config, "alice", "/DIG up Bell Tower").await;
+    assert!(out.contains("✅"), "{}", out);
+    let tower = store.get_room("town_square").unwrap().exits[&Direction::Up].clone();
+    assert!(store.find_room_any_owner(&tower).unwrap().is_some());
+    run(&store, &config, "alice", "/SETFLAG here dark").await;
+    assert!(square_has(&store, RoomFlag::Dark));
+
+    // Newest first
+    let out = run(&store, &config, "alice", "@UNDO").await;
+    assert!(out.contains("/SETFLAG here DARK"), "{}", out);
+    assert!(!square_has(&store, RoomFlag::Dark));
+    let out = run(&store, &config, "alice", "@UNDO").await;
+    assert!(out.contains("/DIG"), "{}", out);
+    assert!(store.find_room_any_owner(&tower).unwrap().is_none());
+    assert!(!store
+        .get_room("town_square")
+        .unwrap()
+        .exits
+        .contains_key(&Direction::Up));
+    assert_eq!(
+        run(&store, &config, "alice", "@UNDO").await,
+        "Nothing to undo."
+    );
+
+    // Redo replays in the original order
+    let out = run(&store, &config, "alice", "@REDO").await;
+    assert!(out.contains("/DIG"), "{}", out);
+    assert!(store.find_room_any_owner(&tower).unwrap().is_some());
+    let out = run(&store, &config, "alice", "@REDO").await;
+    assert!(out.contains("/SETFLAG"), "{}", out);
+    assert!(square_has(&store, RoomFlag::Dark));
+    assert_eq!(
+        run(&store, &config, "alice", "@REDO").await,
+        "Nothing to redo."
+    );
+
+    // A fresh edit ends the redo run
+    run(&store, &config, "alice", "@UNDO").await;
+    run(&store, &config, "alice", "/SETFLAG here private").await;
+    assert_eq!(
+        run(&store, &config, "alice", "@REDO").await,
+        "Nothing to redo."
+    );
+}
+
+#[tokio::test]
+async fn undo_refuses_when_someone_changed_the_record_since() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp);
+
+    run(&store, &config, "alice", "/SETFLAG here dark").await;
+    let mut room = store.get_room("town_square").unwrap();
+    room.name = "Renamed".into();
+    store.put_room(room).unwrap();
+
+    let out = run(&store, &config, "alice", "@UNDO").await;
+    assert!(
+        out.contains("room town_square has changed since"),
+        "{}",
+        out
+    );
+    assert!(square_has(&store, RoomFlag::Dark));
+}
+
+#[tokio::test]
+async fn edit_log_filters_and_pages() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp);
+
+    for flag in ["dark", "crowded", "moderated", "private"] {
+        run(&store, &config, "alice", &format!("/SETFLAG here {}", flag)).await;
+    }
+    run(&store, &config, "bob", "/DIG northeast Shed").await;
+
+    let first = run(&store, &config, "alice", "@EDITLOG here").await;
+    assert!(first.len() <= 200, "{} bytes: {}", first.len(), first);
+    assert!(first.starts_with("Edits town_square p1/"), "{}", first);
+    assert!(first.contains("@EDITLOG here 2 for more"), "{}", first);
+
+    let mine = run(&store, &config, "alice", "@EDITLOG alice 1").await;
+    assert!(mine.contains("alice /SETFLAG here PRIVATE"), "{}", mine);
+    assert!(!mine.contains("bob"), "{}", mine);
+
+    // Level 2 builders only see their own history
+    let own = run(&store, &config, "bob", "@EDITLOG").await;
+    assert!(own.starts_with("Edits bob p1/1:"), "{}", own);
+    assert!(own.contains("/DIG NORTHEAST SHED"), "{}", own);
+    let denied = run(&store, &config, "bob", "@EDITLOG alice").await;
+    assert!(denied.contains("Permission denied"), "{}", denied);
+}
+
+#[tokio::test]
+async fn sysop_rolls_back_a_builder_window() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp);
+
+    run(&store, &config, "bob", "/DIG northeast Shed").await;
+    run(&store, &config, "alice", "/SETFLAG here dark").await;
+    run(&store, &config, "alice", "/SETFLAG here crowded").await;
+
+    let denied = run(&store, &config, "bob", "@ROLLBACK alice 1h").await;
+    assert!(denied.contains("Permission denied"), "{}", denied);
+
+    let out = run(&store, &config, "admin", "@ROLLBACK alice 1h").await;
+    assert!(out.contains("Rolled back 2 edits by alice"), "{}", out);
+    assert!(!square_has(&store, RoomFlag::Dark));
+    assert!(!square_has(&store, RoomFlag::Crowded));
+    // Bob's earlier exit is left alone
+    assert!(store
+        .get_room("town_square")
+        .unwrap()
+        .exits
+        .contains_key(&Direction::Northeast));
+    let edits = store.list_world_edits().unwrap();
+    assert!(edits
+        .iter()
+        .filter(|e| e.builder == "alice")
+        .all(|e| e.undone_by.as_deref() == Some("admin")));
+
+    // The sysop's rollback is not on alice's redo run
+    assert!(edit_log::next_redo(&store, "alice").unwrap().is_none());
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Multi-channel public commands: each `[[meshtastic.channels]]` entry has its own
+//! prefix, enabled commands and broadcast policy, and replies go out on the
+//! channel the request arrived on.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::{ChannelBroadcast, ChannelConfig, Config};
+use meshbbs::meshtastic::{OutgoingMessage, TextEvent};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::time::{sleep, Duration};
+
+fn public(source: u32, channel: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: None,
+        is_direct: false,
+        channel: Some(channel),
+        content: content.into(),
+    }
+}
+
+/// Everything queued for the radio since the last call
+fn drain(rx: &mut UnboundedReceiver<OutgoingMessage>) -> Vec<OutgoingMessage> {
+    let mut out = Vec::new();
+    while let Ok(msg) = rx.try_recv() {
+        out.push(msg);
+    }
+    out
+}
+
+async fn setup() -> (
+    BbsServer,
+    UnboundedReceiver<OutgoingMessage>,
+    tempfile::TempDir,
+) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.welcome.enabled = false;
+    cfg.meshtastic.help_broadcast_delay_ms = Some(100);
+    cfg.meshtastic.min_send_gap_ms = Some(10);
+    cfg.meshtastic.post_dm_broadcast_gap_ms = Some(10);
+    cfg.meshtastic.channels = vec![
+        ChannelConfig {
+            index: 2,
+            commands: Some(vec!["help".into(), "SEEN".into(), "8BALL".into()]),
+            prefix: Some("!".into()),
+            help_command: None,
+            broadcast: ChannelBroadcast::Public,
+        },
+        ChannelConfig {
+            index: 3,
+            commands: None,
+            prefix: None,
+            help_command: None,
+            broadcast: ChannelBroadcast::Dm,
+        },
+    ];
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    let (tx, rx) = unbounded_channel();
+    server.test_set_outgoing(tx);
+    (server, rx, tmp)
+}
+
+#[tokio::test]
+async fn commands_follow_the_policy_of_the_channel_they_arrive_on() {
+    let (mut server, mut rx, _tmp) = setup().await;
+
+    server
+        .route_text_event(public(10, 2, "!8BALL"))
+        .await
+        .unwrap();
+    let sent = drain(&mut rx);
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].to_node, None);
+    assert_eq!(sent[0].channel, 2);
+    assert!(sent[0].content.starts_with("!8BALL"), "{}", sent[0].content);
+
+    // Wrong prefix and disabled commands stay silent on channel 2
+    server
+        .route_text_event(public(11, 2, "^8BALL"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(public(12, 2, "!SLOT"))
+        .await
+        .unwrap();
+    assert!(drain(&mut rx).is_empty());
+
+    // The primary channel keeps the [bbs] prefix and every command
+    server
+        .route_text_event(public(13, 0, "^SLOT"))
+        .await
+        .unwrap();
+    let sent = drain(&mut rx);
+    assert_eq!(sent.len(), 1);
+    assert_eq!((sent[0].to_node, sent[0].channel), (None, 0));
+
+    // A DM-only channel answers the asker privately on that channel
+    server
+        .route_text_event(public(14, 3, "^FORTUNE"))
+        .await
+        .unwrap();
+    let sent = drain(&mut rx);
+    assert_eq!(sent.len(), 1);
+    assert_eq!((sent[0].to_node, sent[0].channel), (Some(14), 3));
+
+    // Channels that are not configured are ignored
+    server
+        .route_text_event(public(15, 5, "^SLOT"))
+        .await
+        .unwrap();
+    assert!(drain(&mut rx).is_empty());
+
+    // Later DMs to a node go out on the channel it was heard on
+    server
+        .route_text_event(TextEvent {
+            source: 10,
+            dest: Some(1),
+            is_direct: true,
+            channel: None,
+            content: "HI".into(),
+        })
+        .await
+        .unwrap();
+    let sent = drain(&mut rx);
+    assert!(!sent.is_empty());
+    assert!(sent.iter().all(|m| m.to_node == Some(10) && m.channel == 2));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn help_lists_only_the_commands_enabled_on_the_channel() {
+    let (mut server, mut rx, _tmp) = setup().await;
+
+    server
+        .route_text_event(public(20, 2, "!HELP"))
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(400)).await;
+    let sent = drain(&mut rx);
+    assert_eq!(sent.len(), 2, "{:?}", sent);
+    assert_eq!((sent[0].to_node, sent[0].channel), (Some(20), 2));
+    let listing = &sent[1];
+    assert_eq!((listing.to_node, listing.channel), (None, 2));
+    assert!(listing.content.contains("!HELP - Show this help"));
+    assert!(listing.content.contains("!SEEN <node>"));
+    assert!(!listing.content.contains("SLOT"), "{}", listing.content);
+    assert!(!listing.content.contains("LOGIN"), "{}", listing.content);
+
+    // The DM-only channel sends the help DM but no public listing
+    server
+        .route_text_event(public(21, 3, "^HELP"))
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(400)).await;
+    let sent = drain(&mut rx);
+    assert_eq!(sent.len(), 1, "{:?}", sent);
+    assert_eq!((sent[0].to_node, sent[0].channel), (Some(21), 3));
+}
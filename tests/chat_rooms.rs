@@ -0,0 +1,184 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Teleconference chat: entering rooms from the main menu, fanout to other members,
+//! scrollback for latecomers and moderator-controlled rooms.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+
+const ALICE_NODE: u32 = 100;
+const BOB_NODE: u32 = 200;
+const CAROL_NODE: u32 = 300;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Last message the server sent to `node`
+fn last_to(server: &BbsServer, node: u32) -> String {
+    server
+        .test_messages()
+        .iter()
+        .rev()
+        .find(|(to, _)| *to == node.to_string())
+        .map(|(_, m)| m.clone())
+        .unwrap_or_default()
+}
+
+fn sent_count(server: &BbsServer, node: u32) -> usize {
+    server
+        .test_messages()
+        .iter()
+        .filter(|(to, _)| *to == node.to_string())
+        .count()
+}
+
+async fn setup() -> (BbsServer, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.welcome.enabled = false;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    for (node, user) in [
+        (ALICE_NODE, "alice"),
+        (BOB_NODE, "bob"),
+        (CAROL_NODE, "carol"),
+    ] {
+        server.test_register(user, "password123").await.unwrap();
+        if user == "carol" {
+            server.test_update_level(user, 5).await.unwrap();
+        }
+        server
+            .route_text_event(dm(node, &format!("LOGIN {} password123", user)))
+            .await
+            .unwrap();
+    }
+    (server, tmp)
+}
+
+#[tokio::test]
+async fn members_hear_each_other_and_latecomers_get_scrollback() {
+    let (mut server, _tmp) = setup().await;
+
+    server.route_text_event(dm(ALICE_NODE, "C")).await.unwrap();
+    let entered = last_to(&server, ALICE_NODE);
+    assert!(
+        entered.starts_with("[Chat] #lobby: nobody else here\n"),
+        "{}",
+        entered
+    );
+    assert!(entered.ends_with("alice@chat/lobby>"), "{}", entered);
+
+    // Speaking is not echoed back to the speaker
+    let before = sent_count(&server, ALICE_NODE);
+    server
+        .route_text_event(dm(ALICE_NODE, "anyone on 2m tonight?"))
+        .await
+        .unwrap();
+    assert_eq!(sent_count(&server, ALICE_NODE), before);
+
+    server
+        .route_text_event(dm(BOB_NODE, "CHAT #Lobby"))
+        .await
+        .unwrap();
+    let joined = last_to(&server, BOB_NODE);
+    assert!(
+        joined.starts_with("[Chat] #lobby: alice\n<alice> anyone on 2m tonight?\n"),
+        "{}",
+        joined
+    );
+    assert_eq!(last_to(&server, ALICE_NODE), "* bob joined");
+
+    server
+        .route_text_event(dm(BOB_NODE, "yes, 146.52"))
+        .await
+        .unwrap();
+    assert_eq!(last_to(&server, ALICE_NODE), "<bob> yes, 146.52");
+    server
+        .route_text_event(dm(BOB_NODE, "/me waves"))
+        .await
+        .unwrap();
+    assert_eq!(last_to(&server, ALICE_NODE), "* bob waves");
+
+    server
+        .route_text_event(dm(ALICE_NODE, "/who"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, ALICE_NODE).starts_with("#lobby: alice, bob\n"));
+
+    server
+        .route_text_event(dm(ALICE_NODE, "/join radio"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, ALICE_NODE).starts_with("[Chat] #radio: nobody else here\n"));
+    assert_eq!(last_to(&server, BOB_NODE), "* alice left");
+    server
+        .route_text_event(dm(ALICE_NODE, "/rooms"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, ALICE_NODE).starts_with("Rooms: #lobby(1) #radio(1)\n"));
+
+    server
+        .route_text_event(dm(ALICE_NODE, "/leave"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, ALICE_NODE).starts_with("Left #radio.\nMain Menu:\n"));
+    // Back at the main menu, plain input is a BBS command again
+    server.route_text_event(dm(ALICE_NODE, "M")).await.unwrap();
+    assert!(!last_to(&server, BOB_NODE).contains("<alice>"));
+}
+
+#[tokio::test]
+async fn moderators_can_silence_a_room_and_remove_members() {
+    let (mut server, _tmp) = setup().await;
+    for node in [ALICE_NODE, BOB_NODE, CAROL_NODE] {
+        server.route_text_event(dm(node, "C")).await.unwrap();
+    }
+
+    server
+        .route_text_event(dm(ALICE_NODE, "/mod"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, ALICE_NODE).starts_with("Permission denied.\n"));
+
+    server
+        .route_text_event(dm(CAROL_NODE, "/mod"))
+        .await
+        .unwrap();
+    assert_eq!(
+        last_to(&server, BOB_NODE),
+        "* #lobby is moderated by carol: only moderators speak"
+    );
+    server
+        .route_text_event(dm(BOB_NODE, "but I have a question"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, BOB_NODE).starts_with("#lobby is moderated; only moderators speak.\n"));
+    server
+        .route_text_event(dm(CAROL_NODE, "Net starts now"))
+        .await
+        .unwrap();
+    assert_eq!(last_to(&server, BOB_NODE), "<carol> Net starts now");
+
+    server
+        .route_text_event(dm(CAROL_NODE, "/kick bob"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, CAROL_NODE).starts_with("Removed bob from #lobby.\n"));
+    assert!(last_to(&server, BOB_NODE).starts_with("You were removed from #lobby by carol.\n"));
+    assert_eq!(last_to(&server, ALICE_NODE), "* bob was removed by carol");
+
+    // Bob is back at the main menu and no longer hears the room
+    let before = sent_count(&server, BOB_NODE);
+    server
+        .route_text_event(dm(CAROL_NODE, "/mod"))
+        .await
+        .unwrap();
+    assert_eq!(last_to(&server, ALICE_NODE), "* #lobby is open again");
+    assert_eq!(sent_count(&server, BOB_NODE), before);
+}
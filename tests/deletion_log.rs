@@ -54,6 +54,7 @@ async fn base_config(dir: &str) -> Config {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     }
 }
 
@@ -0,0 +1,148 @@
+//! Per-party dungeon instances (@DUNGEON, DUNGEON, instance routing and reaping).
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::dungeon;
+use meshbbs::tmush::types::{Direction, RoomFlag};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+/// Store with a one-room dungeon behind the town square's down exit
+async fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    let mut architect = PlayerRecord::new("alice", "alice", "town_square");
+    architect.grant_builder(3);
+    store.put_player(architect).unwrap();
+    for name in ["bob", "carol", "dave"] {
+        store
+            .put_player(PlayerRecord::new(name, name, "town_square"))
+            .unwrap();
+    }
+
+    let out = run(
+        &store,
+        &config,
+        "alice",
+        "@DUNGEON CREATE tunnels maintenance_tunnels",
+    )
+    .await;
+    assert!(out.contains("✅ Dungeon 'tunnels' created"), "{}", out);
+    (store, config)
+}
+
+fn room_of(store: &TinyMushStore, user: &str) -> String {
+    store.get_player(user).unwrap().current_room
+}
+
+#[tokio::test]
+async fn each_party_gets_its_own_copy() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    let denied = run(&store, &config, "bob", "@DUNGEON CREATE mine town_square").await;
+    assert!(denied.contains("Permission denied"), "{}", denied);
+
+    run(&store, &config, "bob", "D").await;
+    run(&store, &config, "carol", "D").await;
+    let bobs = room_of(&store, "bob");
+    let carols = room_of(&store, "carol");
+    assert!(dungeon::is_instance_copy(&bobs), "{}", bobs);
+    assert!(dungeon::is_instance_copy(&carols), "{}", carols);
+    assert_ne!(bobs, carols);
+
+    // Exits inside the template point at the copy, the way out is unchanged
+    let copy = store.get_room(&bobs).unwrap();
+    assert_eq!(copy.exits[&Direction::Down], bobs);
+    assert_eq!(copy.exits[&Direction::North], "town_square");
+    assert!(copy.flags.contains(&RoomFlag::Instanced));
+    assert!(!store
+        .get_room("maintenance_tunnels")
+        .unwrap()
+        .flags
+        .contains(&RoomFlag::Instanced));
+
+    // Leaving and coming back lands in the same instance
+    run(&store, &config, "bob", "N").await;
+    assert_eq!(room_of(&store, "bob"), "town_square");
+    run(&store, &config, "bob", "D").await;
+    assert_eq!(room_of(&store, "bob"), bobs);
+    assert_eq!(store.list_dungeon_instances().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn invited_players_join_the_leaders_instance() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    run(&store, &config, "bob", "D").await;
+    let bobs = room_of(&store, "bob");
+
+    let out = run(&store, &config, "bob", "DUNGEON INVITE carol").await;
+    assert!(out.contains("joined your party"), "{}", out);
+    run(&store, &config, "carol", "D").await;
+    assert_eq!(room_of(&store, "carol"), bobs);
+
+    // Party size is capped by the template
+    run(&store, &config, "alice", "@DUNGEON PARTY tunnels 2").await;
+    let full = run(&store, &config, "bob", "DUNGEON INVITE dave").await;
+    assert!(full.contains("party is full"), "{}", full);
+
+    // The last member out closes the instance
+    let out = run(&store, &config, "bob", "DUNGEON LEAVE").await;
+    assert!(out.contains("You leave your dungeon party"), "{}", out);
+    assert_eq!(room_of(&store, "bob"), "town_square");
+    assert_eq!(store.list_dungeon_instances().unwrap().len(), 1);
+    run(&store, &config, "carol", "DUNGEON LEAVE").await;
+    assert!(store.list_dungeon_instances().unwrap().is_empty());
+    assert!(store.find_room_any_owner(&bobs).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn instance_cap_turns_new_parties_away() {
+    let tmp = TempDir::new().unwrap();
+    let (store, mut config) = setup(&tmp).await;
+    config.games.tinymush_dungeon_max_instances = Some(1);
+
+    run(&store, &config, "bob", "D").await;
+    let out = run(&store, &config, "carol", "D").await;
+    assert!(out.contains("Too many parties"), "{}", out);
+    assert_eq!(room_of(&store, "carol"), "town_square");
+}
+
+#[tokio::test]
+async fn idle_instances_are_reaped() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    run(&store, &config, "bob", "D").await;
+    let bobs = room_of(&store, "bob");
+    let now = chrono::Utc::now();
+    assert!(
+        dungeon::reap_idle(&store, chrono::Duration::minutes(30), now)
+            .unwrap()
+            .is_empty()
+    );
+
+    let reaped = dungeon::reap_idle(&store, chrono::Duration::zero(), now).unwrap();
+    assert_eq!(reaped.len(), 1);
+    assert_eq!(room_of(&store, "bob"), "town_square");
+    assert!(store.find_room_any_owner(&bobs).unwrap().is_none());
+    assert!(store.list_dungeon_instances().unwrap().is_empty());
+}
@@ -0,0 +1,207 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Text file library: areas with read levels, size limits, download counts and
+//! the [F]ile browsing flow with sliced reading.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+use meshbbs::storage::files::MAX_LIBRARY_FILE_BYTES;
+use meshbbs::storage::Storage;
+
+const SYSOP_NODE: u32 = 100;
+const USER_NODE: u32 = 200;
+const MOD_NODE: u32 = 300;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Last message the server sent to `node`
+fn last_to(server: &BbsServer, node: u32) -> String {
+    server
+        .test_messages()
+        .iter()
+        .rev()
+        .find(|(to, _)| *to == node.to_string())
+        .map(|(_, m)| m.clone())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn areas_enforce_size_limits_and_count_downloads() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    let storage = Storage::new(&data_dir).await.unwrap();
+
+    // Files need an existing area
+    assert!(storage
+        .add_library_file("radio", "freqs", "146.520 simplex", false, "sysop")
+        .await
+        .is_err());
+    assert!(storage
+        .set_file_area("radio", Some("Frequencies"), None, "sysop")
+        .await
+        .unwrap());
+    assert!(!storage
+        .set_file_area("radio", None, Some(1), "sysop")
+        .await
+        .unwrap());
+    assert!(storage
+        .set_file_area("../etc", None, None, "sysop")
+        .await
+        .is_err());
+
+    storage
+        .add_library_file("radio", "Freqs", "146.520 simplex", false, "sysop")
+        .await
+        .unwrap();
+    let file = storage
+        .add_library_file("radio", "freqs", "446.000 UHF calling", true, "sysop")
+        .await
+        .unwrap();
+    assert_eq!(file.name, "freqs");
+    assert_eq!(
+        storage.read_library_file("radio", "freqs").await.unwrap(),
+        "146.520 simplex\n446.000 UHF calling"
+    );
+    assert_eq!(file.size, 35);
+
+    let too_big = "x".repeat(MAX_LIBRARY_FILE_BYTES as usize + 1);
+    assert!(storage
+        .add_library_file("radio", "big", &too_big, false, "sysop")
+        .await
+        .is_err());
+    // Appending past the limit is refused and leaves the file as it was
+    let almost = "y".repeat(MAX_LIBRARY_FILE_BYTES as usize - 20);
+    assert!(storage
+        .add_library_file("radio", "freqs", &almost, true, "sysop")
+        .await
+        .is_err());
+    assert_eq!(
+        storage
+            .read_library_file("radio", "freqs")
+            .await
+            .unwrap()
+            .len(),
+        35
+    );
+
+    storage
+        .record_file_download("radio", "freqs")
+        .await
+        .unwrap();
+    storage
+        .record_file_download("radio", "freqs")
+        .await
+        .unwrap();
+    let library = storage.get_file_library().await.unwrap();
+    let area = &library.areas["radio"];
+    assert_eq!(area.description, "Frequencies");
+    assert_eq!(area.read_level, 1);
+    assert_eq!(area.files.len(), 1);
+    assert_eq!(area.files[0].downloads, 2);
+
+    assert!(storage
+        .delete_library_file("radio", "freqs", "sysop")
+        .await
+        .unwrap());
+    assert!(!storage
+        .delete_library_file("radio", "freqs", "sysop")
+        .await
+        .unwrap());
+    let audit = storage.get_admin_audit_page(1, 10).await.unwrap();
+    assert_eq!(audit[0].action, "FILEDEL");
+    assert_eq!(audit[0].target.as_deref(), Some("radio/freqs"));
+}
+
+#[tokio::test]
+async fn sysop_uploads_and_users_page_through_visible_areas() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.bbs.sysop = "keeper".into();
+    cfg.welcome.enabled = false;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server.test_register("keeper", "password123").await.unwrap();
+    server.test_register("alice", "password123").await.unwrap();
+    for (node, user) in [(SYSOP_NODE, "keeper"), (USER_NODE, "alice")] {
+        server
+            .route_text_event(dm(node, &format!("LOGIN {} password123", user)))
+            .await
+            .unwrap();
+    }
+
+    server
+        .route_text_event(dm(USER_NODE, "FILEAREA radio"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, USER_NODE).starts_with("Permission denied.\n"));
+
+    for cmd in [
+        "FILEAREA radio 0 Local frequencies",
+        "FILEAREA emcomm 5 Emergency plans",
+        "FILEADD radio nets Tuesday 19:00 on 146.520 MHz, net control rotates.",
+        "FILEAPPEND radio nets Thursday 20:00 on 446.000 MHz for the UHF check-in with all stations welcome.",
+        "FILEADD emcomm plan Muster at the fire hall.",
+    ] {
+        server
+            .route_text_event(dm(SYSOP_NODE, cmd))
+            .await
+            .unwrap();
+    }
+    assert!(last_to(&server, SYSOP_NODE).starts_with("Saved emcomm/plan ("));
+
+    server.route_text_event(dm(USER_NODE, "F")).await.unwrap();
+    let areas = last_to(&server, USER_NODE);
+    assert!(
+        areas.starts_with("[BBS] Files (1 areas)\n1 radio(1)\n"),
+        "{}",
+        areas
+    );
+
+    server.route_text_event(dm(USER_NODE, "1")).await.unwrap();
+    let list = last_to(&server, USER_NODE);
+    assert!(
+        list.starts_with("[BBS] Files > radio\nLocal frequencies\n1 nets "),
+        "{}",
+        list
+    );
+    assert!(list.contains(" 0dl"), "{}", list);
+
+    server.route_text_event(dm(USER_NODE, "1")).await.unwrap();
+    let first = last_to(&server, USER_NODE);
+    assert!(
+        first.starts_with("[BBS][radio > nets] s1/2\nTuesday"),
+        "{}",
+        first
+    );
+    server.route_text_event(dm(USER_NODE, "+")).await.unwrap();
+    let second = last_to(&server, USER_NODE);
+    assert!(
+        second.starts_with("[BBS][radio > nets] s2/2\n"),
+        "{}",
+        second
+    );
+    assert!(second.contains("all stations welcome."), "{}", second);
+    server.route_text_event(dm(USER_NODE, "+")).await.unwrap();
+    assert!(last_to(&server, USER_NODE).starts_with("End of file. B back\n"));
+
+    server.route_text_event(dm(USER_NODE, "B")).await.unwrap();
+    assert!(last_to(&server, USER_NODE).contains(" 1dl"));
+
+    // Moderators also see the level 5 area
+    server.test_register("mod", "password123").await.unwrap();
+    server.test_update_level("mod", 5).await.unwrap();
+    server
+        .route_text_event(dm(MOD_NODE, "LOGIN mod password123"))
+        .await
+        .unwrap();
+    server.route_text_event(dm(MOD_NODE, "F")).await.unwrap();
+    let mod_view = last_to(&server, MOD_NODE);
+    assert!(mod_view.contains("1 emcomm(1)  2 radio(1)"), "{}", mod_view);
+}
@@ -0,0 +1,197 @@
+//! Player guilds (GUILD, GC): membership, ranks, chat, treasury and hall.
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::types::{
+    CurrencyAmount, HousingInstance, RoomOwner, RoomRecord, TransactionReason,
+};
+use meshbbs::tmush::{PlayerRecord, RoomEventKind, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+/// Alice founds the Iron Wolves; bob and carol are invited and join
+async fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    for name in ["alice", "bob", "carol", "dave"] {
+        store
+            .put_player(PlayerRecord::new(name, name, "town_square"))
+            .unwrap();
+    }
+
+    let out = run(&store, &config, "alice", "GUILD CREATE Iron Wolves").await;
+    assert!(out.contains("You founded Iron Wolves"), "{}", out);
+    for name in ["bob", "carol"] {
+        run(&store, &config, "alice", &format!("GUILD INVITE {}", name)).await;
+        let out = run(&store, &config, name, "GUILD JOIN iron wolves").await;
+        assert!(out.contains("Welcome to Iron Wolves"), "{}", out);
+    }
+    store.room_events().drain();
+    (store, config)
+}
+
+fn rank_of(store: &TinyMushStore, user: &str) -> u8 {
+    store
+        .get_guild("iron_wolves")
+        .unwrap()
+        .member(user)
+        .unwrap()
+        .rank
+}
+
+#[tokio::test]
+async fn members_join_only_by_invitation_and_climb_ranks() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    let out = run(&store, &config, "dave", "GUILD JOIN Iron Wolves").await;
+    assert!(out.contains("You need an invitation"), "{}", out);
+    let out = run(&store, &config, "bob", "GUILD INVITE dave").await;
+    assert!(out.contains("Only Officers and up"), "{}", out);
+
+    // Officers may promote up to Member, only the leader makes officers
+    run(&store, &config, "alice", "GUILD PROMOTE bob").await;
+    run(&store, &config, "alice", "GUILD PROMOTE bob").await;
+    assert_eq!(rank_of(&store, "bob"), 2);
+    run(&store, &config, "bob", "GUILD PROMOTE carol").await;
+    assert_eq!(rank_of(&store, "carol"), 1);
+    let out = run(&store, &config, "bob", "GUILD PROMOTE carol").await;
+    assert!(out.contains("up to Member"), "{}", out);
+    let out = run(&store, &config, "bob", "GUILD KICK alice").await;
+    assert!(out.contains("ranked below Officer"), "{}", out);
+
+    // The leader can't walk out without handing over leadership
+    let out = run(&store, &config, "alice", "GUILD LEAVE").await;
+    assert!(out.contains("Promote someone to Leader"), "{}", out);
+    let out = run(&store, &config, "alice", "GUILD PROMOTE bob").await;
+    assert!(out.contains("bob is now Leader"), "{}", out);
+    assert_eq!(rank_of(&store, "alice"), 2);
+    run(&store, &config, "alice", "GUILD LEAVE").await;
+    let guild = store.get_guild("iron_wolves").unwrap();
+    assert_eq!(guild.members.len(), 2);
+    assert_eq!(guild.leader().unwrap().username, "bob");
+    assert!(store.guild_for_player("alice").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn guild_chat_reaches_every_other_member() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let mut dave = store.get_player("dave").unwrap();
+    dave.current_room = "town_square".into();
+    store.put_player(dave).unwrap();
+
+    let out = run(&store, &config, "bob", "GC Meet at the Tavern").await;
+    assert_eq!(out, "[Iron Wolves] You: Meet at the Tavern");
+    let events = store.room_events().drain();
+    let mut targets: Vec<_> = events.iter().filter_map(|e| e.target.clone()).collect();
+    targets.sort();
+    assert_eq!(targets, vec!["alice", "carol"]);
+    assert!(events
+        .iter()
+        .all(|e| e.kind == RoomEventKind::Guild("Iron Wolves".into())));
+    assert_eq!(events[0].render(), "[Iron Wolves] bob: Meet at the Tavern");
+
+    let out = run(&store, &config, "dave", "GC hello?").await;
+    assert!(out.contains("You're not in a guild"), "{}", out);
+    assert!(store.room_events().is_empty());
+}
+
+#[tokio::test]
+async fn treasury_withdrawals_are_rank_gated() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    store
+        .grant_currency(
+            "bob",
+            &CurrencyAmount::decimal(500),
+            TransactionReason::AdminGrant,
+        )
+        .unwrap();
+
+    let out = run(&store, &config, "bob", "GUILD DEPOSIT 300").await;
+    assert!(out.contains("Deposited 300"), "{}", out);
+    assert_eq!(
+        store
+            .get_guild("iron_wolves")
+            .unwrap()
+            .treasury
+            .base_value(),
+        300
+    );
+    assert_eq!(store.get_player("bob").unwrap().currency.base_value(), 200);
+    let out = run(&store, &config, "bob", "GUILD DEPOSIT 900").await;
+    assert!(out.contains("don't have that much"), "{}", out);
+
+    // Officers and up by default
+    let out = run(&store, &config, "bob", "GUILD WITHDRAW 50").await;
+    assert!(out.contains("Only Officers and up"), "{}", out);
+    let out = run(&store, &config, "alice", "GUILD WITHDRAW 50").await;
+    assert!(out.contains("now 250"), "{}", out);
+
+    let out = run(&store, &config, "bob", "GUILD BANK RANK recruit").await;
+    assert!(out.contains("Only the Leader"), "{}", out);
+    run(&store, &config, "alice", "GUILD BANK RANK recruit").await;
+    let out = run(&store, &config, "carol", "GUILD BANK WITHDRAW 250").await;
+    assert!(out.contains("now 0"), "{}", out);
+    assert_eq!(
+        store.get_player("carol").unwrap().currency.base_value(),
+        250
+    );
+}
+
+#[tokio::test]
+async fn hall_guest_list_follows_membership() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let mut room = RoomRecord::world("alice_flat", "Alice's Flat", "A flat.", "A small flat.");
+    room.owner = RoomOwner::Player {
+        username: "alice".into(),
+    };
+    store.put_room(room).unwrap();
+    let mut hall = HousingInstance::new("alice_flat", "studio_apartment", "alice", "alice_flat");
+    hall.room_mappings
+        .insert("main_room".into(), "alice_flat".into());
+    store.put_housing_instance(&hall).unwrap();
+
+    let out = run(&store, &config, "alice", "GUILD HALL SET").await;
+    assert!(out.contains("Stand in your own housing"), "{}", out);
+    let mut alice = store.get_player("alice").unwrap();
+    alice.current_room = hall.entry_room_id.clone();
+    store.put_player(alice).unwrap();
+    let out = run(&store, &config, "alice", "GUILD HALL SET").await;
+    assert!(out.contains("This is now the Iron Wolves hall"), "{}", out);
+
+    let guests = |store: &TinyMushStore| {
+        let mut guests = store.get_housing_instance(&hall.id).unwrap().guests;
+        guests.sort();
+        guests
+    };
+    assert_eq!(guests(&store), vec!["bob", "carol"]);
+
+    run(&store, &config, "alice", "GUILD INVITE dave").await;
+    run(&store, &config, "dave", "GUILD JOIN Iron Wolves").await;
+    assert_eq!(guests(&store), vec!["bob", "carol", "dave"]);
+    run(&store, &config, "alice", "GUILD KICK carol").await;
+    assert_eq!(guests(&store), vec!["bob", "dave"]);
+    assert_eq!(store.get_guest_housing_instances("carol").unwrap().len(), 0);
+
+    run(&store, &config, "alice", "GUILD HALL CLEAR").await;
+    assert!(guests(&store).is_empty());
+    assert!(store.get_guild("iron_wolves").unwrap().hall_id.is_none());
+}
@@ -68,6 +68,7 @@ async fn test_config_with_beacon(enabled: bool, frequency: &str) -> Config {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     }
 }
 
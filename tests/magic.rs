@@ -0,0 +1,218 @@
+//! Magic (LEARN, CAST, SPELLS): trainers, scrolls, MP, cooldowns, targets and OnHeal.
+use chrono::{Duration, Utc};
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::magic;
+use meshbbs::tmush::skills;
+use meshbbs::tmush::types::{
+    CurrencyAmount, ItemStack, MobRecord, NpcCombatStats, NpcRecord, ObjectRecord, ObjectTrigger,
+    RoomRecord, SpellRecord, SpellTarget, TransactionReason,
+};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+/// Alice and bob in a glade with a tutor who teaches Mend
+async fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    store
+        .put_room(RoomRecord::world(
+            "glade",
+            "Glade",
+            "A glade.",
+            "A quiet glade.",
+        ))
+        .unwrap();
+    store
+        .put_npc(NpcRecord::new(
+            "tutor",
+            "Tutor",
+            "Hedge Mage",
+            "A mage.",
+            "glade",
+        ))
+        .unwrap();
+    store
+        .put_spell(
+            SpellRecord::new("mend", "Mend", 5)
+                .with_target(SpellTarget::Player)
+                .with_effect("heal(12)")
+                .with_cooldown(10)
+                .with_trainer("tutor", 50),
+        )
+        .unwrap();
+    for name in ["alice", "bob"] {
+        store
+            .put_player(PlayerRecord::new(name, name, "glade"))
+            .unwrap();
+    }
+    (store, config)
+}
+
+fn give(store: &TinyMushStore, user: &str, object_id: &str) {
+    let mut player = store.get_player(user).unwrap();
+    player
+        .inventory_stacks
+        .push(ItemStack::new(object_id.to_string(), 1));
+    store.put_player(player).unwrap();
+}
+
+#[tokio::test]
+async fn spells_are_learned_from_trainers_and_scrolls() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    let out = run(&store, &config, "alice", "LEARN").await;
+    assert!(out.contains("Mend 5mp - 50 (Tutor)"), "{}", out);
+    let out = run(&store, &config, "alice", "LEARN mend").await;
+    assert!(out.contains("Training costs 50."), "{}", out);
+    store
+        .grant_currency(
+            "alice",
+            &CurrencyAmount::decimal(80),
+            TransactionReason::AdminGrant,
+        )
+        .unwrap();
+    let out = run(&store, &config, "alice", "LEARN mend").await;
+    assert!(out.contains("Tutor teaches you Mend for 50."), "{}", out);
+    assert_eq!(store.get_player("alice").unwrap().currency.base_value(), 30);
+    let out = run(&store, &config, "alice", "LEARN mend").await;
+    assert!(out.contains("You already know Mend."), "{}", out);
+
+    // The seeded Recall scroll needs magic rank 2
+    store
+        .put_object(ObjectRecord::new_world(
+            "scroll_of_recall",
+            "Scroll of Recall",
+            "Faded ink.",
+        ))
+        .unwrap();
+    give(&store, "alice", "scroll_of_recall");
+    let out = run(&store, &config, "alice", "LEARN scroll").await;
+    assert!(out.contains("You need magic rank 2."), "{}", out);
+    let mut alice = store.get_player("alice").unwrap();
+    skills::practice(&mut alice, "magic", 30);
+    store.put_player(alice).unwrap();
+    let out = run(&store, &config, "alice", "LEARN scroll").await;
+    assert!(
+        out.contains("It crumbles to dust as you learn Recall."),
+        "{}",
+        out
+    );
+    assert_eq!(
+        store
+            .player_item_quantity("alice", "scroll_of_recall")
+            .unwrap(),
+        0
+    );
+
+    let out = run(&store, &config, "alice", "SPELLS").await;
+    assert!(out.starts_with("MP 10/10 | Spells 1/1"), "{}", out);
+    assert!(out.contains("Mend 5mp ally\nRecall 8mp\n"), "{}", out);
+    assert!(out.len() <= magic::SPELLBOOK_PAGE_BYTES);
+}
+
+#[tokio::test]
+async fn healing_another_player_costs_mp_and_fires_on_heal() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    let mut alice = store.get_player("alice").unwrap();
+    alice.spells.push("mend".to_string());
+    store.put_player(alice).unwrap();
+    let mut bob = store.get_player("bob").unwrap();
+    bob.stats.hp = 5;
+    store.put_player(bob).unwrap();
+    let mut amulet = ObjectRecord::new_world("amulet", "Amulet", "A warm stone.");
+    amulet.actions.insert(
+        ObjectTrigger::OnHeal,
+        "message(\"The amulet glows.\")".into(),
+    );
+    store.put_object(amulet).unwrap();
+    give(&store, "bob", "amulet");
+
+    let out = run(&store, &config, "alice", "CAST mend carol").await;
+    assert!(out.contains("You don't see 'carol' here."), "{}", out);
+    let out = run(&store, &config, "alice", "CAST mend bob").await;
+    assert!(out.contains("You cast Mend on bob."), "{}", out);
+    assert!(out.contains("Healed for 12 HP"), "{}", out);
+    assert_eq!(store.get_player("bob").unwrap().stats.hp, 17);
+    assert_eq!(store.get_player("alice").unwrap().stats.mp, 5);
+
+    let events = store.room_events().drain();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].target.as_deref(), Some("bob"));
+    assert!(events[0].text.starts_with("alice casts Mend on you."));
+    assert!(events[0].text.contains("The amulet glows."));
+
+    let out = run(&store, &config, "alice", "CAST mend bob").await;
+    assert!(out.contains("Not ready yet"), "{}", out);
+
+    // One MP per interval since the cast
+    let later = Utc::now() + Duration::seconds(2 * magic::MP_REGEN_SECONDS + 5);
+    let alice = magic::refresh_mp(&store, "alice", later).unwrap();
+    assert_eq!(alice.stats.mp, 7);
+    assert_eq!(alice.skills["magic"].practice, 1);
+}
+
+#[tokio::test]
+async fn mob_spells_start_and_win_fights() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    store
+        .put_spell(
+            SpellRecord::new("spark", "Spark", 4)
+                .with_target(SpellTarget::Mob)
+                .with_damage(50, 50),
+        )
+        .unwrap();
+    let combat = NpcCombatStats {
+        max_hp: 10,
+        attack: 1,
+        defense: 9,
+        damage_range: (1, 2),
+        loot_table: Vec::new(),
+    };
+    store
+        .put_mob(MobRecord::new(
+            "rat",
+            "Giant Rat",
+            "A rat.",
+            "glade",
+            combat,
+        ))
+        .unwrap();
+    let mut alice = store.get_player("alice").unwrap();
+    alice.spells.push("spark".to_string());
+    store.put_player(alice).unwrap();
+
+    let out = run(&store, &config, "alice", "CAST spark").await;
+    assert!(out.contains("Cast it on what?"), "{}", out);
+    let out = run(&store, &config, "alice", "CAST spark rat").await;
+    assert!(out.contains("You cast Spark at the Giant Rat."), "{}", out);
+    assert!(
+        out.contains("Your spell hits the Giant Rat for 50 damage."),
+        "{}",
+        out
+    );
+    assert!(out.contains("You defeated the Giant Rat!"), "{}", out);
+    let alice = store.get_player("alice").unwrap();
+    assert!(!alice.in_combat);
+    assert_eq!(alice.stats.mp, 6);
+}
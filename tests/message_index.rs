@@ -0,0 +1,105 @@
+//! Integration tests for the sled message index.
+//!
+//! Covers the one-shot migration of existing JSON posts, counts served from the
+//! index, index maintenance on store/delete, reconciling files written behind
+//! the storage layer's back, and the explicit rebuild.
+use chrono::{Duration, Utc};
+use meshbbs::storage::Storage;
+use std::path::Path;
+
+fn write_legacy(data_dir: &Path, topic: &str, id: &str, author: &str, ts: &str) {
+    let dir = data_dir.join("messages").join(topic);
+    std::fs::create_dir_all(&dir).unwrap();
+    let body = format!(
+        r#"{{"id":"{id}","topic":"{topic}","author":"{author}","content":"legacy {id}","timestamp":"{ts}","replies":[]}}"#
+    );
+    std::fs::write(dir.join(format!("{}.json", id)), body).unwrap();
+}
+
+#[tokio::test]
+async fn existing_posts_are_migrated_on_open() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_legacy(tmp.path(), "general", "m1", "alice", "2025-01-01T00:00:00Z");
+    write_legacy(tmp.path(), "general", "m2", "bob", "2025-01-02T00:00:00Z");
+    write_legacy(tmp.path(), "local", "m3", "alice", "2025-01-03T00:00:00Z");
+    assert!(!tmp.path().join("message_index").exists());
+
+    let storage = Storage::new(&tmp.path().to_string_lossy()).await.unwrap();
+    assert!(storage.message_index_active());
+    assert!(tmp.path().join("message_index").exists());
+
+    let general = storage.get_messages("general", 10).await.unwrap();
+    let ids: Vec<_> = general.iter().map(|m| m.id.as_str()).collect();
+    assert_eq!(ids, vec!["m2", "m1"], "newest first");
+    assert_eq!(storage.get_messages("general", 1).await.unwrap().len(), 1);
+    assert_eq!(storage.count_user_posts("alice").await.unwrap(), 2);
+
+    let since = "2025-01-01T12:00:00Z".parse().unwrap();
+    assert_eq!(storage.count_messages_since(since).await.unwrap(), 2);
+    assert_eq!(
+        storage
+            .count_messages_since_in_topic("general", since)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        storage.topic_high_water("local").await.unwrap(),
+        Some("2025-01-03T00:00:00Z".parse().unwrap())
+    );
+    assert_eq!(storage.rebuild_message_index().await.unwrap(), 3);
+}
+
+#[tokio::test]
+async fn index_follows_store_delete_and_external_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    let mut storage = Storage::new(&data_dir).await.unwrap();
+    storage
+        .create_topic("general", "General", "", 0, 0, "sysop")
+        .await
+        .unwrap();
+
+    let before = Utc::now() - Duration::seconds(1);
+    let first = storage
+        .store_message("general", "carol", "first")
+        .await
+        .unwrap();
+    storage
+        .store_message("general", "carol", "second")
+        .await
+        .unwrap();
+    assert_eq!(storage.count_user_posts("carol").await.unwrap(), 2);
+    assert_eq!(storage.count_messages_since(before).await.unwrap(), 2);
+
+    assert!(storage.delete_message("general", &first).await.unwrap());
+    assert_eq!(storage.count_user_posts("carol").await.unwrap(), 1);
+    let listed = storage.get_messages("general", 10).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].content, "second");
+
+    // A file dropped in by another process is picked up on the next read
+    write_legacy(
+        tmp.path(),
+        "general",
+        "ext1",
+        "dave",
+        "2030-01-01T00:00:00Z",
+    );
+    let listed = storage.get_messages("general", 10).await.unwrap();
+    assert_eq!(listed[0].id, "ext1");
+    assert_eq!(storage.count_user_posts("dave").await.unwrap(), 1);
+
+    // ...and so is one removed behind our back
+    std::fs::remove_file(tmp.path().join("messages/general/ext1.json")).unwrap();
+    assert_eq!(storage.get_messages("general", 10).await.unwrap().len(), 1);
+    assert_eq!(storage.count_user_posts("dave").await.unwrap(), 0);
+
+    // Another handle in the same process shares the open index
+    let second = Storage::new(&data_dir).await.unwrap();
+    assert!(second.message_index_active());
+    assert_eq!(second.count_user_posts("carol").await.unwrap(), 1);
+
+    storage.delete_topic("general").await.unwrap();
+    assert_eq!(storage.count_user_posts("carol").await.unwrap(), 0);
+}
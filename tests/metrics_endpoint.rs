@@ -0,0 +1,79 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Local metrics listener: the server's published status is served as JSON on
+//! /status (what `meshbbs status` reads) and as Prometheus text on /metrics.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+use meshbbs::metrics::http;
+use std::time::Duration;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+#[tokio::test]
+async fn status_and_metrics_reflect_sessions_commands_and_storage() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.bbs.name = "Ridge \"Top\" BBS".into();
+    cfg.welcome.enabled = false;
+    cfg.metrics.enabled = true;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server.test_register("alice", "password123").await.unwrap();
+    server
+        .route_text_event(dm(100, "LOGIN alice password123"))
+        .await
+        .unwrap();
+    for cmd in ["M", "2", "HELP", "WHO"] {
+        server.route_text_event(dm(100, cmd)).await.unwrap();
+    }
+    server.route_text_event(dm(200, "HELP")).await.unwrap();
+    server.publish_status().await;
+
+    let listener = http::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    tokio::spawn(http::serve(listener));
+    let wait = Duration::from_secs(5);
+
+    let status = http::fetch_status(&addr, wait).await.unwrap();
+    assert_eq!(status.bbs_name, "Ridge \"Top\" BBS");
+    assert_eq!(status.sessions_active, 2);
+    assert_eq!(status.sessions_logged_in, 1);
+    assert_eq!(status.total_users, 1);
+    assert!(status.storage_bytes.get("users").is_some_and(|b| *b > 0));
+    let dm_counts = status.commands.get("dm").expect("dm commands counted");
+    assert_eq!(dm_counts.get("HELP"), Some(&2));
+    assert_eq!(dm_counts.get("<n>"), Some(&1));
+    assert_eq!(dm_counts.get("LOGIN"), Some(&1));
+    assert!(
+        !dm_counts.keys().any(|k| k.contains("PASSWORD")),
+        "{:?}",
+        dm_counts
+    );
+
+    let text = http::fetch(&addr, "/metrics", wait).await.unwrap();
+    assert!(
+        text.contains("meshbbs_info{name=\"Ridge \\\"Top\\\" BBS\""),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("meshbbs_sessions{state=\"logged_in\"} 1\n"),
+        "{}",
+        text
+    );
+    assert!(text.contains("meshbbs_commands_total{channel=\"dm\",command=\"HELP\"} 2\n"));
+    assert!(text.contains("# TYPE meshbbs_ack_latency_seconds summary\n"));
+    assert!(text.contains("meshbbs_storage_bytes{area=\"users\"} "));
+    assert!(text.contains("meshbbs_backup_enabled "));
+
+    let err = http::fetch(&addr, "/nope", wait).await.unwrap_err();
+    assert!(err.to_string().contains("404"), "{}", err);
+}
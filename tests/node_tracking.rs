@@ -0,0 +1,146 @@
+//! Integration tests for node position/telemetry tracking.
+//!
+//! Covers the NodeCache bookkeeping (last heard, link quality, position,
+//! battery, lookups and distance) and the NODES / NODE DM commands and
+//! SEEN rendering built on top of the persisted cache.
+use chrono::{Duration, Utc};
+use meshbbs::bbs::nodes::{render_seen, NODES_PER_PAGE};
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::NodeCache;
+
+const BBS: u32 = 0x1000_0001;
+const ALFA: u32 = 0xa1fa_0001;
+const BRAVO: u32 = 0xb0b0_0002;
+
+fn sample_cache() -> NodeCache {
+    let mut cache = NodeCache::new();
+    cache.our_node_id = Some(BBS);
+    cache.update_node(BBS, "Home BBS".into(), "BBS".into());
+    cache.update_position(BBS, 37.7749, -122.4194, None);
+    cache.update_node(ALFA, "Alpha Station".into(), "ALFA".into());
+    cache.record_heard(ALFA, Some(6.5), Some(-90), Some(0));
+    // Roughly 11 km north of the BBS
+    cache.update_position(ALFA, 37.8749, -122.4194, Some(12));
+    cache.update_device_metrics(ALFA, Some(87), Some(4.01));
+    cache.update_node(BRAVO, String::new(), String::new());
+    cache.record_heard(BRAVO, None, None, Some(3));
+    cache
+}
+
+#[test]
+fn cache_tracks_link_position_and_battery() {
+    let mut cache = sample_cache();
+    let alfa = &cache.nodes[&ALFA];
+    assert_eq!(alfa.snr, Some(6.5));
+    assert_eq!(alfa.rssi, Some(-90));
+    assert_eq!(alfa.hops_away, Some(0));
+    assert_eq!(alfa.battery_level, Some(87));
+    assert_eq!(alfa.position.unwrap().altitude, Some(12));
+
+    // Missing values keep what we had; a (0, 0) fix is ignored
+    cache.record_heard(ALFA, None, None, None);
+    cache.update_device_metrics(ALFA, None, Some(3.9));
+    cache.update_position(ALFA, 0.0, 0.0, None);
+    let alfa = &cache.nodes[&ALFA];
+    assert_eq!(alfa.snr, Some(6.5));
+    assert_eq!(alfa.battery_level, Some(87));
+    assert_eq!(alfa.voltage, Some(3.9));
+    assert!((alfa.position.unwrap().latitude - 37.8749).abs() < 1e-9);
+
+    let km = cache.distance_km(ALFA).unwrap();
+    assert!((km - 11.1).abs() < 0.2, "{}", km);
+    assert!(cache.distance_km(BRAVO).is_none());
+
+    // Lookups by id, short name and long name; the BBS itself is not listed
+    assert_eq!(cache.find("!a1fa0001").unwrap().node_id, ALFA);
+    assert_eq!(cache.find(&ALFA.to_string()).unwrap().node_id, ALFA);
+    assert_eq!(cache.find("alfa").unwrap().node_id, ALFA);
+    assert_eq!(cache.find("alpha station").unwrap().node_id, ALFA);
+    assert!(cache.find("nobody").is_none());
+    assert_eq!(cache.nodes[&BRAVO].label(), "!b0b00002");
+    assert!(cache.recently_heard().iter().all(|n| n.node_id != BBS));
+}
+
+#[test]
+fn cache_round_trips_and_reads_old_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("node_cache.json");
+    sample_cache().save_to_file(&path).unwrap();
+    let loaded = NodeCache::load_from_file(&path).unwrap();
+    assert_eq!(loaded.our_node_id, Some(BBS));
+    assert_eq!(loaded.nodes[&ALFA].battery_level, Some(87));
+
+    // Caches written before position/telemetry tracking still load
+    let old = r#"{"nodes":{"42":{"node_id":42,"long_name":"Old","short_name":"OLD",
+        "last_seen":"2025-01-01T00:00:00Z","first_seen":"2025-01-01T00:00:00Z"}},
+        "last_updated":"2025-01-01T00:00:00Z"}"#;
+    std::fs::write(&path, old).unwrap();
+    let loaded = NodeCache::load_from_file(&path).unwrap();
+    assert!(loaded.nodes[&42].position.is_none());
+    assert!(loaded.our_node_id.is_none());
+}
+
+#[test]
+fn seen_reply_is_compact() {
+    let cache = sample_cache();
+    let now = Utc::now() + Duration::minutes(5);
+    let line = render_seen(Some(&cache), "ALFA", now);
+    assert!(line.starts_with("ALFA heard 5m ago"), "{}", line);
+    assert!(
+        line.contains("0 hops") && line.contains("11km away"),
+        "{}",
+        line
+    );
+    assert!(line.contains("bat 87% 4.01V"), "{}", line);
+    assert_eq!(render_seen(None, "ALFA", now), "ALFA not heard");
+    assert_eq!(render_seen(Some(&cache), "zulu", now), "zulu not heard");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn nodes_and_node_commands() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    let mut cache = sample_cache();
+    for i in 0..NODES_PER_PAGE as u32 {
+        cache.update_node(0x2000 + i, format!("Filler {}", i), format!("F{}", i));
+    }
+    cache
+        .save_to_file(tmp.path().join("node_cache.json"))
+        .unwrap();
+
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = data_dir;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server.test_register("alice", "password123").await.unwrap();
+    let a = "node_a";
+    server
+        .route_test_text_direct(a, "LOGIN alice")
+        .await
+        .unwrap();
+
+    server.route_test_text_direct(a, "NODES").await.unwrap();
+    let m = server.test_messages().last().unwrap().1.clone();
+    assert!(m.contains("Heard nodes (1/2)"), "{}", m);
+    assert!(m.contains("NODES 2 more"), "{}", m);
+    assert!(!m.contains("\nBBS "), "own node is hidden: {}", m);
+    assert!(m.len() <= 230, "{}", m.len());
+
+    server.route_test_text_direct(a, "NODES 2").await.unwrap();
+    let m = server.test_messages().last().unwrap().1.clone();
+    assert!(m.contains("Heard nodes (2/2)"), "{}", m);
+
+    server.route_test_text_direct(a, "NODE alfa").await.unwrap();
+    let m = server.test_messages().last().unwrap().1.clone();
+    assert!(m.contains("ALFA !a1fa0001"), "{}", m);
+    assert!(m.contains("Alpha Station"), "{}", m);
+    assert!(m.contains("SNR 6.5 RSSI -90"), "{}", m);
+    assert!(m.contains("Pos 37.87490,-122.41940 12m"), "{}", m);
+    assert!(m.contains("11km from BBS"), "{}", m);
+    assert!(m.contains("Battery 87% 4.01V"), "{}", m);
+    assert!(m.len() <= 230, "{}", m.len());
+
+    server.route_test_text_direct(a, "NODE zulu").await.unwrap();
+    let m = server.test_messages().last().unwrap().1.clone();
+    assert!(m.contains("not heard"), "{}", m);
+}
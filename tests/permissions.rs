@@ -85,6 +85,7 @@ async fn config_with_areas(dir: &str) -> Config {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     }
 }
 
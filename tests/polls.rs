@@ -0,0 +1,216 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Poll threads: moderator POLL / POLLCLOSE, one vote per user from the read view,
+//! results saved with the message JSON and the public ^POLL summary.
+use chrono::{Duration, Utc};
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+use meshbbs::storage::polls::Poll;
+use meshbbs::storage::Storage;
+
+const MOD_NODE: u32 = 100;
+const ALICE_NODE: u32 = 200;
+const BOB_NODE: u32 = 300;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Public channel message; each query uses its own node to stay under the reply rate limit
+fn public(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: None,
+        is_direct: false,
+        channel: Some(0),
+        content: content.into(),
+    }
+}
+
+/// Last message the server sent to `node` ("BCAST" for broadcasts)
+fn last_to(server: &BbsServer, node: &str) -> String {
+    server
+        .test_messages()
+        .iter()
+        .rev()
+        .find(|(to, _)| to == node)
+        .map(|(_, m)| m.clone())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn moderators_run_polls_and_users_vote_once() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.welcome.enabled = false;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server
+        .test_create_topic("general", "General", "General chat", 0, 0, "sysop")
+        .await
+        .unwrap();
+    for (node, user) in [
+        (MOD_NODE, "carol"),
+        (ALICE_NODE, "alice"),
+        (BOB_NODE, "bob"),
+    ] {
+        server.test_register(user, "password123").await.unwrap();
+        if user == "carol" {
+            server.test_update_level(user, 5).await.unwrap();
+        }
+        server
+            .route_text_event(dm(node, &format!("LOGIN {} password123", user)))
+            .await
+            .unwrap();
+    }
+
+    server.route_text_event(public(9, "^POLL")).await.unwrap();
+    assert_eq!(last_to(&server, "BCAST"), "^POLL ⟶ No open polls.");
+
+    server
+        .route_text_event(dm(ALICE_NODE, "POLL general Net night? | Tue | Wed"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, &ALICE_NODE.to_string()).starts_with("Permission denied.\n"));
+    server
+        .route_text_event(dm(MOD_NODE, "POLL general 48h Net night? | Tue"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, &MOD_NODE.to_string()).starts_with("A poll needs 2-5 options.\n"));
+    server
+        .route_text_event(dm(
+            MOD_NODE,
+            "POLL general 48h Net night? | Tue | Wed | Thu",
+        ))
+        .await
+        .unwrap();
+    let created = last_to(&server, &MOD_NODE.to_string());
+    assert!(created.starts_with("Poll "), "{}", created);
+    assert!(created.contains(" created in general.\n"), "{}", created);
+    let id = created.split(' ').nth(1).unwrap().to_string();
+
+    // Voters open the thread from the Topics view and vote with V<n>
+    for node in [ALICE_NODE, BOB_NODE] {
+        for key in ["M", "2", "1"] {
+            server.route_text_event(dm(node, key)).await.unwrap();
+        }
+    }
+    let read = last_to(&server, &ALICE_NODE.to_string());
+    assert!(
+        read.contains("Net night?\n1 Tue 0\n2 Wed 0\n3 Thu 0\n(0 votes, ends "),
+        "{}",
+        read
+    );
+    assert!(read.contains("V<n> vote"), "{}", read);
+
+    server.route_text_event(dm(ALICE_NODE, "V2")).await.unwrap();
+    let voted = last_to(&server, &ALICE_NODE.to_string());
+    assert!(
+        voted.starts_with("Voted 2.\nNet night?\n1 Tue 0\n2 Wed 1\n"),
+        "{}",
+        voted
+    );
+    server
+        .route_text_event(dm(ALICE_NODE, "V 1"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, &ALICE_NODE.to_string()).starts_with("You already voted for 2.\n"));
+    server.route_text_event(dm(BOB_NODE, "V 9")).await.unwrap();
+    assert!(last_to(&server, &BOB_NODE.to_string()).starts_with("Choose 1-3.\n"));
+    server.route_text_event(dm(BOB_NODE, "V 2")).await.unwrap();
+
+    server.route_text_event(public(10, "^POLL")).await.unwrap();
+    let summary = last_to(&server, "BCAST");
+    assert!(
+        summary.starts_with(
+            "^POLL ⟶ [general] Net night? | 1 Tue 0 | 2 Wed 2 | 3 Thu 0 | (2 votes, ends "
+        ),
+        "{}",
+        summary
+    );
+    assert!(summary.len() <= 200);
+
+    server
+        .route_text_event(dm(MOD_NODE, &format!("POLLCLOSE general {}", &id[..4])))
+        .await
+        .unwrap();
+    let closed = last_to(&server, &MOD_NODE.to_string());
+    assert!(closed.starts_with("Closed. Net night?\n"), "{}", closed);
+    assert!(
+        closed.contains("2 Wed* 2\n3 Thu 0\n(2 votes, closed)\n"),
+        "{}",
+        closed
+    );
+    server
+        .route_text_event(dm(MOD_NODE, &format!("POLLCLOSE general {}", id)))
+        .await
+        .unwrap();
+    assert!(last_to(&server, &MOD_NODE.to_string()).starts_with("Failed: Poll already closed\n"));
+
+    server.route_text_event(dm(BOB_NODE, "V3")).await.unwrap();
+    assert!(last_to(&server, &BOB_NODE.to_string()).starts_with("This poll is closed.\n"));
+    server.route_text_event(public(11, "^POLL")).await.unwrap();
+    assert_eq!(last_to(&server, "BCAST"), "^POLL ⟶ No open polls.");
+}
+
+#[tokio::test]
+async fn votes_persist_and_only_public_open_polls_are_announced() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    let mut storage = Storage::new(&data_dir).await.unwrap();
+    storage
+        .create_topic("general", "general", "", 0, 0, "sysop")
+        .await
+        .unwrap();
+    storage
+        .create_topic("board", "board", "", 5, 0, "sysop")
+        .await
+        .unwrap();
+    storage
+        .register_user("alice", "password123", None)
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    let expired = Poll::new("Old?", &["a", "b"], Some(now - Duration::hours(1))).unwrap();
+    storage
+        .create_poll("general", "carol", expired)
+        .await
+        .unwrap();
+    let open = Poll::new("Repeater fund?", &["$50", "$100"], None).unwrap();
+    let id = storage.create_poll("general", "carol", open).await.unwrap();
+    let private = Poll::new("Budget?", &["yes", "no"], None).unwrap();
+    storage
+        .create_poll("board", "carol", private)
+        .await
+        .unwrap();
+
+    assert!(storage
+        .vote_poll("general", &id, "mallory", 1)
+        .await
+        .is_err());
+    storage.vote_poll("general", &id, "alice", 2).await.unwrap();
+
+    let storage = Storage::new(&data_dir).await.unwrap();
+    let thread = storage.get_message("general", &id).await.unwrap().unwrap();
+    let poll = thread.poll.unwrap();
+    assert_eq!(poll.tally(), vec![0, 1]);
+    assert_eq!(poll.votes.get("alice"), Some(&1));
+
+    let public_only = |t: &str| {
+        storage
+            .get_topic_config(t)
+            .is_none_or(|c| c.read_level == 0)
+    };
+    let latest = storage.latest_open_poll(public_only, now).await.unwrap();
+    assert_eq!(latest.map(|m| m.id), Some(id));
+
+    let audit = storage.get_admin_audit_page(1, 10).await.unwrap();
+    assert_eq!(audit.iter().filter(|e| e.action == "POLL").count(), 3);
+}
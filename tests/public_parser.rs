@@ -130,3 +130,17 @@ fn test_help_command_invalid_fallback() {
         other => panic!("Expected Help to fallback to default when invalid keyword provided, got {:?}", other),
     }
 }
+
+#[test]
+fn test_seen_command() {
+    let parser = PublicCommandParser::new();
+    match parser.parse("^seen Alpha Station") {
+        PublicCommand::Seen(n) => assert_eq!(n, "Alpha Station"),
+        other => panic!("Expected Seen, got {:?}", other),
+    }
+    match parser.parse("^SEEN") {
+        PublicCommand::Invalid(_) => {}
+        other => panic!("Expected Invalid without a node, got {:?}", other),
+    }
+    assert_eq!(parser.parse("^SEENX"), PublicCommand::Unknown);
+}
@@ -0,0 +1,200 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Reply notifications: auto-subscribe on post, SUB / SUBS / NOTIFY, and the
+//! summary DM sent when a subscriber's node is heard or they log in.
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+
+const ALICE_NODE: u32 = 100;
+const BOB_NODE: u32 = 200;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Messages the server has sent to `node` so far
+fn sent_to(server: &BbsServer, node: u32) -> Vec<String> {
+    server
+        .test_messages()
+        .iter()
+        .filter(|(to, _)| *to == node.to_string())
+        .map(|(_, m)| m.clone())
+        .collect()
+}
+
+async fn setup() -> (BbsServer, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.welcome.enabled = false;
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    for topic in ["general", "radio"] {
+        server
+            .test_create_topic(topic, topic, "", 0, 0, "sysop")
+            .await
+            .unwrap();
+    }
+    server.test_register("alice", "password123").await.unwrap();
+    server.test_register("bob", "password123").await.unwrap();
+    // Logging in once binds alice's node to her account
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGIN alice password123"))
+        .await
+        .unwrap();
+    (server, tmp)
+}
+
+#[tokio::test]
+async fn thread_authors_hear_about_replies_once_per_batch_up_to_the_cap() {
+    let (mut server, _tmp) = setup().await;
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGOUT"))
+        .await
+        .unwrap();
+    let thread = server
+        .test_store_message("general", "alice", "Antenna tips\nWhat works?")
+        .await
+        .unwrap();
+
+    // Her own node being heard with nothing new sends nothing
+    let before = sent_to(&server, ALICE_NODE).len();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert_eq!(sent_to(&server, ALICE_NODE).len(), before);
+
+    server
+        .test_append_reply("general", &thread, "bob", "A long wire")
+        .await
+        .unwrap();
+    server
+        .test_append_reply("general", &thread, "bob", "Or a J-pole")
+        .await
+        .unwrap();
+    server
+        .test_append_reply("general", &thread, "alice", "Thanks!")
+        .await
+        .unwrap();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    let sent = sent_to(&server, ALICE_NODE);
+    assert_eq!(sent.len(), before + 1);
+    assert_eq!(
+        sent.last().unwrap(),
+        "New since your last visit:\nAntenna tips (2 replies)\nNOTIFY OFF to stop\n"
+    );
+    // Already summarised
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert_eq!(sent_to(&server, ALICE_NODE).len(), before + 1);
+
+    // The next batch arrives with her login instead
+    server
+        .test_append_reply("general", &thread, "bob", "Height matters")
+        .await
+        .unwrap();
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGIN alice password123"))
+        .await
+        .unwrap();
+    let sent = sent_to(&server, ALICE_NODE);
+    assert!(sent[sent.len() - 2].contains("you are now logged in"));
+    assert!(sent.last().unwrap().contains("Antenna tips (1 reply)\n"));
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGOUT"))
+        .await
+        .unwrap();
+
+    // Third summary today is the last one
+    server
+        .test_append_reply("general", &thread, "bob", "Ground plane too")
+        .await
+        .unwrap();
+    let count = sent_to(&server, ALICE_NODE).len();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert_eq!(sent_to(&server, ALICE_NODE).len(), count + 1);
+    server
+        .test_append_reply("general", &thread, "bob", "Anyone?")
+        .await
+        .unwrap();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert_eq!(sent_to(&server, ALICE_NODE).len(), count + 1);
+}
+
+#[tokio::test]
+async fn topics_can_be_followed_and_notifications_turned_off() {
+    let (mut server, _tmp) = setup().await;
+    server
+        .route_text_event(dm(ALICE_NODE, "SUB radio"))
+        .await
+        .unwrap();
+    assert!(sent_to(&server, ALICE_NODE)
+        .last()
+        .unwrap()
+        .starts_with("Following radio."));
+    server
+        .route_text_event(dm(ALICE_NODE, "SUB nowhere"))
+        .await
+        .unwrap();
+    assert!(sent_to(&server, ALICE_NODE)
+        .last()
+        .unwrap()
+        .starts_with("No topic 'nowhere'."));
+    server
+        .route_text_event(dm(ALICE_NODE, "SUBS"))
+        .await
+        .unwrap();
+    assert!(sent_to(&server, ALICE_NODE)
+        .last()
+        .unwrap()
+        .starts_with("Following 1 (notify on):\n[radio]\n"));
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGOUT"))
+        .await
+        .unwrap();
+
+    let id = server
+        .test_store_message("radio", "bob", "Net tonight")
+        .await
+        .unwrap();
+    server
+        .test_append_reply("radio", &id, "bob", "8pm")
+        .await
+        .unwrap();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert!(sent_to(&server, ALICE_NODE)
+        .last()
+        .unwrap()
+        .contains("[radio] 2 new\n"));
+
+    // Opting out keeps the subscription but stops the DMs
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGIN alice password123"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(dm(ALICE_NODE, "NOTIFY OFF"))
+        .await
+        .unwrap();
+    assert!(sent_to(&server, ALICE_NODE)
+        .last()
+        .unwrap()
+        .starts_with("Reply notifications off."));
+    server
+        .route_text_event(dm(ALICE_NODE, "LOGOUT"))
+        .await
+        .unwrap();
+    server
+        .test_store_message("radio", "bob", "Net moved to 9pm")
+        .await
+        .unwrap();
+    let count = sent_to(&server, ALICE_NODE).len();
+    server.test_node_heard(ALICE_NODE).await.unwrap();
+    assert_eq!(sent_to(&server, ALICE_NODE).len(), count);
+
+    // Bob never subscribed to anything but his own thread
+    server.test_node_heard(BOB_NODE).await.unwrap();
+    assert!(sent_to(&server, BOB_NODE).is_empty());
+}
@@ -0,0 +1,100 @@
+//! Experience, levels and skills (SKILLS): exploration, crafting and skill-gated recipes.
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::skills;
+use meshbbs::tmush::types::{CraftingRecipe, Direction, ItemStack, ObjectRecord, RoomRecord};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+/// Alice stands in a glade with a path north to a grove
+async fn setup(tmp: &TempDir) -> (TinyMushStore, Config) {
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    let mut glade = RoomRecord::world("glade", "Glade", "A glade.", "A quiet glade.");
+    glade.exits.insert(Direction::North, "grove".to_string());
+    let mut grove = RoomRecord::world("grove", "Grove", "A grove.", "An old grove.");
+    grove.exits.insert(Direction::South, "glade".to_string());
+    store.put_room(glade).unwrap();
+    store.put_room(grove).unwrap();
+    store
+        .put_player(PlayerRecord::new("alice", "alice", "glade"))
+        .unwrap();
+    (store, config)
+}
+
+#[tokio::test]
+async fn first_visits_earn_experience_and_survival() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+
+    let out = run(&store, &config, "alice", "N").await;
+    assert!(out.contains("Grove"), "{}", out);
+    run(&store, &config, "alice", "S").await;
+    run(&store, &config, "alice", "N").await;
+
+    let alice = store.get_player("alice").unwrap();
+    assert_eq!(alice.stats.experience, 2 * skills::EXPLORE_XP as u64);
+    assert_eq!(alice.skills["survival"].practice, 2);
+
+    let out = run(&store, &config, "alice", "SKILLS").await;
+    assert!(out.contains("Level 1 - 10/100 XP"), "{}", out);
+    assert!(out.contains("survival 0 (2/10)"), "{}", out);
+}
+
+#[tokio::test]
+async fn recipes_can_require_a_skill_rank() {
+    let tmp = TempDir::new().unwrap();
+    let (store, config) = setup(&tmp).await;
+    store
+        .put_object(ObjectRecord::new_world("twig", "Twig", "A twig."))
+        .unwrap();
+    let mut recipe = CraftingRecipe::new("twig_antenna", "Twig Antenna", "basic_antenna", "admin")
+        .with_material("twig", 1);
+    recipe.skill_required = Some("survival".to_string());
+    recipe.skill_level = 1;
+    store.put_recipe(recipe).unwrap();
+    let mut alice = store.get_player("alice").unwrap();
+    alice
+        .inventory_stacks
+        .push(ItemStack::new("twig".to_string(), 1));
+    store.put_player(alice).unwrap();
+
+    let out = run(&store, &config, "alice", "CRAFT twig_antenna").await;
+    assert!(
+        out.contains("You need survival rank 1 to craft Twig Antenna (you have 0)"),
+        "{}",
+        out
+    );
+
+    let mut alice = store.get_player("alice").unwrap();
+    skills::practice(&mut alice, "survival", 10);
+    store.put_player(alice).unwrap();
+    let out = run(&store, &config, "alice", "CRAFT twig_antenna").await;
+    assert!(
+        out.contains("You successfully craft Twig Antenna! +15 XP"),
+        "{}",
+        out
+    );
+
+    let alice = store.get_player("alice").unwrap();
+    assert_eq!(alice.stats.experience, 15);
+    assert_eq!(alice.skills["survival"].practice, 1);
+    assert_eq!(store.player_item_quantity("alice", "twig").unwrap(), 0);
+}
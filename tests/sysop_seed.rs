@@ -71,6 +71,7 @@ fn sysop_user_seeded_with_hash() {
                 cooldown_minutes: 5,
                 max_welcomes_per_node: 1,
             },
+            replication: Default::default(),
         };
         let mut server = BbsServer::new(cfg).await.unwrap();
         server.seed_sysop().await.unwrap();
@@ -0,0 +1,223 @@
+//! Integration tests for TinyMUSH PvE combat.
+//!
+//! Drives ATTACK/DEFEND/FLEE/USE through the command processor against
+//! purpose-built mobs so outcomes are deterministic enough to assert on.
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::storage::Storage;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::types::{
+    InventoryConfig, LootDrop, MobRecord, NpcCombatStats, ObjectiveType, PlayerQuest,
+    QuestObjective,
+};
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+struct Harness {
+    _dir: TempDir,
+    store: TinyMushStore,
+    storage: Storage,
+    config: Config,
+    session: Session,
+}
+
+impl Harness {
+    async fn new(username: &str, room_id: &str) -> Self {
+        let dir = TempDir::new().expect("tempdir");
+        let store = TinyMushStoreBuilder::new(dir.path().join("tinymush"))
+            .open()
+            .expect("store");
+        let mut config = Config::default();
+        config.storage.data_dir = dir.path().to_string_lossy().to_string();
+        config.games.tinymush_enabled = true;
+        let storage = Storage::new(&config.storage.data_dir).await.unwrap();
+
+        store
+            .put_player(PlayerRecord::new(username, username, room_id))
+            .unwrap();
+
+        let mut session = Session::new(
+            format!("{}_session", username),
+            format!("{}_node", username),
+        );
+        session.login(username.to_string(), 1).await.unwrap();
+        session.state = SessionState::TinyMush;
+
+        Self {
+            _dir: dir,
+            store,
+            storage,
+            config,
+            session,
+        }
+    }
+
+    async fn run(&mut self, command: &str) -> String {
+        let mut processor = TinyMushProcessor::new(self.store.clone());
+        processor
+            .process_command(&mut self.session, command, &mut self.storage, &self.config)
+            .await
+            .unwrap()
+    }
+}
+
+fn mob(id: &str, room_id: &str, max_hp: u32, attack: u8, damage: u8) -> MobRecord {
+    MobRecord::new(
+        id,
+        "Training Dummy",
+        "A straw dummy that fights back.",
+        room_id,
+        NpcCombatStats {
+            max_hp,
+            attack,
+            defense: 0,
+            damage_range: (damage, damage),
+            loot_table: vec![LootDrop {
+                object_id: "copper_wire".to_string(),
+                chance: 1.0,
+                quantity: (2, 2),
+            }],
+        },
+    )
+    .with_enemy_type("dummy")
+}
+
+#[tokio::test]
+async fn victory_grants_loot_quest_progress_and_achievement() {
+    let mut h = Harness::new("alice", "maintenance_tunnels").await;
+    h.store
+        .put_mob(mob("dummy", "maintenance_tunnels", 1, 0, 1))
+        .unwrap();
+
+    let mut player = h.store.get_player("alice").unwrap();
+    player.quests.push(PlayerQuest::new(
+        "dummy_hunt",
+        vec![QuestObjective::new(
+            "Defeat a dummy",
+            ObjectiveType::KillEnemy {
+                enemy_type: "dummy".to_string(),
+                count: 1,
+            },
+            1,
+        )],
+    ));
+    // The tunnels are dark; carry a light so LOOK shows the room contents
+    player.inventory.push("torch".to_string());
+    h.store.put_player(player).unwrap();
+
+    let look = h.run("LOOK").await;
+    assert!(look.contains("Training Dummy"), "{}", look);
+
+    let out = h.run("ATTACK dummy").await;
+    assert!(out.contains("You defeated the Training Dummy"), "{}", out);
+    assert!(out.contains("Copper Wire x2"), "{}", out);
+    assert!(out.contains("Quest objectives complete"), "{}", out);
+    assert!(out.contains("First Blood"), "{}", out);
+
+    let player = h.store.get_player("alice").unwrap();
+    assert!(!player.in_combat);
+    assert!(player
+        .inventory_stacks
+        .iter()
+        .any(|s| s.object_id == "copper_wire" && s.quantity == 2));
+    assert!(player.quests[0].all_objectives_complete());
+
+    // Defeated mob is gone until it respawns
+    assert!(h
+        .store
+        .get_mobs_in_room("maintenance_tunnels")
+        .unwrap()
+        .iter()
+        .all(|m| m.id != "dummy"));
+    assert!(h.run("ATTACK dummy").await.contains("don't see"));
+}
+
+#[tokio::test]
+async fn defeat_respawns_player_at_town_square() {
+    let mut h = Harness::new("bob", "maintenance_tunnels").await;
+    h.store
+        .put_mob(mob("brute", "maintenance_tunnels", 1_000_000, 100, 255))
+        .unwrap();
+
+    let mut out = h.run("ATTACK training").await;
+    for _ in 0..20 {
+        if out.contains("defeated by") {
+            break;
+        }
+        out = h.run("ATTACK").await;
+    }
+    assert!(
+        out.contains("You have been defeated by the Training Dummy"),
+        "{}",
+        out
+    );
+
+    let player = h.store.get_player("bob").unwrap();
+    assert_eq!(player.current_room, "town_square");
+    assert_eq!(player.stats.hp, player.stats.max_hp);
+    assert!(!player.in_combat);
+}
+
+#[tokio::test]
+async fn combat_blocks_movement_and_safe_rooms_refuse_fights() {
+    let mut h = Harness::new("carol", "town_square").await;
+    h.store
+        .put_mob(mob("dummy", "town_square", 50, 0, 1))
+        .unwrap();
+    assert!(h.run("ATTACK dummy").await.contains("safe area"));
+
+    let mut player = h.store.get_player("carol").unwrap();
+    player.current_room = "maintenance_tunnels".to_string();
+    h.store.put_player(player).unwrap();
+    let mut sparring = mob("sparring", "maintenance_tunnels", 1_000_000, 0, 1);
+    sparring.name = "Sparring Bot".to_string();
+    h.store.put_mob(sparring).unwrap();
+
+    let out = h.run("ATTACK sparring").await;
+    assert!(out.contains("ATTACK | DEFEND | FLEE"), "{}", out);
+    assert!(h.run("N").await.contains("Try FLEE"));
+    assert!(h.run("DEFEND").await.contains("brace"));
+
+    let mut fled = false;
+    for _ in 0..50 {
+        if h.run("FLEE").await.contains("You escape") {
+            fled = true;
+            break;
+        }
+    }
+    assert!(fled);
+    assert!(!h.store.get_player("carol").unwrap().in_combat);
+    assert!(h.run("DEFEND").await.contains("aren't fighting"));
+}
+
+#[tokio::test]
+async fn using_an_item_mid_fight_heals_and_costs_the_turn() {
+    let mut h = Harness::new("dave", "maintenance_tunnels").await;
+    h.store
+        .put_mob(mob("dummy", "maintenance_tunnels", 1_000_000, 0, 1))
+        .unwrap();
+    h.store
+        .player_add_item(
+            "dave",
+            "example_healing_potion",
+            1,
+            &InventoryConfig::default(),
+        )
+        .unwrap();
+
+    h.run("ATTACK dummy").await;
+    let mut player = h.store.get_player("dave").unwrap();
+    player.stats.hp = 5;
+    h.store.put_player(player).unwrap();
+
+    let out = h.run("USE potion").await;
+    assert!(out.contains("Healed for 50 HP"), "{}", out);
+    assert!(
+        out.contains("hits you") || out.contains("misses you"),
+        "enemy should act after USE: {}",
+        out
+    );
+    let player = h.store.get_player("dave").unwrap();
+    assert!(player.stats.hp >= player.stats.max_hp - 1);
+    assert!(player.in_combat);
+}
@@ -0,0 +1,232 @@
+//! Integration tests for TinyMUSH faction reputation.
+//!
+//! Covers the REPUTATION command, dialogue that reads and writes standing, and
+//! shops whose owner belongs to a faction.
+use std::collections::HashMap;
+
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::storage::Storage;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::types::{
+    CurrencyAmount, DialogAction, DialogChoice, DialogCondition, DialogNode, NpcRecord,
+    ObjectRecord,
+};
+use meshbbs::tmush::{PlayerRecord, ShopItem, ShopRecord, TinyMushStore, TinyMushStoreBuilder};
+use tempfile::TempDir;
+
+struct Harness {
+    _dir: TempDir,
+    store: TinyMushStore,
+    storage: Storage,
+    config: Config,
+    session: Session,
+}
+
+impl Harness {
+    async fn new(username: &str) -> Self {
+        let dir = TempDir::new().expect("tempdir");
+        let store = TinyMushStoreBuilder::new(dir.path().join("tinymush"))
+            .open()
+            .expect("store");
+        let mut config = Config::default();
+        config.storage.data_dir = dir.path().to_string_lossy().to_string();
+        config.games.tinymush_enabled = true;
+        let storage = Storage::new(&config.storage.data_dir).await.unwrap();
+
+        let mut player = PlayerRecord::new(username, username, "town_square");
+        player.currency = CurrencyAmount::decimal(10_000);
+        store.put_player(player).unwrap();
+
+        let mut session = Session::new(
+            format!("{}_session", username),
+            format!("{}_node", username),
+        );
+        session.login(username.to_string(), 1).await.unwrap();
+        session.state = SessionState::TinyMush;
+
+        Self {
+            _dir: dir,
+            store,
+            storage,
+            config,
+            session,
+        }
+    }
+
+    async fn run(&mut self, command: &str) -> String {
+        let mut processor = TinyMushProcessor::new(self.store.clone());
+        processor
+            .process_command(&mut self.session, command, &mut self.storage, &self.config)
+            .await
+            .unwrap()
+    }
+
+    fn set_reputation(&self, username: &str, faction: &str, points: i32) {
+        let mut player = self.store.get_player(username).unwrap();
+        player.set_reputation(faction, points);
+        self.store.put_player(player).unwrap();
+    }
+
+    /// A tinker NPC running a wrench stall in town square
+    fn add_tinker_shop(&self) {
+        self.store
+            .put_npc(NpcRecord::new(
+                "stall_tinker",
+                "Stall Tinker",
+                "Tinker",
+                "A tinker minding a stall.",
+                "town_square",
+            ))
+            .unwrap();
+        let tinkers = self
+            .store
+            .get_faction("tinkers")
+            .unwrap()
+            .with_npc("stall_tinker");
+        self.store.put_faction(tinkers).unwrap();
+
+        let mut wrench = ObjectRecord::new_world("wrench", "Wrench", "A sturdy wrench.");
+        wrench.currency_value = CurrencyAmount::decimal(100);
+        wrench.takeable = true;
+        self.store.put_object(wrench).unwrap();
+
+        let mut shop = ShopRecord::new(
+            "tinker_stall".to_string(),
+            "Tinker Stall".to_string(),
+            "town_square".to_string(),
+            "stall_tinker".to_string(),
+        );
+        shop.add_item(ShopItem::infinite("wrench".to_string()))
+            .unwrap();
+        shop.currency = CurrencyAmount::decimal(10_000);
+        self.store.put_shop(shop).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn reputation_command_lists_factions_and_details() {
+    let mut h = Harness::new("alice").await;
+    h.set_reputation("alice", "tinkers", 30);
+
+    let out = h.run("REPUTATION").await;
+    assert!(out.starts_with("=STANDING="), "{}", out);
+    assert!(out.contains("🟢 Tinkers Guild 30"), "{}", out);
+    assert!(out.contains("Scholars Circle 0"), "{}", out);
+    assert!(
+        out.len() <= 230,
+        "standing should stay compact: {}",
+        out.len()
+    );
+
+    let detail = h.run("STANDING tinkers").await;
+    assert!(detail.contains("Friendly (30)"), "{}", detail);
+    assert!(detail.contains("Shops: 10% discount"), "{}", detail);
+    assert!(
+        detail.contains("Now: Access to basic crafting recipes"),
+        "{}",
+        detail
+    );
+    assert!(detail.contains("Next: Honored at 50"), "{}", detail);
+
+    assert!(h.run("REP nobody").await.contains("No faction called"));
+}
+
+#[tokio::test]
+async fn dialog_adjusts_and_gates_on_reputation() {
+    let mut h = Harness::new("bob").await;
+
+    let mut tree = HashMap::new();
+    tree.insert(
+        "greeting".to_string(),
+        DialogNode::new("Need something?")
+            .with_choice(DialogChoice::new("I can help").goto("helped"))
+            .with_choice(
+                DialogChoice::new("Any secrets?")
+                    .goto("secret")
+                    .with_condition(DialogCondition::MinReputation {
+                        faction: "tinkers".to_string(),
+                        points: 25,
+                    }),
+            ),
+    );
+    tree.insert(
+        "helped".to_string(),
+        DialogNode::new("Much obliged!").with_action(DialogAction::AdjustReputation {
+            faction: "tinkers".to_string(),
+            amount: 30,
+        }),
+    );
+    tree.insert("secret".to_string(), DialogNode::new("The tower hums."));
+    let mut npc = NpcRecord::new(
+        "gate_tinker",
+        "Gate Tinker",
+        "Tinker",
+        "A tinker by the gate.",
+        "town_square",
+    );
+    npc.dialog_tree = tree;
+    h.store.put_npc(npc).unwrap();
+
+    let out = h.run("TALK gate").await;
+    assert!(out.contains("1) I can help"), "{}", out);
+    assert!(!out.contains("Any secrets?"), "{}", out);
+
+    let out = h.run("1").await;
+    assert!(
+        out.contains("⭐ Tinkers Guild +30: now 🟢 Friendly"),
+        "{}",
+        out
+    );
+    assert_eq!(
+        h.store.get_player("bob").unwrap().get_reputation("tinkers"),
+        30
+    );
+
+    h.run("EXIT").await;
+    let out = h.run("TALK gate").await;
+    assert!(out.contains("2) Any secrets?"), "{}", out);
+}
+
+#[tokio::test]
+async fn faction_shops_discount_surcharge_and_refuse() {
+    let mut h = Harness::new("carol").await;
+    h.add_tinker_shop();
+
+    // Neutral: default 20% markup
+    let out = h.run("BUY wrench").await;
+    assert!(out.contains("minor_units: 120"), "{}", out);
+
+    h.set_reputation("carol", "tinkers", 30);
+    let out = h.run("BUY wrench").await;
+    assert!(out.contains("minor_units: 108"), "{}", out);
+    assert!(out.contains("Tinkers Guild 10% discount"), "{}", out);
+    assert!(h.run("LIST").await.contains("$1.08"));
+
+    h.set_reputation("carol", "tinkers", -30);
+    let out = h.run("BUY wrench").await;
+    assert!(out.contains("minor_units: 138"), "{}", out);
+    assert!(out.contains("15% surcharge"), "{}", out);
+
+    h.set_reputation("carol", "tinkers", -60);
+    let out = h.run("BUY wrench").await;
+    assert!(
+        out.contains("Tinker Stall refuses to deal with you"),
+        "{}",
+        out
+    );
+    assert!(out.contains("Tinkers Guild: Hostile"), "{}", out);
+    assert!(h.run("SELL wrench").await.contains("refuses to deal"));
+    assert!(h.run("LIST").await.contains("refuses to deal"));
+
+    let wrenches = h
+        .store
+        .get_player("carol")
+        .unwrap()
+        .inventory_stacks
+        .iter()
+        .find(|s| s.object_id == "wrench")
+        .map(|s| s.quantity)
+        .unwrap_or(0);
+    assert_eq!(wrenches, 3);
+}
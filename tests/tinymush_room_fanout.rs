@@ -0,0 +1,171 @@
+//! Integration tests for TinyMUSH real-time room fanout.
+//!
+//! Speech, emotes, whispers and movement by one player should be delivered as
+//! DMs to the other in-game sessions standing in the same room, and to nobody else.
+use meshbbs::bbs::server::BbsServer;
+use meshbbs::bbs::session::Session;
+use meshbbs::config::Config;
+
+async fn test_config() -> Config {
+    let mut cfg = Config::default();
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_path = temp_dir.path().to_string_lossy().to_string();
+    cfg.storage.data_dir = temp_path.clone();
+    cfg.bbs.max_users = 20;
+    cfg.games.tinymush_enabled = true;
+    cfg.games.tinymush_db_path = Some(format!("{}/tinymush", temp_path));
+    std::mem::forget(temp_dir);
+    cfg
+}
+
+async fn enter_game(server: &mut BbsServer, name: &str) -> String {
+    server.test_register(name, "password123").await.unwrap();
+    let mut session = Session::new(format!("node_{}", name), format!("node_{}", name));
+    session.login(name.into(), 1).await.unwrap();
+    let key = session.node_id.clone();
+    server.test_insert_session(session);
+    server.route_test_text_direct(&key, "G1").await.unwrap();
+    key
+}
+
+fn messages_to(server: &BbsServer, key: &str) -> Vec<String> {
+    server
+        .test_messages()
+        .iter()
+        .filter(|(to, _)| to == key)
+        .map(|(_, m)| m.clone())
+        .collect()
+}
+
+#[tokio::test]
+async fn say_and_emote_reach_only_room_occupants() {
+    let mut server = BbsServer::new(test_config().await).await.unwrap();
+    let alice = enter_game(&mut server, "alice").await;
+    let bob = enter_game(&mut server, "bob").await;
+    let carol = enter_game(&mut server, "carol").await;
+
+    server
+        .test_tmush_set_player_room(&alice, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&bob, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&carol, "south_market")
+        .await
+        .unwrap();
+
+    server
+        .route_test_text_direct(&alice, "SAY hello there")
+        .await
+        .unwrap();
+    server
+        .route_test_text_direct(&alice, "EMOTE waves")
+        .await
+        .unwrap();
+
+    let bob_msgs = messages_to(&server, &bob);
+    assert!(bob_msgs
+        .iter()
+        .any(|m| m.contains("alice says: \"hello there\"")));
+    assert!(bob_msgs.iter().any(|m| m.contains("alice waves")));
+    assert!(messages_to(&server, &carol)
+        .iter()
+        .all(|m| !m.contains("hello there")));
+    // The speaker only sees their own confirmation
+    assert!(messages_to(&server, &alice)
+        .iter()
+        .all(|m| !m.contains("alice says:")));
+}
+
+#[tokio::test]
+async fn whisper_reaches_only_target() {
+    let mut server = BbsServer::new(test_config().await).await.unwrap();
+    let alice = enter_game(&mut server, "alice").await;
+    let bob = enter_game(&mut server, "bob").await;
+    let carol = enter_game(&mut server, "carol").await;
+    for key in [&alice, &bob, &carol] {
+        server
+            .test_tmush_set_player_room(key, "town_square")
+            .await
+            .unwrap();
+    }
+
+    server
+        .route_test_text_direct(&alice, "WHISPER bob secret plan")
+        .await
+        .unwrap();
+
+    assert!(messages_to(&server, &bob)
+        .iter()
+        .any(|m| m.contains("alice whispers: \"secret plan\"")));
+    assert!(messages_to(&server, &carol)
+        .iter()
+        .all(|m| !m.contains("secret plan")));
+}
+
+#[tokio::test]
+async fn movement_announces_departure_and_arrival() {
+    let mut server = BbsServer::new(test_config().await).await.unwrap();
+    let alice = enter_game(&mut server, "alice").await;
+    let bob = enter_game(&mut server, "bob").await;
+    let carol = enter_game(&mut server, "carol").await;
+
+    server
+        .test_tmush_set_player_room(&alice, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&bob, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&carol, "south_market")
+        .await
+        .unwrap();
+
+    server.route_test_text_direct(&alice, "S").await.unwrap();
+
+    assert!(messages_to(&server, &bob)
+        .iter()
+        .any(|m| m.contains("alice leaves south.")));
+    assert!(messages_to(&server, &carol)
+        .iter()
+        .any(|m| m.contains("alice arrives from the north.")));
+}
+
+#[tokio::test]
+async fn teleport_announces_departure_and_arrival() {
+    let mut server = BbsServer::new(test_config().await).await.unwrap();
+    let alice = enter_game(&mut server, "alice").await;
+    let bob = enter_game(&mut server, "bob").await;
+    let carol = enter_game(&mut server, "carol").await;
+    server.test_tmush_grant_admin("alice", 3).await.unwrap();
+
+    server
+        .test_tmush_set_player_room(&alice, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&bob, "town_square")
+        .await
+        .unwrap();
+    server
+        .test_tmush_set_player_room(&carol, "south_market")
+        .await
+        .unwrap();
+
+    server
+        .route_test_text_direct(&alice, "@TELEPORT south_market")
+        .await
+        .unwrap();
+
+    assert!(messages_to(&server, &bob)
+        .iter()
+        .any(|m| m.contains("alice leaves.")));
+    assert!(messages_to(&server, &carol)
+        .iter()
+        .any(|m| m.contains("alice arrives.")));
+}
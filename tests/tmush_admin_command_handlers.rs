@@ -61,8 +61,12 @@ fn test_config(data_dir: String) -> Config {
             tinyhack_enabled: false,
             tinymush_enabled: true,
             tinymush_db_path: Some(tinymush_path_str),
+            tinymush_dungeon_max_instances: None,
+            tinymush_dungeon_idle_minutes: None,
+            tinymush_world_tick_seconds: None,
         },
         welcome: Default::default(),
+        replication: Default::default(),
     }
 }
 
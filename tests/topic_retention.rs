@@ -0,0 +1,179 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Topic retention: MODIFYTOPIC policies, the archive sweep and ARCHIVE / UNARCHIVE.
+use chrono::{Duration, Utc};
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+use meshbbs::storage::retention::{archive_path, TopicRetention};
+use meshbbs::storage::Storage;
+
+const SYSOP_NODE: u32 = 100;
+const MOD_NODE: u32 = 200;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Last message the server sent to `node`
+fn last_to(server: &BbsServer, node: u32) -> String {
+    server
+        .test_messages()
+        .iter()
+        .rev()
+        .find(|(to, _)| *to == node.to_string())
+        .map(|(_, m)| m.clone())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn sweep_archives_expired_threads_and_restore_brings_them_back() {
+    let tmp = tempfile::tempdir().unwrap();
+    let data_dir = tmp.path().to_string_lossy().to_string();
+    let mut storage = Storage::new(&data_dir).await.unwrap();
+    for topic in ["general", "local"] {
+        storage
+            .create_topic(topic, topic, "", 0, 0, "sysop")
+            .await
+            .unwrap();
+    }
+    let old = storage
+        .store_message("general", "alice", "old news")
+        .await
+        .unwrap();
+    let pinned = storage
+        .store_message("general", "alice", "house rules")
+        .await
+        .unwrap();
+    storage
+        .set_message_pinned("general", &pinned, true)
+        .await
+        .unwrap();
+    storage
+        .store_message("local", "bob", "no policy here")
+        .await
+        .unwrap();
+    let policy = TopicRetention {
+        max_age_days: Some(30),
+        ..Default::default()
+    };
+    storage
+        .set_topic_retention("general", Some(policy))
+        .await
+        .unwrap();
+
+    // Nothing is old enough yet
+    assert!(storage
+        .sweep_retention(Utc::now())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let later = Utc::now() + Duration::days(40);
+    let swept = storage.sweep_retention(later).await.unwrap();
+    assert_eq!(swept, vec![("general".to_string(), 1)]);
+    let remaining = storage.get_messages("general", 10).await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, pinned);
+    assert_eq!(storage.get_messages("local", 10).await.unwrap().len(), 1);
+    assert!(archive_path(&data_dir, "general").exists());
+    let archived = storage.list_archived("general").await.unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].content, "old news");
+
+    let audit = storage.get_admin_audit_page(1, 10).await.unwrap();
+    assert_eq!(audit[0].action, "ARCHIVE");
+    assert_eq!(audit[0].target.as_deref(), Some("general"));
+    assert_eq!(audit[0].details.as_deref(), Some("1 threads (maxage=30d)"));
+
+    // Restore by id prefix
+    assert!(storage
+        .restore_archived("general", "ffffffff", "mod")
+        .await
+        .unwrap()
+        .is_none());
+    let restored = storage
+        .restore_archived("general", &old[..8], "mod")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored.id, old);
+    assert_eq!(storage.get_messages("general", 10).await.unwrap().len(), 2);
+    assert!(storage.list_archived("general").await.unwrap().is_empty());
+    assert!(!archive_path(&data_dir, "general").exists());
+    let audit = storage.get_admin_audit_page(1, 10).await.unwrap();
+    assert!(audit
+        .iter()
+        .any(|e| e.action == "UNARCHIVE" && e.actor == "mod"));
+}
+
+#[tokio::test]
+async fn sysop_sets_policy_and_moderators_browse_the_archive() {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    cfg.bbs.sysop = "keeper".into();
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server
+        .test_create_topic("general", "General", "General chat", 0, 0, "sysop")
+        .await
+        .unwrap();
+    server.test_register("keeper", "password123").await.unwrap();
+    server.test_register("mod", "password123").await.unwrap();
+    server.test_update_level("mod", 5).await.unwrap();
+    for (node, user) in [(SYSOP_NODE, "keeper"), (MOD_NODE, "mod")] {
+        server
+            .route_text_event(dm(node, &format!("LOGIN {} password123", user)))
+            .await
+            .unwrap();
+    }
+    for i in 0..3 {
+        server
+            .test_store_message("general", "alice", &format!("post {}", i))
+            .await
+            .unwrap();
+    }
+
+    server
+        .route_text_event(dm(SYSOP_NODE, "MODIFYTOPIC general maxthreads=2"))
+        .await
+        .unwrap();
+    let reply = last_to(&server, SYSOP_NODE);
+    assert!(reply.contains("(retention maxthreads=2)"), "{}", reply);
+    let swept = server.test_sweep_retention(Utc::now()).await.unwrap();
+    assert_eq!(swept, vec![("general".to_string(), 1)]);
+
+    server
+        .route_text_event(dm(MOD_NODE, "ARCHIVE general"))
+        .await
+        .unwrap();
+    let listing = last_to(&server, MOD_NODE);
+    assert!(listing.starts_with("Archive general (1):\n"), "{}", listing);
+    assert!(listing.contains("alice post 0"), "{}", listing);
+    let id = listing.lines().nth(1).unwrap().split(' ').next().unwrap();
+
+    // Clearing the limit stops further sweeps; the thread can come back
+    server
+        .route_text_event(dm(SYSOP_NODE, "MODIFYTOPIC general maxthreads=off"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(dm(MOD_NODE, &format!("UNARCHIVE general {}", id)))
+        .await
+        .unwrap();
+    assert!(last_to(&server, MOD_NODE).starts_with("Restored "));
+    assert!(server
+        .test_sweep_retention(Utc::now())
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        server.test_get_messages("general", 10).await.unwrap().len(),
+        3
+    );
+}
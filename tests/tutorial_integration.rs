@@ -48,6 +48,7 @@ fn seed_tutorial_npcs(store: &TinyMushStore) {
         dialog,
         dialog_tree: HashMap::new(), // Empty dialog tree (tests use old dialog system)
         flags: vec![NpcFlag::TutorialNpc, NpcFlag::QuestGiver],
+        patrol: Vec::new(),
         created_at: chrono::Utc::now(),
         schema_version: 1,
     };
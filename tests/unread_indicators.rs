@@ -84,6 +84,7 @@ async fn unread_indicators_topics_and_threads() {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     };
 
     let mut server = BbsServer::new(cfg.clone()).await.unwrap();
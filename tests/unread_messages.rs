@@ -75,6 +75,7 @@ async fn unread_message_count_on_login() {
             cooldown_minutes: 5,
             max_welcomes_per_node: 1,
         },
+        replication: Default::default(),
     };
     let cfg_clone = cfg.clone();
     let mut server = BbsServer::new(cfg_clone).await.unwrap();
@@ -0,0 +1,219 @@
+#![cfg(feature = "meshtastic-proto")]
+//! Bans, posting mutes and public ignores (BAN / MUTE / IGNORE and friends).
+use meshbbs::bbs::BbsServer;
+use meshbbs::config::Config;
+use meshbbs::meshtastic::TextEvent;
+use meshbbs::storage::moderation::{parse_duration, parse_node_id, BanTarget};
+use meshbbs::storage::Storage;
+
+const MOD_NODE: u32 = 100;
+const BOB_NODE: u32 = 200;
+const BAD_NODE: u32 = 0xdead_beef;
+
+fn dm(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: Some(1),
+        is_direct: true,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+fn public(source: u32, content: &str) -> TextEvent {
+    TextEvent {
+        source,
+        dest: None,
+        is_direct: false,
+        channel: None,
+        content: content.into(),
+    }
+}
+
+/// Last message the server sent to `node`
+fn last_to(server: &BbsServer, node: u32) -> String {
+    server
+        .test_messages()
+        .iter()
+        .rev()
+        .find(|(to, _)| *to == node.to_string())
+        .map(|(_, m)| m.clone())
+        .unwrap_or_default()
+}
+
+async fn setup() -> (BbsServer, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cfg = Config::default();
+    cfg.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let mut server = BbsServer::new(cfg).await.expect("server");
+    server
+        .test_create_topic("general", "General", "General chat", 0, 0, "sysop")
+        .await
+        .unwrap();
+    server.test_register("mod", "password123").await.unwrap();
+    server.test_update_level("mod", 5).await.unwrap();
+    server.test_register("bob", "password123").await.unwrap();
+    server.test_register("carol", "password123").await.unwrap();
+    server
+        .route_text_event(dm(MOD_NODE, "LOGIN mod password123"))
+        .await
+        .unwrap();
+    (server, tmp)
+}
+
+#[test]
+fn parses_targets_and_lengths() {
+    assert_eq!(parse_node_id("!deadbeef"), Some(BAD_NODE));
+    assert_eq!(parse_node_id("0xDEADBEEF"), Some(BAD_NODE));
+    assert_eq!(parse_node_id("3735928559"), Some(BAD_NODE));
+    assert_eq!(parse_node_id("bob"), None);
+    assert_eq!(BanTarget::parse("bob"), BanTarget::User("bob".into()));
+    assert_eq!(parse_duration("90m").unwrap().num_minutes(), 90);
+    assert_eq!(parse_duration("7D").unwrap().num_days(), 7);
+    assert_eq!(parse_duration("2w").unwrap().num_days(), 14);
+    assert!(parse_duration("0h").is_none());
+    assert!(parse_duration("spam").is_none());
+}
+
+#[tokio::test]
+async fn banned_nodes_and_users_are_turned_away() {
+    let (mut server, tmp) = setup().await;
+
+    server
+        .route_text_event(dm(MOD_NODE, "BAN !deadbeef 2d flooding"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, MOD_NODE).contains("Banned !deadbeef (2d)"));
+
+    // No session is created for the banned node; it is told why once
+    server.route_text_event(dm(BAD_NODE, "HELP")).await.unwrap();
+    assert!(server.test_get_session(&BAD_NODE.to_string()).is_none());
+    let notice = last_to(&server, BAD_NODE);
+    assert!(notice.starts_with("You are banned until"), "{}", notice);
+    assert!(notice.contains("flooding"), "{}", notice);
+    let sent = server.test_messages().len();
+    server
+        .route_text_event(public(BAD_NODE, "^HELP"))
+        .await
+        .unwrap();
+    assert_eq!(server.test_messages().len(), sent, "public is ignored too");
+
+    // A logged in user is dropped when banned and cannot log back in
+    server
+        .route_text_event(dm(BOB_NODE, "LOGIN bob password123"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(dm(MOD_NODE, "BAN bob"))
+        .await
+        .unwrap();
+    assert!(server.test_get_session(&BOB_NODE.to_string()).is_none());
+    server
+        .route_text_event(dm(BOB_NODE, "LOGIN bob password123"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, BOB_NODE).starts_with("You are banned.\n"));
+
+    // Peers are protected; listing and lifting work
+    server
+        .route_text_event(dm(MOD_NODE, "BAN mod"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, MOD_NODE).contains("yourself"));
+    server.route_text_event(dm(MOD_NODE, "BANS")).await.unwrap();
+    let list = last_to(&server, MOD_NODE);
+    assert!(list.contains("BAN !deadbeef 2d flooding"), "{}", list);
+    assert!(list.contains("BAN bob perm"), "{}", list);
+    server
+        .route_text_event(dm(MOD_NODE, "UNBAN bob"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(dm(BOB_NODE, "LOGIN bob password123"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, BOB_NODE).contains("logged in"));
+
+    // Plain users cannot moderate
+    server
+        .route_text_event(dm(BOB_NODE, "BAN carol"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, BOB_NODE).starts_with("Permission denied."));
+
+    // Everything is persisted and audited
+    drop(server);
+    let storage = Storage::new(&tmp.path().to_string_lossy()).await.unwrap();
+    assert!(storage.node_ban(BAD_NODE).is_some());
+    assert!(storage.user_ban("bob").is_none());
+    let audit = storage.get_admin_audit_page(1, 10).await.unwrap();
+    let actions: Vec<&str> = audit.iter().map(|e| e.action.as_str()).collect();
+    assert!(
+        actions.contains(&"BAN") && actions.contains(&"UNBAN"),
+        "{:?}",
+        actions
+    );
+}
+
+#[tokio::test]
+async fn mutes_block_posting_and_ignores_silence_public() {
+    let (mut server, _tmp) = setup().await;
+    let id = server
+        .test_store_message("general", "bob", "Hello\n\nfirst post")
+        .await
+        .unwrap();
+
+    server
+        .route_text_event(dm(MOD_NODE, "MUTE bob general 1h off topic"))
+        .await
+        .unwrap();
+    assert!(last_to(&server, MOD_NODE).contains("Muted bob in general (1h)"));
+    let err = server
+        .test_store_message("general", "bob", "again")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("muted"), "{}", err);
+    assert!(server
+        .test_store_message("general", "carol", "hi")
+        .await
+        .is_ok());
+
+    // A global mute also covers replies
+    server
+        .route_text_event(dm(MOD_NODE, "MUTE carol"))
+        .await
+        .unwrap();
+    assert!(server
+        .test_append_reply("general", &id, "carol", "me too")
+        .await
+        .is_err());
+    server
+        .route_text_event(dm(MOD_NODE, "UNMUTE carol"))
+        .await
+        .unwrap();
+    assert!(server
+        .test_append_reply("general", &id, "carol", "me too")
+        .await
+        .is_ok());
+
+    // Ignored nodes get no public answers until unignored
+    server
+        .route_text_event(dm(MOD_NODE, "IGNORE !deadbeef"))
+        .await
+        .unwrap();
+    let sent = server.test_messages().len();
+    server
+        .route_text_event(public(BAD_NODE, "^HELP"))
+        .await
+        .unwrap();
+    assert_eq!(server.test_messages().len(), sent);
+    server
+        .route_text_event(dm(MOD_NODE, "UNIGNORE !deadbeef"))
+        .await
+        .unwrap();
+    server
+        .route_text_event(public(BAD_NODE, "^HELP"))
+        .await
+        .unwrap();
+    assert!(server.test_messages().len() > sent);
+}
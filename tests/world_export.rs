@@ -0,0 +1,198 @@
+//! World export/import through JSON area files (`meshbbs world` and @EXPORT/@IMPORT).
+use meshbbs::bbs::session::{Session, SessionState};
+use meshbbs::config::Config;
+use meshbbs::tmush::commands::TinyMushProcessor;
+use meshbbs::tmush::world_io::{export_world, import_world, ImportOptions, MANIFEST_FILE};
+use meshbbs::tmush::{PlayerRecord, TinyMushError, TinyMushStore, TinyMushStoreBuilder};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn empty_store(dir: &Path) -> TinyMushStore {
+    TinyMushStoreBuilder::new(dir)
+        .without_world_seed()
+        .open()
+        .expect("store")
+}
+
+/// Rewrite one area file through a JSON closure
+fn edit_area(dir: &Path, kind: &str, edit: impl FnOnce(&mut serde_json::Value)) {
+    let path = dir.join(format!("{}.json", kind));
+    let mut file: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    edit(&mut file);
+    std::fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+}
+
+#[test]
+fn export_round_trips_into_an_empty_world() {
+    let tmp = TempDir::new().unwrap();
+    let source = TinyMushStore::open(tmp.path().join("a")).unwrap();
+    let out = tmp.path().join("export");
+
+    let manifest = export_world(&source, &out, Some("sysop")).unwrap();
+    assert!(out.join(MANIFEST_FILE).exists());
+    assert!(manifest.counts["rooms"] > 0);
+    assert!(manifest.counts["npcs"] > 0);
+    assert_eq!(manifest.schema_versions["rooms"], 2);
+
+    let target = empty_store(&tmp.path().join("b"));
+    let report = import_world(&target, &out, ImportOptions::default()).unwrap();
+    assert_eq!(report.collisions(), 0, "{}", report.summary());
+    assert_eq!(
+        target.list_room_ids().unwrap(),
+        source.list_room_ids().unwrap()
+    );
+    assert_eq!(
+        target.list_npc_ids().unwrap(),
+        source.list_npc_ids().unwrap()
+    );
+    for id in source.list_npc_ids().unwrap() {
+        assert_eq!(target.get_npc(&id).unwrap(), source.get_npc(&id).unwrap());
+    }
+
+    assert!(manifest.counts["mobs"] > 0);
+    assert!(manifest.counts["factions"] > 0);
+    assert_eq!(
+        target.list_mob_ids().unwrap(),
+        source.list_mob_ids().unwrap()
+    );
+    for id in source.list_mob_ids().unwrap() {
+        assert_eq!(
+            target.get_mob(&id).unwrap().name,
+            source.get_mob(&id).unwrap().name
+        );
+    }
+    assert_eq!(
+        target.list_factions().unwrap(),
+        source.list_factions().unwrap()
+    );
+    assert!(manifest.counts["spells"] > 0);
+    assert_eq!(target.list_spells().unwrap(), source.list_spells().unwrap());
+
+    // A second import of the same files is a no-op
+    let again = import_world(&target, &out, ImportOptions::default()).unwrap();
+    assert_eq!(again.changes(), 0, "{}", again.summary());
+    let rooms = again.kinds.iter().find(|k| k.kind == "rooms").unwrap();
+    assert_eq!(rooms.unchanged, manifest.counts["rooms"]);
+}
+
+#[test]
+fn collisions_are_reported_and_only_replaced_on_overwrite() {
+    let tmp = TempDir::new().unwrap();
+    let source = TinyMushStore::open(tmp.path().join("a")).unwrap();
+    let out = tmp.path().join("export");
+    export_world(&source, &out, None).unwrap();
+
+    let room_id = source.list_room_ids().unwrap()[0].clone();
+    let mut edited = source.get_room(&room_id).unwrap();
+    edited.name = "Renamed Locally".into();
+    source.put_room(edited).unwrap();
+
+    let dry = ImportOptions {
+        overwrite: true,
+        dry_run: true,
+    };
+    let report = import_world(&source, &out, dry).unwrap();
+    let rooms = report.kinds.iter().find(|k| k.kind == "rooms").unwrap();
+    assert_eq!(rooms.replaced, vec![room_id.clone()]);
+    assert!(report.summary().starts_with("Dry run"));
+    assert_eq!(source.get_room(&room_id).unwrap().name, "Renamed Locally");
+
+    let report = import_world(&source, &out, ImportOptions::default()).unwrap();
+    assert_eq!(report.collisions(), 1);
+    assert!(report.summary().contains(&format!("collide: {}", room_id)));
+    assert_eq!(source.get_room(&room_id).unwrap().name, "Renamed Locally");
+
+    let overwrite = ImportOptions {
+        overwrite: true,
+        dry_run: false,
+    };
+    let report = import_world(&source, &out, overwrite).unwrap();
+    assert_eq!(report.changes(), 1);
+    assert_ne!(source.get_room(&room_id).unwrap().name, "Renamed Locally");
+}
+
+#[test]
+fn newer_schemas_abort_and_older_ones_migrate() {
+    let tmp = TempDir::new().unwrap();
+    let source = TinyMushStore::open(tmp.path().join("a")).unwrap();
+    let out = tmp.path().join("export");
+    export_world(&source, &out, None).unwrap();
+
+    // A record from the future stops the import before anything is written
+    edit_area(&out, "rooms", |f| {
+        f["records"][0]["schema_version"] = 99.into()
+    });
+    let target = empty_store(&tmp.path().join("b"));
+    let err = import_world(&target, &out, ImportOptions::default()).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            TinyMushError::SchemaMismatch {
+                entity: "rooms",
+                found: 99,
+                ..
+            }
+        ),
+        "{}",
+        err
+    );
+    assert!(target.list_npc_ids().unwrap().is_empty());
+
+    // Older records are upgraded on the way in
+    edit_area(&out, "rooms", |f| {
+        f["records"][0]["schema_version"] = 1.into()
+    });
+    let report = import_world(&target, &out, ImportOptions::default()).unwrap();
+    let rooms = report.kinds.iter().find(|k| k.kind == "rooms").unwrap();
+    assert_eq!(rooms.migrated, 1);
+    let first = target.list_room_ids().unwrap()[0].clone();
+    assert_eq!(target.get_room(&first).unwrap().schema_version, 2);
+}
+
+async fn run(store: &TinyMushStore, config: &Config, user: &str, cmd: &str) -> String {
+    let mut session = Session::new(format!("{}_session", user), format!("{}_node", user));
+    session.login(user.to_string(), 1).await.unwrap();
+    session.state = SessionState::TinyMush;
+    let mut storage = meshbbs::storage::Storage::new(&config.storage.data_dir)
+        .await
+        .unwrap();
+    TinyMushProcessor::new(store.clone())
+        .process_command(&mut session, cmd, &mut storage, config)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn admin_commands_export_and_import() {
+    let tmp = TempDir::new().unwrap();
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().to_string_lossy().to_string();
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .open()
+        .unwrap();
+    store
+        .put_player(PlayerRecord::new("alice", "Alice", "town_square"))
+        .unwrap();
+
+    let denied = run(&store, &config, "alice", "@EXPORT snap").await;
+    assert!(denied.contains("Permission denied"), "{}", denied);
+
+    let out = run(&store, &config, "admin", "@EXPORT snap").await;
+    assert!(out.contains("World exported as 'snap'"), "{}", out);
+    assert!(tmp
+        .path()
+        .join("world_exports/snap")
+        .join(MANIFEST_FILE)
+        .exists());
+
+    let bad = run(&store, &config, "admin", "@EXPORT ../escape").await;
+    assert!(bad.contains("may only use"), "{}", bad);
+
+    let diff = run(&store, &config, "admin", "@IMPORT snap --dry-run").await;
+    assert!(diff.starts_with("Dry run"), "{}", diff);
+    assert!(diff.contains("0 collisions"), "{}", diff);
+
+    let missing = run(&store, &config, "admin", "@IMPORT nosuch").await;
+    assert!(missing.contains("No export named"), "{}", missing);
+}
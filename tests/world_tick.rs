@@ -0,0 +1,199 @@
+//! World tick: NPC patrols, OnIdle triggers and companion behaviors between commands.
+use chrono::{Duration, Utc};
+use meshbbs::tmush::events::{RoomEvent, RoomEventKind};
+use meshbbs::tmush::types::{
+    CompanionBehavior, CompanionRecord, CompanionType, Direction, MobRecord, NpcCombatStats,
+    NpcRecord, ObjectRecord, ObjectTrigger, RoomRecord,
+};
+use meshbbs::tmush::world_tick::WorldTick;
+use meshbbs::tmush::{PlayerRecord, TinyMushStore, TinyMushStoreBuilder};
+use rand::rngs::mock::StepRng;
+use tempfile::TempDir;
+
+/// gate <-> market <-> well; alice waits at the gate, bob at the well
+fn setup(tmp: &TempDir) -> TinyMushStore {
+    let store = TinyMushStoreBuilder::new(tmp.path().join("tinymush"))
+        .without_world_seed()
+        .open()
+        .unwrap();
+    let mut gate = RoomRecord::world("gate", "Gate", "The gate.", "The town gate.");
+    gate.exits.insert(Direction::North, "market".into());
+    let mut market = RoomRecord::world("market", "Market", "The market.", "A market.");
+    market.exits.insert(Direction::South, "gate".into());
+    market.exits.insert(Direction::North, "well".into());
+    let mut well = RoomRecord::world("well", "Well", "The well.", "An old well.");
+    well.exits.insert(Direction::South, "market".into());
+    for room in [gate, market, well] {
+        store.put_room(room).unwrap();
+    }
+    store
+        .put_player(PlayerRecord::new("alice", "alice", "gate"))
+        .unwrap();
+    store
+        .put_player(PlayerRecord::new("bob", "bob", "well"))
+        .unwrap();
+    store
+}
+
+fn online() -> Vec<String> {
+    vec!["alice".to_string(), "bob".to_string()]
+}
+
+fn lines(events: &[RoomEvent]) -> Vec<String> {
+    events.iter().map(|e| e.render()).collect()
+}
+
+#[test]
+fn npcs_walk_their_patrol_route() {
+    let tmp = TempDir::new().unwrap();
+    let store = setup(&tmp);
+    let mut npc = NpcRecord::new("patrol_guard", "Guard", "Town Guard", "A guard.", "gate");
+    npc.patrol = vec!["gate".into(), "market".into(), "well".into()];
+    store.put_npc(npc).unwrap();
+    let mut tick = WorldTick::new();
+
+    let report = tick
+        .run(&store, &online(), Utc::now(), &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(report.npcs_moved, 1);
+    assert_eq!(store.get_npc("patrol_guard").unwrap().room_id, "market");
+    // Nobody is in the market, so only alice sees anything
+    let events = store.room_events().drain();
+    assert_eq!(lines(&events), vec!["Guard leaves north."]);
+    assert_eq!(events[0].kind, RoomEventKind::Ambient);
+
+    // Nobody online, nothing moves
+    tick.run(&store, &[], Utc::now(), &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(store.get_npc("patrol_guard").unwrap().room_id, "market");
+}
+
+#[test]
+fn idle_triggers_fire_only_where_players_are() {
+    let tmp = TempDir::new().unwrap();
+    let store = setup(&tmp);
+    for (id, room_id) in [("idle_fountain", "gate"), ("idle_bell", "market")] {
+        let mut object = ObjectRecord::new_world(id, id, "Ambient.");
+        object.actions.insert(
+            ObjectTrigger::OnIdle,
+            format!("message(\"The {} murmurs.\")", id),
+        );
+        store.put_object(object).unwrap();
+        let mut room = store.get_room(room_id).unwrap();
+        room.items.push(id.to_string());
+        store.put_room(room).unwrap();
+    }
+
+    let report = WorldTick::new()
+        .run(&store, &online(), Utc::now(), &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(report.idle_triggers, 1);
+    let events = store.room_events().drain();
+    assert_eq!(lines(&events), vec!["The idle_fountain murmurs."]);
+    assert_eq!(events[0].room_id, "gate");
+}
+
+#[test]
+fn companions_heal_on_cooldown_and_chatter() {
+    let tmp = TempDir::new().unwrap();
+    let store = setup(&tmp);
+    let mut alice = store.get_player("alice").unwrap();
+    alice.stats.hp = 5;
+    store.put_player(alice).unwrap();
+    let mut dog = CompanionRecord::new("tick_biscuit", "Biscuit", CompanionType::Dog, "gate");
+    dog.owner = Some("alice".into());
+    dog.behaviors = vec![
+        CompanionBehavior::Healing {
+            heal_amount: 4,
+            cooldown_seconds: 60,
+        },
+        CompanionBehavior::IdleChatter {
+            messages: vec!["wags her tail.".into()],
+        },
+    ];
+    store.put_companion(dog).unwrap();
+    let mut tick = WorldTick::new();
+    let now = Utc::now();
+
+    // StepRng(0) always passes the chatter roll
+    let report = tick
+        .run(&store, &online(), now, &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(report.heals, 1);
+    assert_eq!(report.chatter, 1);
+    assert_eq!(store.get_player("alice").unwrap().stats.hp, 9);
+    let events = store.room_events().drain();
+    assert_eq!(
+        lines(&events),
+        vec![
+            "Biscuit tends to you. +4 HP (9/20)",
+            "Biscuit wags her tail."
+        ]
+    );
+    assert_eq!(events[0].target.as_deref(), Some("alice"));
+    assert_eq!(events[1].kind, RoomEventKind::Chatter);
+
+    // Let the rate limiter's one-second cooldown pass between ticks
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    tick.run(
+        &store,
+        &online(),
+        now + Duration::seconds(30),
+        &mut StepRng::new(u64::MAX, 0),
+    )
+    .unwrap();
+    assert_eq!(store.get_player("alice").unwrap().stats.hp, 9);
+    assert!(store.room_events().is_empty());
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    tick.run(
+        &store,
+        &online(),
+        now + Duration::seconds(60),
+        &mut StepRng::new(u64::MAX, 0),
+    )
+    .unwrap();
+    assert_eq!(store.get_player("alice").unwrap().stats.hp, 13);
+}
+
+#[test]
+fn companions_warn_once_about_nearby_mobs() {
+    let tmp = TempDir::new().unwrap();
+    let store = setup(&tmp);
+    let combat = NpcCombatStats {
+        max_hp: 10,
+        attack: 1,
+        defense: 0,
+        damage_range: (1, 2),
+        loot_table: Vec::new(),
+    };
+    store
+        .put_mob(MobRecord::new(
+            "tick_rat",
+            "Giant Rat",
+            "A rat.",
+            "market",
+            combat,
+        ))
+        .unwrap();
+    let mut cat = CompanionRecord::new("tick_whiskers", "Whiskers", CompanionType::Cat, "gate");
+    cat.owner = Some("alice".into());
+    cat.behaviors = vec![CompanionBehavior::AlertDanger];
+    store.put_companion(cat).unwrap();
+    let mut tick = WorldTick::new();
+
+    let report = tick
+        .run(&store, &online(), Utc::now(), &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(report.alerts, 1);
+    assert_eq!(
+        lines(&store.room_events().drain()),
+        vec!["Whiskers growls toward the north. Something is there: Giant Rat."]
+    );
+
+    std::thread::sleep(std::time::Duration::from_millis(1100));
+    let report = tick
+        .run(&store, &online(), Utc::now(), &mut StepRng::new(0, 0))
+        .unwrap();
+    assert_eq!(report.alerts, 0);
+}